@@ -0,0 +1,410 @@
+//! # Daegonica Module: agent_history::checkpoint
+//!
+//! **Purpose:** Snapshots of a conversation taken before it is rewritten, and rolling back to them
+//!
+//! **Context:**
+//! - Summarize, `history remove`, `history dedupe`, `clear` and `resync fresh` each change
+//!   history in ways the archives only partly undo
+//! - Which actions take a checkpoint is decided in one place, `InputAction::checkpoint_reason`
+//! - `rollback` lists and restores them; it checkpoints the state it replaces, so it can be undone too
+//!
+//! **Responsibilities:**
+//! - Write checkpoints to `{root}/{persona_name}/checkpoints/`, in stored form
+//! - Skip a checkpoint identical to the newest one
+//! - Keep at most `history.checkpoints_kept` per persona, within `history.checkpoint_budget_bytes`
+//! - List, load and restore checkpoints
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use chrono::{DateTime, Utc};
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use strum::Display;
+use crate::prelude::*;
+use crate::agent_history::redaction::Redactor;
+use crate::persona::persona_dir;
+use crate::utilities::time::{format_timestamp, Zone, ARCHIVE_STAMP_FORMAT};
+
+/// A persona's checkpoint directory (`{root}/{persona_name}/checkpoints`)
+pub(crate) fn checkpoint_dir(persona_name: &str) -> PathBuf {
+    persona_dir(persona_name).join("checkpoints")
+}
+
+/// # CheckpointReason
+///
+/// **Summary:**
+/// The operation a checkpoint was taken before; also part of its file name.
+///
+/// **Variants:**
+/// - `Summarize`: History replaced by a summary (manual or automatic)
+/// - `HistoryRemove`: Messages taken out with `history remove`
+/// - `Dedupe`: Duplicates removed with `history dedupe`
+/// - `Clear`: Saved history deleted with `clear`
+/// - `FreshStart`: History archived and restarted with `resync fresh`
+/// - `Rollback`: The state a `rollback` replaced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[strum(serialize_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub enum CheckpointReason {
+    Summarize,
+    HistoryRemove,
+    Dedupe,
+    Clear,
+    FreshStart,
+    Rollback,
+}
+
+/// # Checkpoint
+///
+/// **Summary:**
+/// Everything needed to put a conversation back the way it was.
+///
+/// **Fields:**
+/// - `persona`: Persona the conversation belongs to
+/// - `reason`: Operation the checkpoint was taken before
+/// - `created_at`: RFC3339 time it was taken
+/// - `message_count`: Length of `messages`, readable without loading them
+/// - `summarization_count`: Times the history had been summarized
+/// - `started_at`: RFC3339 time the conversation began
+/// - `last_exchange_at`: RFC3339 time of the last reply, if any
+/// - `last_response_id` / `last_response_at`: The server-side thread, if any
+/// - `messages`: The full `local_history`, in its stored form (`ephemeral_patterns` redacted)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub persona: String,
+    pub reason: CheckpointReason,
+    pub created_at: String,
+    pub message_count: usize,
+    pub summarization_count: usize,
+    pub started_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_exchange_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_response_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_response_at: Option<String>,
+    pub messages: Vec<Message>,
+}
+
+impl Checkpoint {
+    /// # capture
+    ///
+    /// **Purpose:**
+    /// Takes a checkpoint of `conversation` as it is now.
+    pub fn capture(conversation: &GrokConversation, reason: CheckpointReason) -> Self {
+        let messages = Redactor::for_persona(&conversation.persona).redact_messages(&conversation.local_history);
+        Self {
+            persona: conversation.persona.name.clone(),
+            reason,
+            created_at: Utc::now().to_rfc3339(),
+            message_count: messages.len(),
+            summarization_count: conversation.summarization_count,
+            started_at: conversation.started_at.clone(),
+            last_exchange_at: conversation.last_exchange_at.map(|at| at.to_rfc3339()),
+            last_response_id: conversation.get_last_response_id().cloned(),
+            last_response_at: conversation.response_id_set_at().map(|at| at.to_rfc3339()),
+            messages,
+        }
+    }
+
+    /// # restore_into
+    ///
+    /// **Purpose:**
+    /// Puts `conversation` back in the state this checkpoint recorded.
+    ///
+    /// **Details:**
+    /// The current system prompt is kept, as when history is loaded from disk,
+    /// so a persona edited since the checkpoint keeps its new prompt. The
+    /// response ID comes back too: the server thread it names is the one
+    /// that matches the restored messages.
+    pub fn restore_into(self, conversation: &mut GrokConversation) {
+        let parse = |at: &str| DateTime::parse_from_rfc3339(at).ok().map(|at| at.with_timezone(&Utc));
+        let mut messages = self.messages;
+        if let (Some(prompt), Some(first)) = (conversation.local_history.first(), messages.first_mut())
+            && first.role == "system"
+        {
+            *first = prompt.clone();
+        }
+
+        conversation.replace_history(messages);
+        conversation.summarization_count = self.summarization_count;
+        conversation.started_at = self.started_at;
+        conversation.last_exchange_at = self.last_exchange_at.as_deref().and_then(parse);
+        match self.last_response_id {
+            Some(id) => conversation.restore_response_id(id, self.last_response_at.as_deref().and_then(parse)),
+            None => conversation.forget_response_id(),
+        }
+    }
+
+    /// Whether two checkpoints hold the same conversation state (reason and time aside)
+    fn same_state(&self, other: &Checkpoint) -> bool {
+        let messages = |checkpoint: &Checkpoint| serde_json::to_value(&checkpoint.messages).ok();
+        self.last_response_id == other.last_response_id
+            && self.summarization_count == other.summarization_count
+            && self.message_count == other.message_count
+            && messages(self) == messages(other)
+    }
+}
+
+/// The fields `list_checkpoints` reads; the messages are skipped over, not kept
+#[derive(Debug, Deserialize)]
+struct CheckpointHeader {
+    reason: CheckpointReason,
+    created_at: String,
+    message_count: usize,
+}
+
+/// # CheckpointSummary
+///
+/// **Summary:**
+/// One checkpoint file, as `rollback` lists it.
+///
+/// **Fields:**
+/// - `path`: Checkpoint file location
+/// - `reason`: Operation it was taken before
+/// - `created_at`: When it was taken
+/// - `size_bytes`: File size on disk
+/// - `message_count`: Number of messages it restores
+#[derive(Debug, Clone)]
+pub struct CheckpointSummary {
+    pub path: PathBuf,
+    pub reason: CheckpointReason,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+    pub message_count: usize,
+}
+
+impl CheckpointSummary {
+    /// One-line summary for the `rollback` listing, with the time shown in `zone`
+    pub fn describe(&self, zone: Zone) -> String {
+        format!("{}  before {}  {} messages, {:.1} KB",
+            format_timestamp(self.created_at, zone), self.reason, self.message_count, self.size_bytes as f64 / 1024.0)
+    }
+}
+
+/// # write_checkpoint
+///
+/// **Purpose:**
+/// Checkpoints `conversation` before the operation named by `reason`.
+///
+/// **Returns:**
+/// `Result<Option<PathBuf>, Box<dyn std::error::Error>>` - The new checkpoint file, or None
+/// if nothing was written: the persona keeps no history, or the newest
+/// checkpoint already holds this exact state
+///
+/// **Details:**
+/// Files are named `{timestamp}_{reason}.json` (`ARCHIVE_STAMP_FORMAT`, local
+/// time), with a counter added for a second one within the same second.
+/// Older checkpoints are pruned afterwards (see `prune_checkpoints`).
+///
+/// **Errors / Failures:**
+/// - Directory creation or file write errors
+/// - JSON serialization errors
+pub fn write_checkpoint(conversation: &GrokConversation, reason: CheckpointReason) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+    if !conversation.persona.enable_history {
+        return Ok(None);
+    }
+    let persona_name = &conversation.persona.name;
+    let checkpoint = Checkpoint::capture(conversation, reason);
+
+    let newest = list_checkpoints(persona_name)?.into_iter().next();
+    if let Some(newest) = newest
+        && load_checkpoint(&newest.path).is_ok_and(|previous| previous.same_state(&checkpoint))
+    {
+        log_info!("Skipped {} checkpoint for {}: unchanged since {}", reason, persona_name, newest.path.display());
+        return Ok(None);
+    }
+
+    let dir = checkpoint_dir(persona_name);
+    std::fs::create_dir_all(&dir)?;
+    let stem = format!("{}_{}", chrono::Local::now().format(ARCHIVE_STAMP_FORMAT), reason);
+    let mut path = dir.join(format!("{}.json", stem));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{}_{}.json", stem, n));
+        n += 1;
+    }
+
+    let mut writer = BufWriter::new(File::create(&path)?);
+    serde_json::to_writer_pretty(&mut writer, &checkpoint)?;
+    writer.flush()?;
+    log_info!("Checkpointed {} ({} messages) before {} to {}", persona_name, checkpoint.message_count, reason, path.display());
+
+    prune_checkpoints(persona_name)?;
+    Ok(Some(path))
+}
+
+/// # list_checkpoints
+///
+/// **Purpose:**
+/// Summarizes a persona's checkpoints, newest first.
+///
+/// **Returns:**
+/// `Result<Vec<CheckpointSummary>, Box<dyn std::error::Error>>` - Empty if there are none
+///
+/// **Details:**
+/// A file that doesn't parse as a checkpoint is logged and left out.
+pub fn list_checkpoints(persona_name: &str) -> Result<Vec<CheckpointSummary>, Box<dyn std::error::Error>> {
+    let dir = checkpoint_dir(persona_name);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut summaries = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let header: CheckpointHeader = match serde_json::from_reader(BufReader::new(File::open(&path)?)) {
+            Ok(header) => header,
+            Err(e) => {
+                log_error!("Skipping unreadable checkpoint {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        let Ok(created_at) = DateTime::parse_from_rfc3339(&header.created_at) else {
+            log_error!("Skipping checkpoint {} with a bad timestamp: {}", path.display(), header.created_at);
+            continue;
+        };
+        summaries.push(CheckpointSummary {
+            size_bytes: fs::metadata(&path)?.len(),
+            path,
+            reason: header.reason,
+            created_at: created_at.with_timezone(&Utc),
+            message_count: header.message_count,
+        });
+    }
+    summaries.sort_by_key(|summary| std::cmp::Reverse(summary.created_at));
+    Ok(summaries)
+}
+
+/// Reads a whole checkpoint file
+pub fn load_checkpoint(path: &Path) -> Result<Checkpoint, Box<dyn std::error::Error>> {
+    Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+}
+
+/// # prune_checkpoints
+///
+/// **Purpose:**
+/// Deletes a persona's oldest checkpoints beyond `history.checkpoints_kept`.
+///
+/// **Returns:**
+/// `Result<usize, Box<dyn std::error::Error>>` - Number of checkpoints deleted
+///
+/// **Details:**
+/// Checkpoints are also dropped once the ones kept would add up to more than
+/// `history.checkpoint_budget_bytes`, except the newest, which is always kept.
+pub fn prune_checkpoints(persona_name: &str) -> Result<usize, Box<dyn std::error::Error>> {
+    let config = &GLOBAL_CONFIG.history;
+    let mut kept_bytes = 0;
+    let mut deleted = 0;
+    for (i, summary) in list_checkpoints(persona_name)?.into_iter().enumerate() {
+        kept_bytes += summary.size_bytes;
+        if i == 0 || (i < config.checkpoints_kept && kept_bytes <= config.checkpoint_budget_bytes) {
+            continue;
+        }
+        fs::remove_file(&summary.path)?;
+        deleted += 1;
+    }
+    if deleted > 0 {
+        log_info!("Pruned {} old checkpoint(s) for {}", deleted, persona_name);
+    }
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_personas_root;
+
+    fn message(role: &str, content: &str) -> Message {
+        Message { role: role.to_string(), content: content.to_string(), meta: None, source: None }
+    }
+
+    /// A history-keeping conversation stored as `name`, with `exchanges` question/answer pairs
+    fn conversation(name: &str, exchanges: usize) -> GrokConversation {
+        test_personas_root();
+        let persona = Persona::from_yaml_str(&format!("name: {}\nsystem_prompt: Be brief.\n", name)).unwrap();
+        let mut conversation = GrokConversation::new(Arc::new(persona));
+        for n in 0..exchanges {
+            conversation.local_history.push(message("user", &format!("question {}", n)));
+            conversation.local_history.push(message("assistant", &format!("answer {}", n)));
+        }
+        conversation
+    }
+
+    fn contents(messages: &[Message]) -> Vec<&str> {
+        messages.iter().map(|msg| msg.content.as_str()).collect()
+    }
+
+    #[test]
+    fn a_checkpoint_is_written_once_per_state() {
+        let mut conversation = conversation("CheckpointOnce", 2);
+        conversation.set_last_response_id("resp_1".to_string());
+
+        let path = write_checkpoint(&conversation, CheckpointReason::Dedupe).unwrap().expect("written");
+        assert!(path.file_name().unwrap().to_string_lossy().ends_with("_dedupe.json"), "{}", path.display());
+        assert_eq!(write_checkpoint(&conversation, CheckpointReason::Clear).unwrap(), None, "unchanged since the newest");
+
+        conversation.local_history.pop();
+        write_checkpoint(&conversation, CheckpointReason::Clear).unwrap().expect("a changed state is written");
+        let listed = list_checkpoints("CheckpointOnce").unwrap();
+        let reasons: Vec<(CheckpointReason, usize)> = listed.iter().map(|summary| (summary.reason, summary.message_count)).collect();
+        assert_eq!(reasons, [(CheckpointReason::Clear, 4), (CheckpointReason::Dedupe, 5)], "newest first");
+
+        let loaded = load_checkpoint(&path).unwrap();
+        assert_eq!(loaded.last_response_id.as_deref(), Some("resp_1"));
+        assert_eq!(contents(&loaded.messages), ["Be brief.", "question 0", "answer 0", "question 1", "answer 1"]);
+    }
+
+    #[test]
+    fn a_persona_without_history_has_no_checkpoints() {
+        let mut conversation = conversation("CheckpointNoHistory", 1);
+        Arc::make_mut(&mut conversation.persona).enable_history = false;
+        assert_eq!(write_checkpoint(&conversation, CheckpointReason::Clear).unwrap(), None);
+        assert!(!checkpoint_dir("CheckpointNoHistory").exists());
+    }
+
+    #[test]
+    fn only_the_newest_checkpoints_are_kept() {
+        let kept = GLOBAL_CONFIG.history.checkpoints_kept;
+        let mut conversation = conversation("CheckpointPruned", 0);
+        for n in 0..kept + 3 {
+            conversation.local_history.push(message("user", &format!("question {}", n)));
+            write_checkpoint(&conversation, CheckpointReason::HistoryRemove).unwrap().expect("each state differs");
+        }
+        let counts: Vec<usize> = list_checkpoints("CheckpointPruned").unwrap().iter().map(|summary| summary.message_count).collect();
+        let newest: Vec<usize> = (5..=kept + 4).rev().collect();
+        assert_eq!(counts, newest, "the three oldest were pruned");
+        assert_eq!(fs::read_dir(checkpoint_dir("CheckpointPruned")).unwrap().count(), kept);
+    }
+
+    #[test]
+    fn restoring_puts_back_messages_thread_and_counts_but_keeps_the_current_prompt() {
+        let mut conversation = conversation("CheckpointRestore", 2);
+        conversation.set_last_response_id("resp_1".to_string());
+        conversation.summarization_count = 3;
+        let checkpoint = Checkpoint::capture(&conversation, CheckpointReason::Summarize);
+
+        conversation.replace_history(vec![message("system", "Be briefer."), message("user", "new start")]);
+        conversation.forget_response_id();
+        conversation.summarization_count = 0;
+
+        checkpoint.restore_into(&mut conversation);
+        assert_eq!(contents(&conversation.local_history), ["Be briefer.", "question 0", "answer 0", "question 1", "answer 1"]);
+        assert_eq!(conversation.get_last_response_id().map(String::as_str), Some("resp_1"));
+        assert_eq!(conversation.summarization_count, 3);
+
+        let unthreaded = Checkpoint::capture(&conversation, CheckpointReason::Rollback);
+        let unthreaded = Checkpoint { last_response_id: None, last_response_at: None, ..unthreaded };
+        unthreaded.restore_into(&mut conversation);
+        assert_eq!(conversation.get_last_response_id(), None, "a checkpoint without a thread drops the current one");
+    }
+}
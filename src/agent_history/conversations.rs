@@ -24,6 +24,75 @@
 //! ---------------------------------------------------------------
 
 use crate::prelude::*;
+use crate::persona::experiment::ExperimentTag;
+use crate::utilities::language::{self, Lang, LanguageSource, LanguageTracker};
+use std::ops::Range;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use crate::agent_history::freshness;
+use crate::llm::request::ChatRequestError;
+
+/// Start of the system message that holds the conversation summary (the recent tier)
+pub const SUMMARY_PREFIX: &str = "[Previous conversation summary: ";
+
+/// Start of the system message that holds the long-term summary (sent before the recent one)
+pub const LONG_TERM_PREFIX: &str = "[Long-term summary: ";
+
+/// Whether a message is either summary tier
+pub fn is_summary_message(message: &Message) -> bool {
+    message.role == "system"
+        && (message.content.starts_with(SUMMARY_PREFIX) || message.content.starts_with(LONG_TERM_PREFIX))
+}
+
+/// `Message::source` of system messages added with the `nudge` command
+pub const NUDGE_SOURCE: &str = "nudge";
+
+/// Whether a message is a recorded nudge
+pub fn is_nudge(message: &Message) -> bool {
+    message.source.as_deref() == Some(NUDGE_SOURCE)
+}
+
+/// # response_id_rejected
+///
+/// **Purpose:**
+/// Whether a request error says the server no longer knows the `previous_response_id` it was sent.
+///
+/// **Details:**
+/// The clients report failures as text (`API error: <status> - <body>`), so
+/// this looks for a 400 or 404 whose body is about the previous response.
+pub fn response_id_rejected(error: &str) -> bool {
+    let error = error.to_lowercase();
+    let status = error.starts_with("api error: 400") || error.starts_with("api error: 404");
+    status && (error.contains("previous_response_id")
+        || error.contains("previous response")
+        || (error.contains("response") && error.contains("not found")))
+}
+
+/// # Nudge
+///
+/// **Summary:**
+/// An instruction added with `nudge`, active until `nudge clear`.
+///
+/// **Fields:**
+/// - `text`: The instruction
+/// - `in_thread`: Already sent in the server-side thread, so threaded requests don't repeat it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nudge {
+    pub text: String,
+    pub in_thread: bool,
+}
+
+impl Nudge {
+    /// The system message that carries it
+    pub fn message(&self) -> Message {
+        Message {
+            role: "system".to_string(),
+            content: self.text.clone(),
+            meta: None,
+            source: Some(NUDGE_SOURCE.to_string()),
+        }
+    }
+}
 
 /// # GrokConversation
 ///
@@ -33,7 +102,22 @@ use crate::prelude::*;
 /// **Fields:**
 /// - `local_history`: Complete message history (system prompt + all messages)
 /// - `last_response_id`: Grok's last response ID for threading
+/// - `last_response_at`: When `last_response_id` was set (for `grok.response_id_ttl_hours`)
 /// - `persona`: The AI persona configuration for this conversation
+/// - `experiment`: Prompt experiment variant this conversation runs under, if any
+/// - `started_at`: RFC3339 time the conversation began (carried over from loaded history)
+/// - `last_user_message_at`: When the last user message was added (duplicate guard)
+/// - `language`: Language detected from the user's messages
+/// - `language_override`: Language set with the `language` command (beats everything else)
+/// - `summarize_override`: Summarize mode set with `set summarize` (beats the persona's)
+/// - `model_override`: Model set with the `model` command (beats `grok.model_name`)
+/// - `reminded_at`: History length at the last manual-mode summarize reminder
+/// - `nudges`: Active nudges for this session (history keeps a record of every nudge, cleared or not)
+/// - `summarization_count`: Times this history has been summarized (carried over from loaded history)
+/// - `total_message_count`: Messages added over the conversation's whole life (carried over from loaded
+///   history); summarizing, trimming and clearing never lower it
+/// - `last_exchange_at`: When the last reply was added (carried over from loaded history)
+/// - `context_note`: One-off system note for the next request, never stored (see `agent_history::freshness`)
 ///
 /// **Usage Example:**
 /// ```rust
@@ -46,7 +130,21 @@ use crate::prelude::*;
 pub struct GrokConversation {
     pub local_history: Vec<Message>,
     last_response_id: Option<String>,
+    last_response_at: Option<DateTime<Utc>>,
     pub persona: Arc<Persona>,
+    pub experiment: Option<ExperimentTag>,
+    pub started_at: String,
+    last_user_message_at: Option<Instant>,
+    language: LanguageTracker,
+    language_override: Option<Lang>,
+    summarize_override: Option<SummarizeMode>,
+    model_override: Option<String>,
+    reminded_at: Option<usize>,
+    nudges: Vec<Nudge>,
+    pub summarization_count: usize,
+    pub total_message_count: usize,
+    pub last_exchange_at: Option<DateTime<Utc>>,
+    context_note: Option<String>,
 }
 
 impl GrokConversation {
@@ -70,6 +168,8 @@ impl GrokConversation {
         let sys_message = Message {
             role: "system".to_string(),
             content: persona.system_prompt.clone(),
+            meta: None,
+            source: None,
         };
 
         let local_history = vec![sys_message];
@@ -77,7 +177,21 @@ impl GrokConversation {
         GrokConversation {
             local_history,
             last_response_id: None,
+            last_response_at: None,
             persona,
+            experiment: None,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            last_user_message_at: None,
+            language: LanguageTracker::default(),
+            language_override: None,
+            summarize_override: None,
+            model_override: None,
+            reminded_at: None,
+            nudges: Vec::new(),
+            summarization_count: 0,
+            total_message_count: 0,
+            last_exchange_at: None,
+            context_note: None,
         }
     }
 
@@ -99,10 +213,30 @@ impl GrokConversation {
     /// let conversation = GrokConversation::with_history(persona, loaded);
     /// ```
     pub fn with_history(persona: Arc<Persona>, loaded_history: Vec<Message>) -> Self {
+        let mut language = LanguageTracker::default();
+        language.seed(
+            loaded_history.iter().filter(|m| m.role == "user").map(|m| m.content.as_str()),
+            GLOBAL_CONFIG.history.language_shift_messages,
+        );
+
         GrokConversation {
             local_history: loaded_history,
             last_response_id: None,
+            last_response_at: None,
             persona,
+            experiment: None,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            last_user_message_at: None,
+            language,
+            language_override: None,
+            summarize_override: None,
+            model_override: None,
+            reminded_at: None,
+            nudges: Vec::new(),
+            summarization_count: 0,
+            total_message_count: 0,
+            last_exchange_at: None,
+            context_note: None,
         }
     }
 
@@ -115,22 +249,146 @@ impl GrokConversation {
     /// - `content`: The user's message text
     ///
     /// **Returns:**
-    /// None (mutates local_history)
+    /// `bool` - false if the message was dropped as a duplicate
+    ///
+    /// **Details:**
+    /// A message identical to the last entry in history, sent within
+    /// `history.duplicate_message_window_secs` of it, is assumed to be a retry
+    /// racing a queued send and is skipped. The same text sent later, or after
+    /// a reply, is kept. Use `force_user_message` to bypass the guard.
     ///
     /// **Examples:**
     /// ```rust
     /// conversation.add_user_message("What is Rust?");
     /// ```
-    pub fn add_user_message(&mut self, content: &str) {
+    pub fn add_user_message(&mut self, content: &str) -> bool {
+        let window = Duration::from_secs(GLOBAL_CONFIG.history.duplicate_message_window_secs);
+        let within_window = self.last_user_message_at
+            .is_some_and(|at| at.elapsed() < window);
+        let repeats_last = self.local_history.last()
+            .is_some_and(|last| last.role == "user" && last.content == content);
+
+        if within_window && repeats_last {
+            log_info!("Skipped duplicate user message for {} ({} chars)", self.persona.name, content.len());
+            return false;
+        }
+
+        self.force_user_message(content);
+        true
+    }
+
+    /// # force_user_message
+    ///
+    /// **Purpose:**
+    /// Adds a user message without the duplicate guard.
+    pub fn force_user_message(&mut self, content: &str) {
 
         let new_msg = Message {
             role: "user".to_string(),
             content: content.to_string(),
+            meta: None,
+            source: None,
         };
 
         self.local_history.push(new_msg);
+        self.total_message_count += 1;
+        self.last_user_message_at = Some(Instant::now());
+    }
+
+    /// # consecutive_duplicates
+    ///
+    /// **Purpose:**
+    /// Finds messages that repeat the message right before them (same role and content).
+    ///
+    /// **Returns:**
+    /// `Vec<usize>` - Indices into `local_history` that a dedupe would remove
+    ///
+    /// **Details:**
+    /// System messages are never reported.
+    pub fn consecutive_duplicates(&self) -> Vec<usize> {
+        self.local_history.windows(2)
+            .enumerate()
+            .filter(|(_, pair)| {
+                pair[1].role != "system"
+                    && pair[0].role == pair[1].role
+                    && pair[0].content == pair[1].content
+            })
+            .map(|(i, _)| i + 1)
+            .collect()
+    }
+
+    /// # remove_consecutive_duplicates
+    ///
+    /// **Purpose:**
+    /// Drops every message reported by `consecutive_duplicates`.
+    ///
+    /// **Returns:**
+    /// `usize` - Number of messages removed
+    pub fn remove_consecutive_duplicates(&mut self) -> usize {
+        let duplicates = self.consecutive_duplicates();
+        let mut index = 0;
+        self.local_history.retain(|_| {
+            let keep = !duplicates.contains(&index);
+            index += 1;
+            keep
+        });
+        duplicates.len()
     }
     
+    /// # removal_range
+    ///
+    /// **Purpose:**
+    /// Works out which messages `history remove` would take out.
+    ///
+    /// **Parameters:**
+    /// - `index`: Position in `local_history`, as shown by `history list`
+    /// - `pair`: Also take the other half of the exchange
+    ///
+    /// **Returns:**
+    /// `Result<Range<usize>, String>` - The messages to remove, or why it's refused
+    ///
+    /// **Details:**
+    /// System messages (the prompt and summaries) are protected. With `pair`, a
+    /// user message takes the reply right after it and a reply takes the user
+    /// message right before it.
+    pub fn removal_range(&self, index: usize, pair: bool) -> Result<Range<usize>, String> {
+        let Some(message) = self.local_history.get(index) else {
+            return Err(format!("No message #{} (history has {})", index, self.local_history.len()));
+        };
+        if message.role == "system" {
+            return Err(format!("#{} is a system message (prompt or summary) and can't be removed", index));
+        }
+        if !pair {
+            return Ok(index..index + 1);
+        }
+
+        let role_at = |i: usize| self.local_history.get(i).map(|m| m.role.as_str());
+        match message.role.as_str() {
+            "user" if role_at(index + 1) == Some("assistant") => Ok(index..index + 2),
+            "assistant" if index > 0 && role_at(index - 1) == Some("user") => Ok(index - 1..index + 1),
+            _ => Err(format!("#{} has no paired message next to it; drop --pair to remove it alone", index)),
+        }
+    }
+
+    /// # remove_messages
+    ///
+    /// **Purpose:**
+    /// Takes a range (from `removal_range`) out of history.
+    ///
+    /// **Returns:**
+    /// `Vec<Message>` - The removed messages
+    ///
+    /// **Details:**
+    /// Clears the response ID: the server-side thread still holds the removed
+    /// messages, so the next request has to send the edited history in full.
+    pub fn remove_messages(&mut self, range: Range<usize>) -> Vec<Message> {
+        let removed: Vec<Message> = self.local_history.drain(range).collect();
+        self.last_response_id = None;
+        self.last_response_at = None;
+        log_info!("Removed {} message(s) from {} history", removed.len(), self.persona.name);
+        removed
+    }
+
     /// # add_assistant_message
     ///
     /// **Purpose:**
@@ -147,13 +405,49 @@ impl GrokConversation {
     /// conversation.add_assistant_message(response.full_text);
     /// ```
     pub fn add_assistant_message(&mut self, content: String) {
+        self.add_assistant_message_with_meta(content, None);
+    }
+
+    /// # add_assistant_message_with_meta
+    ///
+    /// **Purpose:**
+    /// Adds an assistant reply along with the model identity that produced it.
+    ///
+    /// **Parameters:**
+    /// - `content`: The assistant's response text
+    /// - `meta`: Model, temperature, latency, and token usage for the reply
+    ///
+    /// **Returns:**
+    /// None (mutates local_history)
+    pub fn add_assistant_message_with_meta(&mut self, content: String, meta: Option<ResponseMeta>) {
 
         let msg = Message {
             role: "assistant".to_string(),
             content,
+            meta,
+            source: None,
         };
 
         self.local_history.push(msg);
+        self.total_message_count += 1;
+        self.last_exchange_at = Some(Utc::now());
+    }
+
+    /// # nth_last_assistant_message
+    ///
+    /// **Purpose:**
+    /// Finds the nth-last assistant message (and with it, its metadata).
+    ///
+    /// **Parameters:**
+    /// - `nth_last`: 1 for the last assistant reply, 2 for the one before, ...
+    ///
+    /// **Returns:**
+    /// `Option<&Message>` - The assistant message, if that many exist
+    pub fn nth_last_assistant_message(&self, nth_last: usize) -> Option<&Message> {
+        self.local_history.iter()
+            .rev()
+            .filter(|msg| msg.role == "assistant")
+            .nth(nth_last.saturating_sub(1))
     }
 
     /// # set_last_response_id
@@ -165,9 +459,40 @@ impl GrokConversation {
     /// - `id`: The response ID from Grok API
     ///
     /// **Returns:**
-    /// None (mutates last_response_id)
+    /// None (mutates last_response_id and its timestamp)
     pub fn set_last_response_id(&mut self, id: String) {
         self.last_response_id = Some(id);
+        self.last_response_at = Some(Utc::now());
+    }
+
+    /// # restore_response_id
+    ///
+    /// **Purpose:**
+    /// Puts back the response ID saved with the history, so the next request continues the thread.
+    ///
+    /// **Parameters:**
+    /// - `id`: The saved response ID
+    /// - `set_at`: When it was set; None (a hand-edited file) counts as expired once a TTL applies
+    pub fn restore_response_id(&mut self, id: String, set_at: Option<DateTime<Utc>>) {
+        self.last_response_id = Some(id);
+        self.last_response_at = Some(set_at.unwrap_or(DateTime::<Utc>::MIN_UTC));
+    }
+
+    /// When the current response ID was set (None without one)
+    pub fn response_id_set_at(&self) -> Option<DateTime<Utc>> {
+        self.last_response_id.as_ref()?;
+        self.last_response_at
+    }
+
+    /// # forget_response_id
+    ///
+    /// **Purpose:**
+    /// Drops the response ID (rejected by the server, or rolled back past),
+    /// so the next request sends the full history.
+    pub fn forget_response_id(&mut self) {
+        log_info!("Dropped the response ID for {}; sending full history", self.persona.name);
+        self.last_response_id = None;
+        self.last_response_at = None;
     }
 
     /// # get_last_response_id
@@ -181,6 +506,51 @@ impl GrokConversation {
         self.last_response_id.as_ref()
     }
 
+    /// # response_id_age
+    ///
+    /// **Purpose:**
+    /// How long ago the current response ID was set.
+    ///
+    /// **Returns:**
+    /// `Option<chrono::Duration>` - None when there is no response ID
+    pub fn response_id_age(&self) -> Option<chrono::Duration> {
+        self.last_response_id.as_ref()?;
+        self.last_response_at.map(|at| Utc::now() - at)
+    }
+
+    /// # response_id_expired
+    ///
+    /// **Purpose:**
+    /// Whether the response ID is older than `grok.response_id_ttl_hours`.
+    ///
+    /// **Details:**
+    /// The API only keeps threaded state for a limited time; past that,
+    /// `previous_response_id` yields errors or silently lost context.
+    /// A TTL of 0 never expires.
+    pub fn response_id_expired(&self) -> bool {
+        let ttl_hours = GLOBAL_CONFIG.grok.response_id_ttl_hours;
+        ttl_hours > 0 && self.response_id_age()
+            .is_some_and(|age| age >= chrono::Duration::hours(ttl_hours as i64))
+    }
+
+    /// # expire_stale_response_id
+    ///
+    /// **Purpose:**
+    /// Drops the response ID if it has expired, so the next request sends the full history.
+    ///
+    /// **Returns:**
+    /// `bool` - true if an ID was dropped (the caller should tell the user)
+    pub fn expire_stale_response_id(&mut self) -> bool {
+        if !self.response_id_expired() {
+            return false;
+        }
+        log_info!("Response ID for {} expired after {}h; sending full history",
+            self.persona.name, GLOBAL_CONFIG.grok.response_id_ttl_hours);
+        self.last_response_id = None;
+        self.last_response_at = None;
+        true
+    }
+
     /// # build_request
     ///
     /// **Purpose:**
@@ -189,22 +559,55 @@ impl GrokConversation {
     /// **Details:**
     /// - If no response_id: Sends full history (new conversation or first message)
     /// - If response_id exists: Only sends the last user message (conversation threading)
+    /// - An expired response_id (see `response_id_expired`) counts as absent
+    /// - Active nudges go in as system messages; see `build_request_with`
     ///
     /// **Returns:**
-    /// ChatRequest ready to send to GrokClient
+    /// `Result<ChatRequest, ChatRequestError>` - Request ready to send, or why the
+    /// persona's settings can't make one (e.g. temperature out of range)
     ///
     /// **Examples:**
     /// ```rust
-    /// let request = conversation.build_request();
+    /// let request = conversation.build_request()?;
     /// let response = client.send_streaming_request(&request, tx).await?;
     /// ```
-    pub fn build_request(&self) -> ChatRequest {
-        let input = if self.last_response_id.is_none() {
-            log_info!("Building request with full history ({} messages)", self.local_history.len());
-            self.local_history.clone()
+    pub fn build_request(&self) -> Result<ChatRequest, ChatRequestError> {
+        self.build_request_with(true)
+    }
+
+    /// # build_request_with
+    ///
+    /// **Purpose:**
+    /// `build_request`, choosing whether active nudges take part.
+    ///
+    /// **Parameters:**
+    /// - `with_nudges`: Include active nudges (false for tweet drafts)
+    ///
+    /// **Details:**
+    /// Recorded nudges never go out from history: cleared ones are done with,
+    /// and active ones are added from `nudges` instead.
+    /// - Full history: active nudges come right before the newest user message
+    /// - Threaded: nudges not yet in the thread are sent ahead of the newest message
+    /// - Without nudges, a thread that already holds one is not continued; the
+    ///   full history goes out instead
+    ///
+    /// A pending `context_note` goes out with the nudges, ahead of them, in
+    /// either mode.
+    pub fn build_request_with(&self, with_nudges: bool) -> Result<ChatRequest, ChatRequestError> {
+        let thread_has_nudges = self.nudges.iter().any(|nudge| nudge.in_thread);
+        let previous_response_id = self.last_response_id.clone()
+            .filter(|_| !self.response_id_expired())
+            .filter(|_| with_nudges || !thread_has_nudges);
+
+        let input: Vec<Message> = if previous_response_id.is_none() {
+            log_debug!("Building request with full history ({} messages)", self.local_history.len());
+            self.local_history.iter()
+                .filter(|msg| !is_nudge(msg))
+                .cloned()
+                .collect()
         } else {
-            if let Some(last_msg) = self.local_history.last() {
-                log_info!("Building request with last message only (threaded conversation)");
+            if let Some(last_msg) = self.local_history.iter().rev().find(|msg| !is_nudge(msg)) {
+                log_debug!("Building request with last message only (threaded conversation)");
                 vec![last_msg.clone()]
             } else {
                 log_error!("No messages in history despite response ID existing!");
@@ -212,13 +615,107 @@ impl GrokConversation {
             }
         };
 
-        ChatRequest {
-            model: GLOBAL_CONFIG.grok.model_name.to_string(),
-            input,
-            temperature: self.persona.temperature.unwrap_or(GLOBAL_CONFIG.grok.default_temperature),
-            previous_response_id: self.last_response_id.clone(),
-            stream: GLOBAL_CONFIG.grok.stream_enabled,
+        let mut ephemeral = Vec::new();
+        if with_nudges {
+            ephemeral.extend(self.context_note.iter().map(|note| Message {
+                role: "system".to_string(),
+                content: note.clone(),
+                meta: None,
+                source: None,
+            }));
+            ephemeral.extend(self.nudges.iter()
+                .filter(|nudge| previous_response_id.is_none() || !nudge.in_thread)
+                .map(Nudge::message));
         }
+
+        ChatRequest::builder()
+            .model(self.model())
+            .persona(&self.persona)
+            .messages(input)
+            .ephemeral(ephemeral)
+            .previous_response_id(previous_response_id)
+            .build()
+    }
+
+    /// # add_nudge
+    ///
+    /// **Purpose:**
+    /// Records an instruction in history and makes it active from the next request on.
+    pub fn add_nudge(&mut self, text: &str) {
+        let nudge = Nudge { text: text.to_string(), in_thread: false };
+        self.local_history.push(nudge.message());
+        self.total_message_count += 1;
+        self.nudges.push(nudge);
+        log_info!("Added nudge for {} ({} active)", self.persona.name, self.nudges.len());
+    }
+
+    /// Nudges sent with every request until cleared
+    pub fn active_nudges(&self) -> &[Nudge] {
+        &self.nudges
+    }
+
+    /// # clear_nudges
+    ///
+    /// **Purpose:**
+    /// Stops sending the active nudges; their records stay in history.
+    ///
+    /// **Returns:**
+    /// `usize` - Number of nudges cleared
+    ///
+    /// **Details:**
+    /// A server-side thread that already holds a nudge would keep obeying it, so
+    /// the response ID is dropped and the next request sends the history in full.
+    pub fn clear_nudges(&mut self) -> usize {
+        if self.nudges.iter().any(|nudge| nudge.in_thread) {
+            self.last_response_id = None;
+            self.last_response_at = None;
+        }
+        let cleared = self.nudges.len();
+        self.nudges.clear();
+        cleared
+    }
+
+    /// # nudges_sent
+    ///
+    /// **Purpose:**
+    /// Records whether the request that just got a response ID carried the active nudges.
+    ///
+    /// **Details:**
+    /// Call after `set_last_response_id` with the `with_nudges` the request was
+    /// built with. A request without nudges never continues a thread holding
+    /// one, so either way the new thread holds them exactly when they were sent.
+    /// A context note that went out with them has done its job and is dropped.
+    pub fn nudges_sent(&mut self, with_nudges: bool) {
+        for nudge in &mut self.nudges {
+            nudge.in_thread = with_nudges;
+        }
+        if with_nudges {
+            self.context_note = None;
+        }
+    }
+
+    /// # context_gap
+    ///
+    /// **Purpose:**
+    /// How long ago the last exchange was, if longer than `history.resync_after_hours`.
+    ///
+    /// **Returns:**
+    /// `Option<chrono::Duration>` - None for a conversation with nothing to pick up
+    pub fn context_gap(&self, now: DateTime<Utc>) -> Option<chrono::Duration> {
+        let (_, replies) = self.conversation_counts();
+        if replies == 0 && self.summary_text().is_none() && self.long_term_summary_text().is_none() {
+            return None;
+        }
+        freshness::stale_gap(self.last_exchange_at?, now, GLOBAL_CONFIG.history.resync_after_hours)
+    }
+
+    /// Sets (or clears) the note sent once with the next request
+    pub fn set_context_note(&mut self, note: Option<String>) {
+        self.context_note = note;
+    }
+
+    pub fn context_note(&self) -> Option<&str> {
+        self.context_note.as_deref()
     }
 
     /// # should_summarize
@@ -239,14 +736,11 @@ impl GrokConversation {
     /// }
     /// ```
     pub fn should_summarize(&self) -> bool {
-        if !self.persona.enable_history {
+        if !self.persona.enable_history || self.summarize_mode() != SummarizeMode::Auto {
             return false;
         }
 
-        let message_count = self.local_history.iter()
-            .filter(|msg| msg.role != "system" || !msg.content.contains("[Previous conversation summary:"))
-            .count();
-
+        let message_count = self.history_length();
         let threshold_exceeded = message_count > self.persona.summary_threshold;
 
         if threshold_exceeded {
@@ -257,6 +751,111 @@ impl GrokConversation {
         threshold_exceeded
     }
 
+    /// # context_texts
+    ///
+    /// **Purpose:**
+    /// Everything the model holds for the next request, as text.
+    ///
+    /// **Details:**
+    /// The history as an unthreaded request sends it (nudge records left out),
+    /// then the active nudges and any pending context note. A threaded request
+    /// sends less, but the thread already holds the rest, so the model's
+    /// context is the same either way.
+    pub fn context_texts(&self) -> impl Iterator<Item = &str> {
+        self.local_history.iter()
+            .filter(|msg| !is_nudge(msg))
+            .map(|msg| msg.content.as_str())
+            .chain(self.nudges.iter().map(|nudge| nudge.text.as_str()))
+            .chain(self.context_note.as_deref())
+    }
+
+    /// Messages counted against `summary_threshold` (everything but the summaries)
+    pub fn history_length(&self) -> usize {
+        self.local_history.iter()
+            .filter(|msg| !is_summary_message(msg))
+            .count()
+    }
+
+    /// The summarize mode in effect: `set summarize` override, else the persona's
+    pub fn summarize_mode(&self) -> SummarizeMode {
+        self.summarize_override.unwrap_or(self.persona.summarize_mode)
+    }
+
+    pub fn summarize_override(&self) -> Option<SummarizeMode> {
+        self.summarize_override
+    }
+
+    /// Sets (or with None, clears) the `set summarize` override
+    pub fn set_summarize_override(&mut self, mode: Option<SummarizeMode>) {
+        self.summarize_override = mode;
+        self.reminded_at = None;
+    }
+
+    /// The model requests go to: the `model` command's choice, else `grok.model_name`
+    pub fn model(&self) -> &str {
+        self.model_override.as_deref().unwrap_or(&GLOBAL_CONFIG.grok.model_name)
+    }
+
+    pub fn model_override(&self) -> Option<&str> {
+        self.model_override.as_deref()
+    }
+
+    /// # set_model_override
+    ///
+    /// **Purpose:**
+    /// Sets (or with None, clears) the `model` command override.
+    ///
+    /// **Returns:**
+    /// `bool` - Whether a response thread was dropped
+    ///
+    /// **Details:**
+    /// A response ID only threads on with the model that produced it, so
+    /// changing the model drops it and the next request sends the full history.
+    pub fn set_model_override(&mut self, model: Option<String>) -> bool {
+        let previous = self.model().to_string();
+        self.model_override = model;
+        if self.model() == previous {
+            return false;
+        }
+        self.last_response_at = None;
+        self.last_response_id.take().is_some()
+    }
+
+    /// # summary_reminder
+    ///
+    /// **Purpose:**
+    /// Decides whether a manual-mode conversation should be reminded to summarize.
+    ///
+    /// **Parameters:**
+    /// - `every`: Messages between reminders once past the threshold
+    ///
+    /// **Returns:**
+    /// `Option<usize>` - The history length to mention, when a reminder is due
+    ///
+    /// **Details:**
+    /// The first reminder comes as soon as the threshold is passed, the next
+    /// after `every` more messages. Dropping back under the threshold (after
+    /// `summarize` or `clear`) starts over.
+    pub fn summary_reminder(&mut self, every: usize) -> Option<usize> {
+        if !self.persona.enable_history || self.summarize_mode() != SummarizeMode::Manual {
+            return None;
+        }
+
+        let count = self.history_length();
+        if count <= self.persona.summary_threshold {
+            self.reminded_at = None;
+            return None;
+        }
+
+        match self.reminded_at {
+            Some(last) if count < last + every.max(1) => None,
+            _ => {
+                self.reminded_at = Some(count);
+                Some(count)
+            }
+        }
+    }
+
     /// # message_count
     ///
     /// **Purpose:**
@@ -279,9 +878,150 @@ impl GrokConversation {
         self.local_history.first()
     }
 
+    /// # replace_persona
+    ///
+    /// **Purpose:**
+    /// Switches the conversation to a reloaded persona and its system prompt.
+    ///
+    /// **Returns:**
+    /// `bool` - Whether the system prompt changed
+    ///
+    /// **Details:**
+    /// The prompt replaces the system message at index 0 (inserted if the
+    /// history doesn't start with one). A changed prompt drops the response
+    /// ID, since the server's thread still holds the old one; the next request
+    /// sends the full history.
+    pub fn replace_persona(&mut self, persona: Arc<Persona>) -> bool {
+        let prompt = persona.system_prompt.clone();
+        self.persona = persona;
+
+        let changed = match self.local_history.first_mut() {
+            Some(first) if first.role == "system" && !is_summary_message(first) => {
+                let changed = first.content != prompt;
+                first.content = prompt;
+                changed
+            }
+            _ => {
+                self.local_history.insert(0, Message {
+                    role: "system".to_string(),
+                    content: prompt,
+                    meta: None,
+                    source: None,
+                });
+                true
+            }
+        };
+        if changed && self.last_response_id.is_some() {
+            self.forget_response_id();
+        }
+        changed
+    }
+
+    /// # summary_text
+    ///
+    /// **Purpose:**
+    /// The summary currently standing in for older messages (the recent tier), without its wrapper.
+    ///
+    /// **Returns:**
+    /// `Option<&str>` - None if the history hasn't been summarized
+    pub fn summary_text(&self) -> Option<&str> {
+        self.summary_with_prefix(SUMMARY_PREFIX)
+    }
+
+    /// # long_term_summary_text
+    ///
+    /// **Purpose:**
+    /// The long-term summary older recent summaries were folded into, without its wrapper.
+    ///
+    /// **Returns:**
+    /// `Option<&str>` - None until the first fold
+    pub fn long_term_summary_text(&self) -> Option<&str> {
+        self.summary_with_prefix(LONG_TERM_PREFIX)
+    }
+
+    fn summary_with_prefix(&self, prefix: &str) -> Option<&str> {
+        self.local_history.iter()
+            .filter(|msg| msg.role == "system")
+            .find_map(|msg| msg.content.strip_prefix(prefix))
+            .map(|summary| summary.strip_suffix(']').unwrap_or(summary))
+    }
+
+    /// # injected_messages
+    ///
+    /// **Purpose:**
+    /// System messages other than the prompt, summaries, and nudges; they go out with every full request.
+    pub fn injected_messages(&self) -> Vec<&Message> {
+        self.local_history.iter()
+            .skip(1)
+            .filter(|msg| msg.role == "system" && !is_summary_message(msg) && !is_nudge(msg))
+            .collect()
+    }
+
+    /// # conversation_counts
+    ///
+    /// **Purpose:**
+    /// Number of user and assistant messages in memory.
+    ///
+    /// **Returns:**
+    /// `(usize, usize)` - (user, assistant)
+    pub fn conversation_counts(&self) -> (usize, usize) {
+        self.local_history.iter().fold((0, 0), |(user, assistant), msg| match msg.role.as_str() {
+            "user" => (user + 1, assistant),
+            "assistant" => (user, assistant + 1),
+            _ => (user, assistant),
+        })
+    }
+
+    /// Whether the next request continues a thread and sends only the newest message
+    pub fn next_request_threaded(&self) -> bool {
+        self.last_response_id.is_some() && !self.response_id_expired()
+    }
+
     /// # clear_history
     ///
     /// **Purpose:**
+    /// # observe_language
+    ///
+    /// **Purpose:**
+    /// Feeds a user message (as typed, before any prompt template) to language detection.
+    ///
+    /// **Details:**
+    /// Skipped while the language is pinned by persona or override. The detected
+    /// language only changes when it was unknown or after
+    /// `history.language_shift_messages` consecutive messages in another one.
+    pub fn observe_language(&mut self, sample: &str) {
+        if self.language_override.is_some() || self.persona_language().is_some() {
+            return;
+        }
+        if let Some(lang) = self.language.observe(sample, GLOBAL_CONFIG.history.language_shift_messages) {
+            log_info!("Conversation language for {}: {}", self.persona.name, lang.eng_name());
+        }
+    }
+
+    /// Language pinned by the persona's `language` field, if it names a known one
+    fn persona_language(&self) -> Option<Lang> {
+        self.persona.language.as_deref().and_then(language::parse_language)
+    }
+
+    /// # language
+    ///
+    /// **Purpose:**
+    /// The language replies should be in, and where it came from.
+    ///
+    /// **Returns:**
+    /// `Option<(Lang, LanguageSource)>` - Override first, then persona pin, then detection;
+    /// None while nothing is known
+    pub fn language(&self) -> Option<(Lang, LanguageSource)> {
+        self.language_override.map(|lang| (lang, LanguageSource::Override))
+            .or_else(|| self.persona_language().map(|lang| (lang, LanguageSource::Persona)))
+            .or_else(|| self.language.detected().map(|lang| (lang, LanguageSource::Detected)))
+    }
+
+    /// Sets (or with None, clears) the `language` command override
+    pub fn set_language_override(&mut self, lang: Option<Lang>) {
+        self.language_override = lang;
+    }
+
     /// Resets conversation to just the system prompt.
     ///
     /// **Returns:**
@@ -297,12 +1037,31 @@ impl GrokConversation {
         if let Some(prompt) = system_prompt {
             self.local_history = vec![prompt];
             self.last_response_id = None;
+            self.last_response_at = None;
+            self.language.reset();
             log_info!("Conversation history cleared");
         } else {
             log_error!("Cannot clear history - no system prompt found!");
         }
     }
 
+    /// # fresh_start
+    ///
+    /// **Purpose:**
+    /// Clears the conversation down to the system prompt and the long-term summary.
+    ///
+    /// **Details:**
+    /// Like `clear_history`, but long-term memory survives; the recent summary
+    /// and every message go. Archive the history first if it should be recoverable.
+    pub fn fresh_start(&mut self) {
+        let long_term = self.local_history.iter()
+            .find(|msg| msg.role == "system" && msg.content.starts_with(LONG_TERM_PREFIX))
+            .cloned();
+        self.clear_history();
+        self.local_history.extend(long_term);
+        self.context_note = None;
+    }
+
     /// # replace_history
     ///
     /// **Purpose:**
@@ -326,4 +1085,499 @@ impl GrokConversation {
         log_info!("History replaced: {} messages -> {} messages", old_len, self.local_history.len());
     }
 
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Step = fn(&mut GrokConversation);
+    type Roles<'a> = &'a [(&'a str, &'a str)];
+
+    fn conversation() -> GrokConversation {
+        GrokConversation::new(Arc::new(Persona::from_yaml_str("name: Dedupe\nsystem_prompt: Be brief.\n").unwrap()))
+    }
+
+    fn message(role: &str, content: &str) -> Message {
+        Message { role: role.to_string(), content: content.to_string(), meta: None, source: None }
+    }
+
+    fn contents(conversation: &GrokConversation) -> Vec<(&str, &str)> {
+        conversation.local_history.iter().map(|m| (m.role.as_str(), m.content.as_str())).collect()
+    }
+
+    /// Pretends the last user message arrived `secs` ago
+    fn age_last_user_message(conversation: &mut GrokConversation, secs: u64) {
+        conversation.last_user_message_at = Instant::now().checked_sub(Duration::from_secs(secs));
+        assert!(conversation.last_user_message_at.is_some(), "the clock goes back {}s", secs);
+    }
+
+    #[test]
+    fn a_repeat_inside_the_window_is_dropped() {
+        let mut conversation = conversation();
+
+        assert!(conversation.add_user_message("Summarize that"));
+        assert!(!conversation.add_user_message("Summarize that"), "a racing retry is dropped");
+
+        assert_eq!(contents(&conversation), [("system", "Be brief."), ("user", "Summarize that")]);
+        assert_eq!(conversation.total_message_count, 1);
+    }
+
+    #[test]
+    fn legitimate_repeats_are_kept() {
+        assert!(GLOBAL_CONFIG.history.duplicate_message_window_secs > 0, "the guard is on by default");
+        let cases: [(&str, Step); 3] = [
+            ("outside the window", |c| age_last_user_message(c, GLOBAL_CONFIG.history.duplicate_message_window_secs + 1)),
+            ("after a reply", |c| c.add_assistant_message("Sure?".to_string())),
+            ("after a summary", |c| c.local_history.push(message("system", "[Summary of earlier conversation]"))),
+        ];
+
+        for (case, between) in cases {
+            let mut conversation = conversation();
+            conversation.add_user_message("yes");
+            between(&mut conversation);
+
+            assert!(conversation.add_user_message("yes"), "{}", case);
+            assert_eq!(conversation.local_history.iter().filter(|m| m.content == "yes").count(), 2, "{}", case);
+        }
+    }
+
+    #[test]
+    fn only_an_identical_message_counts_as_a_repeat() {
+        let mut conversation = conversation();
+        conversation.add_user_message("yes");
+
+        assert!(conversation.add_user_message("yes."));
+        assert!(conversation.add_user_message("Yes."));
+        assert!(!conversation.add_user_message("Yes."));
+        assert_eq!(conversation.history_length(), 4);
+    }
+
+    #[test]
+    fn forcing_skips_the_guard_and_restarts_the_window() {
+        let mut conversation = conversation();
+        conversation.add_user_message("again");
+        age_last_user_message(&mut conversation, 3600);
+
+        conversation.force_user_message("again");
+        assert_eq!(conversation.history_length(), 3);
+        assert!(!conversation.add_user_message("again"), "the forced message starts a new window");
+    }
+
+    #[test]
+    fn consecutive_duplicates_are_found_by_role_and_content() {
+        let cases: [(Roles, &[usize]); 5] = [
+            (&[("system", "p"), ("user", "a"), ("assistant", "b")], &[]),
+            (&[("system", "p"), ("user", "a"), ("user", "a"), ("assistant", "b")], &[2]),
+            (&[("system", "p"), ("user", "a"), ("user", "a"), ("user", "a")], &[2, 3]),
+            (&[("system", "p"), ("user", "a"), ("assistant", "a"), ("assistant", "b"), ("assistant", "b")], &[4]),
+            (&[("system", "p"), ("system", "p"), ("user", "a"), ("assistant", "b"), ("user", "a")], &[]),
+        ];
+
+        for (history, expected) in cases {
+            let mut conversation = conversation();
+            conversation.local_history = history.iter().map(|(role, content)| message(role, content)).collect();
+            assert_eq!(conversation.consecutive_duplicates(), expected, "{:?}", history);
+        }
+    }
+
+    #[test]
+    fn removing_duplicates_keeps_the_first_of_each_run_in_order() {
+        let mut conversation = conversation();
+        conversation.local_history.extend([
+            message("user", "one"),
+            message("user", "one"),
+            message("assistant", "reply"),
+            message("assistant", "reply"),
+            message("assistant", "reply"),
+            message("user", "two"),
+            message("assistant", "other"),
+        ]);
+
+        assert_eq!(conversation.remove_consecutive_duplicates(), 3);
+        assert_eq!(contents(&conversation), [
+            ("system", "Be brief."),
+            ("user", "one"),
+            ("assistant", "reply"),
+            ("user", "two"),
+            ("assistant", "other"),
+        ]);
+        assert!(conversation.consecutive_duplicates().is_empty());
+        assert_eq!(conversation.remove_consecutive_duplicates(), 0);
+    }
+
+    /// A short exchange whose response ID was set `age` ago
+    fn threaded(age: Option<chrono::Duration>) -> GrokConversation {
+        let mut conversation = conversation();
+        conversation.local_history.extend([message("user", "hi"), message("assistant", "hello"), message("user", "again")]);
+        conversation.restore_response_id("resp_1".to_string(), age.map(|age| Utc::now() - age));
+        conversation
+    }
+
+    #[test]
+    fn response_ids_expire_at_the_ttl() {
+        let ttl = chrono::Duration::hours(GLOBAL_CONFIG.grok.response_id_ttl_hours as i64);
+        assert!(ttl > chrono::Duration::zero(), "a TTL applies by default");
+        let cases = [
+            ("fresh", Some(chrono::Duration::hours(1)), false),
+            ("a minute short", Some(ttl - chrono::Duration::minutes(1)), false),
+            ("exactly the ttl", Some(ttl), true),
+            ("well past", Some(ttl * 3), true),
+            ("restored without a timestamp", None, true),
+        ];
+
+        for (case, age, expired) in cases {
+            let conversation = threaded(age);
+            assert_eq!(conversation.response_id_expired(), expired, "{}", case);
+            assert_eq!(conversation.next_request_threaded(), !expired, "{}", case);
+
+            let request = conversation.build_request().unwrap();
+            let sent = if expired { 4 } else { 1 };
+            assert_eq!(request.input.len(), sent, "{}", case);
+            assert_eq!(request.previous_response_id.is_some(), !expired, "{}", case);
+        }
+    }
+
+    #[test]
+    fn a_stale_id_is_expired_once_and_a_fresh_one_is_kept() {
+        let mut conversation = threaded(Some(chrono::Duration::days(365)));
+        assert!(conversation.expire_stale_response_id(), "the first request notes it");
+        assert!(!conversation.expire_stale_response_id(), "and only the first");
+        assert_eq!(conversation.get_last_response_id(), None);
+        assert_eq!(conversation.response_id_set_at(), None);
+        assert_eq!(conversation.response_id_age(), None);
+
+        conversation.set_last_response_id("resp_2".to_string());
+        assert!(!conversation.expire_stale_response_id());
+        assert_eq!(conversation.get_last_response_id().map(String::as_str), Some("resp_2"));
+        assert!(conversation.response_id_age().is_some_and(|age| age < chrono::Duration::minutes(1)));
+    }
+
+    #[test]
+    fn switching_models_drops_the_id_whatever_its_age() {
+        let cases = [("fresh", chrono::Duration::hours(1)), ("expired", chrono::Duration::days(365))];
+
+        for (case, age) in cases {
+            let mut conversation = threaded(Some(age));
+            assert!(conversation.set_model_override(Some("grok-other".to_string())), "{}", case);
+            assert_eq!(conversation.response_id_set_at(), None, "{}", case);
+            assert!(!conversation.expire_stale_response_id(), "nothing left to expire: {}", case);
+            assert_eq!(conversation.build_request().unwrap().input.len(), 4, "{}", case);
+
+            // The next reply threads on the new model with its own clock
+            conversation.set_last_response_id("resp_2".to_string());
+            assert!(!conversation.response_id_expired(), "{}", case);
+            assert!(!conversation.set_model_override(Some("grok-other".to_string())), "same model keeps it: {}", case);
+            assert!(conversation.next_request_threaded(), "{}", case);
+        }
+    }
+
+    const SPANISH: &str = "Hola, ¿cómo estás? Quiero hablar contigo sobre el proyecto de la semana que viene, porque tenemos muchas cosas pendientes.";
+
+    #[test]
+    fn the_override_beats_the_persona_pin_which_beats_detection() {
+        let pinned = |yaml: &str| GrokConversation::new(Arc::new(Persona::from_yaml_str(yaml).unwrap()));
+        let cases = [
+            ("detected", conversation(), None, Some((Lang::Spa, LanguageSource::Detected))),
+            ("pinned", pinned("name: P\nsystem_prompt: x\nlanguage: French\n"), None, Some((Lang::Fra, LanguageSource::Persona))),
+            ("pinned and overridden", pinned("name: P\nsystem_prompt: x\nlanguage: fra\n"), Some(Lang::Deu), Some((Lang::Deu, LanguageSource::Override))),
+            ("an unknown pin falls back to detection", pinned("name: P\nsystem_prompt: x\nlanguage: Elvish\n"), None, Some((Lang::Spa, LanguageSource::Detected))),
+        ];
+
+        for (case, mut conversation, overridden, expected) in cases {
+            conversation.set_language_override(overridden);
+            conversation.observe_language(SPANISH);
+            assert_eq!(conversation.language(), expected, "{}", case);
+        }
+    }
+
+    #[test]
+    fn detection_is_skipped_while_pinned_and_picks_up_after_auto() {
+        let mut conversation = conversation();
+        conversation.set_language_override(Some(Lang::Eng));
+        conversation.observe_language(SPANISH);
+        conversation.set_language_override(None);
+        assert_eq!(conversation.language(), None, "nothing was detected while overridden");
+
+        conversation.observe_language(SPANISH);
+        assert_eq!(conversation.language(), Some((Lang::Spa, LanguageSource::Detected)));
+        conversation.clear_history();
+        assert_eq!(conversation.language(), None, "a cleared conversation starts over");
+    }
+
+    /// A history-keeping conversation that passes its threshold with 4 messages after the prompt
+    fn summarizing(mode: &str) -> GrokConversation {
+        let yaml = format!("name: S\nsystem_prompt: x\nenable_history: true\nsummary_threshold: 4\nhistory_message_limit: 2\nsummarize_mode: {}\n", mode);
+        GrokConversation::new(Arc::new(Persona::from_yaml_str(&yaml).unwrap()))
+    }
+
+    /// Adds `count` messages, alternating user and assistant
+    fn grow(conversation: &mut GrokConversation, count: usize) {
+        for i in 0..count {
+            let role = if conversation.history_length() % 2 == 1 { "user" } else { "assistant" };
+            conversation.local_history.push(message(role, &format!("message {}", i)));
+        }
+    }
+
+    #[test]
+    fn only_auto_mode_triggers_summarization() {
+        let cases = [
+            ("auto", None, true),
+            ("manual", None, false),
+            ("off", None, false),
+            ("auto", Some(SummarizeMode::Manual), false),
+            ("manual", Some(SummarizeMode::Auto), true),
+            ("off", Some(SummarizeMode::Auto), true),
+        ];
+
+        for (mode, overridden, expected) in cases {
+            let mut conversation = summarizing(mode);
+            conversation.set_summarize_override(overridden);
+            grow(&mut conversation, 3);
+            assert!(!conversation.should_summarize(), "{} {:?}: at the threshold", mode, overridden);
+            grow(&mut conversation, 1);
+            assert_eq!(conversation.should_summarize(), expected, "{} {:?}: past it", mode, overridden);
+        }
+
+        let mut unsaved = GrokConversation::new(Arc::new(Persona::from_yaml_str(
+            "name: S\nsystem_prompt: x\nenable_history: false\nsummary_threshold: 4\nhistory_message_limit: 2\n"
+        ).unwrap()));
+        grow(&mut unsaved, 10);
+        assert!(!unsaved.should_summarize(), "nothing is summarized without history");
+    }
+
+    #[test]
+    fn manual_mode_reminds_past_the_threshold_then_every_n_messages() {
+        let mut conversation = summarizing("manual");
+        let mut reminders = Vec::new();
+        for _ in 0..20 {
+            grow(&mut conversation, 1);
+            reminders.extend(conversation.summary_reminder(5));
+        }
+        assert_eq!(reminders, [5, 10, 15, 20], "first past the threshold of 4, then every 5 more");
+
+        // Checking twice at the same length reminds once
+        grow(&mut conversation, 4);
+        assert_eq!(conversation.summary_reminder(5), Some(25));
+        assert_eq!(conversation.summary_reminder(5), None);
+    }
+
+    #[test]
+    fn dropping_under_the_threshold_starts_the_reminders_over() {
+        let mut conversation = summarizing("manual");
+        grow(&mut conversation, 6);
+        assert_eq!(conversation.summary_reminder(10), Some(7));
+
+        conversation.clear_history();
+        assert_eq!(conversation.summary_reminder(10), None);
+        grow(&mut conversation, 4);
+        assert_eq!(conversation.summary_reminder(10), Some(5), "not held back by the reminder before the clear");
+
+        // Switching modes starts over too
+        conversation.set_summarize_override(Some(SummarizeMode::Manual));
+        assert_eq!(conversation.summary_reminder(10), Some(5));
+    }
+
+    #[test]
+    fn only_manual_mode_reminds() {
+        for (mode, overridden) in [("auto", None), ("off", None), ("manual", Some(SummarizeMode::Off))] {
+            let mut conversation = summarizing(mode);
+            conversation.set_summarize_override(overridden);
+            grow(&mut conversation, 10);
+            assert_eq!(conversation.summary_reminder(1), None, "{} {:?}", mode, overridden);
+        }
+    }
+
+    #[test]
+    fn every_zero_still_waits_for_one_more_message() {
+        let mut conversation = summarizing("manual");
+        grow(&mut conversation, 5);
+        assert_eq!(conversation.summary_reminder(0), Some(6));
+        assert_eq!(conversation.summary_reminder(0), None);
+        grow(&mut conversation, 1);
+        assert_eq!(conversation.summary_reminder(0), Some(7));
+    }
+
+    /// Roles and contents of what `build_request_with` would send, and whether it continues the thread
+    fn sent(conversation: &GrokConversation, with_nudges: bool) -> (Vec<(String, String)>, bool) {
+        let request = conversation.build_request_with(with_nudges).unwrap();
+        assert!(request.input.iter().all(|msg| msg.source.is_none() && msg.meta.is_none()), "bookkeeping stays local");
+        let input = request.input.iter().map(|msg| (msg.role.clone(), msg.content.clone())).collect();
+        (input, request.previous_response_id.is_some())
+    }
+
+    fn pairs(expected: Roles) -> Vec<(String, String)> {
+        expected.iter().map(|(role, content)| (role.to_string(), content.to_string())).collect()
+    }
+
+    /// A conversation with one exchange behind it and "use bullets" nudged since
+    fn nudged() -> GrokConversation {
+        let mut conversation = conversation();
+        conversation.add_user_message("hi");
+        conversation.add_assistant_message("Hello.".to_string());
+        conversation.add_nudge("use bullets");
+        conversation.add_user_message("what's next?");
+        conversation
+    }
+
+    #[test]
+    fn a_full_history_request_sends_active_nudges_before_the_newest_message() {
+        let conversation = nudged();
+        assert!(is_nudge(&conversation.local_history[3]), "recorded as a system message in history");
+
+        let (input, threaded) = sent(&conversation, true);
+        assert!(!threaded);
+        assert_eq!(input, pairs(&[
+            ("system", "Be brief."),
+            ("user", "hi"),
+            ("assistant", "Hello."),
+            ("system", "use bullets"),
+            ("user", "what's next?"),
+        ]));
+
+        let (input, _) = sent(&conversation, false);
+        assert!(!input.iter().any(|(_, content)| content == "use bullets"), "drafts go without: {:?}", input);
+    }
+
+    #[test]
+    fn a_threaded_request_sends_each_nudge_once() {
+        let mut conversation = nudged();
+        conversation.set_last_response_id("resp_1".to_string());
+
+        let (input, threaded) = sent(&conversation, true);
+        assert!(threaded);
+        assert_eq!(input, pairs(&[("system", "use bullets"), ("user", "what's next?")]), "not yet in the thread");
+        conversation.nudges_sent(true);
+
+        conversation.add_assistant_message("- Lunch.".to_string());
+        conversation.add_user_message("and then?");
+        conversation.set_last_response_id("resp_2".to_string());
+        assert_eq!(sent(&conversation, true), (pairs(&[("user", "and then?")]), true), "the thread already holds it");
+
+        conversation.add_nudge("be formal");
+        assert_eq!(
+            sent(&conversation, true),
+            (pairs(&[("system", "be formal"), ("user", "and then?")]), true),
+            "only the new one, and never the recorded nudge as the last message"
+        );
+    }
+
+    #[test]
+    fn a_request_without_nudges_leaves_a_thread_that_holds_one() {
+        let mut conversation = nudged();
+        conversation.set_last_response_id("resp_1".to_string());
+        conversation.nudges_sent(true);
+
+        let (input, threaded) = sent(&conversation, false);
+        assert!(!threaded, "the thread would keep obeying the nudge");
+        assert_eq!(input, pairs(&[("system", "Be brief."), ("user", "hi"), ("assistant", "Hello."), ("user", "what's next?")]));
+
+        // A thread started without the nudge can be continued without it
+        conversation.set_last_response_id("resp_draft".to_string());
+        conversation.nudges_sent(false);
+        assert!(sent(&conversation, false).1);
+        assert_eq!(sent(&conversation, true).0[0], ("system".to_string(), "use bullets".to_string()), "and is nudged again when chat resumes");
+    }
+
+    #[test]
+    fn clearing_nudges_keeps_their_record_and_drops_a_thread_that_holds_them() {
+        let cases: [(&str, bool, bool); 2] = [
+            ("sent in the thread", true, false),
+            ("never sent", false, true),
+        ];
+        for (case, in_thread, still_threaded) in cases {
+            let mut conversation = nudged();
+            conversation.set_last_response_id("resp_1".to_string());
+            conversation.nudges_sent(in_thread);
+
+            assert_eq!(conversation.clear_nudges(), 1, "{}", case);
+            assert!(conversation.active_nudges().is_empty(), "{}", case);
+            assert_eq!(conversation.next_request_threaded(), still_threaded, "{}", case);
+            assert_eq!(conversation.local_history.iter().filter(|msg| is_nudge(msg)).count(), 1, "{}: history keeps the record", case);
+            let (input, _) = sent(&conversation, true);
+            assert!(!input.iter().any(|(_, content)| content == "use bullets"), "{}: {:?}", case, input);
+        }
+        assert_eq!(conversation().clear_nudges(), 0);
+    }
+
+    /// One exchange stored `days` ago, as a reopened conversation would load it
+    fn reopened(days: i64) -> GrokConversation {
+        let mut conversation = conversation();
+        conversation.local_history.extend([message("user", "hi"), message("assistant", "Hello.")]);
+        conversation.last_exchange_at = Some(Utc::now() - chrono::Duration::days(days));
+        conversation
+    }
+
+    #[test]
+    fn only_a_conversation_with_something_to_pick_up_has_a_gap() {
+        let threshold = chrono::Duration::hours(GLOBAL_CONFIG.history.resync_after_hours as i64);
+        assert!(threshold > chrono::Duration::zero(), "the check is on by default");
+        let now = Utc::now();
+        let recent = reopened(0);
+        let at = recent.last_exchange_at.unwrap();
+
+        assert_eq!(recent.context_gap(at + threshold), None, "exactly the threshold");
+        assert!(recent.context_gap(at + threshold + chrono::Duration::seconds(1)).is_some());
+        assert!(reopened(16).context_gap(Utc::now()).is_some_and(|gap| gap.num_days() == 16));
+
+        let mut unanswered = conversation();
+        unanswered.add_user_message("anyone?");
+        unanswered.last_exchange_at = Some(now - chrono::Duration::days(16));
+        assert_eq!(unanswered.context_gap(now), None, "no reply yet, nothing to be stale");
+
+        let mut summarized_only = unanswered;
+        summarized_only.local_history.insert(1, message("system", &format!("{}- Planned a trip.]", SUMMARY_PREFIX)));
+        assert!(summarized_only.context_gap(now).is_some(), "a summary is something to pick up");
+
+        let mut never_stamped = reopened(16);
+        never_stamped.last_exchange_at = None;
+        assert_eq!(never_stamped.context_gap(now), None);
+    }
+
+    #[test]
+    fn the_context_note_goes_out_once_in_either_mode_and_never_into_history() {
+        const NOTE: &str = "Note: the last exchange was 16 days ago.";
+        for threaded in [false, true] {
+            let mut conversation = reopened(16);
+            conversation.set_context_note(Some(NOTE.to_string()));
+            conversation.add_nudge("use bullets");
+            conversation.add_user_message("where were we?");
+            if threaded {
+                conversation.set_last_response_id("resp_1".to_string());
+            }
+
+            let (input, sent_threaded) = sent(&conversation, true);
+            assert_eq!(sent_threaded, threaded);
+            let expected: Roles = if threaded {
+                &[("system", NOTE), ("system", "use bullets"), ("user", "where were we?")]
+            } else {
+                &[("system", "Be brief."), ("user", "hi"), ("assistant", "Hello."), ("system", NOTE), ("system", "use bullets"), ("user", "where were we?")]
+            };
+            assert_eq!(input, pairs(expected), "threaded: {}", threaded);
+            assert!(!conversation.local_history.iter().any(|msg| msg.content == NOTE), "threaded: {}", threaded);
+
+            // A draft leaves it for the next chat request
+            conversation.nudges_sent(false);
+            assert_eq!(conversation.context_note(), Some(NOTE), "threaded: {}", threaded);
+            conversation.nudges_sent(true);
+            assert_eq!(conversation.context_note(), None, "threaded: {}", threaded);
+            assert!(!sent(&conversation, true).0.iter().any(|(_, content)| content == NOTE), "threaded: {}", threaded);
+        }
+    }
+
+    #[test]
+    fn a_fresh_start_keeps_only_the_prompt_and_long_term_memory() {
+        let mut conversation = reopened(16);
+        conversation.local_history.splice(1..1, [
+            message("system", &format!("{}- Ana: birthday 3 May.]", LONG_TERM_PREFIX)),
+            message("system", &format!("{}- Said hello.]", SUMMARY_PREFIX)),
+        ]);
+        conversation.set_context_note(Some("Note: the last exchange was 16 days ago.".to_string()));
+
+        conversation.fresh_start();
+        assert_eq!(contents(&conversation), [("system", "Be brief."), ("system", "[Long-term summary: - Ana: birthday 3 May.]")]);
+        assert_eq!(conversation.context_note(), None);
+        assert_eq!(conversation.summary_text(), None);
+        assert_eq!(conversation.conversation_counts(), (0, 0));
+    }
+}
+
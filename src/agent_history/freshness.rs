@@ -0,0 +1,144 @@
+//! # Daegonica Module: agent_history::freshness
+//!
+//! **Purpose:** Notice when a conversation is picked up after a long break
+//!
+//! **Context:**
+//! - Checked once when an agent opens, against the time of the last stored reply
+//! - A stored summary reads as if it happened yesterday; the model needs telling otherwise
+//! - The note rides along with the first request only and is never added to history
+//!
+//! **Responsibilities:**
+//! - Decide whether the gap since the last exchange passes `history.resync_after_hours`
+//! - Word the context note, the recap request, and the pane banner
+//! - Define the two ways to act on the banner (`resync recap` / `resync fresh`)
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use chrono::{DateTime, Duration, Utc};
+use strum::{Display, EnumString};
+use crate::utilities::accessibility::glyph;
+
+/// # ResyncChoice
+///
+/// **Summary:**
+/// What to do about a conversation picked up after a long break.
+///
+/// **Variants:**
+/// - `Recap`: Ask the agent to recap where things were left
+/// - `Fresh`: Archive the history and start over, keeping the long-term summary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum ResyncChoice {
+    Recap,
+    Fresh,
+}
+
+/// # stale_gap
+///
+/// **Purpose:**
+/// The time since the last exchange, if it is longer than the threshold.
+///
+/// **Parameters:**
+/// - `last_exchange`: When the last reply was stored
+/// - `now`: Current time
+/// - `threshold_hours`: `history.resync_after_hours` (0 turns the check off)
+///
+/// **Returns:**
+/// `Option<Duration>` - The gap, only when strictly longer than the threshold
+pub fn stale_gap(last_exchange: DateTime<Utc>, now: DateTime<Utc>, threshold_hours: u64) -> Option<Duration> {
+    if threshold_hours == 0 {
+        return None;
+    }
+    let gap = now - last_exchange;
+    (gap > Duration::hours(threshold_hours as i64)).then_some(gap)
+}
+
+/// A gap in words: "16 days", "1 day", "30 hours"
+pub fn describe_gap(gap: Duration) -> String {
+    match gap.num_days() {
+        0 => match gap.num_hours() {
+            1 => "1 hour".to_string(),
+            hours => format!("{} hours", hours),
+        },
+        1 => "1 day".to_string(),
+        days => format!("{} days", days),
+    }
+}
+
+/// The system note sent with the first request after the gap
+pub fn context_note(gap: Duration) -> String {
+    format!(
+        "Note: the last exchange was {} ago. Don't treat the earlier conversation as recent; \
+         things may have changed since.",
+        describe_gap(gap)
+    )
+}
+
+/// The message `resync recap` sends
+pub fn recap_request(gap: Duration) -> String {
+    format!(
+        "It's been {} since we last talked. Briefly recap where we left off: what we were working on, \
+         what was decided, and anything still open.",
+        describe_gap(gap)
+    )
+}
+
+/// Pane line offering the two choices
+pub fn banner(gap: Duration) -> String {
+    format!(
+        "{} Last exchange was {} ago. `resync recap` (Ctrl+R) for a recap, `resync fresh` (Ctrl+F) to archive \
+         and start fresh keeping long-term memory, or just carry on.",
+        glyph("⏳", "Note:"),
+        describe_gap(gap)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_a_gap_strictly_past_the_threshold_is_stale() {
+        let now = Utc::now();
+        let cases = [
+            ("an hour short", Duration::hours(167), 168, None),
+            ("exactly the threshold", Duration::hours(168), 168, None),
+            ("a second past", Duration::hours(168) + Duration::seconds(1), 168, Some(Duration::hours(168) + Duration::seconds(1))),
+            ("two weeks", Duration::days(16), 168, Some(Duration::days(16))),
+            ("turned off", Duration::days(365), 0, None),
+            ("a clock that went backwards", Duration::hours(-5), 1, None),
+        ];
+        for (name, ago, threshold, expected) in cases {
+            assert_eq!(stale_gap(now - ago, now, threshold), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn gaps_are_described_in_whole_days_or_hours() {
+        let cases = [
+            (Duration::minutes(90), "1 hour"),
+            (Duration::hours(30), "1 day"),
+            (Duration::hours(23), "23 hours"),
+            (Duration::days(16) + Duration::hours(20), "16 days"),
+        ];
+        for (gap, described) in cases {
+            assert_eq!(describe_gap(gap), described, "{:?}", gap);
+        }
+        assert!(context_note(Duration::days(16)).starts_with("Note: the last exchange was 16 days ago."));
+        assert!(recap_request(Duration::days(2)).starts_with("It's been 2 days since we last talked."));
+        assert!(banner(Duration::days(16)).contains("Last exchange was 16 days ago. `resync recap` (Ctrl+R)"));
+    }
+
+    #[test]
+    fn choices_parse_in_any_case() {
+        assert_eq!("recap".parse::<ResyncChoice>(), Ok(ResyncChoice::Recap));
+        assert_eq!("FRESH".parse::<ResyncChoice>(), Ok(ResyncChoice::Fresh));
+        assert!("later".parse::<ResyncChoice>().is_err());
+    }
+}
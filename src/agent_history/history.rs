@@ -14,6 +14,8 @@
 //! - Archive complete conversation history
 //! - Manage persona-specific history directories
 //! - Handle ConversationHistory serialization/deserialization
+//! - Stream large archives message by message instead of loading them whole
+//! - Load what `history verify` checks and write back its repairs
 //!
 //! **Author:** Daegonica Software
 //! **Version:** 0.1.0
@@ -24,7 +26,143 @@
 //! ---------------------------------------------------------------
 
 use crate::prelude::*;
+use crate::agent_history::read_state::ReadMarker;
+use crate::agent_history::redaction::Redactor;
+use crate::agent_history::conversations::{is_nudge, is_summary_message, LONG_TERM_PREFIX, SUMMARY_PREFIX};
+use crate::agent_history::integrity::{self, ArchiveScan, VerifyInput, VerifyReport};
+use crate::agent_history::overview::personas_with_history;
+use crate::agent_history::schema::{parse_history, stored_version, HistorySchemaError, CURRENT_HISTORY_VERSION};
+use crate::utilities::time::{format_timestamp, parse_archive_stamp, Zone, ARCHIVE_STAMP_FORMAT};
+use crate::persona::{persona_dir, personas_root};
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{BufReader, BufWriter};
 use std::path::Path;
+use serde::de::{self, DeserializeSeed, SeqAccess, Visitor};
+
+/// Directory holding every persona's timestamped archives (`{root}/archives`)
+fn archive_dir() -> PathBuf {
+    personas_root().join("archives")
+}
+
+/// Recovery copies of messages taken out with `history remove` (`{root}/archives/removed`)
+fn removed_dir() -> PathBuf {
+    archive_dir().join("removed")
+}
+
+/// A persona's history directory (`{root}/{persona_name}/history`)
+pub(crate) fn history_dir(persona_name: &str) -> PathBuf {
+    persona_dir(persona_name).join("history")
+}
+
+/// A persona's saved history (`{root}/{persona_name}/history/{persona_name}_history.json`)
+pub(crate) fn history_path(persona_name: &str) -> PathBuf {
+    history_dir(persona_name).join(format!("{}_history.json", persona_name))
+}
+
+/// # ConversationOverrides
+///
+/// **Summary:**
+/// Per-persona settings changed at runtime that should outlive the session.
+///
+/// **Fields:**
+/// - `summarize_mode`: Set with `set summarize`; None follows the persona file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversationOverrides {
+    #[serde(default)]
+    pub summarize_mode: Option<SummarizeMode>,
+}
+
+/// # RemovedMessages
+///
+/// **Summary:**
+/// Recovery record written before `history remove` edits a history.
+///
+/// **Fields:**
+/// - `persona`: Persona whose history was edited
+/// - `removed_at`: RFC3339 time of the removal
+/// - `index`: Position of the first removed message in `local_history`
+/// - `messages`: The removed messages, in their stored form (`ephemeral_patterns` redacted)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemovedMessages {
+    pub persona: String,
+    pub removed_at: String,
+    pub index: usize,
+    pub messages: Vec<Message>,
+}
+
+/// # ArchiveSummary
+///
+/// **Summary:**
+/// Cheap description of one archive file, gathered without materializing its messages.
+///
+/// **Fields:**
+/// - `path`: Archive file location
+/// - `archived_at`: Local timestamp taken from the file name (`ARCHIVE_STAMP_FORMAT`)
+/// - `size_bytes`: File size on disk
+/// - `message_count`: Number of messages in the archive
+#[derive(Debug, Clone)]
+pub struct ArchiveSummary {
+    pub path: PathBuf,
+    pub archived_at: String,
+    pub size_bytes: u64,
+    pub message_count: usize,
+}
+
+impl ArchiveSummary {
+    /// When the archive was written, read from its file name
+    pub fn archived_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_archive_stamp(&self.archived_at)
+    }
+
+    /// One-line summary for the `archives` command, with the time shown in `zone`
+    pub fn describe(&self, zone: Zone) -> String {
+        let name = self.path.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let when = self.archived_time()
+            .map(|t| format_timestamp(t, zone))
+            .unwrap_or_else(|| self.archived_at.clone());
+        format!("{}  {}  {} messages, {:.1} KB",
+            when, name, self.message_count, self.size_bytes as f64 / 1024.0)
+    }
+}
+
+/// Feeds each element of a JSON array to a callback as soon as it is parsed
+struct MessageSeqVisitor<F> {
+    on_message: F,
+}
+
+impl<'de, F> Visitor<'de> for MessageSeqVisitor<F>
+where
+    F: FnMut(Message) -> io::Result<()>,
+{
+    type Value = usize;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON array of messages")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<usize, A::Error> {
+        let mut count = 0;
+        while let Some(message) = seq.next_element::<Message>()? {
+            (self.on_message)(message).map_err(de::Error::custom)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+impl<'de, F> DeserializeSeed<'de> for MessageSeqVisitor<F>
+where
+    F: FnMut(Message) -> io::Result<()>,
+{
+    type Value = usize;
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<usize, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
 
 /// # HistoryManager
 ///
@@ -54,11 +192,16 @@ impl HistoryManager {
     /// `Result<ConversationHistory, Box<dyn std::error::Error>>` - Loaded history or error
     ///
     /// **File Location:**
-    /// `personas/{persona_name}/history/{persona_name}_history.json`
+    /// `{root}/{persona_name}/history/{persona_name}_history.json`
+    ///
+    /// **Details:**
+    /// Files from older builds are migrated to the current format before
+    /// deserializing (see `agent_history::schema`).
     ///
     /// **Errors / Failures:**
     /// - File not found (no previous history)
     /// - Invalid JSON format
+    /// - File written by a newer build
     /// - I/O errors reading file
     ///
     /// **Examples:**
@@ -69,12 +212,12 @@ impl HistoryManager {
     /// }
     /// ```
     pub fn load_persona_history(persona_name: &str) -> Result<ConversationHistory, Box<dyn std::error::Error>> {
-        let path = format!("personas/{}/history/{}_history.json", persona_name, persona_name);
+        let path = history_path(persona_name);
 
-        log_info!("Loading history from: {}", path);
+        log_debug!("Loading history from: {}", path.display());
 
         let content = std::fs::read_to_string(&path)?;
-        let history: ConversationHistory = serde_json::from_str(&content)?;
+        let history = parse_history(&content)?;
 
         log_info!("Loaded history: {} total messages, {} recent messages",
             history.total_message_count, history.recent_messages.len());
@@ -95,7 +238,7 @@ impl HistoryManager {
     /// `Vec<Message>` - Complete message history ready for conversation
     ///
     /// **Details:**
-    /// Builds: [system_prompt, optional_summary, recent_messages]
+    /// Builds: [system_prompt, optional_long_term_summary, optional_summary, recent_messages]
     ///
     /// **Examples:**
     /// ```rust
@@ -107,12 +250,26 @@ impl HistoryManager {
         let mut messages = vec![Message {
             role: "system".to_string(),
             content: persona.system_prompt.clone(),
+            meta: None,
+            source: None,
         }];
 
+        // Oldest context first: the long-term tier, then the recent one
+        if let Some(long_term) = loaded_history.long_term_summary {
+            messages.push(Message {
+                role: "system".to_string(),
+                content: format!("{}{}]", LONG_TERM_PREFIX, long_term),
+                meta: None,
+                source: None,
+            });
+        }
+
         if let Some(summary) = loaded_history.summary {
             messages.push(Message {
                 role: "system".to_string(),
-                content: format!("[Previous conversation summary: {}]", summary),
+                content: format!("{}{}]", SUMMARY_PREFIX, summary),
+                meta: None,
+                source: None,
             });
         }
 
@@ -134,13 +291,15 @@ impl HistoryManager {
     /// `Result<(), Box<dyn std::error::Error>>` - Success or I/O error
     ///
     /// **File Location:**
-    /// `personas/{persona_name}/history/{persona_name}_history.json`
+    /// `{root}/{persona_name}/history/{persona_name}_history.json`
     ///
     /// **Details:**
     /// - Creates directory if it doesn't exist
     /// - Saves only recent messages (based on persona.history_message_limit)
-    /// - Preserves existing summary if present
+    /// - Preserves the recent and long-term summaries if present
+    /// - Stores messages and summaries with the persona's `ephemeral_patterns` redacted
     /// - Updates timestamp
+    /// - Always writes the current format version, but refuses to overwrite a newer one
     ///
     /// **Errors / Failures:**
     /// - Directory creation failures
@@ -154,8 +313,7 @@ impl HistoryManager {
     pub fn save_persona_history(conversation: &GrokConversation) -> Result<(), Box<dyn std::error::Error>> {
         let persona_name = &conversation.persona.name;
 
-        let dir_path = format!("personas/{}/history", persona_name);
-        std::fs::create_dir_all(&dir_path)?;
+        std::fs::create_dir_all(history_dir(persona_name))?;
 
         let limit = conversation.persona.history_message_limit;
 
@@ -165,28 +323,39 @@ impl HistoryManager {
             1
         };
 
-        let recent_messages: Vec<Message> = conversation.local_history[recent_start..].to_vec();
-
-        let existing_summary = conversation.local_history.iter()
-            .find(|msg| msg.role == "system" && msg.content.contains("[Previous conversation summary:"))
-            .and_then(|msg| {
-                msg.content
-                    .strip_prefix("[Previous conversation summary: ")
-                    .and_then(|s: &str| s.strip_suffix("]"))
-                    .map(|s: &str| s.to_string())
-            });
+        // Summaries are stored in their own fields, never as recent messages
+        let redactor = Redactor::for_persona(&conversation.persona);
+        let recent_messages: Vec<Message> = conversation.local_history[recent_start..].iter()
+            .filter(|msg| !is_summary_message(msg))
+            .map(|msg| redactor.redact_message(msg))
+            .collect();
+        let stored = |text: &str| redactor.redact_text(text).into_owned();
 
         let history = ConversationHistory {
             persona_name: persona_name.clone(),
-            summary: existing_summary,
+            summary: conversation.summary_text().map(stored),
+            long_term_summary: conversation.long_term_summary_text().map(stored),
             recent_messages,
-            total_message_count: conversation.local_history.len() -1,
+            // Never below what's in memory, so a conversation older than the counter still adds up
+            total_message_count: conversation.total_message_count.max(conversation.local_history.len() - 1),
             last_updated: chrono::Utc::now().to_rfc3339(),
-            summarization_count: 0,
+            summarization_count: conversation.summarization_count,
+            experiment: conversation.experiment.clone(),
+            started_at: Some(conversation.started_at.clone()),
+            last_exchange_at: conversation.last_exchange_at.map(|at| at.to_rfc3339()),
+            last_response_id: conversation.get_last_response_id().cloned(),
+            last_response_at: conversation.response_id_set_at().map(|at| at.to_rfc3339()),
+            format_version: CURRENT_HISTORY_VERSION,
         };
 
+        let path = history_path(persona_name);
+
+        // Never clobber a file this build couldn't read back
+        if let Some(found) = stored_version(&path).filter(|v| *v > CURRENT_HISTORY_VERSION) {
+            return Err(HistorySchemaError::NewerVersion { found, supported: CURRENT_HISTORY_VERSION }.into());
+        }
+
         let json = serde_json::to_string_pretty(&history)?;
-        let path = format!("personas/{}/history/{}_history.json", persona_name, persona_name);
         std::fs::write(&path, json)?;
 
         log_info!("Saved history for {} ({} messages)", persona_name, history.recent_messages.len());
@@ -228,10 +397,11 @@ impl HistoryManager {
     /// `Result<(), Box<dyn std::error::Error>>` - Success or I/O error
     ///
     /// **File Location:**
-    /// `personas/archives/{persona_name}_{timestamp}.json`
+    /// `{root}/archives/{persona_name}_{timestamp}.json`
     ///
     /// **Details:**
-    /// Creates timestamped archive before history is summarized/truncated
+    /// Creates timestamped archive before history is summarized/truncated.
+    /// Messages are archived in their stored form (`ephemeral_patterns` redacted).
     ///
     /// **Examples:**
     /// ```rust
@@ -239,18 +409,315 @@ impl HistoryManager {
     /// HistoryManager::archive_full_history(&conversation)?;
     /// ```
     pub fn archive_full_history(conversation: &GrokConversation) -> Result<(), Box<dyn std::error::Error>> {
-        std::fs::create_dir_all("personas/archives")?;
+        let dir = archive_dir();
+        std::fs::create_dir_all(&dir)?;
 
-        let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
-        let path = format!("personas/archives/{}_{}.json", conversation.persona.name, timestamp);
+        let timestamp = chrono::Local::now().format(ARCHIVE_STAMP_FORMAT);
+        let path = dir.join(format!("{}_{}.json", conversation.persona.name, timestamp));
 
-        let json = serde_json::to_string_pretty(&conversation.local_history)?;
-        std::fs::write(&path, json)?;
+        let stored = Redactor::for_persona(&conversation.persona).redact_messages(&conversation.local_history);
+        let mut writer = BufWriter::new(File::create(&path)?);
+        serde_json::to_writer_pretty(&mut writer, &stored)?;
+        writer.flush()?;
 
-        log_info!("Archived full history for {} to {}", conversation.persona.name, path);
+        log_info!("Archived full history for {} to {}", conversation.persona.name, path.display());
         Ok(())
     }
 
+    /// # archive_removed_messages
+    ///
+    /// **Purpose:**
+    /// Saves messages about to be removed so they can be recovered.
+    ///
+    /// **Parameters:**
+    /// - `record`: What is being removed, and from where
+    ///
+    /// **Returns:**
+    /// `Result<PathBuf, Box<dyn std::error::Error>>` - The recovery file,
+    /// `{root}/archives/removed/{persona_name}_{timestamp}_{index}.json`
+    ///
+    /// **Details:**
+    /// Kept in a subdirectory so `archives` doesn't list these as full archives.
+    pub fn archive_removed_messages(record: &RemovedMessages) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let dir = removed_dir();
+        std::fs::create_dir_all(&dir)?;
+
+        let timestamp = chrono::Local::now().format(ARCHIVE_STAMP_FORMAT);
+        let path = dir.join(format!("{}_{}_{}.json", record.persona, timestamp, record.index));
+
+        let mut writer = BufWriter::new(File::create(&path)?);
+        serde_json::to_writer_pretty(&mut writer, record)?;
+        writer.flush()?;
+
+        log_info!("Saved {} removed message(s) for {} to {}", record.messages.len(), record.persona, path.display());
+        Ok(path)
+    }
+
+    /// # for_each_archived_message
+    ///
+    /// **Purpose:**
+    /// Streams an archive's messages to a callback one at a time.
+    ///
+    /// **Parameters:**
+    /// - `path`: Archive file (a JSON array of messages)
+    /// - `on_message`: Called with each message in order; an error stops the scan
+    ///
+    /// **Returns:**
+    /// `Result<usize, Box<dyn std::error::Error>>` - Number of messages visited
+    ///
+    /// **Details:**
+    /// Only one message is held in memory at a time, so this is safe for
+    /// archives of any size. Use this instead of `load_archive` for scanning.
+    ///
+    /// **Examples:**
+    /// ```no_run
+    /// # use std::path::Path;
+    /// # use grokprime_brain::agent_history::history::HistoryManager;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let path = Path::new("shadow_archive.json");
+    /// let mut chars = 0;
+    /// HistoryManager::for_each_archived_message(path, |msg| {
+    ///     chars += msg.content.len();
+    ///     Ok(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn for_each_archived_message<F>(path: &Path, on_message: F) -> Result<usize, Box<dyn std::error::Error>>
+    where
+        F: FnMut(Message) -> io::Result<()>,
+    {
+        let reader = BufReader::new(File::open(path)?);
+        let mut deserializer = serde_json::Deserializer::from_reader(reader);
+        let count = MessageSeqVisitor { on_message }.deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(count)
+    }
+
+    /// # load_archive
+    ///
+    /// **Purpose:**
+    /// Loads a whole archive into memory.
+    ///
+    /// **Returns:**
+    /// `Result<Vec<Message>, Box<dyn std::error::Error>>` - Every archived message
+    ///
+    /// **Errors / Failures:**
+    /// - The archive is larger than `history.archive_streaming_threshold_bytes`;
+    ///   callers must use `for_each_archived_message` or `export_archive` instead
+    /// - I/O or JSON errors
+    pub fn load_archive(path: &Path) -> Result<Vec<Message>, Box<dyn std::error::Error>> {
+        let size = fs::metadata(path)?.len();
+        let threshold = GLOBAL_CONFIG.history.archive_streaming_threshold_bytes;
+        if size > threshold {
+            return Err(format!(
+                "{} is {:.1} MB, over the {:.1} MB full-load limit; export it with `archives export` instead",
+                path.display(), size as f64 / 1_048_576.0, threshold as f64 / 1_048_576.0
+            ).into());
+        }
+
+        let mut messages = Vec::new();
+        Self::for_each_archived_message(path, |message| {
+            messages.push(message);
+            Ok(())
+        })?;
+        Ok(messages)
+    }
+
+    /// # persona_archives
+    ///
+    /// **Purpose:**
+    /// A persona's archive files, oldest first, without opening them.
+    ///
+    /// **Returns:**
+    /// `io::Result<Vec<(PathBuf, String)>>` - Each archive's path and the timestamp
+    /// part of its name (empty if the persona has never been archived)
+    fn persona_archives(persona_name: &str) -> io::Result<Vec<(PathBuf, String)>> {
+        let entries = match fs::read_dir(archive_dir()) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let prefix = format!("{}_", persona_name);
+        let mut archives: Vec<(PathBuf, String)> = entries.filter_map(|e| e.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+            .filter_map(|path| {
+                let archived_at = path.file_stem()?.to_str()?.strip_prefix(&prefix)?.to_string();
+                Some((path, archived_at))
+            })
+            .collect();
+        archives.sort_by(|a, b| a.1.cmp(&b.1));
+        Ok(archives)
+    }
+
+    /// # list_archives
+    ///
+    /// **Purpose:**
+    /// Summarizes every archive for a persona, oldest first.
+    ///
+    /// **Parameters:**
+    /// - `persona_name`: Persona whose archives to list
+    ///
+    /// **Returns:**
+    /// `Result<Vec<ArchiveSummary>, Box<dyn std::error::Error>>` - One summary per archive
+    /// (empty if the persona has never been archived)
+    ///
+    /// **Details:**
+    /// Message counts come from a streaming scan, so large archives cost time but not memory.
+    pub fn list_archives(persona_name: &str) -> Result<Vec<ArchiveSummary>, Box<dyn std::error::Error>> {
+        let mut summaries = Vec::new();
+        for (path, archived_at) in Self::persona_archives(persona_name)? {
+            let size_bytes = fs::metadata(&path)?.len();
+            let message_count = Self::for_each_archived_message(&path, |_| Ok(()))?;
+            summaries.push(ArchiveSummary { path, archived_at, size_bytes, message_count });
+        }
+        Ok(summaries)
+    }
+
+    /// # saved_histories
+    ///
+    /// **Purpose:**
+    /// Every persona with a saved history file, sorted by name.
+    pub fn saved_histories() -> Vec<String> {
+        let mut personas: Vec<String> = personas_with_history(personas_root())
+            .into_iter()
+            .filter(|persona| Self::history_exists(persona))
+            .collect();
+        personas.sort();
+        personas
+    }
+
+    /// # load_verify_input
+    ///
+    /// **Purpose:**
+    /// Reads what `history verify` checks: the history file and a scan of every archive.
+    ///
+    /// **Returns:**
+    /// `Result<VerifyInput, Box<dyn std::error::Error>>` - The loaded structures
+    ///
+    /// **Details:**
+    /// Archives are streamed, so only the newest one's last messages (as many
+    /// as the history holds) are kept. An archive that doesn't parse is
+    /// recorded in its `ArchiveScan`, not returned as an error.
+    ///
+    /// **Errors / Failures:**
+    /// - The history file is missing or doesn't load
+    /// - The archive directory can't be read
+    pub fn load_verify_input(persona_name: &str) -> Result<VerifyInput, Box<dyn std::error::Error>> {
+        let history = Self::load_persona_history(persona_name)?;
+        let paths = Self::persona_archives(persona_name)?;
+        let tail_len = history.recent_messages.len();
+
+        let newest = paths.len().saturating_sub(1);
+        let archives = paths.into_iter().enumerate()
+            .map(|(index, (path, archived_at))| {
+                let mut tail = VecDeque::new();
+                let messages = Self::for_each_archived_message(&path, |message| {
+                    if index == newest && tail_len > 0 {
+                        if tail.len() == tail_len {
+                            tail.pop_front();
+                        }
+                        tail.push_back(message);
+                    }
+                    Ok(())
+                }).map_err(|e| e.to_string());
+                ArchiveScan {
+                    name: path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default(),
+                    archived_at: parse_archive_stamp(&archived_at),
+                    messages,
+                    tail: tail.into(),
+                }
+            })
+            .collect();
+
+        Ok(VerifyInput { history, archives })
+    }
+
+    /// # verify_history
+    ///
+    /// **Purpose:**
+    /// Runs `integrity::verify` over a persona's saved history and archives.
+    pub fn verify_history(persona_name: &str) -> Result<VerifyReport, Box<dyn std::error::Error>> {
+        let input = Self::load_verify_input(persona_name)?;
+        Ok(integrity::verify(persona_name, &input, chrono::Utc::now()))
+    }
+
+    /// # repair_history
+    ///
+    /// **Purpose:**
+    /// Applies `integrity::repair` to a persona's history file and saves it.
+    ///
+    /// **Returns:**
+    /// `Result<Vec<String>, Box<dyn std::error::Error>>` - The changes made; the file
+    /// is left untouched when there were none
+    ///
+    /// **Details:**
+    /// Edits the file as stored, so an open agent for the persona would write
+    /// its own copy back on its next save; callers check for one first.
+    pub fn repair_history(persona_name: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let mut history = Self::load_persona_history(persona_name)?;
+        let changes = integrity::repair(&mut history);
+        if changes.is_empty() {
+            return Ok(changes);
+        }
+
+        let json = serde_json::to_string_pretty(&history)?;
+        std::fs::write(history_path(persona_name), json)?;
+        log_info!("Repaired history for {}: {}", persona_name, changes.join("; "));
+        Ok(changes)
+    }
+
+    /// # resolve_archive
+    ///
+    /// **Purpose:**
+    /// Turns a user-supplied archive name into a path.
+    ///
+    /// **Details:**
+    /// Accepts a full path, a file name inside `{root}/archives`, or a file name without `.json`.
+    pub fn resolve_archive(name: &str) -> PathBuf {
+        let direct = PathBuf::from(name);
+        if direct.exists() {
+            return direct;
+        }
+
+        let in_dir = archive_dir().join(name);
+        if in_dir.extension().is_some() {
+            in_dir
+        } else {
+            in_dir.with_extension("json")
+        }
+    }
+
+    /// # export_archive
+    ///
+    /// **Purpose:**
+    /// Writes an archive out as a readable Markdown transcript.
+    ///
+    /// **Parameters:**
+    /// - `path`: Archive to export
+    /// - `dest`: Output file
+    ///
+    /// **Returns:**
+    /// `Result<usize, Box<dyn std::error::Error>>` - Number of messages written
+    ///
+    /// **Details:**
+    /// Each message is written as soon as it is read, so memory use does not
+    /// grow with archive size.
+    pub fn export_archive(path: &Path, dest: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+        let mut writer = BufWriter::new(File::create(dest)?);
+        let count = Self::for_each_archived_message(path, |message| {
+            // Nudges are instructions, not the user talking; label them as such
+            let heading = if is_nudge(&message) { "system (nudge)" } else { message.role.as_str() };
+            writeln!(writer, "### {}\n\n{}\n", heading, message.content)
+        })?;
+        writer.flush()?;
+
+        log_info!("Exported {} messages from {} to {}", count, path.display(), dest.display());
+        Ok(count)
+    }
+
     /// # history_exists
     ///
     /// **Purpose:**
@@ -269,8 +736,7 @@ impl HistoryManager {
     /// }
     /// ```
     pub fn history_exists(persona_name: &str) -> bool {
-        let path = format!("personas/{}/history/{}_history.json", persona_name, persona_name);
-        Path::new(&path).exists()
+        history_path(persona_name).exists()
     }
 
     /// # delete_history
@@ -289,10 +755,188 @@ impl HistoryManager {
     /// HistoryManager::delete_history("shadow")?;
     /// ```
     pub fn delete_history(persona_name: &str) -> Result<(), std::io::Error> {
-        let path = format!("personas/{}/history/{}_history.json", persona_name, persona_name);
-        std::fs::remove_file(&path)?;
+        std::fs::remove_file(history_path(persona_name))?;
         log_info!("Deleted history for {}", persona_name);
         Ok(())
     }
 
-}
\ No newline at end of file
+    /// # load_read_state
+    ///
+    /// **Purpose:**
+    /// Loads the persona's last-read marker.
+    ///
+    /// **Returns:**
+    /// `Result<Option<ReadMarker>, Box<dyn std::error::Error>>` - None if no marker was saved yet
+    ///
+    /// **File Location:**
+    /// `{root}/{persona_name}/history/read_state.json`
+    pub fn load_read_state(persona_name: &str) -> Result<Option<ReadMarker>, Box<dyn std::error::Error>> {
+        let path = history_dir(persona_name).join("read_state.json");
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// # save_read_state
+    ///
+    /// **Purpose:**
+    /// Saves the persona's last-read marker.
+    ///
+    /// **Details:**
+    /// Written to a temporary file and renamed into place, so a sync client or a
+    /// second device never sees a half-written marker.
+    pub fn save_read_state(persona_name: &str, marker: &ReadMarker) -> Result<(), Box<dyn std::error::Error>> {
+        let dir_path = history_dir(persona_name);
+        std::fs::create_dir_all(&dir_path)?;
+
+        let path = dir_path.join("read_state.json");
+        let temp_path = dir_path.join("read_state.json.tmp");
+        std::fs::write(&temp_path, serde_json::to_string_pretty(marker)?)?;
+        std::fs::rename(&temp_path, &path)?;
+
+        log_info!("Saved read marker for {} at message {}", persona_name, marker.position);
+        Ok(())
+    }
+
+    /// # load_overrides
+    ///
+    /// **Purpose:**
+    /// Loads the persona's runtime overrides.
+    ///
+    /// **Returns:**
+    /// `Result<ConversationOverrides, Box<dyn std::error::Error>>` - Defaults if none were saved yet
+    ///
+    /// **File Location:**
+    /// `{root}/{persona_name}/history/overrides.json`
+    pub fn load_overrides(persona_name: &str) -> Result<ConversationOverrides, Box<dyn std::error::Error>> {
+        let path = history_dir(persona_name).join("overrides.json");
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(ConversationOverrides::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// # save_overrides
+    ///
+    /// **Purpose:**
+    /// Saves the persona's runtime overrides (temporary file, then rename).
+    pub fn save_overrides(persona_name: &str, overrides: &ConversationOverrides) -> Result<(), Box<dyn std::error::Error>> {
+        let dir_path = history_dir(persona_name);
+        std::fs::create_dir_all(&dir_path)?;
+
+        let path = dir_path.join("overrides.json");
+        let temp_path = dir_path.join("overrides.json.tmp");
+        std::fs::write(&temp_path, serde_json::to_string_pretty(overrides)?)?;
+        std::fs::rename(&temp_path, &path)?;
+
+        log_info!("Saved overrides for {}: {:?}", persona_name, overrides);
+        Ok(())
+    }
+
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent_history::conversations::NUDGE_SOURCE;
+    use crate::test_support::ScratchDir;
+
+    /// Message `i` of a generated archive: a long pasted log, every 50th a nudge
+    fn generated(i: usize) -> Message {
+        let (role, source) = match i % 50 {
+            49 => ("user", Some(NUDGE_SOURCE.to_string())),
+            n if n % 2 == 0 => ("user", None),
+            _ => ("assistant", None),
+        };
+        let line = format!("[{:05}] worker \"café\" said: ok\t✓\n", i);
+        Message { role: role.into(), content: format!("message {}\n{}", i, line.repeat(100)), meta: None, source }
+    }
+
+    /// Writes messages `0..count` as an archive, one at a time like the archiver would
+    fn write_archive(path: &Path, count: usize) {
+        let mut out = BufWriter::new(File::create(path).expect("create archive"));
+        out.write_all(b"[").unwrap();
+        for i in 0..count {
+            if i > 0 {
+                out.write_all(b",").unwrap();
+            }
+            serde_json::to_writer(&mut out, &generated(i)).unwrap();
+        }
+        out.write_all(b"]").unwrap();
+        out.flush().unwrap();
+    }
+
+    fn markdown(message: &Message) -> String {
+        let heading = if is_nudge(message) { "system (nudge)" } else { message.role.as_str() };
+        format!("### {}\n\n{}\n\n", heading, message.content)
+    }
+
+    #[test]
+    fn a_small_archive_exports_each_message_as_a_section() {
+        let dir = ScratchDir::new("export-small");
+        let archive = dir.write("shadow_2026-01-15_12-00-00.json", &serde_json::to_string(&vec![
+            Message { role: "user".into(), content: "hi".into(), meta: None, source: None },
+            Message { role: "assistant".into(), content: "hello\n\nthere".into(), meta: None, source: None },
+            Message { role: "user".into(), content: "be brief".into(), meta: None, source: Some(NUDGE_SOURCE.into()) },
+        ]).unwrap());
+        let dest = dir.join("out.md");
+
+        assert_eq!(HistoryManager::export_archive(&archive, &dest).unwrap(), 3);
+        assert_eq!(
+            fs::read_to_string(&dest).unwrap(),
+            "### user\n\nhi\n\n### assistant\n\nhello\n\nthere\n\n### system (nudge)\n\nbe brief\n\n"
+        );
+        assert_eq!(HistoryManager::load_archive(&archive).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn an_archive_over_the_threshold_streams_through_export() {
+        let dir = ScratchDir::new("export-large");
+        let threshold = GLOBAL_CONFIG.history.archive_streaming_threshold_bytes;
+        let per_message = serde_json::to_string(&generated(0)).unwrap().len() as u64;
+        let count = (threshold / per_message + 200) as usize;
+        let archive = dir.join("shadow_2026-01-15_12-00-00.json");
+        write_archive(&archive, count);
+        let size = fs::metadata(&archive).unwrap().len();
+        assert!(size > threshold, "generated {} bytes, not over the {} byte threshold", size, threshold);
+
+        let refused = HistoryManager::load_archive(&archive).expect_err("too large to load whole").to_string();
+        assert!(refused.contains("full-load limit") && refused.contains("archives export"), "{}", refused);
+
+        let dest = dir.join("out.md");
+        assert_eq!(HistoryManager::export_archive(&archive, &dest).unwrap(), count);
+        let exported = fs::read_to_string(&dest).unwrap();
+        let headings: Vec<&str> = exported.lines().filter(|line| line.starts_with("### ")).collect();
+        assert_eq!(headings.len(), count);
+        assert_eq!(headings.iter().filter(|heading| **heading == "### system (nudge)").count(), count / 50);
+        assert!(exported.starts_with(&markdown(&generated(0))));
+        assert!(exported.ends_with(&markdown(&generated(count - 1))));
+        let middle = markdown(&generated(count / 2));
+        assert!(exported.contains(&middle), "message {} is exported intact", count / 2);
+
+        let mut seen = 0;
+        let visited = HistoryManager::for_each_archived_message(&archive, |message| {
+            let expected = generated(seen);
+            assert_eq!((message.role, message.content, message.source), (expected.role, expected.content, expected.source), "message {}", seen);
+            seen += 1;
+            Ok(())
+        }).unwrap();
+        assert_eq!((visited, seen), (count, count));
+    }
+
+    #[test]
+    fn a_damaged_archive_fails_instead_of_exporting_part_of_it() {
+        let dir = ScratchDir::new("export-damaged");
+        let archive = dir.join("shadow_2026-01-15_12-00-00.json");
+        write_archive(&archive, 20);
+        let full = fs::read(&archive).unwrap();
+        fs::write(&archive, &full[..full.len() / 2]).unwrap();
+        assert!(HistoryManager::export_archive(&archive, &dir.join("out.md")).is_err());
+
+        let stopped = HistoryManager::for_each_archived_message(&dir.write("a.json", "[]"), |_| Ok(())).unwrap();
+        assert_eq!(stopped, 0);
+        assert!(HistoryManager::for_each_archived_message(&dir.write("b.json", "{}"), |_| Ok(())).is_err());
+    }
+}
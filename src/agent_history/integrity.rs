@@ -0,0 +1,661 @@
+//! # Daegonica Module: agent_history::integrity
+//!
+//! **Purpose:** Audit a persona's saved history against its archives (`history verify`)
+//!
+//! **Context:**
+//! - `HistoryManager::load_verify_input` reads the history file and scans the archives; everything here is pure over what it read
+//! - Messages carry no ids or timestamps: duplicates are matched on role and content, and time order comes from the history's own timestamps and the archive file names
+//! - Used by `history verify` in the TUI and `verify-history` on the command line (non-zero exit on failures)
+//!
+//! **Responsibilities:**
+//! - Check the message total, the archives, the summary count, duplicates, and timestamp order
+//! - Report pass / warn / fail per check, naming the archive or message involved
+//! - Repair the safe cases: recount the total, put the history's timestamps back in order
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use chrono::{DateTime, Duration, Utc};
+use strum::Display;
+use crate::models::{ConversationHistory, Message};
+
+/// How far `last_updated` may run ahead of the clock before it's reported
+const CLOCK_SKEW_ALLOWANCE_MINUTES: i64 = 5;
+
+/// # CheckStatus
+///
+/// **Summary:**
+/// Outcome of one check, ordered from best to worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// # CheckKind
+///
+/// **Summary:**
+/// The checks `verify` runs, in report order.
+///
+/// **Variants:**
+/// - `Counts`: `total_message_count` against the messages the file holds
+/// - `Archives`: Every archive parses and was written before the history was last saved
+/// - `Summaries`: `summarization_count` against the stored summaries and the archives written since the conversation started
+/// - `Duplicates`: Repeated messages, including the kept messages of the newest archive appearing twice
+/// - `Timestamps`: `started_at` <= `last_exchange_at` <= `last_updated`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum CheckKind {
+    Counts,
+    Archives,
+    Summaries,
+    Duplicates,
+    Timestamps,
+}
+
+/// # CheckResult
+///
+/// **Summary:**
+/// One check's outcome and the specifics behind it.
+///
+/// **Fields:**
+/// - `kind`: Which check
+/// - `status`: Worst finding (pass when there were none)
+/// - `details`: One line per finding, or a one-line summary on a pass
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckResult {
+    pub kind: CheckKind,
+    pub status: CheckStatus,
+    pub details: Vec<String>,
+}
+
+impl CheckResult {
+    fn pass(kind: CheckKind, detail: String) -> Self {
+        Self { kind, status: CheckStatus::Pass, details: vec![detail] }
+    }
+
+    /// A result from `(status, detail)` findings; `summary` is used when there are none
+    fn from_findings(kind: CheckKind, findings: Vec<(CheckStatus, String)>, summary: String) -> Self {
+        let Some(status) = findings.iter().map(|(status, _)| *status).max() else {
+            return Self::pass(kind, summary);
+        };
+        Self { kind, status, details: findings.into_iter().map(|(_, detail)| detail).collect() }
+    }
+}
+
+/// # ArchiveScan
+///
+/// **Summary:**
+/// What a streaming pass over one archive file found.
+///
+/// **Fields:**
+/// - `name`: File name, for the report
+/// - `archived_at`: Time from the file name, if it could be read
+/// - `messages`: Message count, or why the file doesn't parse
+/// - `tail`: The archive's last messages (only filled for the newest archive)
+#[derive(Debug, Clone)]
+pub struct ArchiveScan {
+    pub name: String,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub messages: Result<usize, String>,
+    pub tail: Vec<Message>,
+}
+
+/// # VerifyInput
+///
+/// **Summary:**
+/// Everything the checks look at for one persona.
+///
+/// **Fields:**
+/// - `history`: The saved history file
+/// - `archives`: The persona's archives, oldest first
+#[derive(Debug, Clone)]
+pub struct VerifyInput {
+    pub history: ConversationHistory,
+    pub archives: Vec<ArchiveScan>,
+}
+
+/// # VerifyReport
+///
+/// **Summary:**
+/// Every check's result for one persona, and what `fix` would change.
+///
+/// **Fields:**
+/// - `persona`: Persona verified
+/// - `checks`: One result per `CheckKind`, in report order
+/// - `repairs`: What `repair` would change, one line each (empty when nothing is repairable)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyReport {
+    pub persona: String,
+    pub checks: Vec<CheckResult>,
+    pub repairs: Vec<String>,
+}
+
+impl VerifyReport {
+    /// Worst status across the checks
+    pub fn status(&self) -> CheckStatus {
+        self.checks.iter().map(|check| check.status).max().unwrap_or(CheckStatus::Pass)
+    }
+
+    fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|check| check.status == status).count()
+    }
+
+    /// # describe
+    ///
+    /// **Purpose:**
+    /// The report as display lines: a heading, then each check with its details.
+    ///
+    /// **Details:**
+    /// Repairable findings are listed last; callers add how to apply them,
+    /// since that differs between the TUI and the command line.
+    pub fn describe(&self) -> Vec<String> {
+        let heading = match (self.count(CheckStatus::Fail), self.count(CheckStatus::Warn)) {
+            (0, 0) => "all checks passed".to_string(),
+            (failed, warned) => format!("{} failed, {} warning(s)", failed, warned),
+        };
+        let mut lines = vec![format!("{}: {}", self.persona, heading)];
+        for check in &self.checks {
+            let mut details = check.details.iter();
+            let first = details.next().map(String::as_str).unwrap_or_default();
+            lines.push(format!("  [{}] {}: {}", check.status, check.kind, first));
+            lines.extend(details.map(|detail| format!("         {}", detail)));
+        }
+        if !self.repairs.is_empty() {
+            lines.push("  Repairable:".to_string());
+            lines.extend(self.repairs.iter().map(|repair| format!("    {}", repair)));
+        }
+        lines
+    }
+}
+
+/// # verify
+///
+/// **Purpose:**
+/// Runs every check over one persona's loaded history and archives.
+///
+/// **Parameters:**
+/// - `persona`: Name for the report
+/// - `input`: What `HistoryManager::load_verify_input` read
+/// - `now`: Current time, for the clock-skew check
+pub fn verify(persona: &str, input: &VerifyInput, now: DateTime<Utc>) -> VerifyReport {
+    let mut repaired = input.history.clone();
+    VerifyReport {
+        persona: persona.to_string(),
+        checks: vec![
+            check_counts(&input.history),
+            check_archives(input),
+            check_summaries(input),
+            check_duplicates(input),
+            check_timestamps(&input.history, now),
+        ],
+        repairs: repair(&mut repaired),
+    }
+}
+
+/// Messages the file holds: recent messages plus one per stored summary
+pub fn stored_message_count(history: &ConversationHistory) -> usize {
+    history.recent_messages.len() + summary_slots(history)
+}
+
+fn summary_slots(history: &ConversationHistory) -> usize {
+    history.summary.is_some() as usize + history.long_term_summary.is_some() as usize
+}
+
+/// Where recent message `index` sits in the loaded conversation, as `history list` numbers it
+fn listed_index(history: &ConversationHistory, index: usize) -> usize {
+    1 + summary_slots(history) + index
+}
+
+fn same_message(a: &Message, b: &Message) -> bool {
+    a.role == b.role && a.content == b.content
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// # check_counts
+///
+/// **Purpose:**
+/// Compares `total_message_count` with the messages the file holds.
+///
+/// **Details:**
+/// The total is the whole conversation at the last save, so it can exceed
+/// what's stored (`history_message_limit` trims the file) but never be lower.
+pub fn check_counts(history: &ConversationHistory) -> CheckResult {
+    let stored = stored_message_count(history);
+    let total = history.total_message_count;
+    if total < stored {
+        return CheckResult {
+            kind: CheckKind::Counts,
+            status: CheckStatus::Fail,
+            details: vec![format!(
+                "total_message_count is {} but the file holds {} message(s) ({} recent, {} summary)",
+                total, stored, history.recent_messages.len(), summary_slots(history)
+            )],
+        };
+    }
+    let summary = match total - stored {
+        0 => format!("{} message(s), total matches", stored),
+        trimmed => format!("{} stored of {}; {} older message(s) were past history_message_limit", stored, total, trimmed),
+    };
+    CheckResult::pass(CheckKind::Counts, summary)
+}
+
+/// # check_archives
+///
+/// **Purpose:**
+/// Checks that every archive parses and predates the history's last save.
+///
+/// **Details:**
+/// Summarizing and `resync fresh` archive first and save the history right
+/// after, so an archive newer than `last_updated` means that save never landed.
+pub fn check_archives(input: &VerifyInput) -> CheckResult {
+    let last_updated = parse_time(&input.history.last_updated);
+    let mut findings = Vec::new();
+    let mut total = 0;
+
+    for archive in &input.archives {
+        match &archive.messages {
+            Err(e) => findings.push((CheckStatus::Fail, format!("{} doesn't parse: {}", archive.name, e))),
+            Ok(0) => findings.push((CheckStatus::Warn, format!("{} holds no messages", archive.name))),
+            Ok(count) => total += count,
+        }
+        match (archive.archived_at, last_updated) {
+            (None, _) => findings.push((CheckStatus::Warn, format!("{}: no readable time in the file name", archive.name))),
+            (Some(at), Some(saved)) if at > saved => findings.push((CheckStatus::Warn, format!(
+                "{} was written after the history was last saved ({}); the summary or fresh start it was made for may not have been saved",
+                archive.name, input.history.last_updated
+            ))),
+            _ => {}
+        }
+    }
+
+    let summary = match input.archives.len() {
+        0 => "no archives".to_string(),
+        count => format!("{} archive(s), {} message(s), all readable", count, total),
+    };
+    CheckResult::from_findings(CheckKind::Archives, findings, summary)
+}
+
+/// # check_summaries
+///
+/// **Purpose:**
+/// Checks `summarization_count` against the stored summaries and the archives.
+///
+/// **Details:**
+/// Every summarization archives the full history first, so the conversation
+/// needs at least one archive per summarization since it started. More is
+/// fine: `resync fresh` archives too. Without `started_at` every archive counts.
+pub fn check_summaries(input: &VerifyInput) -> CheckResult {
+    let history = &input.history;
+    let count = history.summarization_count;
+    let has_summary = history.summary.is_some() || history.long_term_summary.is_some();
+    let mut findings = Vec::new();
+
+    if count > 0 && !has_summary {
+        findings.push((CheckStatus::Fail, format!("summarized {} time(s) but no summary is stored", count)));
+    }
+    if count == 0 && has_summary {
+        findings.push((CheckStatus::Warn, "a summary is stored but summarization_count is 0".to_string()));
+    }
+
+    let started = history.started_at.as_deref().and_then(parse_time);
+    let archived = input.archives.iter()
+        .filter(|archive| match (started, archive.archived_at) {
+            (Some(started), Some(at)) => at >= started,
+            _ => true,
+        })
+        .count();
+    if archived < count {
+        let since = history.started_at.as_deref().map(|at| format!(" since {}", at)).unwrap_or_default();
+        findings.push((CheckStatus::Warn, format!(
+            "summarized {} time(s) but only {} archive(s) were written{}; {} archive(s) are missing",
+            count, archived, since, count - archived
+        )));
+    }
+
+    let summary = format!("summarized {} time(s), {} archive(s) since the conversation started", count, archived);
+    CheckResult::from_findings(CheckKind::Summaries, findings, summary)
+}
+
+/// # kept_overlap
+///
+/// **Purpose:**
+/// How many messages at the start of `recent` continue from the end of `tail`.
+///
+/// **Details:**
+/// Summarizing keeps the newest messages, so they end the archive written
+/// just before and start the live history; this finds the longest such run.
+pub fn kept_overlap(tail: &[Message], recent: &[Message]) -> usize {
+    (1..=tail.len().min(recent.len()))
+        .rev()
+        .find(|&len| {
+            tail[tail.len() - len..].iter()
+                .zip(&recent[..len])
+                .all(|(a, b)| same_message(a, b))
+        })
+        .unwrap_or(0)
+}
+
+/// # check_duplicates
+///
+/// **Purpose:**
+/// Finds repeated messages in the stored history.
+///
+/// **Details:**
+/// - A message repeating the one before it is a warning; `history dedupe` removes those
+/// - The newest archive's kept messages appearing twice in a row at the start of
+///   the history is a failure: a save or merge wrote them again
+///
+/// Indices are as `history list` shows them once the history is loaded.
+pub fn check_duplicates(input: &VerifyInput) -> CheckResult {
+    let history = &input.history;
+    let recent = &history.recent_messages;
+    let mut findings = Vec::new();
+
+    if let Some(newest) = input.archives.last() {
+        let kept = kept_overlap(&newest.tail, recent);
+        if kept > 0 && recent.len() >= kept * 2
+            && recent[..kept].iter().zip(&recent[kept..kept * 2]).all(|(a, b)| same_message(a, b))
+        {
+            findings.push((CheckStatus::Fail, format!(
+                "the {} message(s) kept from {} appear twice: #{}-#{} and #{}-#{}",
+                kept, newest.name,
+                listed_index(history, 0), listed_index(history, kept - 1),
+                listed_index(history, kept), listed_index(history, kept * 2 - 1),
+            )));
+        }
+    }
+
+    let repeats: Vec<String> = recent.windows(2)
+        .enumerate()
+        .filter(|(_, pair)| pair[1].role != "system" && same_message(&pair[0], &pair[1]))
+        .map(|(i, _)| format!("#{}", listed_index(history, i + 1)))
+        .collect();
+    if !repeats.is_empty() {
+        findings.push((CheckStatus::Warn, format!(
+            "{} {} the message before (`history dedupe` removes {})",
+            repeats.join(", "),
+            if repeats.len() == 1 { "repeats" } else { "repeat" },
+            if repeats.len() == 1 { "it" } else { "them" },
+        )));
+    }
+
+    CheckResult::from_findings(CheckKind::Duplicates, findings, format!("none in {} message(s)", recent.len()))
+}
+
+/// # check_timestamps
+///
+/// **Purpose:**
+/// Checks `started_at` <= `last_exchange_at` <= `last_updated`, and that none is unreadable.
+///
+/// **Parameters:**
+/// - `history`: The saved history
+/// - `now`: Current time; `last_updated` well past it means a skewed clock wrote the file
+pub fn check_timestamps(history: &ConversationHistory, now: DateTime<Utc>) -> CheckResult {
+    let mut findings = Vec::new();
+    let mut read = |field: &str, value: Option<&str>| {
+        let value = value?;
+        let parsed = parse_time(value);
+        if parsed.is_none() {
+            findings.push((CheckStatus::Warn, format!("{} isn't a valid time: {}", field, value)));
+        }
+        parsed.map(|t| (field.to_string(), t))
+    };
+    let stamps: Vec<(String, DateTime<Utc>)> = [
+        read("started_at", history.started_at.as_deref()),
+        read("last_exchange_at", history.last_exchange_at.as_deref()),
+        read("last_updated", Some(&history.last_updated)),
+    ].into_iter().flatten().collect();
+
+    for pair in stamps.windows(2) {
+        let ((earlier, a), (later, b)) = (&pair[0], &pair[1]);
+        if a > b {
+            findings.push((CheckStatus::Fail, format!("{} ({}) is after {} ({})", earlier, a.to_rfc3339(), later, b.to_rfc3339())));
+        }
+    }
+    if let Some((_, saved)) = stamps.iter().find(|(field, _)| field == "last_updated")
+        && *saved > now + Duration::minutes(CLOCK_SKEW_ALLOWANCE_MINUTES)
+    {
+        findings.push((CheckStatus::Warn, format!("last_updated ({}) is in the future; check the clock", saved.to_rfc3339())));
+    }
+
+    CheckResult::from_findings(CheckKind::Timestamps, findings, "in order".to_string())
+}
+
+/// # repair
+///
+/// **Purpose:**
+/// Fixes what can be fixed without guessing, in place.
+///
+/// **Returns:**
+/// `Vec<String>` - One line per change (empty when nothing needed fixing)
+///
+/// **Details:**
+/// - A total below what the file holds is recounted
+/// - `started_at` later than the first exchange or save is moved back to it, and
+///   `last_updated` earlier than the last exchange is moved up to it
+///
+/// Archives, summaries, and duplicates are reported only: fixing them means
+/// choosing which copy of the conversation is right.
+pub fn repair(history: &mut ConversationHistory) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    let stored = stored_message_count(history);
+    if history.total_message_count < stored {
+        changes.push(format!("total_message_count {} -> {}", history.total_message_count, stored));
+        history.total_message_count = stored;
+    }
+
+    let exchange = history.last_exchange_at.as_deref().and_then(parse_time);
+    let saved = parse_time(&history.last_updated);
+    if let (Some(exchange), Some(saved)) = (exchange, saved)
+        && saved < exchange
+    {
+        changes.push(format!("last_updated {} -> {}", history.last_updated, exchange.to_rfc3339()));
+        history.last_updated = exchange.to_rfc3339();
+    }
+
+    let first_after_start = exchange.or(parse_time(&history.last_updated));
+    if let Some(started_at) = history.started_at.clone()
+        && let (Some(started), Some(first)) = (parse_time(&started_at), first_after_start)
+        && started > first
+    {
+        changes.push(format!("started_at {} -> {}", started_at, first.to_rfc3339()));
+        history.started_at = Some(first.to_rfc3339());
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(role: &str, content: &str) -> Message {
+        Message { role: role.to_string(), content: content.to_string(), meta: None, source: None }
+    }
+
+    fn at(text: &str) -> DateTime<Utc> {
+        parse_time(text).expect("test time")
+    }
+
+    fn now() -> DateTime<Utc> {
+        at("2026-01-21T00:00:00Z")
+    }
+
+    fn archive(name: &str, archived_at: &str, messages: Result<usize, String>) -> ArchiveScan {
+        ArchiveScan { name: name.to_string(), archived_at: Some(at(archived_at)), messages, tail: Vec::new() }
+    }
+
+    /// Summarized once; the newest archive ends with the two messages kept after it
+    fn healthy() -> VerifyInput {
+        let recent = vec![
+            msg("user", "kept question"),
+            msg("assistant", "kept answer"),
+            msg("user", "new question"),
+            msg("assistant", "new answer"),
+        ];
+        let mut history = ConversationHistory::new("shadow".to_string());
+        history.summary = Some("[Summary of earlier conversation]\nTopics: tests.".to_string());
+        history.recent_messages = recent.clone();
+        history.total_message_count = 20;
+        history.summarization_count = 1;
+        history.started_at = Some("2026-01-10T09:00:00+00:00".to_string());
+        history.last_exchange_at = Some("2026-01-20T18:00:00+00:00".to_string());
+        history.last_updated = "2026-01-20T18:01:00+00:00".to_string();
+
+        let mut newest = archive("shadow_2026-01-15_12-00-00.json", "2026-01-15T12:00:00Z", Ok(16));
+        newest.tail = vec![msg("user", "older"), recent[0].clone(), recent[1].clone()];
+        VerifyInput { history, archives: vec![newest] }
+    }
+
+    fn check(input: &VerifyInput, kind: CheckKind) -> CheckResult {
+        verify("shadow", input, now()).checks.into_iter().find(|check| check.kind == kind).expect("every kind is checked")
+    }
+
+    fn failing(status: CheckStatus, details: &[&str]) -> (CheckStatus, Vec<String>) {
+        (status, details.iter().map(|detail| detail.to_string()).collect())
+    }
+
+    fn outcome(result: CheckResult) -> (CheckStatus, Vec<String>) {
+        (result.status, result.details)
+    }
+
+    #[test]
+    fn a_healthy_history_passes_every_check() {
+        let report = verify("shadow", &healthy(), now());
+        let kinds: Vec<_> = report.checks.iter().map(|check| check.kind).collect();
+        assert_eq!(kinds, [CheckKind::Counts, CheckKind::Archives, CheckKind::Summaries, CheckKind::Duplicates, CheckKind::Timestamps]);
+        assert_eq!(report.status(), CheckStatus::Pass, "{:#?}", report.checks);
+        assert!(report.repairs.is_empty());
+        assert_eq!(report.describe(), [
+            "shadow: all checks passed",
+            "  [pass] counts: 5 stored of 20; 15 older message(s) were past history_message_limit",
+            "  [pass] archives: 1 archive(s), 16 message(s), all readable",
+            "  [pass] summaries: summarized 1 time(s), 1 archive(s) since the conversation started",
+            "  [pass] duplicates: none in 4 message(s)",
+            "  [pass] timestamps: in order",
+        ]);
+    }
+
+    #[test]
+    fn counts_fail_when_the_total_is_below_what_is_stored() {
+        let mut input = healthy();
+        input.history.total_message_count = 3;
+        assert_eq!(
+            outcome(check(&input, CheckKind::Counts)),
+            failing(CheckStatus::Fail, &["total_message_count is 3 but the file holds 5 message(s) (4 recent, 1 summary)"])
+        );
+    }
+
+    #[test]
+    fn archives_that_are_unreadable_empty_undated_or_too_new_are_named() {
+        let mut input = healthy();
+        let mut undated = archive("shadow_notes.json", "2026-01-12T00:00:00Z", Ok(4));
+        undated.archived_at = None;
+        input.archives = vec![
+            archive("shadow_2026-01-11_10-00-00.json", "2026-01-11T10:00:00Z", Err("EOF while parsing".to_string())),
+            archive("shadow_2026-01-12_10-00-00.json", "2026-01-12T10:00:00Z", Ok(0)),
+            undated,
+            archive("shadow_2026-01-20_19-00-00.json", "2026-01-20T19:00:00Z", Ok(8)),
+        ];
+        assert_eq!(outcome(check(&input, CheckKind::Archives)), failing(CheckStatus::Fail, &[
+            "shadow_2026-01-11_10-00-00.json doesn't parse: EOF while parsing",
+            "shadow_2026-01-12_10-00-00.json holds no messages",
+            "shadow_notes.json: no readable time in the file name",
+            "shadow_2026-01-20_19-00-00.json was written after the history was last saved (2026-01-20T18:01:00+00:00); the summary or fresh start it was made for may not have been saved",
+        ]));
+    }
+
+    #[test]
+    fn summaries_must_match_the_count_and_the_archives() {
+        let mut input = healthy();
+        input.history.summary = None;
+        input.history.summarization_count = 2;
+        assert_eq!(outcome(check(&input, CheckKind::Summaries)), failing(CheckStatus::Fail, &[
+            "summarized 2 time(s) but no summary is stored",
+            "summarized 2 time(s) but only 1 archive(s) were written since 2026-01-10T09:00:00+00:00; 1 archive(s) are missing",
+        ]));
+
+        let mut input = healthy();
+        input.history.summarization_count = 0;
+        assert_eq!(
+            outcome(check(&input, CheckKind::Summaries)),
+            failing(CheckStatus::Warn, &["a summary is stored but summarization_count is 0"])
+        );
+
+        // Archives from before the conversation started don't count
+        let mut input = healthy();
+        input.archives[0].archived_at = Some(at("2026-01-05T00:00:00Z"));
+        assert_eq!(check(&input, CheckKind::Summaries).status, CheckStatus::Warn);
+    }
+
+    #[test]
+    fn duplicates_flag_kept_messages_written_twice_and_repeats() {
+        let mut input = healthy();
+        let recent = &mut input.history.recent_messages;
+        let kept: Vec<Message> = recent[..2].to_vec();
+        recent.splice(2..2, kept);
+        recent.push(msg("assistant", "new answer"));
+        input.history.total_message_count = 30;
+        assert_eq!(outcome(check(&input, CheckKind::Duplicates)), failing(CheckStatus::Fail, &[
+            "the 2 message(s) kept from shadow_2026-01-15_12-00-00.json appear twice: #2-#3 and #4-#5",
+            "#8 repeats the message before (`history dedupe` removes it)",
+        ]));
+        assert_eq!(kept_overlap(&input.archives[0].tail, &input.history.recent_messages), 2);
+        assert_eq!(kept_overlap(&[msg("user", "elsewhere")], &input.history.recent_messages), 0);
+    }
+
+    #[test]
+    fn timestamps_out_of_order_unreadable_or_in_the_future_are_reported() {
+        let mut input = healthy();
+        input.history.started_at = Some("2026-01-20T20:00:00+00:00".to_string());
+        input.history.last_exchange_at = Some("yesterday".to_string());
+        assert_eq!(outcome(check(&input, CheckKind::Timestamps)), failing(CheckStatus::Fail, &[
+            "last_exchange_at isn't a valid time: yesterday",
+            "started_at (2026-01-20T20:00:00+00:00) is after last_updated (2026-01-20T18:01:00+00:00)",
+        ]));
+
+        let mut input = healthy();
+        input.history.last_exchange_at = None;
+        input.history.last_updated = "2026-01-21T00:30:00+00:00".to_string();
+        assert_eq!(
+            outcome(check(&input, CheckKind::Timestamps)),
+            failing(CheckStatus::Warn, &["last_updated (2026-01-21T00:30:00+00:00) is in the future; check the clock"])
+        );
+    }
+
+    #[test]
+    fn repair_recounts_and_reorders_but_leaves_the_rest() {
+        let mut input = healthy();
+        input.history.total_message_count = 2;
+        input.history.last_updated = "2026-01-20T17:00:00+00:00".to_string();
+        input.history.started_at = Some("2026-01-20T19:00:00+00:00".to_string());
+        input.history.summarization_count = 0;
+
+        let report = verify("shadow", &input, now());
+        assert_eq!(report.repairs, [
+            "total_message_count 2 -> 5",
+            "last_updated 2026-01-20T17:00:00+00:00 -> 2026-01-20T18:00:00+00:00",
+            "started_at 2026-01-20T19:00:00+00:00 -> 2026-01-20T18:00:00+00:00",
+        ]);
+        assert_eq!(report.describe().last().map(String::as_str), Some("    started_at 2026-01-20T19:00:00+00:00 -> 2026-01-20T18:00:00+00:00"));
+
+        let mut history = input.history.clone();
+        assert_eq!(repair(&mut history), report.repairs);
+        assert!(repair(&mut history).is_empty(), "a second repair changes nothing");
+        let repaired = verify("shadow", &VerifyInput { history, archives: input.archives }, now());
+        let still_failing: Vec<_> = repaired.checks.iter().filter(|check| check.status != CheckStatus::Pass).map(|check| check.kind).collect();
+        assert_eq!(still_failing, [CheckKind::Summaries], "only what repair leaves alone remains");
+    }
+}
@@ -0,0 +1,537 @@
+//! # Daegonica Module: agent_history::legacy
+//!
+//! **Purpose:** Move histories from the old flat `history/` directory into the per-persona layout
+//!
+//! **Context:**
+//! - Early builds saved `history/{persona}_history.json`: a raw message list, later a `ConversationHistory`
+//! - The loader only reads `{root}/{persona}/history/`, so those conversations looked lost
+//! - Run by `grokprime-brain migrate-history` (a dry run unless `--apply`); startup only detects the files
+//!
+//! **Responsibilities:**
+//! - Find legacy files and infer each one's persona from its file name
+//! - Read either old format and convert it to the current `ConversationHistory`
+//! - Plan what happens to each file (move, keep newer, merge, skip) and carry the plan out
+//! - Report every file's disposition
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use chrono::{DateTime, Utc};
+use strum::{Display, EnumString};
+use crate::prelude::*;
+use crate::agent_history::conversations::{LONG_TERM_PREFIX, SUMMARY_PREFIX};
+use crate::agent_history::history::{history_dir, history_path};
+use crate::agent_history::schema::{parse_history, CURRENT_HISTORY_VERSION};
+use crate::persona::{discover_personas, persona_dir};
+use crate::utilities::time::ARCHIVE_STAMP_FORMAT;
+
+/// The flat directory early builds wrote to, relative to the working directory
+pub const LEGACY_HISTORY_DIR: &str = "history";
+
+/// Where handled legacy files are set aside, inside the legacy directory, so a re-run skips them
+const MIGRATED_SUBDIR: &str = "migrated";
+
+/// # ConflictChoice
+///
+/// **Summary:**
+/// What to do when the persona already has a history in the new layout.
+///
+/// **Variants:**
+/// - `KeepNewer`: Keep whichever file has the later `last_updated`
+/// - `Merge`: Keep both conversations, older messages first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ConflictChoice {
+    KeepNewer,
+    Merge,
+}
+
+/// # LegacyFormat
+///
+/// **Summary:**
+/// Which old format a legacy file was in.
+///
+/// **Variants:**
+/// - `Messages`: A bare JSON array of messages, system prompt first
+/// - `Conversation`: A `ConversationHistory` of any format version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegacyFormat {
+    Messages,
+    Conversation,
+}
+
+impl LegacyFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LegacyFormat::Messages => "message list, converted",
+            LegacyFormat::Conversation => "history file",
+        }
+    }
+}
+
+/// # MigrateOptions
+///
+/// **Summary:**
+/// The `migrate-history` flags.
+///
+/// **Fields:**
+/// - `from`: Legacy directory to read
+/// - `apply`: Carry out the plan; otherwise only report it
+/// - `conflict`: How to settle files whose persona already has a history (None leaves them)
+/// - `create_stubs`: Create a stub persona for files whose persona doesn't exist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrateOptions {
+    pub from: PathBuf,
+    pub apply: bool,
+    pub conflict: Option<ConflictChoice>,
+    pub create_stubs: bool,
+}
+
+/// # Disposition
+///
+/// **Summary:**
+/// What happens (or happened) to one legacy file.
+///
+/// **Variants:**
+/// - `Move`: No history in the new layout yet; the file becomes it
+/// - `KeepCurrent`: The existing history is newer and stays; the legacy file is set aside
+/// - `ReplaceCurrent`: The legacy file is newer; the existing history is set aside
+/// - `Merge`: Both are combined into the new layout, older messages first
+/// - `Conflict`: The persona already has a history and no `--conflict` was given; left in place
+/// - `UnknownPersona`: No persona by that name and no `--create-stubs`; left in place
+/// - `Invalid(String)`: Not readable as either old format; left in place
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Disposition {
+    Move,
+    KeepCurrent,
+    ReplaceCurrent,
+    Merge,
+    Conflict,
+    UnknownPersona,
+    Invalid(String),
+}
+
+/// # FileReport
+///
+/// **Summary:**
+/// One legacy file's line in the report.
+///
+/// **Fields:**
+/// - `file`: The legacy file
+/// - `persona`: Persona inferred from its name
+/// - `format`: The old format it was read as, if it could be
+/// - `disposition`: What happens to it
+/// - `stub`: A stub persona is created for it
+/// - `error`: Why applying the disposition failed, if it did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileReport {
+    pub file: PathBuf,
+    pub persona: String,
+    pub format: Option<LegacyFormat>,
+    pub disposition: Disposition,
+    pub stub: bool,
+    pub error: Option<String>,
+}
+
+impl FileReport {
+    pub fn describe(&self) -> String {
+        let name = self.file.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        let action = match &self.disposition {
+            Disposition::Move => "move into the new layout".to_string(),
+            Disposition::KeepCurrent => "keep the existing history (newer); set this file aside".to_string(),
+            Disposition::ReplaceCurrent => "replace the existing history (older, set aside) with this file".to_string(),
+            Disposition::Merge => "merge with the existing history, older messages first".to_string(),
+            Disposition::Conflict => "skipped: the persona already has a history; choose --conflict keep-newer or --conflict merge".to_string(),
+            Disposition::UnknownPersona => format!("skipped: no persona '{}'; --create-stubs creates one", self.persona),
+            Disposition::Invalid(reason) => format!("skipped: {}", reason),
+        };
+        let mut line = format!("  {} -> {}: {}", name, self.persona, action);
+        if let Some(format) = self.format {
+            line.push_str(&format!(" ({})", format.label()));
+        }
+        if self.stub {
+            line.push_str(&format!(", creating a stub persona in {}", persona_dir(&self.persona).display()));
+        }
+        if let Some(error) = &self.error {
+            line.push_str(&format!(" FAILED: {}", error));
+        }
+        line
+    }
+
+    /// Whether applying this leaves the legacy file in place for another run
+    fn is_pending(&self) -> bool {
+        self.error.is_some()
+            || matches!(self.disposition, Disposition::Conflict | Disposition::UnknownPersona | Disposition::Invalid(_))
+    }
+}
+
+/// # MigrationReport
+///
+/// **Summary:**
+/// Every legacy file found and what happens to it.
+///
+/// **Fields:**
+/// - `from`: The legacy directory
+/// - `applied`: The plan was carried out (false for a dry run)
+/// - `files`: One entry per legacy file, by file name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+    pub from: PathBuf,
+    pub applied: bool,
+    pub files: Vec<FileReport>,
+}
+
+impl MigrationReport {
+    pub fn describe(&self) -> String {
+        if self.files.is_empty() {
+            return format!("No legacy history files in {}.", self.from.display());
+        }
+        let header = if self.applied {
+            format!(
+                "Migrated legacy histories from {} (handled files were moved to {}):",
+                self.from.display(),
+                self.from.join(MIGRATED_SUBDIR).display()
+            )
+        } else {
+            format!("Legacy histories in {} (dry run; add --apply to carry this out):", self.from.display())
+        };
+        let mut lines = vec![header];
+        lines.extend(self.files.iter().map(FileReport::describe));
+        let pending = self.files.iter().filter(|file| file.is_pending()).count();
+        if pending > 0 {
+            lines.push(format!("{} file(s) left in place; re-run after resolving them.", pending));
+        }
+        lines.join("\n")
+    }
+}
+
+/// # legacy_files
+///
+/// **Purpose:**
+/// The `.json` files directly inside a legacy directory, by name.
+///
+/// **Details:**
+/// A missing or unreadable directory has none. The `migrated/` subdirectory
+/// is never scanned, which is what makes a re-run a no-op.
+pub fn legacy_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries.filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// The persona a legacy file belongs to: its name without `_history.json`
+pub fn infer_persona(path: &Path) -> String {
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    stem.strip_suffix("_history").map(str::to_string).unwrap_or(stem)
+}
+
+/// # read_legacy
+///
+/// **Purpose:**
+/// Reads a legacy file in either old format as a current `ConversationHistory`.
+///
+/// **Returns:**
+/// `Result<(ConversationHistory, LegacyFormat), String>` - The history and the format it was in
+///
+/// **Errors / Failures:**
+/// - Unreadable, or neither a message list nor a history file (including one
+///   written by a newer build)
+pub fn read_legacy(path: &Path, persona: &str) -> Result<(ConversationHistory, LegacyFormat), String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("can't read it ({})", e))?;
+    if let Ok(messages) = serde_json::from_str::<Vec<Message>>(&content) {
+        let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok().map(DateTime::<Utc>::from);
+        return Ok((from_messages(persona, messages, modified), LegacyFormat::Messages));
+    }
+    parse_history(&content)
+        .map(|history| (history, LegacyFormat::Conversation))
+        .map_err(|e| format!("neither a message list nor a history file ({})", e))
+}
+
+/// # from_messages
+///
+/// **Purpose:**
+/// Converts a raw message list to a `ConversationHistory`.
+///
+/// **Details:**
+/// The leading system message was the persona prompt of the day; it's dropped,
+/// since loading adds the current one. Summary messages become the summary
+/// fields; other system messages (nudges) are kept in place. With no better
+/// time known, `last_updated` is the file's modification time.
+fn from_messages(persona: &str, messages: Vec<Message>, modified: Option<DateTime<Utc>>) -> ConversationHistory {
+    let mut history = ConversationHistory::new(persona.to_string());
+    let skip_prompt = messages.first().is_some_and(|first| first.role == "system" && !first.content.starts_with('['));
+    for message in messages.into_iter().skip(usize::from(skip_prompt)) {
+        let unwrap = |prefix: &str| message.content.strip_prefix(prefix)
+            .map(|text| text.strip_suffix(']').unwrap_or(text).to_string());
+        match (message.role.as_str(), unwrap(SUMMARY_PREFIX), unwrap(LONG_TERM_PREFIX)) {
+            ("system", Some(summary), _) => history.summary = Some(summary),
+            ("system", _, Some(long_term)) => history.long_term_summary = Some(long_term),
+            _ => history.recent_messages.push(message),
+        }
+    }
+    history.total_message_count = history.recent_messages.len();
+    if let Some(modified) = modified {
+        history.last_updated = modified.to_rfc3339();
+    }
+    history
+}
+
+/// `last_updated` as a time; unparseable counts as oldest
+fn updated_at(history: &ConversationHistory) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(&history.last_updated)
+        .map(|at| at.with_timezone(&Utc))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+/// # merge_histories
+///
+/// **Purpose:**
+/// Combines two histories of one persona, the older one's messages first.
+///
+/// **Details:**
+/// When the newer history starts with the older one's last messages (one was
+/// copied from the other), that overlap is kept once. Summaries and settings
+/// come from the newer history where it has them. The server-side thread
+/// never saw the older messages, so neither response ID is kept.
+pub fn merge_histories(a: ConversationHistory, b: ConversationHistory) -> ConversationHistory {
+    let (older, newer) = if updated_at(&a) <= updated_at(&b) { (a, b) } else { (b, a) };
+    let same = |x: &Message, y: &Message| x.role == y.role && x.content == y.content;
+    let overlap = (0..=older.recent_messages.len().min(newer.recent_messages.len()))
+        .rev()
+        .find(|n| {
+            let tail = &older.recent_messages[older.recent_messages.len() - n..];
+            tail.iter().zip(&newer.recent_messages[..*n]).all(|(x, y)| same(x, y))
+        })
+        .unwrap_or(0);
+
+    let mut recent_messages = older.recent_messages;
+    recent_messages.extend(newer.recent_messages.into_iter().skip(overlap));
+    ConversationHistory {
+        total_message_count: (older.total_message_count + newer.total_message_count)
+            .saturating_sub(overlap)
+            .max(recent_messages.len()),
+        recent_messages,
+        summary: newer.summary.or(older.summary),
+        long_term_summary: newer.long_term_summary.or(older.long_term_summary),
+        summarization_count: newer.summarization_count.max(older.summarization_count),
+        started_at: older.started_at.or(newer.started_at),
+        last_response_id: None,
+        last_response_at: None,
+        format_version: CURRENT_HISTORY_VERSION,
+        ..newer
+    }
+}
+
+/// # migrate
+///
+/// **Purpose:**
+/// Plans the migration of every legacy file and, with `apply`, carries it out.
+///
+/// **Returns:**
+/// `Result<MigrationReport, ShadowError>` - Each file's disposition (and any failure applying it)
+///
+/// **Errors / Failures:**
+/// - The personas directory can't be scanned
+///
+/// **Details:**
+/// Nothing is deleted. Handled legacy files, and any existing history they
+/// replace, are moved to `{from}/migrated/`. Files that need a decision stay
+/// where they are. A failure on one file is reported and the rest go ahead.
+pub fn migrate(options: &MigrateOptions) -> Result<MigrationReport, ShadowError> {
+    let personas: Vec<String> = discover_personas()?.into_iter().map(|(name, _)| name).collect();
+    let mut files = Vec::new();
+    for file in legacy_files(&options.from) {
+        let mut report = plan_file(&file, &personas, options);
+        if options.apply && !report.is_pending() {
+            report.error = apply_file(&file, &report, options).err().map(|e| e.to_string());
+        }
+        files.push(report);
+    }
+    Ok(MigrationReport { from: options.from.clone(), applied: options.apply, files })
+}
+
+/// Decides what happens to one legacy file, without touching anything
+fn plan_file(file: &Path, personas: &[String], options: &MigrateOptions) -> FileReport {
+    let persona = infer_persona(file);
+    let mut report = FileReport {
+        file: file.to_path_buf(),
+        persona: persona.clone(),
+        format: None,
+        disposition: Disposition::Move,
+        stub: false,
+        error: None,
+    };
+    let legacy = match read_legacy(file, &persona) {
+        Ok((legacy, format)) => {
+            report.format = Some(format);
+            legacy
+        }
+        Err(reason) => {
+            report.disposition = Disposition::Invalid(reason);
+            return report;
+        }
+    };
+    if !personas.contains(&persona) {
+        if !options.create_stubs {
+            report.disposition = Disposition::UnknownPersona;
+            return report;
+        }
+        report.stub = true;
+    }
+
+    let current_path = history_path(&persona);
+    if !current_path.exists() {
+        return report;
+    }
+    report.disposition = match (options.conflict, fs::read_to_string(&current_path).map(|content| parse_history(&content))) {
+        (_, Err(e)) => Disposition::Invalid(format!("the existing history can't be read ({})", e)),
+        (_, Ok(Err(e))) => Disposition::Invalid(format!("the existing history can't be read ({})", e)),
+        (None, Ok(Ok(_))) => Disposition::Conflict,
+        (Some(ConflictChoice::Merge), Ok(Ok(_))) => Disposition::Merge,
+        (Some(ConflictChoice::KeepNewer), Ok(Ok(current))) => {
+            if updated_at(&legacy) > updated_at(&current) { Disposition::ReplaceCurrent } else { Disposition::KeepCurrent }
+        }
+    };
+    report
+}
+
+/// Carries out a planned disposition that needs no further decision
+fn apply_file(file: &Path, report: &FileReport, options: &MigrateOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let persona = &report.persona;
+    if report.stub {
+        write_stub_persona(persona)?;
+    }
+    let current_path = history_path(persona);
+    let write = |history: &ConversationHistory| -> Result<(), Box<dyn std::error::Error>> {
+        fs::create_dir_all(history_dir(persona))?;
+        fs::write(&current_path, serde_json::to_string_pretty(history)?)?;
+        Ok(())
+    };
+
+    let (legacy, _) = read_legacy(file, persona)?;
+    match report.disposition {
+        Disposition::Move => write(&legacy)?,
+        Disposition::KeepCurrent => {}
+        Disposition::ReplaceCurrent => {
+            let stamp = chrono::Local::now().format(ARCHIVE_STAMP_FORMAT);
+            set_aside(&current_path, &options.from, &format!("{}_history.replaced-{}.json", persona, stamp))?;
+            write(&legacy)?;
+        }
+        Disposition::Merge => {
+            let current = parse_history(&fs::read_to_string(&current_path)?)?;
+            write(&merge_histories(current, legacy))?;
+        }
+        Disposition::Conflict | Disposition::UnknownPersona | Disposition::Invalid(_) => return Ok(()),
+    }
+
+    let name = file.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    set_aside(file, &options.from, &name)?;
+    log_info!("Migrated legacy history {} for {}", file.display(), persona);
+    Ok(())
+}
+
+/// Moves `path` into `{from}/migrated/` as `name`, numbering it if that's taken
+fn set_aside(path: &Path, from: &Path, name: &str) -> Result<PathBuf, std::io::Error> {
+    let dir = from.join(MIGRATED_SUBDIR);
+    fs::create_dir_all(&dir)?;
+    let mut target = dir.join(name);
+    let mut n = 1;
+    while target.exists() {
+        target = dir.join(format!("{}.{}", name, n));
+        n += 1;
+    }
+    fs::rename(path, &target)?;
+    Ok(target)
+}
+
+/// # write_stub_persona
+///
+/// **Purpose:**
+/// Creates a minimal persona file so a migrated history has a persona to load with.
+///
+/// **Details:**
+/// Never overwrites an existing file. The prompt is a placeholder to edit.
+fn write_stub_persona(persona: &str) -> Result<(), std::io::Error> {
+    let dir = persona_dir(persona);
+    let path = dir.join(format!("{}.yaml", persona));
+    if path.exists() {
+        return Ok(());
+    }
+    fs::create_dir_all(&dir)?;
+    fs::write(
+        &path,
+        format!(
+            "name: {name}\n\
+             description: \"Created by migrate-history for an old history file; edit the prompt\"\n\
+             system_prompt: \"You are {name}.\"\n",
+            name = persona
+        ),
+    )?;
+    log_info!("Created stub persona {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: &str) -> Message {
+        Message { role: role.to_string(), content: content.to_string(), meta: None, source: None }
+    }
+
+    #[test]
+    fn the_persona_comes_from_the_file_name() {
+        let cases = [
+            ("history/shadow_history.json", "shadow"),
+            ("history/Friday_history.json", "Friday"),
+            ("history/notes.json", "notes"),
+            ("history/my_history_history.json", "my_history"),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(infer_persona(Path::new(path)), expected, "{}", path);
+        }
+    }
+
+    #[test]
+    fn a_message_list_keeps_nudges_but_not_its_prompt() {
+        let history = from_messages("shadow", vec![
+            message("system", &format!("{}Planned the week.]", SUMMARY_PREFIX)),
+            message("user", "Morning."),
+            message("system", "Nudge: it's been quiet."),
+            message("assistant", "Morning!"),
+        ], None);
+        assert_eq!(history.summary.as_deref(), Some("Planned the week."), "a leading summary isn't the prompt");
+        let roles: Vec<&str> = history.recent_messages.iter().map(|msg| msg.role.as_str()).collect();
+        assert_eq!(roles, ["user", "system", "assistant"]);
+        assert_eq!(history.total_message_count, 3);
+    }
+
+    #[test]
+    fn merging_without_an_overlap_keeps_every_message() {
+        let mut older = ConversationHistory::new("shadow".to_string());
+        older.recent_messages = vec![message("user", "One."), message("assistant", "1")];
+        older.last_updated = "2025-01-01T00:00:00+00:00".to_string();
+        older.last_response_id = Some("resp_old".to_string());
+        let mut newer = ConversationHistory::new("shadow".to_string());
+        newer.recent_messages = vec![message("user", "Two."), message("assistant", "2")];
+        newer.total_message_count = 10;
+        newer.summary = Some("Counted.".to_string());
+
+        let merged = merge_histories(newer, older);
+        let contents: Vec<&str> = merged.recent_messages.iter().map(|msg| msg.content.as_str()).collect();
+        assert_eq!(contents, ["One.", "1", "Two.", "2"], "order follows last_updated, not the arguments");
+        assert_eq!((merged.total_message_count, merged.summary.as_deref()), (10, Some("Counted.")));
+        assert_eq!(merged.last_response_id, None, "the thread never saw the older messages");
+    }
+}
@@ -17,5 +17,16 @@
 //! This file is part of the Daegonica Software codebase.
 //! ---------------------------------------------------------------
 
+pub mod checkpoint;
 pub mod conversations;
-pub mod history;
\ No newline at end of file
+pub mod freshness;
+pub mod history;
+pub mod integrity;
+pub mod legacy;
+pub mod overview;
+pub mod persistence;
+pub mod read_state;
+pub mod redaction;
+pub mod schema;
+pub mod shaping;
+pub mod summary_format;
\ No newline at end of file
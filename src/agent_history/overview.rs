@@ -0,0 +1,483 @@
+//! # Daegonica Module: agent_history::overview
+//!
+//! **Purpose:** One table summarizing every persona's stored history
+//!
+//! **Context:**
+//! - Backs the `overview` command; covers personas on disk, not just open agents
+//! - Reads only history metadata and file sizes, never whole conversations into memory
+//!
+//! **Responsibilities:**
+//! - Collect per-persona counts, last activity, history size, and archive count
+//! - Keep going when one persona's files are missing or corrupt ("?" plus a warning)
+//! - Sort rows by a chosen column and render them as an aligned table or JSON
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::cmp::Reverse;
+use std::io::BufReader;
+use chrono::{DateTime, Utc};
+use serde::de::IgnoredAny;
+use strum::{Display, EnumIter, EnumString};
+use crate::prelude::*;
+use crate::utilities::time::{format_timestamp, Zone};
+
+/// # OverviewSort
+///
+/// **Summary:**
+/// Column the overview is sorted by (`overview --sort <column>`).
+///
+/// **Details:**
+/// `persona` sorts by name; every other column puts the largest or most
+/// recent first. Rows without a value always go last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, Display, EnumIter)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum OverviewSort {
+    #[default]
+    Persona,
+    Messages,
+    Activity,
+    Summaries,
+    Size,
+    Archives,
+}
+
+/// The parts of a history file the overview needs; everything else is skipped unread
+#[derive(Debug, Deserialize)]
+struct HistoryHeader {
+    #[serde(default)]
+    total_message_count: Option<usize>,
+    #[serde(default)]
+    last_updated: Option<String>,
+    #[serde(default)]
+    summarization_count: Option<usize>,
+    #[serde(default)]
+    recent_messages: Vec<IgnoredAny>,
+}
+
+/// # PersonaOverview
+///
+/// **Summary:**
+/// One row of the overview.
+///
+/// **Fields:**
+/// - `persona`: Persona name
+/// - `has_history`: A history file exists (otherwise the history columns are blank, not unknown)
+/// - `messages`: Messages exchanged, including summarized ones
+/// - `last_active`: When the history was last saved
+/// - `summaries`: Times the history was summarized
+/// - `history_bytes`: Size of the history file
+/// - `archives`: Archive files kept for the persona
+/// - `warnings`: What couldn't be read, for the lines under the table
+///
+/// **Details:**
+/// A None in a column the persona should have means it couldn't be read; it's
+/// shown as "?" and explained in `warnings`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PersonaOverview {
+    pub persona: String,
+    pub has_history: bool,
+    pub messages: Option<usize>,
+    pub last_active: Option<DateTime<Utc>>,
+    pub summaries: Option<usize>,
+    pub history_bytes: Option<u64>,
+    pub archives: Option<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl PersonaOverview {
+    /// # collect
+    ///
+    /// **Purpose:**
+    /// Reads one persona's row from `root` (normally `personas/`).
+    ///
+    /// **File Locations:**
+    /// - `{root}/{persona}/history/{persona}_history.json`
+    /// - `{root}/archives/{persona}_*.json`
+    pub fn collect(root: &Path, persona: &str) -> Self {
+        let mut row = Self { persona: persona.to_string(), ..Default::default() };
+
+        let history_path = root.join(persona).join("history").join(format!("{}_history.json", persona));
+        match fs::metadata(&history_path) {
+            Ok(metadata) => {
+                row.has_history = true;
+                row.history_bytes = Some(metadata.len());
+                row.read_header(&history_path);
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => {
+                row.has_history = true;
+                row.warnings.push(format!("{}: {}", history_path.display(), e));
+            }
+        }
+
+        match count_archives(&root.join("archives"), persona) {
+            Ok(count) => row.archives = Some(count),
+            Err(e) => row.warnings.push(format!("archives: {}", e)),
+        }
+        row
+    }
+
+    fn read_header(&mut self, path: &Path) {
+        let header = File::open(path)
+            .map_err(|e| e.to_string())
+            .and_then(|file| {
+                serde_json::from_reader::<_, HistoryHeader>(BufReader::new(file)).map_err(|e| e.to_string())
+            });
+        let header = match header {
+            Ok(header) => header,
+            Err(e) => {
+                self.warnings.push(format!("{}: unreadable history ({})", path.display(), e));
+                return;
+            }
+        };
+
+        // Files written before counts were stored only have their recent messages
+        self.messages = Some(header.total_message_count.unwrap_or(header.recent_messages.len()));
+        self.summaries = Some(header.summarization_count.unwrap_or(0));
+        match header.last_updated.as_deref().map(DateTime::parse_from_rfc3339) {
+            Some(Ok(at)) => self.last_active = Some(at.with_timezone(&Utc)),
+            Some(Err(e)) => self.warnings.push(format!("{}: bad last_updated ({})", path.display(), e)),
+            None => self.warnings.push(format!("{}: no last_updated", path.display())),
+        }
+    }
+
+    /// A history column for the table: the value, "?" if unreadable, "-" without history
+    fn cell<T>(&self, value: Option<T>, show: impl Fn(T) -> String) -> String {
+        match value {
+            Some(value) => show(value),
+            None if self.has_history => "?".to_string(),
+            None => "-".to_string(),
+        }
+    }
+}
+
+/// Archive files for a persona (listing only; archives aren't opened)
+fn count_archives(dir: &Path, persona: &str) -> io::Result<usize> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    let prefix = format!("{}_", persona);
+    Ok(entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+        .filter(|path| path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| stem.strip_prefix(&prefix).is_some_and(|rest| !rest.is_empty())))
+        .count())
+}
+
+/// # personas_with_history
+///
+/// **Purpose:**
+/// Directories under `root` holding a `history` folder, so personas whose YAML
+/// is gone still show up.
+pub fn personas_with_history(root: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("history").is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect()
+}
+
+/// Human-readable size ("812 B", "14.2 KB", "3.1 MB")
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        b if b < 1024 => format!("{} B", b),
+        b if b < 1024 * 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{:.1} MB", b as f64 / 1_048_576.0),
+    }
+}
+
+/// # Overview
+///
+/// **Summary:**
+/// Every persona's row plus workspace-wide figures.
+///
+/// **Fields:**
+/// - `personas`: One row per persona, sorted
+/// - `outbox_queued`: Posts waiting in the tweet outbox, None if it couldn't be read
+/// - `warnings`: Workspace-level problems (per-persona ones live on the rows)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Overview {
+    pub personas: Vec<PersonaOverview>,
+    pub outbox_queued: Option<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl Overview {
+    /// # collect
+    ///
+    /// **Purpose:**
+    /// Builds the overview for the given personas.
+    ///
+    /// **Parameters:**
+    /// - `root`: Personas directory
+    /// - `personas`: Names to include (duplicates are dropped)
+    /// - `outbox_queued`: Outbox size, or the error from loading it
+    pub fn collect(root: &Path, personas: &[String], outbox_queued: Result<usize, String>) -> Self {
+        let mut names = personas.to_vec();
+        names.sort();
+        names.dedup();
+
+        let (outbox_queued, warnings) = match outbox_queued {
+            Ok(count) => (Some(count), Vec::new()),
+            Err(e) => (None, vec![format!("tweet outbox: {}", e)]),
+        };
+        Self {
+            personas: names.iter().map(|name| PersonaOverview::collect(root, name)).collect(),
+            outbox_queued,
+            warnings,
+        }
+    }
+
+    /// # sort_by
+    ///
+    /// **Purpose:**
+    /// Orders the rows by a column (ties by name).
+    pub fn sort_by(&mut self, column: OverviewSort) {
+        self.personas.sort_by(|a, b| a.persona.cmp(&b.persona));
+        match column {
+            OverviewSort::Persona => {}
+            // Option orders None first, so Reverse(Some) puts missing values last
+            OverviewSort::Messages => self.personas.sort_by_key(|row| Reverse(row.messages)),
+            OverviewSort::Activity => self.personas.sort_by_key(|row| Reverse(row.last_active)),
+            OverviewSort::Summaries => self.personas.sort_by_key(|row| Reverse(row.summaries)),
+            OverviewSort::Size => self.personas.sort_by_key(|row| Reverse(row.history_bytes)),
+            OverviewSort::Archives => self.personas.sort_by_key(|row| Reverse(row.archives)),
+        }
+    }
+
+    /// # render_table
+    ///
+    /// **Purpose:**
+    /// Aligned plain-text table, followed by workspace figures and any warnings.
+    pub fn render_table(&self, zone: Zone) -> String {
+        let header = ["Persona", "Messages", "Last active", "Summaries", "History", "Archives"];
+        let rows: Vec<[String; 6]> = self.personas.iter()
+            .map(|row| [
+                row.persona.clone(),
+                row.cell(row.messages, |n| n.to_string()),
+                row.cell(row.last_active, |at| format_timestamp(at, zone)),
+                row.cell(row.summaries, |n| n.to_string()),
+                row.cell(row.history_bytes, format_size),
+                row.archives.map_or("?".to_string(), |n| n.to_string()),
+            ])
+            .collect();
+
+        let mut widths = header.map(|title| title.chars().count());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let line = |cells: Vec<&str>| -> String {
+            cells.iter().zip(widths).enumerate()
+                .map(|(column, (cell, width))| {
+                    // Names left-aligned, figures right-aligned
+                    if column == 0 { format!("{:<width$}", cell) } else { format!("{:>width$}", cell) }
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+
+        let mut out = vec![line(header.to_vec())];
+        out.extend(rows.iter().map(|row| line(row.iter().map(String::as_str).collect())));
+        if rows.is_empty() {
+            out.push("(no personas found)".to_string());
+        }
+
+        out.push(String::new());
+        out.push(match self.outbox_queued {
+            Some(count) => format!("Tweet outbox: {} queued", count),
+            None => "Tweet outbox: ?".to_string(),
+        });
+
+        let warnings: Vec<String> = self.personas.iter()
+            .flat_map(|row| row.warnings.iter().map(move |warning| format!("{}: {}", row.persona, warning)))
+            .chain(self.warnings.iter().cloned())
+            .collect();
+        if !warnings.is_empty() {
+            out.push(format!("Warnings ({}):", warnings.len()));
+            out.extend(warnings.iter().map(|warning| format!("  {}", warning)));
+        }
+        out.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+    use crate::test_support::ScratchDir;
+
+    const UTC: Zone = Zone::Fixed(FixedOffset::east_opt(0).unwrap());
+
+    /// A personas directory with healthy, legacy, empty, broken and history-less personas
+    fn fixture_tree() -> ScratchDir {
+        let root = ScratchDir::new("overview");
+        root.write("healthy/history/healthy_history.json", r#"{
+            "persona_name": "healthy",
+            "total_message_count": 240,
+            "summarization_count": 3,
+            "last_updated": "2025-03-14T09:30:00Z",
+            "recent_messages": [{"role": "user", "content": "hi"}, {"role": "assistant", "content": "hello"}]
+        }"#);
+        root.write("archives/healthy_20250101.json", "{}");
+        root.write("archives/healthy_20250201.json", "{}");
+        root.write("archives/healthy.json", "{}");
+        root.write("archives/healthy_notes.txt", "");
+        root.write("legacy/history/legacy_history.json", r#"{
+            "last_updated": "2025-01-02T18:00:00+02:00",
+            "recent_messages": [{"role": "user", "content": "a"}, {"role": "assistant", "content": "b"}, {"role": "user", "content": "c"}]
+        }"#);
+        fs::create_dir_all(root.join("empty/history")).unwrap();
+        root.write("broken/history/broken_history.json", r#"{"total_message_count": 12, "recent_messages": ["#);
+        root.write("undated/history/undated_history.json", r#"{"total_message_count": 5, "recent_messages": []}"#);
+        root.write("nohistory/nohistory.yaml", "name: nohistory\nsystem_prompt: x\n");
+        root
+    }
+
+    fn names(root: &ScratchDir) -> Vec<String> {
+        let mut names = personas_with_history(root.path());
+        names.sort();
+        names
+    }
+
+    fn row<'a>(overview: &'a Overview, persona: &str) -> &'a PersonaOverview {
+        overview.personas.iter().find(|row| row.persona == persona).expect("a row for the persona")
+    }
+
+    #[test]
+    fn personas_are_found_by_their_history_folder() {
+        let root = fixture_tree();
+        assert_eq!(names(&root), ["broken", "empty", "healthy", "legacy", "undated"]);
+        assert!(personas_with_history(&root.join("missing")).is_empty());
+    }
+
+    #[test]
+    fn healthy_and_legacy_rows_are_read_from_the_header() {
+        let root = fixture_tree();
+        let healthy = PersonaOverview::collect(root.path(), "healthy");
+        assert_eq!(
+            (healthy.messages, healthy.summaries, healthy.archives),
+            (Some(240), Some(3), Some(2)),
+            "archives need a suffix after the name"
+        );
+        assert_eq!(healthy.last_active, Some("2025-03-14T09:30:00Z".parse().unwrap()));
+        assert!(healthy.history_bytes.is_some_and(|bytes| bytes > 0));
+        assert!(healthy.warnings.is_empty(), "{:?}", healthy.warnings);
+
+        let legacy = PersonaOverview::collect(root.path(), "legacy");
+        assert_eq!((legacy.messages, legacy.summaries), (Some(3), Some(0)), "counted from the recent messages");
+        assert_eq!(legacy.last_active, Some("2025-01-02T16:00:00Z".parse().unwrap()));
+    }
+
+    #[test]
+    fn broken_and_missing_files_become_question_marks_and_warnings() {
+        let root = fixture_tree();
+        let cases = [
+            ("empty", false, None, "", 0),
+            ("nohistory", false, None, "", 0),
+            ("broken", true, None, "unreadable history", 1),
+            ("undated", true, Some(5), "no last_updated", 1),
+        ];
+
+        for (persona, has_history, messages, warning, warnings) in cases {
+            let row = PersonaOverview::collect(root.path(), persona);
+            assert_eq!(row.has_history, has_history, "{}", persona);
+            assert_eq!(row.messages, messages, "{}", persona);
+            assert_eq!(row.warnings.len(), warnings, "{}: {:?}", persona, row.warnings);
+            assert!(row.warnings.iter().all(|line| line.contains(warning)), "{}: {:?}", persona, row.warnings);
+            assert_eq!(row.archives, Some(0), "{}", persona);
+        }
+    }
+
+    #[test]
+    fn sorting_puts_the_largest_first_and_missing_values_last() {
+        let root = fixture_tree();
+        let mut overview = Overview::collect(root.path(), &names(&root), Ok(0));
+        let order = |overview: &Overview| overview.personas.iter().map(|row| row.persona.clone()).collect::<Vec<_>>();
+
+        let cases = [
+            (OverviewSort::Persona, ["broken", "empty", "healthy", "legacy", "undated"]),
+            (OverviewSort::Messages, ["healthy", "undated", "legacy", "broken", "empty"]),
+            (OverviewSort::Activity, ["healthy", "legacy", "broken", "empty", "undated"]),
+            (OverviewSort::Summaries, ["healthy", "legacy", "undated", "broken", "empty"]),
+            (OverviewSort::Archives, ["healthy", "broken", "empty", "legacy", "undated"]),
+        ];
+        for (column, expected) in cases {
+            overview.sort_by(column);
+            assert_eq!(order(&overview), expected, "by {}", column);
+        }
+    }
+
+    #[test]
+    fn one_table_for_the_whole_tree() {
+        let root = fixture_tree();
+        let mut names = names(&root);
+        names.extend(["nohistory".to_string(), "healthy".to_string()]);
+        let overview = Overview::collect(root.path(), &names, Err("expected value at line 1".to_string()));
+
+        let table = overview.render_table(UTC);
+        let lines: Vec<&str> = table.lines().collect();
+        let size = |persona: &str| format_size(row(&overview, persona).history_bytes.expect("a history file"));
+        assert_eq!(lines[..8], [
+            "Persona    Messages       Last active  Summaries  History  Archives",
+            &format!("broken            ?                 ?          ?  {:>7}         0", size("broken")),
+            "empty             -                 -          -        -         0",
+            &format!("healthy         240  2025-03-14 09:30          3  {:>7}         2", size("healthy")),
+            &format!("legacy            3  2025-01-02 16:00          0  {:>7}         0", size("legacy")),
+            "nohistory         -                 -          -        -         0",
+            &format!("undated           5                 ?          0  {:>7}         0", size("undated")),
+            "",
+        ]);
+        assert_eq!(lines[8], "Tweet outbox: ?");
+        assert_eq!(lines[9], "Warnings (3):");
+        assert!(lines[10].starts_with("  broken: ") && lines[10].contains("unreadable history"), "{}", lines[10]);
+        assert!(lines[11].starts_with("  undated: ") && lines[11].ends_with("no last_updated"), "{}", lines[11]);
+        assert_eq!(lines[12], "  tweet outbox: expected value at line 1");
+        assert_eq!(lines.len(), 13);
+    }
+
+    #[test]
+    fn json_carries_the_same_rows() {
+        let root = fixture_tree();
+        let overview = Overview::collect(root.path(), &["healthy".to_string(), "broken".to_string()], Ok(4));
+        let json: serde_json::Value = serde_json::to_value(&overview).unwrap();
+
+        assert_eq!(json["outbox_queued"], 4);
+        assert!(json.get("warnings").is_none());
+        assert_eq!(json["personas"][0]["persona"], "broken");
+        assert_eq!(json["personas"][0]["messages"], serde_json::Value::Null);
+        assert_eq!(json["personas"][0]["warnings"].as_array().map(Vec::len), Some(1));
+        assert_eq!(json["personas"][1]["messages"], 240);
+        assert_eq!(json["personas"][1]["last_active"], "2025-03-14T09:30:00Z");
+        assert!(json["personas"][1].get("warnings").is_none());
+    }
+
+    #[test]
+    fn an_empty_workspace_still_renders() {
+        let overview = Overview::collect(Path::new("/nonexistent"), &[], Ok(0));
+        assert_eq!(overview.render_table(UTC), "Persona  Messages  Last active  Summaries  History  Archives\n(no personas found)\n\nTweet outbox: 0 queued");
+        assert_eq!(format_size(812), "812 B");
+        assert_eq!(format_size(14_540), "14.2 KB");
+        assert_eq!(format_size(3_250_586), "3.1 MB");
+    }
+}
@@ -0,0 +1,207 @@
+//! # Daegonica Module: agent_history::persistence
+//!
+//! **Purpose:** Stop retrying writes once the disk has refused them
+//!
+//! **Context:**
+//! - Running from a read-only mount makes every automatic history save fail
+//! - Shared by history auto-saves, archives, and the TUI session file
+//!
+//! **Responsibilities:**
+//! - Recognize write failures that won't fix themselves (read-only, permission denied)
+//! - Track the process-wide active / suspended state
+//! - Skip automatic writes while suspended; let explicit saves retry and recover
+//! - Probe writability up front at startup
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::error::Error;
+use std::sync::RwLock;
+use once_cell::sync::Lazy;
+use crate::prelude::*;
+
+static STATE: Lazy<RwLock<PersistenceState>> = Lazy::new(|| RwLock::new(PersistenceState::Active));
+
+/// # PersistenceState
+///
+/// **Summary:**
+/// Whether automatic writes are being attempted.
+///
+/// **Variants:**
+/// - `Active`: Writes are attempted normally
+/// - `Suspended`: A write failed in a way that will keep failing; automatic
+///   writes are skipped until an explicit save succeeds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PersistenceState {
+    Active,
+    Suspended { reason: String },
+}
+
+/// # blocked_reason
+///
+/// **Purpose:**
+/// Classifies an error as a persistent write failure.
+///
+/// **Returns:**
+/// `Option<&'static str>` - "read-only filesystem" or "permission denied", None for anything else
+pub fn blocked_reason(error: &(dyn Error + 'static)) -> Option<&'static str> {
+    match error.downcast_ref::<io::Error>()?.kind() {
+        io::ErrorKind::ReadOnlyFilesystem => Some("read-only filesystem"),
+        io::ErrorKind::PermissionDenied => Some("permission denied"),
+        _ => None,
+    }
+}
+
+/// # state
+///
+/// **Purpose:**
+/// The current persistence state.
+pub fn state() -> PersistenceState {
+    STATE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Why automatic writes are suspended, if they are
+pub fn suspended_reason() -> Option<String> {
+    match state() {
+        PersistenceState::Active => None,
+        PersistenceState::Suspended { reason } => Some(reason),
+    }
+}
+
+/// # suspend
+///
+/// **Purpose:**
+/// Moves to the suspended state.
+///
+/// **Returns:**
+/// `bool` - true if writes were active until now (the caller should tell the user once)
+pub fn suspend(reason: &str) -> bool {
+    let mut state = STATE.write().unwrap_or_else(|e| e.into_inner());
+    let newly = *state == PersistenceState::Active;
+    *state = PersistenceState::Suspended { reason: reason.to_string() };
+    if newly {
+        log_error!("History persistence suspended: {}", reason);
+    }
+    newly
+}
+
+/// # resume
+///
+/// **Purpose:**
+/// Moves back to the active state after a successful write.
+///
+/// **Returns:**
+/// `bool` - true if writes had been suspended
+pub fn resume() -> bool {
+    let mut state = STATE.write().unwrap_or_else(|e| e.into_inner());
+    let was_suspended = *state != PersistenceState::Active;
+    *state = PersistenceState::Active;
+    if was_suspended {
+        log_info!("History persistence resumed");
+    }
+    was_suspended
+}
+
+/// # auto_write
+///
+/// **Purpose:**
+/// Runs an automatic write unless persistence is suspended.
+///
+/// **Returns:**
+/// `Result<Option<T>, Box<dyn Error>>` - None if the write was skipped
+///
+/// **Details:**
+/// A read-only or permission failure suspends persistence; the error is still
+/// returned that one time so the caller can report it.
+pub fn auto_write<T>(write: impl FnOnce() -> Result<T, Box<dyn Error>>) -> Result<Option<T>, Box<dyn Error>> {
+    if suspended_reason().is_some() {
+        return Ok(None);
+    }
+    match write() {
+        Ok(value) => Ok(Some(value)),
+        Err(e) => {
+            if let Some(reason) = blocked_reason(e.as_ref()) {
+                suspend(reason);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// # explicit_write
+///
+/// **Purpose:**
+/// Runs a write the user asked for, even while suspended.
+///
+/// **Details:**
+/// Success clears the suspended state (e.g. after a remount); a read-only or
+/// permission failure sets it.
+pub fn explicit_write<T>(write: impl FnOnce() -> Result<T, Box<dyn Error>>) -> Result<T, Box<dyn Error>> {
+    match write() {
+        Ok(value) => {
+            resume();
+            Ok(value)
+        }
+        Err(e) => {
+            if let Some(reason) = blocked_reason(e.as_ref()) {
+                suspend(reason);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// # probe_writable
+///
+/// **Purpose:**
+/// Checks that a directory can be written by creating and removing a scratch file.
+///
+/// **Details:**
+/// Suspends persistence when the directory is read-only or not permitted,
+/// so the first exchange doesn't discover it the hard way.
+///
+/// **Errors / Failures:**
+/// - Any I/O error creating the directory or the scratch file
+pub fn probe_writable(dir: &Path) -> io::Result<()> {
+    let probe = dir.join(".write_probe");
+    let result = fs::create_dir_all(dir)
+        .and_then(|_| fs::write(&probe, b""))
+        .and_then(|_| fs::remove_file(&probe));
+
+    if let Some(reason) = result.as_ref().err().and_then(|e| blocked_reason(e)) {
+        suspend(reason);
+    }
+    result
+}
+
+/// # warning
+///
+/// **Purpose:**
+/// The one-line notice shown while suspended.
+pub fn warning() -> Option<String> {
+    suspended_reason().map(|reason| format!("history not being saved: {}", reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_failures_that_will_keep_failing_block_writes() {
+        let cases: [(Box<dyn Error>, Option<&str>); 5] = [
+            (io::Error::from(io::ErrorKind::ReadOnlyFilesystem).into(), Some("read-only filesystem")),
+            (io::Error::from(io::ErrorKind::PermissionDenied).into(), Some("permission denied")),
+            (io::Error::from(io::ErrorKind::NotFound).into(), None),
+            (io::Error::from(io::ErrorKind::StorageFull).into(), None),
+            ("history file is corrupt".into(), None),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(blocked_reason(error.as_ref()), expected, "{}", error);
+        }
+    }
+}
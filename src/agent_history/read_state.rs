@@ -0,0 +1,243 @@
+//! # Daegonica Module: agent_history::read_state
+//!
+//! **Purpose:** Remember how far the user has read each persona's conversation
+//!
+//! **Context:**
+//! - Stored next to the persona history (`read_state.json`) so it follows a synced data dir
+//! - Advanced by the TUI once the user has sat at the bottom of new content for a moment
+//! - Read when an agent opens, to replay what's new under a "new since" divider
+//!
+//! **Responsibilities:**
+//! - Define `ReadMarker` and find it again after summarization or edits shift indices
+//! - Work out the first unread message of a history
+//! - Resolve two devices' markers to the earlier one so nothing is skipped
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use chrono::{DateTime, Utc};
+use crate::prelude::*;
+use crate::utilities::accessibility::glyph;
+use crate::utilities::time::{format_timestamp, Zone};
+
+/// # fingerprint
+///
+/// **Purpose:**
+/// Stable hash of a message's role and content (FNV-1a, identical on every build and device).
+pub fn fingerprint(message: &Message) -> u64 {
+    let bytes = message.role.bytes().chain([0]).chain(message.content.bytes());
+    bytes.fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+/// Whether a message is part of the conversation the user reads (not the prompt or a summary)
+fn is_conversation(message: &Message) -> bool {
+    message.role != "system"
+}
+
+/// # ReadMarker
+///
+/// **Summary:**
+/// The last message the user has read, and where they read it.
+///
+/// **Fields:**
+/// - `position`: Index of that message in `local_history` when it was read
+/// - `fingerprint`: `fingerprint` of that message, used to find it again
+/// - `read_at`: When the marker was set
+/// - `host`: Hostname of the device it was set on
+///
+/// **Details:**
+/// Indices shift when history is summarized or edited, so the marker is found by
+/// fingerprint, taking the match nearest `position`. A marker that can't be
+/// found counts as "nothing read": the message was summarized away or removed,
+/// and every message still in the history is newer than it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadMarker {
+    pub position: usize,
+    pub fingerprint: u64,
+    pub read_at: DateTime<Utc>,
+    pub host: String,
+}
+
+impl ReadMarker {
+    /// # at_end
+    ///
+    /// **Purpose:**
+    /// A marker on the last conversation message of `history`, None if there isn't one.
+    pub fn at_end(history: &[Message], host: &str) -> Option<Self> {
+        let position = history.iter().rposition(is_conversation)?;
+        Some(Self {
+            position,
+            fingerprint: fingerprint(&history[position]),
+            read_at: Utc::now(),
+            host: host.to_string(),
+        })
+    }
+
+    /// Whether both markers point at the same message (ignoring when and where they were set)
+    pub fn same_message(&self, other: &ReadMarker) -> bool {
+        self.position == other.position && self.fingerprint == other.fingerprint
+    }
+
+    /// # locate
+    ///
+    /// **Purpose:**
+    /// Index of the marked message in `history`, None if it's no longer there.
+    pub fn locate(&self, history: &[Message]) -> Option<usize> {
+        history.iter()
+            .enumerate()
+            .filter(|(_, message)| is_conversation(message) && fingerprint(message) == self.fingerprint)
+            .map(|(index, _)| index)
+            .min_by_key(|index| index.abs_diff(self.position))
+    }
+
+    /// # first_unread
+    ///
+    /// **Purpose:**
+    /// Index of the first conversation message after the marker.
+    ///
+    /// **Returns:**
+    /// `Option<usize>` - None when everything has been read
+    pub fn first_unread(&self, history: &[Message]) -> Option<usize> {
+        let start = self.locate(history).map_or(0, |index| index + 1);
+        (start..history.len()).find(|index| is_conversation(&history[*index]))
+    }
+
+    /// The divider line drawn above the first unread message
+    pub fn divider(&self, zone: Zone) -> String {
+        let rule = glyph("──", "--");
+        format!("{} new since {} on {} {}", rule, format_timestamp(self.read_at, zone), self.host, rule)
+    }
+}
+
+/// # resolve_conflict
+///
+/// **Purpose:**
+/// Picks the marker to save when another device may have written one meanwhile.
+///
+/// **Parameters:**
+/// - `loaded`: The marker this device last loaded or saved
+/// - `on_disk`: The marker in the file right now
+/// - `ours`: The marker this device wants to save
+/// - `history`: The current history, to compare positions
+///
+/// **Details:**
+/// If the file still holds what this device last saw, `ours` wins. Otherwise both
+/// devices moved the marker, and the one earlier in the history is kept so no
+/// message is skipped on either. A marker that can't be located is the earliest.
+pub fn resolve_conflict(
+    loaded: Option<&ReadMarker>,
+    on_disk: Option<ReadMarker>,
+    ours: ReadMarker,
+    history: &[Message],
+) -> ReadMarker {
+    match on_disk {
+        Some(theirs) if loaded != Some(&theirs) && theirs.locate(history) < ours.locate(history) => theirs,
+        _ => ours,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: &str) -> Message {
+        Message { role: role.to_string(), content: content.to_string(), meta: None, source: None }
+    }
+
+    fn history(messages: &[(&str, &str)]) -> Vec<Message> {
+        messages.iter().map(|(role, content)| message(role, content)).collect()
+    }
+
+    /// A marker on `history[position]`
+    fn marker_at(history: &[Message], position: usize, host: &str) -> ReadMarker {
+        ReadMarker { position, fingerprint: fingerprint(&history[position]), read_at: Utc::now(), host: host.to_string() }
+    }
+
+    const CHAT: [(&str, &str); 7] = [
+        ("system", "Be brief."),
+        ("user", "one"),
+        ("assistant", "reply"),
+        ("user", "two"),
+        ("assistant", "reply"),
+        ("user", "three"),
+        ("assistant", "done"),
+    ];
+
+    #[test]
+    fn the_end_marker_skips_system_messages() {
+        let mut chat = history(&CHAT);
+        chat.push(message("system", "[Summary of earlier conversation]"));
+
+        assert_eq!(ReadMarker::at_end(&chat, "desk").map(|marker| marker.position), Some(6));
+        assert_eq!(ReadMarker::at_end(&history(&[("system", "Be brief.")]), "desk"), None);
+        assert_eq!(ReadMarker::at_end(&[], "desk"), None);
+    }
+
+    #[test]
+    fn the_first_unread_follows_the_marker() {
+        let chat = history(&CHAT);
+        let cases = [
+            ("after the first question", 1, Some(2)),
+            ("a repeated reply is told apart by position", 4, Some(5)),
+            ("everything read", 6, None),
+        ];
+
+        for (case, position, expected) in cases {
+            assert_eq!(marker_at(&chat, position, "desk").first_unread(&chat), expected, "{}", case);
+        }
+    }
+
+    #[test]
+    fn the_marker_is_found_again_after_summarization_shifts_indices() {
+        let chat = history(&CHAT);
+        let read = marker_at(&chat, 4, "desk");
+        let summarized = history(&[
+            ("system", "Be brief."),
+            ("system", "[Summary of earlier conversation]\n- one, reply"),
+            ("user", "two"),
+            ("assistant", "reply"),
+            ("user", "three"),
+            ("assistant", "done"),
+        ]);
+
+        assert_eq!(read.locate(&summarized), Some(3), "the nearest matching reply, not the first");
+        assert_eq!(read.first_unread(&summarized), Some(4));
+
+        let summarized_past_it = history(&[("system", "Be brief."), ("system", "[Summary]"), ("user", "three"), ("assistant", "done")]);
+        assert_eq!(read.locate(&summarized_past_it), None);
+        assert_eq!(read.first_unread(&summarized_past_it), Some(2), "a marker summarized away means nothing was read");
+    }
+
+    #[test]
+    fn conflicting_markers_resolve_to_the_earlier_one() {
+        let chat = history(&CHAT);
+        let loaded = marker_at(&chat, 2, "desk");
+        let gone = ReadMarker { fingerprint: 0, ..marker_at(&chat, 2, "laptop") };
+        let cases = [
+            ("nothing on disk", None, 6, 6),
+            ("the file is what we loaded", Some(loaded.clone()), 6, 6),
+            ("the other device read less", Some(marker_at(&chat, 4, "laptop")), 6, 4),
+            ("the other device read more", Some(marker_at(&chat, 6, "laptop")), 4, 4),
+            ("the other marker is gone", Some(gone), 6, 2),
+        ];
+
+        for (case, on_disk, ours, expected) in cases {
+            let resolved = resolve_conflict(Some(&loaded), on_disk, marker_at(&chat, ours, "desk"), &chat);
+            assert_eq!(resolved.position, expected, "{}", case);
+        }
+    }
+
+    #[test]
+    fn the_divider_names_the_host() {
+        let chat = history(&CHAT);
+        let divider = marker_at(&chat, 2, "laptop").divider(Zone::Local);
+
+        assert!(divider.starts_with("── new since "), "{}", divider);
+        assert!(divider.ends_with(" on laptop ──"), "{}", divider);
+    }
+}
@@ -0,0 +1,240 @@
+//! # Daegonica Module: agent_history::redaction
+//!
+//! **Purpose:** Keep content matching a persona's `ephemeral_patterns` off disk
+//!
+//! **Context:**
+//! - Applied on the write path only: history saves, full archives, and `history remove` recovery files
+//! - The summarizer gets the redacted form too, so a summary can't carry the content to disk
+//! - The in-memory conversation is never changed; the model keeps seeing the original for the rest of the session
+//!
+//! **Responsibilities:**
+//! - Define `EphemeralPattern` (a regex plus whether it redacts the match or the whole message)
+//! - Validate patterns when the persona loads
+//! - Produce the stored form of a message or text, and say whether it differs
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::borrow::Cow;
+use regex::Regex;
+use crate::prelude::*;
+use crate::utilities::accessibility::glyph;
+
+/// Stored in place of each matching span
+pub const REDACTED_SPAN: &str = "[redacted]";
+/// Stored in place of a whole message matched by a `message`-scoped pattern
+pub const REDACTED_MESSAGE: &str = "[message not stored]";
+
+/// Badge for a pane line or `history list` entry that is stored redacted
+pub fn redacted_badge() -> &'static str {
+    glyph("◌", "(stored redacted)")
+}
+
+/// # RedactScope
+///
+/// **Summary:**
+/// How much of a matching message is left out of what's stored.
+///
+/// **Variants:**
+/// - `Span`: Only the matched text is replaced
+/// - `Message`: The whole message is replaced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedactScope {
+    #[default]
+    Span,
+    Message,
+}
+
+/// # EphemeralPattern
+///
+/// **Summary:**
+/// One persona `ephemeral_patterns` entry.
+///
+/// **Fields:**
+/// - `pattern`: Regex searched for anywhere in a message
+/// - `scope`: Redact just the match (default) or the whole message
+///
+/// **Details:**
+/// In YAML an entry is either a bare regex (span scope) or a map:
+/// ```yaml
+/// ephemeral_patterns:
+///   - '\$\d[\d,]*'
+///   - { pattern: '(?i)\bsalary\b', scope: message }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "PatternSpec")]
+pub struct EphemeralPattern {
+    pub pattern: String,
+    pub scope: RedactScope,
+}
+
+/// The accepted YAML shapes of an `EphemeralPattern`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PatternSpec {
+    Bare(String),
+    Scoped {
+        pattern: String,
+        #[serde(default)]
+        scope: RedactScope,
+    },
+}
+
+impl From<PatternSpec> for EphemeralPattern {
+    fn from(spec: PatternSpec) -> Self {
+        match spec {
+            PatternSpec::Bare(pattern) => Self { pattern, scope: RedactScope::Span },
+            PatternSpec::Scoped { pattern, scope } => Self { pattern, scope },
+        }
+    }
+}
+
+/// # compile_pattern
+///
+/// **Purpose:**
+/// Compiles one ephemeral pattern.
+///
+/// **Errors / Failures:**
+/// - Not a valid regex
+/// - Matches the empty string (it would redact every message)
+pub fn compile_pattern(pattern: &str) -> Result<Regex, String> {
+    let regex = Regex::new(pattern).map_err(|e| format!("ephemeral pattern \"{}\" is not a valid regex: {}", pattern, e))?;
+    if regex.is_match("") {
+        return Err(format!("ephemeral pattern \"{}\" matches empty text", pattern));
+    }
+    Ok(regex)
+}
+
+/// # Redactor
+///
+/// **Summary:**
+/// A persona's compiled ephemeral patterns.
+///
+/// **Details:**
+/// Built per use, like `StripRules`; a persona without patterns gives an
+/// empty redactor that borrows every text back unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    rules: Vec<(Regex, RedactScope)>,
+}
+
+impl Redactor {
+    /// # for_persona
+    ///
+    /// **Purpose:**
+    /// Compiles `persona.ephemeral_patterns` (validated when the persona loaded;
+    /// one that still fails is logged and skipped).
+    pub fn for_persona(persona: &Persona) -> Self {
+        let rules = persona.ephemeral_patterns.iter()
+            .filter_map(|entry| match compile_pattern(&entry.pattern) {
+                Ok(regex) => Some((regex, entry.scope)),
+                Err(e) => {
+                    log_error!("Persona {}: {}", persona.name, e);
+                    None
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether `text` would be stored redacted
+    pub fn matches(&self, text: &str) -> bool {
+        self.rules.iter().any(|(regex, _)| regex.is_match(text))
+    }
+
+    /// # redact_text
+    ///
+    /// **Purpose:**
+    /// The stored form of `text`.
+    ///
+    /// **Returns:**
+    /// `Cow<str>` - Borrowed when nothing matched
+    ///
+    /// **Details:**
+    /// A matching `message`-scoped pattern replaces the whole text; otherwise
+    /// each span pattern's matches are replaced in turn.
+    pub fn redact_text<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.rules.iter().any(|(regex, scope)| *scope == RedactScope::Message && regex.is_match(text)) {
+            return Cow::Borrowed(REDACTED_MESSAGE);
+        }
+        let mut redacted = Cow::Borrowed(text);
+        for (regex, _) in &self.rules {
+            if let Cow::Owned(replaced) = regex.replace_all(&redacted, REDACTED_SPAN) {
+                redacted = Cow::Owned(replaced);
+            }
+        }
+        redacted
+    }
+
+    /// The stored form of one message (role, metadata, and source kept)
+    pub fn redact_message(&self, message: &Message) -> Message {
+        Message { content: self.redact_text(&message.content).into_owned(), ..message.clone() }
+    }
+
+    /// The stored form of every message, in order
+    pub fn redact_messages(&self, messages: &[Message]) -> Vec<Message> {
+        messages.iter().map(|message| self.redact_message(message)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redactor(yaml_patterns: &str) -> Redactor {
+        let yaml = format!("name: Private\nsystem_prompt: Talk.\nephemeral_patterns:\n{}", yaml_patterns);
+        Redactor::for_persona(&Persona::from_yaml_str(&yaml).unwrap())
+    }
+
+    #[test]
+    fn entries_are_bare_regexes_or_scoped_maps() {
+        let yaml = "name: Private\nsystem_prompt: Talk.\nephemeral_patterns:\n  - '\\$\\d+'\n  - { pattern: salary, scope: message }\n  - { pattern: Ana }\n";
+        let persona = Persona::from_yaml_str(yaml).unwrap();
+        let scopes: Vec<(&str, RedactScope)> = persona.ephemeral_patterns.iter().map(|entry| (entry.pattern.as_str(), entry.scope)).collect();
+        assert_eq!(scopes, [("\\$\\d+", RedactScope::Span), ("salary", RedactScope::Message), ("Ana", RedactScope::Span)]);
+    }
+
+    #[test]
+    fn spans_and_whole_messages_are_redacted() {
+        let redactor = redactor("  - '\\$\\d[\\d,]*'\n  - '(?i)\\bana\\b'\n  - { pattern: '(?i)\\bsalary\\b', scope: message }\n");
+        let cases = [
+            ("every match of every span pattern", "Ana said $120,000 or $95,000.", "[redacted] said [redacted] or [redacted]."),
+            ("a message pattern takes the whole text", "My salary is $120,000, Ana knows.", REDACTED_MESSAGE),
+            ("nothing matched", "Lunch at noon?", "Lunch at noon?"),
+            ("word boundaries hold", "Banana bread.", "Banana bread."),
+        ];
+        for (name, text, stored) in cases {
+            assert_eq!(redactor.redact_text(text), stored, "{}", name);
+            assert_eq!(redactor.matches(text), text != stored, "{}", name);
+        }
+        assert!(matches!(redactor.redact_text("Lunch at noon?"), Cow::Borrowed(_)), "unmatched text isn't copied");
+    }
+
+    #[test]
+    fn a_redacted_message_keeps_everything_but_its_content() {
+        let redactor = redactor("  - Ana\n");
+        let message = Message { role: "user".into(), content: "Ana called.".into(), meta: None, source: Some("paste".into()) };
+        let stored = redactor.redact_message(&message);
+        assert_eq!(stored.content, "[redacted] called.");
+        assert_eq!((stored.role.as_str(), stored.source.as_deref()), ("user", Some("paste")));
+        assert_eq!(message.content, "Ana called.", "the original is left as it was");
+    }
+
+    #[test]
+    fn a_persona_without_patterns_stores_everything() {
+        let redactor = Redactor::for_persona(&Persona::from_yaml_str("name: Open\nsystem_prompt: Talk.\n").unwrap());
+        assert!(redactor.is_empty());
+        assert!(!redactor.matches("My salary is $120,000."));
+        assert_eq!(redactor.redact_text("My salary is $120,000."), "My salary is $120,000.");
+    }
+}
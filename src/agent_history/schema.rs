@@ -0,0 +1,181 @@
+//! # Daegonica Module: agent_history::schema
+//!
+//! **Purpose:** Versioned on-disk format for persona history files
+//!
+//! **Context:**
+//! - `ConversationHistory` keeps gaining fields; files written by older builds must still load
+//! - Used by `HistoryManager` on every load and save of `{persona}_history.json`
+//!
+//! **Responsibilities:**
+//! - Define the current format version and what each version looks like
+//! - Detect a file's version (absent = v1) and migrate it forward step by step
+//! - Refuse files written by a newer build with a clear message
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use serde_json::{Map, Value};
+use thiserror::Error;
+use crate::prelude::*;
+
+/// Version written by this build
+///
+/// - v1: unversioned; `experiment`, `started_at`, and sometimes
+///   `summarization_count` / `total_message_count` may be missing
+/// - v2: `format_version` present; summary and counts always written
+/// - v3: `long_term_summary` added (null until the first fold); older builds
+///   would drop it on save, so they must refuse the file
+/// - v4: `last_exchange_at`, `last_response_id` and `last_response_at` added
+///   (omitted until set); older builds would drop them on save
+///
+/// A new field in `ConversationHistory` needs a new version, a migration and a
+/// fixture in `tests/fixtures/history/`; `tests/history_schema.rs` fails until it has them.
+pub const CURRENT_HISTORY_VERSION: u32 = 4;
+
+/// # HistorySchemaError
+///
+/// **Summary:**
+/// Why a history file couldn't be brought to the current format.
+#[derive(Debug, Error)]
+pub enum HistorySchemaError {
+    #[error("this history was written by a newer build (format v{found}, this build reads up to v{supported}); update before opening it")]
+    NewerVersion { found: u32, supported: u32 },
+
+    #[error("history file is not valid JSON: {0}")]
+    InvalidJson(String),
+
+    #[error("history file doesn't match format v{version}: {reason}")]
+    InvalidShape { version: u32, reason: String },
+}
+
+/// One forward step: migrates a document from version `n` to `n + 1`
+type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+/// Ordered migrations; index 0 takes v1 to v2
+const MIGRATIONS: &[Migration] = &[migrate_v1_to_v2, migrate_v2_to_v3, migrate_v3_to_v4];
+
+/// v1 files relied on loader defaults; write them out explicitly
+fn migrate_v1_to_v2(doc: &mut Map<String, Value>) -> Result<(), String> {
+    let recent = doc.get("recent_messages")
+        .and_then(Value::as_array)
+        .map(Vec::len)
+        .ok_or("missing recent_messages")?;
+
+    doc.entry("summary").or_insert(Value::Null);
+    doc.entry("total_message_count").or_insert(Value::from(recent));
+    doc.entry("summarization_count").or_insert(Value::from(0));
+    doc.entry("experiment").or_insert(Value::Null);
+    doc.entry("started_at").or_insert(Value::Null);
+    if !doc.contains_key("last_updated") {
+        doc.insert("last_updated".to_string(), Value::from(chrono::Utc::now().to_rfc3339()));
+    }
+    Ok(())
+}
+
+/// v2 files had a single summary tier; it stays the recent one
+fn migrate_v2_to_v3(doc: &mut Map<String, Value>) -> Result<(), String> {
+    doc.entry("long_term_summary").or_insert(Value::Null);
+    Ok(())
+}
+
+/// v3 files never recorded the last exchange or response; those stay unset until the next reply
+fn migrate_v3_to_v4(_doc: &mut Map<String, Value>) -> Result<(), String> {
+    Ok(())
+}
+
+/// # detect_version
+///
+/// **Purpose:**
+/// Reads `format_version` from a raw document (absent means v1).
+pub fn detect_version(doc: &Map<String, Value>) -> Result<u32, HistorySchemaError> {
+    match doc.get("format_version") {
+        None | Some(Value::Null) => Ok(1),
+        Some(value) => value.as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v >= 1)
+            .ok_or_else(|| HistorySchemaError::InvalidShape {
+                version: 0,
+                reason: format!("format_version {} is not a positive integer", value),
+            }),
+    }
+}
+
+/// # migrate
+///
+/// **Purpose:**
+/// Brings a raw history document up to `CURRENT_HISTORY_VERSION`.
+///
+/// **Returns:**
+/// `Result<Value, HistorySchemaError>` - The migrated document, stamped with the current version
+///
+/// **Errors / Failures:**
+/// - The file is from a newer build
+/// - A migration step finds the document malformed
+pub fn migrate(value: Value) -> Result<Value, HistorySchemaError> {
+    let Value::Object(mut doc) = value else {
+        return Err(HistorySchemaError::InvalidShape {
+            version: 1,
+            reason: "expected a JSON object".to_string(),
+        });
+    };
+
+    let found = detect_version(&doc)?;
+    if found > CURRENT_HISTORY_VERSION {
+        return Err(HistorySchemaError::NewerVersion { found, supported: CURRENT_HISTORY_VERSION });
+    }
+
+    for (index, step) in MIGRATIONS.iter().enumerate().skip(found as usize - 1) {
+        let version = index as u32 + 1;
+        step(&mut doc).map_err(|reason| HistorySchemaError::InvalidShape { version, reason })?;
+        log_info!("Migrated history format v{} -> v{}", version, version + 1);
+    }
+
+    doc.insert("format_version".to_string(), Value::from(CURRENT_HISTORY_VERSION));
+    Ok(Value::Object(doc))
+}
+
+/// # stored_version
+///
+/// **Purpose:**
+/// Reads the format version of a history file already on disk.
+///
+/// **Returns:**
+/// `Option<u32>` - None if the file is missing or unreadable
+pub fn stored_version(path: &Path) -> Option<u32> {
+    let content = fs::read_to_string(path).ok()?;
+    match serde_json::from_str::<Value>(&content).ok()? {
+        Value::Object(doc) => detect_version(&doc).ok(),
+        _ => None,
+    }
+}
+
+/// # parse_history
+///
+/// **Purpose:**
+/// Parses a history file of any supported version into the current typed struct.
+///
+/// **Examples:**
+/// ```no_run
+/// # use std::fs;
+/// # use grokprime_brain::agent_history::schema::{parse_history, CURRENT_HISTORY_VERSION};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let path = "shadow_history.json";
+/// let history = parse_history(&fs::read_to_string(path)?)?;
+/// assert_eq!(history.format_version, CURRENT_HISTORY_VERSION);
+/// # Ok(())
+/// # }
+/// ```
+pub fn parse_history(content: &str) -> Result<ConversationHistory, HistorySchemaError> {
+    let raw: Value = serde_json::from_str(content)
+        .map_err(|e| HistorySchemaError::InvalidJson(e.to_string()))?;
+    let migrated = migrate(raw)?;
+    serde_json::from_value(migrated).map_err(|e| HistorySchemaError::InvalidShape {
+        version: CURRENT_HISTORY_VERSION,
+        reason: e.to_string(),
+    })
+}
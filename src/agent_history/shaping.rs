@@ -0,0 +1,404 @@
+//! # Daegonica Module: agent_history::shaping
+//!
+//! **Purpose:** Keep pasted blobs out of what the historian is asked to summarize
+//!
+//! **Context:**
+//! - Used by `Connection::summarize_history` when building the transcript for the historian
+//! - Only the text sent for summarization is shaped; history and archives keep every message as-is
+//!
+//! **Responsibilities:**
+//! - Guess what a message body is (prose, code, logs, base64, hex, dense data)
+//! - Replace oversized or non-prose bodies with a short placeholder
+//! - Tally what was left out so the user knows the summary's blind spots
+//! - Split a transcript that is still too long into chunks for sequential passes
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+/// Below this many characters a message is always kept; short hashes and IDs are fine
+const MIN_SHAPED_CHARS: usize = 256;
+/// Logs longer than this many lines are left out even under the size limit
+const MAX_LOG_LINES: usize = 40;
+/// Share of whitespace under which a long body is treated as dense data
+const MIN_WHITESPACE_RATIO: f64 = 0.04;
+
+/// # ContentKind
+///
+/// **Summary:**
+/// Best guess at what a message body is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ContentKind {
+    Prose,
+    Code,
+    BuildLog,
+    Log,
+    Base64,
+    Hex,
+    Dense,
+}
+
+impl ContentKind {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            ContentKind::Prose => "prose",
+            ContentKind::Code => "code",
+            ContentKind::BuildLog => "build log",
+            ContentKind::Log => "log output",
+            ContentKind::Base64 => "base64 data",
+            ContentKind::Hex => "hex dump",
+            ContentKind::Dense => "dense data",
+        }
+    }
+}
+
+fn is_build_line(line: &str) -> bool {
+    let line = line.trim_start();
+    ["Compiling ", "Checking ", "Finished ", "Running ", "Downloaded ", "warning:", "error:", "error[", "-->", "= note:", "= help:", "npm ", "make["]
+        .iter()
+        .any(|marker| line.starts_with(marker))
+}
+
+fn is_log_line(line: &str) -> bool {
+    let line = line.trim_start();
+    // Timestamp-led lines: "2026-01-21 ...", "12:03:44 ...", "[2026-...", "[12:03]"
+    let stamp = line.trim_start_matches('[');
+    let leading_digits = stamp.chars().take_while(|c| c.is_ascii_digit()).count();
+    let timestamped = leading_digits >= 2 && stamp[leading_digits..].starts_with(['-', ':', '/', '.']);
+    timestamped || ["INFO", "WARN", "ERROR", "DEBUG", "TRACE"].iter().any(|level| {
+        line.starts_with(level) || line.contains(&format!("[{}]", level)) || line.contains(&format!(" {} ", level))
+    })
+}
+
+fn is_code_line(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.ends_with([';', '{', '}', ')', ',', ':'])
+        || ["fn ", "let ", "pub ", "use ", "impl ", "def ", "class ", "import ", "return ", "#include", "const ", "//", "#["]
+            .iter()
+            .any(|keyword| trimmed.starts_with(keyword))
+}
+
+/// Compact body of only hex digits (separators and `0x` prefixes aside)
+fn looks_hex(compact: &str) -> bool {
+    let digits: String = compact.replace("0x", "").chars().filter(|c| !matches!(c, ':' | '-' | ',')).collect();
+    digits.len() >= 64 && digits.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Compact body in the base64 alphabet with the character mix real encodings have
+fn looks_base64(text: &str, compact: &str) -> bool {
+    let longest_token = text.split_whitespace().map(str::len).max().unwrap_or(0);
+    longest_token >= 64
+        && compact.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=' | '-' | '_'))
+        && compact.chars().any(|c| c.is_ascii_uppercase())
+        && compact.chars().any(|c| c.is_ascii_lowercase())
+        && compact.chars().any(|c| c.is_ascii_digit())
+}
+
+/// Share of whitespace characters in `text`
+pub fn whitespace_ratio(text: &str) -> f64 {
+    let total = text.chars().count();
+    if total == 0 {
+        return 1.0;
+    }
+    text.chars().filter(|c| c.is_whitespace()).count() as f64 / total as f64
+}
+
+/// # classify
+///
+/// **Purpose:**
+/// Guesses what a message body is.
+///
+/// **Details:**
+/// Encodings are checked first (hex before base64, since hex digits are
+/// base64 too), then line shapes: a quarter of the lines looking like
+/// compiler output makes a build log, half timestamped or levelled lines make
+/// a log, a third ending like statements make code. Anything long with almost
+/// no whitespace is dense data; everything else is prose.
+pub fn classify(text: &str) -> ContentKind {
+    let text = text.trim();
+    if text.chars().count() < 64 {
+        return ContentKind::Prose;
+    }
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if looks_hex(&compact) {
+        return ContentKind::Hex;
+    }
+    if looks_base64(text, &compact) {
+        return ContentKind::Base64;
+    }
+
+    let lines: Vec<&str> = text.lines().filter(|line| !line.trim().is_empty()).collect();
+    if lines.len() >= 5 {
+        let share = |test: fn(&str) -> bool| lines.iter().filter(|line| test(line)).count() as f64 / lines.len() as f64;
+        if share(is_build_line) >= 0.25 {
+            return ContentKind::BuildLog;
+        }
+        if share(is_log_line) >= 0.5 {
+            return ContentKind::Log;
+        }
+        if share(is_code_line) >= 0.34 {
+            return ContentKind::Code;
+        }
+    }
+    if whitespace_ratio(text) < MIN_WHITESPACE_RATIO {
+        return ContentKind::Dense;
+    }
+    ContentKind::Prose
+}
+
+/// # Elision
+///
+/// **Summary:**
+/// A message body left out of the summarization transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elision {
+    pub kind: ContentKind,
+    pub lines: usize,
+    pub chars: usize,
+}
+
+impl Elision {
+    /// Stand-in text, e.g. "[large pasted content: 1842 lines, looks like build log]"
+    pub fn placeholder(&self) -> String {
+        format!("[large pasted content: {} lines, looks like {}]", self.lines, self.kind.describe())
+    }
+}
+
+/// # shape_message
+///
+/// **Purpose:**
+/// Decides whether a message body goes to the historian as-is.
+///
+/// **Parameters:**
+/// - `content`: The message body
+/// - `max_chars`: Longest body sent as-is, whatever it contains
+///
+/// **Returns:**
+/// `Result<&str, Elision>` - The body to send, or what was left out
+///
+/// **Details:**
+/// Bodies under a few hundred characters are always kept. Longer ones are
+/// left out when over `max_chars`, when they look like base64, hex, or dense
+/// data, or when they are logs of more than a screenful of lines. Code and
+/// prose under the limit are kept: they usually carry the decisions worth summarizing.
+pub fn shape_message(content: &str, max_chars: usize) -> Result<&str, Elision> {
+    let chars = content.chars().count();
+    if chars < MIN_SHAPED_CHARS {
+        return Ok(content);
+    }
+    let kind = classify(content);
+    let lines = content.lines().count();
+    let elide = chars > max_chars
+        || matches!(kind, ContentKind::Base64 | ContentKind::Hex | ContentKind::Dense)
+        || (matches!(kind, ContentKind::Log | ContentKind::BuildLog) && lines > MAX_LOG_LINES);
+    if elide { Err(Elision { kind, lines, chars }) } else { Ok(content) }
+}
+
+/// # ElisionReport
+///
+/// **Summary:**
+/// Everything left out of one summarization.
+///
+/// **Fields:**
+/// - `blocks`: Messages replaced by a placeholder
+/// - `lines`: Lines they held
+/// - `chars`: Characters they held
+/// - `kinds`: What they looked like, without repeats
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ElisionReport {
+    pub blocks: usize,
+    pub lines: usize,
+    pub chars: usize,
+    pub kinds: Vec<ContentKind>,
+}
+
+impl ElisionReport {
+    pub fn add(&mut self, elision: &Elision) {
+        self.blocks += 1;
+        self.lines += elision.lines;
+        self.chars += elision.chars;
+        if !self.kinds.contains(&elision.kind) {
+            self.kinds.push(elision.kind);
+        }
+    }
+
+    /// # describe
+    ///
+    /// **Purpose:**
+    /// One line for the user, e.g. "Not summarized: 2 pasted blocks (1900 lines, 96.3 KB; build log, base64 data)".
+    ///
+    /// **Returns:**
+    /// `Option<String>` - None when nothing was left out
+    pub fn describe(&self) -> Option<String> {
+        if self.blocks == 0 {
+            return None;
+        }
+        let kinds: Vec<&str> = self.kinds.iter().map(ContentKind::describe).collect();
+        Some(format!(
+            "Not summarized: {} pasted block{} ({} lines, {:.1} KB; {}). History still has them in full.",
+            self.blocks,
+            if self.blocks == 1 { "" } else { "s" },
+            self.lines,
+            self.chars as f64 / 1024.0,
+            kinds.join(", "),
+        ))
+    }
+}
+
+/// # chunk_transcript
+///
+/// **Purpose:**
+/// Packs transcript entries, in order, into chunks of at most `max_chars` each.
+///
+/// **Returns:**
+/// `Vec<String>` - Entries joined by blank lines; one chunk when everything fits.
+/// An entry longer than `max_chars` gets a chunk to itself rather than being split.
+pub fn chunk_transcript(entries: &[String], max_chars: usize) -> Vec<String> {
+    const SEPARATOR: &str = "\n\n";
+    let mut chunks: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for entry in entries {
+        if !current.is_empty() && current.len() + SEPARATOR.len() + entry.len() > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push_str(SEPARATOR);
+        }
+        current.push_str(entry);
+    }
+    if !current.is_empty() || chunks.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prose() -> String {
+        "We went over the release plan again this morning. Ana wants the migration to land before \
+         the freeze, and Ben thinks the docs can wait a week. I said I'd check with the support team \
+         whether the old export format still matters to anyone, since dropping it would simplify things."
+            .to_string()
+    }
+
+    fn code() -> String {
+        "use std::collections::HashMap;\n\
+         \n\
+         pub fn tally(words: &[&str]) -> HashMap<&str, usize> {\n\
+         \x20   let mut counts = HashMap::new();\n\
+         \x20   for word in words {\n\
+         \x20       *counts.entry(*word).or_insert(0) += 1;\n\
+         \x20   }\n\
+         \x20   counts\n\
+         }\n"
+            .to_string()
+    }
+
+    fn build_log(lines: usize) -> String {
+        (0..lines).map(|i| match i % 3 {
+            0 => format!("   Compiling crate-{} v0.{}.0", i, i),
+            1 => format!("warning: unused variable `x{}`", i),
+            _ => format!("  --> src/lib.rs:{}:9", i),
+        }).collect::<Vec<_>>().join("\n")
+    }
+
+    fn log(lines: usize) -> String {
+        (0..lines).map(|i| format!("2026-01-21 12:{:02}:03 INFO request {} served in {}ms", i % 60, i, i * 3)).collect::<Vec<_>>().join("\n")
+    }
+
+    fn base64() -> String {
+        let line = "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsuIFRoZSBxdWljayBicm93biBmb3gganVtcHMgb3ZlcjEyMw==";
+        [line; 8].join("\n")
+    }
+
+    fn hex() -> String {
+        ["deadbeef00112233445566778899aabbccddeeff0123456789abcdef"; 6].join(" ")
+    }
+
+    #[test]
+    fn classify_recognizes_each_kind() {
+        let cases = [
+            ("prose", prose(), ContentKind::Prose),
+            ("code", code(), ContentKind::Code),
+            ("build log", build_log(12), ContentKind::BuildLog),
+            ("timestamped log", log(12), ContentKind::Log),
+            ("base64", base64(), ContentKind::Base64),
+            ("hex", hex(), ContentKind::Hex),
+            ("dense", "x=1;y=2;".repeat(40), ContentKind::Dense),
+            ("short hash", "see commit 9f2c4e1a7b3d for the fix".to_string(), ContentKind::Prose),
+            ("lowercase only", "abcdefgh".repeat(20), ContentKind::Dense),
+        ];
+        for (name, text, expected) in cases {
+            assert_eq!(classify(&text), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn shape_message_keeps_what_is_worth_summarizing() {
+        let long_prose = format!("{} ", prose()).repeat(30);
+        let cases: [(&str, String, Option<ContentKind>); 8] = [
+            ("short data is always kept", "aGVsbG8gd29ybGQ=".repeat(10), None),
+            ("prose under the limit", prose(), None),
+            ("code under the limit", code().repeat(10), None),
+            ("a screenful of log", log(30), None),
+            ("a long log", log(41), Some(ContentKind::Log)),
+            ("a long build log", build_log(60), Some(ContentKind::BuildLog)),
+            ("base64 of any length", base64(), Some(ContentKind::Base64)),
+            ("prose over the limit", long_prose, Some(ContentKind::Prose)),
+        ];
+        for (name, text, elided) in cases {
+            match (shape_message(&text, 6000), elided) {
+                (Ok(kept), None) => assert_eq!(kept, text, "{}", name),
+                (Err(elision), Some(kind)) => {
+                    assert_eq!(elision.kind, kind, "{}", name);
+                    assert_eq!(elision.lines, text.lines().count(), "{}", name);
+                    assert_eq!(elision.chars, text.chars().count(), "{}", name);
+                }
+                (result, expected) => panic!("{}: got {:?}, expected elision {:?}", name, result.map(str::len), expected),
+            }
+        }
+    }
+
+    #[test]
+    fn placeholders_and_the_report_name_what_was_left_out() {
+        let build = Elision { kind: ContentKind::BuildLog, lines: 1842, chars: 90_000 };
+        let blob = Elision { kind: ContentKind::Base64, lines: 58, chars: 8_600 };
+        assert_eq!(build.placeholder(), "[large pasted content: 1842 lines, looks like build log]");
+
+        let mut report = ElisionReport::default();
+        assert_eq!(report.describe(), None);
+        report.add(&build);
+        assert_eq!(
+            report.describe().as_deref(),
+            Some("Not summarized: 1 pasted block (1842 lines, 87.9 KB; build log). History still has them in full.")
+        );
+        report.add(&blob);
+        report.add(&build);
+        assert_eq!(report.kinds, [ContentKind::BuildLog, ContentKind::Base64], "kinds are listed once");
+        assert_eq!(
+            report.describe().as_deref(),
+            Some("Not summarized: 3 pasted blocks (3742 lines, 184.2 KB; build log, base64 data). History still has them in full.")
+        );
+    }
+
+    #[test]
+    fn chunk_transcript_packs_entries_in_order() {
+        let entries: Vec<String> = ["aaaa", "bbbb", "cccc", "dddddddddddd", "ee"].iter().map(|s| s.to_string()).collect();
+        let cases: [(&str, usize, &[&str]); 4] = [
+            ("everything fits", 100, &["aaaa\n\nbbbb\n\ncccc\n\ndddddddddddd\n\nee"]),
+            ("the separator counts", 10, &["aaaa\n\nbbbb", "cccc", "dddddddddddd", "ee"]),
+            ("an oversized entry gets its own chunk", 8, &["aaaa", "bbbb", "cccc", "dddddddddddd", "ee"]),
+            ("exactly at the limit", 16, &["aaaa\n\nbbbb\n\ncccc", "dddddddddddd\n\nee"]),
+        ];
+        for (name, max_chars, expected) in cases {
+            assert_eq!(chunk_transcript(&entries, max_chars), expected, "{}", name);
+        }
+        assert_eq!(chunk_transcript(&[], 100), [""], "an empty transcript is still one (empty) pass");
+    }
+}
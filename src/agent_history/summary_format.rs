@@ -0,0 +1,120 @@
+//! # Daegonica Module: agent_history::summary_format
+//!
+//! **Purpose:** Check that a historian summary follows its four-section schema
+//!
+//! **Context:**
+//! - The historian persona is told to reply with Topics / Decisions / Context / Status,
+//!   each label once, in order, with "none" for an empty section
+//! - A summary replaces the messages it covers, so a malformed one is worth flagging
+//! - Used by `Connection::summarize_history`, which keeps the summary either way
+//!
+//! **Responsibilities:**
+//! - Split a summary into its sections
+//! - Say which rule a summary breaks when it doesn't parse
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use thiserror::Error;
+
+/// Section labels, in the order the historian must write them
+pub const SUMMARY_SECTIONS: [&str; 4] = ["Topics", "Decisions", "Context", "Status"];
+
+/// # SummarySections
+///
+/// **Summary:**
+/// A summary split into its four sections (continuation lines joined with newlines).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummarySections {
+    pub topics: String,
+    pub decisions: String,
+    pub context: String,
+    pub status: String,
+}
+
+/// # SummaryFormatError
+///
+/// **Summary:**
+/// The first schema rule a summary breaks.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SummaryFormatError {
+    #[error("text before the first section: \"{0}\"")]
+    Preamble(String),
+
+    #[error("no {0} section")]
+    Missing(&'static str),
+
+    #[error("{0} appears more than once")]
+    Repeated(&'static str),
+
+    #[error("{0} is out of order")]
+    OutOfOrder(&'static str),
+
+    #[error("{0} is empty (expected \"none\")")]
+    Empty(&'static str),
+}
+
+impl SummarySections {
+    /// # parse
+    ///
+    /// **Purpose:**
+    /// Reads a summary written to the historian's schema.
+    ///
+    /// **Returns:**
+    /// `Result<SummarySections, SummaryFormatError>` - The sections, or the first rule broken
+    ///
+    /// **Details:**
+    /// A section starts with its label and a colon at the start of a line,
+    /// case-insensitively and after any leading `-` / `*` bullet. Lines up to
+    /// the next label belong to it, so bullet or prose styles inside a section
+    /// still parse. Blank lines are ignored.
+    pub fn parse(summary: &str) -> Result<SummarySections, SummaryFormatError> {
+        let mut sections: [Option<Vec<String>>; 4] = Default::default();
+        let mut current: Option<usize> = None;
+
+        for line in summary.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if let Some((index, rest)) = section_start(line) {
+                let label = SUMMARY_SECTIONS[index];
+                if sections[index].is_some() {
+                    return Err(SummaryFormatError::Repeated(label));
+                }
+                if current.is_some_and(|previous| previous > index) {
+                    return Err(SummaryFormatError::OutOfOrder(label));
+                }
+                sections[index] = Some((!rest.is_empty()).then(|| rest.to_string()).into_iter().collect());
+                current = Some(index);
+                continue;
+            }
+            match current.and_then(|index| sections[index].as_mut()) {
+                Some(lines) => lines.push(line.to_string()),
+                None => return Err(SummaryFormatError::Preamble(line.to_string())),
+            }
+        }
+
+        let mut texts = Vec::with_capacity(SUMMARY_SECTIONS.len());
+        for (label, section) in SUMMARY_SECTIONS.iter().zip(sections) {
+            let lines = section.ok_or(SummaryFormatError::Missing(label))?;
+            if lines.is_empty() {
+                return Err(SummaryFormatError::Empty(label));
+            }
+            texts.push(lines.join("\n"));
+        }
+        let [topics, decisions, context, status]: [String; 4] = texts.try_into().expect("four sections");
+        Ok(SummarySections { topics, decisions, context, status })
+    }
+}
+
+/// The section a line opens, and the text after its label
+fn section_start(line: &str) -> Option<(usize, &str)> {
+    let line = line.trim_start_matches(['-', '*']).trim_start();
+    SUMMARY_SECTIONS.iter().enumerate().find_map(|(index, label)| {
+        let head = line.get(..label.len())?;
+        let rest = line[label.len()..].strip_prefix(':')?;
+        head.eq_ignore_ascii_case(label).then_some((index, rest.trim()))
+    })
+}
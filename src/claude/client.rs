@@ -9,8 +9,8 @@
 //! **Responsibilities:**
 //! - Authenticate with x-api-key header
 //! - Send requests to Claude /v1/messages endpoint
-//! - Stream SSE responses
-//! - Parse Claude-specific event format
+//! - Stream SSE responses, splitting lines across network chunks (`SseLines`)
+//! - Parse Claude-specific event format (`ClaudeStreamState`, shared by both send paths)
 //!
 //! **Author:** Daegonica Software
 //! **Version:** 0.1.0
@@ -18,25 +18,25 @@
 
 use crate::prelude::*;
 use crate::llm::{LlmClient, StreamResponse};
+use crate::llm::credentials::{ApiProvider, SharedCredential};
 use crate::claude::models::*;
 use futures_util::StreamExt;
 use async_trait::async_trait;
 
+/// Model every Claude request is sent to
+pub const CLAUDE_MODEL: &str = "claude-sonnet-4-20250514";
+
 #[derive(Debug, Clone)]
 pub struct ClaudeClient {
-    api_key: String,
-    client: Client,
+    credential: SharedCredential,
 }
 
 impl ClaudeClient {
     pub fn new() -> Result<Self, String> {
-        dotenv().ok();
-        let api_key = env::var("CLAUDE_KEY")
-            .map_err(|_| "CLAUDE_KEY environment variable not set".to_string())?;
+        let credential = SharedCredential::for_provider(ApiProvider::Claude)?;
 
         Ok( ClaudeClient {
-            api_key,
-            client: Client::new(),
+            credential,
         })
     }
 
@@ -44,13 +44,15 @@ impl ClaudeClient {
     ///
     /// # Key Differences:
     /// - Extract system prompt from messages[0]
+    /// - Later system messages (summary, nudges) are appended to it
     /// - Filter out system message from messages array
     /// - Ensure max_tokens is set (required by Claude)
     fn adapt_request(&self, request: &ChatRequest) -> ClaudeRequest {
         let system = request.input.iter()
-            .find(|m| m.role == "system")
-            .map(|m| m.content.clone())
-            .unwrap_or_default();
+            .filter(|m| m.role == "system")
+            .map(|m| m.content.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n");
 
         let messages: Vec<ClaudeMessage> = request.input.iter()
             .filter(|m| m.role != "system")
@@ -61,8 +63,8 @@ impl ClaudeClient {
             .collect();
 
         ClaudeRequest {
-            model: "claude-sonnet-4-20250514".to_string(),
-            max_tokens: 4096,
+            model: CLAUDE_MODEL.to_string(),
+            max_tokens: request.max_output_tokens.unwrap_or(4096),
             system,
             messages,
             temperature: Some(request.temperature),
@@ -77,14 +79,14 @@ impl LlmClient for ClaudeClient {
     async fn send_streaming(
         &self,
         request: &ChatRequest,
-        tx: mpsc::UnboundedSender<StreamChunk>,
+        stream: &mut ChunkStream,
     ) -> Result<StreamResponse, Box<dyn std::error::Error>> {
 
         let claude_request = self.adapt_request(request);
 
-        let response = self.client
+        let response = self.credential.client()
             .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", &self.api_key)
+            .header("x-api-key", self.credential.api_key())
             .header("anthropic-version", "2023-06-01")
             .header("content-type", "application/json")
             .json(&claude_request)
@@ -96,54 +98,392 @@ impl LlmClient for ClaudeClient {
         if !status.is_success() {
             let error_text = response.text().await?;
             log_error!("Claude API error: {} - {}", status, error_text);
-            tx.send(StreamChunk::Error(format!("API error: {} - {}", status, error_text)))?;
-            return Err(format!("API error: {}", status).into());
+            return Err(format!("API error: {} - {}{}", status, error_text, self.credential.unauthorized_hint(status)).into());
         }
 
 
-        let mut stream = response.bytes_stream();
-        let mut full_reply = String::new();
-        let mut response_id: Option<String> = None;
-        let mut line_buffer = String::new();
-
-        while let Some(chunk_result) = stream.next().await {
-            let chunk_bytes = chunk_result?;
-            line_buffer.push_str(&String::from_utf8_lossy(&chunk_bytes));
-
-            while let Some(newline_pos) = line_buffer.find('\n') {
-                let line = line_buffer[..newline_pos].to_string();
-                line_buffer.drain(..=newline_pos);
-
-                if let Some(data) = line.strip_prefix("data: ") {
-                    if let Ok(msg_start) = serde_json::from_str::<ClaudeMessageStart>(data) {
-                        if msg_start.type_ == "message_start" {
-                            response_id = Some(msg_start.message.id.clone());
-                        }
-                    }
+        let mut bytes = response.bytes_stream();
+        let mut state = ClaudeStreamState::default();
+        let mut lines = SseLines::default();
 
-                    if let Ok(content_delta) = serde_json::from_str::<ClaudeContentDelta>(data) {
-                        if content_delta.type_ == "content_block_delta" {
-                            let text = &content_delta.delta.text;
-                            full_reply.push_str(text);
-                            tx.send(StreamChunk::Delta(text.clone()))?;
-                        }
+        'read: while let Some(chunk_result) = bytes.next().await {
+            for line in lines.push(&chunk_result?) {
+                match state.feed_line(&line) {
+                    Ok(Some(delta)) => stream.delta(delta)?,
+                    Ok(None) => {}
+                    Err(e) => {
+                        log_error!("Claude stream error: {}", e);
+                        return Err(e.into());
                     }
                 }
+                if state.stopped {
+                    break 'read;
+                }
             }
         }
 
-
-        Ok(StreamResponse {
-            response_id: response_id.ok_or("No response ID received")?,
-            full_text: full_reply,
-        })
+        state.finish()
     }
 
     async fn send_blocking(
         &self,
-        _request: &ChatRequest,
-        _print_stream: bool,
+        request: &ChatRequest,
+        print_stream: bool,
     ) -> Result<StreamResponse, Box<dyn std::error::Error>> {
-        unimplemented!("Claude send_blocking not yet implemented")
+
+        let claude_request = self.adapt_request(request);
+
+        let response = self.credential.client()
+            .post("https://api.anthropic.com/v1/messages")
+            .header("x-api-key", self.credential.api_key())
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .json(&claude_request)
+            .send()
+            .await?;
+
+        let status = response.status();
+
+        if !status.is_success() {
+            let error_text = response.text().await?;
+            log_error!("Claude API error: {} - {}", status, error_text);
+            return Err(format!("API error: {} - {}{}", status, error_text, self.credential.unauthorized_hint(status)).into());
+        }
+
+        let mut stream = response.bytes_stream();
+        let mut state = ClaudeStreamState::default();
+        let mut lines = SseLines::default();
+
+        'read: while let Some(chunk_result) = stream.next().await {
+            for line in lines.push(&chunk_result?) {
+                let delta = state.feed_line(&line).inspect_err(|e| log_error!("Claude stream error: {}", e))?;
+                if let Some(delta) = delta.filter(|_| print_stream) {
+                    print!("{}", delta);
+                    io::stdout().flush().ok();
+                }
+                if state.stopped {
+                    break 'read;
+                }
+            }
+        }
+
+        if print_stream {
+            println!();
+        }
+
+        state.finish()
+    }
+}
+
+/// # SseLines
+///
+/// **Summary:**
+/// Splits a byte stream into SSE lines, whatever the network chunk boundaries.
+///
+/// **Details:**
+/// Bytes are held until their line ends, so a line (or a multi-byte
+/// character) split across chunks is decoded whole. A trailing `\r` is
+/// dropped, so CRLF streams read the same as LF ones.
+#[derive(Debug, Default)]
+pub struct SseLines {
+    pending: Vec<u8>,
+}
+
+impl SseLines {
+    /// Adds a chunk and returns every line it completed
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(chunk);
+        let Some(last_newline) = self.pending.iter().rposition(|b| *b == b'\n') else {
+            return Vec::new();
+        };
+        let rest = self.pending.split_off(last_newline + 1);
+        let complete = std::mem::replace(&mut self.pending, rest);
+
+        complete[..last_newline].split(|b| *b == b'\n')
+            .map(|line| String::from_utf8_lossy(line.strip_suffix(b"\r").unwrap_or(line)).into_owned())
+            .collect()
+    }
+}
+
+/// # ClaudeStreamState
+///
+/// **Summary:**
+/// What a Claude SSE stream has said so far, fed one line at a time.
+///
+/// **Fields:**
+/// - `response_id` / `model` / `input_tokens`: From `message_start`
+/// - `output_tokens`: From the latest `message_delta`
+/// - `full_text`: Every `content_block_delta` so far
+/// - `stopped`: `message_stop` arrived; nothing after it matters
+///
+/// **Details:**
+/// Shared by the streaming and blocking paths, so both read events the same
+/// way and both fail on an `error` event instead of returning a short reply.
+#[derive(Debug, Default)]
+pub struct ClaudeStreamState {
+    pub response_id: Option<String>,
+    pub model: Option<String>,
+    pub input_tokens: Option<u32>,
+    pub output_tokens: Option<u32>,
+    pub full_text: String,
+    pub stopped: bool,
+}
+
+impl ClaudeStreamState {
+    /// # feed_line
+    ///
+    /// **Purpose:**
+    /// Applies one SSE line.
+    ///
+    /// **Returns:**
+    /// `Result<Option<String>, String>` - The text delta it carried, if any
+    ///
+    /// **Errors / Failures:**
+    /// - The line is an `error` event; the message names its type, e.g.
+    ///   "Claude stream error (overloaded_error): Overloaded"
+    ///
+    /// **Details:**
+    /// `event:` lines, pings, and unknown events are ignored; the `type` in
+    /// each `data:` payload is what's matched on.
+    pub fn feed_line(&mut self, line: &str) -> Result<Option<String>, String> {
+        let Some(data) = line.strip_prefix("data:").map(|data| data.strip_prefix(' ').unwrap_or(data)) else {
+            return Ok(None);
+        };
+        let Ok(event) = serde_json::from_str::<ClaudeEvent>(data) else {
+            return Ok(None);
+        };
+
+        match event.type_.as_str() {
+            "error" => {
+                let message = serde_json::from_str::<ClaudeErrorEvent>(data)
+                    .map(|e| format!("Claude stream error ({}): {}", e.error.type_, e.error.message))
+                    .unwrap_or_else(|_| format!("Claude stream error: {}", data));
+                return Err(message);
+            }
+            "message_start" => {
+                if let Ok(msg_start) = serde_json::from_str::<ClaudeMessageStart>(data) {
+                    self.response_id = Some(msg_start.message.id);
+                    self.model = Some(msg_start.message.model);
+                    self.input_tokens = msg_start.message.usage.and_then(|u| u.input_tokens);
+                }
+            }
+            "message_delta" => {
+                if let Ok(msg_delta) = serde_json::from_str::<ClaudeMessageDelta>(data) {
+                    self.output_tokens = msg_delta.usage.output_tokens.or(self.output_tokens);
+                }
+            }
+            "content_block_delta" => {
+                if let Ok(content_delta) = serde_json::from_str::<ClaudeContentDelta>(data) {
+                    self.full_text.push_str(&content_delta.delta.text);
+                    return Ok(Some(content_delta.delta.text));
+                }
+            }
+            "message_stop" => self.stopped = true,
+            _ => {}
+        }
+        Ok(None)
     }
-}
\ No newline at end of file
+
+    /// # finish
+    ///
+    /// **Purpose:**
+    /// The finished response, once the stream is done.
+    ///
+    /// **Errors / Failures:**
+    /// - No `message_start` arrived (no response id)
+    /// - The stream ended before `message_stop`, so the reply may be cut off
+    pub fn finish(self) -> Result<StreamResponse, Box<dyn std::error::Error>> {
+        let response_id = self.response_id.ok_or("No response ID received")?;
+        if !self.stopped {
+            return Err("Claude stream ended before message_stop; the reply may be incomplete".into());
+        }
+        Ok(StreamResponse {
+            response_id,
+            full_text: self.full_text,
+            model: self.model,
+            input_tokens: self.input_tokens,
+            output_tokens: self.output_tokens,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A short reply as Claude streams it, blank separator lines included
+    const REPLY: &str = "\
+event: message_start
+data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_01\",\"type\":\"message\",\"role\":\"assistant\",\"model\":\"claude-sonnet-4-20250514\",\"content\":[],\"usage\":{\"input_tokens\":12,\"output_tokens\":1}}}
+
+event: content_block_start
+data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}
+
+event: ping
+data: {\"type\":\"ping\"}
+
+event: content_block_delta
+data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Hello\"}}
+
+event: content_block_delta
+data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\", wörld 👋\"}}
+
+event: content_block_stop
+data: {\"type\":\"content_block_stop\",\"index\":0}
+
+event: message_delta
+data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"end_turn\"},\"usage\":{\"output_tokens\":7}}
+
+event: message_stop
+data: {\"type\":\"message_stop\"}
+
+";
+
+    const OVERLOADED: &str = "\
+event: error
+data: {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}
+
+";
+
+    /// Feeds `sse` in chunks of `size` bytes; returns the deltas and the state (or the first error)
+    fn feed(sse: &str, size: usize) -> Result<(Vec<String>, ClaudeStreamState), String> {
+        let mut state = ClaudeStreamState::default();
+        let mut lines = SseLines::default();
+        let mut deltas = Vec::new();
+        for chunk in sse.as_bytes().chunks(size) {
+            for line in lines.push(chunk) {
+                deltas.extend(state.feed_line(&line)?);
+                if state.stopped {
+                    return Ok((deltas, state));
+                }
+            }
+        }
+        Ok((deltas, state))
+    }
+
+    #[test]
+    fn a_whole_reply_streams_its_deltas_and_finishes() {
+        let (deltas, state) = feed(REPLY, REPLY.len()).unwrap();
+        assert_eq!(deltas, ["Hello", ", wörld 👋"]);
+
+        let response = state.finish().unwrap();
+        assert_eq!(response.response_id, "msg_01");
+        assert_eq!(response.full_text, "Hello, wörld 👋");
+        assert_eq!(response.model.as_deref(), Some("claude-sonnet-4-20250514"));
+        assert_eq!(response.input_tokens, Some(12));
+        assert_eq!(response.output_tokens, Some(7));
+    }
+
+    #[test]
+    fn lines_split_across_chunks_read_the_same() {
+        for size in [1, 2, 3, 7, 64] {
+            let (deltas, state) = feed(REPLY, size).unwrap();
+            assert_eq!(deltas, ["Hello", ", wörld 👋"], "chunks of {}", size);
+            assert_eq!(state.finish().unwrap().full_text, "Hello, wörld 👋");
+        }
+    }
+
+    #[test]
+    fn crlf_streams_read_the_same() {
+        let crlf = REPLY.replace('\n', "\r\n");
+        let (deltas, state) = feed(&crlf, 5).unwrap();
+        assert_eq!(deltas, ["Hello", ", wörld 👋"]);
+        assert!(state.stopped);
+    }
+
+    #[test]
+    fn nothing_after_message_stop_is_read() {
+        let trailing = format!("{}data: {{\"type\":\"content_block_delta\",\"index\":0,\"delta\":{{\"type\":\"text_delta\",\"text\":\"late\"}}}}\n", REPLY);
+        let (deltas, state) = feed(&trailing, 16).unwrap();
+        assert_eq!(deltas.last().map(String::as_str), Some(", wörld 👋"));
+        assert_eq!(state.finish().unwrap().full_text, "Hello, wörld 👋");
+    }
+
+    #[test]
+    fn an_error_event_fails_with_its_type_and_message() {
+        let error = feed(OVERLOADED, 4).unwrap_err();
+        assert_eq!(error, "Claude stream error (overloaded_error): Overloaded");
+    }
+
+    #[test]
+    fn an_error_mid_reply_fails_after_the_deltas_before_it() {
+        let cut = REPLY.find("event: content_block_stop").unwrap();
+        let mut state = ClaudeStreamState::default();
+        let mut lines = SseLines::default();
+        let mut result = Ok(None);
+        for line in lines.push(format!("{}{}", &REPLY[..cut], OVERLOADED).as_bytes()) {
+            result = state.feed_line(&line);
+            if result.is_err() {
+                break;
+            }
+        }
+
+        assert!(result.is_err());
+        assert_eq!(state.full_text, "Hello, wörld 👋");
+    }
+
+    #[test]
+    fn a_stream_without_message_stop_is_incomplete() {
+        let cut = REPLY.find("event: message_stop").unwrap();
+        let (_, state) = feed(&REPLY[..cut], 32).unwrap();
+        let error = state.finish().map(|_| ()).unwrap_err().to_string();
+        assert!(error.contains("before message_stop"), "{}", error);
+    }
+
+    #[test]
+    fn a_stream_without_message_start_has_no_response_id() {
+        let start = REPLY.find("event: content_block_start").unwrap();
+        let (_, state) = feed(&REPLY[start..], 32).unwrap();
+        assert_eq!(state.finish().map(|_| ()).unwrap_err().to_string(), "No response ID received");
+    }
+
+    #[test]
+    fn other_lines_are_ignored() {
+        let mut state = ClaudeStreamState::default();
+        for line in ["", "event: content_block_delta", ": keep-alive", "data: not json", "data: {\"type\":\"ping\"}"] {
+            assert_eq!(state.feed_line(line), Ok(None), "{:?}", line);
+        }
+        assert_eq!(
+            state.feed_line("data:{\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"x\"}}"),
+            Ok(Some("x".to_string()))
+        );
+    }
+
+    #[test]
+    fn an_unfinished_line_waits_for_its_newline() {
+        let mut lines = SseLines::default();
+        assert!(lines.push(b"data: {\"ty").is_empty());
+        assert!(lines.push("pe\":\"pi".as_bytes()).is_empty());
+        assert_eq!(lines.push(b"ng\"}\n\nda"), ["data: {\"type\":\"ping\"}", ""]);
+        assert_eq!(lines.push(b"ta: x\n"), ["data: x"]);
+    }
+
+    #[test]
+    fn a_summary_and_nudges_join_the_system_prompt() {
+        use crate::llm::credentials::{EnvValue, Provenance, ValueSource};
+        let key = EnvValue {
+            value: "test-key".to_string(),
+            provenance: Provenance { source: ValueSource::DotEnv, both_set: false, differs: false },
+        };
+        let client = ClaudeClient { credential: SharedCredential::detached(ApiProvider::Claude, key) };
+        let message = |role: &str, content: &str| Message { role: role.into(), content: content.into(), meta: None, source: None };
+        let request = ChatRequest::builder()
+            .messages([
+                message("system", "Be brief."),
+                message("system", "[Summary of earlier conversation]\n- Met Ana.]"),
+                message("user", "hi"),
+                message("assistant", "Hello."),
+                message("user", "what's next?"),
+            ])
+            .ephemeral([message("system", "answer in bullet points")])
+            .build()
+            .unwrap();
+
+        let adapted = client.adapt_request(&request);
+
+        assert_eq!(adapted.system, "Be brief.\n\n[Summary of earlier conversation]\n- Met Ana.]\n\nanswer in bullet points");
+        let turns: Vec<(&str, &str)> = adapted.messages.iter().map(|m| (m.role.as_str(), m.content.as_str())).collect();
+        assert_eq!(turns, [("user", "hi"), ("assistant", "Hello."), ("user", "what's next?")]);
+    }
+}
+
@@ -56,4 +56,42 @@ pub struct ClaudeMessageMeta {
     pub id: String,
     pub model: String,
     pub role: String,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub usage: Option<ClaudeUsage>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ClaudeMessageDelta {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub usage: ClaudeUsage,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ClaudeUsage {
+    #[serde(default)]
+    pub input_tokens: Option<u32>,
+    #[serde(default)]
+    pub output_tokens: Option<u32>,
+}
+/// Any SSE payload, read first to learn which event it is
+#[derive(Deserialize, Debug)]
+pub struct ClaudeEvent {
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+/// An `error` event sent mid-stream, e.g. when the API is overloaded
+#[derive(Deserialize, Debug)]
+pub struct ClaudeErrorEvent {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub error: ClaudeErrorDetail,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ClaudeErrorDetail {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub message: String,
+}
@@ -25,8 +25,45 @@ use crate::prelude::*;
 use crate::tui::ShadowApp;
 use std::fmt::Debug;
 use uuid::Uuid;
+use crate::persona::agent::{nudge_line, CancelReply};
 use crate::persona::agent_manager::AgentManager;
 use crate::persona::operations::AgentOperations;
+use crate::persona::tools::{Tool, ToolSet};
+use crate::persona::builtin::export_builtins;
+use crate::persona::changelog::read_changelog;
+use crate::utilities::time::{self as time_fmt, Zone};
+use crate::llm::credentials::swap_keys;
+use crate::persona::background::BackgroundReport;
+use crate::utilities::accessibility::glyph;
+use crate::utilities::log_level::{self, LogLevel};
+use crate::utilities::strict;
+use crate::utilities::links;
+use crate::persona::experiment::{Experiment, Rating};
+use crate::persona::jobs::{GenerationJob, JobId};
+use crate::user::macros::{Macro, MacroRecording, MacroReplay, MacroStore};
+use crate::twitter::quota::{Outbox, PostLog};
+use crate::twitter::spellcheck;
+use crate::tui::layout::LayoutMode;
+use crate::utilities::profile;
+use crate::agent_history::persistence;
+use crate::agent_history::history::{ConversationOverrides, RemovedMessages};
+use crate::agent_history::integrity::CheckStatus;
+use crate::user::outgoing::{line_diff, Pipeline};
+use crate::twitter::preview::{self, TweetPreview};
+use crate::persona::focus::{self, FocusBlock, FocusOutcome};
+use crate::utilities::language::{self, LanguageSource};
+use crate::tui::inspect::InspectView;
+use crate::persona::{discover_personas, personas_root, resolve_prompt_path};
+use crate::agent_history::overview::{self, Overview, OverviewSort};
+use crate::agent_history::redaction::{self, Redactor};
+use crate::agent_history::freshness::{self, ResyncChoice};
+use crate::agent_history::checkpoint::{self, CheckpointReason};
+use crate::utilities::insights::{format_tokens, InsightSources, Month, MonthInsights};
+use crate::utilities::explain::{self, ExplainRequest};
+use crate::llm::credentials::ApiProvider;
+use crate::llm::usage::{CostEstimate, TokenTally};
+use crate::claude::client::CLAUDE_MODEL;
+use strum::IntoEnumIterator;
 
 pub trait AgentContext {
     fn get_agent_manager(&self) -> &AgentManager;
@@ -127,6 +164,33 @@ pub enum CommandResult {
     Error(String)
 }
 
+/// What every command that needs an agent says when none is open
+pub const NO_AGENT_GUIDANCE: &str = "No agent open. Type `new shadow` to start one, or `list` to see every persona.";
+
+/// Commands that work before any agent is open, shown by `help`
+pub const HELP_TEXT: &str = "\
+Getting started:
+  new <persona>    open an agent (try `new shadow`)
+  list             show every persona
+  status           show open agents
+  tour             replay the guided tour
+With an agent open:
+  <message>        talk to the current agent
+  attach <file>    send a file for review (try it with `new reviewer`)
+  historyinfo      what the agent remembers; `history list` shows messages
+  summarize        condense the conversation history
+  close            close the current agent
+Other commands: overview, insights, usage, model, nudge, resync, rollback, explain, stop, speak, open, draft, tweet, jobs, macro, focus, global, layout, timestamps, language, set, reload, quit";
+
+/// # no_agent_open
+///
+/// **Purpose:**
+/// Points the user at `new` / `list` in the Global pane when a command needs an agent.
+fn no_agent_open(ops: &mut dyn AgentOperations) -> CommandResult {
+    ops.display_message(NO_AGENT_GUIDANCE.to_string());
+    CommandResult::Continue
+}
+
 /// # SendMessageCommand
 ///
 /// **Summary:**
@@ -153,31 +217,73 @@ impl SendMessageCommand {
 
 impl Command for SendMessageCommand {
     fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let persona_notice = ops.get_current_agent_id()
+            .and_then(|id| ops.refresh_persona_status(id));
+
         let Some(agent) = ops.current_agent_info_mut() else {
-            ops.display_message("No agent available. Create one with 'new <persona>'".to_string());
-            return CommandResult::Continue;
+            return no_agent_open(ops);
         };
 
-        agent.add_message(format!("> {}", self.content));
-        agent.is_waiting = true;
-
-        if let Some(old_task) = agent.active_task.take() {
-            old_task.abort();
+        if let Some(notice) = persona_notice {
+            agent.add_message(notice);
         }
+        agent.send(Pipeline::chat().run(&self.content));
+        let persona_name = agent.persona_name.clone();
+        ops.check_in_answered(&persona_name);
+        CommandResult::Continue
+    }
+}
 
-        let connection = agent.connection.clone();
-        let tx = agent.chunk_sender.clone();
-        let content_owned = self.content.clone();
+/// Largest file `attach` sends; bigger ones should be cut down to the part under review
+pub const ATTACH_MAX_BYTES: u64 = 64 * 1024;
+
+/// # AttachCommand
+///
+/// **Summary:**
+/// Sends a file to the current agent for review (`attach <file> [note]`).
+///
+/// **Fields:**
+/// - `path`: File to send, relative to the working directory
+/// - `note`: What to look at, sent ahead of the code (a generic review request if empty)
+///
+/// **Details:**
+/// The file goes through `Pipeline::attach`, so the pane echoes its name and
+/// `sent` shows the full message. Files over `ATTACH_MAX_BYTES` or not UTF-8 are refused.
+#[derive(Debug, Clone)]
+pub struct AttachCommand {
+    path: String,
+    note: String,
+}
+
+impl AttachCommand {
+    pub fn new(path: String, note: String) -> Self {
+        Self { path, note }
+    }
+}
 
-        let handle = tokio::spawn(async move {
-            let mut conn = connection.lock().await;
-            conn.add_user_message(&content_owned);
-            if let Err(e) = conn.handle_response_streaming(tx.clone()).await {
-                let _ = tx.send(StreamChunk::Error(format!("{}", e)));
+impl Command for AttachCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        if ops.current_agent_info_mut().is_none() {
+            return no_agent_open(ops);
+        }
+        match fs::metadata(&self.path) {
+            Ok(meta) if meta.len() > ATTACH_MAX_BYTES => {
+                return CommandResult::Error(format!(
+                    "{} is {} KB; attach sends at most {} KB", self.path, meta.len() / 1024, ATTACH_MAX_BYTES / 1024
+                ));
             }
-        });
+            Ok(_) => {}
+            Err(e) => return CommandResult::Error(format!("Can't attach {}: {}", self.path, e)),
+        }
+        let code = match fs::read_to_string(&self.path) {
+            Ok(code) => code,
+            Err(e) => return CommandResult::Error(format!("Can't attach {}: {}", self.path, e)),
+        };
 
-        agent.active_task = Some(handle);
+        let Some(agent) = ops.current_agent_info_mut() else {
+            return no_agent_open(ops);
+        };
+        agent.send(Pipeline::attach(&self.path, code).run(&self.note));
         CommandResult::Continue
     }
 }
@@ -198,8 +304,7 @@ impl SaveHistoryCommand {
 impl Command for SaveHistoryCommand {
     fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
         let Some(agent) = ops.current_agent_info_mut() else {
-            ops.display_message("No agent available to save history for.".to_string());
-            return CommandResult::Continue;
+            return no_agent_open(ops);
         };
 
         let connection = agent.connection.clone();
@@ -209,18 +314,22 @@ impl Command for SaveHistoryCommand {
             ops.display_message("Failed to acquire connection lock.".to_string());
             return CommandResult::Continue;
         };
+        let was_suspended = persistence::suspended_reason().is_some();
         let result = conn.save_persona_history();
         let persona_name = conn.conversation.persona.name.clone();
         drop(conn); // Release lock before using ops again
 
         match result {
             Ok(_) => {
-                ops.display_message(format!("History saved for {}", persona_name));
+                ops.display_agent_message(&persona_name, format!("History saved for {}", persona_name));
+                if was_suspended {
+                    ops.display_message("History is writable again; automatic saves resumed.".to_string());
+                }
                 log_info!("History saved for {}", persona_name);
             }
             Err(e) => {
                 log_error!("Failed to save history: {}", e);
-                ops.display_message(format!("Failed to save history: {}", e));
+                ops.display_agent_message(&persona_name, format!("Failed to save history: {}", e));
             }
         }
 
@@ -244,8 +353,7 @@ impl HistoryInfoCommand {
 impl Command for HistoryInfoCommand {
     fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
         let Some(agent) = ops.current_agent_info_mut() else {
-            ops.display_message("No agent available.".to_string());
-            return CommandResult::Continue;
+            return no_agent_open(ops);
         };
 
         let connection = agent.connection.clone();
@@ -256,17 +364,359 @@ impl Command for HistoryInfoCommand {
             return CommandResult::Continue;
         };
         let msg_count = conn.conversation.local_history.len();
-        let has_summary = conn.conversation.local_history.iter()
-            .any(|msg| msg.role == "system" && msg.content.contains("[Previous conversation summary:"));
+        let all_time = conn.conversation.total_message_count;
+        let tier = |text: Option<&str>| match text {
+            Some(text) => format!("{:.1} KB", text.len() as f64 / 1024.0),
+            None => "none".to_string(),
+        };
+        let summaries = format!(
+            "recent summary: {}, long-term summary: {}, summarized {} times",
+            tier(conn.conversation.summary_text()),
+            tier(conn.conversation.long_term_summary_text()),
+            conn.conversation.summarization_count,
+        );
         let persona_name = conn.conversation.persona.name.clone();
+        let zone = Zone::for_persona(&conn.conversation.persona);
+        let started_at = chrono::DateTime::parse_from_rfc3339(&conn.conversation.started_at).ok();
+        let thread = match conn.conversation.response_id_age() {
+            None => "none".to_string(),
+            Some(_) if conn.conversation.response_id_expired() => "expired (next message sends full history)".to_string(),
+            Some(age) => format!("set {}", time_fmt::format_relative(chrono::Utc::now() - age)),
+        };
+        let model = match ApiProvider::for_persona(&conn.conversation.persona) {
+            ApiProvider::Claude => CLAUDE_MODEL.to_string(),
+            ApiProvider::Grok if conn.conversation.model_override().is_some() => {
+                format!("{} (set with `model`)", conn.conversation.model())
+            }
+            ApiProvider::Grok => conn.conversation.model().to_string(),
+        };
         drop(conn); // Release lock before using ops again
 
-        log_info!("{}: {} messages, Summary present: {}", persona_name, msg_count, has_summary);
-        ops.display_message(format!(
-            "History for {}: {} messages, Summary present: {}",
-            persona_name, msg_count, has_summary
+        log_info!("{}: {} messages ({} all time), {}", persona_name, msg_count, all_time, summaries);
+        let started = started_at
+            .map(|t| time_fmt::format_timestamp(t.with_timezone(&chrono::Utc), zone))
+            .unwrap_or_else(|| "unknown".to_string());
+        ops.display_agent_message(&persona_name, format!(
+            "History for {}: {} messages in memory, {} all time, {}, started {}, model: {}, response thread: {}",
+            persona_name, msg_count, all_time, summaries, started, model, thread
         ).to_string());
 
+        let last_change = read_changelog(&persona_name).ok()
+            .and_then(|entries| entries.into_iter().last());
+        if let Some((entry, changed_at)) = last_change.and_then(|e| e.changed_at().map(|t| (e, t))) {
+            let span = match started_at {
+                Some(started) if changed_at > started => "mid-conversation",
+                Some(_) => "before this conversation",
+                None => "conversation start unknown",
+            };
+            ops.display_agent_message(&persona_name, format!(
+                "Persona {} changed {}, {}",
+                entry.changed_fields.join(", "), time_fmt::format_timestamp(changed_at, zone), span
+            ));
+        }
+
+        CommandResult::Continue
+    }
+}
+
+/// # DedupeHistoryCommand
+///
+/// **Summary:**
+/// Command to find (and optionally remove) back-to-back duplicate messages in the current history.
+///
+/// **Fields:**
+/// - `apply`: false for a dry run that only lists duplicates; true to remove them and save
+#[derive(Debug, Clone)]
+pub struct DedupeHistoryCommand {
+    apply: bool,
+}
+
+impl DedupeHistoryCommand {
+    pub fn new(apply: bool) -> Self {
+        Self { apply }
+    }
+}
+
+impl Command for DedupeHistoryCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent) = ops.current_agent_info() else {
+            return no_agent_open(ops);
+        };
+        let connection = agent.connection.clone();
+
+        let Ok(mut conn) = connection.try_lock() else {
+            return CommandResult::Error("Agent is busy; try again once the reply finishes.".to_string());
+        };
+        let persona_name = conn.conversation.persona.name.clone();
+        let duplicates = conn.conversation.consecutive_duplicates();
+
+        if duplicates.is_empty() {
+            drop(conn);
+            ops.display_agent_message(&persona_name, "No duplicate messages found.".to_string());
+            return CommandResult::Continue;
+        }
+
+        let previews: Vec<String> = duplicates.iter()
+            .map(|&i| {
+                let msg = &conn.conversation.local_history[i];
+                let preview: String = msg.content.chars().take(60).collect();
+                format!("  #{} {}: {}", i, msg.role, preview)
+            })
+            .collect();
+
+        if !self.apply {
+            drop(conn);
+            ops.display_agent_message(&persona_name, format!(
+                "{} duplicate message(s) would be removed:", previews.len()
+            ));
+            for preview in previews {
+                ops.display_agent_message(&persona_name, preview);
+            }
+            ops.display_agent_message(&persona_name, "Run 'history dedupe confirm' to remove them.".to_string());
+            return CommandResult::Continue;
+        }
+
+        let removed = conn.conversation.remove_consecutive_duplicates();
+        let saved = conn.save_persona_history();
+        drop(conn); // Release lock before using ops again
+
+        ops.display_agent_message(&persona_name, format!("Removed {} duplicate message(s)", removed));
+        if let Err(e) = saved {
+            return CommandResult::Error(format!("Deduped in memory but failed to save history: {}", e));
+        }
+        CommandResult::Continue
+    }
+}
+
+/// Recent messages `history list` shows
+const HISTORY_LIST_LIMIT: usize = 20;
+
+/// One `history list` line: index, role, and the start of the first line as stored on disk
+fn history_line(index: usize, msg: &Message, redactor: &Redactor) -> String {
+    let stored = redactor.redact_text(&msg.content);
+    let first_line: String = stored.lines().next().unwrap_or_default().chars().take(60).collect();
+    let protected = if msg.role == "system" { " (protected)" } else { "" };
+    let badge = if redactor.matches(&msg.content) { format!(" {}", redaction::redacted_badge()) } else { String::new() };
+    format!("  #{} {}{}: {}{}", index, msg.role, protected, first_line, badge)
+}
+
+/// `history list` lines for the last `HISTORY_LIST_LIMIT` messages
+fn history_listing(history: &[Message], redactor: &Redactor) -> Vec<String> {
+    let start = history.len().saturating_sub(HISTORY_LIST_LIMIT);
+    history.iter().enumerate().skip(start)
+        .map(|(i, msg)| history_line(i, msg, redactor))
+        .collect()
+}
+
+/// # HistoryListCommand
+///
+/// **Summary:**
+/// Command to list the current agent's recent messages with the indices `history remove` takes.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryListCommand;
+
+impl HistoryListCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Command for HistoryListCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent) = ops.current_agent_info() else {
+            return no_agent_open(ops);
+        };
+        let connection = agent.connection.clone();
+
+        let Ok(conn) = connection.try_lock() else {
+            return CommandResult::Error("Agent is busy; try again once the reply finishes.".to_string());
+        };
+        let persona_name = conn.conversation.persona.name.clone();
+        let total = conn.conversation.local_history.len();
+        let redactor = Redactor::for_persona(&conn.conversation.persona);
+        let lines = history_listing(&conn.conversation.local_history, &redactor);
+        drop(conn);
+
+        ops.display_agent_message(&persona_name, format!(
+            "Last {} of {} messages (remove with 'history remove <n> [--pair]'):", lines.len(), total
+        ));
+        for line in lines {
+            ops.display_agent_message(&persona_name, line);
+        }
+        CommandResult::Continue
+    }
+}
+
+/// # HistoryRemoveCommand
+///
+/// **Summary:**
+/// Command to take one poisoned message (or a whole exchange) out of the current history.
+///
+/// **Fields:**
+/// - `index`: Message index from `history list`
+/// - `pair`: Also remove the other half of the exchange
+/// - `apply`: false for a dry run; true to remove, save, and write a recovery copy
+///
+/// **Details:**
+/// Nothing is removed unless the recovery copy made it to disk. Indices after the
+/// removed messages shift down, so the updated list is shown afterwards.
+#[derive(Debug, Clone)]
+pub struct HistoryRemoveCommand {
+    index: usize,
+    pair: bool,
+    apply: bool,
+}
+
+impl HistoryRemoveCommand {
+    pub fn new(index: usize, pair: bool, apply: bool) -> Self {
+        Self { index, pair, apply }
+    }
+}
+
+impl Command for HistoryRemoveCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent) = ops.current_agent_info() else {
+            return no_agent_open(ops);
+        };
+        let connection = agent.connection.clone();
+
+        let Ok(mut conn) = connection.try_lock() else {
+            return CommandResult::Error("Agent is busy; try again once the reply finishes.".to_string());
+        };
+        let persona_name = conn.conversation.persona.name.clone();
+        let range = match conn.conversation.removal_range(self.index, self.pair) {
+            Ok(range) => range,
+            Err(reason) => return CommandResult::Error(reason),
+        };
+
+        let redactor = Redactor::for_persona(&conn.conversation.persona);
+        let previews: Vec<String> = range.clone()
+            .map(|i| history_line(i, &conn.conversation.local_history[i], &redactor))
+            .collect();
+
+        if !self.apply {
+            drop(conn);
+            ops.display_agent_message(&persona_name, format!("{} message(s) would be removed:", previews.len()));
+            for preview in previews {
+                ops.display_agent_message(&persona_name, preview);
+            }
+            let pair = if self.pair { " --pair" } else { "" };
+            ops.display_agent_message(&persona_name, format!(
+                "Run 'history remove {}{} confirm' to remove them.", self.index, pair
+            ));
+            return CommandResult::Continue;
+        }
+
+        let record = RemovedMessages {
+            persona: persona_name.clone(),
+            removed_at: chrono::Utc::now().to_rfc3339(),
+            index: range.start,
+            messages: redactor.redact_messages(&conn.conversation.local_history[range.clone()]),
+        };
+        let recovery = match persistence::explicit_write(|| HistoryManager::archive_removed_messages(&record)) {
+            Ok(path) => path,
+            Err(e) => return CommandResult::Error(format!("Not removing without a recovery copy: {}", e)),
+        };
+
+        let removed = conn.conversation.remove_messages(range);
+        let saved = conn.save_persona_history();
+        let lines = history_listing(&conn.conversation.local_history, &redactor);
+        drop(conn); // Release lock before using ops again
+
+        ops.display_agent_message(&persona_name, format!(
+            "Removed {} message(s); recovery copy at {}. The next message re-sends the edited history.",
+            removed.len(), recovery.display()
+        ));
+        ops.display_agent_message(&persona_name, "Updated list:".to_string());
+        for line in lines {
+            ops.display_agent_message(&persona_name, line);
+        }
+        if let Err(e) = saved {
+            return CommandResult::Error(format!("Removed in memory but failed to save history: {}", e));
+        }
+        CommandResult::Continue
+    }
+}
+
+/// # VerifyHistoryCommand
+///
+/// **Summary:**
+/// Command to audit saved histories against their archives (`history verify`).
+///
+/// **Fields:**
+/// - `persona`: Persona to verify; None verifies every persona with a saved history
+/// - `fix`: List what can be repaired
+/// - `apply`: Carry the repairs out
+///
+/// **Details:**
+/// Works on the files, not on open agents, so a persona with an open agent is
+/// never repaired: the agent's next save would write its own copy back.
+#[derive(Debug, Clone)]
+pub struct VerifyHistoryCommand {
+    persona: Option<String>,
+    fix: bool,
+    apply: bool,
+}
+
+impl VerifyHistoryCommand {
+    pub fn new(persona: Option<String>, fix: bool, apply: bool) -> Self {
+        Self { persona, fix, apply }
+    }
+}
+
+impl Command for VerifyHistoryCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let personas = match &self.persona {
+            Some(name) if !HistoryManager::history_exists(name) => {
+                ops.display_message(format!("No saved history for {}.", name));
+                return CommandResult::Continue;
+            }
+            Some(name) => vec![name.clone()],
+            None => HistoryManager::saved_histories(),
+        };
+        if personas.is_empty() {
+            ops.display_message("No saved histories to verify.".to_string());
+            return CommandResult::Continue;
+        }
+
+        let open: Vec<String> = ops.get_all_agent_names().into_iter().map(|(_, name)| name).collect();
+        let mut repairable = false;
+        for persona in personas {
+            let report = match HistoryManager::verify_history(&persona) {
+                Ok(report) => report,
+                Err(e) => {
+                    ops.display_warning(format!("{}: [fail] history doesn't load: {}", persona, e));
+                    continue;
+                }
+            };
+            let lines = report.describe().join("\n");
+            if report.status() == CheckStatus::Pass {
+                ops.display_message(lines);
+            } else {
+                ops.display_warning(lines);
+            }
+
+            if !self.fix || report.repairs.is_empty() {
+                continue;
+            }
+            if !self.apply {
+                repairable = true;
+            } else if open.contains(&persona) {
+                ops.display_warning(format!(
+                    "Not repairing {}: close its agent first, or its next save writes the old values back.", persona
+                ));
+            } else {
+                match persistence::explicit_write(|| HistoryManager::repair_history(&persona)) {
+                    Ok(changes) => ops.display_message(format!("Repaired {}: {}", persona, changes.join("; "))),
+                    Err(e) => return CommandResult::Error(format!("Failed to repair {}: {}", persona, e)),
+                }
+            }
+        }
+
+        if repairable {
+            let target = self.persona.as_deref().map(|name| format!("{} ", name)).unwrap_or_default();
+            ops.display_message(format!("Run 'history verify {}fix confirm' to apply the repairs.", target));
+        }
         CommandResult::Continue
     }
 }
@@ -287,8 +737,7 @@ impl ClearHistoryCommand {
 impl Command for ClearHistoryCommand {
     fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
         let Some(agent) = ops.current_agent_info_mut() else {
-            ops.display_message("No agent available.".to_string());
-            return CommandResult::Continue;
+            return no_agent_open(ops);
         };
 
         let Ok(conn) = agent.connection.try_lock() else {
@@ -297,20 +746,124 @@ impl Command for ClearHistoryCommand {
         };
         let persona_name = conn.conversation.persona.name.clone();
         drop(conn);
-        let path = format!("history/{}_history.json", &persona_name);
-        let result = std::fs::remove_file(&path);
-
-        match result {
+        match HistoryManager::delete_history(&persona_name) {
             Ok(_) => {
                 log_info!("Cleared history for {}", persona_name);
-                ops.display_message(format!("Cleared history for {}", persona_name));
+                ops.display_agent_message(&persona_name, format!("Cleared history for {}", persona_name));
             }
             Err(_) => {
                 log_error!("No history for {}", persona_name);
-                ops.display_message(format!("No history for {}", persona_name));
+                ops.display_agent_message(&persona_name, format!("No history for {}", persona_name));
+            }
+        }
+
+        CommandResult::Continue
+    }
+}
+
+/// # RollbackCommand
+///
+/// **Summary:**
+/// Command to list the current agent's checkpoints and restore one (`rollback`).
+///
+/// **Fields:**
+/// - `index`: Checkpoint number from the listing (1 = newest); None lists them
+/// - `apply`: false previews the checkpoint; true restores it and saves
+///
+/// **Details:**
+/// The state being replaced is checkpointed first (after the target is loaded,
+/// so pruning can't delete it), which makes every rollback undoable with
+/// `rollback 1 confirm`. Nothing is restored unless that checkpoint was written.
+#[derive(Debug, Clone)]
+pub struct RollbackCommand {
+    index: Option<usize>,
+    apply: bool,
+}
+
+impl RollbackCommand {
+    pub fn new(index: Option<usize>, apply: bool) -> Self {
+        Self { index, apply }
+    }
+}
+
+impl Command for RollbackCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent) = ops.current_agent_info() else {
+            return no_agent_open(ops);
+        };
+        let connection = agent.connection.clone();
+
+        let Ok(mut conn) = connection.try_lock() else {
+            return CommandResult::Error("Agent is busy; try again once the reply finishes.".to_string());
+        };
+        let persona_name = conn.conversation.persona.name.clone();
+        if !conn.conversation.persona.enable_history {
+            return CommandResult::Error(format!("{} doesn't keep history, so it has no checkpoints.", persona_name));
+        }
+        let zone = Zone::for_persona(&conn.conversation.persona);
+        let checkpoints = match checkpoint::list_checkpoints(&persona_name) {
+            Ok(checkpoints) => checkpoints,
+            Err(e) => return CommandResult::Error(format!("Failed to read checkpoints: {}", e)),
+        };
+
+        if checkpoints.is_empty() {
+            drop(conn);
+            ops.display_agent_message(&persona_name, format!(
+                "No checkpoints for {} yet. One is written before summarize, history remove, history dedupe, clear and resync fresh.",
+                persona_name
+            ));
+            return CommandResult::Continue;
+        }
+
+        let Some(index) = self.index else {
+            drop(conn);
+            ops.display_agent_message(&persona_name, format!(
+                "Checkpoints for {} (newest first; preview with 'rollback <n>'):", persona_name
+            ));
+            for (i, summary) in checkpoints.iter().enumerate() {
+                ops.display_agent_message(&persona_name, format!("  #{}  {}", i + 1, summary.describe(zone)));
             }
+            return CommandResult::Continue;
+        };
+
+        let Some(target) = index.checked_sub(1).and_then(|i| checkpoints.get(i)) else {
+            return CommandResult::Error(format!(
+                "No checkpoint #{}; 'rollback' lists {} for {}.", index, checkpoints.len(), persona_name
+            ));
+        };
+        let restored = match checkpoint::load_checkpoint(&target.path) {
+            Ok(restored) => restored,
+            Err(e) => return CommandResult::Error(format!("Failed to load checkpoint #{}: {}", index, e)),
+        };
+        let description = target.describe(zone);
+
+        if !self.apply {
+            let current = conn.conversation.local_history.len();
+            drop(conn);
+            ops.display_agent_message(&persona_name, format!(
+                "#{}  {}\nRestoring it replaces the current {} messages with its {}.",
+                index, description, current, restored.message_count
+            ));
+            ops.display_agent_message(&persona_name, format!(
+                "Run 'rollback {} confirm' to restore it; the current state is checkpointed first.", index
+            ));
+            return CommandResult::Continue;
+        }
+
+        if let Err(e) = conn.checkpoint(CheckpointReason::Rollback) {
+            return CommandResult::Error(format!("Not rolling back without a checkpoint of the current state: {}", e));
         }
+        let message_count = restored.message_count;
+        restored.restore_into(&mut conn.conversation);
+        let saved = conn.save_persona_history();
+        drop(conn); // Release lock before using ops again
 
+        ops.display_agent_message(&persona_name, format!(
+            "Rolled back to #{} ({} messages). 'rollback 1 confirm' undoes this.", index, message_count
+        ));
+        if let Err(e) = saved {
+            return CommandResult::Error(format!("Rolled back in memory but failed to save history: {}", e));
+        }
         CommandResult::Continue
     }
 }
@@ -341,15 +894,17 @@ impl Command for NewAgentCommand {
             let id = Uuid::new_v4();
             ops.add_new_agent(id, persona_ref);
             ops.set_current_agent_id(Some(id));
-            ops.display_message(format!(
+            ops.display_agent_message(&self.persona_name, format!(
                 "Created new agent with persona '{}'",
                 capitalize_first(&self.persona_name)
             ));
         } else {
-            ops.display_message(format!(
-                "Persona '{}' not found.",
-                capitalize_first(&self.persona_name)
-            ));
+            let msg = format!("Persona '{}' not found.", capitalize_first(&self.persona_name));
+            let hint = explain::hint(&msg);
+            ops.display_message(msg);
+            if let Some(hint) = hint {
+                ops.display_message(hint);
+            }
         }
 
         CommandResult::Continue
@@ -371,12 +926,15 @@ impl CloseAgentCommand {
 
 impl Command for CloseAgentCommand {
     fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
-        if let Some(id) = ops.get_current_agent_id() {
-            ops.remove_agent(id);
-            ops.display_message("Closed current agent.".to_string());
-        } else {
-            ops.display_message("No agent to close.".to_string());
-        }
+        let Some(id) = ops.get_current_agent_id() else {
+            return no_agent_open(ops);
+        };
+
+        let persona_name = ops.get_agent_info(id)
+            .map(|agent| agent.persona_name.clone())
+            .unwrap_or_default();
+        ops.remove_agent(id);
+        ops.display_agent_message(&persona_name, "Closed current agent.".to_string());
 
         CommandResult::Continue
     }
@@ -397,22 +955,39 @@ impl AgentStatusCommand {
 
 impl Command for AgentStatusCommand {
     fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        if ops.get_agent_order().is_empty() {
+            ops.display_message(format!("{}\nLog level: {}{}", NO_AGENT_GUIDANCE, log_level::describe(), strict_status()));
+            return CommandResult::Continue;
+        }
+
         let mut status = String::new();
         status.push_str(&format!("Current agent: {}\n", ops.current_agent_info()
             .map(|agent| capitalize_first(&agent.persona_name))
             .unwrap_or("<none>".to_string())));
 
-        status.push_str(&format!(" - Current agent: {}\n", ops.current_agent_info_mut()
-            .map(|agent| capitalize_first(&agent.persona_name))
-            .unwrap_or("<none>".to_string())));
-
         status.push_str(" - All agents:\n");
         let current_id = ops.get_current_agent_id();
         for (agent_id, agent_name) in ops.get_all_agent_names() {
             let marker = if Some(agent_id) == current_id { " ->"} else { " " };
-            status.push_str(&format!("{} {}\n", marker, capitalize_first(&agent_name)));
+            let muted = if ops.is_agent_muted(agent_id) { glyph(" 🔇", " (muted)") } else { "" };
+            let issue = ops.get_agent_info(agent_id)
+                .and_then(|agent| agent.persona_issue.clone())
+                .map(|issue| format!("{} {}", glyph(" ⚠", " persona problem:"), issue))
+                .unwrap_or_default();
+            let summarize = ops.get_agent_info(agent_id)
+                .and_then(|agent| agent.connection.try_lock().ok().map(|conn| conn.conversation.summarize_mode()))
+                .filter(|mode| *mode != SummarizeMode::Auto)
+                .map(|mode| format!(" (summarize: {})", mode))
+                .unwrap_or_default();
+            let strips = ops.get_agent_info(agent_id)
+                .and_then(|agent| agent.connection.try_lock().ok().map(|conn| conn.strip_matches))
+                .filter(|count| *count > 0)
+                .map(|count| format!(" (stripped replies: {})", count))
+                .unwrap_or_default();
+            status.push_str(&format!("{} {}{}{}{}{}\n", marker, capitalize_first(&agent_name), muted, summarize, strips, issue));
         }
-        status.push_str(&format!(" - Total tabs: {}", ops.get_agent_order().len()));
+        status.push_str(&format!(" - Total tabs: {}\n", ops.get_agent_order().len()));
+        status.push_str(&format!(" - Log level: {}{}", log_level::describe(), strict_status()));
 
         ops.display_message(format!("{}", status));
 
@@ -420,172 +995,2669 @@ impl Command for AgentStatusCommand {
     }
 }
 
-/// # SummarizeCommand
+/// The `status` line for strict mode, empty when it's off
+fn strict_status() -> &'static str {
+    if strict::is_enabled() {
+        "\n - Strict mode: on (commands that post, delete, or overwrite are refused)"
+    } else {
+        ""
+    }
+}
+
+/// # UsageCommand
 ///
 /// **Summary:**
-/// Command to trigger conversation history summarization for the current agent.
-#[derive(Debug, Clone)]
-pub struct SummarizeCommand;
-
-impl SummarizeCommand {
+/// Command to show each agent's token use this session, with an estimated cost.
+///
+/// **Details:**
+/// Summarization requests are listed apart from replies, since they're spend
+/// the user never sees. Costs use `insights.model_prices`. An agent mid-request
+/// is shown as busy rather than waited on.
+#[derive(Debug, Clone, Default)]
+pub struct UsageCommand;
+
+impl UsageCommand {
     pub fn new() -> Self {
         Self
     }
 }
 
-impl Command for SummarizeCommand {
+impl Command for UsageCommand {
     fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
-        let Some(agent) = ops.current_agent_info_mut() else {
-            ops.display_message("No agent available.".to_string());
+        if ops.get_agent_order().is_empty() {
+            ops.display_message(NO_AGENT_GUIDANCE.to_string());
             return CommandResult::Continue;
-        };
+        }
 
-        let connection = agent.connection.clone();
-        let tx = agent.chunk_sender.clone();
-        ops.display_message("Summarization started...".to_string());
-
-        tokio::spawn(async move {
-            tx.send(StreamChunk::Info("Starting summarization...".to_string())).ok();
-            let mut conn = connection.lock().await;
-            if let Err(e) = conn.summarize_history().await {
-                tx.send(StreamChunk::Error(format!("Summarization error: {}", e))).ok();
-            } else {
-                tx.send(StreamChunk::Info("Summarization complete.".to_string())).ok();
-                if let Err(e) = conn.save_persona_history() {
-                    tx.send(StreamChunk::Error(format!("Failed to save persona history: {}", e))).ok();
-                }
+        let prices = &GLOBAL_CONFIG.insights.model_prices;
+        let current_id = ops.get_current_agent_id();
+        let mut lines = vec!["Token use this session:".to_string()];
+        let mut total = TokenTally::default();
+        let mut total_cost = CostEstimate { cost: 0.0, unpriced: Vec::new() };
+        for (agent_id, agent_name) in ops.get_all_agent_names() {
+            let marker = if Some(agent_id) == current_id { " ->" } else { "   " };
+            let Some(agent) = ops.get_agent_info(agent_id) else {
+                continue;
+            };
+            let Ok(conn) = agent.connection.try_lock() else {
+                lines.push(format!("{} {}: busy (try again once the reply finishes)", marker, capitalize_first(&agent_name)));
+                continue;
+            };
+            let usage = &conn.usage;
+            let agent_total = usage.total();
+            lines.push(format!("{} {}: {} tokens", marker, capitalize_first(&agent_name), format_tokens(agent_total.total_tokens())));
+            if agent_total.requests == 0 {
+                continue;
             }
-        });
+            let cost = usage.estimated_cost(prices);
+            lines.push(format!("      replies: {}", usage.replies.describe()));
+            if usage.summaries.requests > 0 {
+                lines.push(format!("      summaries: {}", usage.summaries.describe()));
+            }
+            if let Some(last) = &usage.last {
+                lines.push(format!("      last request: {}", last.describe()));
+            }
+            lines.push(format!("      estimated cost: {}", cost.describe(!prices.is_empty())));
 
-        ops.display_message("Summarization task spawned.".to_string());
+            total.merge(&agent_total);
+            total_cost.cost += cost.cost;
+            for model in cost.unpriced {
+                if !total_cost.unpriced.contains(&model) {
+                    total_cost.unpriced.push(model);
+                }
+            }
+        }
+        lines.push(format!(
+            " Total: {} tokens over {} requests, {}",
+            format_tokens(total.total_tokens()),
+            total.requests,
+            total_cost.describe(!prices.is_empty()),
+        ));
+
+        ops.display_message(lines.join("\n"));
+        CommandResult::Continue
+    }
+}
+
+/// # SummarizeCommand
+///
+/// **Summary:**
+/// Command to trigger conversation history summarization for the current agent.
+#[derive(Debug, Clone)]
+pub struct SummarizeCommand;
+
+impl SummarizeCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Command for SummarizeCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent_id) = ops.get_current_agent_id() else {
+            return no_agent_open(ops);
+        };
+
+        // Refuse up front when the mode can be read now; the job checks again otherwise
+        let refusal = ops.current_agent_info()
+            .and_then(|agent| agent.connection.try_lock().ok().and_then(|conn| summarize_refusal(&conn.conversation)));
+        if let Some(refusal) = refusal {
+            ops.display_warning(refusal);
+            return CommandResult::Continue;
+        }
+
+        let job = GenerationJob::new("summarize", agent_id, |ctx| async move {
+            let mut conn = match ctx.connection.try_lock() {
+                Ok(conn) => conn,
+                Err(_) => {
+                    ctx.progress("waiting for the current reply to finish");
+                    ctx.connection.lock().await
+                }
+            };
+            if let Some(refusal) = summarize_refusal(&conn.conversation) {
+                return Err(refusal);
+            }
+            let outcome = conn.summarize_history().await.map_err(|e| e.to_string())?;
+            let Some(outcome) = outcome else {
+                return Ok("history is already short enough; nothing to summarize".to_string());
+            };
+            conn.save_persona_history()
+                .map_err(|e| format!("Failed to save persona history: {}", e))?;
+            Ok(match outcome.elided.describe() {
+                Some(note) => format!("history summarized ({})\n{}", outcome.describe(), note),
+                None => format!("history summarized ({})", outcome.describe()),
+            })
+        });
+        ops.spawn_job(job);
+
+        CommandResult::Continue
+    }
+}
+
+/// Why `summarize` is refused for a conversation, if it is (summarize mode `off`)
+fn summarize_refusal(conversation: &GrokConversation) -> Option<String> {
+    (conversation.summarize_mode() == SummarizeMode::Off).then(|| format!(
+        "Summarization is off for {}, so its history is never rewritten. `set summarize manual` allows `summarize` again.",
+        conversation.persona.name
+    ))
+}
+
+/// # QuitCommand
+///
+/// **Summary:**
+/// Command to gracefully shut down the application.
+///
+/// **Details:**
+/// While a focus block runs, the first quit warns instead of exiting.
+#[derive(Debug, Clone)]
+pub struct QuitCommand;
+
+impl QuitCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Command for QuitCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        // The first quit during a focus block only warns; the block resumes on next start
+        if let Some(timer) = ops.focus_timer_mut().filter(|timer| !timer.quit_warned) {
+            timer.quit_warned = true;
+            let warning = format!(
+                "A {} is running ({}). It resumes when you restart; quit again to exit anyway.",
+                timer.block.describe(), timer.block.countdown()
+            );
+            ops.display_warning(warning);
+            return CommandResult::Continue;
+        }
+        CommandResult::Shutdown
+    }
+}
+
+/// # HelpCommand
+///
+/// **Summary:**
+/// Command to show the getting-started command list.
+#[derive(Debug, Clone, Default)]
+pub struct HelpCommand;
+
+impl HelpCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Command for HelpCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        ops.display_message(HELP_TEXT.to_string());
+        CommandResult::Continue
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ListAgentsCommand;
+
+impl ListAgentsCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Command for ListAgentsCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let mut personas: Vec<String> = ops.get_all_personas()
+            .iter()
+            .map(|persona| if persona.is_builtin {
+                format!("{} (built-in)", persona.name)
+            } else {
+                persona.name.clone()
+            })
+            .collect();
+        personas.sort();
+        ops.display_message(format!("Available personas: {}", personas.join(", ")));
+        CommandResult::Continue
+    }
+}
+
+/// # ExportBuiltinPersonasCommand
+///
+/// **Summary:**
+/// Command to write the embedded personas to disk so they can be customized.
+///
+/// **Fields:**
+/// - `dir`: Target personas directory
+/// - `inline`: Keep each prompt in its YAML instead of a separate `prompt.md`
+#[derive(Debug, Clone)]
+pub struct ExportBuiltinPersonasCommand {
+    dir: String,
+    inline: bool,
+}
+
+impl ExportBuiltinPersonasCommand {
+    pub fn new(dir: String, inline: bool) -> Self {
+        Self { dir, inline }
+    }
+}
+
+impl Command for ExportBuiltinPersonasCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        match export_builtins(Path::new(&self.dir), self.inline) {
+            Ok(written) if written.is_empty() => {
+                ops.display_message(format!("All built-in personas already exist in {}", self.dir));
+            }
+            Ok(written) => {
+                for path in &written {
+                    ops.display_message(format!("Exported {}", path.display()));
+                }
+                ops.display_message("Restart to load the exported personas.".to_string());
+            }
+            Err(e) => {
+                log_error!("Failed to export built-in personas: {}", e);
+                return CommandResult::Error(format!("Failed to export built-in personas: {}", e));
+            }
+        }
+
+        CommandResult::Continue
+    }
+}
+
+/// # PersonaHistoryCommand
+///
+/// **Summary:**
+/// Command to list recorded prompt/setting changes for a persona.
+///
+/// **Fields:**
+/// - `persona_name`: Persona whose changelog to show
+#[derive(Debug, Clone)]
+pub struct PersonaHistoryCommand {
+    persona_name: String,
+}
+
+impl PersonaHistoryCommand {
+    pub fn new(persona_name: String) -> Self {
+        Self { persona_name }
+    }
+}
+
+impl Command for PersonaHistoryCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let entries = match read_changelog(&self.persona_name) {
+            Ok(entries) => entries,
+            Err(e) => return CommandResult::Error(format!("Failed to read changelog: {}", e)),
+        };
+
+        if entries.is_empty() {
+            ops.display_message(format!("No recorded changes for {}", self.persona_name));
+            return CommandResult::Continue;
+        }
+
+        ops.display_message(format!("Changelog for {} ({} entries):", self.persona_name, entries.len()));
+        for entry in entries {
+            ops.display_message(format!("  {}", entry.describe()));
+        }
+        CommandResult::Continue
+    }
+}
+
+/// # OverviewCommand
+///
+/// **Summary:**
+/// Command to show one table of every persona's history on disk.
+///
+/// **Fields:**
+/// - `sort`: Column to sort by (`persona` when None)
+/// - `json`: Print the rows as JSON instead, for scripts
+///
+/// **Details:**
+/// Covers loaded personas plus any persona directory with a history folder,
+/// whether or not an agent is open. Only history headers and file sizes are
+/// read; a persona whose files are missing or corrupt gets "?" and a warning
+/// instead of failing the table.
+#[derive(Debug, Clone)]
+pub struct OverviewCommand {
+    sort: Option<String>,
+    json: bool,
+}
+
+impl OverviewCommand {
+    pub fn new(sort: Option<String>, json: bool) -> Self {
+        Self { sort, json }
+    }
+}
+
+impl Command for OverviewCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let sort = match self.sort.as_deref().map(str::parse::<OverviewSort>) {
+            Some(Ok(sort)) => sort,
+            Some(Err(_)) => {
+                let columns: Vec<String> = OverviewSort::iter().map(|column| column.to_string()).collect();
+                return CommandResult::Error(format!("Unknown column; sort by one of: {}", columns.join(", ")));
+            }
+            None => OverviewSort::default(),
+        };
+
+        let root = personas_root();
+        let mut names: Vec<String> = ops.get_all_personas().iter().map(|persona| persona.name.clone()).collect();
+        names.extend(overview::personas_with_history(root));
+        let outbox = Outbox::load().map(|outbox| outbox.queued.len()).map_err(|e| e.to_string());
+
+        let mut overview = Overview::collect(root, &names, outbox);
+        overview.sort_by(sort);
+
+        if self.json {
+            match serde_json::to_string_pretty(&overview) {
+                Ok(json) => ops.display_message(json),
+                Err(e) => return CommandResult::Error(format!("Failed to encode overview: {}", e)),
+            }
+        } else {
+            ops.display_message(format!("Overview of {} persona(s), by {}:", overview.personas.len(), sort));
+            ops.display_message(overview.render_table(Zone::default()));
+        }
+        CommandResult::Continue
+    }
+}
+
+/// # InsightsCommand
+///
+/// **Summary:**
+/// Command to show a month's local usage report, or write it as Markdown.
+///
+/// **Fields:**
+/// - `month`: Month as YYYY-MM (the current one when None)
+/// - `export`: Markdown file to write instead of showing the report
+///
+/// **Details:**
+/// Everything is computed from the event log and focus log already on disk,
+/// in local time; nothing leaves the machine. Missing logs give empty
+/// figures and a note rather than an error.
+#[derive(Debug, Clone)]
+pub struct InsightsCommand {
+    month: Option<String>,
+    export: Option<String>,
+}
+
+impl InsightsCommand {
+    pub fn new(month: Option<String>, export: Option<String>) -> Self {
+        Self { month, export }
+    }
+}
+
+impl Command for InsightsCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let zone = Zone::default();
+        let month = match self.month.as_deref().map(Month::parse) {
+            Some(Ok(month)) => month,
+            Some(Err(e)) => return CommandResult::Error(e),
+            None => Month::containing(chrono::Utc::now(), zone),
+        };
+        let insights = MonthInsights::collect(&InsightSources::configured(), month, zone, &GLOBAL_CONFIG.insights.model_prices);
+
+        match &self.export {
+            Some(path) => match fs::write(path, insights.to_markdown()) {
+                Ok(()) => ops.display_message(format!("Wrote insights for {} to {}", month, path)),
+                Err(e) => return CommandResult::Error(format!("Failed to write {}: {}", path, e)),
+            },
+            None => ops.display_message(insights.render()),
+        }
+        CommandResult::Continue
+    }
+}
+
+/// # ListArchivesCommand
+///
+/// **Summary:**
+/// Command to list the current agent's archives with message counts and sizes.
+///
+/// **Fields:**
+/// - `since`: Optional time argument ("last monday", "2d ago"); older archives are hidden
+#[derive(Debug, Clone)]
+pub struct ListArchivesCommand {
+    since: Option<String>,
+}
+
+impl ListArchivesCommand {
+    pub fn new(since: Option<String>) -> Self {
+        Self { since }
+    }
+}
+
+impl Command for ListArchivesCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(persona_name) = ops.current_agent_info().map(|info| info.persona_name.clone()) else {
+            return no_agent_open(ops);
+        };
+        let zone = ops.get_persona(&persona_name)
+            .map(|persona| Zone::for_persona(&persona))
+            .unwrap_or_default();
+
+        let since = match self.since.as_deref().map(|since| time_fmt::parse_time_arg(since, zone)) {
+            Some(Ok(since)) => Some(since),
+            Some(Err(e)) => return CommandResult::Error(e.to_string()),
+            None => None,
+        };
+
+        let archives = match HistoryManager::list_archives(&persona_name) {
+            Ok(archives) => archives,
+            Err(e) => return CommandResult::Error(format!("Failed to list archives: {}", e)),
+        };
+        let archives: Vec<_> = archives.into_iter()
+            .filter(|archive| match (since, archive.archived_time()) {
+                (Some(since), Some(at)) => at >= since,
+                _ => true,
+            })
+            .collect();
+
+        if archives.is_empty() {
+            ops.display_message(format!("No archives for {}", persona_name));
+            return CommandResult::Continue;
+        }
+
+        ops.display_message(format!("Archives for {} ({}):", persona_name, archives.len()));
+        for archive in archives {
+            ops.display_message(format!("  {}", archive.describe(zone)));
+        }
+        CommandResult::Continue
+    }
+}
+
+/// # ExportArchiveCommand
+///
+/// **Summary:**
+/// Command to stream an archive out to a Markdown transcript.
+///
+/// **Fields:**
+/// - `archive`: Archive path or file name under the personas root's `archives`
+/// - `dest`: Output file
+#[derive(Debug, Clone)]
+pub struct ExportArchiveCommand {
+    archive: String,
+    dest: String,
+}
+
+impl ExportArchiveCommand {
+    pub fn new(archive: String, dest: String) -> Self {
+        Self { archive, dest }
+    }
+}
+
+impl Command for ExportArchiveCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let source = HistoryManager::resolve_archive(&self.archive);
+        match HistoryManager::export_archive(&source, Path::new(&self.dest)) {
+            Ok(count) => {
+                ops.display_message(format!("Exported {} messages to {}", count, self.dest));
+                CommandResult::Continue
+            }
+            Err(e) => CommandResult::Error(format!("Failed to export {}: {}", source.display(), e)),
+        }
+    }
+}
+
+/// # ReloadKeysCommand
+///
+/// **Summary:**
+/// Command to re-read API keys from .env and swap them into all open connections.
+///
+/// **Details:**
+/// The swap happens before this returns, so the next request uses the new key.
+/// Each key is then verified in the background (5s timeout per provider) and
+/// the outcome reported to the Global pane.
+#[derive(Debug, Clone, Default)]
+pub struct ReloadKeysCommand;
+
+impl ReloadKeysCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Command for ReloadKeysCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let swapped = swap_keys();
+        ops.display_message("Keys re-read; new requests use them. Checking each with its provider...".to_string());
+
+        ops.run_in_background(Box::pin(async move {
+            let reports = futures_util::future::join_all(swapped.into_iter().map(|key| key.check())).await;
+            reports.into_iter()
+                .map(|report| BackgroundReport { msg: report.describe(), failed: report.failed() })
+                .collect()
+        }));
+
+        CommandResult::Continue
+    }
+}
+
+/// # ReloadPersonasCommand
+///
+/// **Summary:**
+/// Command to re-scan the personas directory and report what changed.
+///
+/// **Fields:**
+/// - `apply`: Also update open agents to their persona's new system prompt
+///
+/// **Details:**
+/// The TUI already reloads persona files as they change; this catches
+/// anything the watcher missed, and is the only way to reload in CLI mode.
+/// Without `apply`, open agents keep the persona they were started with.
+#[derive(Debug, Clone)]
+pub struct ReloadPersonasCommand {
+    apply: bool,
+}
+
+impl ReloadPersonasCommand {
+    pub fn new(apply: bool) -> Self {
+        Self { apply }
+    }
+}
+
+impl Command for ReloadPersonasCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let report = match ops.rescan_personas(self.apply) {
+            Ok(report) => report,
+            Err(e) => return CommandResult::Error(format!("Failed to scan personas: {}", e)),
+        };
+
+        let summary = report.summary().unwrap_or_else(|| {
+            let mut summary = format!("Personas unchanged ({} checked)", report.unchanged);
+            if self.apply {
+                summary.push_str("; open agents are up to date");
+            }
+            summary
+        });
+        ops.display_message(summary);
+        CommandResult::Continue
+    }
+}
+
+/// # MuteAgentCommand
+///
+/// **Summary:**
+/// Command to mute or unmute the current agent.
+///
+/// **Fields:**
+/// - `muted`: true to mute, false to unmute
+///
+/// **Details:**
+/// Muted agents keep streaming and saving history; only their Global pane
+/// notifications and auto-scroll are suppressed.
+#[derive(Debug, Clone)]
+pub struct MuteAgentCommand {
+    muted: bool,
+}
+
+impl MuteAgentCommand {
+    pub fn new(muted: bool) -> Self {
+        Self { muted }
+    }
+}
+
+impl Command for MuteAgentCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent) = ops.current_agent_info() else {
+            return no_agent_open(ops);
+        };
+        let (id, persona_name) = (agent.id, agent.persona_name.clone());
+
+        if ops.set_agent_muted(id, self.muted) {
+            let state = if self.muted { "muted" } else { "unmuted" };
+            ops.display_message(format!("{} {}", capitalize_first(&persona_name), state));
+        }
+
+        CommandResult::Continue
+    }
+}
+
+/// # SpeakCommand
+///
+/// **Summary:**
+/// Command to turn speaking the current agent's replies on or off (`speak on|off`).
+///
+/// **Fields:**
+/// - `speaking`: true to speak finished replies aloud, false to stop
+///
+/// **Details:**
+/// Turning it off also cuts off anything being spoken. See `utilities::speech`.
+#[derive(Debug, Clone)]
+pub struct SpeakCommand {
+    speaking: bool,
+}
+
+impl SpeakCommand {
+    pub fn new(speaking: bool) -> Self {
+        Self { speaking }
+    }
+}
+
+impl Command for SpeakCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent) = ops.current_agent_info() else {
+            return no_agent_open(ops);
+        };
+        let (id, persona_name) = (agent.id, agent.persona_name.clone());
+
+        if ops.set_agent_speaking(id, self.speaking) {
+            let state = if self.speaking { "Speaking replies aloud" } else { "Not speaking replies" };
+            ops.display_agent_message(&persona_name, state.to_string());
+        }
+
+        CommandResult::Continue
+    }
+}
+
+/// # OpenLinkCommand
+///
+/// **Summary:**
+/// Command to list the links in the current pane, or open one in the browser (`open [n]`).
+///
+/// **Fields:**
+/// - `nth`: 1 for the most recent link, 2 for the one before, ...; None lists them
+///
+/// **Details:**
+/// Links are numbered newest first, counting each URL once. Works where the
+/// terminal can't make links clickable, and in CLI mode. See `utilities::links`.
+#[derive(Debug, Clone)]
+pub struct OpenLinkCommand {
+    nth: Option<usize>,
+}
+
+impl OpenLinkCommand {
+    pub fn new(nth: Option<usize>) -> Self {
+        Self { nth }
+    }
+}
+
+/// Most links `open` lists
+const OPEN_LINK_LIMIT: usize = 10;
+
+impl Command for OpenLinkCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent) = ops.current_agent_info() else {
+            return no_agent_open(ops);
+        };
+        let persona_name = agent.persona_name.clone();
+        let limit = self.nth.map_or(OPEN_LINK_LIMIT, |nth| nth.max(OPEN_LINK_LIMIT));
+        let urls = links::recent_urls(agent.messages.iter().map(|message| message.text.as_str()), limit);
+
+        if urls.is_empty() {
+            ops.display_agent_message(&persona_name, "No links in this pane.".to_string());
+            return CommandResult::Continue;
+        }
+
+        let Some(nth) = self.nth else {
+            let mut listing = String::from("Links (newest first; `open <n>` opens one):");
+            for (index, url) in urls.iter().enumerate() {
+                listing.push_str(&format!("\n  {}. {}", index + 1, url));
+            }
+            ops.display_agent_message(&persona_name, listing);
+            return CommandResult::Continue;
+        };
+
+        let Some(url) = nth.checked_sub(1).and_then(|index| urls.get(index)) else {
+            ops.display_warning(format!("There is no link {} (this pane has {}).", nth, urls.len()));
+            return CommandResult::Continue;
+        };
+        match links::open_in_browser(url, OsInfo::new().os_type()) {
+            Ok(()) => ops.display_agent_message(&persona_name, format!("Opening {}", url)),
+            Err(e) => ops.display_warning(format!("Couldn't open {}: {}", url, e)),
+        }
+
+        CommandResult::Continue
+    }
+}
+
+/// # LogLevelCommand
+///
+/// **Summary:**
+/// Command to change log verbosity at runtime, globally or for one module path.
+///
+/// **Fields:**
+/// - `level`: New maximum verbosity
+/// - `target`: Optional module path (e.g. `grokprime_brain::grok::client`)
+#[derive(Debug, Clone)]
+pub struct LogLevelCommand {
+    level: LogLevel,
+    target: Option<String>,
+}
+
+impl LogLevelCommand {
+    pub fn new(level: LogLevel, target: Option<String>) -> Self {
+        Self { level, target }
+    }
+}
+
+impl Command for LogLevelCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        log_level::set_level(self.level, self.target.as_deref());
+        ops.display_message(format!("Log filter: {}", log_level::describe()));
+        CommandResult::Continue
+    }
+}
+
+/// # WhenceCommand
+///
+/// **Summary:**
+/// Command to show which model wrote one of the current agent's replies.
+///
+/// **Fields:**
+/// - `nth_last`: 1 for the last assistant reply, 2 for the one before, ...
+#[derive(Debug, Clone)]
+pub struct WhenceCommand {
+    nth_last: usize,
+}
+
+impl WhenceCommand {
+    pub fn new(nth_last: usize) -> Self {
+        Self { nth_last }
+    }
+}
+
+impl Command for WhenceCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent) = ops.current_agent_info() else {
+            return no_agent_open(ops);
+        };
+
+        let persona_name = agent.persona_name.clone();
+        let connection = agent.connection.clone();
+
+        let Ok(conn) = connection.try_lock() else {
+            ops.display_message("Failed to acquire connection lock.".to_string());
+            return CommandResult::Continue;
+        };
+        let report = match conn.conversation.nth_last_assistant_message(self.nth_last) {
+            None => format!("No assistant reply #{} from the end", self.nth_last),
+            Some(msg) => match &msg.meta {
+                Some(meta) => format!("Reply #{} from the end: {}", self.nth_last, meta.describe()),
+                None => format!("Reply #{} from the end has no model metadata (recorded before tracking)", self.nth_last),
+            },
+        };
+        drop(conn);
+
+        ops.display_agent_message(&persona_name, report);
+        CommandResult::Continue
+    }
+}
+
+/// # SentCommand
+///
+/// **Summary:**
+/// Command to show exactly what was sent for one of the current agent's messages.
+///
+/// **Fields:**
+/// - `nth_last`: 1 for the last sent message, 2 for the one before, ...
+///
+/// **Details:**
+/// The content is read back from the conversation history, not the record,
+/// and diffed against the typed input when the two differ.
+#[derive(Debug, Clone)]
+pub struct SentCommand {
+    nth_last: usize,
+}
+
+impl SentCommand {
+    pub fn new(nth_last: usize) -> Self {
+        Self { nth_last }
+    }
+}
+
+impl Command for SentCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent) = ops.current_agent_info() else {
+            return no_agent_open(ops);
+        };
+
+        let persona_name = agent.persona_name.clone();
+        let Some(record) = agent.sent.iter().rev().nth(self.nth_last - 1).cloned() else {
+            ops.display_agent_message(&persona_name, format!("No sent message #{} from the end", self.nth_last));
+            return CommandResult::Continue;
+        };
+
+        let Ok(conn) = agent.connection.try_lock() else {
+            ops.display_message("Failed to acquire connection lock.".to_string());
+            return CommandResult::Continue;
+        };
+        let in_history = conn.local_history().iter()
+            .rev()
+            .find(|msg| msg.role == "user" && msg.content == record.send)
+            .map(|msg| msg.content.clone());
+        drop(conn);
+
+        let mut lines = vec![format!("Sent message #{} from the end:", self.nth_last)];
+        match &in_history {
+            Some(content) => lines.push(content.clone()),
+            None => lines.push(format!(
+                "(no longer in history: dropped as a duplicate or summarized away)\n{}", record.send
+            )),
+        }
+        if record.send != record.typed {
+            lines.push(format!("Changed by: {}. Typed {} sent:", record.steps.join(", "), glyph("→", "versus")));
+            lines.extend(line_diff(&record.typed, &record.send));
+        }
+
+        ops.display_agent_message(&persona_name, lines.join("\n"));
+        CommandResult::Continue
+    }
+}
+
+/// # GlobalFilterCommand
+///
+/// **Summary:**
+/// Command to restrict the Global pane to a persona, errors, or command output.
+///
+/// **Fields:**
+/// - `filter`: Filter argument, or None to show everything again
+#[derive(Debug, Clone)]
+pub struct GlobalFilterCommand {
+    filter: Option<String>,
+}
+
+impl GlobalFilterCommand {
+    pub fn new(filter: Option<String>) -> Self {
+        Self { filter }
+    }
+}
+
+impl Command for GlobalFilterCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        ops.set_global_filter(self.filter.as_deref().map(GlobalFilter::parse));
+        CommandResult::Continue
+    }
+}
+
+/// # GlobalClearCommand
+///
+/// **Summary:**
+/// Command to empty the Global pane.
+#[derive(Debug, Clone, Default)]
+pub struct GlobalClearCommand;
+
+impl GlobalClearCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Command for GlobalClearCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        ops.clear_global_messages();
+        CommandResult::Continue
+    }
+}
+
+/// # SetLayoutCommand
+///
+/// **Summary:**
+/// Command to switch the TUI between split, single-pane, stacked, and automatic layout.
+#[derive(Debug, Clone)]
+pub struct SetLayoutCommand {
+    mode: LayoutMode,
+}
+
+impl SetLayoutCommand {
+    pub fn new(mode: LayoutMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl Command for SetLayoutCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        if ops.set_layout_mode(self.mode) {
+            ops.display_message(match self.mode {
+                LayoutMode::Auto => format!(
+                    "Layout: auto (single pane below {} columns)", GLOBAL_CONFIG.tui.single_pane_below_width
+                ),
+                LayoutMode::Split => "Layout: split".to_string(),
+                LayoutMode::Single => "Layout: single pane (Ctrl+G shows System)".to_string(),
+                LayoutMode::Stacked => "Layout: stacked (one pane per agent, Alt+L switches back to split)".to_string(),
+            });
+        }
+        CommandResult::Continue
+    }
+}
+
+/// # TimestampsCommand
+///
+/// **Summary:**
+/// Command to show or hide when each agent pane message was added (`timestamps [on|off]`).
+///
+/// **Fields:**
+/// - `show`: true shows, false hides, None toggles
+#[derive(Debug, Clone)]
+pub struct TimestampsCommand {
+    show: Option<bool>,
+}
+
+impl TimestampsCommand {
+    pub fn new(show: Option<bool>) -> Self {
+        Self { show }
+    }
+}
+
+impl Command for TimestampsCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        if let Some(shown) = ops.set_show_timestamps(self.show) {
+            ops.display_message(format!("Timestamps {}", if shown { "on" } else { "off" }));
+        }
+        CommandResult::Continue
+    }
+}
+
+/// # ExperimentStartCommand
+///
+/// **Summary:**
+/// Command to start an A/B prompt experiment for the persona named in the variant YAML.
+///
+/// **Fields:**
+/// - `name`: Experiment name
+/// - `variant_path`: Path to the variant B persona YAML
+///
+/// **Details:**
+/// Only agents created after the experiment starts are assigned a variant.
+#[derive(Debug, Clone)]
+pub struct ExperimentStartCommand {
+    name: String,
+    variant_path: String,
+}
+
+impl ExperimentStartCommand {
+    pub fn new(name: String, variant_path: String) -> Self {
+        Self { name, variant_path }
+    }
+}
+
+impl Command for ExperimentStartCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let variant = match Persona::from_yaml_file(Path::new(&self.variant_path)) {
+            Ok(variant) => variant,
+            Err(e) => {
+                return CommandResult::Error(format!("Failed to load variant {}: {}", self.variant_path, e));
+            }
+        };
+
+        if ops.get_persona(&variant.name).is_none() {
+            return CommandResult::Error(format!(
+                "Variant persona '{}' doesn't match any loaded persona", variant.name
+            ));
+        }
+
+        let experiment = Experiment::new(
+            self.name.clone(),
+            variant.name.clone(),
+            self.variant_path.clone(),
+            variant.system_prompt,
+        );
+
+        if let Err(e) = ops.experiments_mut().start(experiment) {
+            log_error!("Failed to start experiment {}: {}", self.name, e);
+            return CommandResult::Error(format!("Failed to start experiment: {}", e));
+        }
+
+        ops.display_message(format!(
+            "Experiment '{}' started: new '{}' agents alternate between variant A (current prompt) and B ({})",
+            self.name, variant.name, self.variant_path
+        ));
+        CommandResult::Continue
+    }
+}
+
+/// # ExperimentStatusCommand
+///
+/// **Summary:**
+/// Command to show assignment and rating counts for every active experiment.
+#[derive(Debug, Clone, Default)]
+pub struct ExperimentStatusCommand;
+
+impl ExperimentStatusCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Command for ExperimentStatusCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let mut lines = Vec::new();
+        for experiment in ops.experiments().all() {
+            lines.push(format!("Experiment '{}' on {}:", experiment.name, experiment.persona_name));
+            lines.extend(experiment.tally().iter().map(|tally| tally.describe()));
+        }
+
+        if lines.is_empty() {
+            ops.display_message("No active experiments".to_string());
+            return CommandResult::Continue;
+        }
+
+        if let Some(tag) = ops.current_agent_info().and_then(|agent| agent.experiment.clone()) {
+            lines.push(format!("Current agent: {}", tag.label()));
+        }
+
+        for line in lines {
+            ops.display_message(line);
+        }
+        CommandResult::Continue
+    }
+}
+
+/// # ExperimentStopCommand
+///
+/// **Summary:**
+/// Command to end an experiment and print its final tally.
+///
+/// **Fields:**
+/// - `name`: Experiment to stop (optional when only one is active)
+#[derive(Debug, Clone)]
+pub struct ExperimentStopCommand {
+    name: Option<String>,
+}
+
+impl ExperimentStopCommand {
+    pub fn new(name: Option<String>) -> Self {
+        Self { name }
+    }
+}
+
+impl Command for ExperimentStopCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let experiment = match ops.experiments_mut().stop(self.name.as_deref()) {
+            Ok(experiment) => experiment,
+            Err(e) => return CommandResult::Error(format!("Failed to stop experiment: {}", e)),
+        };
+
+        ops.display_message(format!(
+            "Experiment '{}' on {} stopped. Results saved to {}",
+            experiment.name,
+            experiment.persona_name,
+            Experiment::path(&experiment.name).display()
+        ));
+        for tally in experiment.tally() {
+            ops.display_message(tally.describe());
+        }
+        CommandResult::Continue
+    }
+}
+
+/// # RateConversationCommand
+///
+/// **Summary:**
+/// Command to record a thumbs-up/down for the current agent's experiment conversation.
+///
+/// **Fields:**
+/// - `rating`: Good or bad
+#[derive(Debug, Clone)]
+pub struct RateConversationCommand {
+    rating: Rating,
+}
+
+impl RateConversationCommand {
+    pub fn new(rating: Rating) -> Self {
+        Self { rating }
+    }
+}
+
+impl Command for RateConversationCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent) = ops.current_agent_info() else {
+            return no_agent_open(ops);
+        };
+
+        let (id, persona_name) = (agent.id, agent.persona_name.clone());
+        let Some(tag) = agent.experiment.clone() else {
+            ops.display_agent_message(&persona_name, "This conversation isn't part of an experiment".to_string());
+            return CommandResult::Continue;
+        };
+
+        let Some(experiment) = ops.experiments_mut().by_name_mut(&tag.experiment) else {
+            return CommandResult::Error(format!("Experiment '{}' is no longer active", tag.experiment));
+        };
+
+        experiment.rate(id, self.rating);
+        if let Err(e) = experiment.save() {
+            log_error!("Failed to save experiment {}: {}", experiment.name, e);
+        }
+
+        ops.display_agent_message(&persona_name, format!("Rated {:?} ({})", self.rating, tag.label()));
+        CommandResult::Continue
+    }
+}
+
+/// # StrictRefusedCommand
+///
+/// **Summary:**
+/// Stands in for a command strict mode refuses, reporting why instead of running it.
+///
+/// **Fields:**
+/// - `command`: The refused action's name
+/// - `effect`: What it would have done
+#[derive(Debug, Clone)]
+pub struct StrictRefusedCommand {
+    command: &'static str,
+    effect: ActionEffect,
+}
+
+impl Command for StrictRefusedCommand {
+    fn execute(&self, _ops: &mut dyn AgentOperations) -> CommandResult {
+        log_info!("Strict mode refused {}", self.command);
+        CommandResult::Error(strict::refusal(self.command, self.effect))
+    }
+}
+
+/// # CheckpointedCommand
+///
+/// **Summary:**
+/// Runs a command that rewrites history after checkpointing the current agent's conversation.
+///
+/// **Fields:**
+/// - `reason`: Recorded with the checkpoint (from `InputAction::checkpoint_reason`)
+/// - `command`: The command to run once the checkpoint is written
+///
+/// **Details:**
+/// If the checkpoint can't be written, the command doesn't run. With no
+/// agent open, or the agent busy, the command runs as-is and reports that itself.
+#[derive(Debug)]
+pub struct CheckpointedCommand {
+    reason: CheckpointReason,
+    command: Box<dyn Command>,
+}
+
+impl Command for CheckpointedCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent) = ops.current_agent_info() else {
+            return self.command.execute(ops);
+        };
+        let connection = agent.connection.clone();
+
+        let written = match connection.try_lock() {
+            Ok(conn) => conn.checkpoint(self.reason),
+            Err(_) => return self.command.execute(ops),
+        };
+        if let Err(e) = written {
+            return CommandResult::Error(format!("Not running {} without a checkpoint: {}", self.reason, e));
+        }
+        self.command.execute(ops)
+    }
+}
+
+/// # ToolGatedCommand
+///
+/// **Summary:**
+/// Runs a command only if the current agent's persona has the tool it needs.
+///
+/// **Fields:**
+/// - `tool`: Required tool (from `InputAction::required_tool`)
+/// - `command`: The command to run if the persona has it
+///
+/// **Details:**
+/// The persona is looked up by name, so an edited `tools` list applies as soon
+/// as it's reloaded. With no agent open the command runs as-is and reports that itself.
+#[derive(Debug)]
+pub struct ToolGatedCommand {
+    tool: Tool,
+    command: Box<dyn Command>,
+}
+
+impl Command for ToolGatedCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(persona_name) = ops.current_agent_info().map(|agent| agent.persona_name.clone()) else {
+            return self.command.execute(ops);
+        };
+        let allowed = ops.get_persona(&persona_name)
+            .is_none_or(|persona| ToolSet::for_persona(&persona).allows(self.tool));
+        if allowed {
+            return self.command.execute(ops);
+        }
+
+        log_info!("Refused a {} command for {}: not in its tools", self.tool, persona_name);
+        if let Some(agent) = ops.current_agent_info_mut() {
+            agent.add_message(format!(
+                "This agent doesn't have the {} tool. Add `{}` to `tools` in the {} persona to allow it.",
+                self.tool, self.tool, persona_name
+            ));
+        }
+        CommandResult::Continue
+    }
+}
+
+#[derive(Debug)]
+struct UnimplementedCommand {
+    feature: String,
+}
+
+impl Command for UnimplementedCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        ops.display_message(format!("Feature not yet implemented: {}", self.feature));
+        CommandResult::Continue
+    }
+}
+
+/// # TweetCommand
+///
+/// **Summary:**
+/// Command to preview a tweet and hold it until `tweet confirm`.
+#[derive(Debug, Clone)]
+struct TweetCommand {
+    text: String,
+}
+
+impl Command for TweetCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let preview = TweetPreview::single(self.text.clone());
+        ops.display_message(preview.render());
+        preview::set_pending(preview);
+        CommandResult::Continue
+    }
+}
+
+/// # ConfirmTweetCommand
+///
+/// **Summary:**
+/// Command to post the pending preview as a background job.
+///
+/// **Details:**
+/// The job posts `preview.parts()` as-is, the same parts the preview rendered.
+#[derive(Debug, Clone, Default)]
+pub struct ConfirmTweetCommand;
+
+impl ConfirmTweetCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Command for ConfirmTweetCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent_id) = ops.get_current_agent_id() else {
+            return no_agent_open(ops);
+        };
+        let Some(pending) = preview::take_pending() else {
+            ops.display_message("Nothing to post. Preview one with 'tweet <text>' or 'draft <idea>'.".to_string());
+            return CommandResult::Continue;
+        };
+
+        let job = GenerationJob::new("tweet", agent_id, |ctx| async move {
+            let twitter = TwitterConnection::try_new(Arc::new(ChannelOutput::new(&ctx.tx)))?;
+            let posted = twitter.post_thread(pending.parts()).await.map_err(|e| e.to_string())?;
+            Ok(format!("posted {} tweet(s)", posted.len()))
+        });
+        ops.spawn_job(job);
+        CommandResult::Continue
+    }
+}
+
+/// # CancelTweetCommand
+///
+/// **Summary:**
+/// Command to discard the pending preview.
+#[derive(Debug, Clone, Default)]
+pub struct CancelTweetCommand;
+
+impl CancelTweetCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Command for CancelTweetCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        match preview::take_pending() {
+            Some(_) => ops.display_message("Pending tweet discarded.".to_string()),
+            None => ops.display_message("No pending tweet.".to_string()),
+        }
+        CommandResult::Continue
+    }
+}
+
+/// # QueueTweetCommand
+///
+/// **Summary:**
+/// Command to defer a tweet to the persistent outbox until quota is available.
+#[derive(Debug, Clone)]
+pub struct QueueTweetCommand {
+    text: String,
+}
+
+impl QueueTweetCommand {
+    pub fn new(text: String) -> Self {
+        Self { text }
+    }
+}
+
+impl Command for QueueTweetCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let mut outbox = match Outbox::load() {
+            Ok(outbox) => outbox,
+            Err(e) => return CommandResult::Error(format!("Failed to read outbox: {}", e)),
+        };
+        outbox.enqueue(vec![self.text.clone()]);
+        if let Err(e) = outbox.save() {
+            return CommandResult::Error(format!("Failed to save outbox: {}", e));
+        }
+
+        ops.display_message(format!("Tweet queued ({} in outbox)", outbox.queued.len()));
+        if let Some(preview) = spellcheck::check_and_annotate(&self.text) {
+            ops.display_message(preview);
+        }
+        CommandResult::Continue
+    }
+}
+
+/// # CheckTweetCommand
+///
+/// **Summary:**
+/// Command to preview a tweet with spelling, length, and punctuation hints.
+#[derive(Debug, Clone)]
+pub struct CheckTweetCommand {
+    text: String,
+}
+
+impl CheckTweetCommand {
+    pub fn new(text: String) -> Self {
+        Self { text }
+    }
+}
+
+impl Command for CheckTweetCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        match spellcheck::check_and_annotate(&self.text) {
+            Some(preview) => ops.display_message(preview),
+            None => ops.display_message("Tweet spellcheck is disabled (twitter.spellcheck).".to_string()),
+        }
+        CommandResult::Continue
+    }
+}
+
+/// # IgnoreWordCommand
+///
+/// **Summary:**
+/// Command to stop the tweet spellcheck from flagging a word.
+#[derive(Debug, Clone)]
+pub struct IgnoreWordCommand {
+    word: String,
+}
+
+impl IgnoreWordCommand {
+    pub fn new(word: String) -> Self {
+        Self { word }
+    }
+}
+
+impl Command for IgnoreWordCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        match spellcheck::ignore_word(&self.word) {
+            Ok(()) => {
+                ops.display_message(format!("\"{}\" added to the custom dictionary", self.word));
+                CommandResult::Continue
+            }
+            Err(e) => CommandResult::Error(format!("Failed to update custom dictionary: {}", e)),
+        }
+    }
+}
+
+/// # TweetQuotaCommand
+///
+/// **Summary:**
+/// Command to show the remaining post quota and the outbox size.
+#[derive(Debug, Clone, Default)]
+pub struct TweetQuotaCommand;
+
+impl TweetQuotaCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Command for TweetQuotaCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let log = match PostLog::load() {
+            Ok(log) => log,
+            Err(e) => return CommandResult::Error(format!("Failed to read tweets log: {}", e)),
+        };
+        let queued = Outbox::load().map(|outbox| outbox.queued.len()).unwrap_or(0);
+
+        ops.display_message(format!("Tweet quota: {}", log.quota().describe()));
+        ops.display_message(format!("Outbox: {} queued post(s)", queued));
+        CommandResult::Continue
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DraftTweetCommand {
+    text: String,
+}
+
+impl Command for DraftTweetCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent) = ops.current_agent_info_mut() else {
+            return no_agent_open(ops);
+        };
+
+        // An explicit language wins; otherwise the idea's own language, then the conversation's
+        let resolved = agent.connection.try_lock().ok().and_then(|conn| conn.conversation.language());
+        let draft_language = match resolved {
+            Some((lang, LanguageSource::Override | LanguageSource::Persona)) => Some(lang),
+            detected => language::detect(&self.text).or(detected.map(|(lang, _)| lang)),
+        };
+
+        let sent = Pipeline::draft(draft_language).run(&self.text);
+        let define_tweet = sent.send.clone();
+        let idea = sent.typed.clone();
+        agent.echo_sent(sent);
+        agent.is_waiting = true;
+        agent.request_started = Some(std::time::Instant::now());
+        let mut reply = agent.open_reply_stream();
+
+        let job = GenerationJob::new("draft", agent.id, |ctx| async move {
+            let mut connection = ctx.connection.lock().await;
+            // Detection sees the idea, not the English template around it
+            if connection.conversation.add_user_message(&define_tweet) {
+                connection.conversation.observe_language(&idea);
+            }
+            // The draft streams into the agent pane like any reply; nudges steer chat, not tweets
+            if let Err(e) = connection.handle_response_streaming_without_nudges(&mut reply).await {
+                if !reply.is_finished() {
+                    let _ = reply.fail(format!("{}", e));
+                }
+                return Err(e.to_string());
+            }
+
+            let draft = connection.local_history().last().map(|m| m.content.clone()).unwrap_or_default();
+            let preview = TweetPreview::from_draft(&draft);
+            let _ = reply.info(preview.render());
+            preview::set_pending(preview);
+            Ok("draft ready for review".to_string())
+        }).streaming_reply();
+        ops.spawn_job(job);
+
+        CommandResult::Continue
+    }
+}
+
+/// # ListJobsCommand
+///
+/// **Summary:**
+/// Command to list running generation jobs.
+#[derive(Debug, Clone, Default)]
+pub struct ListJobsCommand;
+
+impl ListJobsCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Command for ListJobsCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        if ops.jobs().is_empty() {
+            ops.display_message("No jobs running.".to_string());
+            return CommandResult::Continue;
+        }
+
+        let lines: Vec<String> = ops.jobs().running().iter()
+            .map(|job| {
+                let agent = ops.get_agent_info(job.agent)
+                    .map(|agent| capitalize_first(&agent.persona_name))
+                    .unwrap_or_else(|| "<unknown>".to_string());
+                format!(" {}. {} for {} ({}s)", job.id, job.name, agent, job.started.elapsed().as_secs())
+            })
+            .collect();
+
+        ops.display_message(format!("Running jobs:\n{}", lines.join("\n")));
+        CommandResult::Continue
+    }
+}
+
+/// # CancelJobCommand
+///
+/// **Summary:**
+/// Command to abort a running generation job by number.
+#[derive(Debug, Clone)]
+pub struct CancelJobCommand {
+    pub id: JobId,
+}
+
+impl CancelJobCommand {
+    pub fn new(id: JobId) -> Self {
+        Self { id }
+    }
+}
+
+impl Command for CancelJobCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(job) = ops.jobs_mut().cancel(self.id) else {
+            ops.display_message(format!("No running job {}. See 'jobs'.", self.id));
+            return CommandResult::Continue;
+        };
+
+        // A cancelled draft never sends Complete, so the pane has to stop waiting here
+        if let Some(agent) = ops.get_agent_info_mut(job.agent).filter(|_| job.streams_reply) {
+            agent.flush_reply_text();
+            agent.add_message("(cancelled)");
+            agent.is_waiting = false;
+            agent.reply_stream = None;
+            agent.request_started = None;
+        }
+
+        ops.display_message(format!("Cancelled job {} ({}).", job.id, job.name));
+        CommandResult::Continue
+    }
+}
+
+/// # StopCommand
+///
+/// **Summary:**
+/// Command to cancel the current agent's in-flight reply (`stop`, Ctrl+C).
+///
+/// **Details:**
+/// A tweet draft streams into the pane as a job, so with no reply running
+/// the agent's streaming job is cancelled instead. A reply (or draft)
+/// already saved to history isn't stopped while it summarizes. Anything
+/// being spoken aloud (`speak on`) is cut off too.
+#[derive(Debug, Clone, Default)]
+pub struct StopCommand;
+
+impl StopCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Command for StopCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let silenced = ops.stop_speech();
+        let Some(agent) = ops.current_agent_info_mut() else {
+            return no_agent_open(ops);
+        };
+        match agent.cancel_reply() {
+            CancelReply::Cancelled => return CommandResult::Continue,
+            CancelReply::Committed => {
+                ops.display_message("The reply is already saved; it can't be stopped while history is summarized.".to_string());
+                return CommandResult::Continue;
+            }
+            CancelReply::NotRunning => {}
+        }
+
+        let agent_id = agent.id;
+        let draft = ops.jobs().running().iter()
+            .find(|job| job.agent == agent_id && job.streams_reply)
+            .map(|job| job.id);
+        match draft {
+            Some(id) => CancelJobCommand::new(id).execute(ops),
+            None if silenced => CommandResult::Continue,
+            None => {
+                ops.display_message("Nothing to stop: no reply is running.".to_string());
+                CommandResult::Continue
+            }
+        }
+    }
+}
+
+/// # MacroRecordCommand
+///
+/// **Summary:**
+/// Command to start capturing submitted lines into a named macro.
+#[derive(Debug, Clone)]
+pub struct MacroRecordCommand {
+    pub name: String,
+    pub trusted: bool,
+}
+
+impl MacroRecordCommand {
+    pub fn new(name: String, trusted: bool) -> Self {
+        Self { name, trusted }
+    }
+}
+
+impl Command for MacroRecordCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        if let Some(recording) = ops.macro_recording() {
+            let msg = format!("Already recording macro '{}'. Finish it with 'macro stop'.", recording.name);
+            ops.display_message(msg);
+            return CommandResult::Continue;
+        }
+
+        let recording = MacroRecording {
+            name: self.name.clone(),
+            steps: Vec::new(),
+            trusted: self.trusted,
+        };
+        if ops.start_macro_recording(recording) {
+            ops.display_message(format!(
+                "Recording macro '{}'. Submit commands, then 'macro stop' to save.", self.name
+            ));
+        }
+        CommandResult::Continue
+    }
+}
+
+/// # MacroStopCommand
+///
+/// **Summary:**
+/// Command to stop a running replay, or finish the recording and save it.
+#[derive(Debug, Clone)]
+pub struct MacroStopCommand {
+    pub trusted: bool,
+}
+
+impl MacroStopCommand {
+    pub fn new(trusted: bool) -> Self {
+        Self { trusted }
+    }
+}
+
+impl Command for MacroStopCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        if let Some(replay) = ops.stop_macro_replay() {
+            ops.display_message(format!(
+                "Stopped macro '{}' with {} step(s) left.", replay.name, replay.remaining.len()
+            ));
+            return CommandResult::Continue;
+        }
+
+        let Some(recording) = ops.take_macro_recording() else {
+            ops.display_message("Not recording a macro.".to_string());
+            return CommandResult::Continue;
+        };
+
+        if recording.steps.is_empty() {
+            ops.display_message(format!("Nothing recorded; macro '{}' not saved.", recording.name));
+            return CommandResult::Continue;
+        }
+
+        let mut store = match MacroStore::load() {
+            Ok(store) => store,
+            Err(e) => return CommandResult::Error(format!("Failed to read macros: {}", e)),
+        };
+        let trusted = recording.trusted || self.trusted;
+        let count = recording.steps.len();
+        store.macros.insert(recording.name.clone(), Macro { steps: recording.steps, trusted });
+        if let Err(e) = store.save() {
+            return CommandResult::Error(format!("Failed to save macros: {}", e));
+        }
+
+        ops.display_message(format!(
+            "Saved macro '{}' ({} step{}{}).",
+            recording.name, count, if count == 1 { "" } else { "s" }, if trusted { ", trusted" } else { "" }
+        ));
+        CommandResult::Continue
+    }
+}
+
+/// # MacroRunCommand
+///
+/// **Summary:**
+/// Command to replay a saved macro.
+///
+/// **Details:**
+/// Untrusted macros with destructive steps only run when confirmed.
+#[derive(Debug, Clone)]
+pub struct MacroRunCommand {
+    pub name: String,
+    pub confirmed: bool,
+}
+
+impl MacroRunCommand {
+    pub fn new(name: String, confirmed: bool) -> Self {
+        Self { name, confirmed }
+    }
+}
+
+impl Command for MacroRunCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let store = match MacroStore::load() {
+            Ok(store) => store,
+            Err(e) => return CommandResult::Error(format!("Failed to read macros: {}", e)),
+        };
+        let Some(saved) = store.macros.get(&self.name) else {
+            ops.display_message(format!("No macro named '{}'. See 'macro list'.", self.name));
+            return CommandResult::Continue;
+        };
+
+        if !saved.trusted && !self.confirmed {
+            let destructive = saved.destructive_steps(&UserInput::new_for_tui());
+            if !destructive.is_empty() {
+                ops.display_message(format!(
+                    "Macro '{}' includes destructive steps ({}). Run it with 'macro run {} confirm'.",
+                    self.name, destructive.join("; "), self.name
+                ));
+                return CommandResult::Continue;
+            }
+        }
+
+        if ops.start_macro_replay(MacroReplay::new(&self.name, &saved.steps)) {
+            ops.display_message(format!(
+                "Running macro '{}' ({} steps). 'macro stop' aborts it.", self.name, saved.steps.len()
+            ));
+        }
+        CommandResult::Continue
+    }
+}
+
+/// # MacroListCommand
+///
+/// **Summary:**
+/// Command to list saved macros and their steps.
+#[derive(Debug, Clone, Default)]
+pub struct MacroListCommand;
+
+impl MacroListCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Command for MacroListCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let store = match MacroStore::load() {
+            Ok(store) => store,
+            Err(e) => return CommandResult::Error(format!("Failed to read macros: {}", e)),
+        };
+        if store.macros.is_empty() {
+            ops.display_message("No macros saved. Record one with 'macro record <name>'.".to_string());
+            return CommandResult::Continue;
+        }
+
+        let lines: Vec<String> = store.macros.iter()
+            .map(|(name, saved)| format!(
+                " - {}{}: {}", name, if saved.trusted { " (trusted)" } else { "" }, saved.steps.join("; ")
+            ))
+            .collect();
+        ops.display_message(format!("Macros:\n{}", lines.join("\n")));
+        CommandResult::Continue
+    }
+}
+
+/// # MacroDeleteCommand
+///
+/// **Summary:**
+/// Command to delete a saved macro.
+#[derive(Debug, Clone)]
+pub struct MacroDeleteCommand {
+    pub name: String,
+}
+
+impl MacroDeleteCommand {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+impl Command for MacroDeleteCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let mut store = match MacroStore::load() {
+            Ok(store) => store,
+            Err(e) => return CommandResult::Error(format!("Failed to read macros: {}", e)),
+        };
+        if store.macros.remove(&self.name).is_none() {
+            ops.display_message(format!("No macro named '{}'.", self.name));
+            return CommandResult::Continue;
+        }
+        if let Err(e) = store.save() {
+            return CommandResult::Error(format!("Failed to save macros: {}", e));
+        }
+
+        ops.display_message(format!("Deleted macro '{}'.", self.name));
+        CommandResult::Continue
+    }
+}
+
+/// # ProfileCommand
+///
+/// **Summary:**
+/// Command to show recent command timings, or draw-loop frame times with `frames`.
+///
+/// **Details:**
+/// Entries over `tui.slow_command_ms` / `tui.slow_frame_ms` are shown as warnings.
+#[derive(Debug, Clone)]
+pub struct ProfileCommand {
+    pub frames: bool,
+}
+
+impl ProfileCommand {
+    pub fn new(frames: bool) -> Self {
+        Self { frames }
+    }
+
+    fn command_report() -> Vec<(String, bool)> {
+        let samples = profile::command_samples();
+        if samples.is_empty() {
+            return vec![("No commands timed yet.".to_string(), false)];
+        }
+
+        let mut slowest: Vec<&profile::CommandSample> = samples.iter().collect();
+        slowest.sort_by_key(|sample| std::cmp::Reverse(sample.duration));
+
+        let mut lines = vec![(format!("Slowest of the last {} commands:", samples.len()), false)];
+        lines.extend(slowest.iter().take(10).map(|sample| {
+            let slow = profile::is_slow_command(sample.duration);
+            (format!(
+                " {}{:>8.1} ms  {}{} ({}s ago)",
+                if slow { glyph("⚠ ", "slow: ") } else { "" },
+                profile::millis(sample.duration),
+                sample.command,
+                if sample.spawned_async { " +async" } else { "" },
+                sample.at.elapsed().as_secs(),
+            ), slow)
+        }));
+
+        lines.push(("Per command (count, mean, p95, max):".to_string(), false));
+        lines.extend(profile::aggregate_commands(samples.iter()).into_iter().map(|(command, stats)| {
+            let slow = profile::is_slow_command(stats.p95);
+            (format!(
+                " {}{}: {} · {:.1} ms · {:.1} ms · {:.1} ms",
+                if slow { glyph("⚠ ", "slow: ") } else { "" },
+                command,
+                stats.count,
+                profile::millis(stats.mean),
+                profile::millis(stats.p95),
+                profile::millis(stats.max),
+            ), slow)
+        }));
+        lines
+    }
+
+    fn frame_report() -> Vec<(String, bool)> {
+        let frames = profile::frame_samples();
+        let slow_count = frames.iter().filter(|frame| profile::is_slow_frame(**frame)).count();
+        let Some(stats) = profile::Stats::from_durations(frames) else {
+            return vec![("No frames timed yet (frame times are only collected in TUI mode).".to_string(), false)];
+        };
+
+        vec![
+            (format!(
+                "Last {} frames: mean {:.1} ms, p95 {:.1} ms, max {:.1} ms",
+                stats.count, profile::millis(stats.mean), profile::millis(stats.p95), profile::millis(stats.max)
+            ), false),
+            (format!(
+                "{}{} frame{} at or over {} ms",
+                if slow_count > 0 { glyph("⚠ ", "slow: ") } else { "" },
+                slow_count, if slow_count == 1 { "" } else { "s" }, GLOBAL_CONFIG.tui.slow_frame_ms
+            ), slow_count > 0),
+        ]
+    }
+}
+
+impl Command for ProfileCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let lines = if self.frames { Self::frame_report() } else { Self::command_report() };
+
+        // Consecutive lines with the same severity go out as one message
+        for run in lines.chunk_by(|a, b| a.1 == b.1) {
+            let text = run.iter().map(|(line, _)| line.as_str()).collect::<Vec<_>>().join("\n");
+            if run[0].1 {
+                ops.display_warning(text);
+            } else {
+                ops.display_message(text);
+            }
+        }
+        CommandResult::Continue
+    }
+}
+
+/// # FocusStartCommand
+///
+/// **Summary:**
+/// Command to start a focus block (`focus <minutes> [goal]`).
+#[derive(Debug, Clone)]
+pub struct FocusStartCommand {
+    minutes: u32,
+    goal: Option<String>,
+}
+
+impl FocusStartCommand {
+    pub fn new(minutes: u32, goal: Option<String>) -> Self {
+        Self { minutes, goal }
+    }
+}
+
+impl Command for FocusStartCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        if let Some(timer) = ops.focus_timer() {
+            ops.display_message(format!(
+                "A {} is already running ({}). Use 'focus cancel' first.",
+                timer.block.describe(), timer.block.countdown()
+            ));
+            return CommandResult::Continue;
+        }
+
+        let block = FocusBlock::new(self.minutes, self.goal.clone());
+        if !ops.start_focus(block.clone()) {
+            return CommandResult::Continue;
+        }
+        focus::log_focus_event(&block, FocusOutcome::Started);
+        ops.display_message(format!(
+            "Started {}. Notifications are held until it ends; {} will check in at {}.",
+            block.describe(),
+            capitalize_first(&GLOBAL_CONFIG.tui.focus_check_in_persona),
+            block.ends_at().with_timezone(&chrono::Local).format("%H:%M"),
+        ));
+        CommandResult::Continue
+    }
+}
+
+/// # FocusCancelCommand
+///
+/// **Summary:**
+/// Command to end the running focus block early (`focus cancel`).
+#[derive(Debug, Clone, Default)]
+pub struct FocusCancelCommand;
+
+impl FocusCancelCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Command for FocusCancelCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(block) = ops.end_focus() else {
+            ops.display_message("No focus block is running.".to_string());
+            return CommandResult::Continue;
+        };
+        focus::log_focus_event(&block, FocusOutcome::Cancelled);
+        ops.display_message(format!("Cancelled {} with {} to go.", block.describe(), block.countdown().trim_end_matches(" left")));
+        CommandResult::Continue
+    }
+}
+
+/// # FocusStatusCommand
+///
+/// **Summary:**
+/// Command to show the running focus block (`focus`).
+#[derive(Debug, Clone, Default)]
+pub struct FocusStatusCommand;
+
+impl FocusStatusCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Command for FocusStatusCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let status = match ops.focus_timer() {
+            Some(timer) => format!("{} ({})", capitalize_first(&timer.block.describe()), timer.block.countdown()),
+            None => "No focus block is running. Start one with 'focus <minutes> [goal]'.".to_string(),
+        };
+        ops.display_message(status);
+        CommandResult::Continue
+    }
+}
+
+/// # FocusCheckInCommand
+///
+/// **Summary:**
+/// Asks the check-in persona for a report once a focus block has run out.
+///
+/// **Details:**
+/// Run by the app when the focus timer fires, not parsed from input. Once the
+/// check-in is sent, follow-ups are armed in case the user doesn't answer
+/// (`TuiConfig::check_in_escalation`).
+#[derive(Debug, Clone)]
+pub struct FocusCheckInCommand {
+    block: FocusBlock,
+}
+
+impl FocusCheckInCommand {
+    pub fn new(block: FocusBlock) -> Self {
+        Self { block }
+    }
+}
+
+impl Command for FocusCheckInCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let persona_name = GLOBAL_CONFIG.tui.focus_check_in_persona.clone();
+        ops.display_message(format!("{} ended.", capitalize_first(&self.block.describe())));
+        if send_check_in(ops, &persona_name, &self.block.check_in_message()) {
+            ops.arm_check_in_escalation(&persona_name);
+        }
         CommandResult::Continue
     }
 }
 
-/// # QuitCommand
+/// # send_check_in
+///
+/// **Purpose:**
+/// Sends a check-in (or a follow-up to one) from the given persona.
+///
+/// **Details:**
+/// The persona's agent is opened if needed and made current so its reply is
+/// visible; an agent busy with another reply is left alone.
+///
+/// **Returns:**
+/// `bool` - Whether the message was sent
+pub fn send_check_in(ops: &mut dyn AgentOperations, persona_name: &str, message: &str) -> bool {
+    let existing = ops.get_all_agent_names().into_iter()
+        .find(|(_, name)| name == persona_name)
+        .map(|(id, _)| id);
+    let id = match existing {
+        Some(id) => id,
+        None => {
+            let Some(persona) = ops.get_persona(persona_name) else {
+                ops.display_warning(format!("Focus check-in persona '{}' not found.", persona_name));
+                return false;
+            };
+            let id = Uuid::new_v4();
+            ops.add_new_agent(id, persona);
+            id
+        }
+    };
+    ops.set_current_agent_id(Some(id));
+
+    let Some(agent) = ops.get_agent_info_mut(id) else {
+        return false;
+    };
+    if agent.is_waiting {
+        ops.display_warning(format!(
+            "{} is busy; check in there once its reply finishes.", capitalize_first(persona_name)
+        ));
+        return false;
+    }
+    agent.send(Pipeline::chat().run(message));
+    true
+}
+
+/// # TourCommand
 ///
 /// **Summary:**
-/// Command to gracefully shut down the application.
-#[derive(Debug, Clone)]
-pub struct QuitCommand;
+/// Command to (re)start the guided TUI tour.
+#[derive(Debug, Clone, Default)]
+pub struct TourCommand;
 
-impl QuitCommand {
+impl TourCommand {
     pub fn new() -> Self {
         Self
     }
 }
 
-impl Command for QuitCommand {
-    fn execute(&self, _ops: &mut dyn AgentOperations) -> CommandResult {
-        CommandResult::Shutdown
+impl Command for TourCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        ops.start_tour();
+        CommandResult::Continue
     }
 }
 
+/// # LanguageCommand
+///
+/// **Summary:**
+/// Command to show or override the current agent's conversation language.
+///
+/// **Fields:**
+/// - `code`: None to show; a code or name to set; `auto` to go back to persona pin / detection
+///
+/// **Details:**
+/// The override only lasts for this agent's session.
 #[derive(Debug, Clone)]
-pub struct ListAgentsCommand;
+pub struct LanguageCommand {
+    code: Option<String>,
+}
 
-impl ListAgentsCommand {
+impl LanguageCommand {
+    pub fn new(code: Option<String>) -> Self {
+        Self { code }
+    }
+}
+
+impl Command for LanguageCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent) = ops.current_agent_info_mut() else {
+            return no_agent_open(ops);
+        };
+        let connection = agent.connection.clone();
+
+        let Ok(mut conn) = connection.try_lock() else {
+            ops.display_message("Failed to acquire connection lock.".to_string());
+            return CommandResult::Continue;
+        };
+        let persona_name = conn.conversation.persona.name.clone();
+
+        match self.code.as_deref() {
+            None => {}
+            Some("auto") => conn.conversation.set_language_override(None),
+            Some(code) => match language::parse_language(code) {
+                Some(lang) => conn.conversation.set_language_override(Some(lang)),
+                None => {
+                    drop(conn);
+                    ops.display_warning(format!(
+                        "Unknown language '{}'. Use an ISO 639-3 code (spa, deu) or an English name (Spanish).", code
+                    ));
+                    return CommandResult::Continue;
+                }
+            },
+        }
+
+        let status = match conn.conversation.language() {
+            Some((lang, source)) => format!("Language for {}: {} ({})", persona_name, lang.eng_name(), source.describe()),
+            None => format!("Language for {}: not detected yet", persona_name),
+        };
+        drop(conn);
+        ops.display_agent_message(&persona_name, status);
+        CommandResult::Continue
+    }
+}
+
+/// # ModelCommand
+///
+/// **Summary:**
+/// Command to show or switch the current agent's Grok model.
+///
+/// **Fields:**
+/// - `model`: None to show; one of `grok.known_models` to switch; `default` to go back to `grok.model_name`
+///
+/// **Details:**
+/// The choice lasts for this agent's session. Switching drops the response
+/// thread, since a thread doesn't carry across models. Claude agents always
+/// use `CLAUDE_MODEL`.
+#[derive(Debug, Clone)]
+pub struct ModelCommand {
+    model: Option<String>,
+}
+
+impl ModelCommand {
+    pub fn new(model: Option<String>) -> Self {
+        Self { model }
+    }
+}
+
+impl Command for ModelCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent) = ops.current_agent_info_mut() else {
+            return no_agent_open(ops);
+        };
+        let connection = agent.connection.clone();
+
+        let Ok(mut conn) = connection.try_lock() else {
+            return CommandResult::Error("Agent is busy; try again once the reply finishes.".to_string());
+        };
+        let persona_name = conn.conversation.persona.name.clone();
+
+        if ApiProvider::for_persona(&conn.conversation.persona) == ApiProvider::Claude {
+            drop(conn);
+            ops.display_agent_message(&persona_name, format!(
+                "Model for {}: {} (Claude agents can't switch models)", persona_name, CLAUDE_MODEL
+            ));
+            return CommandResult::Continue;
+        }
+
+        let mut thread_dropped = false;
+        if let Some(requested) = self.model.as_deref() {
+            let known = &GLOBAL_CONFIG.grok.known_models;
+            let model = match requested {
+                "default" => None,
+                requested => match known.iter().find(|model| model.eq_ignore_ascii_case(requested)) {
+                    Some(model) => Some(model.clone()),
+                    None => {
+                        drop(conn);
+                        ops.display_warning(format!(
+                            "Unknown model '{}'. Use one of: {}, or default.", requested, known.join(", ")
+                        ));
+                        return CommandResult::Continue;
+                    }
+                },
+            };
+            thread_dropped = conn.conversation.set_model_override(model);
+        }
+
+        let source = if conn.conversation.model_override().is_some() { "set with `model`" } else { "default" };
+        let mut status = format!("Model for {}: {} ({})", persona_name, conn.conversation.model(), source);
+        if thread_dropped {
+            status.push_str("; the next message sends the full history");
+        }
+        drop(conn);
+        ops.display_agent_message(&persona_name, status);
+        CommandResult::Continue
+    }
+}
+
+/// # SetSummarizeCommand
+///
+/// **Summary:**
+/// Command to show or override the current agent's summarize mode.
+///
+/// **Fields:**
+/// - `mode`: None to show; `auto`, `manual`, or `off` to override; `default` to follow the persona again
+///
+/// **Details:**
+/// The override is saved per persona, so it survives restarts and applies to
+/// every agent later opened with that persona.
+#[derive(Debug, Clone)]
+pub struct SetSummarizeCommand {
+    mode: Option<String>,
+}
+
+impl SetSummarizeCommand {
+    pub fn new(mode: Option<String>) -> Self {
+        Self { mode }
+    }
+}
+
+impl Command for SetSummarizeCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent) = ops.current_agent_info_mut() else {
+            return no_agent_open(ops);
+        };
+        let connection = agent.connection.clone();
+
+        let Ok(mut conn) = connection.try_lock() else {
+            return CommandResult::Error("Agent is busy; try again once the reply finishes.".to_string());
+        };
+        let persona_name = conn.conversation.persona.name.clone();
+
+        if let Some(value) = self.mode.as_deref() {
+            let mode = match value {
+                "default" => None,
+                value => match value.parse::<SummarizeMode>() {
+                    Ok(mode) => Some(mode),
+                    Err(_) => {
+                        drop(conn);
+                        ops.display_warning(format!(
+                            "Unknown summarize mode '{}'. Use auto, manual, off, or default.", value
+                        ));
+                        return CommandResult::Continue;
+                    }
+                },
+            };
+            conn.conversation.set_summarize_override(mode);
+            let overrides = ConversationOverrides { summarize_mode: mode };
+            match persistence::auto_write(|| HistoryManager::save_overrides(&persona_name, &overrides)) {
+                Ok(Some(())) => {}
+                Ok(None) => log_info!("Overrides for {} not saved: persistence suspended", persona_name),
+                Err(e) => {
+                    log_error!("Failed to save overrides for {}: {}", persona_name, e);
+                    ops.display_warning(format!("Summarize mode changed for this session only: {}", e));
+                }
+            }
+        }
+
+        let source = if conn.conversation.summarize_override().is_some() { "set with `set summarize`" } else { "from persona" };
+        let status = format!("Summarize mode for {}: {} ({})", persona_name, conn.conversation.summarize_mode(), source);
+        drop(conn);
+        ops.display_agent_message(&persona_name, status);
+        CommandResult::Continue
+    }
+}
+
+/// # NudgeAddCommand
+///
+/// **Summary:**
+/// Command to steer the current agent with a system-role instruction instead of a user message.
+///
+/// **Fields:**
+/// - `instruction`: The instruction, e.g. "from now on answer in bullet points"
+///
+/// **Details:**
+/// Nothing is sent right away: the nudge goes out with the next request and
+/// every one after it until `nudge clear`. It's recorded in history as a
+/// system message tagged `nudge`, so exports, drafts, and summaries don't
+/// mistake it for something the user said.
+#[derive(Debug, Clone)]
+pub struct NudgeAddCommand {
+    instruction: String,
+}
+
+impl NudgeAddCommand {
+    pub fn new(instruction: String) -> Self {
+        Self { instruction }
+    }
+}
+
+impl Command for NudgeAddCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent) = ops.current_agent_info_mut() else {
+            return no_agent_open(ops);
+        };
+        let connection = agent.connection.clone();
+
+        let Ok(mut conn) = connection.try_lock() else {
+            return CommandResult::Error("Agent is busy; try again once the reply finishes.".to_string());
+        };
+        conn.conversation.add_nudge(&self.instruction);
+        let active = conn.conversation.active_nudges().len();
+        let persona_name = conn.conversation.persona.name.clone();
+        drop(conn);
+
+        agent.add_message(nudge_line(&self.instruction));
+        ops.display_agent_message(&persona_name, format!(
+            "Nudge added ({} active); it goes out with your next message. `nudge clear` stops it.", active
+        ));
+        CommandResult::Continue
+    }
+}
+
+/// # NudgeListCommand
+///
+/// **Summary:**
+/// Command to show the current agent's active nudges.
+#[derive(Debug, Clone, Default)]
+pub struct NudgeListCommand;
+
+impl NudgeListCommand {
     pub fn new() -> Self {
         Self
     }
 }
 
-impl Command for ListAgentsCommand {
+impl Command for NudgeListCommand {
     fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
-        let personas = vec!["shadow", "friday"];
-        ops.display_message(format!("Available personas: {}", personas.join(", ")));
+        let Some(agent) = ops.current_agent_info() else {
+            return no_agent_open(ops);
+        };
+        let Ok(conn) = agent.connection.try_lock() else {
+            return CommandResult::Error("Agent is busy; try again once the reply finishes.".to_string());
+        };
+        let persona_name = conn.conversation.persona.name.clone();
+        let nudges: Vec<String> = conn.conversation.active_nudges().iter()
+            .enumerate()
+            .map(|(index, nudge)| format!(" {}. {}", index + 1, nudge.text))
+            .collect();
+        drop(conn);
+
+        if nudges.is_empty() {
+            ops.display_agent_message(&persona_name, "No active nudges.".to_string());
+        } else {
+            ops.display_agent_message(&persona_name, format!("Active nudges:\n{}", nudges.join("\n")));
+        }
         CommandResult::Continue
     }
 }
 
-#[derive(Debug)]
-struct UnimplementedCommand {
-    feature: String,
+/// # NudgeClearCommand
+///
+/// **Summary:**
+/// Command to stop sending the current agent's nudges; history keeps their record.
+#[derive(Debug, Clone, Default)]
+pub struct NudgeClearCommand;
+
+impl NudgeClearCommand {
+    pub fn new() -> Self {
+        Self
+    }
 }
 
-impl Command for UnimplementedCommand {
+impl Command for NudgeClearCommand {
     fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
-        ops.display_message(format!("Feature not yet implemented: {}", self.feature));
+        let Some(agent) = ops.current_agent_info_mut() else {
+            return no_agent_open(ops);
+        };
+        let connection = agent.connection.clone();
+
+        let Ok(mut conn) = connection.try_lock() else {
+            return CommandResult::Error("Agent is busy; try again once the reply finishes.".to_string());
+        };
+        let threaded = conn.conversation.next_request_threaded();
+        let cleared = conn.conversation.clear_nudges();
+        let rethreaded = threaded && !conn.conversation.next_request_threaded();
+        let persona_name = conn.conversation.persona.name.clone();
+        drop(conn);
+
+        let status = match cleared {
+            0 => "No active nudges.".to_string(),
+            n if rethreaded => format!("Cleared {} nudge(s); the next message sends the full history without them.", n),
+            n => format!("Cleared {} nudge(s).", n),
+        };
+        ops.display_agent_message(&persona_name, status);
         CommandResult::Continue
     }
 }
 
+/// # ResyncCommand
+///
+/// **Summary:**
+/// Command to answer the offer shown when an agent opens after a long break.
+///
+/// **Fields:**
+/// - `choice`: Ask for a recap, or archive the history and start fresh
+///
+/// **Details:**
+/// A recap is sent like any typed message. A fresh start archives the full
+/// history (as summarizing does), keeps only the prompt and the long-term
+/// summary, and saves that; the context note goes too, since there is no
+/// earlier conversation left to be stale.
 #[derive(Debug, Clone)]
-struct TweetCommand {
-    text: String,
+pub struct ResyncCommand {
+    choice: ResyncChoice,
 }
 
-impl Command for TweetCommand {
+impl ResyncCommand {
+    pub fn new(choice: ResyncChoice) -> Self {
+        Self { choice }
+    }
+}
+
+impl Command for ResyncCommand {
     fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
         let Some(agent) = ops.current_agent_info_mut() else {
-            ops.display_message("No agent available. Create one with 'new <persona>'".to_string());
+            return no_agent_open(ops);
+        };
+        let Some(gap) = agent.resync_offer.take() else {
+            let persona_name = agent.persona_name.clone();
+            ops.display_agent_message(&persona_name, "Nothing to resync: this conversation hasn't been away long.".to_string());
             return CommandResult::Continue;
         };
 
-        agent.add_message(r#"
-            If I can't get the ai to respond to me quick enough with something big enough to actually trigger the autoscroll. Then I'll do it myself.
-            I believe I've learned enough to actually code some useful test commands. I mean sure it's not the way most people would do it. But yet
-            again I'm not most people and I didn't go to school for this shit. I'm just making up as I go and hoping the code works. So far so good
-            I'd say! Alright this is probably enough I can always just spam this command.
-            "#.to_string());
+        if self.choice == ResyncChoice::Recap {
+            return SendMessageCommand::new(freshness::recap_request(gap)).execute(ops);
+        }
+
+        let connection = agent.connection.clone();
+        let Ok(mut conn) = connection.try_lock() else {
+            agent.resync_offer = Some(gap);
+            return CommandResult::Error("Agent is busy; try again once the reply finishes.".to_string());
+        };
+        let persona_name = conn.conversation.persona.name.clone();
+        let (user, assistant) = conn.conversation.conversation_counts();
+
+        if let Err(e) = persistence::explicit_write(|| HistoryManager::archive_full_history(&conn.conversation)) {
+            drop(conn);
+            agent.resync_offer = Some(gap);
+            log_error!("Failed to archive history for {}: {}", persona_name, e);
+            return CommandResult::Error(format!("Could not archive the history, so nothing was cleared: {}", e));
+        }
+        conn.conversation.fresh_start();
+        let kept_long_term = conn.conversation.long_term_summary_text().is_some();
+        let saved = conn.save_persona_history();
+        drop(conn);
+
+        let mut status = format!(
+            "Archived {} message(s) and started fresh{}.",
+            user + assistant,
+            if kept_long_term { "; the long-term summary was kept" } else { "" }
+        );
+        if let Err(e) = saved {
+            log_error!("Failed to save history for {}: {}", persona_name, e);
+            status.push_str(&format!(" Saving the cleared history failed ({}); the old file is still on disk.", e));
+        }
+        ops.display_agent_message(&persona_name, status);
         CommandResult::Continue
     }
 }
 
-#[derive(Debug, Clone)]
-struct DraftTweetCommand {
-    text: String,
+/// # ExplainCommand
+///
+/// **Summary:**
+/// Command to ask about the error the current agent's last request ended in.
+///
+/// **Details:**
+/// The error is packaged with what triggered it, the provider and model, and
+/// local environment facts, then redacted and capped (`explain::ExplainRequest`).
+/// Where it goes depends on whether asking can work:
+/// - Known errors that leave the agent unable to answer (bad key, rate limit) are explained locally
+/// - After `explain.helper_after_failures` failures in a row, `explain.helper_persona` is asked
+///   instead (opened if needed); with no helper set, the explanation is local
+/// - Otherwise the current agent is asked, like a typed message
+#[derive(Debug, Clone, Default)]
+pub struct ExplainCommand;
+
+impl ExplainCommand {
+    pub fn new() -> Self {
+        Self
+    }
 }
 
-impl Command for DraftTweetCommand {
+impl Command for ExplainCommand {
     fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
-        let Some(agent) = ops.current_agent_info_mut() else {
-            ops.display_message("No agent available. Create one with 'new <persona>'".to_string());
+        let Some(agent) = ops.current_agent_info() else {
+            return no_agent_open(ops);
+        };
+        let persona_name = agent.persona_name.clone();
+        let (id, failures) = (agent.id, agent.failures_in_a_row);
+        let Some(failure) = agent.last_failure.clone() else {
+            ops.display_agent_message(&persona_name, "Nothing to explain: the last request didn't fail.".to_string());
+            return CommandResult::Continue;
+        };
+        let Ok(conn) = agent.connection.try_lock() else {
+            return CommandResult::Error("Agent is busy; try again once the reply finishes.".to_string());
+        };
+        let provider = ApiProvider::for_persona(&conn.conversation.persona);
+        let model = match provider {
+            ApiProvider::Claude => CLAUDE_MODEL.to_string(),
+            ApiProvider::Grok => conn.conversation.model().to_string(),
+        };
+        drop(conn);
+
+        let known = explain::classify(&failure.error);
+        if let Some(known) = known.filter(|known| !known.agent_can_answer) {
+            let reason = "asking this agent would fail the same way";
+            ops.display_agent_message(&persona_name, explain::local_explanation(&failure, Some(known), reason));
             return CommandResult::Continue;
+        }
+
+        let config = &GLOBAL_CONFIG.explain;
+        let mut target = id;
+        if failures >= config.helper_after_failures {
+            let Some(helper) = config.helper_persona.as_deref().filter(|helper| *helper != persona_name) else {
+                let reason = format!("the last {} requests here failed and no explain.helper_persona is set", failures);
+                ops.display_agent_message(&persona_name, explain::local_explanation(&failure, known, &reason));
+                return CommandResult::Continue;
+            };
+            target = match helper_agent(ops, helper) {
+                Ok(helper_id) => helper_id,
+                Err(e) => return CommandResult::Error(e),
+            };
+            ops.display_agent_message(&persona_name, format!(
+                "The last {} requests here failed, so {} was asked instead.",
+                failures,
+                capitalize_first(helper)
+            ));
+            ops.set_current_agent_id(Some(target));
+        }
+
+        let request = ExplainRequest {
+            failure,
+            persona: persona_name,
+            provider,
+            model,
+            environment: explain::environment_facts(provider),
         };
+        let Some(agent) = ops.get_agent_info_mut(target) else {
+            return no_agent_open(ops);
+        };
+        agent.send(Pipeline::explain(request, config.max_prompt_chars).run("explain"));
+        CommandResult::Continue
+    }
+}
+
+/// # helper_agent
+///
+/// **Purpose:**
+/// The open agent for `explain.helper_persona`, opening one if there isn't one.
+///
+/// **Errors / Failures:**
+/// - No such persona
+/// - Its agent is in the middle of a reply
+fn helper_agent(ops: &mut dyn AgentOperations, helper: &str) -> Result<Uuid, String> {
+    let open = ops.get_all_agent_names().into_iter().find(|(_, name)| name == helper);
+    if let Some((id, _)) = open {
+        if ops.get_agent_info(id).is_some_and(|agent| agent.is_waiting) {
+            return Err(format!("{} is busy; try again once its reply finishes.", capitalize_first(helper)));
+        }
+        return Ok(id);
+    }
+    let persona = ops.get_persona(helper)
+        .ok_or_else(|| format!("Persona '{}' not found (explain.helper_persona).", helper))?;
+    let id = Uuid::new_v4();
+    ops.add_new_agent(id, persona);
+    Ok(id)
+}
+
+/// # InspectCommand
+///
+/// **Summary:**
+/// Command to show the current agent's live context head, or diff its prompt against the persona file.
+///
+/// **Fields:**
+/// - `diff`: Compare the in-memory system prompt with the persona YAML instead
+///
+/// **Details:**
+/// Read-only. The TUI opens an overlay; CLI and accessible mode get the same
+/// sections as text in the Global pane.
+#[derive(Debug, Clone)]
+pub struct InspectCommand {
+    diff: bool,
+}
+
+impl InspectCommand {
+    pub fn new(diff: bool) -> Self {
+        Self { diff }
+    }
+}
 
+impl Command for InspectCommand {
+    fn execute(&self, ops: &mut dyn AgentOperations) -> CommandResult {
+        let Some(agent) = ops.current_agent_info() else {
+            return no_agent_open(ops);
+        };
         let persona_name = agent.persona_name.clone();
+        let connection = agent.connection.clone();
 
-        if persona_name == "viral" {
-            agent.add_message(format!("> Tweet Draft: {}", self.text));
-            agent.is_waiting = true;
-
-            if let Some(old_task) = agent.active_task.take() {
-                old_task.abort();
-            }
-
-            let connection = agent.connection.clone();
-            let tx = agent.chunk_sender.clone();
-            let text_owned = self.text.clone();
-
-            let handle = tokio::spawn(async move {
-                let mut connection = connection.lock().await;
-                let define_tweet = format!(r#"
-                    Please draft a tweet with the following content: "{}"
-                    Keep it under 280 characters and suitable for Twitter.
-                    Respond only with the tweet text, no additional commentary.
-                    Use a casual and engaging tone.
-                    Have at least one hashtag relevant to the content.
-                    Have at least one mention of a relevant Twitter handle.
-                    Prefer threads if necessary to fit the content.
-                    Make it engaging and likely to get interactions.
-                    Tag it with -Shadow at the end.
-                    "#, text_owned);
-                connection.add_user_message(&define_tweet);
-                if let Err(e) = connection.handle_response_streaming(tx.clone()).await {
-                    let _ = tx.send(StreamChunk::Error(format!("{}", e)));
-                }
-            });
+        let Ok(conn) = connection.try_lock() else {
+            return CommandResult::Error("Agent is busy; try again once the reply finishes.".to_string());
+        };
 
-            agent.active_task = Some(handle);
-        } else {
-            agent.add_message("Wrong Agent! Switch to Viral!")
+        if self.diff {
+            let live = conn.conversation.get_system_prompt()
+                .filter(|msg| msg.role == "system")
+                .map(|msg| msg.content.clone())
+                .unwrap_or_default();
+            drop(conn);
+            let report = match persona_file_prompt(&persona_name) {
+                Ok((source, prompt)) if prompt == live => {
+                    format!("The system prompt in memory matches {}.", source)
+                }
+                Ok((source, prompt)) => {
+                    let mut lines = vec![format!("System prompt: {} (-) versus in memory (+)", source)];
+                    lines.extend(line_diff(&prompt, &live));
+                    lines.join("\n")
+                }
+                Err(e) => return CommandResult::Error(e),
+            };
+            ops.display_agent_message(&persona_name, report);
+            return CommandResult::Continue;
         }
 
+        let view = InspectView::from_conversation(&conn.conversation);
+        drop(conn);
+        if !ops.open_inspect(view.clone()) {
+            ops.display_agent_message(&persona_name, view.render_text());
+        }
         CommandResult::Continue
     }
 }
 
+/// Where a persona's YAML comes from and the system prompt it holds now
+fn persona_file_prompt(name: &str) -> Result<(String, String), String> {
+    let sources = discover_personas().map_err(|e| e.to_string())?;
+    let Some((_, source)) = sources.into_iter().find(|(found, _)| found == name) else {
+        return Err(format!("No persona file found for {}.", name));
+    };
+    let persona = source.load().map_err(|e| format!("Could not load persona {}: {}", name, e))?;
+    let label = match &source {
+        PersonaSource::File(path) => match &persona.system_prompt_file {
+            Some(file) => resolve_prompt_path(path, file).display().to_string(),
+            None => path.display().to_string(),
+        },
+        PersonaSource::Builtin(builtin) => format!("the built-in {} persona", builtin),
+    };
+    Ok((label, persona.system_prompt))
+}
+
 /// # from_input_action
 ///
 /// **Purpose:**
@@ -597,6 +3669,11 @@ impl Command for DraftTweetCommand {
 /// **Returns:**
 /// - `Option<Box<dyn Command>>`: Boxed command if action is supported, None otherwise
 ///
+/// **Details:**
+/// In strict mode, actions whose `effect` it refuses become a `StrictRefusedCommand`.
+/// Actions with a `checkpoint_reason` are wrapped in a `CheckpointedCommand`.
+/// Every way of running a command passes through here, so none can slip past.
+///
 /// **Usage Example:**
 /// ```rust
 /// if let Some(cmd) = from_input_action(action) {
@@ -604,23 +3681,97 @@ impl Command for DraftTweetCommand {
 /// }
 /// ```
 pub fn from_input_action(action: InputAction) -> Box<dyn Command> {
-    match action {
+    let effect = action.effect();
+    if strict::is_enabled() && effect.refused_in_strict() {
+        return Box::new(StrictRefusedCommand { command: (&action).into(), effect });
+    }
+
+    let checkpoint = action.checkpoint_reason();
+    let tool = action.required_tool();
+    let command: Box<dyn Command> = match action {
         InputAction::Quit                   => Box::new(QuitCommand::new()),
         InputAction::SendAsMessage(content) => Box::new(SendMessageCommand::new(content)),
+        InputAction::Attach { path, note }  => Box::new(AttachCommand::new(path, note)),
         InputAction::SaveHistory            => Box::new(SaveHistoryCommand::new()),
         InputAction::HistoryInfo            => Box::new(HistoryInfoCommand::new()),
         InputAction::ClearHistory           => Box::new(ClearHistoryCommand::new()),
+        InputAction::DedupeHistory { apply } => Box::new(DedupeHistoryCommand::new(apply)),
+        InputAction::HistoryList            => Box::new(HistoryListCommand::new()),
+        InputAction::HistoryRemove { index, pair, apply } => Box::new(HistoryRemoveCommand::new(index, pair, apply)),
+        InputAction::VerifyHistory { persona, fix, apply } => Box::new(VerifyHistoryCommand::new(persona, fix, apply)),
         InputAction::Summarize              => Box::new(SummarizeCommand::new()),
         InputAction::NewAgent(persona)      => Box::new(NewAgentCommand::new(persona)),
         InputAction::CloseAgent             => Box::new(CloseAgentCommand::new()),
         InputAction::AgentStatus            => Box::new(AgentStatusCommand::new()),
+        InputAction::Usage                  => Box::new(UsageCommand::new()),
         InputAction::ListAgents             => Box::new(ListAgentsCommand::new()),
+        InputAction::Help                   => Box::new(HelpCommand::new()),
+        InputAction::Inspect { diff }       => Box::new(InspectCommand::new(diff)),
+        InputAction::ExportBuiltinPersonas { dir, inline } => Box::new(ExportBuiltinPersonasCommand::new(dir, inline)),
+        InputAction::PersonaHistory(name)   => Box::new(PersonaHistoryCommand::new(name)),
+        InputAction::Overview { sort, json } => Box::new(OverviewCommand::new(sort, json)),
+        InputAction::Insights { month, export } => Box::new(InsightsCommand::new(month, export)),
+        InputAction::GlobalFilter(filter)   => Box::new(GlobalFilterCommand::new(filter)),
+        InputAction::GlobalClear            => Box::new(GlobalClearCommand::new()),
+        InputAction::SetLayout(mode)        => Box::new(SetLayoutCommand::new(mode)),
+        InputAction::Timestamps(show)       => Box::new(TimestampsCommand::new(show)),
+        InputAction::ListArchives(since)    => Box::new(ListArchivesCommand::new(since)),
+        InputAction::ExportArchive { archive, dest } => Box::new(ExportArchiveCommand::new(archive, dest)),
+        InputAction::ReloadKeys             => Box::new(ReloadKeysCommand::new()),
+        InputAction::ReloadPersonas { apply } => Box::new(ReloadPersonasCommand::new(apply)),
+        InputAction::Whence(nth_last)       => Box::new(WhenceCommand::new(nth_last)),
+        InputAction::Sent(nth_last)         => Box::new(SentCommand::new(nth_last)),
+        InputAction::SetLogLevel { level, target } => Box::new(LogLevelCommand::new(level, target)),
+        InputAction::MuteAgent(muted)       => Box::new(MuteAgentCommand::new(muted)),
+        InputAction::Speak(speaking)        => Box::new(SpeakCommand::new(speaking)),
+        InputAction::OpenLink(nth)          => Box::new(OpenLinkCommand::new(nth)),
+        InputAction::ExperimentStart { name, variant_path } => Box::new(ExperimentStartCommand::new(name, variant_path)),
+        InputAction::ExperimentStatus       => Box::new(ExperimentStatusCommand::new()),
+        InputAction::ExperimentStop(name)   => Box::new(ExperimentStopCommand::new(name)),
+        InputAction::RateConversation(rating) => Box::new(RateConversationCommand::new(rating)),
         InputAction::PostTweet(text)        => Box::new(TweetCommand {text}),
+        InputAction::ConfirmTweet           => Box::new(ConfirmTweetCommand::new()),
+        InputAction::CancelTweet            => Box::new(CancelTweetCommand::new()),
+        InputAction::QueueTweet(text)       => Box::new(QueueTweetCommand::new(text)),
+        InputAction::TweetQuota             => Box::new(TweetQuotaCommand::new()),
+        InputAction::CheckTweet(text)       => Box::new(CheckTweetCommand::new(text)),
+        InputAction::IgnoreWord(word)       => Box::new(IgnoreWordCommand::new(word)),
         InputAction::DraftTweet(text)       => Box::new(DraftTweetCommand {text}),
-        InputAction::DoNothing | InputAction::ContinueNoSend(_) => {
+        InputAction::ListJobs               => Box::new(ListJobsCommand::new()),
+        InputAction::CancelJob(id)          => Box::new(CancelJobCommand::new(id)),
+        InputAction::Stop                   => Box::new(StopCommand::new()),
+        InputAction::MacroRecord { name, trusted } => Box::new(MacroRecordCommand::new(name, trusted)),
+        InputAction::MacroStop { trusted }  => Box::new(MacroStopCommand::new(trusted)),
+        InputAction::MacroRun { name, confirmed } => Box::new(MacroRunCommand::new(name, confirmed)),
+        InputAction::MacroList              => Box::new(MacroListCommand::new()),
+        InputAction::MacroDelete(name)      => Box::new(MacroDeleteCommand::new(name)),
+        InputAction::Profile { frames }     => Box::new(ProfileCommand::new(frames)),
+        InputAction::FocusStart { minutes, goal } => Box::new(FocusStartCommand::new(minutes, goal)),
+        InputAction::FocusCancel            => Box::new(FocusCancelCommand::new()),
+        InputAction::FocusStatus            => Box::new(FocusStatusCommand::new()),
+        InputAction::Tour                   => Box::new(TourCommand::new()),
+        InputAction::Language(code)         => Box::new(LanguageCommand::new(code)),
+        InputAction::Model(model)           => Box::new(ModelCommand::new(model)),
+        InputAction::SetSummarize(mode)     => Box::new(SetSummarizeCommand::new(mode)),
+        InputAction::NudgeAdd(instruction)  => Box::new(NudgeAddCommand::new(instruction)),
+        InputAction::NudgeList              => Box::new(NudgeListCommand::new()),
+        InputAction::NudgeClear             => Box::new(NudgeClearCommand::new()),
+        InputAction::Resync(choice)         => Box::new(ResyncCommand::new(choice)),
+        InputAction::Explain                => Box::new(ExplainCommand::new()),
+        InputAction::Rollback { index, apply } => Box::new(RollbackCommand::new(index, apply)),
+        InputAction::DoNothing | InputAction::ContinueNoSend(_) | InputAction::RecallCommand { .. } => {
             Box::new(UnimplementedCommand {
                 feature: "Hey dumbass, these do nothing".to_string(),
             })
         }
+    };
+
+    let command = match checkpoint {
+        Some(reason) => Box::new(CheckpointedCommand { reason, command }),
+        None => command,
+    };
+    match tool {
+        Some(tool) => Box::new(ToolGatedCommand { tool, command }),
+        None => command,
     }
 }
\ No newline at end of file
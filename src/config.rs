@@ -20,7 +20,10 @@
 //! This file is part of the Daegonica Software codebase.
 //! ---------------------------------------------------------------
 
+use chrono::NaiveTime;
 use ratatui::style::Color;
+use crate::tui::hyperlinks::HyperlinkMode;
+use crate::tui::layout::LayoutMode;
 
 
 /// # AppConfig
@@ -32,6 +35,15 @@ use ratatui::style::Color;
 /// - `grok`: Configuration for Grok API client
 /// - `tui`: Configuration for terminal user interface
 /// - `history`: Configuration for conversation history management
+/// - `twitter`: Posting quota limits for the X API
+/// - `http`: Connection settings for the shared LLM HTTP clients
+/// - `credentials`: Where API keys are read from
+/// - `events`: Machine-readable lifecycle event log
+/// - `strip`: Signature and boilerplate lines removed from replies
+/// - `insights`: Prices used by the local `insights` report
+/// - `explain`: Where `explain` sends the last error, and how much of it
+/// - `tts`: Speaking replies aloud with the OS text-to-speech program (`speak on`)
+/// - `strict_mode`: Start in strict mode, refusing commands that post, delete, or overwrite (as `--strict`)
 ///
 /// **Usage Example:**
 /// ```rust
@@ -43,6 +55,15 @@ pub struct AppConfig {
     pub grok: GrokConfig,
     pub tui: TuiConfig,
     pub history: HistoryConfig,
+    pub twitter: TwitterConfig,
+    pub http: HttpConfig,
+    pub credentials: CredentialsConfig,
+    pub events: EventsConfig,
+    pub strip: StripConfig,
+    pub insights: InsightsConfig,
+    pub explain: ExplainConfig,
+    pub tts: TtsConfig,
+    pub strict_mode: bool,
 }
 
 /// # GrokConfig
@@ -54,21 +75,32 @@ pub struct AppConfig {
 /// - `model_name`: The Grok model to use (e.g., "grok-4-fast")
 /// - `default_temperature`: Default randomness for responses (0.0-1.0)
 /// - `stream_enabled`: Whether to use streaming responses
+/// - `response_id_ttl_hours`: Age after which a stored response ID is no longer
+///   threaded on; the full history is sent instead (0 = never expire)
+/// - `known_models`: Models the `model` command can switch an agent to
+/// - `max_retries`: Times a streamed request is retried after a 429 or 5xx (0 = never)
+/// - `initial_retry_delay_ms`: Wait before the first retry; doubled for each one after, up to a minute
+/// - `request_timeout_secs`: Give up on a request that hasn't started answering after this long (0 = wait forever)
+/// - `stream_timeout_secs`: Give up on a reply still streaming after this long (0 = no limit)
 ///
 /// **Usage Example:**
 /// ```rust
 /// let grok_config = GrokConfig::default();
-/// let request = ChatRequest {
-///     model: grok_config.model_name.clone(),
-///     temperature: grok_config.default_temperature,
-///     stream: grok_config.stream_enabled,
-/// };
+/// // ChatRequestBuilder falls back to these when nothing more specific is set
+/// let request = ChatRequest::builder().messages(messages).build()?;
+/// assert_eq!(request.model, grok_config.model_name);
 /// ```
 #[derive(Debug, Clone)]
 pub struct GrokConfig {
     pub model_name: String,
     pub default_temperature: f32,
     pub stream_enabled: bool,
+    pub response_id_ttl_hours: u64,
+    pub known_models: Vec<String>,
+    pub max_retries: u8,
+    pub initial_retry_delay_ms: u64,
+    pub request_timeout_secs: u64,
+    pub stream_timeout_secs: u64,
 }
 
 /// # TuiConfig
@@ -83,6 +115,30 @@ pub struct GrokConfig {
 /// - `user_message_color`: RGB color for user messages
 /// - `scroll_step`: Lines to scroll per arrow key press
 /// - `page_scroll_step`: Lines to scroll per page up/down
+/// - `repeat_last_command`: Enter on empty input repeats the last repeatable command
+/// - `command_history_size`: Executed command lines kept for `!!`-style recall
+/// - `confirm_history_expansion`: Put a recalled command in the input to confirm or edit
+///   instead of running it (a destructive `!!` always asks)
+/// - `accessible`: Start in screen-reader-friendly mode (same as `--accessible`)
+/// - `layout`: Pane layout (`auto`, `split`, `single`, `stacked`); a `layout` command choice overrides it
+/// - `single_pane_below_width`: Columns under which `auto` shows a single pane
+/// - `split_ratio`: Percent of the width the agent pane gets in the split layout (20–80); Ctrl+Left/Right choices override it
+/// - `slow_command_ms`: Commands taking at least this long are flagged by `profile`
+/// - `slow_frame_ms`: Frames taking at least this long are flagged by `profile frames`
+/// - `syntax_highlighting`: Color Rust code blocks in the agent pane (never in accessible mode)
+/// - `syntax_colors`: Colors per token class for highlighted code
+/// - `focus_check_in_persona`: Persona asked for a report when a focus block ends
+/// - `check_in_escalation`: Follow-ups when the focus check-in goes unanswered
+/// - `watch_personas`: Reload persona files when they change on disk
+/// - `persona_reload_debounce_ms`: Quiet period before a burst of file changes is reloaded
+/// - `context_gauge`: The context-use gauge on the agent pane's right edge
+/// - `usage_status_bar`: Show the last reply's token usage in a row under the tab bar
+/// - `mouse`: Capture the mouse for wheel scrolling and tab clicks (off keeps the terminal's own text selection)
+/// - `set_terminal_title`: Show streaming / idle / error in the terminal title (restored on exit)
+/// - `tmux_status`: Write the same status to `tmux_status_path`, for `#(cat ...)` in tmux's `status-right`
+/// - `tmux_status_path`: File the tmux status is written to (removed on exit)
+/// - `hyperlinks`: Make URLs in the panes clickable (`auto`, `on`, `off`); `auto` checks the terminal
+/// - `show_timestamps`: Start with a `[HH:MM:SS]` in front of each message in the agent pane (`timestamps` toggles it)
 ///
 /// **Usage Example:**
 /// ```rust
@@ -97,6 +153,166 @@ pub struct TuiConfig {
     pub user_message_color: Color,
     pub scroll_step: u16,
     pub page_scroll_step: u16,
+    pub repeat_last_command: bool,
+    pub command_history_size: usize,
+    pub confirm_history_expansion: bool,
+    pub accessible: bool,
+    pub layout: LayoutMode,
+    pub single_pane_below_width: u16,
+    pub split_ratio: u8,
+    pub slow_command_ms: u64,
+    pub slow_frame_ms: u64,
+    pub syntax_highlighting: bool,
+    pub syntax_colors: SyntaxColors,
+    pub focus_check_in_persona: String,
+    pub check_in_escalation: CheckInEscalation,
+    pub watch_personas: bool,
+    pub persona_reload_debounce_ms: u64,
+    pub context_gauge: ContextGaugeConfig,
+    pub usage_status_bar: bool,
+    pub mouse: bool,
+    pub set_terminal_title: bool,
+    pub tmux_status: bool,
+    pub tmux_status_path: String,
+    pub hyperlinks: HyperlinkMode,
+    pub show_timestamps: bool,
+}
+
+/// # SyntaxColors
+///
+/// **Summary:**
+/// Colors for each token class in highlighted code blocks.
+///
+/// **Fields:**
+/// - `keyword`: `fn`, `let`, `match`, ...
+/// - `type_name`: Primitive and capitalized type names
+/// - `string`: String and char literals
+/// - `comment`: Line and block comments
+/// - `lifetime`: `'a`, `'static`
+/// - `macro_call`: `println!`, `vec!`, ...
+/// - `number`: Numeric literals
+#[derive(Debug, Clone)]
+pub struct SyntaxColors {
+    pub keyword: Color,
+    pub type_name: Color,
+    pub string: Color,
+    pub comment: Color,
+    pub lifetime: Color,
+    pub macro_call: Color,
+    pub number: Color,
+}
+
+impl Default for SyntaxColors {
+    fn default() -> Self {
+        Self {
+            keyword: Color::Rgb(255, 140, 0),
+            type_name: Color::LightCyan,
+            string: Color::LightGreen,
+            comment: Color::DarkGray,
+            lifetime: Color::LightMagenta,
+            macro_call: Color::LightBlue,
+            number: Color::LightRed,
+        }
+    }
+}
+
+/// # ContextGaugeConfig
+///
+/// **Summary:**
+/// How the agent pane's context gauge is colored and what it measures against.
+///
+/// **Fields:**
+/// - `enabled`: Draw the gauge
+/// - `warn_ratio`: Share of the context window at which the gauge turns `warn_color`
+/// - `danger_ratio`: Share at which it turns `danger_color`
+/// - `ok_color` / `warn_color` / `danger_color`: Fill color below, between, and above the ratios
+/// - `windows`: Context window per model; the first matching prefix wins
+/// - `default_window`: Tokens assumed for a model no entry matches
+#[derive(Debug, Clone)]
+pub struct ContextGaugeConfig {
+    pub enabled: bool,
+    pub warn_ratio: f64,
+    pub danger_ratio: f64,
+    pub ok_color: Color,
+    pub warn_color: Color,
+    pub danger_color: Color,
+    pub windows: Vec<ContextWindow>,
+    pub default_window: usize,
+}
+
+/// # ContextWindow
+///
+/// **Summary:**
+/// How many tokens one model can hold.
+///
+/// **Fields:**
+/// - `model_prefix`: Matches any model name starting with it (e.g. "grok-3")
+/// - `tokens`: Context window size
+#[derive(Debug, Clone)]
+pub struct ContextWindow {
+    pub model_prefix: String,
+    pub tokens: usize,
+}
+
+impl Default for ContextGaugeConfig {
+    fn default() -> Self {
+        let window = |model_prefix: &str, tokens: usize| ContextWindow { model_prefix: model_prefix.to_string(), tokens };
+        Self {
+            enabled: true,
+            warn_ratio: 0.5,
+            danger_ratio: 0.8,
+            ok_color: Color::Green,
+            warn_color: Color::Yellow,
+            danger_color: Color::Red,
+            windows: vec![
+                window("grok-4-fast", 2_000_000),
+                window("grok-4", 256_000),
+                window("grok-3", 131_072),
+                window("claude-", 200_000),
+            ],
+            default_window: 128_000,
+        }
+    }
+}
+
+/// # CheckInEscalation
+///
+/// **Summary:**
+/// How the check-in persona follows up when its check-in gets no reply.
+///
+/// **Fields:**
+/// - `respond_within_minutes`: Wait after the check-in before the first follow-up
+/// - `max_escalations`: Follow-ups sent at most (0 disables escalation)
+/// - `interval_growth`: Each later wait is this many times the previous one
+/// - `prompt`: Message sent to the persona for each follow-up
+/// - `bell`: Ring the terminal bell with each follow-up
+/// - `bell_in_quiet_hours`: Ring it even during `quiet_hours`
+/// - `quiet_hours`: Local start and end of the bell-free period (may wrap past midnight)
+#[derive(Debug, Clone)]
+pub struct CheckInEscalation {
+    pub respond_within_minutes: u32,
+    pub max_escalations: u32,
+    pub interval_growth: u32,
+    pub prompt: String,
+    pub bell: bool,
+    pub bell_in_quiet_hours: bool,
+    pub quiet_hours: Option<(NaiveTime, NaiveTime)>,
+}
+
+impl Default for CheckInEscalation {
+    fn default() -> Self {
+        Self {
+            respond_within_minutes: 30,
+            max_escalations: 2,
+            interval_growth: 2,
+            prompt: "I still haven't answered your check-in. Call that out bluntly and ask again \
+                     what I actually got done."
+                .to_string(),
+            bell: true,
+            bell_in_quiet_hours: false,
+            quiet_hours: NaiveTime::from_hms_opt(22, 0, 0).zip(NaiveTime::from_hms_opt(7, 0, 0)),
+        }
+    }
 }
 
 /// # HistoryConfig
@@ -109,6 +325,18 @@ pub struct TuiConfig {
 /// - `auto_save`: Whether to save after each message
 /// - `max_messages_before_summary`: Trigger summarization threshold
 /// - `messages_to_keep_after_summary`: How many recent messages to keep
+/// - `archive_streaming_threshold_bytes`: Archives larger than this are never loaded whole
+/// - `duplicate_message_window_secs`: Identical back-to-back user messages within this window are dropped (0 disables)
+/// - `language_shift_messages`: Consecutive user messages in another language before the conversation language switches
+/// - `read_marker_debounce_ms`: How long a pane must sit at the bottom of new content before it counts as read
+/// - `manual_summary_reminder_every`: In `manual` summarize mode, messages between reminders once past the threshold
+/// - `summary_message_max_chars`: Longer messages reach the historian as a placeholder (history keeps them)
+/// - `summary_transcript_max_chars`: Transcripts longer than this are summarized in parts, then merged
+/// - `long_term_fold_every`: Every this many summarizations, the recent summary is folded into the long-term one (0 = never)
+/// - `long_term_summary_max_tokens`: Length the historian is asked to keep the long-term summary under
+/// - `resync_after_hours`: Opening an agent whose last exchange is older than this offers a recap or fresh start (0 = never)
+/// - `checkpoints_kept`: Checkpoints kept per persona before the oldest are deleted
+/// - `checkpoint_budget_bytes`: Checkpoints past this total size per persona are deleted too (the newest is always kept)
 ///
 /// **Usage Example:**
 /// ```rust
@@ -123,6 +351,81 @@ pub struct HistoryConfig {
     pub auto_save: bool,
     pub max_messages_before_summary: usize,
     pub messages_to_keep_after_summary: usize,
+    pub archive_streaming_threshold_bytes: u64,
+    pub duplicate_message_window_secs: u64,
+    pub language_shift_messages: usize,
+    pub read_marker_debounce_ms: u64,
+    pub manual_summary_reminder_every: usize,
+    pub summary_message_max_chars: usize,
+    pub summary_transcript_max_chars: usize,
+    pub long_term_fold_every: usize,
+    pub long_term_summary_max_tokens: u32,
+    pub resync_after_hours: u64,
+    pub checkpoints_kept: usize,
+    pub checkpoint_budget_bytes: u64,
+}
+
+/// # TwitterConfig
+///
+/// **Summary:**
+/// Posting limits used to estimate the remaining X API write quota.
+///
+/// **Fields:**
+/// - `daily_post_limit`: Posts allowed per rolling 24 hours
+/// - `monthly_post_limit`: Posts allowed per calendar month (UTC)
+/// - `spellcheck`: Annotate outgoing tweets with spelling and punctuation hints
+/// - `spellcheck_wordlist`: Plain wordlist (one word per line) used by the spellchecker
+/// - `account_handle`: Handle shown in tweet previews (e.g. `@daegonica`), if set
+///
+/// **Details:**
+/// Defaults match the free tier. Rate-limit headers from the API take precedence when present.
+#[derive(Debug, Clone)]
+pub struct TwitterConfig {
+    pub daily_post_limit: u32,
+    pub monthly_post_limit: u32,
+    pub spellcheck: bool,
+    pub spellcheck_wordlist: String,
+    pub account_handle: Option<String>,
+}
+
+/// # HttpConfig
+///
+/// **Summary:**
+/// Connection settings for the one HTTP client each LLM provider shares.
+///
+/// **Fields:**
+/// - `connect_timeout_secs`: Give up on establishing a connection after this long
+/// - `read_idle_timeout_secs`: Fail a response that sends nothing for this long (a stalled stream)
+/// - `pool_idle_timeout_secs`: Close pooled connections unused for this long
+/// - `pool_max_idle_per_host`: Idle connections kept per host for reuse
+/// - `tcp_keepalive_secs`: TCP keepalive interval on open connections
+///
+/// **Details:**
+/// There is deliberately no overall request timeout: streamed replies can run for minutes.
+/// Grok requests are bounded per request instead (`grok.request_timeout_secs`, `grok.stream_timeout_secs`).
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    pub connect_timeout_secs: u64,
+    pub read_idle_timeout_secs: u64,
+    pub pool_idle_timeout_secs: u64,
+    pub pool_max_idle_per_host: usize,
+    pub tcp_keepalive_secs: u64,
+}
+
+/// # CredentialsConfig
+///
+/// **Summary:**
+/// How API keys and tokens are resolved between the shell environment and `.env`.
+///
+/// **Fields:**
+/// - `prefer_dotenv`: When a variable is set in both, use the `.env` value
+///
+/// **Details:**
+/// `.env` is read directly rather than loaded into the process environment, so
+/// each value's source is known and a stale shell export can be reported.
+#[derive(Debug, Clone)]
+pub struct CredentialsConfig {
+    pub prefer_dotenv: bool,
 }
 
 impl Default for GrokConfig {
@@ -131,6 +434,15 @@ impl Default for GrokConfig {
             model_name: "grok-4-fast".to_string(),
             default_temperature: 0.7,
             stream_enabled: true,
+            response_id_ttl_hours: 720,
+            known_models: ["grok-4", "grok-4-fast", "grok-3", "grok-3-mini"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            max_retries: 3,
+            initial_retry_delay_ms: 1000,
+            request_timeout_secs: 120,
+            stream_timeout_secs: 300,
         }
     }
 }
@@ -144,6 +456,29 @@ impl Default for TuiConfig {
             user_message_color: Color::LightYellow,
             scroll_step: 1,
             page_scroll_step: 10,
+            repeat_last_command: true,
+            command_history_size: 200,
+            confirm_history_expansion: true,
+            accessible: false,
+            layout: LayoutMode::Auto,
+            single_pane_below_width: 100,
+            split_ratio: 70,
+            slow_command_ms: 50,
+            slow_frame_ms: 33,
+            syntax_highlighting: true,
+            syntax_colors: SyntaxColors::default(),
+            focus_check_in_persona: "shadow".to_string(),
+            check_in_escalation: CheckInEscalation::default(),
+            watch_personas: true,
+            persona_reload_debounce_ms: 400,
+            context_gauge: ContextGaugeConfig::default(),
+            usage_status_bar: true,
+            mouse: true,
+            set_terminal_title: false,
+            tmux_status: false,
+            tmux_status_path: "shadow_status.txt".to_string(),
+            hyperlinks: HyperlinkMode::Auto,
+            show_timestamps: false,
         }
     }
 }
@@ -155,6 +490,212 @@ impl Default for HistoryConfig {
             auto_save: true,
             max_messages_before_summary: 20,
             messages_to_keep_after_summary: 12,
+            archive_streaming_threshold_bytes: 8 * 1024 * 1024,
+            duplicate_message_window_secs: 10,
+            language_shift_messages: 3,
+            read_marker_debounce_ms: 1500,
+            manual_summary_reminder_every: 10,
+            summary_message_max_chars: 6000,
+            summary_transcript_max_chars: 48_000,
+            long_term_fold_every: 3,
+            long_term_summary_max_tokens: 600,
+            resync_after_hours: 7 * 24,
+            checkpoints_kept: 10,
+            checkpoint_budget_bytes: 32 * 1024 * 1024,
+        }
+    }
+}
+
+impl Default for TwitterConfig {
+    fn default() -> Self {
+        Self {
+            daily_post_limit: 17,
+            monthly_post_limit: 500,
+            spellcheck: true,
+            spellcheck_wordlist: "/usr/share/dict/words".to_string(),
+            account_handle: None,
+        }
+    }
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout_secs: 10,
+            read_idle_timeout_secs: 120,
+            pool_idle_timeout_secs: 300,
+            pool_max_idle_per_host: 4,
+            tcp_keepalive_secs: 60,
+        }
+    }
+}
+
+impl Default for CredentialsConfig {
+    fn default() -> Self {
+        Self {
+            prefer_dotenv: true,
+        }
+    }
+}
+
+/// # EventsConfig
+///
+/// **Summary:**
+/// Where lifecycle events for external dashboards are written.
+///
+/// **Fields:**
+/// - `enabled`: Append events to `path` (the in-memory bus runs either way)
+/// - `path`: JSON-lines file, created if missing
+#[derive(Debug, Clone)]
+pub struct EventsConfig {
+    pub enabled: bool,
+    pub path: String,
+}
+
+impl Default for EventsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "events.jsonl".to_string(),
+        }
+    }
+}
+
+/// # StripConfig
+///
+/// **Summary:**
+/// Signature and boilerplate lines removed from the edges of replies.
+///
+/// **Fields:**
+/// - `use_defaults`: Apply `default_patterns` to every persona not in `exempt_personas`
+/// - `default_patterns`: Regexes for common sign-offs and "As an AI" openers
+/// - `exempt_personas`: Personas whose signatures are wanted; defaults never apply to them
+/// - `warn_only`: Report matching lines without removing them
+///
+/// **Details:**
+/// Each pattern is matched against one trimmed line at a time, and only the
+/// leading and trailing lines of a reply are checked. A persona's own
+/// `strip_patterns` apply whether or not `use_defaults` is set.
+#[derive(Debug, Clone)]
+pub struct StripConfig {
+    pub use_defaults: bool,
+    pub default_patterns: Vec<String>,
+    pub exempt_personas: Vec<String>,
+    pub warn_only: bool,
+}
+
+impl Default for StripConfig {
+    fn default() -> Self {
+        Self {
+            use_defaults: false,
+            default_patterns: vec![
+                // A whole-line, single-sentence disclaimer: "As an AI language model, I don't have opinions."
+                r"(?i)^as an (ai|ai language model|ai assistant|language model)\b[^.!?]*[.!?]$".to_string(),
+                r"(?i)^i hope (this|that) helps[.!]?$".to_string(),
+                r"(?i)^(let me know|feel free to ask) if you have any (other |more |further )?questions[.!]?$".to_string(),
+                // Sign-offs: "-Shadow", "— Friday", "~Viral" (not "- item", which is a list)
+                r"^(-|—|~|--)[A-Z][A-Za-z]*$".to_string(),
+                r"^(—|~|--)\s+[A-Z][A-Za-z]*$".to_string(),
+            ],
+            exempt_personas: vec!["viral".to_string()],
+            warn_only: false,
+        }
+    }
+}
+
+/// # ModelPrice
+///
+/// **Summary:**
+/// What one model costs, for cost estimates only.
+///
+/// **Fields:**
+/// - `model_prefix`: Matches any model name starting with it (e.g. "grok-4")
+/// - `input_per_million`: Price per million input tokens
+/// - `output_per_million`: Price per million output tokens
+#[derive(Debug, Clone)]
+pub struct ModelPrice {
+    pub model_prefix: String,
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+impl ModelPrice {
+    /// The first price in `prices` whose prefix starts `model`
+    pub fn find<'a>(prices: &'a [ModelPrice], model: &str) -> Option<&'a ModelPrice> {
+        prices.iter().find(|price| model.starts_with(&price.model_prefix))
+    }
+
+    pub fn cost(&self, input_tokens: u64, output_tokens: u64) -> f64 {
+        (input_tokens as f64 * self.input_per_million + output_tokens as f64 * self.output_per_million) / 1_000_000.0
+    }
+}
+
+/// # InsightsConfig
+///
+/// **Summary:**
+/// Settings for the `insights` usage report.
+///
+/// **Fields:**
+/// - `model_prices`: Prices for the cost estimate; the first matching prefix wins.
+///   Empty by default, so the report shows tokens but no cost until prices are filled in
+#[derive(Debug, Clone, Default)]
+pub struct InsightsConfig {
+    pub model_prices: Vec<ModelPrice>,
+}
+
+/// # ExplainConfig
+///
+/// **Summary:**
+/// Settings for `explain`, which asks about the last error in an agent pane.
+///
+/// **Fields:**
+/// - `helper_persona`: Persona asked instead once the current agent keeps failing;
+///   None explains those errors locally
+/// - `helper_after_failures`: Failed requests in a row before the current agent stops being asked
+/// - `max_prompt_chars`: Cap on the prompt sent, applied after secrets are redacted
+#[derive(Debug, Clone)]
+pub struct ExplainConfig {
+    pub helper_persona: Option<String>,
+    pub helper_after_failures: u32,
+    pub max_prompt_chars: usize,
+}
+
+impl Default for ExplainConfig {
+    fn default() -> Self {
+        Self {
+            helper_persona: None,
+            helper_after_failures: 2,
+            max_prompt_chars: 4000,
+        }
+    }
+}
+
+/// # TtsConfig
+///
+/// **Summary:**
+/// Settings for speaking finished replies aloud (`speak on|off`).
+///
+/// **Fields:**
+/// - `speak_by_default`: New agents start with speech on
+/// - `max_chars`: Longer replies are cut at the last word that fits
+/// - `max_sentences`: Only the first this many sentences are spoken (0 = no limit)
+///
+/// **Details:**
+/// Code blocks and URLs are never spoken. The program is picked per OS:
+/// `say` on macOS, PowerShell's System.Speech on Windows, `spd-say` or `espeak` on Linux.
+#[derive(Debug, Clone)]
+pub struct TtsConfig {
+    pub speak_by_default: bool,
+    pub max_chars: usize,
+    pub max_sentences: usize,
+}
+
+impl Default for TtsConfig {
+    fn default() -> Self {
+        Self {
+            speak_by_default: false,
+            max_chars: 600,
+            max_sentences: 0,
         }
     }
 }
@@ -165,6 +706,15 @@ impl Default for AppConfig {
             grok: GrokConfig::default(),
             tui: TuiConfig::default(),
             history: HistoryConfig::default(),
+            twitter: TwitterConfig::default(),
+            http: HttpConfig::default(),
+            credentials: CredentialsConfig::default(),
+            events: EventsConfig::default(),
+            strip: StripConfig::default(),
+            insights: InsightsConfig::default(),
+            explain: ExplainConfig::default(),
+            tts: TtsConfig::default(),
+            strict_mode: false,
         }
     }
 }
@@ -131,6 +131,12 @@ impl From<serde_json::Error> for ShadowError {
     }
 }
 
+impl From<serde_yaml::Error> for ShadowError {
+    fn from(err: serde_yaml::Error) -> Self {
+        ShadowError::InvalidYaml(err.to_string())
+    }
+}
+
 impl From<reqwest::Error> for ShadowError {
     fn from(err: reqwest::Error) -> Self {
         if err.is_timeout() {
@@ -12,7 +12,10 @@
 //! - Send chat requests to Grok endpoint
 //! - Stream responses via Server-Sent Events (SSE)
 //! - Parse response chunks into structured data
+//! - Assemble reply text from every output item, not just the first
 //! - Handle API Errors and status codes
+//! - Retry rate-limited (429) and server-error (5xx) streamed requests with exponential backoff
+//! - Time out a request that never answers, and a streamed reply that never ends
 //!
 //! **Author:** Daegonica Software
 //! **Version:** 0.1.0
@@ -22,9 +25,96 @@
 //! This file is part of the Daegonica Software codebase.
 //! ---------------------------------------------------------------
 
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
 use futures_util::StreamExt;
+use once_cell::sync::Lazy;
+use reqwest::StatusCode;
 use crate::prelude::*;
 use crate::llm::{LlmClient, StreamResponse};
+use crate::llm::credentials::{ApiProvider, SharedCredential};
+
+/// Output item and block types already reported as skipped (each is logged once per run)
+static LOGGED_SKIPPED: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+fn log_skipped_once(kind: &str) {
+    let mut logged = LOGGED_SKIPPED.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if logged.insert(kind.to_string()) {
+        log_debug!("Skipping Grok output of type '{}' (not reply text)", kind);
+    }
+}
+
+/// Grok's responses endpoint
+const RESPONSES_URL: &str = "https://api.x.ai/v1/responses";
+
+/// Longest wait between two retries, however many there have been
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// A timeout from the config, in seconds; 0 means none
+fn limit(secs: u64) -> Duration {
+    if secs == 0 { Duration::MAX } else { Duration::from_secs(secs) }
+}
+
+/// Whether a failed request is worth sending again: rate limits and server errors
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// # retry_delay
+///
+/// **Purpose:**
+/// How long to wait before retry number `attempt` (from 0).
+///
+/// **Details:**
+/// `initial_ms` doubled once per earlier retry, capped at `MAX_RETRY_DELAY`.
+fn retry_delay(initial_ms: u64, attempt: u8) -> Duration {
+    let delay_ms = initial_ms.saturating_mul(1u64.checked_shl(attempt.into()).unwrap_or(u64::MAX));
+    Duration::from_millis(delay_ms).min(MAX_RETRY_DELAY)
+}
+
+/// # reconcile_reply
+///
+/// **Purpose:**
+/// Checks the streamed text against the completed response and recovers what the deltas missed.
+///
+/// **Parameters:**
+/// - `full_reply`: Text assembled from `output_text` deltas so far
+/// - `response`: The response from the `response.completed` event
+///
+/// **Returns:**
+/// `Option<String>` - Text missing from the end of the stream, already appended to `full_reply`
+///
+/// **Details:**
+/// The completed response holds every output item; its reply text is all
+/// `output_text` blocks in order. When that extends the streamed text (an item
+/// whose text was never streamed), the missing tail is appended. If the two
+/// disagree otherwise, the streamed text is kept and the mismatch logged.
+fn reconcile_reply(full_reply: &mut String, response: &ResponsesApiResponse) -> Option<String> {
+    for kind in response.skipped_types() {
+        log_skipped_once(&kind);
+    }
+
+    let assembled = response.output_text();
+    if assembled.is_empty() {
+        return None;
+    }
+    match assembled.strip_prefix(full_reply.as_str()) {
+        Some("") => None,
+        Some(missing) => {
+            log_info!("Recovered {} chars of reply text that weren't streamed", missing.len());
+            full_reply.push_str(missing);
+            Some(missing.to_string())
+        }
+        None => {
+            log_info!(
+                "Streamed reply ({} bytes) differs from the completed response ({} bytes); keeping the streamed text",
+                full_reply.len(), assembled.len()
+            );
+            None
+        }
+    }
+}
 
 /// # GrokClient
 ///
@@ -32,8 +122,8 @@ use crate::llm::{LlmClient, StreamResponse};
 /// Stateless HTTP client for Grok API communication.
 ///
 /// **Fields:**
-/// - `api_key`: Bearer token for API authentication
-/// - `client`: Reqwest HTTP client instance
+/// - `credential`: Shared bearer token and reqwest client (swappable via `reload keys`)
+/// - `endpoint`: Where requests are posted (`RESPONSES_URL` outside tests)
 ///
 /// **Usage Example:**
 /// ```rust
@@ -42,8 +132,8 @@ use crate::llm::{LlmClient, StreamResponse};
 /// ```
 #[derive(Debug, Clone)]
 pub struct GrokClient {
-    api_key: String,
-    client: Client,
+    credential: SharedCredential,
+    endpoint: String,
 }
 
 impl GrokClient {
@@ -66,24 +156,52 @@ impl GrokClient {
     /// let client = GrokClient::new()?;
     /// ```
     pub fn new() -> Result<Self, String> {
-        dotenv().ok();
-        let api_key = env::var("GROK_KEY")
-            .map_err(|_| "GROK_KEY environment variable not set".to_string())?;
+        let credential = SharedCredential::for_provider(ApiProvider::Grok)?;
 
         Ok(GrokClient{
-            api_key,
-            client: Client::new(),
+            credential,
+            endpoint: RESPONSES_URL.to_string(),
         })
     }
 
+    /// Client posting to `endpoint` with `credential` (tests point it at a mock server)
+    #[cfg(test)]
+    pub(crate) fn with_credential(credential: SharedCredential, endpoint: impl Into<String>) -> Self {
+        Self { credential, endpoint: endpoint.into() }
+    }
+
+    /// # post
+    ///
+    /// **Purpose:**
+    /// Sends `request` to the responses endpoint and waits for the response headers.
+    ///
+    /// **Errors / Failures:**
+    /// - Network Errors
+    /// - No response within `grok.request_timeout_secs`
+    async fn post(&self, request: &ChatRequest) -> Result<reqwest::Response, Box<dyn std::error::Error>> {
+        let timeout = limit(GLOBAL_CONFIG.grok.request_timeout_secs);
+        let send = self.credential.client()
+            .post(&self.endpoint)
+            .bearer_auth(self.credential.api_key())
+            .json(request)
+            .send();
+        match tokio::time::timeout(timeout, send).await {
+            Ok(response) => Ok(response?),
+            Err(_) => {
+                log_error!("No response from Grok within {}s", timeout.as_secs());
+                Err(format!("Request timeout: no response from Grok within {}s", timeout.as_secs()).into())
+            }
+        }
+    }
+
     /// # send_streaming_request
     ///
     /// **Purpose:**
-    /// Sends a chat request to Grok API and streams response chunks via channel.
+    /// Sends a chat request to Grok API and streams the reply text into `stream`.
     ///
     /// **Parameters:**
     /// - `request`: The chat request payload
-    /// - `tx`: Channel sender for streaming chunks
+    /// - `stream`: The reply's stream, already begun
     ///
     /// **Returns:**
     /// `Result<StreamResponse, Box<dyn std::error::Error>>` - Complete response data or error
@@ -91,76 +209,120 @@ impl GrokClient {
     /// **StreamResponse contains:**
     /// - `response_id`: Grok's response ID for conversation continuity
     /// - `full_text`: Complete assembled response text
+    /// - `model` / token counts: As reported in the `response.completed` event
     ///
     /// **Errors / Failures:**
     /// - Network Errors
+    /// - No response within `grok.request_timeout_secs`, or a reply still streaming after `grok.stream_timeout_secs`
     /// - API authentication failures
-    /// - HTTP status Errors (non-2xx)
+    /// - HTTP status Errors (non-2xx), once any retries are used up
     /// - JSON parsing Errors
     /// - Channel send failures
     ///
+    /// **Details:**
+    /// A 429 or 5xx is retried up to `grok.max_retries` times, waiting
+    /// `grok.initial_retry_delay_ms` and then twice as long each time (at most
+    /// a minute). Each wait is announced on `stream` as an Info note.
+    ///
     /// **Examples:**
     /// ```rust
-    /// let (tx, rx) = mpsc::unbounded_channel();
-    /// let response = client.send_streaming_request(&request, tx).await?;
+    /// let mut stream = ChunkStream::open(&agent.chunk_sender, TaskKind::Reply);
+    /// stream.begin();
+    /// let response = client.send_streaming_request(&request, &mut stream).await?;
     /// ```
     pub async fn send_streaming_request(
         &self,
         request: &ChatRequest,
-        tx: mpsc::UnboundedSender<StreamChunk>,
+        stream: &mut ChunkStream,
     ) -> Result<StreamResponse, Box<dyn std::error::Error>> {
 
-        let response = self.client
-            .post("https://api.x.ai/v1/responses")
-            .bearer_auth(&self.api_key)
-            .json(request)
-            .send()
-            .await?;
+        let config = &GLOBAL_CONFIG.grok;
+        let mut attempt: u8 = 0;
+        let response = loop {
+            let response = self.post(request).await?;
 
-        let status = response.status();
+            let status = response.status();
+            if status.is_success() {
+                break response;
+            }
+
+            if is_retryable(status) && attempt < config.max_retries {
+                let delay = retry_delay(config.initial_retry_delay_ms, attempt);
+                attempt += 1;
+                log_warn!("Grok returned {}; retry {}/{} in {:?}", status, attempt, config.max_retries, delay);
+                let reason = if status == StatusCode::TOO_MANY_REQUESTS {
+                    "Rate limited".to_string()
+                } else {
+                    format!("Grok error {}", status.as_u16())
+                };
+                stream.info(format!("{}, retrying in {}s...", reason, delay.as_secs_f64()))?;
+                tokio::time::sleep(delay).await;
+                continue;
+            }
 
-        if !status.is_success() {
             let error_text = response.text().await?;
-            log_error!("API error: {} - {}", status, error_text);
-            tx.send(StreamChunk::Error(format!("API error: {} - {}", status, error_text)))?;
-            return Err(format!("API error: {}", status).into());
-        }
+            let retried = if attempt > 0 { format!(" (after {} retries)", attempt) } else { String::new() };
+            log_error!("API error: {} - {}{}", status, error_text, retried);
+            return Err(format!("API error: {} - {}{}{}", status, error_text, retried, self.credential.unauthorized_hint(status)).into());
+        };
 
 
-        let mut stream = response.bytes_stream();
+        let mut bytes = response.bytes_stream();
         let mut full_reply = String::new();
         let mut response_id: Option<String> = None;
+        let mut model: Option<String> = None;
+        let mut usage: Option<Usage> = None;
         let mut line_buffer = String::new();
 
-        while let Some(chunk_result) = stream.next().await {
-            let chunk_bytes = chunk_result?;
-            line_buffer.push_str(&String::from_utf8_lossy(&chunk_bytes));
+        let stream_timeout = limit(config.stream_timeout_secs);
+        let streamed = tokio::time::timeout(stream_timeout, async {
+            while let Some(chunk_result) = bytes.next().await {
+                let chunk_bytes = chunk_result?;
+                line_buffer.push_str(&String::from_utf8_lossy(&chunk_bytes));
 
-            while let Some(newline_pos) = line_buffer.find('\n') {
-                let line = line_buffer[..newline_pos].to_string();
-                line_buffer.drain(..=newline_pos);
+                while let Some(newline_pos) = line_buffer.find('\n') {
+                    let line = line_buffer[..newline_pos].to_string();
+                    line_buffer.drain(..=newline_pos);
 
-                if let Some(data) = line.strip_prefix("data: ") {
-                    if let Ok(delta) = serde_json::from_str::<DeltaChunk>(data) {
-                        if delta.type_ == "response.output_text.delta" {
+                    if let Some(data) = line.strip_prefix("data: ") {
+                        if let Ok(delta) = serde_json::from_str::<DeltaChunk>(data)
+                            && delta.type_ == "response.output_text.delta"
+                        {
                             full_reply.push_str(&delta.delta);
-                            tx.send(StreamChunk::Delta(delta.delta))?;
+                            stream.delta(delta.delta)?;
                         }
-                    }
 
-                    if let Ok(complete) = serde_json::from_str::<CompletedChunk>(data) {
-                        if complete.type_ == "response.completed" {
+                        if let Ok(complete) = serde_json::from_str::<CompletedChunk>(data)
+                            && complete.type_ == "response.completed"
+                        {
+                            if let Some(missing) = reconcile_reply(&mut full_reply, &complete.response) {
+                                stream.delta(missing)?;
+                            }
                             response_id = Some(complete.response.id.clone());
+                            model = Some(complete.response.model);
+                            usage = complete.response.usage;
                         }
                     }
                 }
             }
+            Ok::<(), Box<dyn std::error::Error>>(())
+        }).await;
+        match streamed {
+            Ok(result) => result?,
+            // The caller ends the reply stream with this error, like any other
+            Err(_) => {
+                log_error!("Grok stream still running after {}s ({} chars so far); giving up", stream_timeout.as_secs(), full_reply.len());
+                return Err(format!("Stream timeout: reply not finished within {}s", stream_timeout.as_secs()).into());
+            }
         }
 
 
         Ok(StreamResponse {
             response_id: response_id.ok_or("No response ID received")?,
             full_text: full_reply,
+            model,
+            input_tokens: usage.as_ref().map(|u| u.input_tokens),
+            output_tokens: usage.as_ref().map(|u| u.output_tokens),
         })
     }
 
@@ -187,24 +349,21 @@ impl GrokClient {
         print_stream: bool,
     ) -> Result<StreamResponse, Box<dyn std::error::Error>> {
 
-        let response = self.client
-            .post("https://api.x.ai/v1/responses")
-            .bearer_auth(&self.api_key)
-            .json(request)
-            .send()
-            .await?;
+        let response = self.post(request).await?;
 
         let status = response.status();
 
         if !status.is_success() {
-            let _error_text = response.text().await?;
-            log_error!("API error: {}", status);
-            return Err(format!("API error: {}", status).into());
+            let error_text = response.text().await?;
+            log_error!("API error: {} - {}", status, error_text);
+            return Err(format!("API error: {} - {}{}", status, error_text, self.credential.unauthorized_hint(status)).into());
         }
 
         let mut stream = response.bytes_stream();
         let mut full_reply = String::new();
         let mut response_id: Option<String> = None;
+        let mut model: Option<String> = None;
+        let mut usage: Option<Usage> = None;
         let mut line_buffer = String::new();
 
         while let Some(chunk_result) = stream.next().await {
@@ -233,7 +392,14 @@ impl GrokClient {
 
                     if let Ok(completed) = serde_json::from_str::<CompletedChunk>(data) {
                         if completed.type_ == "response.completed" {
+                            let missing = reconcile_reply(&mut full_reply, &completed.response);
+                            if let Some(missing) = missing.filter(|_| print_stream) {
+                                print!("{}", missing);
+                                io::stdout().flush().ok();
+                            }
                             response_id = Some(completed.response.id.clone());
+                            model = Some(completed.response.model);
+                            usage = completed.response.usage;
                         }
                     }
                 }
@@ -247,6 +413,9 @@ impl GrokClient {
         Ok(StreamResponse {
             response_id: response_id.ok_or("No response ID received")?,
             full_text: full_reply,
+            model,
+            input_tokens: usage.as_ref().map(|u| u.input_tokens),
+            output_tokens: usage.as_ref().map(|u| u.output_tokens),
         })
     }
 
@@ -259,9 +428,9 @@ impl LlmClient for GrokClient {
     async fn send_streaming(
         &self,
         request: &ChatRequest,
-        tx: mpsc::UnboundedSender<StreamChunk>,
+        stream: &mut ChunkStream,
     ) -> Result<StreamResponse, Box<dyn std::error::Error>> {
-        self.send_streaming_request(request, tx).await
+        self.send_streaming_request(request, stream).await
     }
 
     async fn send_blocking(
@@ -271,4 +440,119 @@ impl LlmClient for GrokClient {
     ) -> Result<StreamResponse, Box<dyn std::error::Error>> {
         self.send_blocking_request(request, print_stream).await
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::credentials::{EnvValue, Provenance, ValueSource};
+    use crate::test_support::{MockResponse, MockServer};
+
+    /// The raw `response` of a fixture in `tests/fixtures/responses/`
+    fn fixture_json(name: &str) -> serde_json::Value {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("tests/fixtures/responses/{}.json", name));
+        serde_json::from_str(&fs::read_to_string(&path).expect("fixture exists")).expect("fixture parses")
+    }
+
+    fn fixture(name: &str) -> ResponsesApiResponse {
+        serde_json::from_value(fixture_json(name)).unwrap()
+    }
+
+    /// An SSE body streaming `deltas`, then completing with the fixture's response
+    fn sse(deltas: &[&str], fixture: &str) -> String {
+        let mut body = String::new();
+        for (n, delta) in deltas.iter().enumerate() {
+            let event = serde_json::json!({
+                "type": "response.output_text.delta",
+                "delta": delta,
+                "sequence_number": n,
+                "content_index": 0,
+                "item_id": "msg_1",
+                "output_index": 0,
+            });
+            body.push_str(&format!("data: {}\n\n", event));
+        }
+        let completed = serde_json::json!({ "type": "response.completed", "response": fixture_json(fixture) });
+        body.push_str(&format!("data: {}\n\n", completed));
+        body
+    }
+
+    fn hello() -> ChatRequest {
+        ChatRequest::builder()
+            .model("grok-mock")
+            .messages(vec![Message { role: "user".into(), content: "hello".into(), meta: None, source: None }])
+            .stream(true)
+            .build()
+            .unwrap()
+    }
+
+    async fn client_for(body: String) -> (MockServer, GrokClient) {
+        let server = MockServer::start(move |_| MockResponse::sse(body.clone())).await;
+        let key = EnvValue {
+            value: "test-key".to_string(),
+            provenance: Provenance { source: ValueSource::DotEnv, both_set: false, differs: false },
+        };
+        let credential = SharedCredential::detached(ApiProvider::Grok, key);
+        let client = GrokClient::with_credential(credential, server.url("/v1/responses"));
+        (server, client)
+    }
+
+    #[test]
+    fn reconciling_recovers_a_missing_tail_and_keeps_a_mismatch() {
+        let multi = "First, the short answer. Then the details.\n\nAnd a second message.";
+        let cases = [
+            ("nothing streamed", "", multi, Some(multi)),
+            ("only the first message streamed", "First, the short answer. Then the details.", multi, Some("\n\nAnd a second message.")),
+            ("everything streamed", multi, multi, None),
+            ("a different stream is kept", "Something else entirely.", "Something else entirely.", None),
+        ];
+
+        for (case, streamed, reply, missing) in cases {
+            let mut full_reply = streamed.to_string();
+            assert_eq!(reconcile_reply(&mut full_reply, &fixture("multi_message")).as_deref(), missing, "{}", case);
+            assert_eq!(full_reply, reply, "{}", case);
+        }
+
+        let mut full_reply = "Streamed.".to_string();
+        let empty = serde_json::from_str(r#"{"id":"r","object":"response","created_at":0,"model":"m","output":[]}"#).unwrap();
+        assert_eq!(reconcile_reply(&mut full_reply, &empty), None, "an empty completed response changes nothing");
+        assert_eq!(full_reply, "Streamed.");
+    }
+
+    #[tokio::test]
+    async fn a_streamed_reply_gets_every_item_in_order() {
+        let (_server, client) = client_for(sse(&["Autumn wind rises"], "mixed_blocks")).await;
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut stream = ChunkStream::open(&tx, TaskKind::Reply);
+        stream.begin();
+
+        let response = client.send_streaming_request(&hello(), &mut stream).await.unwrap();
+        stream.complete(response.response_id.clone(), response.full_text.clone(), None).unwrap();
+        drop(stream);
+
+        assert_eq!(response.response_id, "resp_mixed_blocks");
+        assert_eq!(response.full_text, "Autumn wind rises / leaves forget their names / the gate stays open");
+        assert_eq!((response.input_tokens, response.output_tokens), (Some(30), Some(20)));
+        let mut deltas = Vec::new();
+        while let Ok(tagged) = rx.try_recv() {
+            if let StreamChunk::Delta(text) = tagged.chunk {
+                deltas.push(text);
+            }
+        }
+        assert_eq!(deltas, ["Autumn wind rises", " / leaves forget their names / the gate stays open"], "the missing tail is streamed too");
+    }
+
+    #[tokio::test]
+    async fn a_blocking_reply_gets_every_item_in_order() {
+        let cases = [
+            (sse(&[], "multi_message"), "First, the short answer. Then the details.\n\nAnd a second message."),
+            (sse(&["Only this ", "survives."], "empty_content"), "Only this survives."),
+        ];
+
+        for (body, expected) in cases {
+            let (_server, client) = client_for(body).await;
+            let response = client.send_blocking_request(&hello(), false).await.unwrap();
+            assert_eq!(response.full_text, expected);
+        }
+    }
+}
@@ -34,6 +34,52 @@ pub mod errors;
 pub mod llm;
 pub mod claude;
 
+#[cfg(test)]
+mod test_support;
+
+#[doc(hidden)]
+pub use dlog as __dlog;
+
+/// Logs at error level through dlog, subject to the runtime `loglevel` filter
+#[macro_export]
+macro_rules! log_error {
+    ($($arg:tt)*) => {
+        $crate::utilities::log_level::log($crate::utilities::log_level::LogLevel::Error, module_path!(), format_args!($($arg)*))
+    };
+}
+
+/// Logs at warn level through dlog, subject to the runtime `loglevel` filter
+#[macro_export]
+macro_rules! log_warn {
+    ($($arg:tt)*) => {
+        $crate::utilities::log_level::log($crate::utilities::log_level::LogLevel::Warn, module_path!(), format_args!($($arg)*))
+    };
+}
+
+/// Logs at info level through dlog, subject to the runtime `loglevel` filter
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)*) => {
+        $crate::utilities::log_level::log($crate::utilities::log_level::LogLevel::Info, module_path!(), format_args!($($arg)*))
+    };
+}
+
+/// Logs at debug level through dlog, subject to the runtime `loglevel` filter (off by default)
+#[macro_export]
+macro_rules! log_debug {
+    ($($arg:tt)*) => {
+        $crate::utilities::log_level::log($crate::utilities::log_level::LogLevel::Debug, module_path!(), format_args!($($arg)*))
+    };
+}
+
+/// Logs at trace level through dlog, subject to the runtime `loglevel` filter (off by default)
+#[macro_export]
+macro_rules! log_trace {
+    ($($arg:tt)*) => {
+        $crate::utilities::log_level::log($crate::utilities::log_level::LogLevel::Trace, module_path!(), format_args!($($arg)*))
+    };
+}
+
 pub fn capitalize_first(s: &str) -> String {
     let mut chars = s.chars();
     match chars.next() {
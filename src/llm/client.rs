@@ -9,6 +9,7 @@
 //! **Responsibilities:**
 //! - Coordinate between client, conversation, and history
 //! - Provide unified API for all LLM backends
+//! - Offer `send_and_collect` / `send_with_callback` for embedding without channels
 //!
 //! **Author:** Daegonica Software
 //! **Version:** 0.1.0
@@ -16,7 +17,21 @@
 
 use crate::prelude::*;
 use crate::llm::LlmClient;
-use std::path::Path;
+use crate::llm::usage::{SessionUsage, UsageKind};
+use crate::persona::builtin::load_builtin;
+use crate::persona::persona_dir;
+use crate::agent_history::persistence;
+use crate::agent_history::checkpoint::{self, CheckpointReason};
+use crate::utilities::language;
+use crate::utilities::stream_text::normalize_stream_text;
+use crate::utilities::events::{self, EventKind};
+use crate::agent_history::shaping::{self, ElisionReport};
+use crate::agent_history::summary_format::SummarySections;
+use crate::utilities::strip::StripRules;
+use crate::agent_history::redaction::{self, Redactor};
+use crate::agent_history::conversations::{is_nudge, is_summary_message, response_id_rejected, LONG_TERM_PREFIX, SUMMARY_PREFIX};
+use std::time::Instant;
+use uuid::Uuid;
 
 /// Generic LLM connection that works with ANY client
 #[derive(Debug, Clone)]
@@ -24,6 +39,14 @@ pub struct Connection<T: LlmClient> {
     client: T,
     pub conversation: GrokConversation,
     output: Option<SharedOutput>,
+    /// History length before a `send_with_callback` that hasn't completed yet
+    unanswered_from: Option<usize>,
+    /// Agent this connection belongs to, stamped on lifecycle events (None when embedded)
+    pub agent_id: Option<Uuid>,
+    /// Replies this session whose edge lines matched `strip_patterns` (stripped, or flagged with `warn_only`)
+    pub strip_matches: usize,
+    /// Tokens this connection's requests used this session, for `usage`
+    pub usage: SessionUsage,
 }
 
 impl<T: LlmClient> Connection<T> {
@@ -50,12 +73,42 @@ impl<T: LlmClient> Connection<T> {
     pub fn new_without_output(client: T, persona: Arc<Persona>) -> Self {
 
         let conversation = if persona.enable_history {
-            if let Ok(loaded_history) = HistoryManager::load_persona_history(&persona.name) {
+            let loaded = HistoryManager::load_persona_history(&persona.name);
+            // A missing file just means a fresh start; anything else deserves a log line
+            match &loaded {
+                Err(e) if e.downcast_ref::<io::Error>().is_none_or(|e| e.kind() != io::ErrorKind::NotFound) => {
+                    log_error!("Could not load history for {}: {}", persona.name, e);
+                }
+                _ => {}
+            }
+            if let Ok(loaded_history) = loaded {
                 log_info!("Loaded history for {}: {} total messages",
                     persona.name, loaded_history.total_message_count);
 
+                let started_at = loaded_history.started_at.clone();
+                let summarization_count = loaded_history.summarization_count;
+                let total_message_count = loaded_history.total_message_count;
+                // Older files only know when they were last saved, which is close enough
+                let last_exchange_at = loaded_history.last_exchange_at.as_deref()
+                    .unwrap_or(&loaded_history.last_updated)
+                    .parse::<chrono::DateTime<chrono::Utc>>()
+                    .ok();
+                let response_id = loaded_history.last_response_id.clone();
+                let response_id_at = loaded_history.last_response_at.as_deref()
+                    .and_then(|at| at.parse::<chrono::DateTime<chrono::Utc>>().ok());
                 let messages = HistoryManager::build_history_from_loaded(&persona, loaded_history);
-                GrokConversation::with_history(Arc::clone(&persona), messages)
+                let mut conversation = GrokConversation::with_history(Arc::clone(&persona), messages);
+                if let Some(started_at) = started_at {
+                    conversation.started_at = started_at;
+                }
+                conversation.summarization_count = summarization_count;
+                conversation.total_message_count = total_message_count;
+                conversation.last_exchange_at = last_exchange_at;
+                // Picks the server-side thread back up; an expired one is dropped before the next request
+                if let Some(id) = response_id {
+                    conversation.restore_response_id(id, response_id_at);
+                }
+                conversation
             } else {
                 log_info!("No history found for {}, starting fresh", persona.name);
                 GrokConversation::new(persona)
@@ -69,6 +122,10 @@ impl<T: LlmClient> Connection<T> {
             client,
             conversation,
             output: None,
+            unanswered_from: None,
+            agent_id: None,
+            strip_matches: 0,
+            usage: SessionUsage::default(),
         }
     }
 
@@ -103,9 +160,13 @@ impl<T: LlmClient> Connection<T> {
     /// - `content`: The user's message text
     ///
     /// **Returns:**
-    /// None (delegates to conversation)
-    pub fn add_user_message(&mut self, content: &str) {
-        self.conversation.add_user_message(content);
+    /// `bool` - false if the conversation dropped it as a duplicate
+    pub fn add_user_message(&mut self, content: &str) -> bool {
+        let added = self.conversation.add_user_message(content);
+        if added {
+            self.conversation.observe_language(content);
+        }
+        added
     }
 
     /// # save_history
@@ -143,7 +204,31 @@ impl<T: LlmClient> Connection<T> {
     /// **Returns:**
     /// `Result<(), Box<dyn std::error::Error>>` - Success or error
     pub fn save_persona_history(&self) -> Result<(), Box<dyn std::error::Error>> {
-        HistoryManager::save_persona_history(&self.conversation)
+        persistence::explicit_write(|| HistoryManager::save_persona_history(&self.conversation))
+    }
+
+    /// # checkpoint
+    ///
+    /// **Purpose:**
+    /// Checkpoints the conversation before a user-run operation rewrites it (see `agent_history::checkpoint`).
+    ///
+    /// **Returns:**
+    /// `Result<Option<PathBuf>, Box<dyn std::error::Error>>` - The checkpoint file, or None
+    /// if none was needed (no history kept, or unchanged since the newest)
+    pub fn checkpoint(&self, reason: CheckpointReason) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+        persistence::explicit_write(|| checkpoint::write_checkpoint(&self.conversation, reason))
+    }
+
+    /// # autosave_persona_history
+    ///
+    /// **Purpose:**
+    /// Saves after an exchange, unless persistence is suspended (see `agent_history::persistence`).
+    ///
+    /// **Returns:**
+    /// `Result<bool, Box<dyn std::error::Error>>` - false if the save was skipped
+    pub fn autosave_persona_history(&self) -> Result<bool, Box<dyn std::error::Error>> {
+        persistence::auto_write(|| HistoryManager::save_persona_history(&self.conversation))
+            .map(|saved| saved.is_some())
     }
 
     /// # load_persona_history
@@ -193,13 +278,92 @@ impl<T: LlmClient> Connection<T> {
         &self.conversation.persona
     }
 
+    /// # response_meta
+    ///
+    /// **Purpose:**
+    /// Builds the "which model wrote this" record for a completed reply.
+    ///
+    /// **Details:**
+    /// Prefers the model id reported by the API over the one requested.
+    fn response_meta(request: &ChatRequest, response: &StreamResponse, started: Instant) -> ResponseMeta {
+        ResponseMeta {
+            model: response.model.clone().unwrap_or_else(|| request.model.clone()),
+            temperature: request.temperature,
+            latency_ms: Some(started.elapsed().as_millis() as u64),
+            input_tokens: response.input_tokens,
+            output_tokens: response.output_tokens,
+            stripped: Vec::new(),
+        }
+    }
+
+    /// # strip_reply
+    ///
+    /// **Purpose:**
+    /// Applies the persona's strip rules to a finished reply before it goes into history.
+    ///
+    /// **Returns:**
+    /// `(String, Option<String>)` - The text to commit, and a note for the user when lines matched
+    ///
+    /// **Details:**
+    /// Removed lines are recorded in `meta.stripped` so `whence` can show them.
+    /// With `strip.warn_only` the reply is kept as-is and only the note is returned.
+    fn strip_reply(&mut self, text: String, meta: &mut ResponseMeta) -> (String, Option<String>) {
+        let rules = StripRules::for_persona(&self.conversation.persona, &GLOBAL_CONFIG.strip);
+        let Some(stripped) = rules.apply(&text) else {
+            return (text, None);
+        };
+        self.strip_matches += 1;
+        let lines: Vec<String> = stripped.removed.iter().map(|line| format!("\"{}\"", line)).collect();
+        if rules.warn_only {
+            return (text, Some(format!("Reply has lines matching strip patterns (kept, warn_only): {}", lines.join(", "))));
+        }
+        log_info!("Stripped {} line(s) from {} reply", stripped.removed.len(), self.conversation.persona.name);
+        meta.stripped = stripped.removed;
+        (stripped.text, Some(format!("Stripped from the saved reply: {}", lines.join(", "))))
+    }
+
+    /// Publishes a lifecycle event for this connection's persona and agent
+    fn publish(&self, kind: EventKind) {
+        events::publish(kind, Some(&self.conversation.persona.name), self.agent_id);
+    }
+
+    /// The `message_completed` event for a finished request
+    fn completed_event(meta: &ResponseMeta, response: &StreamResponse) -> EventKind {
+        EventKind::MessageCompleted {
+            model: meta.model.clone(),
+            latency_ms: meta.latency_ms,
+            input_tokens: meta.input_tokens,
+            output_tokens: meta.output_tokens,
+            reply_chars: response.full_text.chars().count(),
+        }
+    }
+
+    /// Publishes an `error` event and passes the error on
+    fn publish_error<E: std::fmt::Display>(&self, source: &str, error: E) -> E {
+        self.publish(EventKind::Error { source: source.to_string(), message: error.to_string() });
+        error
+    }
+
+    /// Notice shown when the server rejects the response ID and the request is retried
+    fn rejected_id_note() -> String {
+        "The server no longer has this conversation's thread; sending the full history instead.".to_string()
+    }
+
+    /// Notice shown when a stale response ID is dropped before a request
+    fn expired_id_note() -> String {
+        format!(
+            "Thread older than {}h; the server may have dropped it, so the full history is being sent.",
+            GLOBAL_CONFIG.grok.response_id_ttl_hours
+        )
+    }
+
     /// # handle_response_streaming
     ///
     /// **Purpose:**
     /// Sends request and streams response chunks via channel (for TUI mode).
     ///
     /// **Parameters:**
-    /// - `tx`: Channel sender for StreamChunk messages
+    /// - `stream`: The reply's stream; the caller reports an `Err` to it
     ///
     /// **Returns:**
     /// `Result<(), Box<dyn std::error::Error>>` - Success or error
@@ -212,45 +376,253 @@ impl<T: LlmClient> Connection<T> {
     /// - Triggers summarization if threshold reached
     pub async fn handle_response_streaming(
         &mut self,
-        tx: mpsc::UnboundedSender<StreamChunk>,
+        stream: &mut ChunkStream,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        log_info!("Handling streaming response");
+        self.stream_reply(stream, true).await.map(|_| ())
+    }
 
-        let request = self.conversation.build_request();
+    /// # handle_response_streaming_without_nudges
+    ///
+    /// **Purpose:**
+    /// `handle_response_streaming` for requests active nudges must not steer (tweet drafts).
+    pub async fn handle_response_streaming_without_nudges(
+        &mut self,
+        stream: &mut ChunkStream,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.stream_reply(stream, false).await.map(|_| ())
+    }
 
-        let response = self.client.send_streaming(&request, tx.clone()).await?;
+    /// # stream_reply
+    ///
+    /// **Purpose:**
+    /// The body of `handle_response_streaming`, returning what was received.
+    ///
+    /// **Parameters:**
+    /// - `stream`: The reply's stream, begun here
+    /// - `with_nudges`: Send active nudges with the request
+    ///
+    /// **Details:**
+    /// Once the assistant message is in history, any pending rollback from
+    /// `send_with_callback` is cleared and the stream is committed, so the
+    /// pane stops offering to cancel it: the exchange is complete even if the
+    /// save or summarization after it is interrupted. Save and summarization
+    /// failures don't fail the reply; they follow it as notices.
+    async fn stream_reply(
+        &mut self,
+        stream: &mut ChunkStream,
+        with_nudges: bool,
+    ) -> Result<CompletedReply, Box<dyn std::error::Error>> {
+        log_debug!("Handling streaming response");
+        stream.begin();
+
+        if self.conversation.expire_stale_response_id() {
+            stream.info(Self::expired_id_note())?;
+        }
+        let mut request = self.conversation.build_request_with(with_nudges)?;
+
+        self.publish(EventKind::MessageSent { history_messages: request.input.len() });
+        let started = Instant::now();
+        let threaded = request.previous_response_id.is_some();
+        let response = match self.client.send_streaming(&request, stream).await {
+            Err(e) if threaded && response_id_rejected(&e.to_string()) => None,
+            result => Some(result.map_err(|e| self.publish_error("request", e))?),
+        };
+        // The server lost the thread: send the full history once instead
+        let response = match response {
+            Some(response) => response,
+            None => {
+                self.conversation.forget_response_id();
+                stream.info(Self::rejected_id_note())?;
+                request = self.conversation.build_request_with(with_nudges)?;
+                self.client.send_streaming(&request, stream).await
+                    .map_err(|e| self.publish_error("request", e))?
+            }
+        };
+        let mut meta = Self::response_meta(&request, &response, started);
+        self.usage.record(UsageKind::Reply, &meta.model, meta.input_tokens, meta.output_tokens);
+        self.publish(Self::completed_event(&meta, &response));
+        // Saved as the pane shows it: progress rewrites collapsed, escape codes dropped
+        let (text, strip_note) = self.strip_reply(normalize_stream_text(&response.full_text), &mut meta);
+        if let Some(note) = strip_note {
+            stream.info(note)?;
+        }
 
-        self.conversation.add_assistant_message(response.full_text);
+        self.conversation.add_assistant_message_with_meta(text.clone(), Some(meta.clone()));
         self.conversation.set_last_response_id(response.response_id.clone());
+        self.conversation.nudges_sent(with_nudges);
+        self.unanswered_from = None;
+        // Past this point a stop would undo a saved reply or cut summarization short
+        stream.commit();
 
+        let mut warnings = Vec::new();
         if self.conversation.persona.enable_history {
-            if let Err(e) = self.save_persona_history() {
+            if let Err(e) = self.autosave_persona_history() {
                 log_error!("Failed to save history: {}", e);
+                self.publish_error("save", &e);
+                // Only the failure that suspends persistence is reported; later saves are skipped
+                if let Some(warning) = persistence::warning() {
+                    warnings.push(format!(
+                        "{}; automatic saves are paused until 'save' succeeds", capitalize_first(&warning)
+                    ));
+                }
             }
 
-            if self.conversation.should_summarize() {
+            // Summarizing archives and rewrites the history file; pointless while writes fail
+            if self.conversation.should_summarize() && persistence::suspended_reason().is_none() {
                 log_info!("History threshold reached, triggering summarization...");
-                tx.send(StreamChunk::Info("Summarizing conversation history...".to_string()))?;
-
-                if let Err(e) = self.summarize_history().await {
-                    log_error!("Summarization failed: {}", e);
-                    tx.send(StreamChunk::Error(format!("Summarization failed: {}", e)))?;
-                } else {
-                    if let Err(e) = self.save_persona_history() {
-                        log_error!("Failed to save summarized history: {}", e);
+                stream.info("Summarizing conversation history...")?;
+
+                match self.summarize_history().await {
+                    Err(e) => {
+                        log_error!("Summarization failed: {}", e);
+                        self.publish_error("summarize", &e);
+                        warnings.push(format!("Summarization failed: {}", e));
+                    }
+                    Ok(outcome) => {
+                        if let Some(outcome) = outcome {
+                            stream.info(format!("Summarization complete ({})", outcome.describe()))?;
+                            if let Some(note) = outcome.elided.describe() {
+                                stream.info(note)?;
+                            }
+                        }
+                        if let Err(e) = self.autosave_persona_history() {
+                            log_error!("Failed to save summarized history: {}", e);
+                        }
                     }
                 }
             }
         }
 
-        tx.send(StreamChunk::Complete {
-            response_id: response.response_id,
-            full_reply: self.conversation.local_history.last()
+        stream.complete(
+            response.response_id.clone(),
+            self.conversation.local_history.last()
                 .map(|m| m.content.clone())
                 .unwrap_or_default(),
-        })?;
+            Usage::from_counts(meta.input_tokens, meta.output_tokens),
+        )?;
 
-        Ok(())
+        // Sent after Complete so they land below the reply, not inside it
+        for warning in warnings {
+            stream.notice(warning)?;
+        }
+        if self.conversation.persona.enable_history && Redactor::for_persona(&self.conversation.persona).matches(&text) {
+            stream.notice(format!("{} reply will be stored redacted", redaction::redacted_badge()))?;
+        }
+        if let Some(count) = self.conversation.summary_reminder(GLOBAL_CONFIG.history.manual_summary_reminder_every) {
+            stream.notice(format!("History is {} messages — consider `summarize`", count))?;
+        }
+
+        Ok(CompletedReply {
+            text,
+            response_id: response.response_id,
+            meta,
+        })
+    }
+
+    /// # send_and_collect
+    ///
+    /// **Purpose:**
+    /// Sends one user message and waits for the whole reply. This is the main
+    /// entry point for code embedding the crate.
+    ///
+    /// **Parameters:**
+    /// - `content`: The user's message text, added to the conversation as-is
+    ///
+    /// **Returns:**
+    /// `Result<CompletedReply, ShadowError>` - The reply text, response ID, usage and latency
+    ///
+    /// **Details:**
+    /// Same path as the TUI: history is saved and summarized as usual. Progress
+    /// chunks are discarded; use `send_with_callback` to see them.
+    ///
+    /// **Examples:**
+    /// ```ignore
+    /// use grokprime_brain::llm::client::Connection;
+    ///
+    /// let mut shadow = Connection::new_without_output(client, persona);
+    /// let reply = shadow.send_and_collect("What changed in Rust 2024?").await?;
+    /// println!("{} ({})", reply.text, reply.meta.describe());
+    /// ```
+    pub async fn send_and_collect(&mut self, content: &str) -> Result<CompletedReply, ShadowError> {
+        self.send_with_callback(content, |_| {}).await
+    }
+
+    /// # send_with_callback
+    ///
+    /// **Purpose:**
+    /// Like `send_and_collect`, calling `on_chunk` for every chunk as it arrives.
+    ///
+    /// **Parameters:**
+    /// - `content`: The user's message text
+    /// - `on_chunk`: Called with each Delta/Info/Error chunk, then the Complete chunk (and any Notice after it)
+    ///
+    /// **Returns:**
+    /// `Result<CompletedReply, ShadowError>` - The completed reply
+    ///
+    /// **Errors / Failures:**
+    /// - `ShadowError::ApiError` if the request or stream fails
+    ///
+    /// **Details:**
+    /// A failed send removes its user message again, so a retry doesn't leave two
+    /// user messages in a row. Dropping the future drops the request with it; the
+    /// unanswered user message is removed at the start of the next send (a drop
+    /// can't touch history itself). The duplicate-message guard is skipped, since
+    /// a repeat here is a deliberate call rather than a racing retry.
+    ///
+    /// **Examples:**
+    /// ```ignore
+    /// use grokprime_brain::models::StreamChunk;
+    ///
+    /// let reply = shadow.send_with_callback("Summarize this file", |chunk| {
+    ///     if let StreamChunk::Delta(text) = chunk {
+    ///         print!("{}", text);
+    ///     }
+    /// }).await?;
+    /// ```
+    pub async fn send_with_callback(
+        &mut self,
+        content: &str,
+        mut on_chunk: impl FnMut(&StreamChunk),
+    ) -> Result<CompletedReply, ShadowError> {
+        self.roll_back_unanswered();
+        self.unanswered_from = Some(self.conversation.local_history.len());
+        self.conversation.force_user_message(content);
+        self.conversation.observe_language(content);
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let stream = ChunkStream::open(&tx, TaskKind::Reply);
+        drop(tx);
+        let drain = async {
+            while let Some(tagged) = rx.recv().await {
+                on_chunk(&tagged.chunk);
+            }
+        };
+        // The stream moves into the reply so the drain ends when the reply does
+        let reply = async {
+            let mut stream = stream;
+            let result = self.stream_reply(&mut stream, true).await;
+            if let Err(e) = &result && !stream.is_finished() {
+                let _ = stream.fail(e.to_string());
+            }
+            result
+        };
+        let (result, ()) = tokio::join!(reply, drain);
+
+        result.map_err(|e| {
+            self.roll_back_unanswered();
+            ShadowError::ApiError(e.to_string())
+        })
+    }
+
+    /// Removes a user message whose `send_with_callback` never completed
+    fn roll_back_unanswered(&mut self) {
+        let history_len = self.conversation.local_history.len();
+        if let Some(len) = self.unanswered_from.take().filter(|len| *len < history_len) {
+            log_info!("Removing {} unanswered message(s) for {}", history_len - len, self.conversation.persona.name);
+            self.conversation.local_history.truncate(len);
+            // Never answered, so never part of the conversation
+            self.conversation.total_message_count = self.conversation.total_message_count.saturating_sub(history_len - len);
+        }
     }
 
     /// # handle_response
@@ -261,19 +633,51 @@ impl<T: LlmClient> Connection<T> {
     /// **Returns:**
     /// `Result<(), Box<dyn std::error::Error>>` - Success or error
     pub async fn handle_response(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        log_info!("Handling blocking response");
+        log_debug!("Handling blocking response");
 
-        let request = self.conversation.build_request();
+        if self.conversation.expire_stale_response_id() {
+            println!("{}", Self::expired_id_note());
+        }
+        let mut request = self.conversation.build_request()?;
 
         let print_stream = true;
-        let response = self.client.send_blocking(&request, print_stream).await?;
+        self.publish(EventKind::MessageSent { history_messages: request.input.len() });
+        let started = Instant::now();
+        let threaded = request.previous_response_id.is_some();
+        let response = match self.client.send_blocking(&request, print_stream).await {
+            Err(e) if threaded && response_id_rejected(&e.to_string()) => None,
+            result => Some(result.map_err(|e| self.publish_error("request", e))?),
+        };
+        // The server lost the thread: send the full history once instead
+        let response = match response {
+            Some(response) => response,
+            None => {
+                self.conversation.forget_response_id();
+                println!("{}", Self::rejected_id_note());
+                request = self.conversation.build_request()?;
+                self.client.send_blocking(&request, print_stream).await
+                    .map_err(|e| self.publish_error("request", e))?
+            }
+        };
+        let mut meta = Self::response_meta(&request, &response, started);
+        self.usage.record(UsageKind::Reply, &meta.model, meta.input_tokens, meta.output_tokens);
+        self.publish(Self::completed_event(&meta, &response));
 
-        self.conversation.add_assistant_message(response.full_text);
+        let (text, strip_note) = self.strip_reply(normalize_stream_text(&response.full_text), &mut meta);
+        if let Some(note) = strip_note {
+            println!("{}", note);
+        }
+        self.conversation.add_assistant_message_with_meta(text, Some(meta));
         self.conversation.set_last_response_id(response.response_id);
+        self.conversation.nudges_sent(true);
 
         if self.conversation.persona.enable_history {
-            if let Err(e) = self.save_persona_history() {
+            if let Err(e) = self.autosave_persona_history() {
                 log_error!("Failed to save history: {}", e);
+                self.publish_error("save", &e);
+                if let Some(warning) = persistence::warning() {
+                    eprintln!("Warning: {}; automatic saves are paused until 'save' succeeds", warning);
+                }
             }
         }
 
@@ -286,16 +690,27 @@ impl<T: LlmClient> Connection<T> {
     /// Triggers conversation summarization using historian persona.
     ///
     /// **Returns:**
-    /// `Result<(), Box<dyn std::error::Error>>` - Success or error
+    /// `Result<Option<SummaryOutcome>, Box<dyn std::error::Error>>` - How the summary fared
+    /// and what the historian didn't see, or None if there was nothing to summarize
     ///
     /// **Details:**
-    /// - Archives full history before summarization
-    /// - Sends old messages to historian for summarization
-    /// - Rebuilds history with summary + recent messages
+    /// - Archives full history and writes a checkpoint before summarization
+    /// - Sends old messages to historian, with the persona's style and length limit
+    /// - Pasted blobs (oversized, base64/hex, long logs) go as placeholders; history keeps them
+    /// - A transcript still over `summary_transcript_max_chars` is summarized in parts,
+    ///   and a final pass merges the part summaries
+    /// - Required terms mentioned in those messages must survive into the summary;
+    ///   if any are missing, the historian gets one request to revise
+    /// - The previous recent summary is carried forward as an EARLIER SUMMARY entry,
+    ///   except every `long_term_fold_every`th time, when it is folded into the
+    ///   long-term summary instead and the new recent summary covers only the new messages
+    /// - Rebuilds history with long-term summary + recent summary + recent messages
     /// - Saves updated history
-    pub async fn summarize_history(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let historian_path = "personas/historian/historian.yaml";
-        let historian = match Persona::from_yaml_file(Path::new(historian_path)) {
+    pub async fn summarize_history(&mut self) -> Result<Option<SummaryOutcome>, Box<dyn std::error::Error>> {
+        let historian_path = persona_dir("historian").join("historian.yaml");
+        let historian = match Persona::from_yaml_file(&historian_path)
+            .or_else(|_| load_builtin("historian"))
+        {
             Ok(p) => Arc::new(p),
             Err(e) => {
                 return Err(format!("Failed to load historian persona: {}", e).into());
@@ -306,69 +721,1048 @@ impl<T: LlmClient> Connection<T> {
         let cutoff_index = if self.conversation.local_history.len() > limit + 1 {
             self.conversation.local_history.len() - limit
         } else {
-            return Ok(());
+            return Ok(None);
         };
         let messages_to_summarize = &self.conversation.local_history[1..cutoff_index];
 
-        let formatted = messages_to_summarize
+        let previous_recent = self.conversation.summary_text().map(str::to_string);
+        let previous_long_term = self.conversation.long_term_summary_text().map(str::to_string);
+        let round = self.conversation.summarization_count + 1;
+        let fold_every = GLOBAL_CONFIG.history.long_term_fold_every;
+        // Every Nth round the recent tier moves into the long-term one; otherwise it rolls forward
+        let fold = previous_recent.is_some() && fold_every > 0 && round.is_multiple_of(fold_every);
+
+        let mut elided = ElisionReport::default();
+        // The historian only ever sees the stored form, so a summary can't put ephemeral content on disk
+        let redactor = Redactor::for_persona(&self.conversation.persona);
+        let mut entries: Vec<String> = messages_to_summarize
             .iter()
-            .filter(|msg| !msg.content.contains("[Previous conversation summary:"))
-            .map(|msg| format!("{}: {}", msg.role.to_uppercase(), msg.content))
-            .collect::<Vec<_>>()
-            .join("\n\n");
-
-        let summary_prompt = format!(
-            "Summarize this conversation:\n\n{}\n\nProvide a concise summary following your instructions.",
-            formatted
-        );
+            .filter(|msg| !is_summary_message(msg))
+            .map(|msg| {
+                let stored = redactor.redact_text(&msg.content);
+                let content = match shaping::shape_message(&stored, GLOBAL_CONFIG.history.summary_message_max_chars) {
+                    Ok(content) => content.to_string(),
+                    Err(elision) => {
+                        elided.add(&elision);
+                        elision.placeholder()
+                    }
+                };
+                // Nudges steer the assistant; they aren't the user talking
+                let speaker = if is_nudge(msg) { "INSTRUCTION".to_string() } else { msg.role.to_uppercase() };
+                format!("{}: {}", speaker, content)
+            })
+            .collect();
+        let has_nudges = messages_to_summarize.iter().any(is_nudge);
+        let carried = previous_recent.as_ref().filter(|_| !fold);
+        if let Some(earlier) = carried {
+            entries.insert(0, format!("EARLIER SUMMARY: {}", redactor.redact_text(earlier)));
+        }
+        let formatted = entries.join("\n\n");
+
+        let persona = Arc::clone(&self.conversation.persona);
+        let required = required_terms(&persona.summary_must_include, &formatted);
 
-        log_info!("Sending {} messages to historian for summarization", messages_to_summarize.len());
-
-        let summary_request = ChatRequest {
-            model: "grok-4-fast".to_string(),
-            input: vec![
-                Message {
-                    role: "system".to_string(),
-                    content: historian.system_prompt.clone(),
-                },
-                Message {
-                    role: "user".to_string(),
-                    content: summary_prompt,
-                },
-            ],
-            temperature: historian.temperature.unwrap_or(0.3),
-            previous_response_id: None,
-            stream: false,
+        let chunks = shaping::chunk_transcript(&entries, GLOBAL_CONFIG.history.summary_transcript_max_chars);
+        let passes = chunks.len();
+        let mut summary_prompt = if passes == 1 {
+            format!(
+                "Summarize this conversation:\n\n{}\n\nProvide a concise summary following your instructions.",
+                formatted
+            )
+        } else {
+            log_info!("Transcript is {} chars; summarizing in {} parts", formatted.len(), passes);
+            let mut parts = Vec::with_capacity(passes);
+            for (index, chunk) in chunks.iter().enumerate() {
+                let part_input = [
+                    Message { role: "system".to_string(), content: historian.system_prompt.clone(), meta: None, source: None },
+                    Message {
+                        role: "user".to_string(),
+                        content: format!(
+                            "This is part {} of {} of one long conversation. Summarize only this part; \
+                             a final pass will merge the parts.\n\n{}",
+                            index + 1, passes, chunk
+                        ),
+                        meta: None,
+                        source: None,
+                    },
+                ];
+                parts.push(format!("Part {}: {}", index + 1, self.ask_historian(&historian, &part_input, persona.summary_max_tokens).await?));
+            }
+            format!(
+                "These are summaries of consecutive parts of one conversation, in order:\n\n{}\n\n\
+                 Merge them into one concise summary of the whole conversation following your instructions.",
+                parts.join("\n\n")
+            )
         };
+        if carried.is_some() {
+            summary_prompt.push_str(
+                "\nThe EARLIER SUMMARY covers the conversation before these messages; carry its still-relevant points forward."
+            );
+        }
+        if has_nudges {
+            summary_prompt.push_str(
+                "\nINSTRUCTION lines are steering instructions given to the assistant, not things the user said."
+            );
+        }
+        if let Some(style) = persona.summary_style {
+            summary_prompt.push_str(&format!("\n{}", style.instruction()));
+        }
+        if let Some(tokens) = persona.summary_max_tokens {
+            summary_prompt.push_str(&format!("\nKeep it under roughly {} tokens.", tokens));
+        }
+        if !required.is_empty() {
+            summary_prompt.push_str(&format!("\nThe summary must mention: {}.", required.join(", ")));
+        }
+        if let Some((lang, _)) = self.conversation.language() {
+            summary_prompt.push_str(&format!("\n{}", language::instruction(lang)));
+        }
 
-        let (tx, mut rx) = mpsc::unbounded_channel();
-        let response = self.client.send_streaming(&summary_request, tx).await?;
+        log_info!("Sending {} messages to historian for summarization ({} placeholders)",
+            cutoff_index - 1, elided.blocks);
 
-        while rx.recv().await.is_some() {}
+        let mut input = vec![
+            Message {
+                role: "system".to_string(),
+                content: historian.system_prompt.clone(),
+                meta: None,
+                source: None,
+            },
+            Message {
+                role: "user".to_string(),
+                content: summary_prompt,
+                meta: None,
+                source: None,
+            },
+        ];
 
-        let summary = response.full_text;
-        log_info!("Summary generated: {}", summary);
+        let mut summary = self.ask_historian(&historian, &input, persona.summary_max_tokens).await?;
+        let mut missing = missing_terms(&required, &summary);
 
-        HistoryManager::archive_full_history(&self.conversation)?;
+        let check = if required.is_empty() {
+            SummaryCheck::Unchecked
+        } else if missing.is_empty() {
+            SummaryCheck::Passed
+        } else {
+            log_info!("Summary is missing {:?}; asking the historian to revise once", missing);
+            input.push(Message { role: "assistant".to_string(), content: summary.clone(), meta: None, source: None });
+            input.push(Message {
+                role: "user".to_string(),
+                content: format!(
+                    "Your summary leaves out: {}. Revise it so each of these appears, keeping the same length and format. Reply with the revised summary only.",
+                    missing.join(", ")
+                ),
+                meta: None,
+                source: None,
+            });
+
+            summary = self.ask_historian(&historian, &input, persona.summary_max_tokens).await?;
+            missing = missing_terms(&required, &summary);
+            if missing.is_empty() { SummaryCheck::Revised } else { SummaryCheck::Failed(missing) }
+        };
+
+        log_info!("Summary generated ({}): {}", check.describe(), summary);
+        // Kept either way, like a summary still missing required terms
+        let format_issue = SummarySections::parse(&summary).err().map(|e| e.to_string());
+        if let Some(issue) = &format_issue {
+            log_warn!("Summary doesn't follow the historian's sections: {}", issue);
+        }
+
+        let long_term = match previous_recent.as_deref().filter(|_| fold) {
+            Some(outgoing) => Some(self.fold_long_term(&historian, previous_long_term.as_deref(), outgoing).await?),
+            None => previous_long_term,
+        };
+
+        // Never replace messages with a summary unless the full history made it to disk
+        if persistence::auto_write(|| HistoryManager::archive_full_history(&self.conversation))?.is_none() {
+            return Err(format!(
+                "not summarizing without an archive ({})", persistence::warning().unwrap_or_default()
+            ).into());
+        }
+        if persistence::auto_write(|| checkpoint::write_checkpoint(&self.conversation, CheckpointReason::Summarize))?.is_none() {
+            return Err(format!(
+                "not summarizing without a checkpoint ({})", persistence::warning().unwrap_or_default()
+            ).into());
+        }
 
         let system_prompt = self.conversation.local_history[0].clone();
-        let summary_message = Message {
+        let summary_message = |prefix: &str, text: &str| Message {
             role: "system".to_string(),
-            content: format!("[Previous conversation summary: {}]", summary),
+            content: format!("{}{}]", prefix, text),
+            meta: None,
+            source: None,
         };
 
-        let recent_messages = self.conversation.local_history[cutoff_index..].to_vec();
+        let recent_messages = self.conversation.local_history[cutoff_index..].iter()
+            .filter(|msg| !is_summary_message(msg))
+            .cloned();
 
-        let mut new_history = vec![system_prompt, summary_message];
+        // Oldest context first: prompt, long-term tier, recent tier, then the kept messages
+        let mut new_history = vec![system_prompt];
+        new_history.extend(long_term.as_deref().map(|text| summary_message(LONG_TERM_PREFIX, text)));
+        new_history.push(summary_message(SUMMARY_PREFIX, &summary));
         new_history.extend(recent_messages);
 
         log_info!("History rebuilt with summary. Messages: {} -> {}",
             self.conversation.local_history.len(), new_history.len());
 
+        self.publish(EventKind::Summarized {
+            messages_before: self.conversation.local_history.len(),
+            messages_after: new_history.len(),
+        });
         self.conversation.replace_history(new_history);
+        self.conversation.summarization_count = round;
 
-        Ok(())
+        Ok(Some(SummaryOutcome { check, elided, passes, folded: fold, format_issue }))
     }
 
+    /// # fold_long_term
+    ///
+    /// **Purpose:**
+    /// Folds the outgoing recent summary into the long-term summary.
+    ///
+    /// **Parameters:**
+    /// - `historian`: Persona that writes summaries
+    /// - `long_term`: The long-term summary so far, if any
+    /// - `outgoing`: The recent summary leaving the recent tier
+    ///
+    /// **Returns:**
+    /// `Result<String, Box<dyn std::error::Error>>` - The new long-term summary
+    ///
+    /// **Details:**
+    /// One extra historian request with a compression-focused prompt: durable
+    /// facts from both are kept and passing detail dropped. `summary_must_include`
+    /// is only enforced on the recent tier, so nothing is re-checked here.
+    async fn fold_long_term(
+        &mut self,
+        historian: &Persona,
+        long_term: Option<&str>,
+        outgoing: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let max_tokens = GLOBAL_CONFIG.history.long_term_summary_max_tokens;
+        let mut prompt = match long_term {
+            Some(long_term) => format!(
+                "LONG-TERM SUMMARY so far:\n{}\n\nOLDER SUMMARY to fold in (it covers what came after):\n{}\n\n\
+                 Combine them into one long-term summary.",
+                long_term, outgoing
+            ),
+            None => format!("OLDER SUMMARY to start the long-term record from:\n{}\n\nRewrite it as a long-term summary.", outgoing),
+        };
+        prompt.push_str(
+            "\nCompress hard: keep durable facts (people, decisions, preferences, commitments, dates, open questions) \
+             and drop detail that only mattered at the time. Keep every long-term fact unless the older summary supersedes it."
+        );
+        prompt.push_str(&format!("\nKeep it under roughly {} tokens.", max_tokens));
+        if let Some((lang, _)) = self.conversation.language() {
+            prompt.push_str(&format!("\n{}", language::instruction(lang)));
+        }
+
+        let input = [
+            Message { role: "system".to_string(), content: historian.system_prompt.clone(), meta: None, source: None },
+            Message { role: "user".to_string(), content: prompt, meta: None, source: None },
+        ];
+        log_info!("Folding the recent summary into the long-term summary for {}", self.conversation.persona.name);
+        // Headroom over the asked-for length so an overrun isn't cut mid-sentence
+        self.ask_historian(historian, &input, Some(max_tokens.saturating_mul(2))).await
+    }
+
+    /// # ask_historian
+    ///
+    /// **Purpose:**
+    /// Sends one summarization request and returns the full text of the reply.
+    ///
+    /// **Details:**
+    /// Always asks for a streamed reply, whatever `grok.stream_enabled` says:
+    /// `send_streaming` reads server-sent events and builds the text from every
+    /// delta, so a plain JSON body would come back as an error with no summary.
+    /// Its tokens are added to `usage` as a summary.
+    async fn ask_historian(
+        &mut self,
+        historian: &Persona,
+        input: &[Message],
+        max_output_tokens: Option<u32>,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let mut builder = ChatRequest::builder()
+            .model("grok-4-fast")
+            .messages(input.to_vec())
+            .temperature(historian.temperature.unwrap_or(0.3))
+            .stream(true);
+        if let Some(max_output_tokens) = max_output_tokens {
+            builder = builder.max_output_tokens(max_output_tokens);
+        }
+        let request = builder.build()?;
+
+        // Nobody watches a summary stream in; the receiver only keeps the sends from failing
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut stream = ChunkStream::open(&tx, TaskKind::Reply);
+        stream.begin();
+        let response = self.client.send_streaming(&request, &mut stream).await
+            .inspect_err(|e| { let _ = stream.fail(e.to_string()); })?;
+        let model = response.model.as_deref().unwrap_or(&request.model);
+        self.usage.record(UsageKind::Summary, model, response.input_tokens, response.output_tokens);
+        let _ = stream.complete(response.response_id.clone(), response.full_text.clone(), None);
+
+        Ok(response.full_text)
+    }
+
+}
+
+/// # CompletedReply
+///
+/// **Summary:**
+/// A finished reply returned by `Connection::send_and_collect`.
+///
+/// **Fields:**
+/// - `text`: Full reply text as the model sent it
+/// - `response_id`: API response ID (continues the thread on the next send)
+/// - `meta`: Model, temperature, latency and token usage for the reply
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletedReply {
+    pub text: String,
+    pub response_id: String,
+    pub meta: ResponseMeta,
+}
+
+/// # SummaryOutcome
+///
+/// **Summary:**
+/// What one summarization did.
+///
+/// **Fields:**
+/// - `check`: How the summary fared against `summary_must_include`
+/// - `elided`: Pasted content the historian only saw as placeholders
+/// - `passes`: Parts the transcript was summarized in before merging (1 = a single request)
+/// - `folded`: The previous recent summary was folded into the long-term summary
+/// - `format_issue`: How the summary breaks the historian's four-section schema, if it does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummaryOutcome {
+    pub check: SummaryCheck,
+    pub elided: ElisionReport,
+    pub passes: usize,
+    pub folded: bool,
+    pub format_issue: Option<String>,
+}
+
+impl SummaryOutcome {
+    pub fn describe(&self) -> String {
+        let mut description = match self.passes {
+            1 => self.check.describe(),
+            passes => format!("{}; summarized in {} parts", self.check.describe(), passes),
+        };
+        if self.folded {
+            description.push_str("; older summary folded into the long-term summary");
+        }
+        if let Some(issue) = &self.format_issue {
+            description.push_str(&format!("; not in the historian's format ({})", issue));
+        }
+        description
+    }
+}
+
+/// # SummaryCheck
+///
+/// **Summary:**
+/// Outcome of checking a summary against the persona's `summary_must_include`.
+///
+/// **Variants:**
+/// - `Unchecked`: None of the required terms came up in the summarized messages
+/// - `Passed`: Every required term was in the first summary
+/// - `Revised`: Terms were missing, and the one revision request fixed it
+/// - `Failed`: Terms were still missing after the revision (the summary is kept anyway)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SummaryCheck {
+    Unchecked,
+    Passed,
+    Revised,
+    Failed(Vec<String>),
+}
+
+impl SummaryCheck {
+    pub fn describe(&self) -> String {
+        match self {
+            SummaryCheck::Unchecked => "no required terms to check".to_string(),
+            SummaryCheck::Passed => "required terms: passed".to_string(),
+            SummaryCheck::Revised => "required terms: passed after one revision".to_string(),
+            SummaryCheck::Failed(missing) => format!("required terms: still missing {}", missing.join(", ")),
+        }
+    }
+}
+
+/// Required terms that actually come up in the text being summarized (case-insensitive)
+fn required_terms(must_include: &[String], source: &str) -> Vec<String> {
+    let source = source.to_lowercase();
+    must_include.iter()
+        .map(|term| term.trim())
+        .filter(|term| source.contains(&term.to_lowercase()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Required terms absent from the summary (case-insensitive)
+fn missing_terms(required: &[String], summary: &str) -> Vec<String> {
+    let summary = summary.to_lowercase();
+    required.iter()
+        .filter(|term| !summary.contains(&term.to_lowercase()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grok::client::GrokClient;
+    use crate::llm::credentials::{ApiProvider, EnvValue, Provenance, SharedCredential, ValueSource};
+    use crate::llm::scripted::{ScriptedClient, ScriptedReply};
+    use crate::persona::SummaryStyle;
+    use crate::test_support::{grok_reply_sse, test_personas_root, MockResponse, MockServer};
+
+    fn connection(server: &MockServer) -> Connection<GrokClient> {
+        let key = EnvValue {
+            value: "test-key".to_string(),
+            provenance: Provenance { source: ValueSource::DotEnv, both_set: false, differs: false },
+        };
+        let credential = SharedCredential::detached(ApiProvider::Grok, key);
+        let client = GrokClient::with_credential(credential, server.url("/v1/responses"));
+        let mut persona = Persona::from_yaml_str("name: Summarized\nsystem_prompt: Talk.\n").unwrap();
+        persona.enable_history = false;
+        Connection::new_without_output(client, Arc::new(persona))
+    }
+
+    fn historian_input() -> Vec<Message> {
+        vec![
+            Message { role: "system".into(), content: "You summarize.".into(), meta: None, source: None },
+            Message { role: "user".into(), content: "Summarize this.".into(), meta: None, source: None },
+        ]
+    }
+
+    #[tokio::test]
+    async fn a_summary_streamed_in_many_chunks_comes_back_whole() {
+        let deltas = ["- Met ", "Ana ", "on ", "Monday", ".\n", "- She prefers ", "email."];
+        let server = MockServer::start(move |_| MockResponse::sse(grok_reply_sse("resp_sum", &deltas))).await;
+        let mut connection = connection(&server);
+        let historian = load_builtin("historian").unwrap();
+
+        let summary = connection.ask_historian(&historian, &historian_input(), Some(512)).await.unwrap();
+
+        assert_eq!(summary, "- Met Ana on Monday.\n- She prefers email.");
+        let request: serde_json::Value = serde_json::from_str(&server.requests()[0].body).unwrap();
+        assert_eq!(request["stream"], serde_json::json!(true));
+        assert_eq!(request["max_output_tokens"], serde_json::json!(512));
+        assert_eq!(connection.usage.summaries.requests, 1);
+        assert_eq!(connection.usage.summaries.output_tokens, deltas.len() as u64);
+        assert_eq!(connection.usage.replies.requests, 0);
+    }
+
+    #[tokio::test]
+    async fn a_reply_records_the_model_the_api_reported() {
+        let server = MockServer::start(|_| MockResponse::sse(grok_reply_sse("resp_meta", &["Which ", "model?"]))).await;
+        let mut connection = connection(&server);
+
+        let reply = connection.send_and_collect("who wrote this?").await.unwrap();
+
+        assert_eq!(reply.text, "Which model?");
+        assert_eq!(reply.meta.model, "grok-mock", "what the API reported, not what was requested");
+        assert_eq!((reply.meta.input_tokens, reply.meta.output_tokens), (Some(3), Some(2)));
+        assert!(reply.meta.latency_ms.is_some());
+        let request: serde_json::Value = serde_json::from_str(&server.requests()[0].body).unwrap();
+        let sent = request["temperature"].as_f64().expect("a temperature was sent");
+        assert!((sent - reply.meta.temperature as f64).abs() < 1e-6, "{} sent, {} recorded", sent, reply.meta.temperature);
+
+        let last = connection.conversation.nth_last_assistant_message(1).expect("the reply");
+        assert_eq!(last.meta.as_ref(), Some(&reply.meta));
+    }
+
+    #[tokio::test]
+    async fn metadata_stays_out_of_the_next_request() {
+        let server = MockServer::start(|_| MockResponse::sse(grok_reply_sse("resp_meta", &["Noted."]))).await;
+        let mut connection = connection(&server);
+        connection.send_and_collect("first").await.unwrap();
+        connection.conversation.forget_response_id();
+        connection.send_and_collect("second").await.unwrap();
+
+        let request: serde_json::Value = serde_json::from_str(&server.requests()[1].body).unwrap();
+        let input = request["input"].as_array().expect("input messages");
+        assert!(input.iter().any(|msg| msg["role"] == "assistant"), "the full history was sent: {}", request);
+        assert!(input.iter().all(|msg| msg.get("meta").is_none()), "{}", request);
+    }
+
+    #[tokio::test]
+    async fn a_failed_summary_request_is_an_error_not_a_short_summary() {
+        let server = MockServer::start(|_| MockResponse::json(400, r#"{"error":"bad request"}"#)).await;
+        let mut connection = connection(&server);
+        let historian = load_builtin("historian").unwrap();
+
+        let error = connection.ask_historian(&historian, &historian_input(), None).await.unwrap_err();
+
+        assert!(error.to_string().contains("400"), "{}", error);
+        assert_eq!(connection.usage.summaries.requests, 0);
+    }
+
+    /// A connection on `client` whose persona must keep Ana, deadline and budget (which never comes up), with six messages to summarize
+    fn summarizing_connection(client: &ScriptedClient, name: &str) -> Connection<ScriptedClient> {
+        test_personas_root();
+        let yaml = format!(
+            "name: {}\nsystem_prompt: Talk.\nhistory_message_limit: 2\nsummary_max_tokens: 256\n\
+             summary_style: bullets\nsummary_must_include: [Ana, deadline, budget]\n",
+            name
+        );
+        let mut persona = Persona::from_yaml_str(&yaml).unwrap();
+        persona.enable_history = false;
+        let mut connection = Connection::new_without_output(client.clone(), Arc::new(persona));
+        for (role, content) in [
+            ("user", "Ana moved the DEADLINE to Friday."),
+            ("assistant", "Noted, Friday it is."),
+            ("user", "Can you remind me Thursday?"),
+            ("assistant", "I'll remind you Thursday."),
+            ("user", "Thanks."),
+            ("assistant", "Anytime."),
+        ] {
+            connection.conversation.local_history.push(Message { role: role.into(), content: content.into(), meta: None, source: None });
+        }
+        connection
+    }
+
+    fn last_user(request: &ChatRequest) -> &str {
+        request.input.iter().rev().find(|msg| msg.role == "user").map(|msg| msg.content.as_str()).unwrap_or_default()
+    }
+
+    fn kept_summary(connection: &Connection<ScriptedClient>) -> Option<String> {
+        connection.conversation.summary_text().map(str::to_string)
+    }
+
+    #[tokio::test]
+    async fn a_summary_with_every_required_term_passes_first_time() {
+        let client = ScriptedClient::default();
+        let mut connection = summarizing_connection(&client, "SummaryPasses");
+        client.push(ScriptedReply::text("- ana set the Deadline for Friday."));
+
+        let outcome = connection.summarize_history().await.unwrap().expect("there was something to summarize");
+
+        assert_eq!(outcome.check, SummaryCheck::Passed, "matched case-insensitively");
+        let requests = client.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].max_output_tokens, Some(256));
+        let prompt = last_user(&requests[0]);
+        assert!(prompt.contains(SummaryStyle::Bullets.instruction()), "{}", prompt);
+        assert!(prompt.contains("Keep it under roughly 256 tokens."), "{}", prompt);
+        assert!(prompt.contains("The summary must mention: Ana, deadline."), "budget never came up: {}", prompt);
+        assert_eq!(kept_summary(&connection).as_deref(), Some("- ana set the Deadline for Friday."));
+    }
+
+    #[tokio::test]
+    async fn a_summary_missing_terms_is_revised_once() {
+        let client = ScriptedClient::default();
+        let mut connection = summarizing_connection(&client, "SummaryRevised");
+        client.push(ScriptedReply::text("- A deadline moved to Friday."));
+        client.push(ScriptedReply::text("- Ana moved the deadline to Friday."));
+
+        let outcome = connection.summarize_history().await.unwrap().expect("there was something to summarize");
+
+        assert_eq!(outcome.check, SummaryCheck::Revised);
+        assert!(outcome.describe().starts_with("required terms: passed after one revision"), "{}", outcome.describe());
+        let requests = client.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].max_output_tokens, Some(256), "the revision has the same limit");
+        let first_reply = &requests[1].input[requests[1].input.len() - 2];
+        assert_eq!((first_reply.role.as_str(), first_reply.content.as_str()), ("assistant", "- A deadline moved to Friday."));
+        assert!(last_user(&requests[1]).starts_with("Your summary leaves out: Ana."), "{}", last_user(&requests[1]));
+        assert_eq!(kept_summary(&connection).as_deref(), Some("- Ana moved the deadline to Friday."));
+    }
+
+    #[tokio::test]
+    async fn a_summary_still_missing_terms_after_the_revision_is_kept_and_reported() {
+        let client = ScriptedClient::default();
+        let mut connection = summarizing_connection(&client, "SummaryGivesUp");
+        client.push(ScriptedReply::text("- Something moved."));
+        client.push(ScriptedReply::text("- Something moved to Friday."));
+        client.push(ScriptedReply::text("- Never asked for."));
+
+        let outcome = connection.summarize_history().await.unwrap().expect("there was something to summarize");
+
+        assert_eq!(outcome.check, SummaryCheck::Failed(vec!["Ana".to_string(), "deadline".to_string()]));
+        assert!(outcome.describe().starts_with("required terms: still missing Ana, deadline"), "{}", outcome.describe());
+        assert_eq!(client.requests().len(), 2, "only one revision is asked for");
+        assert_eq!(client.queued(), 1);
+        assert_eq!(kept_summary(&connection).as_deref(), Some("- Something moved to Friday."));
+    }
+
+    #[tokio::test]
+    async fn required_terms_that_never_came_up_are_not_checked() {
+        let client = ScriptedClient::default();
+        let mut connection = summarizing_connection(&client, "SummaryUnchecked");
+        for msg in connection.conversation.local_history.iter_mut().skip(1) {
+            msg.content = "Small talk.".to_string();
+        }
+        client.push(ScriptedReply::text("- Small talk."));
+
+        let outcome = connection.summarize_history().await.unwrap().expect("there was something to summarize");
+
+        assert_eq!(outcome.check, SummaryCheck::Unchecked);
+        assert!(!last_user(&client.requests()[0]).contains("must mention"));
+    }
+
+    fn embedded(client: &ScriptedClient) -> Connection<ScriptedClient> {
+        let mut persona = Persona::from_yaml_str("name: Embedded\nsystem_prompt: Talk.\n").unwrap();
+        persona.enable_history = false;
+        Connection::new_without_output(client.clone(), Arc::new(persona))
+    }
+
+    fn exchanges(connection: &Connection<ScriptedClient>) -> Vec<(&str, &str)> {
+        connection.local_history().iter()
+            .filter(|msg| msg.role != "system")
+            .map(|msg| (msg.role.as_str(), msg.content.as_str()))
+            .collect()
+    }
+
+    /// What `send_with_callback` handed the callback, one line per chunk
+    fn describe(chunk: &StreamChunk) -> String {
+        match chunk {
+            StreamChunk::Delta(text) => format!("delta {}", text),
+            StreamChunk::Complete { response_id, full_reply, .. } => format!("complete {} {}", response_id, full_reply),
+            StreamChunk::Error(msg) => format!("error {}", msg),
+            other => format!("{:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn send_and_collect_returns_the_whole_reply() {
+        let client = ScriptedClient::default();
+        let mut connection = embedded(&client);
+        client.push(ScriptedReply::chunks(["Rust ", "2024 ", "landed."]));
+
+        let reply = connection.send_and_collect("What changed?").await.unwrap();
+
+        assert_eq!(reply.text, "Rust 2024 landed.");
+        assert_eq!(reply.response_id, "scripted-1");
+        assert_eq!(reply.meta.model, client.requests()[0].model);
+        assert!(reply.meta.latency_ms.is_some());
+        assert_eq!(exchanges(&connection), [("user", "What changed?"), ("assistant", "Rust 2024 landed.")]);
+        assert_eq!(connection.conversation.get_last_response_id().map(String::as_str), Some("scripted-1"));
+    }
+
+    #[tokio::test]
+    async fn the_callback_sees_every_chunk_in_order() {
+        let client = ScriptedClient::default();
+        let mut connection = embedded(&client);
+        client.push(ScriptedReply::chunks(["Rust ", "2024 ", "landed."]));
+
+        let mut seen = Vec::new();
+        connection.send_with_callback("What changed?", |chunk| seen.push(describe(chunk))).await.unwrap();
+
+        assert_eq!(seen, [
+            "delta Rust ",
+            "delta 2024 ",
+            "delta landed.",
+            "complete scripted-1 Rust 2024 landed.",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn a_mid_stream_error_fails_the_send_and_takes_the_message_back() {
+        let client = ScriptedClient::default();
+        let mut connection = embedded(&client);
+        client.push(ScriptedReply::chunks(["Half a "]).failing("connection reset"));
+
+        let mut seen = Vec::new();
+        let error = connection.send_with_callback("What changed?", |chunk| seen.push(describe(chunk))).await.unwrap_err();
+
+        assert!(matches!(&error, ShadowError::ApiError(msg) if msg.contains("connection reset")), "{:?}", error);
+        assert_eq!(seen.first().map(String::as_str), Some("delta Half a "));
+        assert!(seen.last().is_some_and(|line| line.starts_with("error ") && line.contains("connection reset")), "{:?}", seen);
+        assert!(exchanges(&connection).is_empty(), "{:?}", exchanges(&connection));
+
+        client.push(ScriptedReply::text("Whole this time."));
+        connection.send_and_collect("What changed?").await.unwrap();
+        assert_eq!(exchanges(&connection), [("user", "What changed?"), ("assistant", "Whole this time.")]);
+    }
+
+    #[tokio::test]
+    async fn dropping_the_future_cancels_and_the_next_send_cleans_up() {
+        let client = ScriptedClient::default();
+        let mut connection = embedded(&client);
+        let (reply, gate) = ScriptedReply::chunks(["Half a ", "reply."]).held_after(1);
+        client.push(reply);
+
+        let first_chunk = Arc::new(tokio::sync::Notify::new());
+        let seen = first_chunk.clone();
+        tokio::select! {
+            _ = connection.send_with_callback("Long question", move |_| seen.notify_one()) => panic!("the reply was held"),
+            _ = first_chunk.notified() => {}
+        }
+        gate.release();
+        assert_eq!(exchanges(&connection), [("user", "Long question")], "a drop can't touch history");
+
+        client.push(ScriptedReply::text("Short answer."));
+        let reply = connection.send_and_collect("Short question").await.unwrap();
+
+        assert_eq!(reply.text, "Short answer.");
+        assert_eq!(exchanges(&connection), [("user", "Short question"), ("assistant", "Short answer.")]);
+        assert_eq!(connection.conversation.total_message_count, 2);
+        let resent = &client.requests()[1];
+        assert!(resent.input.iter().all(|msg| msg.content != "Long question"), "{:?}", resent.input);
+    }
+
+    #[tokio::test]
+    async fn the_summary_request_asks_for_the_conversation_language() {
+        let cases = [
+            ("SummaryNoLanguage", None, None),
+            ("SummaryOverridden", Some(language::Lang::Spa), Some("Respond in the same language as the conversation: Spanish.")),
+        ];
+
+        for (name, lang, expected) in cases {
+            let client = ScriptedClient::default();
+            let mut connection = summarizing_connection(&client, name);
+            connection.conversation.set_language_override(lang);
+            client.push(ScriptedReply::text("- Ana: deadline el viernes, budget sin cambios."));
+
+            connection.summarize_history().await.unwrap().expect("there was something to summarize");
+
+            let request = &client.requests()[0];
+            let asked = request.input.iter().any(|msg| msg.content.contains("Respond in the same language"));
+            assert_eq!(asked, expected.is_some(), "{}", name);
+            if let Some(expected) = expected {
+                assert!(request.input.iter().any(|msg| msg.content.contains(expected)), "{}: {:?}", name, request.input);
+            }
+        }
+    }
+
+    /// A connection on `client` keeping the last 2 of `messages` when it summarizes
+    fn connection_with(client: &ScriptedClient, name: &str, messages: &[(&str, String)]) -> Connection<ScriptedClient> {
+        test_personas_root();
+        let yaml = format!("name: {}\nsystem_prompt: Talk.\nhistory_message_limit: 2\n", name);
+        let mut persona = Persona::from_yaml_str(&yaml).unwrap();
+        persona.enable_history = false;
+        let mut connection = Connection::new_without_output(client.clone(), Arc::new(persona));
+        for (role, content) in messages {
+            connection.conversation.local_history.push(Message { role: role.to_string(), content: content.clone(), meta: None, source: None });
+        }
+        connection
+    }
+
+    #[tokio::test]
+    async fn a_pasted_blob_reaches_the_historian_as_a_placeholder_only() {
+        let client = ScriptedClient::default();
+        let blob = ["TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsuIFRoZSBxdWljayBicm93biBmb3gganVtcHMgb3ZlcjEyMw=="; 20].join("\n");
+        let mut connection = connection_with(&client, "SummaryElides", &[
+            ("user", blob.clone()),
+            ("assistant", "That's a base64-encoded key; don't paste it anywhere public.".to_string()),
+            ("user", "Got it.".to_string()),
+            ("assistant", "Anything else?".to_string()),
+        ]);
+        client.push(ScriptedReply::text("- Pasted a key; was warned not to share it."));
+
+        let outcome = connection.summarize_history().await.unwrap().expect("there was something to summarize");
+
+        let sent = last_user(&client.requests()[0]).to_string();
+        assert!(sent.contains("USER: [large pasted content: 20 lines, looks like base64 data]"), "{}", sent);
+        assert!(!sent.contains("TWFueSBoYW5kcyBt"), "{}", sent);
+        assert!(sent.contains("don't paste it anywhere public"), "short messages go as-is: {}", sent);
+        assert_eq!(outcome.passes, 1);
+        assert_eq!((outcome.elided.blocks, outcome.elided.lines), (1, 20));
+        assert!(outcome.elided.describe().is_some_and(|note| note.contains("base64 data")));
+    }
+
+    #[tokio::test]
+    async fn an_overlong_transcript_is_summarized_in_parts_then_merged() {
+        let client = ScriptedClient::default();
+        let paragraph = |topic: &str| format!("Notes about {}. {}", topic, "We talked it through at length and agreed on next steps. ".repeat(90));
+        let mut messages: Vec<(&str, String)> = ["roadmap", "hiring", "budget", "offsite", "tooling", "support", "security", "docs", "pricing", "launch"]
+            .iter()
+            .enumerate()
+            .map(|(i, topic)| (if i % 2 == 0 { "user" } else { "assistant" }, paragraph(topic)))
+            .collect();
+        messages.extend([("user", "Thanks.".to_string()), ("assistant", "Anytime.".to_string())]);
+        let mut connection = connection_with(&client, "SummaryInParts", &messages);
+        for reply in ["- Roadmap through offsite.", "- Tooling through launch.", "- Ten planning topics, all agreed."] {
+            client.push(ScriptedReply::text(reply));
+        }
+
+        let outcome = connection.summarize_history().await.unwrap().expect("there was something to summarize");
+
+        let requests = client.requests();
+        assert_eq!(outcome.passes, 2);
+        assert_eq!(requests.len(), 3, "one request per part plus the merge");
+        assert!(last_user(&requests[0]).starts_with("This is part 1 of 2 of one long conversation."));
+        assert!(last_user(&requests[0]).contains("Notes about roadmap.") && !last_user(&requests[0]).contains("Notes about launch."));
+        assert!(last_user(&requests[1]).starts_with("This is part 2 of 2 of one long conversation."));
+        assert!(last_user(&requests[1]).contains("Notes about launch."));
+        let merge = last_user(&requests[2]);
+        assert!(merge.contains("Part 1: - Roadmap through offsite.\n\nPart 2: - Tooling through launch."), "{}", merge);
+        assert_eq!(kept_summary(&connection).as_deref(), Some("- Ten planning topics, all agreed."));
+        assert!(outcome.elided.describe().is_none(), "prose under the per-message limit is never elided");
+    }
+
+    #[tokio::test]
+    async fn a_stripped_signature_is_kept_out_of_history_but_recorded() {
+        let client = ScriptedClient::default();
+        let mut persona = Persona::from_yaml_str("name: Signing\nsystem_prompt: Talk.\nstrip_patterns: ['^-Shadow$']\n").unwrap();
+        persona.enable_history = false;
+        let mut connection = Connection::new_without_output(client.clone(), Arc::new(persona));
+        client.push(ScriptedReply::chunks(["Tuesday works.\n", "-Shadow"]));
+        client.push(ScriptedReply::text("Signed:\n-Shadow\nfrom the road"));
+
+        connection.send_and_collect("when?").await.unwrap();
+        connection.send_and_collect("and who?").await.unwrap();
+
+        assert_eq!(exchanges(&connection), [
+            ("user", "when?"),
+            ("assistant", "Tuesday works."),
+            ("user", "and who?"),
+            ("assistant", "Signed:\n-Shadow\nfrom the road"),
+        ], "a signature mid-reply is kept");
+        let stripped: Vec<Vec<String>> = connection.local_history().iter()
+            .filter_map(|msg| msg.meta.as_ref().map(|meta| meta.stripped.clone()))
+            .collect();
+        assert_eq!(stripped, [vec!["-Shadow".to_string()], vec![]]);
+        assert_eq!(connection.strip_matches, 1);
+    }
+
+    /// Adds one exchange stating `fact`, plus a short one the summary keeps verbatim
+    fn talk_about(connection: &mut Connection<ScriptedClient>, fact: &str) {
+        for (role, content) in [("user", fact), ("assistant", "Noted."), ("user", "ok"), ("assistant", "ok")] {
+            connection.conversation.local_history.push(Message { role: role.into(), content: content.into(), meta: None, source: None });
+        }
+    }
+
+    /// The messages after the prompt, summaries shown by tier
+    fn tiers(connection: &Connection<ScriptedClient>) -> Vec<String> {
+        connection.local_history()[1..].iter()
+            .map(|msg| match (msg.content.strip_prefix(LONG_TERM_PREFIX), msg.content.strip_prefix(SUMMARY_PREFIX)) {
+                (Some(text), _) => format!("long-term: {}", text.trim_end_matches(']')),
+                (_, Some(text)) => format!("recent: {}", text.trim_end_matches(']')),
+                _ => format!("{}: {}", msg.role, msg.content),
+            })
+            .collect()
+    }
+
+    /// One summarization round over a new `fact`, answered by `replies`; returns the outcome and the index of its first request
+    async fn round(connection: &mut Connection<ScriptedClient>, client: &ScriptedClient, fact: &str, replies: &[&str]) -> (SummaryOutcome, usize) {
+        talk_about(connection, fact);
+        for reply in replies {
+            client.push(ScriptedReply::text(*reply));
+        }
+        let first = client.requests().len();
+        let outcome = connection.summarize_history().await.unwrap().expect("there was something to summarize");
+        assert_eq!(client.requests().len(), first + replies.len(), "{}: one request per reply", fact);
+        (outcome, first)
+    }
+
+    #[tokio::test]
+    async fn early_facts_move_into_the_long_term_tier_while_the_recent_one_stays_small() {
+        assert_eq!(GLOBAL_CONFIG.history.long_term_fold_every, 3, "the rounds below assume the default");
+        let client = ScriptedClient::default();
+        let mut connection = connection_with(&client, "SummaryTiers", &[]);
+        // Round 1: the first summary
+        let (outcome, _) = round(&mut connection, &client, "Ana's birthday is 3 May.", &["- Ana's birthday: 3 May."]).await;
+        assert!(!outcome.folded);
+        let transcript = last_user(&client.requests()[0]).to_string();
+        assert!(transcript.contains("USER: Ana's birthday is 3 May.") && !transcript.contains("EARLIER SUMMARY"), "{}", transcript);
+        assert_eq!(tiers(&connection), ["recent: - Ana's birthday: 3 May.", "user: ok", "assistant: ok"]);
+
+        // Round 2: the recent summary rolls forward through the historian
+        let (outcome, first) = round(&mut connection, &client, "I moved to Oslo.", &["- Ana's birthday: 3 May.\n- Lives in Oslo."]).await;
+        assert!(!outcome.folded);
+        let transcript = last_user(&client.requests()[first]).to_string();
+        assert!(transcript.starts_with("Summarize this conversation:\n\nEARLIER SUMMARY: - Ana's birthday: 3 May."), "{}", transcript);
+        assert!(transcript.contains("carry its still-relevant points forward"), "{}", transcript);
+
+        // Round 3: the outgoing summary is folded, and the new recent one covers only the new messages
+        let structured = "Topics: the new job\nDecisions: starts Monday\nContext: remote\nStatus: signed";
+        let (outcome, first) = round(&mut connection, &client, "I start the new job Monday.", &[structured, "- Ana: birthday 3 May.\n- Home: Oslo."]).await;
+        assert!(outcome.folded);
+        assert_eq!(outcome.format_issue, None, "the section check applies to the recent tier only");
+        let requests = client.requests();
+        let transcript = last_user(&requests[first]);
+        assert!(!transcript.contains("EARLIER SUMMARY") && !transcript.contains("Ana"), "{}", transcript);
+        let fold = last_user(&requests[first + 1]);
+        assert!(fold.starts_with("OLDER SUMMARY to start the long-term record from:\n- Ana's birthday: 3 May.\n- Lives in Oslo.\n\n"), "{}", fold);
+        assert!(fold.contains("Compress hard"), "{}", fold);
+        assert_eq!(requests[first + 1].max_output_tokens, Some(GLOBAL_CONFIG.history.long_term_summary_max_tokens * 2));
+        assert_eq!(tiers(&connection), [
+            "long-term: - Ana: birthday 3 May.\n- Home: Oslo.".to_string(),
+            format!("recent: {}", structured),
+            "user: ok".to_string(),
+            "assistant: ok".to_string(),
+        ], "oldest context first");
+
+        // Rounds 4 and 5 roll the recent tier; the long-term one is carried untouched and never re-sent
+        for (fact, reply) in [("The team is six people.", "- Team of six."), ("Standup is at 9.", "- Team of six; standup at 9.")] {
+            let (outcome, first) = round(&mut connection, &client, fact, &[reply]).await;
+            assert!(!outcome.folded, "{}", fact);
+            assert!(!last_user(&client.requests()[first]).contains("Ana"), "{}", fact);
+            assert_eq!(connection.conversation.long_term_summary_text(), Some("- Ana: birthday 3 May.\n- Home: Oslo."), "{}", fact);
+        }
+
+        // Round 6: the long-term tier grows from the earlier one plus the outgoing recent summary
+        let (outcome, first) = round(&mut connection, &client, "Lunch is at noon.", &["- Lunch at noon.", "- Ana: birthday 3 May.\n- Home: Oslo.\n- Team of six; standup at 9."]).await;
+        assert!(outcome.folded);
+        let fold = last_user(&client.requests()[first + 1]).to_string();
+        assert!(fold.starts_with(
+            "LONG-TERM SUMMARY so far:\n- Ana: birthday 3 May.\n- Home: Oslo.\n\n\
+             OLDER SUMMARY to fold in (it covers what came after):\n- Team of six; standup at 9.\n\n"
+        ), "{}", fold);
+        assert_eq!(connection.conversation.summary_text(), Some("- Lunch at noon."));
+        assert!(connection.conversation.long_term_summary_text().is_some_and(|text| text.contains("Ana")));
+        assert_eq!(connection.conversation.summarization_count, 6);
+    }
+
+    #[tokio::test]
+    async fn required_terms_are_checked_on_the_recent_tier_only() {
+        let client = ScriptedClient::default();
+        let mut persona = Persona::from_yaml_str("name: TierCheck\nsystem_prompt: Talk.\nhistory_message_limit: 2\nsummary_must_include: [Ana]\n").unwrap();
+        persona.enable_history = false;
+        test_personas_root();
+        let mut connection = Connection::new_without_output(client.clone(), Arc::new(persona));
+        connection.conversation.summarization_count = GLOBAL_CONFIG.history.long_term_fold_every - 1;
+        connection.conversation.local_history.push(Message { role: "system".into(), content: format!("{}- Older things.]", SUMMARY_PREFIX), meta: None, source: None });
+        talk_about(&mut connection, "Ana called about the deadline.");
+        client.push(ScriptedReply::text("- Ana called about the deadline."));
+        client.push(ScriptedReply::text("- Older things, compressed."));
+
+        let outcome = connection.summarize_history().await.unwrap().expect("there was something to summarize");
+
+        assert!(outcome.folded);
+        assert_eq!(outcome.check, SummaryCheck::Passed);
+        assert_eq!(client.requests().len(), 2, "the long-term fold leaves Ana out without a revision request");
+        assert_eq!(connection.conversation.long_term_summary_text(), Some("- Older things, compressed."));
+    }
+
+    #[test]
+    fn both_tiers_survive_a_save_and_load_in_order() {
+        test_personas_root();
+        let client = ScriptedClient::default();
+        let mut connection = connection_with(&client, "TiersSaved", &[("user", "hi".to_string()), ("assistant", "Hello.".to_string())]);
+        connection.conversation.local_history.splice(1..1, [
+            Message { role: "system".into(), content: format!("{}- Ana: birthday 3 May.]", LONG_TERM_PREFIX), meta: None, source: None },
+            Message { role: "system".into(), content: format!("{}- Said hello.]", SUMMARY_PREFIX), meta: None, source: None },
+        ]);
+        connection.conversation.summarization_count = 3;
+        connection.save_persona_history().unwrap();
+
+        let loaded = HistoryManager::load_persona_history("TiersSaved").unwrap();
+        assert_eq!(loaded.long_term_summary.as_deref(), Some("- Ana: birthday 3 May."));
+        assert_eq!(loaded.summary.as_deref(), Some("- Said hello."));
+        assert_eq!(loaded.summarization_count, 3);
+        assert!(loaded.recent_messages.iter().all(|msg| !is_summary_message(msg)), "tiers live in their own fields");
+
+        let persona = connection.conversation.persona.clone();
+        let rebuilt: Vec<String> = HistoryManager::build_history_from_loaded(&persona, loaded).into_iter().map(|msg| msg.content).collect();
+        assert_eq!(rebuilt, [
+            "Talk.".to_string(),
+            format!("{}- Ana: birthday 3 May.]", LONG_TERM_PREFIX),
+            format!("{}- Said hello.]", SUMMARY_PREFIX),
+            "hi".to_string(),
+            "Hello.".to_string(),
+        ]);
+    }
+
+    /// The CLI's blocking path publishes the same events as the TUI's streaming one
+    #[tokio::test]
+    async fn the_blocking_path_publishes_sent_completed_and_errors() {
+        use crate::utilities::events::{EventKind, EVENT_BUS};
+        let client = ScriptedClient::default();
+        let mut connection = embedded(&client);
+        let id = Uuid::new_v4();
+        connection.agent_id = Some(id);
+        let events = EVENT_BUS.subscribe();
+        let mine = || -> Vec<EventKind> {
+            events.try_iter()
+                .filter(|event| event.agent_id == Some(id.to_string()))
+                .inspect(|event| assert_eq!(event.persona.as_deref(), Some("Embedded")))
+                .map(|event| event.kind)
+                .collect()
+        };
+
+        client.push(ScriptedReply::text("Noon."));
+        connection.add_user_message("what time is it?");
+        connection.handle_response().await.unwrap();
+        match mine().as_slice() {
+            [EventKind::MessageSent { history_messages: 2 }, EventKind::MessageCompleted { model, latency_ms: Some(_), reply_chars: 5, .. }] => {
+                assert_eq!(model, &client.requests()[0].model);
+            }
+            other => panic!("{:?}", other),
+        }
+
+        // Threaded on the first reply's ID, so only the new question goes out
+        client.push(ScriptedReply::error("503 Service Unavailable"));
+        connection.add_user_message("again?");
+        assert!(connection.handle_response().await.is_err());
+        assert_eq!(mine(), [
+            EventKind::MessageSent { history_messages: 1 },
+            EventKind::Error { source: "request".into(), message: "503 Service Unavailable".into() },
+        ]);
+    }
+
+    /// A persona that keeps dollar amounts (span) and anything about salary (whole message) off disk
+    fn private_connection(client: &ScriptedClient, name: &str, enable_history: bool) -> Connection<ScriptedClient> {
+        test_personas_root();
+        let yaml = format!(
+            "name: {}\nsystem_prompt: Talk.\nhistory_message_limit: 2\nephemeral_patterns:\n  - '\\$\\d[\\d,]*'\n  - {{ pattern: '(?i)salary', scope: message }}\n",
+            name
+        );
+        let mut persona = Persona::from_yaml_str(&yaml).unwrap();
+        persona.enable_history = enable_history;
+        Connection::new_without_output(client.clone(), Arc::new(persona))
+    }
+
+    #[tokio::test]
+    async fn ephemeral_content_is_saved_redacted_but_kept_in_memory() {
+        let client = ScriptedClient::default();
+        let mut connection = private_connection(&client, "EphemeralSaved", true);
+        client.push(ScriptedReply::text("Congrats, $120,000 is a good offer."));
+
+        let mut notices = Vec::new();
+        connection.send_with_callback("My salary offer is $120,000.", |chunk| {
+            if let StreamChunk::Notice(text) = chunk {
+                notices.push(text.clone());
+            }
+        }).await.unwrap();
+
+        assert_eq!(notices, [format!("{} reply will be stored redacted", redaction::redacted_badge())]);
+        assert_eq!(exchanges(&connection), [
+            ("user", "My salary offer is $120,000."),
+            ("assistant", "Congrats, $120,000 is a good offer."),
+        ], "the conversation in memory is untouched");
+        assert_eq!(last_user(&client.requests()[0]), "My salary offer is $120,000.", "the model is sent the original");
+
+        connection.save_persona_history().unwrap();
+        let saved: Vec<String> = HistoryManager::load_persona_history("EphemeralSaved").unwrap()
+            .recent_messages.into_iter().map(|msg| msg.content).collect();
+        let file = std::fs::read_to_string(crate::agent_history::history::history_path("EphemeralSaved")).unwrap();
+        assert!(!file.contains("120,000"), "{}", file);
+        assert_eq!(saved, [redaction::REDACTED_MESSAGE, "Congrats, [redacted] is a good offer."]);
+    }
+
+    #[tokio::test]
+    async fn the_historian_only_sees_the_stored_form() {
+        let client = ScriptedClient::default();
+        let mut connection = private_connection(&client, "EphemeralSummarized", false);
+        connection.conversation.local_history.push(Message {
+            role: "system".into(), content: format!("{}- Budget was $4,000.]", SUMMARY_PREFIX), meta: None, source: None,
+        });
+        for (role, content) in [
+            ("user", "Ask for $130,000?"),
+            ("assistant", "Yes, $130,000 is fair."),
+            ("user", "My salary talk is Friday."),
+            ("assistant", "Good luck Friday."),
+            ("user", "Thanks, $5 coffee on me."),
+            ("assistant", "Deal."),
+        ] {
+            connection.conversation.local_history.push(Message { role: role.into(), content: content.into(), meta: None, source: None });
+        }
+        client.push(ScriptedReply::text("- Planning a pay negotiation on Friday."));
+
+        connection.summarize_history().await.unwrap().expect("there was something to summarize");
+
+        let sent = last_user(&client.requests()[0]).to_string();
+        for expected in [
+            "EARLIER SUMMARY: - Budget was [redacted].",
+            "USER: Ask for [redacted]?",
+            "ASSISTANT: Yes, [redacted] is fair.",
+            &format!("USER: {}", redaction::REDACTED_MESSAGE),
+        ] {
+            assert!(sent.contains(expected), "missing {:?} in {}", expected, sent);
+        }
+        assert!(!sent.contains("130,000") && !sent.contains("$4,000") && !sent.contains("salary"), "{}", sent);
+        assert_eq!(exchanges(&connection), [("user", "Thanks, $5 coffee on me."), ("assistant", "Deal.")], "kept messages stay as they were");
+    }
 }
-            
\ No newline at end of file
@@ -0,0 +1,776 @@
+//! # Daegonica Module: llm::credentials
+//!
+//! **Purpose:** Shared, swappable API credentials for LLM clients
+//!
+//! **Context:**
+//! - Every GrokClient/ClaudeClient holds a handle to the same per-provider credential
+//! - Lets `reload keys` rotate API keys for all open connections without a restart
+//!
+//! **Responsibilities:**
+//! - Hold the current API key and one pooled reqwest client per provider
+//! - Resolve every credential (LLM keys and Twitter tokens) between .env and the
+//!   shell environment, remembering where each value came from
+//! - Re-read keys on demand, swapping them in before any network check
+//! - Perform a cheap authentication check against each provider
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use crate::prelude::*;
+
+/// # ApiProvider
+///
+/// **Summary:**
+/// LLM providers that need an API key.
+///
+/// **Variants:**
+/// - `Grok`: x.ai Grok (GROK_KEY)
+/// - `Claude`: Anthropic Claude (CLAUDE_KEY)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApiProvider {
+    Grok,
+    Claude,
+}
+
+impl ApiProvider {
+    pub const ALL: [ApiProvider; 2] = [ApiProvider::Grok, ApiProvider::Claude];
+
+    /// Environment variable holding this provider's key
+    pub fn env_var(&self) -> &'static str {
+        match self {
+            ApiProvider::Grok => "GROK_KEY",
+            ApiProvider::Claude => "CLAUDE_KEY",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ApiProvider::Grok => "Grok",
+            ApiProvider::Claude => "Claude",
+        }
+    }
+
+    /// The provider an `api_provider` value names, if it names one
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "grok" => Some(ApiProvider::Grok),
+            "claude" => Some(ApiProvider::Claude),
+            _ => None,
+        }
+    }
+
+    /// The provider a persona's agents talk to (`api_provider`, Grok unless "claude")
+    pub fn for_persona(persona: &Persona) -> Self {
+        Self::from_name(&persona.api_provider).unwrap_or(ApiProvider::Grok)
+    }
+}
+
+/// The .env file credentials are read from (never loaded into the process environment)
+const DOTENV_PATH: &str = ".env";
+
+/// # ValueSource
+///
+/// **Summary:**
+/// Where a credential's value was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueSource {
+    Shell,
+    DotEnv,
+}
+
+impl ValueSource {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            ValueSource::Shell => "your shell environment",
+            ValueSource::DotEnv => ".env",
+        }
+    }
+
+    /// Short form, e.g. "the shell value differs"
+    pub fn label(&self) -> &'static str {
+        match self {
+            ValueSource::Shell => "shell",
+            ValueSource::DotEnv => ".env",
+        }
+    }
+
+    pub fn other(&self) -> ValueSource {
+        match self {
+            ValueSource::Shell => ValueSource::DotEnv,
+            ValueSource::DotEnv => ValueSource::Shell,
+        }
+    }
+}
+
+/// # Provenance
+///
+/// **Summary:**
+/// Where a credential came from and what the other place holds.
+///
+/// **Fields:**
+/// - `source`: The place the value in use was read from
+/// - `both_set`: The variable is set in the shell environment and in .env
+/// - `differs`: Both are set and hold different values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Provenance {
+    pub source: ValueSource,
+    pub both_set: bool,
+    pub differs: bool,
+}
+
+impl Provenance {
+    /// e.g. "from .env (your shell environment has a different value)"
+    pub fn describe(&self) -> String {
+        match (self.both_set, self.differs) {
+            (true, true) => format!("from {} ({} has a different value)", self.source.describe(), self.source.other().describe()),
+            (true, false) => format!("from {} (same value in {})", self.source.describe(), self.source.other().describe()),
+            _ => format!("from {}", self.source.describe()),
+        }
+    }
+
+    /// # conflict_note
+    ///
+    /// **Purpose:**
+    /// Warning for a variable whose two sources disagree, None when they don't.
+    pub fn conflict_note(&self, name: &str) -> Option<String> {
+        self.differs.then(|| format!(
+            "{} came from {}, not {} - the {} value differs",
+            name, self.source.describe(), self.source.other().describe(), self.source.other().label()
+        ))
+    }
+}
+
+/// # EnvValue
+///
+/// **Summary:**
+/// A resolved credential and its provenance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvValue {
+    pub value: String,
+    pub provenance: Provenance,
+}
+
+/// # load_env_value
+///
+/// **Purpose:**
+/// Reads one credential from the shell environment and .env, recording where it came from.
+///
+/// **Returns:**
+/// `Option<EnvValue>` - None if the variable is missing or blank in both
+///
+/// **Details:**
+/// Shared by the LLM clients and TwitterConnection so the same rules apply
+/// everywhere. See `resolve_env_value` for precedence. Since .env is never loaded
+/// into the process environment, the shell value is only ever a real export and
+/// `reload keys` always sees the file's current contents.
+pub fn load_env_value(name: &str) -> Option<EnvValue> {
+    resolve_env_value(
+        env::var(name).ok(),
+        read_dotenv_value(Path::new(DOTENV_PATH), name),
+        GLOBAL_CONFIG.credentials.prefer_dotenv,
+    )
+}
+
+/// # resolve_env_value
+///
+/// **Purpose:**
+/// Picks between a shell value and a .env value for the same variable.
+///
+/// **Parameters:**
+/// - `shell`: Value in the process environment, if set
+/// - `dotenv`: Value in .env, if set
+/// - `prefer_dotenv`: `credentials.prefer_dotenv`; decides only when both are set
+///
+/// **Returns:**
+/// `Option<EnvValue>` - The chosen value, None if neither is set (blank counts as unset)
+pub fn resolve_env_value(shell: Option<String>, dotenv: Option<String>, prefer_dotenv: bool) -> Option<EnvValue> {
+    let set = |value: Option<String>| value.filter(|v| !v.trim().is_empty());
+    let (shell, dotenv) = (set(shell), set(dotenv));
+    let both_set = shell.is_some() && dotenv.is_some();
+    let differs = both_set && shell != dotenv;
+
+    let (value, source) = match (shell, dotenv) {
+        (Some(_), Some(file)) if prefer_dotenv => (file, ValueSource::DotEnv),
+        (Some(shell), _) => (shell, ValueSource::Shell),
+        (None, Some(file)) => (file, ValueSource::DotEnv),
+        (None, None) => return None,
+    };
+    Some(EnvValue { value, provenance: Provenance { source, both_set, differs } })
+}
+
+/// # credential_conflicts
+///
+/// **Purpose:**
+/// Startup warnings for every known credential whose shell and .env values differ.
+pub fn credential_conflicts() -> Vec<String> {
+    ApiProvider::ALL.iter().map(|provider| provider.env_var())
+        .chain(crate::twitter::client::CREDENTIAL_VARS)
+        .filter_map(|name| load_env_value(name)?.provenance.conflict_note(name))
+        .collect()
+}
+
+#[derive(Debug)]
+struct Credential {
+    api_key: String,
+    provenance: Provenance,
+    client: Client,
+}
+
+/// # build_http_client
+///
+/// **Purpose:**
+/// Builds the HTTP client a provider's connections share, tuned for long streamed replies.
+///
+/// **Details:**
+/// - No overall timeout; a stream that goes quiet for `http.read_idle_timeout_secs` fails instead
+/// - Idle connections stay pooled so consecutive messages skip the TCP/TLS handshake
+/// - HTTP/2 is used whenever the endpoint offers it during TLS negotiation
+///
+/// **Errors / Failures:**
+/// - The TLS backend can't be initialized (falls back to reqwest's defaults, logged)
+pub fn build_http_client() -> Client {
+    let http = &GLOBAL_CONFIG.http;
+    Client::builder()
+        .connect_timeout(Duration::from_secs(http.connect_timeout_secs))
+        .read_timeout(Duration::from_secs(http.read_idle_timeout_secs))
+        .pool_idle_timeout(Duration::from_secs(http.pool_idle_timeout_secs))
+        .pool_max_idle_per_host(http.pool_max_idle_per_host)
+        .tcp_keepalive(Duration::from_secs(http.tcp_keepalive_secs))
+        .build()
+        .unwrap_or_else(|e| {
+            log_error!("Failed to build tuned HTTP client, using defaults: {}", e);
+            Client::new()
+        })
+}
+
+/// # SharedCredential
+///
+/// **Summary:**
+/// Thread-safe handle to a provider's current API key and HTTP client.
+///
+/// **Design Note:**
+/// Requests copy the key and client when they start, so in-flight requests finish
+/// with the old key while requests started after a reload pick up the new one.
+/// The HTTP client (and its connection pool) is built once per provider and kept
+/// across key reloads; the key is only ever attached per request.
+///
+/// **Usage Example:**
+/// ```ignore
+/// use grokprime_brain::llm::credentials::{ApiProvider, SharedCredential};
+///
+/// let credential = SharedCredential::for_provider(ApiProvider::Grok)?;
+/// let response = credential.client()
+///     .post(url)
+///     .bearer_auth(credential.api_key())
+///     .send()
+///     .await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedCredential {
+    provider: ApiProvider,
+    inner: Arc<RwLock<Credential>>,
+}
+
+static CREDENTIALS: Lazy<Mutex<HashMap<ApiProvider, SharedCredential>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+impl SharedCredential {
+    fn new(provider: ApiProvider, key: EnvValue) -> Self {
+        Self {
+            provider,
+            inner: Arc::new(RwLock::new(Credential {
+                api_key: key.value,
+                provenance: key.provenance,
+                client: build_http_client(),
+            })),
+        }
+    }
+
+    /// A credential outside the process-wide map, so tests never touch the real keys
+    #[cfg(test)]
+    pub(crate) fn detached(provider: ApiProvider, key: EnvValue) -> Self {
+        Self::new(provider, key)
+    }
+
+    /// # for_provider
+    ///
+    /// **Purpose:**
+    /// Returns the process-wide credential for a provider, reading the key on first use.
+    ///
+    /// **Parameters:**
+    /// - `provider`: Which provider's credential to fetch
+    ///
+    /// **Returns:**
+    /// `Result<Self, String>` - Shared credential or error if the key is not set
+    ///
+    /// **Errors / Failures:**
+    /// - Key missing from both .env and the environment
+    pub fn for_provider(provider: ApiProvider) -> Result<Self, String> {
+        let mut credentials = CREDENTIALS.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(existing) = credentials.get(&provider) {
+            return Ok(existing.clone());
+        }
+
+        let key = load_env_value(provider.env_var())
+            .ok_or_else(|| format!("{} not set in .env or the environment", provider.env_var()))?;
+        let credential = Self::new(provider, key);
+        credentials.insert(provider, credential.clone());
+        Ok(credential)
+    }
+
+    /// Current API key (copied so the lock is not held across awaits)
+    pub fn api_key(&self) -> String {
+        self.inner.read().unwrap_or_else(|e| e.into_inner()).api_key.clone()
+    }
+
+    /// Current HTTP client (cheap clone of reqwest's internal Arc)
+    pub fn client(&self) -> Client {
+        self.inner.read().unwrap_or_else(|e| e.into_inner()).client.clone()
+    }
+
+    /// Where the current key came from
+    pub fn provenance(&self) -> Provenance {
+        self.inner.read().unwrap_or_else(|e| e.into_inner()).provenance
+    }
+
+    /// Swaps in a new key; pooled connections are kept
+    pub fn replace(&self, key: EnvValue) {
+        let mut credential = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        credential.api_key = key.value;
+        credential.provenance = key.provenance;
+    }
+
+    /// # unauthorized_hint
+    ///
+    /// **Purpose:**
+    /// Extra guidance appended to errors shown in the pane when the key was rejected.
+    ///
+    /// **Details:**
+    /// If the shell and .env disagree, says which one was used and how to switch.
+    pub fn unauthorized_hint(&self, status: reqwest::StatusCode) -> String {
+        if status != reqwest::StatusCode::UNAUTHORIZED {
+            return String::new();
+        }
+        let provenance = self.provenance();
+        match (provenance.conflict_note(self.provider.env_var()), provenance.source) {
+            (Some(note), ValueSource::Shell) => format!(
+                " (API key rejected - {}; unset it in your shell or enable credentials.prefer_dotenv)", note
+            ),
+            (Some(note), ValueSource::DotEnv) => format!(
+                " (API key rejected - {}; fix .env and run `reload keys`)", note
+            ),
+            (None, _) => " (API key rejected - update your .env and run `reload keys`)".to_string(),
+        }
+    }
+}
+
+/// # read_dotenv_value
+///
+/// **Purpose:**
+/// Looks up a single `KEY=value` entry in a .env file without touching the environment.
+///
+/// **Returns:**
+/// `Option<String>` - The value with surrounding quotes removed, or None if absent/unreadable
+fn read_dotenv_value(path: &Path, key: &str) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+
+    content.lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            line.split_once('=')
+        })
+        .find(|(name, _)| name.trim() == key)
+        .map(|(_, value)| value.trim().trim_matches('"').trim_matches('\'').to_string())
+}
+
+/// # KeyReloadReport
+///
+/// **Summary:**
+/// Outcome of reloading one provider's key.
+///
+/// **Fields:**
+/// - `provider`: Which provider was reloaded
+/// - `provenance`: Where the key came from (None if no key was found)
+/// - `auth_check`: Result of the authentication check (None if no key)
+#[derive(Debug)]
+pub struct KeyReloadReport {
+    pub provider: ApiProvider,
+    pub provenance: Option<Provenance>,
+    pub auth_check: Option<Result<(), String>>,
+}
+
+impl KeyReloadReport {
+    /// One-line summary suitable for the Global pane
+    pub fn describe(&self) -> String {
+        let name = self.provider.display_name();
+        let Some(provenance) = self.provenance else {
+            return format!("{}: no key found ({})", name, self.provider.env_var());
+        };
+        let origin = provenance.describe();
+        match &self.auth_check {
+            Some(Ok(())) => format!("{}: key reloaded {}, authentication OK", name, origin),
+            Some(Err(e)) => format!("{}: key reloaded {}, authentication failed: {}", name, origin, e),
+            None => format!("{}: key reloaded {}", name, origin),
+        }
+    }
+
+    /// Whether the key is missing or was rejected (shown as an error)
+    pub fn failed(&self) -> bool {
+        matches!(self.auth_check, Some(Err(_))) || self.provenance.is_none()
+    }
+}
+
+/// # SwappedKey
+///
+/// **Summary:**
+/// A provider's key as `swap_keys` left it, ready to be checked.
+///
+/// **Fields:**
+/// - `provider`: Which provider was reloaded
+/// - `provenance`: Where the new key came from (None if no key was found)
+/// - `credential`: The updated shared credential (None if no key was found)
+#[derive(Debug, Clone)]
+pub struct SwappedKey {
+    pub provider: ApiProvider,
+    pub provenance: Option<Provenance>,
+    credential: Option<SharedCredential>,
+}
+
+impl SwappedKey {
+    /// # check
+    ///
+    /// **Purpose:**
+    /// Verifies the new key with its provider and reports the outcome.
+    ///
+    /// **Details:**
+    /// Takes up to 5s per provider; callers on the UI loop should run it in the background.
+    pub async fn check(self) -> KeyReloadReport {
+        let auth_check = match &self.credential {
+            Some(credential) => {
+                let result = check_auth(self.provider, credential).await;
+                log_info!("Key reload: {} auth check {:?}", self.provider.display_name(), result);
+                Some(result)
+            }
+            None => None,
+        };
+        KeyReloadReport { provider: self.provider, provenance: self.provenance, auth_check }
+    }
+}
+
+/// # swap_keys
+///
+/// **Purpose:**
+/// Re-reads every provider's key and swaps it into all open connections.
+///
+/// **Returns:**
+/// `Vec<SwappedKey>` - One per provider; `check` each to verify it
+///
+/// **Details:**
+/// - Existing shared credentials are updated in place (all clients see the new key)
+/// - Providers not used yet are initialized so later clients start with the new key
+/// - Nothing here touches the network, so the swap is done when this returns
+pub fn swap_keys() -> Vec<SwappedKey> {
+    let mut credentials = CREDENTIALS.lock().unwrap_or_else(|e| e.into_inner());
+
+    ApiProvider::ALL.into_iter().map(|provider| {
+        let Some(key) = load_env_value(provider.env_var()) else {
+            log_error!("Key reload: {} not set", provider.env_var());
+            return SwappedKey { provider, provenance: None, credential: None };
+        };
+        let provenance = key.provenance;
+        let credential = swap_key(&mut credentials, provider, key);
+        SwappedKey { provider, provenance: Some(provenance), credential: Some(credential) }
+    }).collect()
+}
+
+/// Puts `key` into `provider`'s credential in `credentials`, creating it if needed
+fn swap_key(
+    credentials: &mut HashMap<ApiProvider, SharedCredential>,
+    provider: ApiProvider,
+    key: EnvValue,
+) -> SharedCredential {
+    match credentials.get(&provider) {
+        Some(existing) => {
+            existing.replace(key);
+            existing.clone()
+        }
+        None => {
+            let credential = SharedCredential::new(provider, key);
+            credentials.insert(provider, credential.clone());
+            credential
+        }
+    }
+}
+
+/// # check_auth
+///
+/// **Purpose:**
+/// Verifies a key with a lightweight authenticated GET (no tokens consumed).
+///
+/// **Returns:**
+/// `Result<(), String>` - Ok if the provider accepted the key
+async fn check_auth(provider: ApiProvider, credential: &SharedCredential) -> Result<(), String> {
+    let client = credential.client();
+    let request = match provider {
+        ApiProvider::Grok => client
+            .get("https://api.x.ai/v1/api-key")
+            .bearer_auth(credential.api_key()),
+        ApiProvider::Claude => client
+            .get("https://api.anthropic.com/v1/models")
+            .header("x-api-key", credential.api_key())
+            .header("anthropic-version", "2023-06-01"),
+    };
+
+    let response = request
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| ShadowError::from(e).to_string())?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(format!("HTTP {}", status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grok::client::GrokClient;
+    use crate::test_support::{grok_reply_sse, MockResponse, MockServer, ScratchDir};
+
+    fn key(value: &str) -> EnvValue {
+        EnvValue {
+            value: value.to_string(),
+            provenance: Provenance { source: ValueSource::DotEnv, both_set: false, differs: false },
+        }
+    }
+
+    fn hello() -> ChatRequest {
+        ChatRequest::builder()
+            .model("grok-mock")
+            .messages(vec![Message { role: "user".into(), content: "hello".into(), meta: None, source: None }])
+            .stream(true)
+            .build()
+            .unwrap()
+    }
+
+    async fn send(client: &GrokClient) -> String {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut stream = ChunkStream::open(&tx, TaskKind::Reply);
+        stream.begin();
+        let response = client.send_streaming_request(&hello(), &mut stream).await.unwrap();
+        stream.complete(response.response_id.clone(), response.full_text.clone(), None).unwrap();
+        response.full_text
+    }
+
+    fn bearer_tokens(server: &MockServer) -> Vec<String> {
+        server.requests().iter()
+            .map(|request| request.header("authorization").unwrap_or_default().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn requests_after_a_swap_carry_the_new_key() {
+        let server = MockServer::start(|_| MockResponse::sse(grok_reply_sse("resp_1", &["Hi", " there"]))).await;
+        let mut credentials = HashMap::new();
+        let credential = swap_key(&mut credentials, ApiProvider::Grok, key("old-key"));
+        let client = GrokClient::with_credential(credential, server.url("/v1/responses"));
+
+        assert_eq!(send(&client).await, "Hi there");
+        swap_key(&mut credentials, ApiProvider::Grok, key("new-key"));
+        assert_eq!(send(&client).await, "Hi there");
+
+        assert_eq!(bearer_tokens(&server), ["Bearer old-key", "Bearer new-key"]);
+        for request in server.requests() {
+            assert_eq!((request.method.as_str(), request.path.as_str()), ("POST", "/v1/responses"));
+            assert!(request.body.contains("\"hello\""), "{}", request.body);
+        }
+    }
+
+    #[tokio::test]
+    async fn consecutive_requests_reuse_one_pooled_connection() {
+        let server = MockServer::keep_alive(|_| MockResponse::sse(grok_reply_sse("resp_1", &["Hi"]))).await;
+        let mut credentials = HashMap::new();
+        let credential = swap_key(&mut credentials, ApiProvider::Grok, key("old-key"));
+        let client = GrokClient::with_credential(credential, server.url("/v1/responses"));
+        let cloned = client.clone();
+
+        send(&client).await;
+        send(&client).await;
+        send(&cloned).await;
+        swap_key(&mut credentials, ApiProvider::Grok, key("new-key"));
+        send(&client).await;
+
+        assert_eq!(server.requests().len(), 4);
+        assert_eq!(server.connections(), 1, "clones and key swaps share the pool");
+        assert_eq!(bearer_tokens(&server).last().map(String::as_str), Some("Bearer new-key"));
+    }
+
+    #[tokio::test]
+    async fn separate_credentials_keep_separate_pools() {
+        let server = MockServer::keep_alive(|_| MockResponse::sse(grok_reply_sse("resp_1", &["Hi"]))).await;
+        let first = GrokClient::with_credential(SharedCredential::detached(ApiProvider::Grok, key("a")), server.url("/v1/responses"));
+        let second = GrokClient::with_credential(SharedCredential::detached(ApiProvider::Grok, key("b")), server.url("/v1/responses"));
+
+        send(&first).await;
+        send(&second).await;
+        send(&first).await;
+
+        assert_eq!(server.connections(), 2);
+    }
+
+    #[tokio::test]
+    async fn a_rejected_key_says_how_to_reload_it() {
+        let server = MockServer::start(|_| MockResponse::json(401, r#"{"error":"Incorrect API key"}"#)).await;
+        let mut credentials = HashMap::new();
+        let credential = swap_key(&mut credentials, ApiProvider::Grok, key("stale-key"));
+        let client = GrokClient::with_credential(credential, server.url("/v1/responses"));
+
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut stream = ChunkStream::open(&tx, TaskKind::Reply);
+        stream.begin();
+        let error = client.send_streaming_request(&hello(), &mut stream).await.map(|_| ()).unwrap_err().to_string();
+        stream.fail("rejected").unwrap();
+
+        assert!(error.contains("401"), "{}", error);
+        assert!(error.ends_with("(API key rejected - update your .env and run `reload keys`)"), "{}", error);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn a_request_in_flight_finishes_with_the_old_key() {
+        let server = MockServer::start(|request| {
+            let reply = MockResponse::sse(grok_reply_sse("resp_1", &["done"]));
+            match request.header("authorization") {
+                Some("Bearer old-key") => reply.after(Duration::from_millis(300)),
+                _ => reply,
+            }
+        }).await;
+        let mut credentials = HashMap::new();
+        let credential = swap_key(&mut credentials, ApiProvider::Grok, key("old-key"));
+        let client = GrokClient::with_credential(credential, server.url("/v1/responses"));
+
+        let slow_client = client.clone();
+        let in_flight = tokio::spawn(async move { send(&slow_client).await });
+        while server.requests().is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        swap_key(&mut credentials, ApiProvider::Grok, key("new-key"));
+        assert_eq!(send(&client).await, "done");
+        assert_eq!(in_flight.await.unwrap(), "done");
+
+        assert_eq!(bearer_tokens(&server), ["Bearer old-key", "Bearer new-key"]);
+    }
+
+    #[test]
+    fn swapping_updates_every_clone_of_the_credential() {
+        let mut credentials = HashMap::new();
+        let first = swap_key(&mut credentials, ApiProvider::Claude, key("old-key"));
+        let second = swap_key(&mut credentials, ApiProvider::Claude, key("new-key"));
+
+        assert_eq!(first.api_key(), "new-key");
+        assert_eq!(second.api_key(), "new-key");
+        assert_eq!(credentials.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn a_missing_key_is_reported_without_a_check() {
+        let swapped = SwappedKey { provider: ApiProvider::Claude, provenance: None, credential: None };
+        let report = swapped.check().await;
+
+        assert!(report.failed());
+        assert!(report.auth_check.is_none());
+        assert_eq!(report.describe(), "Claude: no key found (CLAUDE_KEY)");
+    }
+
+    type Resolved = Option<(&'static str, ValueSource, bool, bool)>;
+
+    #[test]
+    fn the_four_combinations_resolve_with_their_provenance() {
+        let some = |value: &str| Some(value.to_string());
+        let cases: [(&str, Option<String>, Option<String>, bool, Resolved); 9] = [
+            ("env only", some("shell"), None, false, Some(("shell", ValueSource::Shell, false, false))),
+            ("file only", None, some("file"), false, Some(("file", ValueSource::DotEnv, false, false))),
+            ("both equal", some("same"), some("same"), false, Some(("same", ValueSource::Shell, true, false))),
+            ("both differing", some("stale"), some("fresh"), false, Some(("stale", ValueSource::Shell, true, true))),
+            ("env only, prefer .env", some("shell"), None, true, Some(("shell", ValueSource::Shell, false, false))),
+            ("both equal, prefer .env", some("same"), some("same"), true, Some(("same", ValueSource::DotEnv, true, false))),
+            ("both differing, prefer .env", some("stale"), some("fresh"), true, Some(("fresh", ValueSource::DotEnv, true, true))),
+            ("blank shell", some("  "), some("file"), false, Some(("file", ValueSource::DotEnv, false, false))),
+            ("neither", None, some(""), true, None),
+        ];
+
+        for (case, shell, dotenv, prefer_dotenv, expected) in cases {
+            let resolved = resolve_env_value(shell, dotenv, prefer_dotenv);
+            let expected = expected.map(|(value, source, both_set, differs)| EnvValue {
+                value: value.to_string(),
+                provenance: Provenance { source, both_set, differs },
+            });
+            assert_eq!(resolved, expected, "{}", case);
+        }
+    }
+
+    #[test]
+    fn only_differing_values_are_a_conflict() {
+        let provenance = |source, both_set, differs| Provenance { source, both_set, differs };
+        let cases = [
+            (provenance(ValueSource::Shell, false, false), None),
+            (provenance(ValueSource::Shell, true, false), None),
+            (provenance(ValueSource::Shell, true, true), Some("GROK_KEY came from your shell environment, not .env - the .env value differs")),
+            (provenance(ValueSource::DotEnv, true, true), Some("GROK_KEY came from .env, not your shell environment - the shell value differs")),
+        ];
+
+        for (provenance, expected) in cases {
+            assert_eq!(provenance.conflict_note("GROK_KEY").as_deref(), expected, "{:?}", provenance);
+        }
+    }
+
+    #[test]
+    fn a_401_hint_says_which_copy_of_the_key_to_fix() {
+        let hint = |source, differs| {
+            let key = EnvValue { value: "k".to_string(), provenance: Provenance { source, both_set: differs, differs } };
+            SharedCredential::detached(ApiProvider::Grok, key).unauthorized_hint(reqwest::StatusCode::UNAUTHORIZED)
+        };
+
+        assert_eq!(hint(ValueSource::DotEnv, false), " (API key rejected - update your .env and run `reload keys`)");
+        let shell = hint(ValueSource::Shell, true);
+        assert!(shell.contains("GROK_KEY came from your shell environment, not .env"), "{}", shell);
+        assert!(shell.ends_with("unset it in your shell or enable credentials.prefer_dotenv)"), "{}", shell);
+        assert!(hint(ValueSource::DotEnv, true).ends_with("fix .env and run `reload keys`)"));
+
+        let forbidden = SharedCredential::detached(ApiProvider::Grok, key("k")).unauthorized_hint(reqwest::StatusCode::FORBIDDEN);
+        assert_eq!(forbidden, "", "only a 401 blames the key");
+    }
+
+    #[test]
+    fn dotenv_values_are_read_without_the_environment() {
+        let dir = ScratchDir::new("dotenv");
+        let path = dir.write(".env", "# GROK_KEY=commented\nexport GROK_KEY=\"quoted\"\nCLAUDE_KEY = 'single' \nTWITTER_KEY=plain\n");
+        let cases = [
+            ("GROK_KEY", Some("quoted")),
+            ("CLAUDE_KEY", Some("single")),
+            ("TWITTER_KEY", Some("plain")),
+            ("GROK", None),
+        ];
+
+        for (name, expected) in cases {
+            assert_eq!(read_dotenv_value(&path, name).as_deref(), expected, "{}", name);
+        }
+        assert_eq!(read_dotenv_value(&dir.join("missing.env"), "GROK_KEY"), None);
+    }
+}
@@ -9,6 +9,9 @@
 //! **Responsibilities:**
 //! - Define LlmClient trait for API communication
 //! - Define shared response types
+//! - Build provider-agnostic requests (`request`)
+//! - Tally each connection's token use (`usage`)
+//! - Replay canned replies for tests (`scripted`)
 //!
 //! **Author:** Daegonica Software
 //! **Version:** 0.1.0
@@ -25,9 +28,15 @@ use async_trait::async_trait;
 /// **Fields:**
 /// - `response_id`: API-specific ID for conversation continuity
 /// - `full_text`: Complete assembled response text
+/// - `model`: Model id reported by the API, if it sent one
+/// - `input_tokens`: Prompt tokens reported by the API
+/// - `output_tokens`: Completion tokens reported by the API
 pub struct StreamResponse {
     pub response_id: String,
     pub full_text: String,
+    pub model: Option<String>,
+    pub input_tokens: Option<u32>,
+    pub output_tokens: Option<u32>,
 }
 
 /// # LlmClient
@@ -53,7 +62,7 @@ pub trait LlmClient: Send + Sync + Clone {
     ///
     /// # Parameters
     /// - `request`: The chat request payload
-    /// - `tx`: Channel for sending StreamChunk updates
+    /// - `stream`: The reply's stream, already begun; only text deltas are sent to it
     ///
     /// # Returns
     /// Complete StreamResponse with response_id and full_text
@@ -63,10 +72,12 @@ pub trait LlmClient: Send + Sync + Clone {
     /// - Authentication errors
     /// - API errors (non-2xx status)
     /// - Parsing errors
+    ///
+    /// The caller reports errors to the stream; the client doesn't.
     async fn send_streaming(
         &self,
         request: &ChatRequest,
-        tx: mpsc::UnboundedSender<StreamChunk>,
+        stream: &mut ChunkStream,
     ) -> Result<StreamResponse, Box<dyn std::error::Error>>;
 
     /// Send a chat request and return complete response (for CLI mode)
@@ -85,11 +96,16 @@ pub trait LlmClient: Send + Sync + Clone {
 }
 
 pub mod client;
+pub mod credentials;
+pub mod request;
+pub mod scripted;
+pub mod usage;
 
 #[derive(Debug, Clone)]
 pub enum AnyClient {
     Grok(GrokClient),
     Claude(ClaudeClient),
+    Scripted(scripted::ScriptedClient),
 }
 
 #[async_trait]
@@ -97,11 +113,12 @@ impl LlmClient for AnyClient {
     async fn send_streaming(
         &self,
         request: &ChatRequest,
-        tx: mpsc::UnboundedSender<StreamChunk>,
+        stream: &mut ChunkStream,
     ) -> Result<StreamResponse, Box<dyn std::error::Error>> {
         match self {
-            AnyClient::Grok(client) => client.send_streaming(request, tx).await,
-            AnyClient::Claude(client) => client.send_streaming(request, tx).await,
+            AnyClient::Grok(client) => client.send_streaming(request, stream).await,
+            AnyClient::Claude(client) => client.send_streaming(request, stream).await,
+            AnyClient::Scripted(client) => client.send_streaming(request, stream).await,
         }
     }
 
@@ -113,6 +130,7 @@ impl LlmClient for AnyClient {
         match self {
             AnyClient::Grok(client) => client.send_blocking(request, print_stream).await,
             AnyClient::Claude(client) => client.send_blocking(request, print_stream).await,
+            AnyClient::Scripted(client) => client.send_blocking(request, print_stream).await,
         }
     }
 }
\ No newline at end of file
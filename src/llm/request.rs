@@ -0,0 +1,216 @@
+//! # Daegonica Module: llm::request
+//!
+//! **Purpose:** The one way to put together a `ChatRequest`
+//!
+//! **Context:**
+//! - Conversation requests and historian requests used to be struct literals, each repeating the defaults
+//! - Provider clients only read a finished request; adapting it is their job
+//!
+//! **Responsibilities:**
+//! - Define `ChatRequest`, which can only be created through `ChatRequestBuilder`
+//! - Resolve model, temperature, output cap, and streaming: explicit, then persona, then `GLOBAL_CONFIG`
+//! - Place ephemeral messages (nudges, context notes) right before the newest user message
+//! - Reject requests with no input or an out-of-range temperature
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::ops::RangeInclusive;
+use thiserror::Error;
+use crate::prelude::*;
+
+/// Temperatures the providers accept
+pub const TEMPERATURE_RANGE: RangeInclusive<f32> = 0.0..=2.0;
+
+/// # ChatRequest
+///
+/// **Summary:**
+/// Request payload for the Grok API chat endpoint with conversation context.
+///
+/// **Fields:**
+/// - `model`: The Grok model to use (e.g., "grok-4-fast")
+/// - `input`: Vector of messages forming the conversation history
+/// - `temperature`: Sampling temperature for response randomness
+/// - `previous_response_id`: Optional ID for conversation continuity
+/// - `stream`: Ask for a streamed reply
+/// - `max_output_tokens`: Optional cap on the reply length
+///
+/// **Details:**
+/// Fields are readable everywhere, but a private marker keeps struct literals
+/// out: build one with `ChatRequest::builder()`.
+///
+/// **Usage Example:**
+/// ```rust
+/// # use grokprime_brain::llm::request::ChatRequest;
+/// # use grokprime_brain::models::Message;
+/// # use grokprime_brain::persona::builtin::load_builtin;
+/// # fn main() -> anyhow::Result<()> {
+/// # let persona = load_builtin("shadow")?;
+/// # let msg = Message { role: "user".to_string(), content: "Hello Shadow!".to_string(), meta: None, source: None };
+/// let request = ChatRequest::builder()
+///     .persona(&persona)
+///     .messages(vec![msg])
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+// `#[non_exhaustive]` only stops other crates; the marker stops this one too
+#[allow(clippy::manual_non_exhaustive)]
+#[derive(Serialize, Debug, Clone)]
+pub struct ChatRequest {
+    pub model: String,
+    pub input: Vec<Message>,
+    pub temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_response_id: Option<String>,
+    pub stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+    #[serde(skip)]
+    _sealed: (),
+}
+
+impl ChatRequest {
+    pub fn builder() -> ChatRequestBuilder {
+        ChatRequestBuilder::default()
+    }
+}
+
+/// # ChatRequestError
+///
+/// **Summary:**
+/// Why a `ChatRequestBuilder` refused to build.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ChatRequestError {
+    #[error("request has no input messages")]
+    EmptyInput,
+
+    #[error("temperature {0} is outside {min}..={max}", min = TEMPERATURE_RANGE.start(), max = TEMPERATURE_RANGE.end())]
+    TemperatureOutOfRange(f32),
+}
+
+/// # ChatRequestBuilder
+///
+/// **Summary:**
+/// Collects the parts of a `ChatRequest` and fills in the rest at `build`.
+///
+/// **Details:**
+/// Every setting resolves the same way: a value set on the builder wins, then
+/// the persona's (`temperature`, `max_tokens`) if one was given, then
+/// `GLOBAL_CONFIG.grok`. Messages lose their `meta` and `source` on the way
+/// out; those are local bookkeeping, not part of the API payload.
+#[derive(Debug, Clone, Default)]
+pub struct ChatRequestBuilder {
+    model: Option<String>,
+    input: Vec<Message>,
+    ephemeral: Vec<Message>,
+    temperature: Option<f32>,
+    max_output_tokens: Option<u32>,
+    stream: Option<bool>,
+    previous_response_id: Option<String>,
+    persona_temperature: Option<f32>,
+    persona_max_tokens: Option<u32>,
+}
+
+impl ChatRequestBuilder {
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// The conversation to send, in order (replaces any set before)
+    pub fn messages(mut self, messages: impl IntoIterator<Item = Message>) -> Self {
+        self.input = messages.into_iter().collect();
+        self
+    }
+
+    /// # ephemeral
+    ///
+    /// **Purpose:**
+    /// Adds messages sent with this request only.
+    ///
+    /// **Details:**
+    /// They go right before the newest user message, or at the end when the
+    /// input doesn't end with one. Calls add up, in order.
+    pub fn ephemeral(mut self, messages: impl IntoIterator<Item = Message>) -> Self {
+        self.ephemeral.extend(messages);
+        self
+    }
+
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    pub fn max_output_tokens(mut self, max_output_tokens: u32) -> Self {
+        self.max_output_tokens = Some(max_output_tokens);
+        self
+    }
+
+    pub fn stream(mut self, stream: bool) -> Self {
+        self.stream = Some(stream);
+        self
+    }
+
+    /// Continues a server-side thread (None sends a standalone request)
+    pub fn previous_response_id(mut self, id: Option<String>) -> Self {
+        self.previous_response_id = id;
+        self
+    }
+
+    /// Uses the persona's `temperature` and `max_tokens` where nothing was set explicitly
+    pub fn persona(mut self, persona: &Persona) -> Self {
+        self.persona_temperature = persona.temperature;
+        self.persona_max_tokens = persona.max_tokens;
+        self
+    }
+
+    /// # build
+    ///
+    /// **Purpose:**
+    /// Resolves defaults and checks the request can be sent.
+    ///
+    /// **Returns:**
+    /// `Result<ChatRequest, ChatRequestError>` - The request
+    ///
+    /// **Errors / Failures:**
+    /// - No input messages, ephemeral ones included
+    /// - Resolved temperature outside `TEMPERATURE_RANGE`
+    pub fn build(self) -> Result<ChatRequest, ChatRequestError> {
+        let temperature = self.temperature
+            .or(self.persona_temperature)
+            .unwrap_or(GLOBAL_CONFIG.grok.default_temperature);
+        if !TEMPERATURE_RANGE.contains(&temperature) {
+            return Err(ChatRequestError::TemperatureOutOfRange(temperature));
+        }
+
+        let mut input = self.input;
+        let at = match input.last() {
+            Some(last) if last.role == "user" => input.len() - 1,
+            _ => input.len(),
+        };
+        input.splice(at..at, self.ephemeral);
+        if input.is_empty() {
+            return Err(ChatRequestError::EmptyInput);
+        }
+        for message in &mut input {
+            message.meta = None;
+            message.source = None;
+        }
+
+        Ok(ChatRequest {
+            model: self.model.unwrap_or_else(|| GLOBAL_CONFIG.grok.model_name.clone()),
+            input,
+            temperature,
+            previous_response_id: self.previous_response_id,
+            stream: self.stream.unwrap_or(GLOBAL_CONFIG.grok.stream_enabled),
+            max_output_tokens: self.max_output_tokens.or(self.persona_max_tokens),
+            _sealed: (),
+        })
+    }
+}
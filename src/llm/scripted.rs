@@ -0,0 +1,226 @@
+//! # Daegonica Module: llm::scripted
+//!
+//! **Purpose:** An LLM client that replays canned replies instead of calling an API
+//!
+//! **Context:**
+//! - Agents, macros, summaries and the TUI need a model on the other end to test
+//! - Tests must not need API keys or the network, and must control when a reply finishes
+//! - Used through `AnyClient::Scripted` and `AgentManager::client_override`
+//!
+//! **Responsibilities:**
+//! - Queue replies: text in chunks, errors (up front or mid-reply), and replies held until released
+//! - Record every request sent, so tests can check the prompts
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use async_trait::async_trait;
+use tokio::sync::Notify;
+use crate::prelude::*;
+use crate::llm::{LlmClient, StreamResponse};
+
+/// # ScriptedReply
+///
+/// **Summary:**
+/// One canned reply, sent the next time the client is asked for one.
+///
+/// **Fields:**
+/// - `chunks`: Text deltas, streamed in order
+/// - `error`: Fail the request with this, after streaming any `chunks`
+/// - `gate`: Wait for `ReplyGate::release` before streaming chunk `hold_at`
+/// - `hold_at`: Chunks streamed before the gate (0: nothing, and an error waits too)
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedReply {
+    chunks: Vec<String>,
+    error: Option<String>,
+    gate: Option<ReplyGate>,
+    hold_at: usize,
+}
+
+impl ScriptedReply {
+    /// A reply streamed as one chunk
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::chunks([text])
+    }
+
+    /// A reply streamed as the given chunks
+    pub fn chunks<S: Into<String>>(chunks: impl IntoIterator<Item = S>) -> Self {
+        Self { chunks: chunks.into_iter().map(Into::into).collect(), ..Default::default() }
+    }
+
+    /// A request that fails with `msg`, as an API error would
+    pub fn error(msg: impl Into<String>) -> Self {
+        Self { error: Some(msg.into()), ..Default::default() }
+    }
+
+    /// Streams the chunks, then fails with `msg` as a stream cut off mid-reply would
+    pub fn failing(mut self, msg: impl Into<String>) -> Self {
+        self.error = Some(msg.into());
+        self
+    }
+
+    /// Holds the reply back until the returned gate is released
+    pub fn held(self) -> (Self, ReplyGate) {
+        self.held_after(0)
+    }
+
+    /// Streams the first `chunks` chunks, then holds the rest until the returned gate is released
+    pub fn held_after(mut self, chunks: usize) -> (Self, ReplyGate) {
+        let gate = ReplyGate::default();
+        self.gate = Some(gate.clone());
+        self.hold_at = chunks;
+        (self, gate)
+    }
+
+    /// Waits out the gate if it stands before chunk `index`
+    async fn wait_before(&self, index: usize) {
+        if let Some(gate) = self.gate.as_ref().filter(|_| self.hold_at == index) {
+            gate.0.notified().await;
+        }
+    }
+}
+
+/// # ReplyGate
+///
+/// **Summary:**
+/// Lets a held `ScriptedReply` go; releasing before the request arrives is fine.
+#[derive(Debug, Clone, Default)]
+pub struct ReplyGate(Arc<Notify>);
+
+impl ReplyGate {
+    pub fn release(&self) {
+        self.0.notify_one();
+    }
+}
+
+#[derive(Debug, Default)]
+struct Script {
+    replies: VecDeque<ScriptedReply>,
+    requests: Vec<ChatRequest>,
+}
+
+/// # ScriptedClient
+///
+/// **Summary:**
+/// `LlmClient` that answers from a queue of `ScriptedReply`s.
+///
+/// **Details:**
+/// Clones share the queue and the request log, so a test keeps one clone and
+/// hands the other to an agent. A request with nothing queued fails with
+/// "No scripted reply left".
+///
+/// **Usage Example:**
+/// ```no_run
+/// # use grokprime_brain::llm::AnyClient;
+/// # use grokprime_brain::llm::scripted::{ScriptedClient, ScriptedReply};
+/// # use grokprime_brain::tui::app::ShadowApp;
+/// # let mut app = ShadowApp::new();
+/// let client = ScriptedClient::default();
+/// client.push(ScriptedReply::chunks(["Hel", "lo"]));
+/// app.agent_manager.client_override = Some(AnyClient::Scripted(client.clone()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedClient {
+    script: Arc<Mutex<Script>>,
+}
+
+impl ScriptedClient {
+    /// Queues `reply` after any already queued
+    pub fn push(&self, reply: ScriptedReply) {
+        self.lock().replies.push_back(reply);
+    }
+
+    /// Every request sent so far, oldest first
+    pub fn requests(&self) -> Vec<ChatRequest> {
+        self.lock().requests.clone()
+    }
+
+    /// Replies queued and not yet asked for
+    pub fn queued(&self) -> usize {
+        self.lock().replies.len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Script> {
+        self.script.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Records `request` and waits out the next reply's gate if it holds everything
+    async fn next_reply(&self, request: &ChatRequest) -> Result<(usize, ScriptedReply), Box<dyn std::error::Error>> {
+        let (number, reply) = {
+            let mut script = self.lock();
+            script.requests.push(request.clone());
+            (script.requests.len(), script.replies.pop_front())
+        };
+        let reply = reply.ok_or("No scripted reply left")?;
+        if (reply.error.is_some() && reply.chunks.is_empty()) || reply.hold_at == 0 {
+            reply.wait_before(reply.hold_at).await;
+        }
+        match &reply.error {
+            Some(error) if reply.chunks.is_empty() => Err(error.clone().into()),
+            _ => Ok((number, reply)),
+        }
+    }
+
+    fn finish(number: usize, request: &ChatRequest, reply: ScriptedReply) -> Result<StreamResponse, Box<dyn std::error::Error>> {
+        match reply.error {
+            Some(error) => Err(error.into()),
+            None => Ok(Self::response(number, request, reply)),
+        }
+    }
+
+    fn response(number: usize, request: &ChatRequest, reply: ScriptedReply) -> StreamResponse {
+        StreamResponse {
+            response_id: format!("scripted-{}", number),
+            full_text: reply.chunks.concat(),
+            model: Some(request.model.clone()),
+            input_tokens: None,
+            output_tokens: None,
+        }
+    }
+}
+
+#[async_trait]
+impl LlmClient for ScriptedClient {
+    async fn send_streaming(
+        &self,
+        request: &ChatRequest,
+        stream: &mut ChunkStream,
+    ) -> Result<StreamResponse, Box<dyn std::error::Error>> {
+        let (number, reply) = self.next_reply(request).await?;
+        for (index, chunk) in reply.chunks.iter().enumerate() {
+            if index > 0 {
+                reply.wait_before(index).await;
+            }
+            stream.delta(chunk.clone())?;
+        }
+        if !reply.chunks.is_empty() {
+            reply.wait_before(reply.chunks.len()).await;
+        }
+        Self::finish(number, request, reply)
+    }
+
+    async fn send_blocking(
+        &self,
+        request: &ChatRequest,
+        print_stream: bool,
+    ) -> Result<StreamResponse, Box<dyn std::error::Error>> {
+        let (number, reply) = self.next_reply(request).await?;
+        if reply.hold_at > 0 {
+            reply.wait_before(reply.hold_at).await;
+        }
+        if print_stream {
+            for chunk in &reply.chunks {
+                print!("{}", chunk);
+            }
+            println!();
+        }
+        Self::finish(number, request, reply)
+    }
+}
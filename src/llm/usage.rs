@@ -0,0 +1,220 @@
+//! # Daegonica Module: llm::usage
+//!
+//! **Purpose:** Token spend per connection for the session
+//!
+//! **Context:**
+//! - `Connection` records every completed request here: replies and historian (summarization) requests
+//! - Counts come from the provider's reported usage; requests it didn't report are counted, not guessed
+//! - Read by the `usage` command; nothing is persisted (the `insights` report covers past months)
+//!
+//! **Responsibilities:**
+//! - Tally input and output tokens, split into replies and summaries
+//! - Remember the last request's tokens
+//! - Estimate cost per model from `insights.model_prices`
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::collections::BTreeMap;
+use strum::Display;
+use crate::config::ModelPrice;
+use crate::utilities::insights::format_tokens;
+
+/// # UsageKind
+///
+/// **Summary:**
+/// What a request was for.
+///
+/// **Variants:**
+/// - `Reply`: A message to the agent
+/// - `Summary`: A historian request made while summarizing its history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum UsageKind {
+    Reply,
+    Summary,
+}
+
+/// # TokenTally
+///
+/// **Summary:**
+/// Tokens added up over some requests.
+///
+/// **Fields:**
+/// - `requests`: Requests counted
+/// - `input_tokens` / `output_tokens`: Tokens the provider reported
+/// - `unreported`: Requests that came back without usage (counted, no tokens added)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenTally {
+    pub requests: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub unreported: u32,
+}
+
+impl TokenTally {
+    fn add(&mut self, input_tokens: Option<u32>, output_tokens: Option<u32>) {
+        self.requests += 1;
+        if input_tokens.is_none() && output_tokens.is_none() {
+            self.unreported += 1;
+        }
+        self.input_tokens += input_tokens.unwrap_or(0) as u64;
+        self.output_tokens += output_tokens.unwrap_or(0) as u64;
+    }
+
+    pub fn merge(&mut self, other: &TokenTally) {
+        self.requests += other.requests;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.unreported += other.unreported;
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+
+    /// e.g. "8 requests, 10.1k in / 2.2k out (1 without usage)"
+    pub fn describe(&self) -> String {
+        let mut description = format!(
+            "{} request{}, {} in / {} out",
+            self.requests,
+            if self.requests == 1 { "" } else { "s" },
+            format_tokens(self.input_tokens),
+            format_tokens(self.output_tokens),
+        );
+        if self.unreported > 0 {
+            description.push_str(&format!(" ({} without usage)", self.unreported));
+        }
+        description
+    }
+}
+
+/// # RequestUsage
+///
+/// **Summary:**
+/// One completed request's tokens.
+///
+/// **Fields:**
+/// - `kind`: Reply or summary
+/// - `model`: Model that answered
+/// - `input_tokens` / `output_tokens`: As reported, if they were
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestUsage {
+    pub kind: UsageKind,
+    pub model: String,
+    pub input_tokens: Option<u32>,
+    pub output_tokens: Option<u32>,
+}
+
+impl RequestUsage {
+    /// e.g. "1.2k in / 300 out (reply, grok-4-fast)"
+    pub fn describe(&self) -> String {
+        let tokens = |count: Option<u32>| count.map(|c| format_tokens(c as u64)).unwrap_or_else(|| "?".to_string());
+        format!("{} in / {} out ({}, {})", tokens(self.input_tokens), tokens(self.output_tokens), self.kind, self.model)
+    }
+}
+
+/// # CostEstimate
+///
+/// **Summary:**
+/// Estimated spend for a `SessionUsage`.
+///
+/// **Fields:**
+/// - `cost`: Cost of the tokens whose model has a price
+/// - `unpriced`: Models with tokens but no configured price
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostEstimate {
+    pub cost: f64,
+    pub unpriced: Vec<String>,
+}
+
+impl CostEstimate {
+    /// # describe
+    ///
+    /// **Purpose:**
+    /// The estimate for display, e.g. "~$0.42" or "~$0.42 + grok-3 (no price)".
+    ///
+    /// **Details:**
+    /// With nothing priced at all, says where prices are set instead of showing $0.
+    pub fn describe(&self, any_priced: bool) -> String {
+        let cost = match self.cost {
+            _ if !any_priced => return "no prices configured (insights.model_prices)".to_string(),
+            cost if cost > 0.0 && cost < 0.01 => "<$0.01".to_string(),
+            cost => format!("~${:.2}", cost),
+        };
+        if self.unpriced.is_empty() {
+            cost
+        } else {
+            format!("{} + {} (no price)", cost, self.unpriced.join(", "))
+        }
+    }
+}
+
+/// # SessionUsage
+///
+/// **Summary:**
+/// A connection's token use since it was opened.
+///
+/// **Fields:**
+/// - `replies`: Requests answering the user
+/// - `summaries`: Historian requests (a long summarization can be several)
+/// - `last`: The most recent request of either kind
+/// - `by_model`: Both kinds per model, for pricing
+#[derive(Debug, Clone, Default)]
+pub struct SessionUsage {
+    pub replies: TokenTally,
+    pub summaries: TokenTally,
+    pub last: Option<RequestUsage>,
+    by_model: BTreeMap<String, TokenTally>,
+}
+
+impl SessionUsage {
+    /// # record
+    ///
+    /// **Purpose:**
+    /// Adds one completed request.
+    ///
+    /// **Parameters:**
+    /// - `kind`: Reply or summary
+    /// - `model`: Model that answered (the reported one when the provider sent it)
+    /// - `input_tokens` / `output_tokens`: Reported usage, None when missing
+    pub fn record(&mut self, kind: UsageKind, model: &str, input_tokens: Option<u32>, output_tokens: Option<u32>) {
+        match kind {
+            UsageKind::Reply => self.replies.add(input_tokens, output_tokens),
+            UsageKind::Summary => self.summaries.add(input_tokens, output_tokens),
+        }
+        self.by_model.entry(model.to_string()).or_default().add(input_tokens, output_tokens);
+        self.last = Some(RequestUsage { kind, model: model.to_string(), input_tokens, output_tokens });
+    }
+
+    /// Replies and summaries together
+    pub fn total(&self) -> TokenTally {
+        let mut total = self.replies;
+        total.merge(&self.summaries);
+        total
+    }
+
+    /// # estimated_cost
+    ///
+    /// **Purpose:**
+    /// Prices the session's tokens model by model.
+    ///
+    /// **Parameters:**
+    /// - `prices`: `insights.model_prices`; the first matching prefix wins
+    pub fn estimated_cost(&self, prices: &[ModelPrice]) -> CostEstimate {
+        let mut estimate = CostEstimate { cost: 0.0, unpriced: Vec::new() };
+        for (model, tally) in &self.by_model {
+            match ModelPrice::find(prices, model) {
+                Some(price) => estimate.cost += price.cost(tally.input_tokens, tally.output_tokens),
+                None if tally.total_tokens() > 0 => estimate.unpriced.push(model.clone()),
+                None => {}
+            }
+        }
+        estimate
+    }
+}
@@ -20,15 +20,30 @@
 //! This file is part of the Daegonica Software codebase.
 //! ---------------------------------------------------------------
 
-use grokprime_brain::persona::discover_personas;
+use grokprime_brain::persona::{discover_personas, migrate_state_files, personas_root, set_personas_root};
 use grokprime_brain::{
     prelude::*,
     commands::{from_input_action, CommandResult},
     persona::operations::AgentOperations,
 };
+use grokprime_brain::utilities::log_level::set_level;
+use grokprime_brain::utilities::accessibility;
+use grokprime_brain::utilities::strict;
+use grokprime_brain::utilities::cli::{write_completions, CliCommand};
+use grokprime_brain::utilities::events;
+use grokprime_brain::utilities::profile;
+use grokprime_brain::agent_history::legacy::{self, MigrateOptions, LEGACY_HISTORY_DIR};
+use grokprime_brain::agent_history::integrity::CheckStatus;
+use grokprime_brain::agent_history::persistence;
+use grokprime_brain::llm::credentials::credential_conflicts;
+use grokprime_brain::twitter::spellcheck;
+use grokprime_brain::user::outgoing::Pipeline;
+use grokprime_brain::user::command_history::SharedCommandHistory;
+use grokprime_brain::tui::terminal_status::{Activity, TerminalStatus};
+use grokprime_brain::tui::hyperlinks::{self, HyperlinkWriter};
 use clap::Parser;
 use crossterm::{
-    event::{self, Event, KeyEventKind},
+    event::{self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event, KeyEventKind},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
@@ -36,7 +51,10 @@ use uuid::Uuid;
 use std::sync::Arc;
 use ratatui::prelude::*;
 use std::io::stdout;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// Key events arriving closer together than this are read as one burst (paste fallback)
+const KEY_BURST_WINDOW: Duration = Duration::from_millis(3);
 
 /// # main
 ///
@@ -56,9 +74,56 @@ use std::time::Duration;
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
+    let args = Args::parse();
+
+    // Completion scripts go to stdout untouched; no logging or startup work
+    if let Some(CliCommand::Completions { shell }) = args.command {
+        write_completions(shell, &mut stdout());
+        return Ok(());
+    }
+
     log_init("Shadow", Some("logs/shadow.log"), OutputTarget::LogFile)?;
 
-    let args = Args::parse();
+    if let Some(level) = args.log_level {
+        set_level(level, None);
+    }
+    accessibility::set_enabled(args.accessible || GLOBAL_CONFIG.tui.accessible);
+    if args.strict || GLOBAL_CONFIG.strict_mode {
+        strict::enable();
+        log_info!("Strict mode on");
+    }
+    set_personas_root(args.personas_dir.clone())?;
+    log_info!("Personas directory: {}", personas_root().display());
+    for note in migrate_state_files(Path::new(".")) {
+        log_info!("{}", note);
+        eprintln!("{}", note);
+    }
+
+    if let Some(CliCommand::MigrateHistory { from, apply, conflict, create_stubs }) = args.command {
+        if apply && strict::is_enabled() {
+            return Err(strict::refusal("migrate-history --apply", ActionEffect::Overwrites).into());
+        }
+        let report = legacy::migrate(&MigrateOptions { from, apply, conflict, create_stubs })?;
+        println!("{}", report.describe());
+        return Ok(());
+    }
+
+    if let Some(CliCommand::VerifyHistory { persona, fix, yes }) = args.command {
+        if fix && strict::is_enabled() {
+            return Err(strict::refusal("verify-history --fix", ActionEffect::Overwrites).into());
+        }
+        return verify_history(persona, fix, yes);
+    }
+
+    let event_sink = GLOBAL_CONFIG.events.enabled
+        .then(|| events::start_file_sink(Path::new(&GLOBAL_CONFIG.events.path)));
+    if let Some(Err(e)) = event_sink {
+        eprintln!("Warning: not writing events to {} ({})", GLOBAL_CONFIG.events.path, e);
+    }
+
+    if GLOBAL_CONFIG.twitter.spellcheck {
+        tokio::task::spawn_blocking(spellcheck::preload);
+    }
 
     if args.is_tui_mode() {
         run_tui_mode().await?;
@@ -70,8 +135,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 enum CurrentMode {
-    Shadow(ShadowApp),
-    Manager(AgentManager),
+    Shadow(Box<ShadowApp>),
+    Manager(Box<AgentManager>),
 }
 
 fn initialize_app(
@@ -80,29 +145,41 @@ fn initialize_app(
 ) -> anyhow::Result<CurrentMode> {
 
     let personas = discover_personas()?;
-    let persona_paths: Vec<&Path> = personas.iter()
-        .map(|(_, path_buf)| path_buf.as_path())
+    let persona_sources: Vec<&PersonaSource> = personas.iter()
+        .map(|(_, source)| source)
         .collect();
 
-    log_info!("Loading personas from paths: {:?}", persona_paths);
+    log_info!("Loading personas from sources: {:?}", persona_sources);
 
     let user_input = if for_cli {
         UserInput::new(Some(Arc::new(CliOutput)))
     } else {
         UserInput::new_for_tui()
-    };
+    }
+    .with_command_history(SharedCommandHistory::load());
 
     if for_cli {
 
         let mut agent_manager = AgentManager::new();
-        agent_manager.load_personas(persona_paths.clone())?;
+        let skipped_personas = agent_manager.load_personas(persona_sources);
+        agent_manager.load_experiments();
         agent_manager.user_input = Some(user_input);
 
         log_info!("Starting Shadow in CLI mode");
         println!("Welcome to Shadow (CLI Mode)");
         println!("Type 'quit' or 'exit' to leave");
-
-        agent_manager.load_personas(persona_paths)?;
+        if let Err(e) = persistence::probe_writable(personas_root()) {
+            eprintln!("Warning: {} is not writable ({}); history will not be saved.", personas_root().display(), e);
+        }
+        for conflict in credential_conflicts() {
+            eprintln!("Warning: {}", conflict);
+        }
+        if let Some(notice) = legacy_history_notice() {
+            eprintln!("Warning: {}", notice);
+        }
+        for skipped in skipped_personas {
+            eprintln!("Warning: {}", skipped);
+        }
     
         if let Some(persona_ref) = agent_manager.personas.get(default_persona) {
             let id = Uuid::new_v4();
@@ -113,16 +190,36 @@ fn initialize_app(
             anyhow::bail!("Persona '{}' not found!", default_persona);
         }
 
-        Ok(CurrentMode::Manager(agent_manager))
+        Ok(CurrentMode::Manager(Box::new(agent_manager)))
     } else {
 
         let mut app = ShadowApp::new();
-        app.load_personas(persona_paths)?;
+        let skipped_personas = app.load_personas(persona_sources);
+        app.agent_manager.load_experiments();
         app.agent_manager.user_input = Some(user_input);
+        if GLOBAL_CONFIG.tui.watch_personas {
+            app.watch_personas(personas_root());
+        }
 
         log_info!("Starting Shadow in TUI mode");
         app.add_message("Welcome to Shadow (TUI Mode)");
         app.add_message("Press ESC to exit");
+        if let Err(e) = persistence::probe_writable(personas_root()) {
+            app.push_global_message(
+                format!("{} is not writable ({}); history will not be saved.", personas_root().display(), e),
+                MessageSource::Global,
+                MessageKind::Warning,
+            );
+        }
+        for conflict in credential_conflicts() {
+            app.push_global_message(conflict, MessageSource::Global, MessageKind::Warning);
+        }
+        if let Some(notice) = legacy_history_notice() {
+            app.push_global_message(notice, MessageSource::Global, MessageKind::Warning);
+        }
+        for skipped in skipped_personas {
+            app.push_global_message(skipped, MessageSource::Global, MessageKind::Warning);
+        }
     
         if let Some(persona_ref) = app.agent_manager.personas.get(default_persona) {
             let id = Uuid::new_v4();
@@ -132,11 +229,75 @@ fn initialize_app(
         } else {
             anyhow::bail!("Persona '{}' not found!", default_persona);
         }
+        app.resume_focus();
+        app.start_tour_if_new();
 
-        Ok(CurrentMode::Shadow(app))
+        Ok(CurrentMode::Shadow(Box::new(app)))
     }
 }
 
+/// Points at `migrate-history` when the old flat `history/` directory still has files
+fn legacy_history_notice() -> Option<String> {
+    let count = legacy::legacy_files(Path::new(LEGACY_HISTORY_DIR)).len();
+    (count > 0).then(|| format!(
+        "{} history file(s) in {}/ use the old layout and aren't loaded; run `grokprime-brain migrate-history` to see how they'd be moved.",
+        count, LEGACY_HISTORY_DIR
+    ))
+}
+
+/// # verify_history
+///
+/// **Purpose:**
+/// Runs `verify-history`: prints each persona's report and, with `--fix`, repairs after asking.
+///
+/// **Errors / Failures:**
+/// - A history doesn't load, or a check still fails afterwards (so scheduled runs exit non-zero)
+/// - A repair can't be written
+fn verify_history(persona: Option<String>, fix: bool, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let personas = match persona {
+        Some(name) if !HistoryManager::history_exists(&name) => return Err(format!("No saved history for {}", name).into()),
+        Some(name) => vec![name],
+        None => HistoryManager::saved_histories(),
+    };
+
+    let mut failed = Vec::new();
+    for persona in personas {
+        let report = match HistoryManager::verify_history(&persona) {
+            Ok(report) => report,
+            Err(e) => {
+                println!("{}: [fail] history doesn't load: {}", persona, e);
+                failed.push(persona);
+                continue;
+            }
+        };
+        println!("{}", report.describe().join("\n"));
+
+        let mut status = report.status();
+        if fix && !report.repairs.is_empty() && (yes || confirm("Apply these repairs?")?) {
+            let changes = persistence::explicit_write(|| HistoryManager::repair_history(&persona))?;
+            println!("Repaired {}: {}", persona, changes.join("; "));
+            status = HistoryManager::verify_history(&persona)?.status();
+        }
+        if status == CheckStatus::Fail {
+            failed.push(persona);
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("history verification failed for {}", failed.join(", ")).into())
+    }
+}
+
+/// Asks a yes/no question on stdin; anything but "y" or "yes" is no
+fn confirm(question: &str) -> std::io::Result<bool> {
+    print!("{} [y/N] ", question);
+    stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
 
 /// # run_tui_mode
 ///
@@ -165,33 +326,107 @@ async fn run_tui_mode() -> Result<(), Box<dyn std::error::Error>> {
 
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
+    let bracketed_paste = stdout().execute(EnableBracketedPaste).is_ok();
+    if GLOBAL_CONFIG.tui.mouse {
+        stdout().execute(EnableMouseCapture)?;
+    }
 
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
     let CurrentMode::Shadow(mut app) = initialize_app("shadow", false)? else {
         panic!("Expected Shadow variant in TUI mode.");
     };
+    app.paste_fallback = !bracketed_paste;
+    let mut terminal_status = TerminalStatus::from_config();
+    if let Some(status) = &terminal_status {
+        status.start(terminal.backend_mut())?;
+    }
+    let mut hyperlink_writer = hyperlinks::is_enabled().then(HyperlinkWriter::default);
 
     loop {
         app.poll_channels();
+        if app.advance_macro() {
+            break;
+        }
+        let frame_started = Instant::now();
         terminal.draw(|f| app.draw(f))?;
+        profile::record_frame(frame_started.elapsed());
+
+        // Between frames, so the escapes never land inside ratatui's output
+        if let Some(writer) = hyperlink_writer.as_mut() {
+            writer.emit(terminal.backend_mut(), &app.links)?;
+        }
+        if let Some(status) = terminal_status.as_mut()
+            && let Some(activity) = status.next_update(Activity::of_agents(app.agent_manager.agents.values()), Instant::now())
+        {
+            status.emit(terminal.backend_mut(), activity)?;
+        }
 
         if event::poll(Duration::from_millis(10))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    let should_continue = app.handle_key(key);
-                    if !should_continue {
-                        break;
+            // Drain everything that arrives in quick succession so pastes are handled in one go
+            let mut keys = Vec::new();
+            let mut should_continue = true;
+            loop {
+                match event::read()? {
+                    Event::Paste(text) => {
+                        should_continue = app.handle_key_burst(std::mem::take(&mut keys));
+                        // The terminal has bracketed paste after all
+                        app.paste_fallback = false;
+                        app.insert_text(&text);
                     }
+                    Event::Key(key) if key.kind == KeyEventKind::Press => keys.push(key),
+                    Event::Mouse(mouse) => {
+                        should_continue = app.handle_key_burst(std::mem::take(&mut keys)) && app.handle_mouse(mouse);
+                    }
+                    _ => {}
+                }
+
+                if !should_continue || !event::poll(KEY_BURST_WINDOW)? {
+                    break;
                 }
             }
+
+            if !should_continue || !app.handle_key_burst(keys) {
+                break;
+            }
         }
     }
     
+    app.remember_split_ratio();
+    if let Some(status) = &terminal_status {
+        status.finish(terminal.backend_mut())?;
+    }
+    if GLOBAL_CONFIG.tui.mouse {
+        stdout().execute(DisableMouseCapture)?;
+    }
+    if bracketed_paste {
+        stdout().execute(DisableBracketedPaste)?;
+    }
     disable_raw_mode()?;
     stdout().execute(LeaveAlternateScreen)?;
     Ok(())
 }
 
+/// # confirm_recalled
+///
+/// **Purpose:**
+/// Asks before running a recalled `!!`-style command in CLI mode.
+///
+/// **Returns:**
+/// `io::Result<Option<String>>` - The recalled line on Enter, a typed replacement
+/// instead, or None when cancelled with "n"
+fn confirm_recalled(user_input: &mut UserInput, line: String, confirmed: bool) -> std::io::Result<Option<String>> {
+    if confirmed {
+        println!("{} {}", accessibility::glyph("↻", "Repeated:"), line);
+        return Ok(Some(line));
+    }
+    println!("Recalled: {} (Enter runs it, n cancels, or type an edited line to run instead)", line);
+    Ok(match user_input.read_user_input()? {
+        None => Some(line),
+        Some(answer) if answer.eq_ignore_ascii_case("n") => None,
+        Some(edited) => Some(edited),
+    })
+}
+
 /// # run_cli_mode
 ///
 /// **Purpose:**
@@ -227,19 +462,33 @@ async fn run_cli_mode(persona: &str) -> Result<(), Box<dyn std::error::Error>> {
 
         match user_input.read_user_input()? {
             Some(raw_input) => {
-                match user_input.process_input(&raw_input) {
+                let (line, action) = match user_input.process_input(&raw_input) {
+                    InputAction::RecallCommand { line, confirmed } => match confirm_recalled(user_input, line, confirmed)? {
+                        Some(line) => {
+                            let action = user_input.process_input(&line);
+                            (line, action)
+                        }
+                        None => continue,
+                    },
+                    action => (raw_input, action),
+                };
+                match action {
                     InputAction::DoNothing => {},
+                    InputAction::RecallCommand { line, .. } => {
+                        println!("Recalled: {} (type it to run it)", line);
+                    }
                     InputAction::ContinueNoSend(msg) => {
                         println!("{}", msg);
                     }
 
                     InputAction::SendAsMessage(content) => {
                         if let Some(agent) = app.current_pane_mut() {
-                            agent.add_message(format!("> {}", content));
+                            let sent = Pipeline::chat().run(&content);
                             {
                                 let mut connection = agent.connection.lock().await;
-                                connection.add_user_message(&content);
+                                connection.add_user_message(&sent.send);
                             }
+                            agent.echo_sent(sent);
                             
                             let msg_count_before = agent.messages.len();
 
@@ -261,8 +510,8 @@ async fn run_cli_mode(persona: &str) -> Result<(), Box<dyn std::error::Error>> {
                                 if let Some(agent) = app.current_pane() {
                                     if agent.messages.len() > msg_count_before {
                                         if let Some(last_msg) = agent.messages.back() {
-                                            if !last_msg.starts_with('>') {
-                                                print!("\r{}", last_msg);
+                                            if !last_msg.text.starts_with('>') {
+                                                print!("\r{}", last_msg.text);
                                                 std::io::stdout().flush().unwrap();
                                             }
                                         }
@@ -281,8 +530,14 @@ async fn run_cli_mode(persona: &str) -> Result<(), Box<dyn std::error::Error>> {
 
 
                     action => {
+                        user_input.record_command(&line, &action);
+                        let name: &'static str = (&action).into();
+                        let started = Instant::now();
+                        let jobs_before = app.jobs.len();
+
                         let command = from_input_action(action);
-                        let result = command.execute(&mut app as &mut dyn AgentOperations);
+                        let result = command.execute(&mut *app as &mut dyn AgentOperations);
+                        profile::record_command(name, started, app.jobs.len() > jobs_before);
 
                         match result {
                             CommandResult::Continue => {},
@@ -302,7 +557,7 @@ async fn run_cli_mode(persona: &str) -> Result<(), Box<dyn std::error::Error>> {
     }
     
     if let Some(agent) = app.current_pane_mut() {
-        let _ = agent.connection.lock().await.save_persona_history();
+        let _ = agent.connection.lock().await.autosave_persona_history();
     }
 
     Ok(())
@@ -23,6 +23,15 @@
 //! ---------------------------------------------------------------
 
 use serde::{Serialize, Deserialize};
+use strum::IntoStaticStr;
+use crate::persona::experiment::{ExperimentTag, Rating};
+use crate::utilities::log_level::LogLevel;
+use crate::tui::layout::LayoutMode;
+use crate::agent_history::schema::CURRENT_HISTORY_VERSION;
+use crate::agent_history::freshness::ResyncChoice;
+use crate::agent_history::checkpoint::CheckpointReason;
+use crate::persona::tools::Tool;
+pub use crate::llm::request::ChatRequest;
 
 // Response handling
 /// # Message
@@ -33,47 +42,85 @@ use serde::{Serialize, Deserialize};
 /// **Fields:**
 /// - `role`: The role of the message sender ("user", "assistant", "system")
 /// - `content`: The actual text content of the message
+/// - `meta`: Which model produced an assistant reply (never sent to the API)
+/// - `source`: What added a message other than the user or model, e.g. `"nudge"` (never sent to the API)
 ///
 /// **Usage Example:**
 /// ```rust
 /// let msg = Message {
 ///     role: "user".to_string(),
 ///     content: "Hello Shadow!".to_string(),
+///     meta: None,
+///     source: None,
 /// };
 /// ```
 #[derive(Serialize, Debug, Deserialize, Clone)]
 pub struct Message {
     pub role: String,
     pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ResponseMeta>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
 }
-/// # ChatRequest
+
+/// # ResponseMeta
 ///
 /// **Summary:**
-/// Request payload for the Grok API chat endpoint with conversation context.
+/// Identity of the model that wrote an assistant message, as reported by the API.
 ///
 /// **Fields:**
-/// - `model`: The Grok model to use (e.g., "grok-4-fast")
-/// - `input`: Vector of messages forming the conversation history
-/// - `temperature`: Sampling temperature for response randomness (0.0-1.0)
-/// - `previous_response_id`: Optional ID for conversation continuity
+/// - `model`: Model id reported by the provider
+/// - `temperature`: Effective temperature used for the request
+/// - `latency_ms`: Time from sending the request to the last chunk
+/// - `input_tokens`: Prompt tokens, if the provider reported usage
+/// - `output_tokens`: Completion tokens, if the provider reported usage
+/// - `stripped`: Signature/boilerplate lines removed from the reply by `strip_patterns`
 ///
 /// **Usage Example:**
 /// ```rust
-/// let request = ChatRequest {
-///     model: "grok-4-fast".to_string(),
-///     input: vec![msg],
-///     temperature: 0.7,
-///     previous_response_id: None,
-/// };
+/// # use grokprime_brain::models::Message;
+/// # let message = Message { role: "assistant".to_string(), content: "Hi".to_string(), meta: None, source: None };
+/// if let Some(meta) = &message.meta {
+///     println!("{}", meta.describe());
+/// }
 /// ```
-#[derive(Serialize, Debug, Clone)]
-pub struct ChatRequest {
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ResponseMeta {
     pub model: String,
-    pub input: Vec<Message>,
     pub temperature: f32,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub previous_response_id: Option<String>,
-    pub stream: bool,
+    #[serde(default)]
+    pub latency_ms: Option<u64>,
+    #[serde(default)]
+    pub input_tokens: Option<u32>,
+    #[serde(default)]
+    pub output_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stripped: Vec<String>,
+}
+
+impl ResponseMeta {
+    /// Multi-field summary for the `whence` command
+    pub fn describe(&self) -> String {
+        let tokens = |count: Option<u32>| count.map(|c| c.to_string()).unwrap_or_else(|| "?".to_string());
+        format!(
+            "model: {}, temperature: {:.2}, latency: {}, tokens: {} in / {} out",
+            self.model,
+            self.temperature,
+            self.latency_ms.map(|ms| format!("{} ms", ms)).unwrap_or_else(|| "?".to_string()),
+            tokens(self.input_tokens),
+            tokens(self.output_tokens),
+        ) + &self.describe_stripped()
+    }
+
+    /// "; stripped: "-Shadow", "I hope this helps!"" when lines were stripped, else empty
+    fn describe_stripped(&self) -> String {
+        if self.stripped.is_empty() {
+            return String::new();
+        }
+        let lines: Vec<String> = self.stripped.iter().map(|line| format!("\"{}\"", line)).collect();
+        format!("; stripped: {}", lines.join(", "))
+    }
 }
 #[derive(Debug, Deserialize)]
 pub struct DeltaChunk {
@@ -99,13 +146,20 @@ pub struct CompletedChunk {
 ///
 /// **Variants:**
 /// - `Delta(String)`: Incremental text chunk from SSE stream
-/// - `Complete(String)`: Final complete response text
+/// - `Complete { response_id, full_reply, usage }`: Final reply text, with the tokens it used when the provider reported them
 /// - `Error(String)`: Error message from streaming failure
+/// - `Info(String)`: Informational notice for the Global pane
+/// - `Notice(String)`: Quiet notice for the agent's own pane (e.g. a summarize reminder)
+/// - `JobStarted` / `JobProgress` / `JobCompleted` / `JobFailed`: Lifecycle of a background generation job
+///
+/// **Details:**
+/// Chunks are sent through a `ChunkStream`, which keeps them in a valid
+/// order and tags them for the agent's channel.
 ///
 /// **Usage Example:**
 /// ```rust
-/// tx.send(StreamChunk::Delta("Hello".to_string()))?;
-/// tx.send(StreamChunk::Complete("Full response".to_string()))?;
+/// stream.delta("Hello")?;
+/// stream.complete(response_id, "Hello".to_string(), None)?;
 /// ```
 #[derive(Debug, Clone)]
 pub enum StreamChunk {
@@ -113,9 +167,30 @@ pub enum StreamChunk {
     Complete{
         response_id: String,
         full_reply: String,
+        usage: Option<Usage>,
     },
     Error(String),
     Info(String),
+    Notice(String),
+    JobStarted {
+        job: u32,
+        name: String,
+    },
+    JobProgress {
+        job: u32,
+        name: String,
+        msg: String,
+    },
+    JobCompleted {
+        job: u32,
+        name: String,
+        msg: String,
+    },
+    JobFailed {
+        job: u32,
+        name: String,
+        error: String,
+    },
 }
 
 /// # ResponsesApiResponse
@@ -128,7 +203,7 @@ pub enum StreamChunk {
 /// - `object`: Object type returned by the API
 /// - `created_at`: Unix timestamp of response creation
 /// - `model`: The model that generated the response
-/// - `output`: Vector of output messages from the assistant
+/// - `output`: Output items in order (messages, and e.g. reasoning or tool calls)
 /// - `usage`: Optional token usage statistics
 ///
 /// **Usage Example:**
@@ -142,35 +217,82 @@ pub struct ResponsesApiResponse{
     #[serde(rename = "created_at")]
     pub created_at: u64,
     pub model: String,
+    #[serde(default)]
     pub output: Vec<OutputMessage>,
     #[serde(default)]
     pub usage: Option<Usage>,
 }
+
+impl ResponsesApiResponse {
+    /// # output_text
+    ///
+    /// **Purpose:**
+    /// The reply text: every `output_text` block of every message item, in order.
+    pub fn output_text(&self) -> String {
+        self.output.iter()
+            .filter(|item| item.type_ == "message")
+            .flat_map(|item| item.content.iter())
+            .filter(|block| block.type_ == "output_text")
+            .map(|block| block.text.as_str())
+            .collect()
+    }
+
+    /// # skipped_types
+    ///
+    /// **Purpose:**
+    /// Types of the items and blocks `output_text` leaves out, in order of appearance.
+    ///
+    /// **Returns:**
+    /// `Vec<String>` - Item types (`reasoning`, `function_call`), or `message/<block type>`
+    /// for non-text blocks inside a message
+    pub fn skipped_types(&self) -> Vec<String> {
+        self.output.iter()
+            .flat_map(|item| {
+                if item.type_ == "message" {
+                    item.content.iter()
+                        .filter(|block| block.type_ != "output_text")
+                        .map(|block| format!("message/{}", block.type_))
+                        .collect()
+                } else {
+                    vec![item.type_.clone()]
+                }
+            })
+            .collect()
+    }
+}
 /// # OutputMessage
 ///
 /// **Summary:**
-/// Individual message within the API response output array.
+/// Individual item within the API response output array.
 ///
 /// **Fields:**
-/// - `id`: Unique identifier for this output message
-/// - `role`: Role of the message sender (typically "assistant")
-/// - `type_`: Type of the message
-/// - `status`: Processing status of the message
-/// - `content`: Vector of content blocks containing the actual response
+/// - `id`: Unique identifier for this output item
+/// - `role`: Role of the message sender ("assistant"; empty for non-message items)
+/// - `type_`: Type of the item (`message`, `reasoning`, `function_call`, ...)
+/// - `status`: Processing status of the item
+/// - `content`: Content blocks of a message (empty for other item types)
+///
+/// **Details:**
+/// Only `type_` is required, so reasoning and tool-call items (which have no
+/// role or content) don't make the whole response fail to parse.
 ///
 /// **Usage Example:**
 /// ```rust
-/// if let Some(first_msg) = response.output.first() {
-///     println!("Status: {}", first_msg.status);
+/// for item in response.output.iter().filter(|item| item.type_ == "message") {
+///     println!("Status: {}", item.status);
 /// }
 /// ```
 #[derive(Deserialize, Debug)]
 pub struct OutputMessage {
+    #[serde(default)]
     pub id: String,
+    #[serde(default)]
     pub role: String,
     #[serde(rename= "type")]
     pub type_: String,
+    #[serde(default)]
     pub status: String,
+    #[serde(default)]
     pub content: Vec<ContentBlock>,
 }
 /// # ContentBlock
@@ -180,7 +302,7 @@ pub struct OutputMessage {
 ///
 /// **Fields:**
 /// - `type_`: Type of content (typically "output_text")
-/// - `text`: The actual text content from the assistant
+/// - `text`: The actual text content from the assistant (empty for blocks without text, e.g. `refusal`)
 ///
 /// **Usage Example:**
 /// ```rust
@@ -194,6 +316,7 @@ pub struct OutputMessage {
 pub struct ContentBlock {
     #[serde(rename = "type")]
     pub type_: String,
+    #[serde(default)]
     pub text: String
 }
 /// # Usage
@@ -212,13 +335,21 @@ pub struct ContentBlock {
 ///     println!("Tokens used: {}", usage.total_tokens);
 /// }
 /// ```
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Usage {
     pub input_tokens: u32,
     pub output_tokens: u32,
     pub total_tokens: u32,
 }
 
+impl Usage {
+    /// Usage from separately reported counts; None unless both were reported
+    pub fn from_counts(input_tokens: Option<u32>, output_tokens: Option<u32>) -> Option<Usage> {
+        let (input_tokens, output_tokens) = (input_tokens?, output_tokens?);
+        Some(Usage { input_tokens, output_tokens, total_tokens: input_tokens + output_tokens })
+    }
+}
+
 
 
 // Error response from the API
@@ -260,6 +391,43 @@ pub struct ApiErrorDetail {
 pub struct ApiErrorResponse {
     pub error: ApiErrorDetail,
 }
+/// # ActionEffect
+///
+/// **Summary:**
+/// What an `InputAction` does beyond showing or preparing something.
+///
+/// **Variants:**
+/// - `None`: Reads, previews, drafts, dry runs, or session settings
+/// - `Ends`: Stops something in this session (quit, close an agent, cancel a job)
+/// - `Overwrites`: Writes files (exports) that can replace existing ones
+/// - `Deletes`: Removes saved messages, history, or macros
+/// - `Publishes`: Acts outside the app where others see it (posting a tweet)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionEffect {
+    None,
+    Ends,
+    Overwrites,
+    Deletes,
+    Publishes,
+}
+
+impl ActionEffect {
+    /// Whether strict mode refuses actions with this effect
+    pub fn refused_in_strict(&self) -> bool {
+        matches!(self, ActionEffect::Overwrites | ActionEffect::Deletes | ActionEffect::Publishes)
+    }
+
+    pub fn describe(&self) -> &'static str {
+        match self {
+            ActionEffect::None => "has no lasting effect",
+            ActionEffect::Ends => "ends something in this session",
+            ActionEffect::Overwrites => "writes files that can replace existing ones",
+            ActionEffect::Deletes => "deletes saved data",
+            ActionEffect::Publishes => "publishes outside the app",
+        }
+    }
+}
+
 /// # InputAction
 ///
 /// **Summary:**
@@ -269,18 +437,81 @@ pub struct ApiErrorResponse {
 /// - `Quit`: Exit the application
 /// - `DoNothing`: No action needed (e.g., invalid input handled)
 /// - `ContinueNoSend(String)`: Display a message without sending to API
+/// - `RecallCommand { line, confirmed }`: A `!!`-style expansion resolved to an earlier command line;
+///   run it when `confirmed`, otherwise offer it for confirming or editing first
 /// - `SendAsMessage(String)`: Send the message to the Grok API
+/// - `Attach { path, note }`: Send a file for review, with an optional note
 /// - `ClearHistory`: Clear conversation history for current agent
 /// - `HistoryInfo`: Display history information for current agent
 /// - `SaveHistory`: Save conversation history to disk
 /// - `Summarize`: Trigger history summarization for current agent
-/// - `PostTweet(String)`: Post content to Twitter
+/// - `DedupeHistory { apply }`: List (or with `apply`, remove) back-to-back duplicate messages
+/// - `HistoryList`: List recent messages of the current agent with their indices
+/// - `HistoryRemove { index, pair, apply }`: Remove one message (or an exchange) from context; dry run unless `apply`
+/// - `VerifyHistory { persona, fix, apply }`: Audit saved histories against their archives; with `fix`, list repairs (applied with `apply`)
+/// - `ListArchives(Option<String>)`: List the current agent's archives (optionally since a time)
+/// - `ExportArchive { archive, dest }`: Stream an archive to a Markdown transcript
+/// - `PostTweet(String)`: Preview a tweet and hold it for confirmation
+/// - `ConfirmTweet`: Post the previewed tweet (or thread)
+/// - `CancelTweet`: Discard the previewed tweet
 /// - `DraftTweet(String)`: Generate a tweet draft via AI
+/// - `QueueTweet(String)`: Defer a tweet to the outbox until quota is available
+/// - `TweetQuota`: Show remaining post quota and queued posts
+/// - `CheckTweet(String)`: Show spelling/punctuation hints for a tweet without posting it
+/// - `IgnoreWord(String)`: Add a word to the spellcheck's custom dictionary
 /// - `NewAgent(String)`: Create a new agent with specified persona
 /// - `AgentStatus`: Display current agent status and list all agents
+/// - `Usage`: Show each agent's token use this session and an estimated cost
 /// - `CloseAgent`: Close the current agent
 /// - `ListAgents`: Display all active agents
-#[derive(Debug)]
+/// - `Help`: Show the getting-started command list
+/// - `Inspect { diff }`: Show the live system prompt, summary, and counts (or diff the prompt against the persona file)
+/// - `MuteAgent(bool)`: Mute (true) or unmute (false) the current agent
+/// - `Speak(bool)`: Speak the current agent's finished replies aloud (true) or stop (false)
+/// - `OpenLink(Option<usize>)`: Open the nth most recent link in the current pane (None lists them)
+/// - `ExportBuiltinPersonas { dir, inline }`: Write embedded personas to `dir` (prompt inline in the YAML, or in `prompt.md`)
+/// - `PersonaHistory(String)`: List recorded prompt/setting changes for a persona
+/// - `Overview { sort, json }`: Table of every persona's history on disk, sorted by a column name (or JSON)
+/// - `Insights { month, export }`: Local usage report for a month (YYYY-MM, current when None), or write it as Markdown
+/// - `GlobalFilter(Option<String>)`: Filter the Global pane (None clears the filter)
+/// - `GlobalClear`: Empty the Global pane
+/// - `SetLayout(LayoutMode)`: Switch between split, single-pane, stacked, and automatic layout
+/// - `Timestamps(Option<bool>)`: Show (true) or hide (false) agent pane message times; None toggles
+/// - `ReloadKeys`: Re-read API keys and apply them to all open connections
+/// - `ReloadPersonas { apply }`: Re-scan the personas directory; with `apply`, update open agents' system prompts
+/// - `Whence(usize)`: Show which model wrote the nth-last assistant reply (1 = last)
+/// - `Sent(usize)`: Show exactly what was sent for the nth-last message (1 = last)
+/// - `SetLogLevel { level, target }`: Change log verbosity globally or for one module
+/// - `ExperimentStart { name, variant_path }`: Start an A/B prompt experiment
+/// - `ExperimentStatus`: Show assignment and rating counts for active experiments
+/// - `ExperimentStop(Option<String>)`: End an experiment and print its tally
+/// - `RateConversation(Rating)`: Rate the current agent's experiment conversation
+/// - `ListJobs`: List running generation jobs
+/// - `CancelJob(u32)`: Abort a running generation job by number
+/// - `Stop`: Cancel the current agent's in-flight reply (Ctrl+C)
+/// - `MacroRecord { name, trusted }`: Start capturing submitted lines into a macro
+/// - `MacroStop { trusted }`: Stop recording (and save) or stop a running replay
+/// - `MacroRun { name, confirmed }`: Replay a saved macro
+/// - `MacroList`: List saved macros
+/// - `MacroDelete(String)`: Delete a saved macro
+/// - `Profile { frames }`: Show command timings (or draw-loop frame times)
+/// - `FocusStart { minutes, goal }`: Start a focus block that holds notifications and ends with a check-in
+/// - `FocusCancel`: End the running focus block early
+/// - `FocusStatus`: Show the running focus block and time left
+/// - `Tour`: Start the guided TUI tour from the first step
+/// - `Language(Option<String>)`: Show the current agent's conversation language, or set it (`auto` clears)
+/// - `Model(Option<String>)`: Show the current agent's model, or switch it (`default` clears)
+/// - `SetSummarize(Option<String>)`: Show the current agent's summarize mode, or override it (`default` clears)
+/// - `NudgeAdd(String)`: Steer the current agent with a system-role instruction from the next request on
+/// - `NudgeList`: Show the current agent's active nudges
+/// - `NudgeClear`: Stop sending the current agent's nudges (history keeps them)
+/// - `Resync(ResyncChoice)`: After a long break, ask for a recap or archive and start fresh
+/// - `Explain`: Ask about the error the current agent's last request ended in
+/// - `Rollback { index, apply }`: List the current agent's checkpoints, preview one, or (with `apply`) restore it
+///
+/// **Details:**
+/// The variant name (via `IntoStaticStr`) labels the action in `profile` output.
+#[derive(Debug, IntoStaticStr)]
 pub enum InputAction {
     Quit,
     DoNothing,
@@ -288,22 +519,505 @@ pub enum InputAction {
     // Commands that result in a message to be displayed but not sent
     ContinueNoSend(String),
 
+    // Command history expansion, handled by the input loop before the Command Pattern
+    RecallCommand { line: String, confirmed: bool },
+
     // Send message to Grok API
     SendAsMessage(String),
+    Attach { path: String, note: String },
     ClearHistory,
     HistoryInfo,
     SaveHistory,
     Summarize,
+    DedupeHistory { apply: bool },
+    HistoryList,
+    HistoryRemove {
+        index: usize,
+        pair: bool,
+        apply: bool,
+    },
+    VerifyHistory {
+        persona: Option<String>,
+        fix: bool,
+        apply: bool,
+    },
+    ListArchives(Option<String>),
+    ExportArchive { archive: String, dest: String },
 
     // Twitter-related actions
     PostTweet(String),
+    ConfirmTweet,
+    CancelTweet,
     DraftTweet(String),
+    QueueTweet(String),
+    TweetQuota,
+    CheckTweet(String),
+    IgnoreWord(String),
 
     // Agent management actions
     NewAgent(String),
     AgentStatus,
+    Usage,
     CloseAgent,
     ListAgents,
+    Help,
+    Inspect { diff: bool },
+    MuteAgent(bool),
+    Speak(bool),
+    OpenLink(Option<usize>),
+
+    // Persona management actions
+    ExportBuiltinPersonas { dir: String, inline: bool },
+    PersonaHistory(String),
+    Overview { sort: Option<String>, json: bool },
+    Insights { month: Option<String>, export: Option<String> },
+
+    // Global pane actions
+    GlobalFilter(Option<String>),
+    GlobalClear,
+    SetLayout(LayoutMode),
+    Timestamps(Option<bool>),
+
+    // Runtime maintenance actions
+    ReloadKeys,
+    ReloadPersonas {
+        apply: bool,
+    },
+    Whence(usize),
+    Sent(usize),
+    SetLogLevel {
+        level: LogLevel,
+        target: Option<String>,
+    },
+
+    // Prompt experiment actions
+    ExperimentStart {
+        name: String,
+        variant_path: String,
+    },
+    ExperimentStatus,
+    ExperimentStop(Option<String>),
+    RateConversation(Rating),
+
+    // Generation job actions
+    ListJobs,
+    CancelJob(u32),
+    Stop,
+
+    // Macro actions
+    MacroRecord {
+        name: String,
+        trusted: bool,
+    },
+    MacroStop {
+        trusted: bool,
+    },
+    MacroRun {
+        name: String,
+        confirmed: bool,
+    },
+    MacroList,
+    MacroDelete(String),
+
+    // Diagnostics
+    Profile {
+        frames: bool,
+    },
+
+    // Focus blocks
+    FocusStart {
+        minutes: u32,
+        goal: Option<String>,
+    },
+    FocusCancel,
+    FocusStatus,
+
+    // Onboarding
+    Tour,
+
+    // Conversation language
+    Language(Option<String>),
+
+    // Model selection
+    Model(Option<String>),
+
+    // Summarize mode
+    SetSummarize(Option<String>),
+
+    // System-role steering
+    NudgeAdd(String),
+    NudgeList,
+    NudgeClear,
+    Resync(ResyncChoice),
+    Explain,
+
+    // Checkpoints
+    Rollback {
+        index: Option<usize>,
+        apply: bool,
+    },
+}
+
+impl InputAction {
+    /// # is_repeatable
+    ///
+    /// **Purpose:**
+    /// Whether Enter on empty input may re-run this action.
+    ///
+    /// **Details:**
+    /// Chat messages and anything destructive, outward-facing, or creating state
+    /// (clearing history, closing agents, tweeting, starting/stopping experiments)
+    /// must never auto-repeat.
+    pub fn is_repeatable(&self) -> bool {
+        match self {
+            InputAction::HistoryInfo
+            | InputAction::SaveHistory
+            | InputAction::Summarize
+            | InputAction::ListArchives(_)
+            | InputAction::DedupeHistory { apply: false }
+            | InputAction::HistoryList
+            | InputAction::HistoryRemove { apply: false, .. }
+            | InputAction::VerifyHistory { fix: false, .. }
+            | InputAction::AgentStatus
+            | InputAction::Usage
+            | InputAction::ListAgents
+            | InputAction::Help
+            | InputAction::Inspect { .. }
+            | InputAction::MuteAgent(_)
+            | InputAction::Speak(_)
+            | InputAction::OpenLink(None)
+            | InputAction::PersonaHistory(_)
+            | InputAction::Overview { .. }
+            | InputAction::Insights { export: None, .. }
+            | InputAction::GlobalFilter(_)
+            | InputAction::SetLayout(_)
+            | InputAction::Timestamps(Some(_))
+            | InputAction::ReloadKeys
+            | InputAction::ReloadPersonas { apply: false }
+            | InputAction::Whence(_)
+            | InputAction::Sent(_)
+            | InputAction::SetLogLevel { .. }
+            | InputAction::TweetQuota
+            | InputAction::CheckTweet(_)
+            | InputAction::ExperimentStatus
+            | InputAction::ListJobs
+            | InputAction::MacroList
+            | InputAction::Profile { .. }
+            | InputAction::FocusStatus
+            | InputAction::Language(None)
+            | InputAction::Model(None)
+            | InputAction::SetSummarize(None)
+            | InputAction::NudgeList
+            | InputAction::Rollback { apply: false, .. } => true,
+
+            InputAction::Quit
+            | InputAction::DoNothing
+            | InputAction::ContinueNoSend(_)
+            | InputAction::RecallCommand { .. }
+            | InputAction::SendAsMessage(_)
+            | InputAction::Attach { .. }
+            | InputAction::ClearHistory
+            | InputAction::ExportArchive { .. }
+            | InputAction::DedupeHistory { apply: true }
+            | InputAction::HistoryRemove { apply: true, .. }
+            | InputAction::VerifyHistory { fix: true, .. }
+            | InputAction::PostTweet(_)
+            | InputAction::ConfirmTweet
+            | InputAction::CancelTweet
+            | InputAction::DraftTweet(_)
+            | InputAction::QueueTweet(_)
+            | InputAction::IgnoreWord(_)
+            | InputAction::NewAgent(_)
+            | InputAction::CloseAgent
+            | InputAction::ExportBuiltinPersonas { .. }
+            | InputAction::Insights { export: Some(_), .. }
+            | InputAction::GlobalClear
+            | InputAction::OpenLink(Some(_))
+            | InputAction::Timestamps(None)
+            | InputAction::ReloadPersonas { apply: true }
+            | InputAction::ExperimentStart { .. }
+            | InputAction::ExperimentStop(_)
+            | InputAction::RateConversation(_)
+            | InputAction::CancelJob(_)
+            | InputAction::Stop
+            | InputAction::MacroRecord { .. }
+            | InputAction::MacroStop { .. }
+            | InputAction::MacroRun { .. }
+            | InputAction::MacroDelete(_)
+            | InputAction::FocusStart { .. }
+            | InputAction::FocusCancel
+            | InputAction::Tour
+            | InputAction::Language(Some(_))
+            | InputAction::Model(Some(_))
+            | InputAction::SetSummarize(Some(_))
+            | InputAction::NudgeAdd(_)
+            | InputAction::NudgeClear
+            | InputAction::Resync(_)
+            | InputAction::Explain
+            | InputAction::Rollback { apply: true, .. } => false,
+        }
+    }
+
+    /// # effect
+    ///
+    /// **Purpose:**
+    /// What running this action does beyond the session, for every gate that cares.
+    ///
+    /// **Details:**
+    /// Macro replay (`is_destructive`) and strict mode both read this, so an
+    /// action can't be treated as harmless by one and dangerous by the other.
+    /// Previews, drafts, dry runs (`apply: false`), and queueing are `None`.
+    pub fn effect(&self) -> ActionEffect {
+        match self {
+            InputAction::ConfirmTweet => ActionEffect::Publishes,
+
+            InputAction::ClearHistory
+            | InputAction::DedupeHistory { apply: true }
+            | InputAction::HistoryRemove { apply: true, .. }
+            | InputAction::MacroDelete(_)
+            | InputAction::Resync(ResyncChoice::Fresh)
+            | InputAction::Rollback { apply: true, .. } => ActionEffect::Deletes,
+
+            InputAction::ExportArchive { .. }
+            | InputAction::VerifyHistory { apply: true, .. }
+            | InputAction::ExportBuiltinPersonas { .. }
+            | InputAction::Insights { export: Some(_), .. }
+            | InputAction::ReloadPersonas { apply: true } => ActionEffect::Overwrites,
+
+            InputAction::Quit
+            | InputAction::CloseAgent
+            | InputAction::GlobalClear
+            | InputAction::ExperimentStop(_)
+            | InputAction::CancelJob(_)
+            | InputAction::Stop => ActionEffect::Ends,
+
+            InputAction::DoNothing
+            | InputAction::ContinueNoSend(_)
+            | InputAction::RecallCommand { .. }
+            | InputAction::SendAsMessage(_)
+            | InputAction::Attach { .. }
+            | InputAction::HistoryInfo
+            | InputAction::SaveHistory
+            | InputAction::Summarize
+            | InputAction::DedupeHistory { apply: false }
+            | InputAction::HistoryList
+            | InputAction::HistoryRemove { apply: false, .. }
+            | InputAction::VerifyHistory { apply: false, .. }
+            | InputAction::ListArchives(_)
+            | InputAction::PostTweet(_)
+            | InputAction::CancelTweet
+            | InputAction::DraftTweet(_)
+            | InputAction::QueueTweet(_)
+            | InputAction::TweetQuota
+            | InputAction::CheckTweet(_)
+            | InputAction::IgnoreWord(_)
+            | InputAction::NewAgent(_)
+            | InputAction::AgentStatus
+            | InputAction::Usage
+            | InputAction::ListAgents
+            | InputAction::Help
+            | InputAction::Inspect { .. }
+            | InputAction::MuteAgent(_)
+            | InputAction::Speak(_)
+            | InputAction::OpenLink(_)
+            | InputAction::PersonaHistory(_)
+            | InputAction::Overview { .. }
+            | InputAction::Insights { export: None, .. }
+            | InputAction::GlobalFilter(_)
+            | InputAction::SetLayout(_)
+            | InputAction::Timestamps(_)
+            | InputAction::ReloadKeys
+            | InputAction::ReloadPersonas { apply: false }
+            | InputAction::Whence(_)
+            | InputAction::Sent(_)
+            | InputAction::SetLogLevel { .. }
+            | InputAction::ExperimentStart { .. }
+            | InputAction::ExperimentStatus
+            | InputAction::RateConversation(_)
+            | InputAction::ListJobs
+            | InputAction::MacroRecord { .. }
+            | InputAction::MacroStop { .. }
+            | InputAction::MacroRun { .. }
+            | InputAction::MacroList
+            | InputAction::Profile { .. }
+            | InputAction::FocusStart { .. }
+            | InputAction::FocusCancel
+            | InputAction::FocusStatus
+            | InputAction::Tour
+            | InputAction::Language(_)
+            | InputAction::Model(_)
+            | InputAction::SetSummarize(_)
+            | InputAction::NudgeAdd(_)
+            | InputAction::NudgeList
+            | InputAction::NudgeClear
+            | InputAction::Resync(ResyncChoice::Recap)
+            | InputAction::Explain
+            | InputAction::Rollback { apply: false, .. } => ActionEffect::None,
+        }
+    }
+
+    /// # checkpoint_reason
+    ///
+    /// **Purpose:**
+    /// Whether this action rewrites the current agent's history, and so needs a checkpoint first.
+    ///
+    /// **Details:**
+    /// The one list of operations that take a checkpoint (see `agent_history::checkpoint`).
+    /// `from_input_action` wraps every action with a reason in a `CheckpointedCommand`.
+    /// It matches every variant, so a new action has to be placed here to compile.
+    /// Two rewrite history but return None because they checkpoint themselves:
+    /// `Summarize`, since `summarize_history` also runs automatically, and
+    /// `Rollback`, which checkpoints only after loading its target so the
+    /// new checkpoint can't shift the number the user picked.
+    pub fn checkpoint_reason(&self) -> Option<CheckpointReason> {
+        match self {
+            InputAction::ClearHistory => Some(CheckpointReason::Clear),
+            InputAction::DedupeHistory { apply: true } => Some(CheckpointReason::Dedupe),
+            InputAction::HistoryRemove { apply: true, .. } => Some(CheckpointReason::HistoryRemove),
+            InputAction::Resync(ResyncChoice::Fresh) => Some(CheckpointReason::FreshStart),
+
+            InputAction::Summarize
+            | InputAction::Rollback { .. }
+            | InputAction::Quit
+            | InputAction::DoNothing
+            | InputAction::ContinueNoSend(_)
+            | InputAction::RecallCommand { .. }
+            | InputAction::SendAsMessage(_)
+            | InputAction::Attach { .. }
+            | InputAction::HistoryInfo
+            | InputAction::SaveHistory
+            | InputAction::DedupeHistory { apply: false }
+            | InputAction::HistoryList
+            | InputAction::HistoryRemove { apply: false, .. }
+            | InputAction::VerifyHistory { .. }
+            | InputAction::ListArchives(_)
+            | InputAction::ExportArchive { .. }
+            | InputAction::PostTweet(_)
+            | InputAction::ConfirmTweet
+            | InputAction::CancelTweet
+            | InputAction::DraftTweet(_)
+            | InputAction::QueueTweet(_)
+            | InputAction::TweetQuota
+            | InputAction::CheckTweet(_)
+            | InputAction::IgnoreWord(_)
+            | InputAction::NewAgent(_)
+            | InputAction::AgentStatus
+            | InputAction::Usage
+            | InputAction::CloseAgent
+            | InputAction::ListAgents
+            | InputAction::Help
+            | InputAction::Inspect { .. }
+            | InputAction::MuteAgent(_)
+            | InputAction::Speak(_)
+            | InputAction::OpenLink(_)
+            | InputAction::ExportBuiltinPersonas { .. }
+            | InputAction::PersonaHistory(_)
+            | InputAction::Overview { .. }
+            | InputAction::Insights { .. }
+            | InputAction::GlobalFilter(_)
+            | InputAction::GlobalClear
+            | InputAction::SetLayout(_)
+            | InputAction::Timestamps(_)
+            | InputAction::ReloadKeys
+            | InputAction::ReloadPersonas { .. }
+            | InputAction::Whence(_)
+            | InputAction::Sent(_)
+            | InputAction::SetLogLevel { .. }
+            | InputAction::ExperimentStart { .. }
+            | InputAction::ExperimentStatus
+            | InputAction::ExperimentStop(_)
+            | InputAction::RateConversation(_)
+            | InputAction::ListJobs
+            | InputAction::CancelJob(_)
+            | InputAction::Stop
+            | InputAction::MacroRecord { .. }
+            | InputAction::MacroStop { .. }
+            | InputAction::MacroRun { .. }
+            | InputAction::MacroList
+            | InputAction::MacroDelete(_)
+            | InputAction::Profile { .. }
+            | InputAction::FocusStart { .. }
+            | InputAction::FocusCancel
+            | InputAction::FocusStatus
+            | InputAction::Tour
+            | InputAction::Language(_)
+            | InputAction::Model(_)
+            | InputAction::SetSummarize(_)
+            | InputAction::NudgeAdd(_)
+            | InputAction::NudgeList
+            | InputAction::NudgeClear
+            | InputAction::Resync(ResyncChoice::Recap)
+            | InputAction::Explain => None,
+        }
+    }
+
+    /// # is_destructive
+    ///
+    /// **Purpose:**
+    /// Whether replaying this action from an untrusted macro needs a confirmed run.
+    ///
+    /// **Details:**
+    /// Covers anything with an `effect`: deleting, overwriting, ending, or posting publicly.
+    pub fn is_destructive(&self) -> bool {
+        self.effect() != ActionEffect::None
+    }
+
+    /// # needs_idle_agent
+    ///
+    /// **Purpose:**
+    /// Whether a macro replay must wait for the current agent's reply before running this.
+    pub fn needs_idle_agent(&self) -> bool {
+        matches!(
+            self,
+            InputAction::SendAsMessage(_)
+                | InputAction::Attach { .. }
+                | InputAction::DraftTweet(_)
+                | InputAction::ClearHistory
+                | InputAction::HistoryInfo
+                | InputAction::SaveHistory
+                | InputAction::Summarize
+                | InputAction::DedupeHistory { .. }
+                | InputAction::HistoryList
+                | InputAction::HistoryRemove { .. }
+                | InputAction::Whence(_)
+                | InputAction::CloseAgent
+                | InputAction::Resync(_)
+                | InputAction::Explain
+                | InputAction::Rollback { .. }
+        )
+    }
+
+    /// # required_tool
+    ///
+    /// **Purpose:**
+    /// The tool the current agent's persona must list to run this action.
+    ///
+    /// **Details:**
+    /// `from_input_action` wraps every action with one in a `ToolGatedCommand`
+    /// (see `persona::tools`). Tweet previews, quota and spelling checks need no
+    /// tool, so any agent can still look at a draft another agent wrote.
+    pub fn required_tool(&self) -> Option<Tool> {
+        match self {
+            InputAction::PostTweet(_)
+            | InputAction::QueueTweet(_) => Some(Tool::TwitterPost),
+            InputAction::DraftTweet(_) => Some(Tool::TwitterDraft),
+            InputAction::SaveHistory
+            | InputAction::Summarize => Some(Tool::History),
+            _ => None,
+        }
+    }
+
+    /// Macro management itself is never captured into a macro
+    pub fn is_macro_control(&self) -> bool {
+        matches!(
+            self,
+            InputAction::MacroRecord { .. }
+                | InputAction::MacroStop { .. }
+                | InputAction::MacroRun { .. }
+                | InputAction::MacroList
+                | InputAction::MacroDelete(_)
+        )
+    }
 }
 
 /// # ConversationHistory
@@ -313,11 +1027,18 @@ pub enum InputAction {
 ///
 /// **Fields:**
 /// - `persona_name`: Name of the persona this history belongs to
-/// - `summary`: Optional summary of previous conversation context
+/// - `summary`: Optional summary of previous conversation context (the recent tier)
+/// - `long_term_summary`: Older summaries folded together and compressed (the long-term tier)
 /// - `recent_messages`: Vector of recent messages kept in full detail
 /// - `total_message_count`: Total number of messages exchanged (including summarized)
 /// - `last_updated`: RFC3339 timestamp of last update
 /// - `summarization_count`: Number of times history has been summarized
+/// - `experiment`: Prompt experiment this conversation was assigned to, if any
+/// - `started_at`: RFC3339 time the conversation began (absent in older files)
+/// - `last_exchange_at`: RFC3339 time of the last reply (absent in older files, where `last_updated` stands in)
+/// - `last_response_id`: Server-side thread to continue after a restart (absent in older files: send the full history)
+/// - `last_response_at`: RFC3339 time `last_response_id` was set, for `grok.response_id_ttl_hours`
+/// - `format_version`: On-disk format version (see `agent_history::schema`)
 ///
 /// **Usage Example:**
 /// ```rust
@@ -327,10 +1048,22 @@ pub enum InputAction {
 pub struct ConversationHistory {
     pub persona_name: String,
     pub summary: Option<String>,
+    pub long_term_summary: Option<String>,
     pub recent_messages: Vec<Message>,
     pub total_message_count: usize,
     pub last_updated: String,
     pub summarization_count: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub experiment: Option<ExperimentTag>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_exchange_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_response_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_response_at: Option<String>,
+    pub format_version: u32,
 }
 
 impl ConversationHistory {
@@ -356,10 +1089,110 @@ impl ConversationHistory {
         Self {
             persona_name,
             summary: None,
+            long_term_summary: None,
             recent_messages: Vec::new(),
             total_message_count: 0,
             last_updated: chrono::Utc::now().to_rfc3339(),
             summarization_count: 0,
+            experiment: None,
+            started_at: None,
+            last_exchange_at: None,
+            last_response_id: None,
+            last_response_at: None,
+            format_version: CURRENT_HISTORY_VERSION,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta() -> ResponseMeta {
+        ResponseMeta {
+            model: "grok-4".into(),
+            temperature: 0.7,
+            latency_ms: Some(1830),
+            input_tokens: Some(1200),
+            output_tokens: Some(85),
+            stripped: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn whence_describes_what_was_recorded() {
+        let cases = [
+            (meta(), "model: grok-4, temperature: 0.70, latency: 1830 ms, tokens: 1200 in / 85 out"),
+            (
+                ResponseMeta { latency_ms: None, input_tokens: None, output_tokens: None, ..meta() },
+                "model: grok-4, temperature: 0.70, latency: ?, tokens: ? in / ? out",
+            ),
+            (
+                ResponseMeta { model: "claude-sonnet-4-20250514".into(), temperature: 1.0, output_tokens: Some(7), ..meta() },
+                "model: claude-sonnet-4-20250514, temperature: 1.00, latency: 1830 ms, tokens: 1200 in / 7 out",
+            ),
+            (
+                ResponseMeta { stripped: vec!["-Shadow".into(), "I hope this helps!".into()], ..meta() },
+                "model: grok-4, temperature: 0.70, latency: 1830 ms, tokens: 1200 in / 85 out; stripped: \"-Shadow\", \"I hope this helps!\"",
+            ),
+        ];
+        for (meta, expected) in cases {
+            assert_eq!(meta.describe(), expected);
+        }
+    }
+
+    #[test]
+    fn messages_load_with_or_without_metadata() {
+        let old: Message = serde_json::from_str(r#"{"role":"assistant","content":"Hi"}"#).unwrap();
+        assert!(old.meta.is_none() && old.source.is_none());
+
+        let newer = r#"{"role":"assistant","content":"Hi","meta":{"model":"grok-4","temperature":0.7,"future":"ignored"},"also_future":1}"#;
+        let newer: Message = serde_json::from_str(newer).unwrap();
+        let read = newer.meta.expect("meta read");
+        assert_eq!((read.model.as_str(), read.latency_ms, read.stripped.len()), ("grok-4", None, 0));
+
+        let plain = Message { role: "user".into(), content: "hello".into(), meta: None, source: None };
+        assert_eq!(serde_json::to_string(&plain).unwrap(), r#"{"role":"user","content":"hello"}"#, "nothing new in files without metadata");
+        let with_meta = Message { meta: Some(meta()), ..plain };
+        let written = serde_json::to_value(&with_meta).unwrap();
+        assert_eq!(written["meta"]["model"], "grok-4");
+        assert!(written["meta"].get("stripped").is_none());
+    }
+
+    /// A `response.completed` payload from `tests/fixtures/responses/`
+    fn fixture(name: &str) -> ResponsesApiResponse {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("tests/fixtures/responses/{}.json", name));
+        serde_json::from_str(&std::fs::read_to_string(&path).expect("fixture exists")).expect("fixture parses")
+    }
+
+    #[test]
+    fn every_text_block_of_every_message_is_read_in_order() {
+        type Case = (&'static str, &'static str, &'static [&'static str]);
+        let cases: [Case; 3] = [
+            ("multi_message", "First, the short answer. Then the details.\n\nAnd a second message.", &[]),
+            (
+                "mixed_blocks",
+                "Autumn wind rises / leaves forget their names / the gate stays open",
+                &["reasoning", "message/refusal", "function_call"],
+            ),
+            ("empty_content", "Only this survives.", &[]),
+        ];
+
+        for (name, text, skipped) in cases {
+            let response = fixture(name);
+            assert_eq!(response.id, format!("resp_{}", name), "{}", name);
+            assert_eq!(response.output_text(), text, "{}", name);
+            assert_eq!(response.skipped_types(), skipped, "{}", name);
+        }
+    }
+
+    #[test]
+    fn a_response_without_output_is_empty_not_an_error() {
+        let response: ResponsesApiResponse = serde_json::from_str(
+            r#"{"id":"resp_1","object":"response","created_at":0,"model":"grok-4"}"#
+        ).unwrap();
+        assert_eq!(response.output_text(), "");
+        assert!(response.skipped_types().is_empty());
+        assert!(response.usage.is_none());
+    }
+}
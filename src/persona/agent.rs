@@ -1,6 +1,7 @@
 // Store all information related to an Agent that can be used in CLI/TUI modes
 use uuid::Uuid;
 use std::collections::VecDeque;
+use std::time::{Duration, Instant, SystemTime};
 
 use crate::prelude::*;
 
@@ -10,25 +11,143 @@ use crate::llm::{
 };
 use crate::grok::client::GrokClient;
 use crate::claude::client::ClaudeClient;
+use crate::persona::experiment::ExperimentTag;
+use crate::persona::review;
+use crate::user::outgoing::{SentMessage, SENT_RECORD_LIMIT};
+use crate::utilities::stream_text::StreamTextFilter;
+use crate::utilities::time::Zone;
+use crate::agent_history::persistence;
+use crate::agent_history::conversations::is_nudge;
+use crate::agent_history::read_state::{resolve_conflict, ReadMarker};
+use crate::agent_history::redaction::{self, Redactor};
+use crate::agent_history::freshness;
+use crate::utilities::explain::{self, FailedRequest};
+use crate::user::system_info;
+use crate::persona::chunk_stream::{ChunkReceiver, ChunkSequencer, CommitFlag};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-type DynamicConnection = Arc<Mutex<Connection<AnyClient>>>;
+pub type DynamicConnection = Arc<Mutex<Connection<AnyClient>>>;
 
+/// Start of a pane line showing a nudge (rendered muted, never continued by a reply)
+pub const NUDGE_PANE_PREFIX: &str = "[nudge] ";
+
+/// Pane line for a nudge
+pub fn nudge_line(text: &str) -> String {
+    format!("{}{}", NUDGE_PANE_PREFIX, text)
+}
+
+/// Whether streamed reply text may be appended to this pane line
+fn continues_reply(line: &str) -> bool {
+    !line.starts_with('>') && !line.starts_with(NUDGE_PANE_PREFIX)
+}
+
+/// # TimestampedMessage
+///
+/// **Summary:**
+/// A pane line and when it was added.
+///
+/// **Fields:**
+/// - `text`: The line as shown (user lines start with `>`)
+/// - `timestamp`: When it was added to the pane; a streamed reply keeps the time it started
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimestampedMessage {
+    pub text: String,
+    pub timestamp: SystemTime,
+}
+
+impl TimestampedMessage {
+    pub fn now(text: impl Into<String>) -> Self {
+        Self { text: text.into(), timestamp: SystemTime::now() }
+    }
+}
+
+/// # CancelReply
+///
+/// **Summary:**
+/// What `AgentInfo::cancel_reply` did.
+///
+/// **Variants:**
+/// - `Cancelled`: The reply was aborted
+/// - `Committed`: The reply is already in history; it's finishing its save or summarization
+/// - `NotRunning`: No reply task was running
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelReply {
+    Cancelled,
+    Committed,
+    NotRunning,
+}
+
+/// # AgentInfo
+///
+/// **Summary:**
+/// Everything about one open agent that CLI and TUI modes share.
+///
+/// **Fields:**
+/// - `id`: Agent id; also keys the TUI's `AgentPane`
+/// - `persona_name`: Persona the agent was opened with
+/// - `connection`: Conversation and LLM client, locked by whichever task is using it
+/// - `messages`: Pane lines with the time each was added, user lines starting with `>`
+/// - `is_waiting`: A reply is in progress
+/// - `chunk_receiver` / `chunk_sender`: Stream chunks from the reply task to the UI loop
+/// - `reply_stream`: Id of the `ChunkStream` the pane is waiting on; reply chunks from any other are stale
+/// - `chunk_checks`: Sequence checks on incoming chunks
+/// - `reply_commit`: Set once the reply being waited on is in history; from then on it can't be cancelled
+/// - `active_task`: The reply task, for cancelling
+/// - `request_started`: When the current request was sent (latency)
+/// - `experiment`: Prompt experiment variant, if any
+/// - `persona_issue`: Why the persona file couldn't be reloaded, if it couldn't
+/// - `pending_text` / `text_filter`: Streamed text not yet shown as whole lines
+/// - `read_marker` / `read_pending_since`: Last read message and the debounce before moving it
+/// - `sent`: Records of recent outgoing messages for `sent`
+/// - `redactor`: The persona's `ephemeral_patterns`, to badge echoes that will be stored redacted
+/// - `resync_offer`: Gap since the last exchange while `resync recap` / `resync fresh` is on offer
+/// - `last_failure`: The request that just failed, while it's the latest thing in the pane (`explain`)
+/// - `failures_in_a_row`: Failed requests since the last reply
+/// - `reports_findings`: The persona lists `findings_report`; each reply gets a line reading its review findings
+///
+/// **Details:**
+/// `AgentOperations` and every command work against this type; TUI-only
+/// presentation (scroll, mute) lives in `tui::AgentPane`.
+/// `tests/agent_lifecycle.rs` runs the same lifecycle against both front ends.
 #[derive(Debug)]
 pub struct AgentInfo {
 
     pub id: Uuid,
     pub persona_name: String,
     pub connection: DynamicConnection,
-    pub messages: VecDeque<String>,
+    pub messages: VecDeque<TimestampedMessage>,
     pub is_waiting: bool,
 
-    pub chunk_receiver: mpsc::UnboundedReceiver<StreamChunk>,
-    pub chunk_sender: mpsc::UnboundedSender<StreamChunk>,
+    pub chunk_receiver: ChunkReceiver,
+    pub chunk_sender: ChunkSender,
+    pub reply_stream: Option<u64>,
+    pub chunk_checks: ChunkSequencer,
+    pub reply_commit: CommitFlag,
 
     pub active_task: Option<tokio::task::JoinHandle<()>>,
+    pub request_started: Option<Instant>,
+
+    pub experiment: Option<ExperimentTag>,
+
+    pub persona_issue: Option<String>,
+
+    pub pending_text: String,
+    pub text_filter: StreamTextFilter,
+
+    pub read_marker: Option<ReadMarker>,
+    pub read_pending_since: Option<Instant>,
 
+    pub sent: VecDeque<SentMessage>,
+
+    pub redactor: Redactor,
+
+    pub resync_offer: Option<chrono::Duration>,
+
+    pub last_failure: Option<FailedRequest>,
+    pub failures_in_a_row: u32,
+
+    pub reports_findings: bool,
 }
 
 impl AgentInfo {
@@ -39,24 +158,493 @@ impl AgentInfo {
             "claude" => AnyClient::Claude(ClaudeClient::new().expect("Failed to init Claude.")),
             _ => AnyClient::Grok(GrokClient::new().expect("Failed to init Grok.")),
         };
+        Self::with_client(id, persona, client)
+    }
+
+    /// # with_client
+    ///
+    /// **Purpose:**
+    /// Opens an agent that talks to `client` rather than its persona's `api_provider`.
+    ///
+    /// **Details:**
+    /// Used with `AgentManager::client_override`, so tests can open agents on a
+    /// `ScriptedClient` without API keys.
+    pub fn with_client(id: Uuid, persona: PersonaRef, client: AnyClient) -> Self {
         let (tx, rx) = mpsc::unbounded_channel();
+        let mut connection = Connection::new_without_output(client, persona.clone());
+        connection.agent_id = Some(id);
+        match HistoryManager::load_overrides(&persona.name) {
+            Ok(overrides) => connection.conversation.set_summarize_override(overrides.summarize_mode),
+            Err(e) => log_error!("Could not load overrides for {}: {}", persona.name, e),
+        }
+        let read_marker = if persona.enable_history {
+            HistoryManager::load_read_state(&persona.name)
+                .unwrap_or_else(|e| {
+                    log_error!("Could not load read marker for {}: {}", persona.name, e);
+                    None
+                })
+        } else {
+            None
+        };
+        let mut unread: Vec<String> = read_marker.as_ref()
+            .map(|marker| unread_lines(marker, connection.local_history(), Zone::for_persona(&persona)))
+            .unwrap_or_default();
+
+        // Picked up after a long break: tell the model once, and offer a recap or a fresh start
+        let resync_offer = connection.conversation.context_gap(chrono::Utc::now());
+        if let Some(gap) = resync_offer {
+            connection.conversation.set_context_note(Some(freshness::context_note(gap)));
+            unread.push(freshness::banner(gap));
+        }
 
         Self {
             id,
             persona_name: persona.name.clone(),
-            connection: Arc::new(Mutex::new(Connection::new_without_output(client, persona))),
-            messages: VecDeque::new(),
+            connection: Arc::new(Mutex::new(connection)),
+            messages: unread.into_iter().map(TimestampedMessage::now).collect(),
             is_waiting: false,
 
             chunk_receiver: rx,
             chunk_sender: tx,
+            reply_stream: None,
+            chunk_checks: ChunkSequencer::default(),
+            reply_commit: CommitFlag::default(),
 
             active_task: None,
+            request_started: None,
+
+            experiment: None,
+
+            persona_issue: None,
+
+            pending_text: String::new(),
+            text_filter: StreamTextFilter::default(),
+
+            read_marker,
+            read_pending_since: None,
+
+            sent: VecDeque::new(),
+
+            // Nothing is stored without history, so nothing is badged either
+            redactor: if persona.enable_history { Redactor::for_persona(&persona) } else { Redactor::default() },
+
+            resync_offer,
+
+            last_failure: None,
+            failures_in_a_row: 0,
+
+            reports_findings: review::reports_findings(&persona),
         }
     }
 
     pub fn add_message(&mut self, msg: impl Into<String>) {
-        self.messages.push_back(msg.into());
+        self.messages.push_back(TimestampedMessage::now(msg));
+    }
+
+    /// # echo_sent
+    ///
+    /// **Purpose:**
+    /// Echoes an outgoing message in the pane and keeps its record for `sent`.
+    pub fn echo_sent(&mut self, sent: SentMessage) {
+        let mut echo = sent.echo();
+        if self.redactor.matches(&sent.send) {
+            echo = format!("{} {}", echo, redaction::redacted_badge());
+        }
+        self.add_message(echo);
+        self.sent.push_back(sent);
+        if self.sent.len() > SENT_RECORD_LIMIT {
+            self.sent.pop_front();
+        }
+    }
+
+    /// # send
+    ///
+    /// **Purpose:**
+    /// Echoes an outgoing message and starts streaming the agent's reply to it.
+    ///
+    /// **Details:**
+    /// A reply still streaming is aborted first. One already committed is
+    /// left to finish saving and summarizing; the new request waits for the
+    /// connection until it has.
+    pub fn send(&mut self, sent: SentMessage) {
+        // Carrying on answers the offer too, and moves past any error
+        self.resync_offer = None;
+        self.last_failure = None;
+        let content = sent.send.clone();
+        self.echo_sent(sent);
+        self.is_waiting = true;
+        self.request_started = Some(Instant::now());
+
+        if let Some(old_task) = self.active_task.take().filter(|_| !self.reply_commit.is_set()) {
+            old_task.abort();
+        }
+
+        let connection = self.connection.clone();
+        let mut stream = self.open_reply_stream();
+
+        self.active_task = Some(tokio::spawn(async move {
+            let mut conn = connection.lock().await;
+            conn.add_user_message(&content);
+            if let Err(e) = conn.handle_response_streaming(&mut stream).await && !stream.is_finished() {
+                let _ = stream.fail(format!("{}", e));
+            }
+        }));
+    }
+
+    /// # open_reply_stream
+    ///
+    /// **Purpose:**
+    /// Opens the stream for a new reply and makes it the one the pane waits on.
+    ///
+    /// **Details:**
+    /// Whatever an earlier reply stream still sends (an aborted task's
+    /// synthetic failure, say) is ignored by `poll_channels` from here on.
+    pub fn open_reply_stream(&mut self) -> ChunkStream {
+        let stream = ChunkStream::open(&self.chunk_sender, TaskKind::Reply);
+        self.reply_stream = Some(stream.id());
+        self.reply_commit = stream.commit_flag();
+        stream
+    }
+
+    /// # cancel_reply
+    ///
+    /// **Purpose:**
+    /// Aborts the reply in flight, leaving what streamed so far in the pane.
+    ///
+    /// **Returns:**
+    /// `CancelReply` - Whether the reply was cancelled, already committed, or not running
+    ///
+    /// **Details:**
+    /// Until the stream is committed (`ChunkStream::commit`) the reply isn't
+    /// in history and its response id isn't kept, so aborting loses neither.
+    /// After that the task is only saving and summarizing, and is left to
+    /// finish. Chunks an aborted task sent but the pane hasn't polled yet are
+    /// dropped by `poll_channels`, since its stream is no longer the one the
+    /// pane waits on.
+    pub fn cancel_reply(&mut self) -> CancelReply {
+        if self.is_waiting && self.reply_commit.is_set() {
+            return CancelReply::Committed;
+        }
+        let Some(task) = self.active_task.take_if(|task| !task.is_finished()) else {
+            return CancelReply::NotRunning;
+        };
+        task.abort();
+        self.flush_reply_text();
+        self.add_message("[response cancelled]");
+        self.is_waiting = false;
+        self.reply_stream = None;
+        self.request_started = None;
+        CancelReply::Cancelled
+    }
+
+    /// # append_reply_text
+    ///
+    /// **Purpose:**
+    /// Appends streamed reply text to the reply in progress, starting one if needed.
+    ///
+    /// **Parameters:**
+    /// - `text`: Streamed text
+    /// - `whole_lines`: Hold text back until a line is complete (accessible mode)
+    ///
+    /// **Details:**
+    /// Text goes through `text_filter` first: `\r` rewrites the current line
+    /// and escape sequences are dropped, so progress output doesn't pile up.
+    /// With `whole_lines`, the partial last line waits in `pending_text` until a
+    /// newline arrives or `flush_reply_text` is called when the reply ends.
+    pub fn append_reply_text(&mut self, text: &str, whole_lines: bool) {
+        if !whole_lines {
+            if !matches!(self.messages.back(), Some(last_msg) if continues_reply(&last_msg.text)) {
+                self.add_message(String::new());
+            }
+            if let Some(reply) = self.messages.back_mut() {
+                self.text_filter.push(&mut reply.text, text);
+                if reply.text.is_empty() {
+                    self.messages.pop_back();
+                }
+            }
+            return;
+        }
+
+        self.text_filter.push(&mut self.pending_text, text);
+        if let Some(newline) = self.pending_text.rfind('\n') {
+            let ready: String = self.pending_text.drain(..newline).collect();
+            self.push_reply_text(&ready);
+        }
+    }
+
+    /// # record_failure
+    ///
+    /// **Purpose:**
+    /// Shows a failed request's error in the pane and keeps it for `explain`.
+    ///
+    /// **Details:**
+    /// Known error categories get their hint line under the error.
+    pub fn record_failure(&mut self, error: String) {
+        self.add_message(format!("Error: {}", error));
+        if let Some(hint) = explain::hint(&error) {
+            self.add_message(hint);
+        }
+        self.add_message("Type you message again to retry, or `explain` (Ctrl+E) to ask about the error.");
+        self.failures_in_a_row += 1;
+        self.last_failure = Some(FailedRequest {
+            error,
+            trigger: self.sent.back().map(|sent| sent.display.clone()),
+        });
+    }
+
+    /// Moves any held-back reply text into the messages and ends the reply
+    pub fn flush_reply_text(&mut self) {
+        self.text_filter.finish();
+        let rest = std::mem::take(&mut self.pending_text);
+        self.push_reply_text(&rest);
+    }
+
+    /// # note_read
+    ///
+    /// **Purpose:**
+    /// Advances the read marker once the user has stayed at the bottom of new content.
+    ///
+    /// **Parameters:**
+    /// - `at_bottom`: The pane is on screen and scrolled to the end
+    ///
+    /// **Details:**
+    /// Called every loop for the current agent. Nothing is marked while a reply
+    /// streams, and the marker only moves after `history.read_marker_debounce_ms`
+    /// at the bottom, so content that merely arrived doesn't count as read.
+    /// Failures are only logged.
+    pub fn note_read(&mut self, at_bottom: bool) {
+        if !at_bottom || self.is_waiting {
+            self.read_pending_since = None;
+            return;
+        }
+        let Ok(conn) = self.connection.try_lock() else {
+            return;
+        };
+        if !conn.conversation.persona.enable_history {
+            return;
+        }
+        let history = conn.local_history();
+        let Some(marker) = ReadMarker::at_end(history, &system_info::host_name()) else {
+            return;
+        };
+        if self.read_marker.as_ref().is_some_and(|read| read.same_message(&marker)) {
+            self.read_pending_since = None;
+            return;
+        }
+
+        let debounce = Duration::from_millis(GLOBAL_CONFIG.history.read_marker_debounce_ms);
+        if self.read_pending_since.get_or_insert_with(Instant::now).elapsed() < debounce {
+            return;
+        }
+        self.read_pending_since = None;
+
+        let on_disk = HistoryManager::load_read_state(&self.persona_name).unwrap_or_else(|e| {
+            log_error!("Could not load read marker for {}: {}", self.persona_name, e);
+            None
+        });
+        let marker = resolve_conflict(self.read_marker.as_ref(), on_disk, marker, history);
+        if let Err(e) = persistence::auto_write(|| HistoryManager::save_read_state(&self.persona_name, &marker)) {
+            log_error!("Failed to save read marker for {}: {}", self.persona_name, e);
+        }
+        drop(conn);
+        self.read_marker = Some(marker);
+    }
+
+    fn push_reply_text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        match self.messages.back_mut() {
+            Some(last_msg) if continues_reply(&last_msg.text) => last_msg.text.push_str(text),
+            _ => self.add_message(text),
+        }
+    }
+
+}
+
+/// # unread_lines
+///
+/// **Purpose:**
+/// Pane lines for the messages after `marker`: the divider, then each message as it was shown.
+fn unread_lines(marker: &ReadMarker, history: &[Message], zone: Zone) -> Vec<String> {
+    let Some(first) = marker.first_unread(history) else {
+        return Vec::new();
+    };
+    let mut lines = vec![marker.divider(zone)];
+    lines.extend(history[first..].iter()
+        .filter(|message| message.role != "system" || is_nudge(message))
+        .map(|message| match message.role.as_str() {
+            "user" => format!("> {}", message.content),
+            "system" => nudge_line(&message.content),
+            _ => message.content.clone(),
+        }));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::scripted::ScriptedClient;
+    use crate::test_support::test_personas_root;
+
+    fn agent() -> AgentInfo {
+        test_personas_root();
+        let persona = Persona::from_yaml_str("name: Batching\nsystem_prompt: Be brief.\nenable_history: false\n").unwrap();
+        AgentInfo::with_client(Uuid::new_v4(), Arc::new(persona), AnyClient::Scripted(ScriptedClient::default()))
+    }
+
+    fn texts(agent: &AgentInfo) -> Vec<String> {
+        agent.messages.iter().map(|msg| msg.text.clone()).collect()
+    }
+
+    #[test]
+    fn whole_lines_hold_text_until_a_line_ends() {
+        let mut agent = agent();
+        agent.add_message("> hello");
+
+        agent.append_reply_text("First line", true);
+        assert_eq!(texts(&agent), ["> hello"], "nothing shows mid-line");
+
+        agent.append_reply_text(" ends.\nSecond", true);
+        assert_eq!(texts(&agent), ["> hello", "First line ends."]);
+
+        agent.append_reply_text(" line", true);
+        assert_eq!(texts(&agent), ["> hello", "First line ends."], "still mid-line");
+
+        agent.append_reply_text(" too.\nThird\n", true);
+        assert_eq!(texts(&agent), ["> hello", "First line ends.\nSecond line too.\nThird"]);
+
+        agent.flush_reply_text();
+        assert_eq!(texts(&agent), ["> hello", "First line ends.\nSecond line too.\nThird\n"]);
+        assert!(agent.pending_text.is_empty());
+    }
+
+    #[test]
+    fn flushing_shows_a_reply_with_no_newline() {
+        let mut agent = agent();
+        agent.add_message("> hi");
+
+        agent.append_reply_text("Just one", true);
+        agent.append_reply_text(" line.", true);
+        assert_eq!(texts(&agent), ["> hi"]);
+
+        agent.flush_reply_text();
+        assert_eq!(texts(&agent), ["> hi", "Just one line."]);
+    }
+
+    #[test]
+    fn without_whole_lines_every_delta_shows() {
+        let mut agent = agent();
+        agent.add_message("> hi");
+
+        agent.append_reply_text("Just", false);
+        assert_eq!(texts(&agent), ["> hi", "Just"]);
+        agent.append_reply_text(" one\nmore", false);
+        assert_eq!(texts(&agent), ["> hi", "Just one\nmore"]);
+    }
+
+    /// An agent whose persona keeps history, so read markers are saved
+    fn reading_agent(name: &str) -> AgentInfo {
+        test_personas_root();
+        let persona = Persona::from_yaml_str(&format!("name: {}\nsystem_prompt: Be brief.\n", name)).unwrap();
+        AgentInfo::with_client(Uuid::new_v4(), Arc::new(persona), AnyClient::Scripted(ScriptedClient::default()))
     }
 
-}
\ No newline at end of file
+    fn exchange(agent: &AgentInfo, question: &str, answer: &str) {
+        let mut conn = agent.connection.try_lock().unwrap();
+        conn.conversation.local_history.push(Message { role: "user".into(), content: question.into(), meta: None, source: None });
+        conn.conversation.local_history.push(Message { role: "assistant".into(), content: answer.into(), meta: None, source: None });
+    }
+
+    /// Sits at the bottom for the whole debounce
+    fn read_to_bottom(agent: &mut AgentInfo) {
+        agent.note_read(true);
+        let debounce = Duration::from_millis(GLOBAL_CONFIG.history.read_marker_debounce_ms + 1);
+        agent.read_pending_since = agent.read_pending_since.and_then(|since| since.checked_sub(debounce));
+        agent.note_read(true);
+    }
+
+    fn marked(agent: &AgentInfo) -> Option<usize> {
+        agent.read_marker.as_ref().map(|marker| marker.position)
+    }
+
+    #[test]
+    fn the_marker_moves_only_after_sitting_at_the_bottom() {
+        let mut agent = reading_agent("ReadAdvance");
+        exchange(&agent, "one", "first");
+
+        agent.note_read(false);
+        assert_eq!((marked(&agent), agent.read_pending_since), (None, None), "scrolled up reads nothing");
+
+        agent.note_read(true);
+        assert!(agent.read_pending_since.is_some());
+        assert_eq!(marked(&agent), None, "arriving isn't reading");
+        agent.is_waiting = true;
+        agent.note_read(true);
+        assert_eq!(agent.read_pending_since, None, "nothing is marked while a reply streams");
+        agent.is_waiting = false;
+
+        read_to_bottom(&mut agent);
+        assert_eq!(marked(&agent), Some(2));
+        assert_eq!(HistoryManager::load_read_state("ReadAdvance").unwrap(), agent.read_marker);
+
+        exchange(&agent, "two", "second");
+        agent.note_read(true);
+        assert_eq!(marked(&agent), Some(2), "new content waits out the debounce too");
+        read_to_bottom(&mut agent);
+        assert_eq!(marked(&agent), Some(4));
+
+        agent.note_read(true);
+        assert_eq!(agent.read_pending_since, None, "already marked");
+    }
+
+    #[test]
+    fn another_devices_earlier_marker_wins() {
+        let mut agent = reading_agent("ReadConflict");
+        exchange(&agent, "one", "first");
+        read_to_bottom(&mut agent);
+        exchange(&agent, "two", "second");
+
+        let history = agent.connection.try_lock().unwrap().local_history().to_vec();
+        let laptop = ReadMarker::at_end(&history[..2], "laptop").unwrap();
+        HistoryManager::save_read_state("ReadConflict", &laptop).unwrap();
+        read_to_bottom(&mut agent);
+
+        assert_eq!(agent.read_marker.as_ref(), Some(&laptop), "the laptop only read the first question");
+        assert_eq!(HistoryManager::load_read_state("ReadConflict").unwrap(), Some(laptop));
+    }
+
+    #[test]
+    fn reopening_shows_the_unread_messages_under_a_divider() {
+        let mut agent = reading_agent("ReadReopen");
+        exchange(&agent, "one", "first");
+        read_to_bottom(&mut agent);
+        exchange(&agent, "two", "second");
+        agent.connection.try_lock().unwrap().save_persona_history().unwrap();
+
+        let reopened = reading_agent("ReadReopen");
+        let lines = texts(&reopened);
+        assert!(lines[0].starts_with("── new since ") && lines[0].ends_with(&format!(" on {} ──", system_info::host_name())), "{:?}", lines);
+        assert_eq!(lines[1..3], ["> two", "second"]);
+    }
+
+    #[test]
+    fn the_divider_lands_on_the_same_message_after_summarization() {
+        let message = |role: &str, content: &str| Message { role: role.into(), content: content.into(), meta: None, source: None };
+        let before = [message("system", "Be brief."), message("user", "one"), message("assistant", "first"), message("user", "two"), message("assistant", "second")];
+        let read = ReadMarker::at_end(&before[..3], "desk").unwrap();
+        let summarized = [
+            message("system", "Be brief."),
+            message("system", "[Long-term summary]\n- met"),
+            message("system", "[Summary of earlier conversation]\n- one"),
+            message("assistant", "first"),
+            message("user", "two"),
+            message("assistant", "second"),
+        ];
+
+        assert_eq!(read.locate(&summarized), Some(3));
+        let lines = unread_lines(&read, &summarized, Zone::Local);
+        assert_eq!(lines[1..], ["> two", "second"], "{:?}", lines);
+        assert_eq!(unread_lines(&read, &before[..3], Zone::Local), Vec::<String>::new(), "nothing unread, no divider");
+    }
+}
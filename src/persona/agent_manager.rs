@@ -8,8 +8,44 @@ use uuid::Uuid;
 
 use crate::prelude::*;
 use crate::persona::agent::AgentInfo;
+use crate::llm::AnyClient;
+use crate::persona::experiment::ExperimentRegistry;
+use crate::persona::builtin;
+use crate::persona::changelog::record_persona_load;
+use crate::persona::jobs::{GenerationJob, JobId, JobRegistry};
+use crate::persona::reload::{FileChange, ReloadPlan, ReloadReport};
+use crate::persona::review;
+use crate::persona::discover_personas;
+use crate::utilities::accessibility;
+use crate::utilities::events::{self, EventKind};
 
 
+/// # AgentEvent
+///
+/// **Summary:**
+/// Attention-worthy things noticed while draining agent channels.
+///
+/// **Variants:**
+/// - `Info`: An informational chunk (e.g. summarization started)
+/// - `ReplyReady`: An agent finished a reply (with its text, and its token usage if reported)
+/// - `Job`: A generation job started, progressed, completed, or failed
+///
+/// **Details:**
+/// The UI decides whether to surface these (muted agents only get logged).
+#[derive(Debug, Clone)]
+pub enum AgentEvent {
+    Info { id: Uuid, msg: String },
+    ReplyReady { id: Uuid, reply: String, usage: Option<Usage> },
+    Job { id: Uuid, msg: String, failed: bool },
+}
+
+/// # AgentManager
+///
+/// **Summary:**
+/// Personas, open agents, experiments and jobs, shared by the CLI and TUI.
+///
+/// **Fields:**
+/// - `client_override`: Client every new agent uses instead of its persona's provider (a `ScriptedClient` in tests)
 #[derive(Debug)]
 pub struct AgentManager {
     pub personas: HashMap<String, PersonaRef>,
@@ -17,6 +53,10 @@ pub struct AgentManager {
     pub current_agent: Option<Uuid>,
     pub agent_order: Vec<Uuid>,
     pub user_input: Option<UserInput>,
+    pub experiments: ExperimentRegistry,
+    pub persona_sources: HashMap<String, PersonaSource>,
+    pub jobs: JobRegistry,
+    pub client_override: Option<AnyClient>,
 }
 
 impl AgentManager {
@@ -28,35 +68,275 @@ impl AgentManager {
             current_agent: None,
             agent_order: Vec::new(),
             user_input: None,
+            experiments: ExperimentRegistry::new(),
+            persona_sources: HashMap::new(),
+            jobs: JobRegistry::new(),
+            client_override: None,
         }
     }
 
-    pub fn load_personas(&mut self, personas_paths: Vec<&Path>) -> anyhow::Result<()> {
-        for path in personas_paths {
-            let persona = Persona::from_yaml_file(path)?;// Quickly deal with errors
+    /// # load_personas
+    ///
+    /// **Purpose:**
+    /// Registers every persona that loads from `persona_sources`.
+    ///
+    /// **Returns:**
+    /// `Vec<String>` - One message per persona skipped because it failed to load
+    /// or validate, for the caller to show at startup
+    ///
+    /// **Details:**
+    /// A broken file no longer stops the others loading; it just isn't offered.
+    pub fn load_personas(&mut self, persona_sources: Vec<&PersonaSource>) -> Vec<String> {
+        let mut skipped = Vec::new();
+        for source in persona_sources {
+            let persona = match source.load() {
+                Ok(persona) => persona,
+                Err(e) => {
+                    log_error!("Skipping persona {}: {}", source.describe(), e);
+                    skipped.push(format!("Skipped persona {}: {}", source.describe(), e));
+                    continue;
+                }
+            };
+            if let Err(e) = record_persona_load(&persona) {
+                log_error!("Failed to update changelog for {}: {}", persona.name, e);
+            }
+            self.persona_sources.insert(persona.name.clone(), source.clone());
             self.personas.insert(persona.name.clone(), Arc::new(persona));
         }
 
-        Ok(())
+        skipped
+    }
+
+    /// # apply_reload
+    ///
+    /// **Purpose:**
+    /// Applies a watcher's reload plan to the persona registry in one pass.
+    ///
+    /// **Parameters:**
+    /// - `plan`: Net file changes from `ReloadAggregator::take_plan`
+    ///
+    /// **Returns:**
+    /// `ReloadReport` - What was reloaded and what failed, for one notification
+    ///
+    /// **Details:**
+    /// A file that fails to load leaves the persona as it was. A removed file falls
+    /// back to the built-in of the same name, if there is one. Open agents keep
+    /// their persona; those backed by a removed or broken file are flagged
+    /// (`persona_issue`) with a one-time pane message, cleared once it loads again.
+    pub fn apply_reload(&mut self, plan: &ReloadPlan) -> ReloadReport {
+        let mut report = ReloadReport { unchanged: plan.unchanged.len(), ..Default::default() };
+
+        for planned in &plan.changes {
+            let source = PersonaSource::File(planned.path.clone());
+            if planned.change == FileChange::Removed {
+                let backed = self.agents_backed_by(&source);
+                self.flag_persona_issue(&backed, Some(format!("persona file {} missing", planned.path.display())));
+                let fallback = builtin::load_builtin(&planned.persona).ok();
+                let names: Vec<String> = self.persona_sources.iter()
+                    .filter(|(_, existing)| **existing == source)
+                    .map(|(name, _)| name.clone())
+                    .collect();
+                for name in names {
+                    match &fallback {
+                        Some(persona) if persona.name == name => {
+                            self.persona_sources.insert(name.clone(), PersonaSource::Builtin(name.clone()));
+                            self.personas.insert(name, Arc::new(persona.clone()));
+                        }
+                        _ => {
+                            self.persona_sources.remove(&name);
+                            self.personas.remove(&name);
+                        }
+                    }
+                }
+                report.applied.push((planned.persona.clone(), planned.change));
+                continue;
+            }
+
+            match source.load() {
+                Ok(persona) => {
+                    if let Err(e) = record_persona_load(&persona) {
+                        log_error!("Failed to update changelog for {}: {}", persona.name, e);
+                    }
+                    log_info!("Reloaded persona {} from {}", persona.name, planned.path.display());
+                    report.applied.push((persona.name.clone(), planned.change));
+                    self.persona_sources.insert(persona.name.clone(), source.clone());
+                    self.personas.insert(persona.name.clone(), Arc::new(persona));
+                    let backed = self.agents_backed_by(&source);
+                    self.flag_persona_issue(&backed, None);
+                }
+                Err(e) => {
+                    log_error!("Failed to reload {}: {}", planned.path.display(), e);
+                    let backed = self.agents_backed_by(&source);
+                    self.flag_persona_issue(&backed, Some(format!("persona file {} invalid: {}", planned.path.display(), e)));
+                    report.failures.push((planned.persona.clone(), e.to_string()));
+                }
+            }
+        }
+
+        report
+    }
+
+    /// # rescan_personas
+    ///
+    /// **Purpose:**
+    /// Re-reads every persona in the personas directory into the registry (`reload`).
+    ///
+    /// **Parameters:**
+    /// - `apply`: Also switch open agents to their persona's current version (`reload --apply`)
+    ///
+    /// **Returns:**
+    /// `Result<ReloadReport, ShadowError>` - Personas added, changed, removed, or failing to load,
+    /// and which agents were updated
+    ///
+    /// **Errors / Failures:**
+    /// - The personas directory can't be read (`discover_personas`)
+    ///
+    /// **Details:**
+    /// The manual counterpart of the watcher's `apply_reload`, for changes it
+    /// missed or CLI mode, which has no watcher. A persona that fails to load
+    /// keeps its last good version; one no longer found is dropped from the
+    /// registry. Open agents keep their persona unless `apply` is set.
+    pub fn rescan_personas(&mut self, apply: bool) -> Result<ReloadReport, ShadowError> {
+        let discovered = discover_personas()?;
+        let mut report = ReloadReport::default();
+
+        for (dir_name, source) in &discovered {
+            let persona = match source.load() {
+                Ok(persona) => persona,
+                Err(e) => {
+                    log_error!("Failed to reload {}: {}", dir_name, e);
+                    report.failures.push((dir_name.clone(), e.to_string()));
+                    continue;
+                }
+            };
+            let change = match self.personas.get(&persona.name) {
+                None => FileChange::Added,
+                Some(existing) if same_persona(existing, &persona)
+                    && self.persona_sources.get(&persona.name) == Some(source) =>
+                {
+                    report.unchanged += 1;
+                    continue;
+                }
+                Some(_) => FileChange::Modified,
+            };
+            if let Err(e) = record_persona_load(&persona) {
+                log_error!("Failed to update changelog for {}: {}", persona.name, e);
+            }
+            log_info!("Reloaded persona {} ({})", persona.name, change.describe());
+            report.applied.push((persona.name.clone(), change));
+            self.persona_sources.insert(persona.name.clone(), source.clone());
+            self.personas.insert(persona.name.clone(), Arc::new(persona));
+        }
+
+        let gone: Vec<String> = self.persona_sources.iter()
+            .filter(|(_, source)| !discovered.iter().any(|(_, found)| found == *source))
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in gone {
+            self.persona_sources.remove(&name);
+            self.personas.remove(&name);
+            report.applied.push((name, FileChange::Removed));
+        }
+
+        if apply {
+            self.apply_personas_to_agents(&mut report);
+        }
+        Ok(report)
+    }
+
+    /// # apply_personas_to_agents
+    ///
+    /// **Purpose:**
+    /// Switches each open agent whose persona changed to the registry's version.
+    ///
+    /// **Details:**
+    /// Replaces the agent's persona and the system prompt at the start of its
+    /// history (`GrokConversation::replace_persona`), then saves the history.
+    /// Agents in a prompt experiment run a variant's prompt and are left
+    /// alone, as are agents busy replying and agents whose persona is gone.
+    fn apply_personas_to_agents(&mut self, report: &mut ReloadReport) {
+        for id in &self.agent_order {
+            let Some(agent) = self.agents.get(id) else {
+                continue;
+            };
+            let name = capitalize_first(&agent.persona_name);
+            let Some(persona) = self.personas.get(&agent.persona_name) else {
+                report.agents_skipped.push((name, "its persona no longer exists".to_string()));
+                continue;
+            };
+            let Ok(mut conn) = agent.connection.try_lock() else {
+                report.agents_skipped.push((name, "busy replying; try again once it finishes".to_string()));
+                continue;
+            };
+            if same_persona(&conn.conversation.persona, persona) {
+                continue;
+            }
+            if agent.experiment.is_some() {
+                report.agents_skipped.push((name, "it's in a prompt experiment".to_string()));
+                continue;
+            }
+
+            conn.conversation.replace_persona(persona.clone());
+            if let Err(e) = conn.autosave_persona_history() {
+                log_error!("Failed to save {} history after reloading its persona: {}", agent.persona_name, e);
+            }
+            report.agents_updated.push(name);
+        }
+    }
+
+    pub fn load_experiments(&mut self) {
+        self.experiments = ExperimentRegistry::load();
     }
 
     pub fn add_agent(&mut self, id: Uuid, persona: PersonaRef) {
 
-        let agent = AgentInfo::new(id, persona);
+        // New conversations of a persona under test get the next variant
+        let assignment = self.experiments.for_persona_mut(&persona.name).map(|experiment| {
+            let tag = experiment.assign(id);
+            if let Err(e) = experiment.save() {
+                log_error!("Failed to save experiment {}: {}", experiment.name, e);
+            }
+            (experiment.persona_for(tag.variant, persona.clone()), tag)
+        });
+
+        let agent = match assignment {
+            Some((variant_persona, tag)) => {
+                let mut agent = self.open_agent(id, variant_persona);
+                if let Ok(mut conn) = agent.connection.try_lock() {
+                    conn.conversation.experiment = Some(tag.clone());
+                }
+                agent.add_message(tag.label());
+                agent.experiment = Some(tag);
+                agent
+            }
+            None => self.open_agent(id, persona),
+        };
         self.agent_order.push(id);
         self.current_agent = Some(id);
+        events::publish(EventKind::AgentCreated, Some(&agent.persona_name), Some(id));
         self.agents.insert(id, agent);
 
     }
 
+    /// Opens an agent on `client_override` if one is set, otherwise on its persona's provider
+    fn open_agent(&self, id: Uuid, persona: PersonaRef) -> AgentInfo {
+        match &self.client_override {
+            Some(client) => AgentInfo::with_client(id, persona, client.clone()),
+            None => AgentInfo::new(id, persona),
+        }
+    }
+
     pub fn remove_agent(&mut self, id: Uuid) {
         if let Some(agent) = self.agents.get_mut(&id) {
             if let Some(task) = agent.active_task.take() {
                 task.abort();
             }
         }
+        self.jobs.cancel_for_agent(id);
 
-        self.agents.remove(&id);
+        if let Some(agent) = self.agents.remove(&id) {
+            events::publish(EventKind::AgentClosed, Some(&agent.persona_name), Some(id));
+        }
         self.agent_order.retain(|&x| x != id);
         if self.current_agent == Some(id) {
             self.current_agent = self.agent_order.last().cloned();
@@ -64,6 +344,69 @@ impl AgentManager {
 
     }
 
+    /// # refresh_persona_status
+    ///
+    /// **Purpose:**
+    /// Re-checks the persona file backing an agent and tracks whether it is still usable.
+    ///
+    /// **Parameters:**
+    /// - `id`: Agent to check
+    ///
+    /// **Returns:**
+    /// `Option<String>` - A one-time message when the file goes missing/invalid or becomes valid again
+    ///
+    /// **Details:**
+    /// The agent always keeps running on the persona it was created with; this only
+    /// reports on the file. Built-in personas never have issues.
+    pub fn refresh_persona_status(&mut self, id: Uuid) -> Option<String> {
+        let agent = self.agents.get_mut(&id)?;
+        let Some(PersonaSource::File(path)) = self.persona_sources.get(&agent.persona_name) else {
+            return None;
+        };
+
+        let issue = if !path.exists() {
+            Some(format!("persona file {} missing", path.display()))
+        } else {
+            Persona::from_yaml_file(path)
+                .err()
+                .map(|e| format!("persona file {} invalid: {}", path.display(), e))
+        };
+
+        set_persona_issue(agent, issue)
+    }
+
+    /// Agents whose persona was loaded from `source`
+    fn agents_backed_by(&self, source: &PersonaSource) -> Vec<Uuid> {
+        self.agents.iter()
+            .filter(|(_, agent)| self.persona_sources.get(&agent.persona_name) == Some(source))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Records the state of the file backing `ids`, telling each pane when it changes
+    fn flag_persona_issue(&mut self, ids: &[Uuid], issue: Option<String>) {
+        for id in ids {
+            if let Some(agent) = self.agents.get_mut(id)
+                && let Some(msg) = set_persona_issue(agent, issue.clone())
+            {
+                agent.add_message(msg);
+            }
+        }
+    }
+
+    /// # spawn_job
+    ///
+    /// **Purpose:**
+    /// Runs a generation job against its target agent's connection.
+    ///
+    /// **Returns:**
+    /// `Option<JobId>` - None if the target agent no longer exists
+    pub fn spawn_job(&mut self, job: GenerationJob) -> Option<JobId> {
+        let agent = self.agents.get(&job.agent)?;
+        let (connection, tx) = (agent.connection.clone(), agent.chunk_sender.clone());
+        Some(self.jobs.spawn(job, connection, tx))
+    }
+
     pub fn get_agent_name(&self, id: Uuid) -> String {
         self.agents.get(&id)
             .map(|agent| agent.persona_name.clone())
@@ -94,45 +437,131 @@ impl AgentManager {
         self.current_agent.and_then(move |id| self.agents.get_mut(&id))
     }
 
-    pub fn poll_channels(&mut self) {
+    pub fn poll_channels(&mut self) -> Vec<AgentEvent> {
+        let mut events = Vec::new();
+
         for (_, agent) in self.agents.iter_mut() {
 
-            while let Ok(chunk) = agent.chunk_receiver.try_recv() {
-                match chunk {
+            while let Ok(tagged) = agent.chunk_receiver.try_recv() {
+                agent.chunk_checks.check(&tagged);
+                match tagged.chunk {
+                    // Left over from a reply that was stopped or replaced
+                    StreamChunk::Delta(_) | StreamChunk::Complete { .. } | StreamChunk::Error(_)
+                        if agent.reply_stream != Some(tagged.stream) => {}
+
                     StreamChunk::Delta(text) => {
-                        if let Some(last_msg) = agent.messages.back_mut() {
-                            if !last_msg.starts_with('>') {
-                                last_msg.push_str(&text);
-                            } else {
-                                agent.add_message(text);
-                            }
-                        } else {
-                            agent.add_message(text);
-                        }
+                        agent.append_reply_text(&text, accessibility::is_enabled());
                     }
 
-                    StreamChunk::Complete{response_id, full_reply: _} => {
+                    StreamChunk::Complete{response_id, full_reply, usage} => {
+                        agent.flush_reply_text();
+                        if agent.reports_findings {
+                            agent.add_message(review::report_line(&full_reply));
+                        }
                         if let Ok(mut conn) = agent.connection.try_lock() {
                             conn.set_last_response_id(response_id.clone());
                         }
 
+                        let latency_ms = agent.request_started.take()
+                            .map(|started| started.elapsed().as_millis() as u64)
+                            .unwrap_or(0);
+                        if let Some(experiment) = agent.experiment.as_ref()
+                            .and_then(|tag| self.experiments.for_persona_mut(&agent.persona_name)
+                                .filter(|experiment| experiment.name == tag.experiment))
+                        {
+                            experiment.record_reply(agent.id, full_reply.chars().count(), latency_ms);
+                            if let Err(e) = experiment.save() {
+                                log_error!("Failed to save experiment {}: {}", experiment.name, e);
+                            }
+                        }
+
                         agent.is_waiting = false;
+                        agent.reply_stream = None;
                         agent.active_task = None;
+                        agent.failures_in_a_row = 0;
+                        events.push(AgentEvent::ReplyReady { id: agent.id, reply: full_reply, usage });
                     }
 
                     StreamChunk::Error(err) => {
-                        agent.add_message(format!("Error: {}", err));
-                        agent.add_message("Type you message again to retry.");
+                        agent.flush_reply_text();
+                        agent.record_failure(err);
                         agent.is_waiting = false;
+                        agent.reply_stream = None;
+                        agent.request_started = None;
                         agent.active_task = None;
                     }
 
                     StreamChunk::Info(msg) => {
                         log_info!("Info: {}", msg);
+                        events.push(AgentEvent::Info { id: agent.id, msg });
+                    }
+
+                    StreamChunk::Notice(msg) => {
+                        log_info!("Notice: {}", msg);
+                        agent.add_message(msg);
+                    }
+
+                    StreamChunk::JobStarted { job, name } => {
+                        let msg = format!("Job {} ({}) started", job, name);
+                        events.push(AgentEvent::Job { id: agent.id, msg, failed: false });
+                    }
+
+                    StreamChunk::JobProgress { job, name, msg } => {
+                        let msg = format!("Job {} ({}): {}", job, name, msg);
+                        events.push(AgentEvent::Job { id: agent.id, msg, failed: false });
+                    }
+
+                    StreamChunk::JobCompleted { job, name, msg } => {
+                        // A job cancelled just as it finished is already gone; stay quiet about it
+                        if self.jobs.finish(job).is_some() {
+                            let msg = format!("Job {} ({}) completed: {}", job, name, msg);
+                            events.push(AgentEvent::Job { id: agent.id, msg, failed: false });
+                        }
+                    }
+
+                    StreamChunk::JobFailed { job, name, error } => {
+                        if self.jobs.finish(job).is_some() {
+                            log_error!("Job {} ({}) failed: {}", job, name, error);
+                            let msg = format!("Job {} ({}) failed: {}", job, name, error);
+                            events.push(AgentEvent::Job { id: agent.id, msg, failed: true });
+                        }
                     }
                 }
             }
         }
+
+        events
+    }
+
+}
+/// Sets an agent's persona issue; returns the pane message when it appears or clears
+fn set_persona_issue(agent: &mut AgentInfo, issue: Option<String>) -> Option<String> {
+    match (&agent.persona_issue, issue) {
+        (None, Some(issue)) => {
+            log_error!("{}: {}", agent.persona_name, issue);
+            let msg = format!("Warning: {} — agent continues with last good configuration", issue);
+            agent.persona_issue = Some(issue);
+            Some(msg)
+        }
+        (Some(_), None) => {
+            agent.persona_issue = None;
+            Some(format!(
+                "Persona file for {} is valid again; open a new agent with 'new {}' to use it",
+                agent.persona_name, agent.persona_name
+            ))
+        }
+        (Some(_), Some(issue)) => {
+            agent.persona_issue = Some(issue);
+            None
+        }
+        (None, None) => None,
     }
+}
 
-}
\ No newline at end of file
+/// Whether two loaded personas have the same settings and prompt (Persona has no PartialEq)
+fn same_persona(a: &Persona, b: &Persona) -> bool {
+    match (serde_json::to_value(a), serde_json::to_value(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
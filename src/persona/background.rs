@@ -0,0 +1,105 @@
+//! # Daegonica Module: persona::background
+//!
+//! **Purpose:** Work that runs off the UI loop and reports to the Global pane
+//!
+//! **Context:**
+//! - Jobs (`persona::jobs`) belong to an agent and report through its chunk channel
+//! - Some commands need the network but no agent (`reload keys` checking each key)
+//! - Running those inline froze the TUI until every request had answered
+//!
+//! **Responsibilities:**
+//! - Define `BackgroundTask`, a future that ends with messages for the Global pane
+//! - Define `BackgroundReports`, which spawns tasks and hands their messages to the UI loop
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::future::Future;
+use std::pin::Pin;
+use crate::prelude::*;
+
+/// # BackgroundReport
+///
+/// **Summary:**
+/// One message from finished background work.
+///
+/// **Fields:**
+/// - `msg`: Text for the Global pane
+/// - `failed`: Shown as an error rather than info
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackgroundReport {
+    pub msg: String,
+    pub failed: bool,
+}
+
+/// Work for `AgentOperations::run_in_background`
+pub type BackgroundTask = Pin<Box<dyn Future<Output = Vec<BackgroundReport>> + Send>>;
+
+/// # BackgroundReports
+///
+/// **Summary:**
+/// Runs background tasks on the tokio runtime and collects what they report.
+///
+/// **Fields:**
+/// - `tx` / `rx`: Reports from finished tasks, drained by the UI loop
+#[derive(Debug)]
+pub struct BackgroundReports {
+    tx: mpsc::UnboundedSender<BackgroundReport>,
+    rx: mpsc::UnboundedReceiver<BackgroundReport>,
+}
+
+impl Default for BackgroundReports {
+    fn default() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self { tx, rx }
+    }
+}
+
+impl BackgroundReports {
+    /// Starts `task`; its reports arrive through `drain` once it finishes
+    pub fn spawn(&self, task: BackgroundTask) {
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            for report in task.await {
+                let _ = tx.send(report);
+            }
+        });
+    }
+
+    /// Reports that arrived since the last call, oldest first
+    pub fn drain(&mut self) -> Vec<BackgroundReport> {
+        std::iter::from_fn(|| self.rx.try_recv().ok()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn reports_arrive_once_the_task_finishes() {
+        let mut reports = BackgroundReports::default();
+        reports.spawn(Box::pin(async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            vec![
+                BackgroundReport { msg: "first".into(), failed: false },
+                BackgroundReport { msg: "second".into(), failed: true },
+            ]
+        }));
+        assert!(reports.drain().is_empty());
+
+        let mut arrived = Vec::new();
+        while arrived.len() < 2 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            arrived.extend(reports.drain());
+        }
+        assert_eq!(arrived.iter().map(|r| r.msg.as_str()).collect::<Vec<_>>(), ["first", "second"]);
+        assert!(arrived[1].failed);
+    }
+}
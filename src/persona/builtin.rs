@@ -0,0 +1,209 @@
+//! # Daegonica Module: persona::builtin
+//!
+//! **Purpose:** Personas compiled into the binary as a fallback
+//!
+//! **Context:**
+//! - Used when the binary runs outside the repo (cargo install, copied elsewhere)
+//! - On-disk personas with the same name always take precedence
+//!
+//! **Responsibilities:**
+//! - Embed the default persona YAML files with include_str!
+//! - Parse embedded personas on demand
+//! - Export embedded personas to disk for customization, prompt in its own file by default
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use crate::prelude::*;
+
+/// # BUILTIN_PERSONAS
+///
+/// **Summary:**
+/// Embedded (persona_name, yaml_contents) pairs shipped with the binary.
+pub const BUILTIN_PERSONAS: &[(&str, &str)] = &[
+    ("shadow", include_str!("../../personas/shadow/shadow.yaml")),
+    ("historian", include_str!("../../personas/historian/historian.yaml")),
+    ("reviewer", include_str!("../../personas/reviewer/reviewer.yaml")),
+];
+
+/// File name an exported persona's prompt is written to, next to its YAML
+pub const PROMPT_FILE_NAME: &str = "prompt.md";
+
+/// # builtin_yaml
+///
+/// **Purpose:**
+/// Looks up the embedded YAML for a built-in persona.
+///
+/// **Parameters:**
+/// - `name`: Persona name (e.g., "shadow")
+///
+/// **Returns:**
+/// `Option<&'static str>` - Embedded YAML, or None if no such built-in exists
+pub fn builtin_yaml(name: &str) -> Option<&'static str> {
+    BUILTIN_PERSONAS.iter()
+        .find(|(builtin_name, _)| *builtin_name == name)
+        .map(|(_, yaml)| *yaml)
+}
+
+/// # load_builtin
+///
+/// **Purpose:**
+/// Parses an embedded persona and marks it as built-in.
+///
+/// **Parameters:**
+/// - `name`: Persona name (e.g., "historian")
+///
+/// **Returns:**
+/// `anyhow::Result<Persona>` - Parsed persona or error
+///
+/// **Errors / Failures:**
+/// - No built-in persona with that name
+/// - Embedded YAML fails to parse (should never happen for shipped files)
+pub fn load_builtin(name: &str) -> anyhow::Result<Persona> {
+    let yaml = builtin_yaml(name)
+        .ok_or_else(|| anyhow::anyhow!("No built-in persona named '{}'", name))?;
+    let mut persona = Persona::from_yaml_str(yaml)?;
+    persona.is_builtin = true;
+    Ok(persona)
+}
+
+/// # prompt_file_layout
+///
+/// **Purpose:**
+/// Rewrites persona YAML to point at `PROMPT_FILE_NAME` instead of carrying its prompt inline.
+///
+/// **Returns:**
+/// `Option<String>` - The rewritten YAML, or None if it has no top-level `system_prompt`
+///
+/// **Details:**
+/// Works on the text so comments and key order survive: the `system_prompt`
+/// line and its indented continuation lines are replaced by one
+/// `system_prompt_file` line. Blank lines after the block are kept.
+pub fn prompt_file_layout(yaml: &str) -> Option<String> {
+    let lines: Vec<&str> = yaml.lines().collect();
+    let start = lines.iter().position(|line| line.starts_with("system_prompt:"))?;
+    let mut end = start + 1;
+    while end < lines.len() && (lines[end].starts_with([' ', '\t']) || lines[end].trim().is_empty()) {
+        end += 1;
+    }
+    while end > start + 1 && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+
+    let mut rewritten: Vec<String> = lines[..start].iter().map(|line| line.to_string()).collect();
+    rewritten.push(format!("system_prompt_file: {}", PROMPT_FILE_NAME));
+    rewritten.extend(lines[end..].iter().map(|line| line.to_string()));
+    Some(rewritten.join("\n") + "\n")
+}
+
+/// # export_builtins
+///
+/// **Purpose:**
+/// Writes the embedded personas to disk so they can be customized.
+///
+/// **Parameters:**
+/// - `dir`: Target personas directory
+/// - `inline`: Keep the prompt inside the YAML instead of writing `prompt.md`
+///
+/// **Returns:**
+/// `Result<Vec<PathBuf>, ShadowError>` - Paths written (existing files are left untouched)
+///
+/// **File Location:**
+/// `{dir}/{persona_name}/{persona_name}.yaml`, plus `{dir}/{persona_name}/prompt.md`
+/// unless `inline` (or a `prompt.md` is already there, which is never overwritten)
+///
+/// **Errors / Failures:**
+/// - Directory creation failures
+/// - File write permission errors
+///
+/// **Examples:**
+/// ```no_run
+/// # use std::path::Path;
+/// # use grokprime_brain::persona::builtin::export_builtins;
+/// # fn main() -> Result<(), grokprime_brain::errors::ShadowError> {
+/// let written = export_builtins(Path::new("personas"), false)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn export_builtins(dir: &Path, inline: bool) -> Result<Vec<PathBuf>, ShadowError> {
+    let mut written = Vec::new();
+
+    for (name, yaml) in BUILTIN_PERSONAS {
+        let persona_dir = dir.join(name);
+        let path = persona_dir.join(format!("{}.yaml", name));
+        let prompt_path = persona_dir.join(PROMPT_FILE_NAME);
+
+        if path.exists() {
+            log_info!("Skipping export of {}: {} already exists", name, path.display());
+            continue;
+        }
+
+        fs::create_dir_all(&persona_dir)?;
+        let split = if inline || prompt_path.exists() {
+            None
+        } else {
+            prompt_file_layout(yaml).zip(load_builtin(name).ok())
+        };
+        match split {
+            Some((layout, persona)) => {
+                fs::write(&prompt_path, &persona.system_prompt)?;
+                written.push(prompt_path);
+                fs::write(&path, layout)?;
+            }
+            None => fs::write(&path, yaml)?,
+        }
+        log_info!("Exported built-in persona {} to {}", name, path.display());
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScratchDir;
+
+    #[test]
+    fn the_prompt_block_becomes_one_file_reference() {
+        let yaml = "# Friday\nname: friday\nsystem_prompt: |\n  Be brief.\n\n  Never guess.\n\n# Sampling\ntemperature: 0.3\n";
+        assert_eq!(
+            prompt_file_layout(yaml).as_deref(),
+            Some("# Friday\nname: friday\nsystem_prompt_file: prompt.md\n\n# Sampling\ntemperature: 0.3\n"),
+            "comments and the blank line after the block survive"
+        );
+        assert_eq!(prompt_file_layout("name: friday\nsystem_prompt_file: prompt.md\n"), None);
+    }
+
+    #[test]
+    fn exported_personas_load_back_with_the_same_prompt() {
+        let dir = ScratchDir::new("export-split");
+        let written = export_builtins(dir.path(), false).unwrap();
+        assert_eq!(written.len(), BUILTIN_PERSONAS.len() * 2, "a YAML and a prompt.md each");
+
+        for (name, _) in BUILTIN_PERSONAS {
+            let exported = Persona::from_yaml_file(&dir.join(format!("{0}/{0}.yaml", name))).unwrap();
+            assert_eq!(exported.system_prompt_file.as_deref(), Some(Path::new(PROMPT_FILE_NAME)), "{}", name);
+            assert_eq!(exported.system_prompt, load_builtin(name).unwrap().system_prompt, "{}", name);
+        }
+    }
+
+    #[test]
+    fn inline_export_and_an_existing_prompt_file_keep_the_prompt_in_the_yaml() {
+        let inline = ScratchDir::new("export-inline");
+        assert_eq!(export_builtins(inline.path(), true).unwrap().len(), BUILTIN_PERSONAS.len());
+        assert!(!inline.join(format!("{}/{}", BUILTIN_PERSONAS[0].0, PROMPT_FILE_NAME)).exists());
+
+        let kept = ScratchDir::new("export-existing-prompt");
+        let (name, yaml) = BUILTIN_PERSONAS[0];
+        let prompt = kept.write(format!("{}/{}", name, PROMPT_FILE_NAME), "My own notes.");
+        export_builtins(kept.path(), false).unwrap();
+        assert_eq!(fs::read_to_string(&prompt).unwrap(), "My own notes.", "never overwritten");
+        assert_eq!(fs::read_to_string(kept.join(format!("{0}/{0}.yaml", name))).unwrap(), *yaml);
+    }
+}
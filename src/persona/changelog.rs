@@ -0,0 +1,369 @@
+//! # Daegonica Module: persona::changelog
+//!
+//! **Purpose:** Audit trail of persona prompt and setting changes
+//!
+//! **Context:**
+//! - Persona YAML edits materially change agent behavior
+//! - Called whenever personas are (re)loaded
+//!
+//! **Responsibilities:**
+//! - Fingerprint personas with a stable content hash
+//! - Compare against the last seen snapshot and append changes to `changelog.jsonl`
+//! - Read the changelog back for display
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::collections::HashSet;
+use chrono::{DateTime, Utc};
+use crate::prelude::*;
+use crate::persona::persona_dir;
+use crate::utilities::time::{format_rfc3339, Zone};
+
+/// # content_hash
+///
+/// **Purpose:**
+/// Stable 64-bit FNV-1a hash of text, hex encoded.
+///
+/// **Details:**
+/// Unlike `DefaultHasher`, the output never changes between Rust versions,
+/// so hashes written to disk stay comparable.
+pub fn content_hash(text: &str) -> String {
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let hash = text.bytes()
+        .fold(OFFSET, |acc, b| (acc ^ b as u64).wrapping_mul(PRIME));
+    format!("{:016x}", hash)
+}
+
+/// # PersonaFingerprint
+///
+/// **Summary:**
+/// The behavior-relevant parts of a persona, used to detect changes.
+///
+/// **Details:**
+/// `prompt_hash` covers the prompt text itself, wherever it was loaded from,
+/// so moving a prompt into `system_prompt_file` is not a change but editing it there is.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonaFingerprint {
+    pub prompt_hash: String,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub enable_history: bool,
+    pub history_message_limit: usize,
+    pub summary_threshold: usize,
+    pub api_provider: String,
+    pub tools: Option<Vec<String>>,
+    #[serde(default)]
+    pub summarize_mode: SummarizeMode,
+}
+
+impl PersonaFingerprint {
+    pub fn of(persona: &Persona) -> Self {
+        Self {
+            prompt_hash: content_hash(&persona.system_prompt),
+            temperature: persona.temperature,
+            max_tokens: persona.max_tokens,
+            enable_history: persona.enable_history,
+            history_message_limit: persona.history_message_limit,
+            summary_threshold: persona.summary_threshold,
+            api_provider: persona.api_provider.clone(),
+            tools: persona.tools.clone(),
+            summarize_mode: persona.summarize_mode,
+        }
+    }
+
+    /// # changed_fields
+    ///
+    /// **Purpose:**
+    /// Lists the names of fields that differ from an older fingerprint.
+    pub fn changed_fields(&self, previous: &PersonaFingerprint) -> Vec<String> {
+        let checks = [
+            ("prompt", self.prompt_hash != previous.prompt_hash),
+            ("temperature", self.temperature != previous.temperature),
+            ("max_tokens", self.max_tokens != previous.max_tokens),
+            ("enable_history", self.enable_history != previous.enable_history),
+            ("history_message_limit", self.history_message_limit != previous.history_message_limit),
+            ("summary_threshold", self.summary_threshold != previous.summary_threshold),
+            ("api_provider", self.api_provider != previous.api_provider),
+            ("tools", self.tools != previous.tools),
+            ("summarize_mode", self.summarize_mode != previous.summarize_mode),
+        ];
+
+        checks.iter()
+            .filter(|(_, changed)| *changed)
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+}
+
+/// Last seen state of a persona (`{root}/{name}/persona_snapshot.json`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersonaSnapshot {
+    fingerprint: PersonaFingerprint,
+    system_prompt: String,
+}
+
+/// # ChangelogEntry
+///
+/// **Summary:**
+/// One line of `{root}/{name}/changelog.jsonl`.
+///
+/// **Fields:**
+/// - `timestamp`: RFC3339 time the change was noticed
+/// - `changed_fields`: Names of fields that changed
+/// - `prompt_hash_before` / `prompt_hash_after`: Prompt hashes around the change
+/// - `prompt_diff`: Line diff summary when the prompt changed
+/// - `before` / `after`: Full fingerprints for the non-prompt fields
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangelogEntry {
+    pub timestamp: String,
+    pub changed_fields: Vec<String>,
+    pub prompt_hash_before: String,
+    pub prompt_hash_after: String,
+    #[serde(default)]
+    pub prompt_diff: Option<String>,
+    pub before: PersonaFingerprint,
+    pub after: PersonaFingerprint,
+}
+
+impl ChangelogEntry {
+    pub fn changed_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.timestamp)
+            .ok()
+            .map(|t| t.with_timezone(&Utc))
+    }
+
+    /// One-line summary for `persona history`
+    pub fn describe(&self) -> String {
+        let when = format_rfc3339(&self.timestamp, Zone::Local);
+        let mut line = format!("{}: {}", when, self.changed_fields.join(", "));
+        if let Some(diff) = &self.prompt_diff {
+            line.push_str(&format!(" (prompt {})", diff));
+        }
+        line
+    }
+}
+
+fn snapshot_path(persona_name: &str) -> PathBuf {
+    persona_dir(persona_name).join("persona_snapshot.json")
+}
+
+fn changelog_path(persona_name: &str) -> PathBuf {
+    persona_dir(persona_name).join("changelog.jsonl")
+}
+
+/// # prompt_diff_summary
+///
+/// **Purpose:**
+/// Summarizes how many prompt lines were added and removed.
+fn prompt_diff_summary(old: &str, new: &str) -> String {
+    let old_lines: HashSet<&str> = old.lines().map(str::trim).collect();
+    let new_lines: HashSet<&str> = new.lines().map(str::trim).collect();
+
+    let added = new_lines.difference(&old_lines).count();
+    let removed = old_lines.difference(&new_lines).count();
+    format!("+{}/-{} lines", added, removed)
+}
+
+/// # record_persona_load
+///
+/// **Purpose:**
+/// Compares a freshly loaded persona with its last seen snapshot and logs any change.
+///
+/// **Parameters:**
+/// - `persona`: The persona that was just loaded
+///
+/// **Returns:**
+/// `Result<Option<ChangelogEntry>, ShadowError>` - The appended entry, or None if
+/// nothing changed, this is the first load, or the persona is built-in
+///
+/// **Errors / Failures:**
+/// - Snapshot or changelog cannot be read/written
+pub fn record_persona_load(persona: &Persona) -> Result<Option<ChangelogEntry>, ShadowError> {
+    if persona.is_builtin {
+        return Ok(None);
+    }
+
+    let fingerprint = PersonaFingerprint::of(persona);
+    let path = snapshot_path(&persona.name);
+
+    let previous: Option<PersonaSnapshot> = match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).ok(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    let entry = previous.and_then(|previous| {
+        let changed_fields = fingerprint.changed_fields(&previous.fingerprint);
+        if changed_fields.is_empty() {
+            return None;
+        }
+
+        let prompt_diff = changed_fields.iter()
+            .any(|field| field == "prompt")
+            .then(|| prompt_diff_summary(&previous.system_prompt, &persona.system_prompt));
+
+        Some(ChangelogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            changed_fields,
+            prompt_hash_before: previous.fingerprint.prompt_hash.clone(),
+            prompt_hash_after: fingerprint.prompt_hash.clone(),
+            prompt_diff,
+            before: previous.fingerprint,
+            after: fingerprint.clone(),
+        })
+    });
+
+    if let Some(entry) = &entry {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(changelog_path(&persona.name))?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        log_info!("Persona {} changed: {}", persona.name, entry.describe());
+    }
+
+    let snapshot = PersonaSnapshot {
+        fingerprint,
+        system_prompt: persona.system_prompt.clone(),
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(&snapshot)?)?;
+
+    Ok(entry)
+}
+
+/// # read_changelog
+///
+/// **Purpose:**
+/// Reads every changelog entry for a persona, oldest first.
+///
+/// **Returns:**
+/// `Result<Vec<ChangelogEntry>, ShadowError>` - Entries (empty if no changes were recorded)
+pub fn read_changelog(persona_name: &str) -> Result<Vec<ChangelogEntry>, ShadowError> {
+    let content = match fs::read_to_string(changelog_path(persona_name)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(content.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_personas_root;
+
+    fn persona(name: &str, prompt: &str, temperature: f32, summary_threshold: usize) -> Persona {
+        Persona::from_yaml_str(&format!(
+            "name: {}\nsystem_prompt: \"{}\"\ntemperature: {}\nhistory_message_limit: 10\nsummary_threshold: {}\n",
+            name, prompt, temperature, summary_threshold,
+        )).unwrap()
+    }
+
+    #[test]
+    fn the_hash_is_stable_and_tracks_the_text() {
+        assert_eq!(content_hash(""), "cbf29ce484222325", "the FNV offset basis");
+        assert_eq!(content_hash("Be brief."), content_hash("Be brief."));
+        assert_ne!(content_hash("Be brief."), content_hash("Be brief!"));
+        assert_eq!(content_hash("Be brief.").len(), 16);
+    }
+
+    #[test]
+    fn the_prompt_diff_counts_lines_added_and_removed() {
+        let cases = [
+            ("a\nb", "a\nb", "+0/-0 lines"),
+            ("a\nb", "a\nb\nc", "+1/-0 lines"),
+            ("a\nb\nc", "a", "+0/-2 lines"),
+            ("a\nb", "a\nc\nd", "+2/-1 lines"),
+            ("  a  \nb", "a\nb", "+0/-0 lines"),
+        ];
+        for (old, new, expected) in cases {
+            assert_eq!(prompt_diff_summary(old, new), expected, "{:?} -> {:?}", old, new);
+        }
+    }
+
+    #[test]
+    fn the_first_load_only_takes_a_snapshot() {
+        test_personas_root();
+        let first = persona("changelog_first", "Be brief.", 0.7, 20);
+
+        assert!(record_persona_load(&first).unwrap().is_none());
+        assert!(snapshot_path("changelog_first").exists());
+        assert!(read_changelog("changelog_first").unwrap().is_empty());
+    }
+
+    #[test]
+    fn an_unchanged_reload_adds_nothing() {
+        test_personas_root();
+        let same = persona("changelog_same", "Be brief.", 0.7, 20);
+
+        record_persona_load(&same).unwrap();
+        assert!(record_persona_load(&same).unwrap().is_none());
+        assert!(record_persona_load(&same.clone()).unwrap().is_none());
+        assert!(!changelog_path("changelog_same").exists());
+    }
+
+    #[test]
+    fn a_multi_field_change_is_one_entry() {
+        test_personas_root();
+        let before = persona("changelog_multi", "Be brief.\\nUse lists.", 0.7, 20);
+        let after = persona("changelog_multi", "Be brief.\\nUse tables.\\nCite sources.", 1.1, 30);
+        record_persona_load(&before).unwrap();
+
+        let entry = record_persona_load(&after).unwrap().expect("a change was recorded");
+
+        assert_eq!(entry.changed_fields, ["prompt", "temperature", "summary_threshold"]);
+        assert_eq!(entry.prompt_diff.as_deref(), Some("+2/-1 lines"));
+        assert_eq!(entry.prompt_hash_before, content_hash(&before.system_prompt));
+        assert_eq!(entry.prompt_hash_after, content_hash(&after.system_prompt));
+        assert_eq!((entry.before.temperature, entry.after.temperature), (Some(0.7), Some(1.1)));
+        assert_eq!((entry.before.summary_threshold, entry.after.summary_threshold), (20, 30));
+        assert!(entry.changed_at().is_some());
+        assert!(entry.describe().ends_with(": prompt, temperature, summary_threshold (prompt +2/-1 lines)"), "{}", entry.describe());
+
+        // Reloading the new version is not another change
+        assert!(record_persona_load(&after).unwrap().is_none());
+        let logged = read_changelog("changelog_multi").unwrap();
+        assert_eq!(logged.len(), 1);
+        assert_eq!(logged[0].changed_fields, entry.changed_fields);
+    }
+
+    #[test]
+    fn entries_read_back_oldest_first() {
+        test_personas_root();
+        record_persona_load(&persona("changelog_order", "Be brief.", 0.7, 20)).unwrap();
+        record_persona_load(&persona("changelog_order", "Be brief.", 0.9, 20)).unwrap();
+        let last = record_persona_load(&persona("changelog_order", "Be terse.", 0.9, 20)).unwrap().unwrap();
+
+        assert_eq!(last.prompt_diff.as_deref(), Some("+1/-1 lines"));
+        let fields: Vec<Vec<String>> = read_changelog("changelog_order").unwrap()
+            .into_iter()
+            .map(|entry| entry.changed_fields)
+            .collect();
+        assert_eq!(fields, [vec!["temperature".to_string()], vec!["prompt".to_string()]]);
+    }
+
+    #[test]
+    fn builtin_personas_are_not_tracked() {
+        test_personas_root();
+        let mut builtin = persona("changelog_builtin", "Be brief.", 0.7, 20);
+        builtin.is_builtin = true;
+
+        assert!(record_persona_load(&builtin).unwrap().is_none());
+        assert!(!snapshot_path("changelog_builtin").exists());
+    }
+}
@@ -0,0 +1,563 @@
+//! # Daegonica Module: persona::chunk_stream
+//!
+//! **Purpose:** Typed handle for sending into an agent's chunk channel
+//!
+//! **Context:**
+//! - Replies, jobs and background reports all push into the same per-agent channel
+//! - The pane only behaves if each reply ends exactly once (Complete or Error),
+//!   with no text after it; a stray or missing end leaves the pane stuck waiting
+//!
+//! **Responsibilities:**
+//! - Define `ChunkStream`, whose methods only allow chunks in a valid order
+//! - Tag every chunk with its task kind, stream id and sequence number
+//! - Send a failure for a stream dropped before it ended (aborted or panicked task)
+//! - Let the pane see when a reply has been committed to history (`CommitFlag`)
+//! - Define `ChunkSequencer`, which checks tags on the receiving side
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use strum::Display;
+use thiserror::Error;
+use crate::prelude::*;
+use crate::persona::jobs::JobId;
+
+/// Stream ids are unique for the lifetime of the process
+static NEXT_STREAM: AtomicU64 = AtomicU64::new(1);
+
+/// How many streams `ChunkSequencer` remembers per agent
+const TRACKED_STREAMS: usize = 32;
+
+/// The sending half of an agent's chunk channel
+pub type ChunkSender = mpsc::UnboundedSender<TaggedChunk>;
+
+/// The receiving half of an agent's chunk channel
+pub type ChunkReceiver = mpsc::UnboundedReceiver<TaggedChunk>;
+
+/// # TaskKind
+///
+/// **Summary:**
+/// What kind of work a stream belongs to.
+///
+/// **Variants:**
+/// - `Reply`: A model reply streaming into the pane (chat, drafts, check-ins)
+/// - `Job`: The lifecycle of a background generation job
+/// - `Report`: Loose messages from background work, e.g. tweet posting output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum TaskKind {
+    Reply,
+    Job,
+    Report,
+}
+
+/// # TaggedChunk
+///
+/// **Summary:**
+/// A `StreamChunk` as it travels through the channel.
+///
+/// **Fields:**
+/// - `kind`: Kind of work that sent it
+/// - `stream`: Id of the `ChunkStream` that sent it
+/// - `seq`: Position within that stream, from 0 with no gaps
+/// - `chunk`: The chunk itself
+#[derive(Debug, Clone)]
+pub struct TaggedChunk {
+    pub kind: TaskKind,
+    pub stream: u64,
+    pub seq: u64,
+    pub chunk: StreamChunk,
+}
+
+/// # StreamClosed
+///
+/// **Summary:**
+/// The agent's end of the channel is gone (its pane was closed).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("the agent's chunk channel is closed")]
+pub struct StreamClosed;
+
+/// Where a stream is in its lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StreamState {
+    Idle,
+    Streaming,
+    Finished,
+}
+
+/// What a chunk does to the lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    /// Starts the stream
+    Begin,
+    /// Needs a started stream (reply text)
+    Body,
+    /// Allowed at any point (Info, Notice, job progress)
+    Aside,
+    /// Ends a started stream
+    Finish,
+    /// Ends a stream, started or not
+    Fail,
+}
+
+/// # CommitFlag
+///
+/// **Summary:**
+/// Read side of a reply stream's commit marker, held by the pane.
+///
+/// **Details:**
+/// Set by `ChunkStream::commit` once the reply is in history with its
+/// response id. The task may still be saving or summarizing after that, and
+/// aborting it then would leave a saved reply marked cancelled and a
+/// summarization half done.
+#[derive(Debug, Clone, Default)]
+pub struct CommitFlag(Arc<AtomicBool>);
+
+impl CommitFlag {
+    pub fn is_set(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// # ChunkStream
+///
+/// **Summary:**
+/// A typed sender for one reply, job or report on an agent's chunk channel.
+///
+/// **Fields:**
+/// - `tx`: The agent's channel
+/// - `kind`: Kind of work, copied into every chunk
+/// - `id`: Stream id, copied into every chunk
+/// - `next_seq`: Sequence number of the next chunk
+/// - `state`: Idle until `begin`, Finished after `complete` / `fail`
+/// - `job`: Job number and name, for `TaskKind::Job` streams
+/// - `committed`: Set by `commit`; shared with whoever holds `commit_flag`
+///
+/// **Details:**
+/// A stream is `begin`-ed, receives `delta`s, then ends with exactly one
+/// `complete` or `fail` (`fail` is also allowed before `begin`). `info` and
+/// `notice` may be sent at any point, including after the end. Anything
+/// else is a bug in the sender: debug builds panic on it, release builds
+/// log it and correct course (a missing `begin` is implied, a chunk after
+/// the end is dropped). A stream dropped after `begin` but before its end
+/// sends the failure itself, so an aborted or panicked task can't leave a
+/// pane waiting forever.
+///
+/// **Usage Example:**
+/// ```rust
+/// # use grokprime_brain::persona::chunk_stream::{ChunkStream, StreamClosed, TaskKind};
+/// # fn main() -> Result<(), StreamClosed> {
+/// # let (chunk_sender, _chunks) = tokio::sync::mpsc::unbounded_channel();
+/// # let (response_id, full_reply, usage) = ("resp_1".to_string(), "Hello".to_string(), None);
+/// let mut stream = ChunkStream::open(&chunk_sender, TaskKind::Reply);
+/// stream.begin();
+/// stream.delta("Hello")?;
+/// stream.complete(response_id, full_reply, usage)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ChunkStream {
+    tx: ChunkSender,
+    kind: TaskKind,
+    id: u64,
+    next_seq: u64,
+    state: StreamState,
+    job: Option<(JobId, String)>,
+    committed: CommitFlag,
+}
+
+impl ChunkStream {
+    /// Opens a new stream of `kind` on an agent's channel
+    pub fn open(tx: &ChunkSender, kind: TaskKind) -> Self {
+        Self {
+            tx: tx.clone(),
+            kind,
+            id: NEXT_STREAM.fetch_add(1, Ordering::Relaxed),
+            next_seq: 0,
+            state: StreamState::Idle,
+            job: None,
+            committed: CommitFlag::default(),
+        }
+    }
+
+    /// Opens the lifecycle stream for job `job` named `name`
+    pub fn for_job(tx: &ChunkSender, job: JobId, name: &str) -> Self {
+        let mut stream = Self::open(tx, TaskKind::Job);
+        stream.job = Some((job, name.to_string()));
+        stream
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn kind(&self) -> TaskKind {
+        self.kind
+    }
+
+    /// Marks the reply as committed to history; it can no longer be cancelled
+    pub fn commit(&self) {
+        self.committed.0.store(true, Ordering::Release);
+    }
+
+    /// A handle reporting whether `commit` has been called
+    pub fn commit_flag(&self) -> CommitFlag {
+        self.committed.clone()
+    }
+
+    /// True once `complete` or `fail` has been sent
+    pub fn is_finished(&self) -> bool {
+        self.state == StreamState::Finished
+    }
+
+    /// # begin
+    ///
+    /// **Purpose:**
+    /// Starts the stream. Job streams announce the job (JobStarted); reply streams send nothing.
+    pub fn begin(&mut self) {
+        let chunk = self.job.as_ref().map(|(job, name)| StreamChunk::JobStarted { job: *job, name: name.clone() });
+        let _ = self.emit(Step::Begin, chunk);
+    }
+
+    /// Streams reply text
+    pub fn delta(&mut self, text: impl Into<String>) -> Result<(), StreamClosed> {
+        self.emit(Step::Body, Some(StreamChunk::Delta(text.into())))
+    }
+
+    /// A note for the Global pane (JobProgress on a job stream)
+    pub fn info(&mut self, msg: impl Into<String>) -> Result<(), StreamClosed> {
+        let chunk = match &self.job {
+            Some((job, name)) => StreamChunk::JobProgress { job: *job, name: name.clone(), msg: msg.into() },
+            None => StreamChunk::Info(msg.into()),
+        };
+        self.emit(Step::Aside, Some(chunk))
+    }
+
+    /// A quiet note for the agent's own pane
+    pub fn notice(&mut self, msg: impl Into<String>) -> Result<(), StreamClosed> {
+        self.emit(Step::Aside, Some(StreamChunk::Notice(msg.into())))
+    }
+
+    /// Ends the stream with an error (JobFailed on a job stream)
+    pub fn fail(&mut self, error: impl Into<String>) -> Result<(), StreamClosed> {
+        let chunk = match &self.job {
+            Some((job, name)) => StreamChunk::JobFailed { job: *job, name: name.clone(), error: error.into() },
+            None => StreamChunk::Error(error.into()),
+        };
+        self.emit(Step::Fail, Some(chunk))
+    }
+
+    /// Ends a reply stream with the finished reply
+    pub fn complete(&mut self, response_id: String, full_reply: String, usage: Option<Usage>) -> Result<(), StreamClosed> {
+        self.emit(Step::Finish, Some(StreamChunk::Complete { response_id, full_reply, usage }))
+    }
+
+    /// Ends a job stream with the job's result message
+    pub fn complete_job(&mut self, msg: impl Into<String>) -> Result<(), StreamClosed> {
+        let chunk = self.job.as_ref().map(|(job, name)| StreamChunk::JobCompleted { job: *job, name: name.clone(), msg: msg.into() });
+        if chunk.is_none() {
+            self.violation("complete_job on a stream that isn't a job");
+        }
+        self.emit(Step::Finish, chunk)
+    }
+
+    /// # emit
+    ///
+    /// **Purpose:**
+    /// Applies `step` to the lifecycle and sends `chunk` if the step is allowed.
+    ///
+    /// **Details:**
+    /// Illegal steps go through `violation`. When it returns (release
+    /// builds), a Body or Finish step before `begin` is let through as if the
+    /// stream had begun, and anything but an Aside after the end is dropped.
+    fn emit(&mut self, step: Step, chunk: Option<StreamChunk>) -> Result<(), StreamClosed> {
+        match (self.state, step) {
+            (StreamState::Finished, Step::Aside) => {}
+            (StreamState::Finished, _) => {
+                self.violation(&format!("{:?} after the stream ended", step));
+                return Ok(());
+            }
+            (StreamState::Streaming, Step::Begin) => {
+                self.violation("begin on a stream that already began");
+                return Ok(());
+            }
+            (StreamState::Idle, Step::Body | Step::Finish) => {
+                self.violation(&format!("{:?} before begin", step));
+            }
+            _ => {}
+        }
+
+        self.state = match step {
+            Step::Begin | Step::Body => StreamState::Streaming,
+            Step::Finish | Step::Fail => StreamState::Finished,
+            Step::Aside => self.state,
+        };
+        let Some(chunk) = chunk else {
+            return Ok(());
+        };
+
+        let tagged = TaggedChunk { kind: self.kind, stream: self.id, seq: self.next_seq, chunk };
+        self.next_seq += 1;
+        self.tx.send(tagged).map_err(|_| StreamClosed)
+    }
+
+    /// Panics in debug builds; logs in release builds, where the caller corrects course
+    fn violation(&self, what: &str) {
+        let msg = format!("Chunk stream {} ({}): {}", self.id, self.kind, what);
+        if cfg!(debug_assertions) && !std::thread::panicking() {
+            panic!("{}", msg);
+        }
+        log_error!("{}", msg);
+    }
+}
+
+impl Drop for ChunkStream {
+    fn drop(&mut self) {
+        if self.state == StreamState::Streaming {
+            log_error!("Chunk stream {} ({}) dropped before it ended", self.id, self.kind);
+            let error = match self.kind {
+                TaskKind::Job => "stopped before finishing",
+                _ => "The reply stopped before it finished",
+            };
+            let _ = self.fail(error);
+        }
+    }
+}
+
+/// # ChunkSequencer
+///
+/// **Summary:**
+/// Receiving-side check that each stream's chunks arrive complete and in order.
+///
+/// **Fields:**
+/// - `streams`: Recent stream ids with the next sequence number expected from each
+/// - `gaps`: Chunks that arrived after one or more were skipped
+/// - `reordered`: Chunks that arrived behind one already seen
+///
+/// **Details:**
+/// Only the last `TRACKED_STREAMS` streams are remembered; a forgotten
+/// stream that sends again counts as a gap.
+#[derive(Debug, Default)]
+pub struct ChunkSequencer {
+    streams: VecDeque<(u64, u64)>,
+    pub gaps: u32,
+    pub reordered: u32,
+}
+
+impl ChunkSequencer {
+    /// # check
+    ///
+    /// **Purpose:**
+    /// Records `chunk` and reports whether it was the one expected next from its stream.
+    ///
+    /// **Returns:**
+    /// `bool` - false for a gap or an out-of-order chunk (already logged and counted)
+    pub fn check(&mut self, chunk: &TaggedChunk) -> bool {
+        let index = self.streams.iter().position(|(stream, _)| *stream == chunk.stream);
+        let expected = index.map(|i| self.streams[i].1).unwrap_or(0);
+
+        match index {
+            Some(i) => self.streams[i].1 = expected.max(chunk.seq + 1),
+            None => {
+                if self.streams.len() == TRACKED_STREAMS {
+                    self.streams.pop_front();
+                }
+                self.streams.push_back((chunk.stream, chunk.seq + 1));
+            }
+        }
+
+        if chunk.seq == expected {
+            return true;
+        }
+        if chunk.seq > expected {
+            self.gaps += 1;
+            log_error!("Chunk stream {} ({}): expected chunk {}, got {}", chunk.stream, chunk.kind, expected, chunk.seq);
+        } else {
+            self.reordered += 1;
+            log_error!("Chunk stream {} ({}): chunk {} arrived after {}", chunk.stream, chunk.kind, chunk.seq, expected - 1);
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commit_flag_follows_its_stream() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut stream = ChunkStream::open(&tx, TaskKind::Reply);
+        let flag = stream.commit_flag();
+        stream.begin();
+        stream.delta("partial").unwrap();
+        assert!(!flag.is_set());
+
+        stream.commit();
+        assert!(flag.is_set());
+        stream.complete("resp".to_string(), "partial".to_string(), None).unwrap();
+    }
+
+    #[test]
+    fn each_stream_has_its_own_commit_flag() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let first = ChunkStream::open(&tx, TaskKind::Reply);
+        let second = ChunkStream::open(&tx, TaskKind::Reply);
+        first.commit();
+        assert!(first.commit_flag().is_set());
+        assert!(!second.commit_flag().is_set());
+    }
+
+    /// Everything sent so far, as (seq, chunk)
+    fn received(rx: &mut ChunkReceiver) -> Vec<(u64, StreamChunk)> {
+        std::iter::from_fn(|| rx.try_recv().ok()).map(|tagged| (tagged.seq, tagged.chunk)).collect()
+    }
+
+    fn finished_reply(tx: &ChunkSender) -> ChunkStream {
+        let mut stream = ChunkStream::open(tx, TaskKind::Reply);
+        stream.begin();
+        stream.delta("done").unwrap();
+        stream.complete("resp".to_string(), "done".to_string(), None).unwrap();
+        stream
+    }
+
+    #[test]
+    fn a_reply_sends_its_chunks_in_order_and_asides_after_the_end() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut stream = finished_reply(&tx);
+        stream.notice("saved").unwrap();
+        assert!(stream.is_finished());
+        drop(stream);
+
+        let chunks = received(&mut rx);
+        let seqs: Vec<u64> = chunks.iter().map(|(seq, _)| *seq).collect();
+        assert_eq!(seqs, [0, 1, 2]);
+        assert!(matches!(&chunks[0].1, StreamChunk::Delta(text) if text == "done"));
+        assert!(matches!(&chunks[1].1, StreamChunk::Complete { response_id, .. } if response_id == "resp"));
+        assert!(matches!(&chunks[2].1, StreamChunk::Notice(msg) if msg == "saved"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Body after the stream ended")]
+    fn delta_after_complete_is_a_violation() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let _ = finished_reply(&tx).delta("late");
+    }
+
+    #[test]
+    #[should_panic(expected = "Finish after the stream ended")]
+    fn a_second_complete_is_a_violation() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let _ = finished_reply(&tx).complete("resp".to_string(), "again".to_string(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Fail after the stream ended")]
+    fn fail_after_complete_is_a_violation() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let _ = finished_reply(&tx).fail("too late");
+    }
+
+    #[test]
+    #[should_panic(expected = "begin on a stream that already began")]
+    fn beginning_twice_is_a_violation() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut stream = ChunkStream::open(&tx, TaskKind::Reply);
+        stream.begin();
+        stream.begin();
+    }
+
+    #[test]
+    #[should_panic(expected = "Body before begin")]
+    fn delta_before_begin_is_a_violation() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let _ = ChunkStream::open(&tx, TaskKind::Reply).delta("early");
+    }
+
+    #[test]
+    #[should_panic(expected = "Finish before begin")]
+    fn complete_before_begin_is_a_violation() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let _ = ChunkStream::open(&tx, TaskKind::Reply).complete("resp".to_string(), String::new(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "complete_job on a stream that isn't a job")]
+    fn complete_job_on_a_reply_is_a_violation() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut stream = ChunkStream::open(&tx, TaskKind::Reply);
+        stream.begin();
+        let _ = stream.complete_job("done");
+    }
+
+    #[test]
+    fn failing_before_begin_is_allowed() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut stream = ChunkStream::open(&tx, TaskKind::Reply);
+        stream.fail("no credentials").unwrap();
+        assert!(stream.is_finished());
+        drop(stream);
+        assert!(matches!(received(&mut rx).as_slice(), [(0, StreamChunk::Error(error))] if error == "no credentials"));
+    }
+
+    #[test]
+    fn a_reply_dropped_mid_stream_sends_its_own_failure() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut stream = ChunkStream::open(&tx, TaskKind::Reply);
+        let id = stream.id();
+        stream.begin();
+        stream.delta("half a rep").unwrap();
+        drop(stream);
+
+        let tagged: Vec<TaggedChunk> = std::iter::from_fn(|| rx.try_recv().ok()).collect();
+        assert_eq!(tagged.len(), 2);
+        assert!(tagged.iter().all(|chunk| chunk.stream == id && chunk.kind == TaskKind::Reply));
+        assert_eq!(tagged[1].seq, 1);
+        assert!(matches!(&tagged[1].chunk, StreamChunk::Error(error) if error == "The reply stopped before it finished"));
+    }
+
+    #[test]
+    fn a_job_dropped_mid_stream_reports_the_job_failed() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut stream = ChunkStream::for_job(&tx, 7, "summarize");
+        stream.begin();
+        stream.info("halfway").unwrap();
+        drop(stream);
+
+        let chunks = received(&mut rx);
+        assert_eq!(chunks.len(), 3);
+        assert!(matches!(&chunks[0].1, StreamChunk::JobStarted { job: 7, name } if name == "summarize"));
+        assert!(matches!(&chunks[1].1, StreamChunk::JobProgress { msg, .. } if msg == "halfway"));
+        assert!(matches!(&chunks[2].1, StreamChunk::JobFailed { job: 7, error, .. } if error == "stopped before finishing"));
+    }
+
+    #[test]
+    fn streams_that_never_began_or_already_ended_drop_quietly() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        drop(ChunkStream::open(&tx, TaskKind::Reply));
+        drop(finished_reply(&tx));
+        assert_eq!(received(&mut rx).len(), 2, "only the finished reply's delta and complete");
+    }
+
+    #[test]
+    fn the_sequencer_counts_gaps_and_reordering() {
+        let tagged = |stream, seq| TaggedChunk { kind: TaskKind::Reply, stream, seq, chunk: StreamChunk::Delta(String::new()) };
+        let mut sequencer = ChunkSequencer::default();
+        assert!(sequencer.check(&tagged(1, 0)));
+        assert!(sequencer.check(&tagged(2, 0)), "streams are tracked separately");
+        assert!(sequencer.check(&tagged(1, 1)));
+        assert!(!sequencer.check(&tagged(1, 3)));
+        assert!(!sequencer.check(&tagged(1, 2)));
+        assert!(sequencer.check(&tagged(1, 4)));
+        assert_eq!((sequencer.gaps, sequencer.reordered), (1, 1));
+    }
+}
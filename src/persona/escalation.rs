@@ -0,0 +1,326 @@
+//! # Daegonica Module: persona::escalation
+//!
+//! **Purpose:** Follow up on a check-in the user hasn't answered
+//!
+//! **Context:**
+//! - Armed when the focus check-in is sent to the check-in persona
+//! - `ShadowApp::poll_channels` asks whether a follow-up is due; any user message to that persona disarms it
+//! - Policy comes from `TuiConfig::check_in_escalation`
+//!
+//! **Responsibilities:**
+//! - Define `PendingEscalation` and the timing of each follow-up
+//! - Persist the pending state so a restart mid-window resumes the same schedule
+//! - Decide whether the terminal bell may ring (quiet hours)
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use chrono::{DateTime, Local, NaiveTime, Utc};
+use crate::prelude::*;
+use crate::agent_history::persistence;
+use crate::persona::{state_path, writable_state_path};
+use crate::config::CheckInEscalation;
+
+/// The unanswered check-in, removed once answered or out of follow-ups
+pub(crate) const ESCALATION_FILE: &str = "check_in_escalation.json";
+
+/// # PendingEscalation
+///
+/// **Summary:**
+/// A check-in waiting for a reply, and when to nudge next.
+///
+/// **Fields:**
+/// - `persona`: Persona that sent the check-in; a user message to it answers
+/// - `checked_in_at`: When the check-in was sent
+/// - `escalations_sent`: Follow-ups sent so far
+/// - `next_at`: When the next follow-up is due
+///
+/// **Details:**
+/// Times are wall-clock and stored, so a restart picks up the same schedule.
+/// A follow-up that fell due while the app was closed is sent once on the next
+/// poll; the wait before the one after it counts from then, so there's never a
+/// burst of catch-up messages.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingEscalation {
+    pub persona: String,
+    pub checked_in_at: DateTime<Utc>,
+    pub escalations_sent: u32,
+    pub next_at: DateTime<Utc>,
+}
+
+impl PendingEscalation {
+    /// # new
+    ///
+    /// **Purpose:**
+    /// Arms the follow-ups for a check-in sent at `at`.
+    ///
+    /// **Returns:**
+    /// `Option<Self>` - None when the policy allows no follow-ups
+    pub fn new(persona: &str, at: DateTime<Utc>, policy: &CheckInEscalation) -> Option<Self> {
+        (policy.max_escalations > 0).then(|| Self {
+            persona: persona.to_string(),
+            checked_in_at: at,
+            escalations_sent: 0,
+            next_at: at + wait_before(0, policy),
+        })
+    }
+
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        now >= self.next_at
+    }
+
+    /// # escalate
+    ///
+    /// **Purpose:**
+    /// Records a follow-up sent at `now` and schedules the next one.
+    ///
+    /// **Returns:**
+    /// `bool` - Whether more follow-ups remain (false means drop the pending state)
+    pub fn escalate(&mut self, now: DateTime<Utc>, policy: &CheckInEscalation) -> bool {
+        self.escalations_sent += 1;
+        self.next_at = now + wait_before(self.escalations_sent, policy);
+        self.escalations_sent < policy.max_escalations
+    }
+
+    /// Progress label for the Global pane, e.g. "1/2"
+    pub fn progress(&self, policy: &CheckInEscalation) -> String {
+        format!("{}/{}", self.escalations_sent, policy.max_escalations)
+    }
+}
+
+/// # wait_before
+///
+/// **Purpose:**
+/// Wait before follow-up number `sent + 1`: `respond_within_minutes`, growing
+/// by `interval_growth` after each follow-up.
+pub fn wait_before(sent: u32, policy: &CheckInEscalation) -> chrono::Duration {
+    let growth = (policy.interval_growth.max(1) as i64).saturating_pow(sent);
+    chrono::Duration::minutes((policy.respond_within_minutes as i64).saturating_mul(growth))
+}
+
+/// # in_quiet_hours
+///
+/// **Purpose:**
+/// Whether `at` falls in the quiet range (start inclusive, end exclusive; a
+/// start after the end wraps past midnight).
+pub fn in_quiet_hours(quiet_hours: Option<(NaiveTime, NaiveTime)>, at: NaiveTime) -> bool {
+    match quiet_hours {
+        Some((start, end)) if start <= end => at >= start && at < end,
+        Some((start, end)) => at >= start || at < end,
+        None => false,
+    }
+}
+
+/// Whether a follow-up at `now` may ring the bell
+pub fn may_ring_bell(policy: &CheckInEscalation, now: DateTime<Utc>) -> bool {
+    let quiet = in_quiet_hours(policy.quiet_hours, now.with_timezone(&Local).time());
+    policy.bell && (!quiet || policy.bell_in_quiet_hours)
+}
+
+/// Rings the terminal bell (ignored if stdout can't be written)
+pub fn ring_bell() {
+    let mut stdout = io::stdout();
+    let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+}
+
+/// # saved_escalation
+///
+/// **Purpose:**
+/// The check-in that was still unanswered when the app last exited, if any.
+pub fn saved_escalation() -> Option<PendingEscalation> {
+    let content = fs::read_to_string(state_path(ESCALATION_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// # save_escalation
+///
+/// **Purpose:**
+/// Stores the pending state, or removes the file when `pending` is None (failures are only logged).
+pub fn save_escalation(pending: Option<&PendingEscalation>) {
+    let saved = persistence::auto_write(|| {
+        match pending {
+            Some(pending) => fs::write(writable_state_path(ESCALATION_FILE)?, serde_json::to_string_pretty(pending)?)?,
+            None if state_path(ESCALATION_FILE).exists() => fs::remove_file(state_path(ESCALATION_FILE))?,
+            None => {}
+        }
+        Ok(())
+    });
+    if let Err(e) = saved {
+        log_error!("Failed to save check-in escalation: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Timelike};
+    use crate::test_support::test_personas_root;
+
+    fn policy(max_escalations: u32, interval_growth: u32) -> CheckInEscalation {
+        CheckInEscalation { respond_within_minutes: 30, max_escalations, interval_growth, ..Default::default() }
+    }
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, 21, 17, 0, 0).unwrap()
+    }
+
+    fn minute(n: i64) -> DateTime<Utc> {
+        start() + Duration::minutes(n)
+    }
+
+    /// The app's side of escalation against a clock the test moves: a poll a minute, a follow-up when due
+    struct Timeline {
+        policy: CheckInEscalation,
+        pending: Option<PendingEscalation>,
+        follow_ups: Vec<i64>,
+    }
+
+    impl Timeline {
+        fn check_in(policy: CheckInEscalation) -> Self {
+            let pending = PendingEscalation::new("shadow", start(), &policy);
+            Self { policy, pending, follow_ups: Vec::new() }
+        }
+
+        /// Polls every minute in `from..=to`, as `ShadowApp::poll_channels` does
+        fn run(&mut self, from: i64, to: i64) {
+            for n in from..=to {
+                let Some(mut pending) = self.pending.take() else {
+                    return;
+                };
+                if !pending.is_due(minute(n)) {
+                    self.pending = Some(pending);
+                    continue;
+                }
+                self.follow_ups.push(n);
+                self.pending = pending.escalate(minute(n), &self.policy).then_some(pending);
+            }
+        }
+
+        /// A restart: the pending state as read back from its file
+        fn restart(&mut self) {
+            let saved = serde_json::to_string(&self.pending).unwrap();
+            self.pending = serde_json::from_str(&saved).unwrap();
+        }
+    }
+
+    #[test]
+    fn an_answer_in_time_sends_no_follow_up() {
+        let mut timeline = Timeline::check_in(policy(2, 2));
+        timeline.run(0, 29);
+        assert!(timeline.follow_ups.is_empty());
+
+        // The user replies at minute 29: the app drops the pending state
+        timeline.pending = None;
+        timeline.run(30, 600);
+        assert!(timeline.follow_ups.is_empty());
+    }
+
+    #[test]
+    fn unanswered_it_escalates_twice_with_growing_waits() {
+        let mut timeline = Timeline::check_in(policy(2, 2));
+        timeline.run(0, 600);
+        assert_eq!(timeline.follow_ups, [30, 90], "30 minutes, then twice that");
+        assert!(timeline.pending.is_none(), "dropped after the last follow-up");
+
+        let cases = [((3, 3), vec![30, 120, 390]), ((3, 1), vec![30, 60, 90]), ((2, 0), vec![30, 60])];
+        for ((max, growth), expected) in cases {
+            let mut timeline = Timeline::check_in(policy(max, growth));
+            timeline.run(0, 1000);
+            assert_eq!(timeline.follow_ups, expected, "max {} growth {}", max, growth);
+        }
+    }
+
+    #[test]
+    fn an_answer_between_follow_ups_stops_the_rest() {
+        let mut timeline = Timeline::check_in(policy(3, 2));
+        timeline.run(0, 45);
+        assert_eq!(timeline.follow_ups, [30]);
+        assert_eq!(timeline.pending.as_ref().map(|pending| pending.progress(&timeline.policy)).as_deref(), Some("1/3"));
+
+        timeline.pending = None;
+        timeline.run(46, 1000);
+        assert_eq!(timeline.follow_ups, [30]);
+    }
+
+    #[test]
+    fn a_restart_mid_escalation_keeps_the_schedule() {
+        let mut timeline = Timeline::check_in(policy(3, 2));
+        timeline.run(0, 40);
+        timeline.restart();
+        timeline.run(41, 1000);
+        assert_eq!(timeline.follow_ups, [30, 90, 210], "same as without the restart");
+    }
+
+    #[test]
+    fn follow_ups_missed_while_closed_are_sent_once_not_in_a_burst() {
+        let mut timeline = Timeline::check_in(policy(3, 2));
+        timeline.run(0, 40);
+        timeline.restart();
+
+        // Closed from minute 41 to 300: both missed follow-ups come down to one
+        timeline.run(300, 1000);
+        assert_eq!(timeline.follow_ups, [30, 300, 420], "the next wait counts from the catch-up");
+    }
+
+    #[test]
+    fn no_follow_ups_when_the_policy_allows_none() {
+        assert_eq!(PendingEscalation::new("shadow", start(), &policy(0, 2)), None);
+    }
+
+    #[test]
+    fn quiet_hours_may_wrap_past_midnight() {
+        let at = |h: u32, m: u32| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        let night = Some((at(22, 0), at(7, 0)));
+        let lunch = Some((at(12, 0), at(13, 0)));
+        let cases = [
+            (night, at(23, 30), true),
+            (night, at(3, 0), true),
+            (night, at(22, 0), true),
+            (night, at(7, 0), false),
+            (night, at(12, 0), false),
+            (lunch, at(12, 30), true),
+            (lunch, at(13, 0), false),
+            (lunch, at(23, 0), false),
+            (None, at(3, 0), false),
+        ];
+
+        for (quiet_hours, time, expected) in cases {
+            assert_eq!(in_quiet_hours(quiet_hours, time), expected, "{:?} at {}", quiet_hours, time);
+        }
+    }
+
+    #[test]
+    fn the_bell_rings_unless_off_or_quiet() {
+        let now = Utc::now();
+        let local = now.with_timezone(&Local).time().with_second(0).unwrap();
+        let around_now = Some((local, local + Duration::hours(1)));
+        let cases = [
+            (true, false, None, true),
+            (false, true, None, false),
+            (true, false, around_now, false),
+            (true, true, around_now, true),
+        ];
+
+        for (bell, bell_in_quiet_hours, quiet_hours, expected) in cases {
+            let policy = CheckInEscalation { bell, bell_in_quiet_hours, quiet_hours, ..Default::default() };
+            assert_eq!(may_ring_bell(&policy, now), expected, "bell {} in quiet {} {:?}", bell, bell_in_quiet_hours, quiet_hours);
+        }
+    }
+
+    #[test]
+    fn the_pending_state_is_saved_and_removed() {
+        test_personas_root();
+        let pending = PendingEscalation::new("shadow", start(), &policy(2, 2)).unwrap();
+
+        save_escalation(Some(&pending));
+        assert_eq!(saved_escalation(), Some(pending));
+        save_escalation(None);
+        assert_eq!(saved_escalation(), None);
+        assert!(!state_path(ESCALATION_FILE).exists());
+    }
+}
@@ -0,0 +1,576 @@
+//! # Daegonica Module: persona::experiment
+//!
+//! **Purpose:** Lightweight A/B prompt experiments per persona
+//!
+//! **Context:**
+//! - Compares a persona's normal prompt (variant A) against a variant YAML (variant B)
+//! - Only new conversations are assigned; existing agents never switch mid-stream
+//! - Used by AgentManager when spawning agents and when replies complete
+//!
+//! **Responsibilities:**
+//! - Assign variants to new conversations (alternating)
+//! - Record ratings, reply lengths, and latency per conversation
+//! - Persist experiments to disk and compute per-variant tallies
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::prelude::*;
+use crate::persona::personas_root;
+
+/// Where experiment files live (`{root}/experiments`)
+fn experiments_dir() -> PathBuf {
+    personas_root().join("experiments")
+}
+
+/// # Variant
+///
+/// **Summary:**
+/// Which prompt a conversation was assigned.
+///
+/// **Variants:**
+/// - `A`: The persona's normal system prompt
+/// - `B`: The experiment's variant prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Variant {
+    A,
+    B,
+}
+
+/// # Rating
+///
+/// **Summary:**
+/// Thumbs-up/down recorded for a conversation via `rate good|bad`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Rating {
+    Good,
+    Bad,
+}
+
+/// # ExperimentTag
+///
+/// **Summary:**
+/// Marks a conversation as part of an experiment (stored in ConversationHistory).
+///
+/// **Fields:**
+/// - `experiment`: Experiment name
+/// - `variant`: Variant the conversation was assigned
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExperimentTag {
+    pub experiment: String,
+    pub variant: Variant,
+}
+
+impl ExperimentTag {
+    /// Transcript label shown at the top of the agent pane
+    pub fn label(&self) -> String {
+        format!("[Experiment '{}': variant {:?}]", self.experiment, self.variant)
+    }
+}
+
+/// # ConversationRecord
+///
+/// **Summary:**
+/// Per-conversation experiment data.
+///
+/// **Fields:**
+/// - `conversation_id`: Agent ID the conversation ran in
+/// - `variant`: Assigned variant
+/// - `rating`: Optional thumbs-up/down
+/// - `replies`: Number of completed replies
+/// - `reply_chars`: Total characters across replies
+/// - `latency_ms`: Total request latency across replies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationRecord {
+    pub conversation_id: String,
+    pub variant: Variant,
+    pub rating: Option<Rating>,
+    pub replies: usize,
+    pub reply_chars: usize,
+    pub latency_ms: u64,
+}
+
+/// # Experiment
+///
+/// **Summary:**
+/// A running (or stopped) A/B experiment for one persona.
+///
+/// **Fields:**
+/// - `name`: Experiment name (also the file name)
+/// - `persona_name`: Persona under test
+/// - `variant_b_path`: Path to the variant YAML
+/// - `variant_b_prompt`: System prompt used for variant B
+/// - `started_at`: RFC3339 start time
+/// - `active`: false once stopped
+/// - `conversations`: Assigned conversations in order
+///
+/// **Usage Example:**
+/// ```rust
+/// # use grokprime_brain::persona::experiment::Experiment;
+/// # let (path, prompt) = ("personas/shadow/terse.md".to_string(), "Be terse.".to_string());
+/// # let (agent_id, reply) = (uuid::Uuid::new_v4(), "Done.");
+/// let mut experiment = Experiment::new("terse".into(), "shadow".into(), path, prompt);
+/// let tag = experiment.assign(agent_id);
+/// experiment.record_reply(agent_id, reply.len(), 1200);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Experiment {
+    pub name: String,
+    pub persona_name: String,
+    pub variant_b_path: String,
+    pub variant_b_prompt: String,
+    pub started_at: String,
+    pub active: bool,
+    pub conversations: Vec<ConversationRecord>,
+}
+
+impl Experiment {
+    pub fn new(name: String, persona_name: String, variant_b_path: String, variant_b_prompt: String) -> Self {
+        Self {
+            name,
+            persona_name,
+            variant_b_path,
+            variant_b_prompt,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            active: true,
+            conversations: Vec::new(),
+        }
+    }
+
+    /// # next_variant
+    ///
+    /// **Purpose:**
+    /// Picks the variant for the next conversation by strict alternation (A, B, A, ...).
+    pub fn next_variant(&self) -> Variant {
+        if self.conversations.len().is_multiple_of(2) { Variant::A } else { Variant::B }
+    }
+
+    /// # assign
+    ///
+    /// **Purpose:**
+    /// Assigns the next variant to a new conversation and records it.
+    ///
+    /// **Parameters:**
+    /// - `conversation_id`: Agent ID of the new conversation
+    ///
+    /// **Returns:**
+    /// `ExperimentTag` to attach to the conversation
+    pub fn assign(&mut self, conversation_id: Uuid) -> ExperimentTag {
+        let variant = self.next_variant();
+        self.conversations.push(ConversationRecord {
+            conversation_id: conversation_id.to_string(),
+            variant,
+            rating: None,
+            replies: 0,
+            reply_chars: 0,
+            latency_ms: 0,
+        });
+
+        ExperimentTag {
+            experiment: self.name.clone(),
+            variant,
+        }
+    }
+
+    /// # persona_for
+    ///
+    /// **Purpose:**
+    /// Returns the persona to run for a variant (B swaps in the variant prompt).
+    pub fn persona_for(&self, variant: Variant, persona: PersonaRef) -> PersonaRef {
+        match variant {
+            Variant::A => persona,
+            Variant::B => Arc::new(Persona {
+                system_prompt: self.variant_b_prompt.clone(),
+                ..(*persona).clone()
+            }),
+        }
+    }
+
+    fn record_mut(&mut self, conversation_id: Uuid) -> Option<&mut ConversationRecord> {
+        let id = conversation_id.to_string();
+        self.conversations.iter_mut().find(|record| record.conversation_id == id)
+    }
+
+    /// # record_reply
+    ///
+    /// **Purpose:**
+    /// Adds a completed reply's length and latency to a conversation's record.
+    pub fn record_reply(&mut self, conversation_id: Uuid, reply_chars: usize, latency_ms: u64) {
+        if let Some(record) = self.record_mut(conversation_id) {
+            record.replies += 1;
+            record.reply_chars += reply_chars;
+            record.latency_ms += latency_ms;
+        }
+    }
+
+    /// # rate
+    ///
+    /// **Purpose:**
+    /// Records (or overwrites) the rating for a conversation.
+    ///
+    /// **Returns:**
+    /// `bool` - false if the conversation isn't part of this experiment
+    pub fn rate(&mut self, conversation_id: Uuid, rating: Rating) -> bool {
+        match self.record_mut(conversation_id) {
+            Some(record) => {
+                record.rating = Some(rating);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// # tally
+    ///
+    /// **Purpose:**
+    /// Aggregates counts per variant.
+    ///
+    /// **Returns:**
+    /// `Vec<VariantTally>` - Always [A, B]
+    pub fn tally(&self) -> Vec<VariantTally> {
+        [Variant::A, Variant::B].iter()
+            .map(|&variant| {
+                let mut tally = VariantTally::new(variant);
+                for record in self.conversations.iter().filter(|r| r.variant == variant) {
+                    tally.conversations += 1;
+                    tally.replies += record.replies;
+                    tally.reply_chars += record.reply_chars;
+                    tally.latency_ms += record.latency_ms;
+                    match record.rating {
+                        Some(Rating::Good) => tally.good += 1,
+                        Some(Rating::Bad) => tally.bad += 1,
+                        None => {}
+                    }
+                }
+                tally
+            })
+            .collect()
+    }
+
+    /// File location: `{root}/experiments/{name}.json`
+    pub fn path(name: &str) -> PathBuf {
+        experiments_dir().join(format!("{}.json", name))
+    }
+
+    /// # save
+    ///
+    /// **Purpose:**
+    /// Writes the experiment to `{root}/experiments/{name}.json`.
+    pub fn save(&self) -> Result<(), ShadowError> {
+        fs::create_dir_all(experiments_dir())?;
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(Self::path(&self.name), json)?;
+        Ok(())
+    }
+
+    /// # load_active
+    ///
+    /// **Purpose:**
+    /// Loads every experiment file that hasn't been stopped.
+    ///
+    /// **Returns:**
+    /// `Vec<Experiment>` - Active experiments (unreadable files are logged and skipped)
+    pub fn load_active() -> Vec<Experiment> {
+        let Ok(entries) = fs::read_dir(experiments_dir()) else {
+            return Vec::new();
+        };
+
+        entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("json"))
+            .filter_map(|path| {
+                let content = fs::read_to_string(&path).ok()?;
+                match serde_json::from_str::<Experiment>(&content) {
+                    Ok(experiment) => Some(experiment),
+                    Err(e) => {
+                        log_error!("Skipping unreadable experiment {}: {}", path.display(), e);
+                        None
+                    }
+                }
+            })
+            .filter(|experiment| experiment.active)
+            .collect()
+    }
+}
+
+/// # VariantTally
+///
+/// **Summary:**
+/// Counts for one variant of an experiment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantTally {
+    pub variant: Variant,
+    pub conversations: usize,
+    pub good: usize,
+    pub bad: usize,
+    pub replies: usize,
+    pub reply_chars: usize,
+    pub latency_ms: u64,
+}
+
+impl VariantTally {
+    fn new(variant: Variant) -> Self {
+        Self {
+            variant,
+            conversations: 0,
+            good: 0,
+            bad: 0,
+            replies: 0,
+            reply_chars: 0,
+            latency_ms: 0,
+        }
+    }
+
+    pub fn avg_reply_chars(&self) -> usize {
+        self.reply_chars.checked_div(self.replies).unwrap_or(0)
+    }
+
+    pub fn avg_latency_ms(&self) -> u64 {
+        self.latency_ms.checked_div(self.replies as u64).unwrap_or(0)
+    }
+
+    /// One-line summary for status/stop output
+    pub fn describe(&self) -> String {
+        format!(
+            "  Variant {:?}: {} conversations, {} good / {} bad, avg reply {} chars, avg latency {} ms",
+            self.variant,
+            self.conversations,
+            self.good,
+            self.bad,
+            self.avg_reply_chars(),
+            self.avg_latency_ms(),
+        )
+    }
+}
+
+/// # ExperimentRegistry
+///
+/// **Summary:**
+/// Active experiments keyed by persona name (at most one per persona).
+#[derive(Debug, Default)]
+pub struct ExperimentRegistry {
+    experiments: HashMap<String, Experiment>,
+}
+
+impl ExperimentRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads active experiments from disk
+    pub fn load() -> Self {
+        let experiments = Experiment::load_active()
+            .into_iter()
+            .map(|experiment| (experiment.persona_name.clone(), experiment))
+            .collect();
+        Self { experiments }
+    }
+
+    pub fn for_persona(&self, persona_name: &str) -> Option<&Experiment> {
+        self.experiments.get(persona_name)
+    }
+
+    pub fn for_persona_mut(&mut self, persona_name: &str) -> Option<&mut Experiment> {
+        self.experiments.get_mut(persona_name)
+    }
+
+    pub fn by_name_mut(&mut self, name: &str) -> Option<&mut Experiment> {
+        self.experiments.values_mut().find(|experiment| experiment.name == name)
+    }
+
+    pub fn all(&self) -> impl Iterator<Item = &Experiment> {
+        self.experiments.values()
+    }
+
+    /// # start
+    ///
+    /// **Purpose:**
+    /// Registers and persists a new experiment.
+    ///
+    /// **Errors / Failures:**
+    /// - Persona already has an active experiment
+    /// - Experiment file cannot be written
+    pub fn start(&mut self, experiment: Experiment) -> Result<(), ShadowError> {
+        if let Some(existing) = self.experiments.get(&experiment.persona_name) {
+            return Err(ShadowError::OperationFailed(format!(
+                "Persona '{}' already has an active experiment '{}'",
+                experiment.persona_name, existing.name
+            )));
+        }
+
+        experiment.save()?;
+        self.experiments.insert(experiment.persona_name.clone(), experiment);
+        Ok(())
+    }
+
+    /// # stop
+    ///
+    /// **Purpose:**
+    /// Ends an experiment (by name, or the only active one) and persists the final state.
+    ///
+    /// **Returns:**
+    /// `Result<Experiment, ShadowError>` - The stopped experiment for tallying
+    pub fn stop(&mut self, name: Option<&str>) -> Result<Experiment, ShadowError> {
+        let persona_name = match name {
+            Some(name) => self.experiments.values()
+                .find(|experiment| experiment.name == name)
+                .map(|experiment| experiment.persona_name.clone())
+                .ok_or_else(|| ShadowError::OperationFailed(format!("No active experiment '{}'", name)))?,
+            None => match self.experiments.len() {
+                0 => return Err(ShadowError::OperationFailed("No active experiments".to_string())),
+                1 => self.experiments.keys().next().cloned().unwrap_or_default(),
+                _ => return Err(ShadowError::OperationFailed(
+                    "Several experiments are active; use 'experiment stop <name>'".to_string()
+                )),
+            },
+        };
+
+        let mut experiment = self.experiments.remove(&persona_name)
+            .ok_or_else(|| ShadowError::OperationFailed("Experiment disappeared".to_string()))?;
+        experiment.active = false;
+        experiment.save()?;
+        Ok(experiment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persona::builtin::load_builtin;
+    use crate::test_support::test_personas_root;
+
+    fn experiment(name: &str, persona_name: &str) -> Experiment {
+        Experiment::new(name.into(), persona_name.into(), "terse.yaml".into(), "Be terse.".into())
+    }
+
+    #[test]
+    fn conversations_alternate_between_variants() {
+        let mut experiment = experiment("alternate", "shadow");
+        let variants: Vec<Variant> = (0..5).map(|_| experiment.assign(Uuid::new_v4()).variant).collect();
+        assert_eq!(variants, [Variant::A, Variant::B, Variant::A, Variant::B, Variant::A]);
+        assert_eq!(experiment.next_variant(), Variant::B);
+
+        let tag = experiment.assign(Uuid::new_v4());
+        assert_eq!(tag, ExperimentTag { experiment: "alternate".into(), variant: Variant::B });
+        assert_eq!(tag.label(), "[Experiment 'alternate': variant B]");
+        assert!(experiment.conversations.iter().all(|record| record.replies == 0 && record.rating.is_none()));
+    }
+
+    #[test]
+    fn only_variant_b_swaps_the_prompt() {
+        let experiment = experiment("prompt", "shadow");
+        let persona: PersonaRef = Arc::new(load_builtin("shadow").expect("built-in shadow"));
+        let a = experiment.persona_for(Variant::A, persona.clone());
+        assert!(Arc::ptr_eq(&a, &persona), "variant A runs the persona as it is");
+
+        let b = experiment.persona_for(Variant::B, persona.clone());
+        assert_eq!(b.system_prompt, "Be terse.");
+        assert_eq!(b.name, persona.name);
+        assert_ne!(persona.system_prompt, "Be terse.", "the original is untouched");
+    }
+
+    #[test]
+    fn replies_and_ratings_land_on_their_conversation() {
+        let mut experiment = experiment("records", "shadow");
+        let (first, second, stranger) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        experiment.assign(first);
+        experiment.assign(second);
+
+        experiment.record_reply(first, 100, 900);
+        experiment.record_reply(first, 50, 1100);
+        experiment.record_reply(stranger, 999, 999);
+        assert!(experiment.rate(second, Rating::Bad));
+        assert!(experiment.rate(second, Rating::Good), "rating again overwrites");
+        assert!(!experiment.rate(stranger, Rating::Good));
+
+        let records: Vec<(Variant, Option<Rating>, usize, usize, u64)> = experiment.conversations.iter()
+            .map(|record| (record.variant, record.rating, record.replies, record.reply_chars, record.latency_ms))
+            .collect();
+        assert_eq!(records, [(Variant::A, None, 2, 150, 2000), (Variant::B, Some(Rating::Good), 0, 0, 0)]);
+    }
+
+    #[test]
+    fn the_tally_counts_each_variant_separately() {
+        let mut experiment = experiment("tally", "shadow");
+        let ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        for id in &ids {
+            experiment.assign(*id);
+        }
+        experiment.record_reply(ids[0], 300, 1000);
+        experiment.record_reply(ids[2], 100, 2001);
+        experiment.record_reply(ids[1], 40, 500);
+        experiment.rate(ids[0], Rating::Good);
+        experiment.rate(ids[2], Rating::Bad);
+        experiment.rate(ids[3], Rating::Good);
+
+        let [a, b]: [VariantTally; 2] = experiment.tally().try_into().expect("a tally for A and B");
+        assert_eq!(
+            a,
+            VariantTally { variant: Variant::A, conversations: 2, good: 1, bad: 1, replies: 2, reply_chars: 400, latency_ms: 3001 }
+        );
+        assert_eq!((a.avg_reply_chars(), a.avg_latency_ms()), (200, 1500));
+        assert_eq!(
+            b.describe(),
+            "  Variant B: 2 conversations, 1 good / 0 bad, avg reply 40 chars, avg latency 500 ms"
+        );
+
+        let empty = Experiment::new("empty".into(), "shadow".into(), String::new(), String::new()).tally();
+        assert!(empty.iter().all(|tally| tally.conversations == 0 && tally.avg_reply_chars() == 0 && tally.avg_latency_ms() == 0));
+    }
+
+    #[test]
+    fn experiments_persist_until_stopped() {
+        test_personas_root();
+        let name = format!("persist{}", std::process::id());
+        let mut registry = ExperimentRegistry::new();
+        let mut started = experiment(&name, "experimentpersona");
+        let id = Uuid::new_v4();
+        started.assign(id);
+        started.rate(id, Rating::Good);
+        registry.start(started).unwrap();
+
+        let reloaded = ExperimentRegistry::load();
+        let loaded = reloaded.for_persona("experimentpersona").expect("saved and active");
+        assert_eq!(loaded.name, name);
+        assert_eq!(loaded.conversations[0].rating, Some(Rating::Good));
+
+        fs::write(Experiment::path("unreadable"), "{ not json").unwrap();
+        assert!(Experiment::load_active().iter().any(|experiment| experiment.name == name), "a bad file doesn't hide the others");
+
+        let stopped = registry.stop(Some(&name)).unwrap();
+        assert!(!stopped.active);
+        assert!(registry.for_persona("experimentpersona").is_none());
+        assert!(Experiment::path(&name).exists(), "kept on disk for the record");
+        assert!(!Experiment::load_active().iter().any(|experiment| experiment.name == name));
+        let _ = fs::remove_file(Experiment::path("unreadable"));
+    }
+
+    #[test]
+    fn the_registry_refuses_what_it_cannot_do() {
+        test_personas_root();
+        let mut registry = ExperimentRegistry::new();
+        assert_eq!(registry.stop(None).unwrap_err().to_string(), ShadowError::OperationFailed("No active experiments".into()).to_string());
+
+        registry.start(experiment("refuse-one", "refusepersona")).unwrap();
+        let twice = registry.start(experiment("refuse-two", "refusepersona")).unwrap_err().to_string();
+        assert!(twice.contains("Persona 'refusepersona' already has an active experiment 'refuse-one'"), "{}", twice);
+        assert!(registry.stop(Some("nonexistent")).unwrap_err().to_string().contains("No active experiment 'nonexistent'"));
+
+        registry.start(experiment("refuse-three", "otherpersona")).unwrap();
+        assert!(registry.stop(None).unwrap_err().to_string().contains("Several experiments are active"));
+        assert_eq!(registry.by_name_mut("refuse-three").map(|experiment| experiment.persona_name.clone()), Some("otherpersona".into()));
+        for name in ["refuse-one", "refuse-three"] {
+            registry.stop(Some(name)).unwrap();
+        }
+        assert_eq!(registry.all().count(), 0);
+    }
+}
@@ -0,0 +1,221 @@
+//! # Daegonica Module: persona::focus
+//!
+//! **Purpose:** Timed focus blocks that hold back interruptions and end with a check-in
+//!
+//! **Context:**
+//! - Started with `focus <minutes> [goal]`, ended early with `focus cancel`
+//! - The TUI holds Global notifications while a block runs and releases them at the end
+//! - When the timer runs out, the check-in persona is asked what got done
+//!
+//! **Responsibilities:**
+//! - Define `FocusBlock` and its remaining-time math
+//! - Persist the running block so a restart resumes it from the stored start time
+//! - Append start / completion / cancellation entries to the focus log
+//! - Run the countdown as a tokio sleep task the app polls
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use chrono::{DateTime, Utc};
+use tokio::sync::oneshot;
+use crate::prelude::*;
+use crate::agent_history::persistence;
+use crate::persona::{state_path, writable_state_path};
+
+/// The running block, removed when it ends
+pub(crate) const FOCUS_SESSION_FILE: &str = "focus_session.json";
+/// One JSON line per start / completion / cancellation
+pub const FOCUS_LOG_FILE: &str = "focus_log.jsonl";
+
+/// Longest block `focus` accepts
+pub const MAX_FOCUS_MINUTES: u32 = 480;
+
+/// # FocusBlock
+///
+/// **Summary:**
+/// One focus block: how long, what for, and when it began.
+///
+/// **Fields:**
+/// - `minutes`: Planned length
+/// - `goal`: What the user meant to get done, if they said
+/// - `started_at`: Wall-clock start; remaining time is always recomputed from it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FocusBlock {
+    pub minutes: u32,
+    pub goal: Option<String>,
+    pub started_at: DateTime<Utc>,
+}
+
+impl FocusBlock {
+    pub fn new(minutes: u32, goal: Option<String>) -> Self {
+        Self { minutes, goal, started_at: Utc::now() }
+    }
+
+    pub fn ends_at(&self) -> DateTime<Utc> {
+        self.started_at + chrono::Duration::minutes(self.minutes as i64)
+    }
+
+    /// # remaining_at
+    ///
+    /// **Purpose:**
+    /// Time left in the block as of `now`.
+    ///
+    /// **Returns:**
+    /// `chrono::Duration` - Zero once the block is over (never negative)
+    pub fn remaining_at(&self, now: DateTime<Utc>) -> chrono::Duration {
+        (self.ends_at() - now).max(chrono::Duration::zero())
+    }
+
+    pub fn remaining(&self) -> chrono::Duration {
+        self.remaining_at(Utc::now())
+    }
+
+    pub fn is_over_at(&self, now: DateTime<Utc>) -> bool {
+        self.remaining_at(now).is_zero()
+    }
+
+    /// Countdown for the Global pane title, e.g. "24:59 left"
+    pub fn countdown(&self) -> String {
+        let seconds = self.remaining().num_seconds();
+        format!("{}:{:02} left", seconds / 60, seconds % 60)
+    }
+
+    pub fn describe(&self) -> String {
+        match &self.goal {
+            Some(goal) => format!("{}m focus block on \"{}\"", self.minutes, goal),
+            None => format!("{}m focus block", self.minutes),
+        }
+    }
+
+    /// # check_in_message
+    ///
+    /// **Purpose:**
+    /// The message sent to the check-in persona when the block runs out.
+    pub fn check_in_message(&self) -> String {
+        match &self.goal {
+            Some(goal) => format!(
+                "Focus block ended ({} minutes): goal was \"{}\". Ask me to report what I actually did.",
+                self.minutes, goal
+            ),
+            None => format!(
+                "Focus block ended ({} minutes, no goal set). Ask me to report what I actually did.",
+                self.minutes
+            ),
+        }
+    }
+}
+
+/// # FocusOutcome
+///
+/// **Summary:**
+/// What happened to a block, as recorded in the focus log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FocusOutcome {
+    Started,
+    Completed,
+    Cancelled,
+}
+
+#[derive(Debug, Serialize)]
+struct FocusLogEntry<'a> {
+    event: FocusOutcome,
+    at: DateTime<Utc>,
+    #[serde(flatten)]
+    block: &'a FocusBlock,
+}
+
+/// # saved_focus_block
+///
+/// **Purpose:**
+/// The block that was running when the app last exited, if any.
+pub fn saved_focus_block() -> Option<FocusBlock> {
+    let content = fs::read_to_string(state_path(FOCUS_SESSION_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// # save_focus_block
+///
+/// **Purpose:**
+/// Stores the running block, or removes the file when `block` is None (failures are only logged).
+pub fn save_focus_block(block: Option<&FocusBlock>) {
+    let saved = persistence::auto_write(|| {
+        match block {
+            Some(block) => fs::write(writable_state_path(FOCUS_SESSION_FILE)?, serde_json::to_string_pretty(block)?)?,
+            None if state_path(FOCUS_SESSION_FILE).exists() => fs::remove_file(state_path(FOCUS_SESSION_FILE))?,
+            None => {}
+        }
+        Ok(())
+    });
+    if let Err(e) = saved {
+        log_error!("Failed to save focus block: {}", e);
+    }
+}
+
+/// # log_focus_event
+///
+/// **Purpose:**
+/// Appends one entry to the focus log (failures are only logged).
+pub fn log_focus_event(block: &FocusBlock, event: FocusOutcome) {
+    let entry = FocusLogEntry { event, at: Utc::now(), block };
+    let written = persistence::auto_write(|| {
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(writable_state_path(FOCUS_LOG_FILE)?)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    });
+    if let Err(e) = written {
+        log_error!("Failed to write focus log: {}", e);
+    }
+}
+
+/// # FocusTimer
+///
+/// **Summary:**
+/// A running block and the sleep task counting it down.
+///
+/// **Fields:**
+/// - `block`: The block being timed
+/// - `quit_warned`: `quit` already warned about this block once
+///
+/// **Details:**
+/// The task only signals; the app checks `fired` each loop and does the
+/// end-of-block work itself. Dropping the timer aborts the task.
+#[derive(Debug)]
+pub struct FocusTimer {
+    pub block: FocusBlock,
+    pub quit_warned: bool,
+    done: oneshot::Receiver<()>,
+    task: task::JoinHandle<()>,
+}
+
+impl FocusTimer {
+    /// # start
+    ///
+    /// **Purpose:**
+    /// Starts counting down whatever is left of `block` (a block already over fires right away).
+    pub fn start(block: FocusBlock) -> Self {
+        let (tx, done) = oneshot::channel();
+        let remaining = block.remaining().to_std().unwrap_or_default();
+        let task = tokio::spawn(async move {
+            tokio::time::sleep(remaining).await;
+            let _ = tx.send(());
+        });
+        Self { block, quit_warned: false, done, task }
+    }
+
+    /// Whether the countdown has finished
+    pub fn fired(&mut self) -> bool {
+        self.done.try_recv().is_ok()
+    }
+}
+
+impl Drop for FocusTimer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
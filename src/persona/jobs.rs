@@ -0,0 +1,228 @@
+//! # Daegonica Module: persona::jobs
+//!
+//! **Purpose:** One-off background generation jobs with uniform progress and cancellation
+//!
+//! **Context:**
+//! - Summarization and tweet drafting share one shape: build a prompt, call
+//!   the model, post-process, deliver a result
+//! - Jobs run against an agent's connection and report through its chunk channel
+//!
+//! **Responsibilities:**
+//! - Define `GenerationJob` (name, target agent, work)
+//! - Spawn jobs and emit JobStarted / JobProgress / JobCompleted / JobFailed chunks
+//!   through a job `ChunkStream`
+//! - Track running jobs so `jobs` can list them and `jobs cancel <n>` can abort one
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::Instant;
+use uuid::Uuid;
+use crate::prelude::*;
+use crate::persona::agent::DynamicConnection;
+
+/// Jobs are numbered from 1 for the lifetime of the process
+pub type JobId = u32;
+
+type JobFuture = Pin<Box<dyn Future<Output = Result<String, String>> + Send>>;
+type JobWork = Box<dyn FnOnce(JobContext) -> JobFuture + Send>;
+
+/// # JobContext
+///
+/// **Summary:**
+/// What a running job gets to work with.
+///
+/// **Fields:**
+/// - `id`: Job number shown to the user
+/// - `name`: Job name shown to the user
+/// - `connection`: The target agent's connection
+/// - `tx`: The target agent's chunk channel, for opening streams of its own
+/// - `lifecycle`: The job's stream, shared with the task that ends it
+pub struct JobContext {
+    pub id: JobId,
+    pub name: String,
+    pub connection: DynamicConnection,
+    pub tx: ChunkSender,
+    lifecycle: Arc<Mutex<ChunkStream>>,
+}
+
+impl JobContext {
+    /// Reports a progress step in the Global pane
+    pub fn progress(&self, msg: impl Into<String>) {
+        let _ = lock_stream(&self.lifecycle).info(msg);
+    }
+}
+
+/// The job stream is only held for one send at a time, so a poisoned lock still holds a usable stream
+fn lock_stream(stream: &Mutex<ChunkStream>) -> std::sync::MutexGuard<'_, ChunkStream> {
+    stream.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// # GenerationJob
+///
+/// **Summary:**
+/// A named piece of model work aimed at one agent.
+///
+/// **Fields:**
+/// - `name`: Short label, e.g. `summarize` or `draft`
+/// - `agent`: Agent whose connection the job uses
+/// - `streams_reply`: The job streams a reply into the agent pane (cancelling
+///   it must also stop the pane's waiting state)
+///
+/// **Details:**
+/// The work closure builds the request, calls the model, and handles the
+/// result; its `Ok` text becomes the completion message, its `Err` the failure.
+///
+/// **Usage Example:**
+/// ```ignore
+/// use grokprime_brain::persona::jobs::GenerationJob;
+///
+/// let job = GenerationJob::new("summarize", agent_id, |ctx| async move {
+///     ctx.connection.lock().await.summarize_history().await.map_err(|e| e.to_string())?;
+///     Ok("history summarized".to_string())
+/// });
+/// ops.spawn_job(job);
+/// ```
+pub struct GenerationJob {
+    pub name: String,
+    pub agent: Uuid,
+    pub streams_reply: bool,
+    work: JobWork,
+}
+
+impl GenerationJob {
+    pub fn new<F, Fut>(name: impl Into<String>, agent: Uuid, work: F) -> Self
+    where
+        F: FnOnce(JobContext) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<String, String>> + Send + 'static,
+    {
+        Self {
+            name: name.into(),
+            agent,
+            streams_reply: false,
+            work: Box::new(move |ctx| Box::pin(work(ctx))),
+        }
+    }
+
+    pub fn streaming_reply(mut self) -> Self {
+        self.streams_reply = true;
+        self
+    }
+}
+
+/// # RunningJob
+///
+/// **Summary:**
+/// Bookkeeping for a spawned job until it completes, fails, or is cancelled.
+#[derive(Debug)]
+pub struct RunningJob {
+    pub id: JobId,
+    pub name: String,
+    pub agent: Uuid,
+    pub streams_reply: bool,
+    pub started: Instant,
+    task: task::JoinHandle<()>,
+}
+
+/// # JobRegistry
+///
+/// **Summary:**
+/// Running jobs across all agents, oldest first.
+#[derive(Debug, Default)]
+pub struct JobRegistry {
+    next_id: JobId,
+    running: Vec<RunningJob>,
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # spawn
+    ///
+    /// **Purpose:**
+    /// Starts a job on the tokio runtime and starts tracking it.
+    ///
+    /// **Parameters:**
+    /// - `job`: The job to run
+    /// - `connection`: Target agent's connection
+    /// - `tx`: Target agent's chunk channel
+    ///
+    /// **Returns:**
+    /// `JobId` - Number to show the user (and to pass to `cancel`)
+    pub fn spawn(
+        &mut self,
+        job: GenerationJob,
+        connection: DynamicConnection,
+        tx: ChunkSender,
+    ) -> JobId {
+        self.next_id += 1;
+        let id = self.next_id;
+        let GenerationJob { name, agent, streams_reply, work } = job;
+
+        let mut lifecycle = ChunkStream::for_job(&tx, id, &name);
+        lifecycle.begin();
+        log_info!("Job {} ({}) started for agent {}", id, name, agent);
+
+        // A cancelled job drops its stream unfinished, which reports the failure
+        let lifecycle = Arc::new(Mutex::new(lifecycle));
+        let ctx = JobContext { id, name: name.clone(), connection, tx, lifecycle: lifecycle.clone() };
+        let task = tokio::spawn(async move {
+            let result = work(ctx).await;
+            let mut lifecycle = lock_stream(&lifecycle);
+            let _ = match result {
+                Ok(msg) => lifecycle.complete_job(msg),
+                Err(error) => lifecycle.fail(error),
+            };
+        });
+
+        self.running.push(RunningJob { id, name, agent, streams_reply, started: Instant::now(), task });
+        id
+    }
+
+    /// Stops tracking a job that reported completion or failure
+    pub fn finish(&mut self, id: JobId) -> Option<RunningJob> {
+        let index = self.running.iter().position(|job| job.id == id)?;
+        Some(self.running.remove(index))
+    }
+
+    /// Aborts a running job; None if it already finished
+    pub fn cancel(&mut self, id: JobId) -> Option<RunningJob> {
+        let job = self.finish(id)?;
+        job.task.abort();
+        log_info!("Job {} ({}) cancelled", job.id, job.name);
+        Some(job)
+    }
+
+    /// Aborts every job aimed at an agent that is going away
+    pub fn cancel_for_agent(&mut self, agent: Uuid) {
+        let ids: Vec<JobId> = self.running.iter()
+            .filter(|job| job.agent == agent)
+            .map(|job| job.id)
+            .collect();
+        for id in ids {
+            self.cancel(id);
+        }
+    }
+
+    pub fn running(&self) -> &[RunningJob] {
+        &self.running
+    }
+
+    pub fn len(&self) -> usize {
+        self.running.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.running.is_empty()
+    }
+}
@@ -20,16 +20,33 @@
 //! This file is part of the Daegonica Software codebase.
 //! ---------------------------------------------------------------
 
+use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use strum::{Display, EnumString};
+use thiserror::Error;
 use walkdir::WalkDir;
 use crate::prelude::*;
+use crate::llm::credentials::ApiProvider;
+use crate::utilities::strip;
+use crate::agent_history::redaction::{self, EphemeralPattern};
 
 pub mod agent;
 pub mod agent_manager;
+pub mod background;
+pub mod builtin;
+pub mod escalation;
+pub mod changelog;
+pub mod chunk_stream;
+pub mod experiment;
+pub mod focus;
+pub mod jobs;
 pub mod operations;
+pub mod reload;
+pub mod review;
+pub mod tools;
 
 /// # Persona
 ///
@@ -40,11 +57,22 @@ pub mod operations;
 /// - `name`: Display name of the persona
 /// - `description`: Optional description of the persona's purpose
 /// - `system_prompt`: The system prompt that defines the persona's behavior
+/// - `system_prompt_file`: Alternative to an inline `system_prompt`: a file, relative to the YAML's directory, holding the prompt
 /// - `temperature`: Optional temperature setting for response randomness
 /// - `max_tokens`: Optional maximum token limit for responses
-/// - `tools`: Optional list of available tools for this persona
+/// - `tools`: Optional list of available tools; `twitter` and `history` gate commands (see `persona::tools`)
 /// - `memory_policy`: Optional memory management strategy
 /// - `startup_commands`: Optional commands to run on agent startup
+/// - `timezone`: Optional display zone override (`local`, `utc`, `+02:00`)
+/// - `summary_max_tokens`: Optional output cap for history summaries
+/// - `summary_style`: Optional summary format (`bullets` or `prose`)
+/// - `summary_must_include`: Terms a summary must keep whenever the summarized messages mention them
+/// - `summarize_mode`: When history is summarized (`auto`, `manual`, `off`); `set summarize` overrides it
+/// - `language`: Optional language for summaries and drafts (`spa`, `Spanish`); skips detection
+/// - `strip_patterns`: Regexes for signature/boilerplate lines removed from the start or end of replies
+/// - `ephemeral_patterns`: Regexes for content kept in memory but stored redacted (just the match, or the whole message)
+/// - `tui_color`: Optional `[r, g, b]` border color for this persona's pane (`tui.border_color` otherwise)
+/// - `is_builtin`: Whether this persona was loaded from the embedded fallback set
 ///
 /// **Usage Example:**
 /// ```rust
@@ -54,8 +82,12 @@ pub mod operations;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Persona {
     pub name: String,
+    #[serde(default)]
     pub system_prompt: String,
 
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt_file: Option<PathBuf>,
+
     pub temperature: Option<f32>,
     pub max_tokens: Option<u32>,
 
@@ -73,6 +105,36 @@ pub struct Persona {
 
     #[serde(default = "default_api_provider")]
     pub api_provider: String,
+
+    #[serde(default)]
+    pub timezone: Option<String>,
+
+    #[serde(default)]
+    pub summary_max_tokens: Option<u32>,
+
+    #[serde(default)]
+    pub summary_style: Option<SummaryStyle>,
+
+    #[serde(default)]
+    pub summary_must_include: Vec<String>,
+
+    #[serde(default)]
+    pub summarize_mode: SummarizeMode,
+
+    #[serde(default)]
+    pub language: Option<String>,
+
+    #[serde(default)]
+    pub strip_patterns: Vec<String>,
+
+    #[serde(default)]
+    pub ephemeral_patterns: Vec<EphemeralPattern>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tui_color: Option<[u8; 3]>,
+
+    #[serde(skip)]
+    pub is_builtin: bool,
 }
 
 impl Persona {
@@ -91,6 +153,11 @@ impl Persona {
     /// - File not found
     /// - Invalid YAML format
     /// - Missing required fields
+    /// - `system_prompt_file` can't be read
+    ///
+    /// **Details:**
+    /// A `system_prompt_file` is read now, so the persona always carries the
+    /// resolved prompt text; nothing downstream knows where it came from.
     ///
     /// **Examples:**
     /// ```rust
@@ -98,10 +165,212 @@ impl Persona {
     /// ```
     pub fn from_yaml_file(path: &Path) -> anyhow::Result<Self> {
         let s = fs::read_to_string(path)?;
-        let p: Persona = serde_yaml::from_str(&s)?;
+        Self::parse(&s, Some(path))
+    }
+
+    /// # from_yaml_str
+    ///
+    /// **Purpose:**
+    /// Parses a persona configuration from YAML text (used for embedded personas).
+    ///
+    /// **Parameters:**
+    /// - `yaml`: The YAML document
+    ///
+    /// **Returns:**
+    /// `anyhow::Result<Self>` - Parsed persona or error
+    ///
+    /// **Details:**
+    /// There is no directory to resolve against, so `system_prompt_file` is rejected.
+    pub fn from_yaml_str(yaml: &str) -> anyhow::Result<Self> {
+        Self::parse(yaml, None)
+    }
+
+    /// # parse
+    ///
+    /// **Purpose:**
+    /// Parses persona YAML and fills in the prompt from `system_prompt_file` when given.
+    ///
+    /// **Parameters:**
+    /// - `yaml`: The YAML document
+    /// - `yaml_path`: Where it was read from, if anywhere
+    ///
+    /// **Errors / Failures:**
+    /// - Both `system_prompt` and `system_prompt_file` set, or neither
+    /// - `system_prompt_file` without a YAML path, or the file can't be read
+    fn parse(yaml: &str, yaml_path: Option<&Path>) -> anyhow::Result<Self> {
+        let value: serde_yaml::Value = serde_yaml::from_str(yaml)?;
+        let inline = value.get("system_prompt").is_some_and(|v| !v.is_null());
+        let mut p: Persona = serde_yaml::from_value(value)?;
+
+        match (inline, &p.system_prompt_file) {
+            (true, Some(_)) => {
+                anyhow::bail!("{}: set either system_prompt or system_prompt_file, not both", p.name);
+            }
+            (false, None) => {
+                anyhow::bail!("{}: needs a system_prompt or a system_prompt_file", p.name);
+            }
+            (false, Some(file)) => {
+                let Some(yaml_path) = yaml_path else {
+                    anyhow::bail!("{}: system_prompt_file is only supported for persona files on disk", p.name);
+                };
+                let prompt_path = resolve_prompt_path(yaml_path, file);
+                p.system_prompt = fs::read_to_string(&prompt_path).map_err(|e| {
+                    anyhow::anyhow!("{}: can't read system_prompt_file {}: {}", p.name, prompt_path.display(), e)
+                })?;
+            }
+            (true, None) => {}
+        }
+
+        if let Err(problems) = p.validate() {
+            let problems: Vec<String> = problems.iter().map(ToString::to_string).collect();
+            let name = if p.name.trim().is_empty() { "unnamed persona" } else { p.name.as_str() };
+            anyhow::bail!("{}: {}", name, problems.join("; "));
+        }
         Ok(p)
     }
 
+    /// # validate
+    ///
+    /// **Purpose:**
+    /// Rejects settings that parse but can't mean anything sensible.
+    ///
+    /// **Returns:**
+    /// `Result<(), Vec<PersonaValidationError>>` - Every problem found, not just the first
+    ///
+    /// **Errors / Failures:**
+    /// - Blank `name` or `system_prompt`
+    /// - `temperature` outside 0.0..=2.0
+    /// - `summary_threshold` not above `history_message_limit`
+    /// - `api_provider` other than `grok` or `claude`
+    /// - `summary_max_tokens` outside 32..=8192
+    /// - Blank or duplicate `summary_must_include` entries
+    /// - `strip_patterns` that don't compile, match an empty line, or span lines
+    /// - `ephemeral_patterns` that don't compile or match empty text
+    pub fn validate(&self) -> Result<(), Vec<PersonaValidationError>> {
+        let mut problems = Vec::new();
+
+        if self.name.trim().is_empty() {
+            problems.push(PersonaValidationError::EmptyName);
+        }
+        if self.system_prompt.trim().is_empty() {
+            problems.push(PersonaValidationError::EmptySystemPrompt);
+        }
+        if let Some(temperature) = self.temperature.filter(|t| !(0.0..=2.0).contains(t)) {
+            problems.push(PersonaValidationError::TemperatureOutOfRange(temperature));
+        }
+        if self.summary_threshold <= self.history_message_limit {
+            problems.push(PersonaValidationError::ThresholdNotAboveLimit {
+                threshold: self.summary_threshold,
+                limit: self.history_message_limit,
+            });
+        }
+        if ApiProvider::from_name(&self.api_provider).is_none() {
+            problems.push(PersonaValidationError::UnknownProvider(self.api_provider.clone()));
+        }
+        if let Some(tokens) = self.summary_max_tokens.filter(|t| !(32..=8192).contains(t)) {
+            problems.push(PersonaValidationError::SummaryMaxTokensOutOfRange(tokens));
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        for term in &self.summary_must_include {
+            let normalized = term.trim().to_lowercase();
+            if normalized.is_empty() {
+                problems.push(PersonaValidationError::BlankMustInclude);
+            } else if !seen.insert(normalized) {
+                problems.push(PersonaValidationError::DuplicateMustInclude(term.trim().to_string()));
+            }
+        }
+
+        for pattern in &self.strip_patterns {
+            if let Err(e) = strip::compile_pattern(pattern) {
+                problems.push(PersonaValidationError::BadPattern(e.to_string()));
+            }
+        }
+        for entry in &self.ephemeral_patterns {
+            if let Err(e) = redaction::compile_pattern(&entry.pattern) {
+                problems.push(PersonaValidationError::BadPattern(e.to_string()));
+            }
+        }
+
+        if problems.is_empty() { Ok(()) } else { Err(problems) }
+    }
+
+}
+
+/// # PersonaValidationError
+///
+/// **Summary:**
+/// One thing `Persona::validate` found wrong with a persona; a file can have several.
+///
+/// **Variants:**
+/// - `EmptyName`: `name` is blank
+/// - `EmptySystemPrompt`: The prompt (inline or from `system_prompt_file`) is blank
+/// - `TemperatureOutOfRange`: `temperature` outside 0.0..=2.0
+/// - `ThresholdNotAboveLimit`: `summary_threshold` would summarize before the history could grow past `history_message_limit`
+/// - `UnknownProvider`: `api_provider` isn't `grok` or `claude`
+/// - `SummaryMaxTokensOutOfRange`: `summary_max_tokens` outside 32..=8192
+/// - `BlankMustInclude` / `DuplicateMustInclude`: A blank or repeated `summary_must_include` entry
+/// - `BadPattern`: A `strip_patterns` or `ephemeral_patterns` entry that can't be used
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum PersonaValidationError {
+    #[error("name is empty")]
+    EmptyName,
+    #[error("system prompt is empty")]
+    EmptySystemPrompt,
+    #[error("temperature must be between 0.0 and 2.0 (got {0})")]
+    TemperatureOutOfRange(f32),
+    #[error("summary_threshold ({threshold}) must be greater than history_message_limit ({limit})")]
+    ThresholdNotAboveLimit { threshold: usize, limit: usize },
+    #[error("unknown api_provider \"{0}\" (use grok or claude)")]
+    UnknownProvider(String),
+    #[error("summary_max_tokens must be between 32 and 8192 (got {0})")]
+    SummaryMaxTokensOutOfRange(u32),
+    #[error("summary_must_include has a blank entry")]
+    BlankMustInclude,
+    #[error("summary_must_include lists \"{0}\" twice")]
+    DuplicateMustInclude(String),
+    #[error("{0}")]
+    BadPattern(String),
+}
+
+/// # SummaryStyle
+///
+/// **Summary:**
+/// How the historian should lay out a summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryStyle {
+    Bullets,
+    Prose,
+}
+
+impl SummaryStyle {
+    /// Instruction appended to the historian prompt
+    pub fn instruction(&self) -> &'static str {
+        match self {
+            SummaryStyle::Bullets => "Format the summary as concise bullet points.",
+            SummaryStyle::Prose => "Write the summary as short prose paragraphs, no bullet points.",
+        }
+    }
+}
+
+/// # SummarizeMode
+///
+/// **Summary:**
+/// When a conversation's history gets summarized.
+///
+/// **Variants:**
+/// - `Auto`: Automatically once `summary_threshold` is passed
+/// - `Manual`: Only with `summarize`; past the threshold the pane gets a periodic reminder
+/// - `Off`: Never; `summarize` is refused too
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, Display, Serialize, Deserialize)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+#[serde(rename_all = "lowercase")]
+pub enum SummarizeMode {
+    #[default]
+    Auto,
+    Manual,
+    Off,
 }
 
 fn default_true() -> bool { GLOBAL_CONFIG.history.enabled }
@@ -109,6 +378,33 @@ fn default_message_limit() -> usize { GLOBAL_CONFIG.history.messages_to_keep_aft
 fn default_summary_threshold() -> usize { GLOBAL_CONFIG.history.max_messages_before_summary }
 fn default_api_provider() -> String { "grok".to_string() }
 
+/// # resolve_prompt_path
+///
+/// **Purpose:**
+/// Where a persona's `system_prompt_file` lives.
+///
+/// **Parameters:**
+/// - `yaml_path`: The persona YAML that references it
+/// - `file`: The `system_prompt_file` value; relative paths are taken from the YAML's directory
+///
+/// **Returns:**
+/// `PathBuf` - The path with `.` and `..` folded away (lexically, without touching
+/// the disk), so it compares equal to the path a watcher event reports
+pub fn resolve_prompt_path(yaml_path: &Path, file: &Path) -> PathBuf {
+    let joined = yaml_path.parent().unwrap_or(Path::new("")).join(file);
+    let mut resolved = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if matches!(resolved.components().next_back(), Some(Component::Normal(_))) => {
+                resolved.pop();
+            }
+            other => resolved.push(other),
+        }
+    }
+    resolved
+}
+
 /// # PersonaRef
 ///
 /// **Summary:**
@@ -120,49 +416,238 @@ fn default_api_provider() -> String { "grok".to_string() }
 /// ```
 pub type PersonaRef = Arc<Persona>;
 
+/// # PersonaSource
+///
+/// **Summary:**
+/// Where a discovered persona's YAML comes from.
+///
+/// **Variants:**
+/// - `File(PathBuf)`: A YAML file under the personas directory
+/// - `Builtin(String)`: A persona embedded in the binary (see `builtin` module)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PersonaSource {
+    File(PathBuf),
+    Builtin(String),
+}
+
+impl PersonaSource {
+    /// # load
+    ///
+    /// **Purpose:**
+    /// Loads the persona from its source.
+    ///
+    /// **Returns:**
+    /// `anyhow::Result<Persona>` - Loaded persona or error
+    pub fn load(&self) -> anyhow::Result<Persona> {
+        match self {
+            PersonaSource::File(path) => Persona::from_yaml_file(path),
+            PersonaSource::Builtin(name) => builtin::load_builtin(name),
+        }
+    }
+
+    pub fn is_builtin(&self) -> bool {
+        matches!(self, PersonaSource::Builtin(_))
+    }
+
+    /// Where the persona comes from, for messages: the file path, or "built-in <name>"
+    pub fn describe(&self) -> String {
+        match self {
+            PersonaSource::File(path) => path.display().to_string(),
+            PersonaSource::Builtin(name) => format!("built-in {}", name),
+        }
+    }
+}
+
+/// Personas root when neither `--personas-dir` nor `SHADOW_PERSONAS_DIR` names one
+pub const DEFAULT_PERSONAS_DIR: &str = "personas";
+
+/// Environment variable naming the personas root (`--personas-dir` wins over it)
+pub const PERSONAS_DIR_ENV: &str = "SHADOW_PERSONAS_DIR";
+
+static PERSONAS_ROOT: OnceCell<PathBuf> = OnceCell::new();
+
+/// # set_personas_root
+///
+/// **Purpose:**
+/// Sets the directory persona files, histories, archives, and experiments
+/// live under, for the whole process.
+///
+/// **Errors / Failures:**
+/// - The root was already set or already read; it can't change mid-session
+///
+/// **Details:**
+/// Call once at startup, before anything loads a persona. Until then
+/// `personas_root` is `DEFAULT_PERSONAS_DIR`, relative to the working directory.
+pub fn set_personas_root(dir: PathBuf) -> Result<(), ShadowError> {
+    PERSONAS_ROOT.set(dir).map_err(|dir| ShadowError::InvalidConfig(format!(
+        "personas directory is already {}; can't switch to {}",
+        personas_root().display(),
+        dir.display()
+    )))
+}
+
+/// The personas root (`personas/` unless set at startup)
+pub fn personas_root() -> &'static Path {
+    PERSONAS_ROOT.get_or_init(|| PathBuf::from(DEFAULT_PERSONAS_DIR))
+}
+
+/// A persona's own directory under the root (`{root}/{name}`)
+pub fn persona_dir(persona_name: &str) -> PathBuf {
+    personas_root().join(persona_name)
+}
+
+/// Directory under the root for app-wide state that belongs to no persona
+pub const STATE_DIR: &str = "state";
+
+/// Where an app-wide state file lives (`{root}/state/{file}`)
+pub fn state_path(file: &str) -> PathBuf {
+    personas_root().join(STATE_DIR).join(file)
+}
+
+/// # writable_state_path
+///
+/// **Purpose:**
+/// Same as `state_path`, creating `{root}/state` first so the file can be written.
+///
+/// **Errors / Failures:**
+/// - The state directory can't be created
+pub fn writable_state_path(file: &str) -> io::Result<PathBuf> {
+    let path = state_path(file);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    Ok(path)
+}
+
+/// State files older versions kept in the working directory
+const LEGACY_STATE_FILES: [&str; 10] = [
+    crate::twitter::spellcheck::CUSTOM_DICTIONARY_FILE,
+    crate::twitter::quota::POST_LOG_FILE,
+    crate::twitter::quota::OUTBOX_FILE,
+    crate::user::command_history::COMMAND_HISTORY_FILE,
+    crate::user::macros::MACROS_FILE,
+    crate::tui::layout::SESSION_FILE,
+    focus::FOCUS_SESSION_FILE,
+    focus::FOCUS_LOG_FILE,
+    escalation::ESCALATION_FILE,
+    crate::tui::tour::TOUR_MARKER_FILE,
+];
+
+/// # migrate_state_files
+///
+/// **Purpose:**
+/// Moves state files left in `from` by older versions into `{root}/state`.
+///
+/// **Returns:**
+/// `Vec<String>` - One line per file moved or left behind, for the startup log
+///
+/// **Details:**
+/// A file already present under the root wins; the old copy is left alone.
+/// Run once at startup, after the root is set.
+pub fn migrate_state_files(from: &Path) -> Vec<String> {
+    let mut notes = Vec::new();
+    for file in LEGACY_STATE_FILES {
+        let legacy = from.join(file);
+        let target = state_path(file);
+        if !legacy.is_file() {
+            continue;
+        }
+        if target.exists() {
+            notes.push(format!("Left {} in place: {} already exists", legacy.display(), target.display()));
+            continue;
+        }
+        let moved = writable_state_path(file).and_then(|target| {
+            fs::rename(&legacy, &target).or_else(|_| fs::copy(&legacy, &target).and_then(|_| fs::remove_file(&legacy)))
+        });
+        match moved {
+            Ok(()) => notes.push(format!("Moved {} to {}", legacy.display(), target.display())),
+            Err(e) => notes.push(format!("Couldn't move {} to {}: {}", legacy.display(), target.display(), e)),
+        }
+    }
+    notes
+}
+
 /// Discover all available personas by scanning the personas directory
 ///
 /// # How it works
-/// - Walks through `personas/` directory recursively
-/// - Finds all `.yaml` files
+/// - Walks through the personas root (`personas/` unless set at startup) recursively
+/// - Finds all `.yaml` files (skipping `state/`, which holds app state like `macros.yaml`)
 /// - Extracts persona name from directory structure
+/// - Adds embedded built-in personas that aren't present on disk
 ///
 /// # Returns
-/// Vector of (persona_name, yaml_path) tuples
+/// Vector of (persona_name, source) tuples
 ///
 /// # Example
 /// ```
 /// personas/
 ///   shadow/
-///     shadow.yaml      -> ("shadow", "personas/shadow/shadow.yaml")
+///     shadow.yaml      -> ("shadow", File("personas/shadow/shadow.yaml"))
 ///   friday/
-///     friday.yaml      -> ("friday", "personas/friday/friday.yaml")
+///     friday.yaml      -> ("friday", File("personas/friday/friday.yaml"))
 ///   custom/
-///     my_persona.yaml  -> ("custom/my_persona", "personas/custom/my_persona.yaml")
+///     my_persona.yaml  -> ("custom/my_persona", File("personas/custom/my_persona.yaml"))
+///                      -> ("historian", Builtin("historian"))
 /// ```
-pub fn discover_personas() -> Result<Vec<(String, PathBuf)>, ShadowError> {
-    let personas_dir = "personas";
+pub fn discover_personas() -> Result<Vec<(String, PersonaSource)>, ShadowError> {
+    discover_personas_in(personas_root())
+}
+
+/// # discover_personas_in
+///
+/// **Purpose:**
+/// Same as `discover_personas`, but scans an explicit directory.
+///
+/// **Parameters:**
+/// - `personas_dir`: Directory to scan
+///
+/// **Returns:**
+/// `Result<Vec<(String, PersonaSource)>, ShadowError>` - Discovered personas sorted by name
+///
+/// **Errors / Failures:**
+/// - Directory exists but is not a directory or cannot be read
+///
+/// **Details:**
+/// A missing directory is not an error: the embedded personas are returned instead.
+pub fn discover_personas_in(personas_dir: &Path) -> Result<Vec<(String, PersonaSource)>, ShadowError> {
     let mut found_personas = Vec::new();
 
-    if !std::path::Path::new(personas_dir).exists() {
-        return Err(ShadowError::IoError("personas/ directory not found".to_string()));
-    }
+    if personas_dir.exists() {
+        if !personas_dir.is_dir() {
+            return Err(ShadowError::InvalidConfig(
+                format!("{} exists but is not a directory", personas_dir.display())
+            ));
+        }
+
+        // Surface unreadable directories instead of silently falling back
+        fs::read_dir(personas_dir)?;
 
-    for entry in WalkDir::new(personas_dir)
-        .follow_links(true)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        let path = entry.path();
+        let state_dir = personas_dir.join(STATE_DIR);
+        for entry in WalkDir::new(personas_dir)
+            .follow_links(true)
+            .into_iter()
+            .filter_entry(|e| e.path() != state_dir)
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
 
-        if path.extension().and_then(|s| s.to_str()) == Some("yaml") {
-            if let Some(parent) = path.parent() {
-                if let Some(dir_name) = parent.file_name() {
-                    let persona_name = dir_name.to_string_lossy().to_string();
-                    found_personas.push((persona_name, path.to_path_buf()));
+            if path.extension().and_then(|s| s.to_str()) == Some("yaml") {
+                if let Some(parent) = path.parent() {
+                    if let Some(dir_name) = parent.file_name() {
+                        let persona_name = dir_name.to_string_lossy().to_string();
+                        found_personas.push((persona_name, PersonaSource::File(path.to_path_buf())));
+                    }
                 }
             }
         }
+    } else {
+        log_info!("{} not found, using built-in personas", personas_dir.display());
+    }
+
+    for (name, _) in builtin::BUILTIN_PERSONAS {
+        if !found_personas.iter().any(|(found, _)| found == name) {
+            found_personas.push((name.to_string(), PersonaSource::Builtin(name.to_string())));
+        }
     }
 
     found_personas.sort_by(|a, b| a.0.cmp(&b.0));
@@ -180,4 +665,272 @@ pub fn get_default_persona() -> Result<String, ShadowError> {
     personas.first()
         .map(|(name, _)| name.clone())
         .ok_or(ShadowError::IoError("No personas found".to_string()))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent_history::history::history_path;
+    use crate::test_support::{test_personas_root, ScratchDir};
+
+    fn builtin_names() -> Vec<String> {
+        let mut names: Vec<String> = builtin::BUILTIN_PERSONAS.iter().map(|(name, _)| name.to_string()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn discovery_without_a_directory_returns_the_builtins() {
+        let scratch = ScratchDir::new("discover-missing");
+        let found = discover_personas_in(&scratch.join("personas")).unwrap();
+
+        let names: Vec<String> = found.iter().map(|(name, _)| name.clone()).collect();
+        assert_eq!(names, builtin_names());
+        assert!(found.iter().all(|(_, source)| source.is_builtin()));
+    }
+
+    #[test]
+    fn discovery_in_an_empty_directory_returns_the_builtins() {
+        let scratch = ScratchDir::new("discover-empty");
+        let found = discover_personas_in(scratch.path()).unwrap();
+
+        let names: Vec<String> = found.iter().map(|(name, _)| name.clone()).collect();
+        assert_eq!(names, builtin_names());
+        assert!(found.iter().all(|(_, source)| source.is_builtin()));
+    }
+
+    #[test]
+    fn files_on_disk_take_precedence_over_builtins() {
+        let scratch = ScratchDir::new("discover-precedence");
+        let shadow = scratch.write("shadow/shadow.yaml", "name: Shadow\nsystem_prompt: custom\n");
+        let custom = scratch.write("custom/custom.yaml", "name: Custom\nsystem_prompt: hi\n");
+
+        let found = discover_personas_in(scratch.path()).unwrap();
+
+        let mut expected = vec![
+            ("custom".to_string(), PersonaSource::File(custom)),
+            ("shadow".to_string(), PersonaSource::File(shadow)),
+        ];
+        for name in builtin_names().into_iter().filter(|name| name != "shadow") {
+            expected.push((name.clone(), PersonaSource::Builtin(name)));
+        }
+        expected.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(found, expected);
+        assert_eq!(found.iter().filter(|(name, _)| name == "shadow").count(), 1);
+    }
+
+    #[test]
+    fn a_path_that_is_not_a_directory_is_an_error() {
+        let scratch = ScratchDir::new("discover-file");
+        let file = scratch.write("personas", "not a directory");
+
+        assert!(discover_personas_in(&file).is_err());
+    }
+
+    #[test]
+    fn persona_data_resolves_under_the_configured_root() {
+        let root = test_personas_root();
+
+        assert_eq!(persona_dir("rootcheck"), root.join("rootcheck"));
+        assert_eq!(history_path("rootcheck"), root.join("rootcheck/history/rootcheck_history.json"));
+        assert!(set_personas_root(PathBuf::from("elsewhere")).is_err());
+        assert_eq!(personas_root(), root);
+    }
+
+    #[test]
+    fn discovery_skips_the_state_directory() {
+        let scratch = ScratchDir::new("discover-state");
+        scratch.write("custom/custom.yaml", "name: Custom\nsystem_prompt: hi\n");
+        scratch.write("state/macros.yaml", "macros: {}\n");
+
+        let found = discover_personas_in(scratch.path()).unwrap();
+        assert!(found.iter().any(|(name, _)| name == "custom"));
+        assert!(!found.iter().any(|(name, _)| name == STATE_DIR));
+    }
+
+    #[test]
+    fn legacy_state_files_move_under_the_root() {
+        let root = test_personas_root();
+        let legacy = ScratchDir::new("legacy-state");
+        legacy.write(escalation::ESCALATION_FILE, "{}");
+        legacy.write(crate::tui::layout::SESSION_FILE, "old session");
+        let kept = writable_state_path(crate::tui::layout::SESSION_FILE).unwrap();
+        fs::write(&kept, "current session").unwrap();
+
+        let notes = migrate_state_files(legacy.path());
+
+        assert_eq!(fs::read_to_string(root.join("state").join(escalation::ESCALATION_FILE)).unwrap(), "{}");
+        assert!(!legacy.join(escalation::ESCALATION_FILE).exists());
+        assert_eq!(fs::read_to_string(&kept).unwrap(), "current session");
+        assert!(legacy.join(crate::tui::layout::SESSION_FILE).exists());
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes.iter().filter(|note| note.starts_with("Left")).count(), 1);
+    }
+
+    fn valid_persona() -> Persona {
+        Persona::from_yaml_str("name: Test\nsystem_prompt: Be brief.\nhistory_message_limit: 10\nsummary_threshold: 20\n").unwrap()
+    }
+
+    fn problems(persona: &Persona) -> Vec<PersonaValidationError> {
+        persona.validate().err().unwrap_or_default()
+    }
+
+    #[test]
+    fn a_sensible_persona_validates() {
+        let mut persona = valid_persona();
+        persona.temperature = Some(2.0);
+        persona.api_provider = "claude".to_string();
+        persona.summary_max_tokens = Some(32);
+        persona.summary_must_include = vec!["names".to_string(), "dates".to_string()];
+        persona.strip_patterns = vec!["^Sources:".to_string()];
+
+        assert_eq!(persona.validate(), Ok(()));
+    }
+
+    #[test]
+    fn a_blank_name_is_rejected() {
+        let mut persona = valid_persona();
+        persona.name = "  ".to_string();
+        assert_eq!(problems(&persona), [PersonaValidationError::EmptyName]);
+    }
+
+    #[test]
+    fn a_blank_system_prompt_is_rejected() {
+        let mut persona = valid_persona();
+        persona.system_prompt = "\n\t".to_string();
+        assert_eq!(problems(&persona), [PersonaValidationError::EmptySystemPrompt]);
+    }
+
+    #[test]
+    fn temperatures_outside_zero_to_two_are_rejected() {
+        for temperature in [-0.1, 2.01, f32::INFINITY] {
+            let mut persona = valid_persona();
+            persona.temperature = Some(temperature);
+            assert_eq!(problems(&persona), [PersonaValidationError::TemperatureOutOfRange(temperature)]);
+        }
+    }
+
+    #[test]
+    fn a_threshold_not_above_the_limit_is_rejected() {
+        let mut persona = valid_persona();
+        persona.summary_threshold = 10;
+        assert_eq!(
+            problems(&persona),
+            [PersonaValidationError::ThresholdNotAboveLimit { threshold: 10, limit: 10 }]
+        );
+    }
+
+    #[test]
+    fn an_unknown_provider_is_rejected() {
+        let mut persona = valid_persona();
+        persona.api_provider = "Claude".to_string();
+        assert_eq!(problems(&persona), [PersonaValidationError::UnknownProvider("Claude".to_string())]);
+    }
+
+    #[test]
+    fn summary_max_tokens_outside_the_range_is_rejected() {
+        for tokens in [31, 8193] {
+            let mut persona = valid_persona();
+            persona.summary_max_tokens = Some(tokens);
+            assert_eq!(problems(&persona), [PersonaValidationError::SummaryMaxTokensOutOfRange(tokens)]);
+        }
+    }
+
+    #[test]
+    fn blank_and_repeated_must_include_entries_are_rejected() {
+        let mut persona = valid_persona();
+        persona.summary_must_include = vec!["Deadlines".to_string(), " ".to_string(), " deadlines ".to_string()];
+        assert_eq!(
+            problems(&persona),
+            [
+                PersonaValidationError::BlankMustInclude,
+                PersonaValidationError::DuplicateMustInclude("deadlines".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unusable_strip_patterns_are_rejected() {
+        for pattern in ["(unclosed", "^.*$", "a\\nb"] {
+            let mut persona = valid_persona();
+            persona.strip_patterns = vec![pattern.to_string()];
+            let found = problems(&persona);
+            assert!(
+                matches!(found.as_slice(), [PersonaValidationError::BadPattern(msg)] if msg.contains(pattern)),
+                "{:?} for {}", found, pattern
+            );
+        }
+    }
+
+    #[test]
+    fn unusable_ephemeral_patterns_are_rejected() {
+        for pattern in ["[unclosed", "x*"] {
+            let mut persona = valid_persona();
+            persona.ephemeral_patterns = vec![EphemeralPattern { pattern: pattern.to_string(), scope: Default::default() }];
+            let found = problems(&persona);
+            assert!(
+                matches!(found.as_slice(), [PersonaValidationError::BadPattern(msg)] if msg.contains(pattern)),
+                "{:?} for {}", found, pattern
+            );
+        }
+    }
+
+    #[test]
+    fn every_problem_is_reported_at_once() {
+        let yaml = "name: \"\"\nsystem_prompt: \" \"\ntemperature: 3.0\napi_provider: openai\n";
+        let error = Persona::from_yaml_str(yaml).unwrap_err().to_string();
+
+        assert!(error.starts_with("unnamed persona: name is empty; system prompt is empty; temperature"), "{}", error);
+        assert!(error.contains("unknown api_provider \"openai\""), "{}", error);
+    }
+
+    #[test]
+    fn prompt_file_paths_resolve_from_the_yaml_directory() {
+        let cases = [
+            ("personas/friday/friday.yaml", "prompt.md", "personas/friday/prompt.md"),
+            ("personas/team/friday/friday.yaml", "../shared/prompt.md", "personas/team/shared/prompt.md"),
+            ("./personas/friday/friday.yaml", "./prompts/../prompt.md", "personas/friday/prompt.md"),
+            ("friday.yaml", "../prompt.md", "../prompt.md"),
+            ("personas/friday/friday.yaml", "/srv/prompts/friday.md", "/srv/prompts/friday.md"),
+        ];
+        for (yaml, file, expected) in cases {
+            assert_eq!(resolve_prompt_path(Path::new(yaml), Path::new(file)), PathBuf::from(expected), "{} + {}", yaml, file);
+        }
+    }
+
+    #[test]
+    fn a_prompt_file_is_read_when_the_persona_loads() {
+        let dir = ScratchDir::new("prompt-file");
+        dir.write("team/shared/prompt.md", "Be brief.\nNever guess.\n");
+        let nested = dir.write("team/friday/friday.yaml", "name: friday\nsystem_prompt_file: ../shared/prompt.md\n");
+        let beside = dir.write("solo/solo.yaml", "name: solo\nsystem_prompt_file: prompt.md\n");
+        dir.write("solo/prompt.md", "Talk.");
+
+        let friday = Persona::from_yaml_file(&nested).unwrap();
+        assert_eq!(friday.system_prompt, "Be brief.\nNever guess.\n");
+        assert_eq!(friday.system_prompt_file.as_deref(), Some(Path::new("../shared/prompt.md")));
+        assert_eq!(Persona::from_yaml_file(&beside).unwrap().system_prompt, "Talk.");
+    }
+
+    #[test]
+    fn a_prompt_must_come_from_exactly_one_place() {
+        let dir = ScratchDir::new("prompt-file-errors");
+        dir.write("both/prompt.md", "Talk.");
+        let cases = [
+            ("both/both.yaml", "name: both\nsystem_prompt: Talk.\nsystem_prompt_file: prompt.md\n", "both: set either system_prompt or system_prompt_file, not both".to_string()),
+            ("neither/neither.yaml", "name: neither\n", "neither: needs a system_prompt or a system_prompt_file".to_string()),
+            (
+                "missing/missing.yaml",
+                "name: missing\nsystem_prompt_file: prompt.md\n",
+                format!("missing: can't read system_prompt_file {}", dir.join("missing/prompt.md").display()),
+            ),
+        ];
+        for (file, yaml, expected) in cases {
+            let error = Persona::from_yaml_file(&dir.write(file, yaml)).unwrap_err().to_string();
+            assert!(error.starts_with(&expected), "{}: {}", file, error);
+        }
+
+        let error = Persona::from_yaml_str("name: embedded\nsystem_prompt_file: prompt.md\n").unwrap_err().to_string();
+        assert_eq!(error, "embedded: system_prompt_file is only supported for persona files on disk");
+    }
+}
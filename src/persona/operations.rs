@@ -1,5 +1,14 @@
 use crate::persona::agent::AgentInfo;
 use crate::persona::agent_manager::AgentManager;
+use crate::persona::background::BackgroundTask;
+use crate::persona::experiment::ExperimentRegistry;
+use crate::persona::focus::{FocusBlock, FocusTimer};
+use crate::persona::jobs::{GenerationJob, JobId, JobRegistry};
+use crate::persona::reload::ReloadReport;
+use crate::user::macros::{MacroRecording, MacroReplay};
+use crate::tui::layout::LayoutMode;
+use crate::tui::inspect::InspectView;
+use crate::utilities::accessibility;
 use uuid::Uuid;
 use crate::prelude::*;
 
@@ -12,15 +21,57 @@ pub trait AgentOperations {
     fn get_agent_info_mut(&mut self, id: Uuid) -> Option<&mut AgentInfo>;
 
     fn display_message(&mut self, msg: String);
+    fn display_warning(&mut self, msg: String);
+    fn display_agent_message(&mut self, persona_name: &str, msg: String);
+
+    fn set_global_filter(&mut self, filter: Option<GlobalFilter>);
+    fn clear_global_messages(&mut self);
+    fn set_layout_mode(&mut self, mode: LayoutMode) -> bool;
+    fn set_show_timestamps(&mut self, show: Option<bool>) -> Option<bool>;
 
     fn add_new_agent(&mut self, id: Uuid, persona: PersonaRef);
     fn remove_agent(&mut self, id: Uuid);
 
     fn get_persona(&self, name: &str) -> Option<PersonaRef>;
+    fn get_all_personas(&self) -> Vec<PersonaRef>;
     fn get_current_agent_id(&self) -> Option<Uuid>;
     fn set_current_agent_id(&mut self, id: Option<Uuid>);
     fn get_agent_order(&self) -> &Vec<Uuid>;
     fn get_all_agent_names(&self) -> Vec<(Uuid, String)>;
+
+    fn experiments(&self) -> &ExperimentRegistry;
+    fn experiments_mut(&mut self) -> &mut ExperimentRegistry;
+
+    fn spawn_job(&mut self, job: GenerationJob) -> Option<JobId>;
+    fn jobs(&self) -> &JobRegistry;
+    fn jobs_mut(&mut self) -> &mut JobRegistry;
+
+    fn macro_recording(&self) -> Option<&MacroRecording>;
+    fn start_macro_recording(&mut self, recording: MacroRecording) -> bool;
+    fn take_macro_recording(&mut self) -> Option<MacroRecording>;
+    fn start_macro_replay(&mut self, replay: MacroReplay) -> bool;
+    fn stop_macro_replay(&mut self) -> Option<MacroReplay>;
+
+    fn refresh_persona_status(&mut self, id: Uuid) -> Option<String>;
+    fn rescan_personas(&mut self, apply: bool) -> Result<ReloadReport, ShadowError>;
+
+    fn is_agent_muted(&self, id: Uuid) -> bool;
+    fn set_agent_muted(&mut self, id: Uuid, muted: bool) -> bool;
+    fn is_agent_speaking(&self, id: Uuid) -> bool;
+    fn set_agent_speaking(&mut self, id: Uuid, speaking: bool) -> bool;
+    fn stop_speech(&mut self) -> bool;
+
+    fn focus_timer(&self) -> Option<&FocusTimer>;
+    fn focus_timer_mut(&mut self) -> Option<&mut FocusTimer>;
+    fn start_focus(&mut self, block: FocusBlock) -> bool;
+    fn end_focus(&mut self) -> Option<FocusBlock>;
+    fn arm_check_in_escalation(&mut self, persona_name: &str);
+    fn check_in_answered(&mut self, persona_name: &str);
+
+    fn start_tour(&mut self) -> bool;
+    fn open_inspect(&mut self, view: InspectView) -> bool;
+
+    fn run_in_background(&mut self, task: BackgroundTask);
 }
 
 impl AgentOperations for AgentManager {
@@ -44,6 +95,32 @@ impl AgentOperations for AgentManager {
         println!("{}", msg);
     }
 
+    fn display_warning(&mut self, msg: String) {
+        println!("{}", msg);
+    }
+
+    fn display_agent_message(&mut self, _persona_name: &str, msg: String) {
+        println!("{}", msg);
+    }
+
+    fn set_global_filter(&mut self, _filter: Option<GlobalFilter>) {
+        println!("Global pane filtering is only available in TUI mode.");
+    }
+
+    fn clear_global_messages(&mut self) {
+        println!("Global pane clearing is only available in TUI mode.");
+    }
+
+    fn set_layout_mode(&mut self, _mode: LayoutMode) -> bool {
+        println!("Layouts are only available in TUI mode.");
+        false
+    }
+
+    fn set_show_timestamps(&mut self, _show: Option<bool>) -> Option<bool> {
+        println!("Message timestamps are only available in TUI mode.");
+        None
+    }
+
     fn add_new_agent(&mut self, id: Uuid, persona: PersonaRef) {
         self.add_agent(id, persona);
     }
@@ -55,6 +132,10 @@ impl AgentOperations for AgentManager {
     fn get_persona(&self, name: &str) -> Option<PersonaRef> {
         self.personas.get(name).cloned()
     }
+
+    fn get_all_personas(&self) -> Vec<PersonaRef> {
+        self.personas.values().cloned().collect()
+    }
     
     fn get_current_agent_id(&self) -> Option<Uuid> {
         self.current_agent
@@ -73,6 +154,116 @@ impl AgentOperations for AgentManager {
             .map(|(id, agent)| (*id, agent.persona_name.clone()))
             .collect()
     }
+
+    fn experiments(&self) -> &ExperimentRegistry {
+        &self.experiments
+    }
+
+    fn experiments_mut(&mut self) -> &mut ExperimentRegistry {
+        &mut self.experiments
+    }
+
+    fn spawn_job(&mut self, job: GenerationJob) -> Option<JobId> {
+        AgentManager::spawn_job(self, job)
+    }
+
+    fn jobs(&self) -> &JobRegistry {
+        &self.jobs
+    }
+
+    fn jobs_mut(&mut self) -> &mut JobRegistry {
+        &mut self.jobs
+    }
+
+    fn macro_recording(&self) -> Option<&MacroRecording> {
+        None
+    }
+
+    fn start_macro_recording(&mut self, _recording: MacroRecording) -> bool {
+        println!("Macros are only available in TUI mode.");
+        false
+    }
+
+    fn take_macro_recording(&mut self) -> Option<MacroRecording> {
+        None
+    }
+
+    fn start_macro_replay(&mut self, _replay: MacroReplay) -> bool {
+        println!("Macros are only available in TUI mode.");
+        false
+    }
+
+    fn stop_macro_replay(&mut self) -> Option<MacroReplay> {
+        None
+    }
+
+    fn refresh_persona_status(&mut self, id: Uuid) -> Option<String> {
+        AgentManager::refresh_persona_status(self, id)
+    }
+
+    fn rescan_personas(&mut self, apply: bool) -> Result<ReloadReport, ShadowError> {
+        AgentManager::rescan_personas(self, apply)
+    }
+
+    fn is_agent_muted(&self, _id: Uuid) -> bool {
+        false
+    }
+
+    fn set_agent_muted(&mut self, _id: Uuid, _muted: bool) -> bool {
+        println!("Muting is only available in TUI mode.");
+        false
+    }
+
+    fn is_agent_speaking(&self, _id: Uuid) -> bool {
+        false
+    }
+
+    fn set_agent_speaking(&mut self, _id: Uuid, _speaking: bool) -> bool {
+        println!("Speaking replies is only available in TUI mode.");
+        false
+    }
+
+    fn stop_speech(&mut self) -> bool {
+        false
+    }
+
+    fn focus_timer(&self) -> Option<&FocusTimer> {
+        None
+    }
+
+    fn focus_timer_mut(&mut self) -> Option<&mut FocusTimer> {
+        None
+    }
+
+    fn start_focus(&mut self, _block: FocusBlock) -> bool {
+        println!("Focus blocks are only available in TUI mode.");
+        false
+    }
+
+    fn end_focus(&mut self) -> Option<FocusBlock> {
+        None
+    }
+
+    fn arm_check_in_escalation(&mut self, _persona_name: &str) {}
+
+    fn check_in_answered(&mut self, _persona_name: &str) {}
+
+    fn start_tour(&mut self) -> bool {
+        println!("The tour is only available in TUI mode.");
+        false
+    }
+
+    fn open_inspect(&mut self, _view: InspectView) -> bool {
+        false
+    }
+
+    // Nothing redraws in CLI mode, so waiting here costs nothing
+    fn run_in_background(&mut self, task: BackgroundTask) {
+        let reports = tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(task));
+        for report in reports {
+            println!("{}", report.msg);
+        }
+    }
 }
 
 impl AgentOperations for ShadowApp {
@@ -93,7 +284,43 @@ impl AgentOperations for ShadowApp {
     }
 
     fn display_message(&mut self, msg: String) {
-        self.add_message(msg);
+        self.push_global_message(msg, MessageSource::Global, MessageKind::Command);
+    }
+
+    fn display_warning(&mut self, msg: String) {
+        self.push_global_message(msg, MessageSource::Global, MessageKind::Warning);
+    }
+
+    fn display_agent_message(&mut self, persona_name: &str, msg: String) {
+        self.push_global_message(msg, MessageSource::Agent(persona_name.to_string()), MessageKind::Command);
+    }
+
+    fn set_global_filter(&mut self, filter: Option<GlobalFilter>) {
+        let notice = match &filter {
+            Some(filter) => format!("Global pane filtered to: {}", filter.label()),
+            None => "Global pane filter cleared.".to_string(),
+        };
+        self.global_filter = filter;
+        self.scroll = 0;
+        self.add_message(notice);
+    }
+
+    fn set_layout_mode(&mut self, mode: LayoutMode) -> bool {
+        self.set_layout(mode);
+        true
+    }
+
+    fn set_show_timestamps(&mut self, show: Option<bool>) -> Option<bool> {
+        self.show_timestamps = show.unwrap_or(!self.show_timestamps);
+        Some(self.show_timestamps)
+    }
+
+    fn clear_global_messages(&mut self) {
+        self.clear_global_messages();
+        // An empty pane gives a screen reader nothing to announce
+        if accessibility::is_enabled() {
+            self.add_message("Global pane cleared.");
+        }
     }
 
     fn add_new_agent(&mut self, id: Uuid, persona: PersonaRef) {
@@ -107,13 +334,17 @@ impl AgentOperations for ShadowApp {
     fn get_persona(&self, name: &str) -> Option<PersonaRef> {
         self.agent_manager.personas.get(name).cloned()
     }
+
+    fn get_all_personas(&self) -> Vec<PersonaRef> {
+        self.agent_manager.personas.values().cloned().collect()
+    }
     
     fn get_current_agent_id(&self) -> Option<Uuid> {
         self.agent_manager.current_agent
     }
     
     fn set_current_agent_id(&mut self, id: Option<Uuid>) {
-        self.agent_manager.current_agent = id;
+        self.set_current_agent(id);
     }
     
     fn get_agent_order(&self) -> &Vec<Uuid> {
@@ -125,4 +356,133 @@ impl AgentOperations for ShadowApp {
             .map(|(id, agent)| (*id, agent.persona_name.clone()))
             .collect()
     }
-}
\ No newline at end of file
+
+    fn experiments(&self) -> &ExperimentRegistry {
+        &self.agent_manager.experiments
+    }
+
+    fn experiments_mut(&mut self) -> &mut ExperimentRegistry {
+        &mut self.agent_manager.experiments
+    }
+
+    fn spawn_job(&mut self, job: GenerationJob) -> Option<JobId> {
+        self.agent_manager.spawn_job(job)
+    }
+
+    fn jobs(&self) -> &JobRegistry {
+        &self.agent_manager.jobs
+    }
+
+    fn jobs_mut(&mut self) -> &mut JobRegistry {
+        &mut self.agent_manager.jobs
+    }
+
+    fn macro_recording(&self) -> Option<&MacroRecording> {
+        self.macro_recording.as_ref()
+    }
+
+    fn start_macro_recording(&mut self, recording: MacroRecording) -> bool {
+        self.macro_recording = Some(recording);
+        true
+    }
+
+    fn take_macro_recording(&mut self) -> Option<MacroRecording> {
+        self.macro_recording.take()
+    }
+
+    fn start_macro_replay(&mut self, replay: MacroReplay) -> bool {
+        self.macro_replay = Some(replay);
+        true
+    }
+
+    fn stop_macro_replay(&mut self) -> Option<MacroReplay> {
+        self.macro_replay.take()
+    }
+
+    fn refresh_persona_status(&mut self, id: Uuid) -> Option<String> {
+        self.agent_manager.refresh_persona_status(id)
+    }
+
+    fn rescan_personas(&mut self, apply: bool) -> Result<ReloadReport, ShadowError> {
+        self.agent_manager.rescan_personas(apply)
+    }
+
+    fn is_agent_muted(&self, id: Uuid) -> bool {
+        self.agent_panes.get(&id).is_some_and(|pane| pane.muted)
+    }
+
+    fn set_agent_muted(&mut self, id: Uuid, muted: bool) -> bool {
+        match self.agent_panes.get_mut(&id) {
+            Some(pane) => {
+                pane.muted = muted;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn is_agent_speaking(&self, id: Uuid) -> bool {
+        self.agent_panes.get(&id).is_some_and(|pane| pane.speaking)
+    }
+
+    fn set_agent_speaking(&mut self, id: Uuid, speaking: bool) -> bool {
+        let Some(pane) = self.agent_panes.get_mut(&id) else {
+            return false;
+        };
+        pane.speaking = speaking;
+        if speaking {
+            // Started now, so a missing speech program is reported right away
+            self.speaker(id);
+        } else {
+            self.stop_speech();
+        }
+        true
+    }
+
+    fn stop_speech(&mut self) -> bool {
+        self.speaker.as_mut().is_some_and(|speaker| speaker.stop())
+    }
+
+    fn focus_timer(&self) -> Option<&FocusTimer> {
+        self.focus.as_ref()
+    }
+
+    fn focus_timer_mut(&mut self) -> Option<&mut FocusTimer> {
+        self.focus.as_mut()
+    }
+
+    fn start_focus(&mut self, block: FocusBlock) -> bool {
+        ShadowApp::start_focus(self, block);
+        true
+    }
+
+    fn end_focus(&mut self) -> Option<FocusBlock> {
+        ShadowApp::end_focus(self)
+    }
+
+    fn arm_check_in_escalation(&mut self, persona_name: &str) {
+        ShadowApp::arm_check_in_escalation(self, persona_name);
+    }
+
+    fn check_in_answered(&mut self, persona_name: &str) {
+        ShadowApp::check_in_answered(self, persona_name);
+    }
+
+    fn start_tour(&mut self) -> bool {
+        ShadowApp::start_tour(self);
+        true
+    }
+
+    fn open_inspect(&mut self, view: InspectView) -> bool {
+        // A screen reader gets the plain-text version in the Global pane instead
+        if accessibility::is_enabled() {
+            return false;
+        }
+        self.inspect = Some(view);
+        true
+    }
+
+    fn run_in_background(&mut self, task: BackgroundTask) {
+        self.background.spawn(task);
+    }
+}
@@ -0,0 +1,620 @@
+//! # Daegonica Module: persona::reload
+//!
+//! **Purpose:** Turn bursts of persona-file change events into one reload
+//!
+//! **Context:**
+//! - Editors save with truncate-then-write or write-temp-then-rename, firing several events per save
+//! - A git checkout can touch many persona files at once
+//! - `ShadowApp::poll_channels` polls the watcher and applies each plan through `AgentManager::apply_reload`
+//!
+//! **Responsibilities:**
+//! - Collect changed paths until the personas directory has been quiet for a short window
+//! - Work out each file's net effect by comparing content hashes (added/modified/removed/unchanged)
+//! - Count a persona's `system_prompt_file` as part of its YAML, so editing only the prompt reloads the persona
+//! - Watch the personas directory with `notify` and feed its events to the aggregator
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::sync::mpsc as std_mpsc;
+use std::time::{Duration, Instant};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use walkdir::WalkDir;
+use crate::prelude::*;
+use crate::persona::resolve_prompt_path;
+
+/// # FileChange
+///
+/// **Summary:**
+/// Net effect of a burst of events on one persona file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChange {
+    Added,
+    Modified,
+    Removed,
+}
+
+impl FileChange {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            FileChange::Added => "added",
+            FileChange::Modified => "modified",
+            FileChange::Removed => "removed",
+        }
+    }
+}
+
+/// # PlannedChange
+///
+/// **Summary:**
+/// One persona file to reload.
+///
+/// **Fields:**
+/// - `persona`: Persona name, taken from the file's directory as `discover_personas` does
+/// - `path`: The YAML file
+/// - `change`: What happened to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedChange {
+    pub persona: String,
+    pub path: PathBuf,
+    pub change: FileChange,
+}
+
+/// # ReloadPlan
+///
+/// **Summary:**
+/// Everything one quiet window's worth of events amounts to.
+///
+/// **Fields:**
+/// - `changes`: Files to reload, sorted by path
+/// - `unchanged`: Personas whose files were rewritten with identical content
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReloadPlan {
+    pub changes: Vec<PlannedChange>,
+    pub unchanged: Vec<String>,
+}
+
+/// Whether a path can be a persona file (editor temp and swap files never are)
+fn is_persona_file(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some("yaml")
+}
+
+/// Persona name for a file: its directory's name
+fn persona_name(path: &Path) -> Option<String> {
+    path.parent()?.file_name().map(|name| name.to_string_lossy().to_string())
+}
+
+/// The `system_prompt_file` a persona YAML references, resolved; None if it has none or doesn't parse
+fn prompt_reference(path: &Path, yaml: &[u8]) -> Option<PathBuf> {
+    let value: serde_yaml::Value = serde_yaml::from_slice(yaml).ok()?;
+    let file = value.get("system_prompt_file")?.as_str()?;
+    Some(resolve_prompt_path(path, Path::new(file)))
+}
+
+/// # snapshot
+///
+/// **Purpose:**
+/// Hashes a persona file together with the prompt file it references.
+///
+/// **Returns:**
+/// `Option<(u64, Option<PathBuf>)>` - The combined hash and the prompt file, or None if the YAML doesn't exist
+///
+/// **Details:**
+/// A referenced prompt file that is missing still hashes (as absent), so
+/// creating or deleting it later counts as a modification of the persona.
+fn snapshot(path: &Path, read: &impl Fn(&Path) -> Option<Vec<u8>>) -> Option<(u64, Option<PathBuf>)> {
+    let yaml = read(path)?;
+    let prompt = prompt_reference(path, &yaml);
+    let mut hasher = DefaultHasher::new();
+    yaml.hash(&mut hasher);
+    prompt.as_deref().map(read).hash(&mut hasher);
+    Some((hasher.finish(), prompt))
+}
+
+/// # ReloadAggregator
+///
+/// **Summary:**
+/// Debounces change events and decides what actually changed.
+///
+/// **Fields:**
+/// - `window`: How long the directory must be quiet before a plan is produced
+/// - `pending`: Paths seen since the last plan
+/// - `last_event`: When the most recent event arrived
+/// - `known`: Content hash of every persona file (with its prompt file) as of the last plan
+/// - `prompt_files`: Persona file to the `system_prompt_file` it references, as of the last plan
+///
+/// **Details:**
+/// Events only mark a path as worth checking; what they say happened is
+/// ignored. Once the window passes with no new events, every marked path is
+/// read and compared to its known hash. That way a truncate-then-write save, a
+/// temp-file rename, or a delete-and-recreate all come out as one modification
+/// (or none, if the bytes didn't change). An event on a prompt file marks
+/// every persona file referencing it, so it reloads through the same path.
+#[derive(Debug, Clone)]
+pub struct ReloadAggregator {
+    window: Duration,
+    pending: Vec<PathBuf>,
+    last_event: Option<Instant>,
+    known: HashMap<PathBuf, u64>,
+    prompt_files: HashMap<PathBuf, PathBuf>,
+}
+
+impl ReloadAggregator {
+    /// # new
+    ///
+    /// **Purpose:**
+    /// Creates an aggregator that starts from the given files' current contents.
+    ///
+    /// **Parameters:**
+    /// - `window`: Quiet period before a plan is produced
+    /// - `files`: Persona files that exist now
+    /// - `read`: Reads a file's content, as for `take_plan`
+    pub fn new(window: Duration, files: impl IntoIterator<Item = PathBuf>, read: impl Fn(&Path) -> Option<Vec<u8>>) -> Self {
+        let mut aggregator = Self {
+            window,
+            pending: Vec::new(),
+            last_event: None,
+            known: HashMap::new(),
+            prompt_files: HashMap::new(),
+        };
+        for path in files {
+            if let Some((hash, prompt)) = snapshot(&path, &read) {
+                aggregator.remember(path, Some(hash), prompt);
+            }
+        }
+        aggregator
+    }
+
+    /// Stores a file's latest hash and prompt reference (None hash: the file is gone)
+    fn remember(&mut self, path: PathBuf, hash: Option<u64>, prompt: Option<PathBuf>) {
+        match prompt {
+            Some(prompt) => self.prompt_files.insert(path.clone(), prompt),
+            None => self.prompt_files.remove(&path),
+        };
+        match hash {
+            Some(hash) => self.known.insert(path, hash),
+            None => self.known.remove(&path),
+        };
+    }
+
+    /// # scan
+    ///
+    /// **Purpose:**
+    /// Creates an aggregator from the persona files currently under `dir`.
+    pub fn scan(dir: &Path, window: Duration) -> Self {
+        let files = WalkDir::new(dir)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| is_persona_file(entry.path()))
+            .map(|entry| entry.into_path());
+        Self::new(window, files, |path| fs::read(path).ok())
+    }
+
+    /// # record
+    ///
+    /// **Purpose:**
+    /// Notes that `path` changed somehow.
+    ///
+    /// **Details:**
+    /// A YAML path is marked itself; a referenced prompt file marks the persona
+    /// files that use it. Anything else is ignored.
+    pub fn record(&mut self, path: PathBuf, at: Instant) {
+        let marked: Vec<PathBuf> = if is_persona_file(&path) {
+            vec![path]
+        } else {
+            self.prompt_files.iter()
+                .filter(|(_, prompt)| **prompt == path)
+                .map(|(yaml, _)| yaml.clone())
+                .collect()
+        };
+        if marked.is_empty() {
+            return;
+        }
+        for path in marked {
+            if !self.pending.contains(&path) {
+                self.pending.push(path);
+            }
+        }
+        self.last_event = Some(at);
+    }
+
+    /// # take_plan
+    ///
+    /// **Purpose:**
+    /// Produces a plan once the window has passed since the last event.
+    ///
+    /// **Parameters:**
+    /// - `now`: Current time
+    /// - `read`: Reads a file's content, None if it doesn't exist (a parameter so plans can be computed without a disk)
+    ///
+    /// **Returns:**
+    /// `Option<ReloadPlan>` - None while events are still arriving, nothing is pending,
+    /// or every pending path was a temporary file that came and went
+    pub fn take_plan(&mut self, now: Instant, read: impl Fn(&Path) -> Option<Vec<u8>>) -> Option<ReloadPlan> {
+        let last_event = self.last_event?;
+        if now.duration_since(last_event) < self.window {
+            return None;
+        }
+        self.last_event = None;
+
+        let mut changes = BTreeMap::new();
+        let mut unchanged = Vec::new();
+        for path in std::mem::take(&mut self.pending) {
+            let Some(persona) = persona_name(&path) else {
+                continue;
+            };
+            let current = snapshot(&path, &read);
+            let change = match (self.known.get(&path).copied(), current.as_ref().map(|(hash, _)| *hash)) {
+                (None, None) => continue,
+                (Some(_), None) => FileChange::Removed,
+                (None, Some(_)) => FileChange::Added,
+                (Some(before), Some(after)) if before == after => {
+                    unchanged.push(persona);
+                    continue;
+                }
+                (Some(_), Some(_)) => FileChange::Modified,
+            };
+            let (hash, prompt) = current.unzip();
+            self.remember(path.clone(), hash, prompt.flatten());
+            changes.insert(path.clone(), PlannedChange { persona, path, change });
+        }
+
+        if changes.is_empty() && unchanged.is_empty() {
+            return None;
+        }
+        unchanged.sort();
+        Some(ReloadPlan { changes: changes.into_values().collect(), unchanged })
+    }
+}
+
+/// # ReloadReport
+///
+/// **Summary:**
+/// What applying a plan did, for the single notification.
+///
+/// **Fields:**
+/// - `applied`: Personas reloaded and how they changed
+/// - `unchanged`: Count of files rewritten without changes
+/// - `failures`: Files that couldn't be loaded, with the error (the last good version stays)
+/// - `agents_updated`: Open agents switched to the reloaded persona (`reload --apply`)
+/// - `agents_skipped`: Open agents `reload --apply` couldn't switch, with the reason
+#[derive(Debug, Clone, Default)]
+pub struct ReloadReport {
+    pub applied: Vec<(String, FileChange)>,
+    pub unchanged: usize,
+    pub failures: Vec<(String, String)>,
+    pub agents_updated: Vec<String>,
+    pub agents_skipped: Vec<(String, String)>,
+}
+
+impl ReloadReport {
+    /// # summary
+    ///
+    /// **Purpose:**
+    /// One-line notification, e.g. "Personas updated: friday modified, scratch added, 2 unchanged",
+    /// followed by failures and any open agents updated or skipped.
+    ///
+    /// **Returns:**
+    /// `Option<String>` - None when nothing changed (files were only rewritten with identical content)
+    pub fn summary(&self) -> Option<String> {
+        if self.applied.is_empty() && self.failures.is_empty()
+            && self.agents_updated.is_empty() && self.agents_skipped.is_empty()
+        {
+            return None;
+        }
+        let mut parts: Vec<String> = self.applied.iter()
+            .map(|(persona, change)| format!("{} {}", persona, change.describe()))
+            .collect();
+        if self.unchanged > 0 {
+            parts.push(format!("{} unchanged", self.unchanged));
+        }
+        let mut summary = if self.applied.is_empty() && self.failures.is_empty() {
+            "Personas unchanged".to_string()
+        } else if self.applied.is_empty() {
+            "Personas not updated".to_string()
+        } else {
+            format!("Personas updated: {}", parts.join(", "))
+        };
+        for (persona, error) in &self.failures {
+            summary.push_str(&format!("\n  {} failed to load (keeping the last good version): {}", persona, error));
+        }
+        if !self.agents_updated.is_empty() {
+            summary.push_str(&format!("\nOpen agents updated: {}", self.agents_updated.join(", ")));
+        }
+        for (agent, reason) in &self.agents_skipped {
+            summary.push_str(&format!("\n  {} not updated: {}", agent, reason));
+        }
+        let agents_handled = !self.agents_updated.is_empty() || !self.agents_skipped.is_empty();
+        if !agents_handled && self.applied.iter().any(|(_, change)| *change == FileChange::Modified) {
+            summary.push_str("\nOpen agents keep their configuration; `new <persona>` uses the update, `reload --apply` updates them.");
+        } else if self.applied.iter().any(|(_, change)| *change == FileChange::Removed) {
+            summary.push_str("\nOpen agents keep their configuration; `new <persona>` uses the update.");
+        }
+        Some(summary)
+    }
+}
+
+/// # PersonaWatcher
+///
+/// **Summary:**
+/// `notify` watcher on the personas directory, feeding a `ReloadAggregator`.
+///
+/// **Details:**
+/// The watcher thread only forwards events over a channel; `poll` drains it
+/// from the UI loop, so reloading never happens off the main thread. Events
+/// carry absolute paths; they're mapped back under `dir` so they match the
+/// paths `discover_personas` recorded. A `system_prompt_file` outside the
+/// personas directory isn't watched; editing it takes effect on the next reload of its YAML.
+#[derive(Debug)]
+pub struct PersonaWatcher {
+    _watcher: RecommendedWatcher,
+    events: std_mpsc::Receiver<notify::Result<notify::Event>>,
+    aggregator: ReloadAggregator,
+    dir: PathBuf,
+    canonical_dir: PathBuf,
+}
+
+impl PersonaWatcher {
+    /// # start
+    ///
+    /// **Purpose:**
+    /// Starts watching `dir` recursively.
+    ///
+    /// **Errors / Failures:**
+    /// - The directory doesn't exist or the OS watcher can't be created
+    pub fn start(dir: &Path, window: Duration) -> notify::Result<Self> {
+        let (tx, events) = std_mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })?;
+        watcher.watch(dir, RecursiveMode::Recursive)?;
+        log_info!("Watching {} for persona changes", dir.display());
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            aggregator: ReloadAggregator::scan(dir, window),
+            dir: dir.to_path_buf(),
+            canonical_dir: dir.canonicalize()?,
+        })
+    }
+
+    /// An event path as `discover_personas` would spell it
+    fn local_path(&self, path: PathBuf) -> PathBuf {
+        match path.strip_prefix(&self.canonical_dir) {
+            Ok(relative) => self.dir.join(relative),
+            Err(_) => path,
+        }
+    }
+
+    /// Drains pending events and returns a plan once they've settled
+    pub fn poll(&mut self, now: Instant) -> Option<ReloadPlan> {
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                Ok(event) => {
+                    for path in event.paths {
+                        let path = self.local_path(path);
+                        // Files written into a new directory can land before it's watched
+                        if path.is_dir() {
+                            for entry in WalkDir::new(&path).into_iter().filter_map(|entry| entry.ok()) {
+                                self.aggregator.record(entry.into_path(), now);
+                            }
+                        } else {
+                            self.aggregator.record(path, now);
+                        }
+                    }
+                }
+                Err(e) => log_error!("Persona watcher error: {}", e),
+            }
+        }
+        self.aggregator.take_plan(now, |path| fs::read(path).ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_millis(300);
+
+    /// Files by path, standing in for the disk
+    type Disk = HashMap<PathBuf, Vec<u8>>;
+
+    fn yaml(name: &str) -> PathBuf {
+        PathBuf::from(format!("personas/{0}/{0}.yaml", name))
+    }
+
+    fn disk(files: &[(&PathBuf, &str)]) -> Disk {
+        files.iter().map(|(path, content)| ((*path).clone(), content.as_bytes().to_vec())).collect()
+    }
+
+    fn aggregator(disk: &Disk) -> ReloadAggregator {
+        ReloadAggregator::new(WINDOW, disk.keys().cloned(), |path| disk.get(path).cloned())
+    }
+
+    /// Records each path as an event at `at`, as a storm of notify events would
+    fn storm(aggregator: &mut ReloadAggregator, paths: &[&PathBuf], at: Instant) {
+        for path in paths {
+            aggregator.record((*path).clone(), at);
+        }
+    }
+
+    fn plan_after_window(aggregator: &mut ReloadAggregator, since: Instant, disk: &Disk) -> Option<ReloadPlan> {
+        aggregator.take_plan(since + WINDOW, |path| disk.get(path).cloned())
+    }
+
+    fn changes(plan: &ReloadPlan) -> Vec<(&str, FileChange)> {
+        plan.changes.iter().map(|change| (change.persona.as_str(), change.change)).collect()
+    }
+
+    #[test]
+    fn a_truncate_then_write_save_is_one_modification() {
+        let friday = yaml("friday");
+        let mut disk = disk(&[(&friday, "name: friday\nsystem_prompt: Be brief.\n")]);
+        let mut aggregator = aggregator(&disk);
+        let start = Instant::now();
+
+        // Truncate, then write in two pieces: four events, the file empty in between
+        disk.insert(friday.clone(), Vec::new());
+        storm(&mut aggregator, &[&friday, &friday], start);
+        disk.insert(friday.clone(), b"name: friday\nsystem_prompt: Be briefer.\n".to_vec());
+        storm(&mut aggregator, &[&friday, &friday], start + Duration::from_millis(5));
+
+        let plan = plan_after_window(&mut aggregator, start + Duration::from_millis(5), &disk).expect("a plan");
+        assert_eq!(changes(&plan), [("friday", FileChange::Modified)]);
+        assert_eq!(plan.changes[0].path, friday);
+        assert!(plan.unchanged.is_empty());
+        assert_eq!(plan_after_window(&mut aggregator, start + WINDOW, &disk), None, "the burst is reported once");
+    }
+
+    #[test]
+    fn a_rename_based_atomic_save_is_one_modification() {
+        let friday = yaml("friday");
+        let temp = PathBuf::from("personas/friday/.friday.yaml.tmp");
+        let backup = PathBuf::from("personas/friday/friday.yaml~");
+        let mut disk = disk(&[(&friday, "name: friday\n")]);
+        let mut aggregator = aggregator(&disk);
+        let start = Instant::now();
+
+        // Write the temp file, back up the original, rename the temp over it
+        disk.insert(temp.clone(), b"name: friday\nsystem_prompt: New.\n".to_vec());
+        disk.insert(backup.clone(), disk[&friday].clone());
+        let saved = disk.remove(&temp).expect("the temp file");
+        disk.insert(friday.clone(), saved);
+        disk.remove(&backup);
+        storm(&mut aggregator, &[&temp, &backup, &temp, &friday, &backup], start);
+
+        let plan = plan_after_window(&mut aggregator, start, &disk).expect("a plan");
+        assert_eq!(changes(&plan), [("friday", FileChange::Modified)], "temp and backup files are never personas");
+    }
+
+    #[test]
+    fn a_rewrite_with_the_same_bytes_is_unchanged() {
+        let friday = yaml("friday");
+        let disk = disk(&[(&friday, "name: friday\n")]);
+        let mut aggregator = aggregator(&disk);
+        let start = Instant::now();
+
+        storm(&mut aggregator, &[&friday, &friday, &friday], start);
+        let plan = plan_after_window(&mut aggregator, start, &disk).expect("a plan");
+        assert!(plan.changes.is_empty());
+        assert_eq!(plan.unchanged, ["friday"]);
+    }
+
+    #[test]
+    fn no_plan_until_the_directory_has_been_quiet_for_the_window() {
+        let friday = yaml("friday");
+        let mut disk = disk(&[(&friday, "name: friday\n")]);
+        let mut aggregator = aggregator(&disk);
+        let start = Instant::now();
+        disk.insert(friday.clone(), b"name: friday\nnsfw: false\n".to_vec());
+
+        aggregator.record(friday.clone(), start);
+        let read = |path: &Path| disk.get(path).cloned();
+        assert_eq!(aggregator.take_plan(start + WINDOW / 2, read), None, "still inside the window");
+        aggregator.record(friday.clone(), start + WINDOW / 2);
+        assert_eq!(aggregator.take_plan(start + WINDOW, read), None, "a new event restarts the window");
+        assert!(aggregator.take_plan(start + WINDOW / 2 + WINDOW, read).is_some());
+        assert_eq!(aggregator.take_plan(start + WINDOW * 10, read), None, "nothing left pending");
+    }
+
+    #[test]
+    fn a_bulk_checkout_is_one_sorted_plan() {
+        let names: Vec<String> = (0..10).map(|i| format!("persona{}", i)).collect();
+        let paths: Vec<PathBuf> = names.iter().map(|name| yaml(name)).collect();
+        let mut disk: Disk = paths.iter().map(|path| (path.clone(), b"name: old\n".to_vec())).collect();
+        let mut aggregator = aggregator(&disk);
+        let start = Instant::now();
+
+        // Checked out in reverse: two modified, one removed, one added, the rest rewritten as they were
+        let scratch = yaml("scratch");
+        disk.insert(paths[9].clone(), b"name: new\n".to_vec());
+        disk.insert(paths[3].clone(), b"name: new\n".to_vec());
+        disk.remove(&paths[5]);
+        disk.insert(scratch.clone(), b"name: scratch\n".to_vec());
+        for (i, path) in paths.iter().chain([&scratch]).rev().enumerate() {
+            aggregator.record(path.clone(), start + Duration::from_millis(i as u64));
+        }
+
+        let plan = plan_after_window(&mut aggregator, start + Duration::from_millis(10), &disk).expect("a plan");
+        assert_eq!(changes(&plan), [
+            ("persona3", FileChange::Modified),
+            ("persona5", FileChange::Removed),
+            ("persona9", FileChange::Modified),
+            ("scratch", FileChange::Added),
+        ]);
+        assert_eq!(plan.unchanged, ["persona0", "persona1", "persona2", "persona4", "persona6", "persona7", "persona8"]);
+    }
+
+    #[test]
+    fn a_file_that_comes_and_goes_within_the_window_is_nothing() {
+        let disk = Disk::new();
+        let mut aggregator = aggregator(&disk);
+        let start = Instant::now();
+
+        storm(&mut aggregator, &[&yaml("fleeting"), &yaml("fleeting")], start);
+        assert_eq!(plan_after_window(&mut aggregator, start, &disk), None);
+    }
+
+    #[test]
+    fn editing_only_the_prompt_file_modifies_its_persona() {
+        let friday = yaml("friday");
+        let prompt = PathBuf::from("personas/friday/prompt.md");
+        let mut disk = disk(&[(&friday, "name: friday\nsystem_prompt_file: prompt.md\n"), (&prompt, "Be brief.")]);
+        let mut aggregator = ReloadAggregator::new(WINDOW, [friday.clone()], |path| disk.get(path).cloned());
+        let start = Instant::now();
+
+        disk.insert(prompt.clone(), b"Be briefer.".to_vec());
+        storm(&mut aggregator, &[&prompt, &prompt], start);
+        let plan = plan_after_window(&mut aggregator, start, &disk).expect("a plan");
+        assert_eq!(changes(&plan), [("friday", FileChange::Modified)]);
+
+        // Unrelated files are ignored outright
+        aggregator.record(PathBuf::from("personas/friday/notes.md"), start + WINDOW);
+        assert_eq!(plan_after_window(&mut aggregator, start + WINDOW, &disk), None);
+    }
+
+    #[test]
+    fn the_report_is_a_single_notification_with_failures_attached() {
+        type Case = (&'static str, ReloadReport, Option<&'static str>);
+        let cases: Vec<Case> = vec![
+            (
+                "changes and rewrites",
+                ReloadReport {
+                    applied: vec![("friday".into(), FileChange::Modified), ("scratch".into(), FileChange::Added)],
+                    unchanged: 2,
+                    ..Default::default()
+                },
+                Some("Personas updated: friday modified, scratch added, 2 unchanged\n\
+                      Open agents keep their configuration; `new <persona>` uses the update, `reload --apply` updates them."),
+            ),
+            (
+                "an addition and a failure",
+                ReloadReport {
+                    applied: vec![("scratch".into(), FileChange::Added)],
+                    failures: vec![("broken".into(), "did not find expected key".into())],
+                    ..Default::default()
+                },
+                Some("Personas updated: scratch added\n  broken failed to load (keeping the last good version): did not find expected key"),
+            ),
+            (
+                "only a failure",
+                ReloadReport { failures: vec![("broken".into(), "bad".into())], unchanged: 1, ..Default::default() },
+                Some("Personas not updated\n  broken failed to load (keeping the last good version): bad"),
+            ),
+            ("only rewrites", ReloadReport { unchanged: 3, ..Default::default() }, None),
+        ];
+
+        for (case, report, expected) in cases {
+            assert_eq!(report.summary().as_deref(), expected, "{}", case);
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! # Daegonica Module: persona::review
+//!
+//! **Purpose:** Read the structured findings the `reviewer` persona replies with
+//!
+//! **Context:**
+//! - The reviewer's prompt pins a findings format: numbered `[severity] location - title`
+//!   entries with Why / Fix lines, optional replacement blocks, and a closing verdict
+//! - Personas listing the `findings_report` tool get a one-line report under each reply
+//! - `attach` sends a file in the shape the reviewer expects (`user::outgoing::AttachTemplate`)
+//!
+//! **Responsibilities:**
+//! - Parse a reply into `Finding`s and a `Verdict`
+//! - Say exactly what's wrong when a reply doesn't follow the format
+//! - Summarize a parsed review in one line
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use strum::{Display, EnumString};
+use thiserror::Error;
+use crate::persona::Persona;
+
+/// `tools` entry that turns on the findings report under each reply
+pub const FINDINGS_REPORT_TOOL: &str = "findings_report";
+
+/// First line of a replacement block: `// file: <path>`
+pub const REPLACEMENT_PATH_PREFIX: &str = "// file:";
+
+static FINDING_LINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\d+\.\s*\[([^\]]+)\]\s*(.+?)\s+-\s+(.+)$").expect("finding pattern is valid")
+});
+
+/// # Severity
+///
+/// **Summary:**
+/// How much a finding matters, most serious first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display, EnumString)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Severity {
+    Blocker,
+    Major,
+    Minor,
+    Nit,
+}
+
+/// # Verdict
+///
+/// **Summary:**
+/// The reviewer's closing line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+#[strum(ascii_case_insensitive)]
+pub enum Verdict {
+    #[strum(serialize = "approve")]
+    Approve,
+    #[strum(serialize = "approve with nits")]
+    ApproveWithNits,
+    #[strum(serialize = "request changes")]
+    RequestChanges,
+}
+
+/// # Replacement
+///
+/// **Summary:**
+/// A fenced block of replacement code, headed `// file: <path>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replacement {
+    pub path: String,
+    pub code: String,
+}
+
+/// # Finding
+///
+/// **Summary:**
+/// One numbered entry of a review.
+///
+/// **Fields:**
+/// - `severity` / `location` / `title`: From the `N. [severity] location - title` line
+/// - `why` / `fix`: The `Why:` and `Fix:` lines, if given
+/// - `replacement`: Replacement code that followed the finding, if any
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub location: String,
+    pub title: String,
+    pub why: Option<String>,
+    pub fix: Option<String>,
+    pub replacement: Option<Replacement>,
+}
+
+/// # Review
+///
+/// **Summary:**
+/// A reply parsed into findings and a verdict.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Review {
+    pub findings: Vec<Finding>,
+    pub verdict: Verdict,
+}
+
+/// # ReviewFormatError
+///
+/// **Summary:**
+/// Why a reply couldn't be read as a review.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ReviewFormatError {
+    #[error("no 'Findings:' section")]
+    NoFindingsSection,
+
+    #[error("the 'Findings:' section lists nothing (expected numbered findings or 'Findings: none')")]
+    EmptyFindings,
+
+    #[error("finding {0} has unknown severity '{1}' (expected blocker, major, minor or nit)")]
+    UnknownSeverity(usize, String),
+
+    #[error("a replacement block isn't closed")]
+    UnclosedBlock,
+
+    #[error("no 'Verdict:' line")]
+    NoVerdict,
+
+    #[error("unknown verdict '{0}' (expected approve, approve with nits or request changes)")]
+    UnknownVerdict(String),
+}
+
+impl Review {
+    /// # parse
+    ///
+    /// **Purpose:**
+    /// Reads a reviewer reply.
+    ///
+    /// **Returns:**
+    /// `Result<Review, ReviewFormatError>` - The findings in reply order, and the verdict
+    ///
+    /// **Details:**
+    /// Text before `Findings:` is ignored, as are lines inside a finding other
+    /// than `Why:` / `Fix:`. A fenced block is a finding's replacement only
+    /// when its first line is `// file: <path>`; other blocks are skipped.
+    pub fn parse(reply: &str) -> Result<Review, ReviewFormatError> {
+        let mut lines = reply.lines();
+        let header = lines.by_ref()
+            .find_map(|line| line.trim().strip_prefix("Findings:"))
+            .ok_or(ReviewFormatError::NoFindingsSection)?;
+        let none_reported = header.trim().eq_ignore_ascii_case("none");
+
+        let mut findings: Vec<Finding> = Vec::new();
+        let mut verdict = None;
+        while let Some(line) = lines.next() {
+            let line = line.trim();
+            if let Some(rest) = line.strip_prefix("Verdict:") {
+                let rest = rest.trim().trim_end_matches('.');
+                verdict = Some(rest.parse::<Verdict>().map_err(|_| ReviewFormatError::UnknownVerdict(rest.to_string()))?);
+                break;
+            }
+            if line.starts_with("```") {
+                let block = fenced_block(&mut lines)?;
+                if let (Some(finding), Some(replacement)) = (findings.last_mut(), replacement(&block)) {
+                    finding.replacement = Some(replacement);
+                }
+                continue;
+            }
+            if let Some(captures) = FINDING_LINE.captures(line) {
+                let severity = captures[1].trim();
+                findings.push(Finding {
+                    severity: severity.parse()
+                        .map_err(|_| ReviewFormatError::UnknownSeverity(findings.len() + 1, severity.to_string()))?,
+                    location: captures[2].trim_matches('`').to_string(),
+                    title: captures[3].to_string(),
+                    why: None,
+                    fix: None,
+                    replacement: None,
+                });
+                continue;
+            }
+            if let Some(finding) = findings.last_mut() {
+                if let Some(why) = line.strip_prefix("Why:") {
+                    finding.why = Some(why.trim().to_string());
+                } else if let Some(fix) = line.strip_prefix("Fix:") {
+                    finding.fix = Some(fix.trim().to_string());
+                }
+            }
+        }
+
+        if findings.is_empty() && !none_reported {
+            return Err(ReviewFormatError::EmptyFindings);
+        }
+        let verdict = verdict.ok_or(ReviewFormatError::NoVerdict)?;
+        Ok(Review { findings, verdict })
+    }
+
+    /// Findings of `severity`
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings.iter().filter(|finding| finding.severity == severity).count()
+    }
+
+    /// # describe
+    ///
+    /// **Purpose:**
+    /// One line for the agent pane, e.g. "3 findings (1 blocker, 2 nits); verdict: request changes".
+    pub fn describe(&self) -> String {
+        if self.findings.is_empty() {
+            return format!("no findings; verdict: {}", self.verdict);
+        }
+        let counts: Vec<String> = [Severity::Blocker, Severity::Major, Severity::Minor, Severity::Nit]
+            .into_iter()
+            .filter_map(|severity| match self.count(severity) {
+                0 => None,
+                1 => Some(format!("1 {}", severity)),
+                n => Some(format!("{} {}s", n, severity)),
+            })
+            .collect();
+        let noun = if self.findings.len() == 1 { "finding" } else { "findings" };
+        format!("{} {} ({}); verdict: {}", self.findings.len(), noun, counts.join(", "), self.verdict)
+    }
+}
+
+/// Lines up to the closing fence (the opening fence already read), indentation kept
+fn fenced_block<'a>(lines: &mut impl Iterator<Item = &'a str>) -> Result<Vec<&'a str>, ReviewFormatError> {
+    let mut block = Vec::new();
+    for line in lines {
+        if line.trim_start().starts_with("```") {
+            return Ok(block);
+        }
+        block.push(line);
+    }
+    Err(ReviewFormatError::UnclosedBlock)
+}
+
+/// The block as a replacement, if it starts with `// file: <path>`
+fn replacement(block: &[&str]) -> Option<Replacement> {
+    let (first, code) = block.split_first()?;
+    let path = first.trim().strip_prefix(REPLACEMENT_PATH_PREFIX)?.trim();
+    (!path.is_empty()).then(|| Replacement { path: path.to_string(), code: code.join("\n") })
+}
+
+/// Whether `persona` lists the `findings_report` tool
+pub fn reports_findings(persona: &Persona) -> bool {
+    persona.tools.as_ref()
+        .is_some_and(|tools| tools.iter().any(|tool| tool.trim().eq_ignore_ascii_case(FINDINGS_REPORT_TOOL)))
+}
+
+/// # report_line
+///
+/// **Purpose:**
+/// The pane line shown under a reply from a persona with `findings_report`.
+pub fn report_line(reply: &str) -> String {
+    match Review::parse(reply) {
+        Ok(review) => format!("Review: {}", review.describe()),
+        Err(e) => format!("Review: couldn't read the findings ({})", e),
+    }
+}
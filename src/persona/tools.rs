@@ -0,0 +1,172 @@
+//! # Daegonica Module: persona::tools
+//!
+//! **Purpose:** Which app commands a persona's agents may run, from the persona's `tools` list
+//!
+//! **Context:**
+//! - `tools` in the persona YAML used to be a description only
+//! - `from_input_action` wraps every action that needs a tool (`InputAction::required_tool`)
+//!   in a `ToolGatedCommand`, which asks the current agent's persona first
+//!
+//! **Responsibilities:**
+//! - Name the tools commands can require (`Tool`)
+//! - Read a persona's `tools` into a `ToolSet`; `twitter` grants both twitter tools,
+//!   entries that aren't tools stay descriptive
+//! - Leave a persona with no `tools` list unrestricted
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::collections::HashSet;
+use strum::{Display, EnumString};
+use crate::persona::Persona;
+
+/// # Tool
+///
+/// **Summary:**
+/// A group of commands a persona has to list in `tools` to run.
+///
+/// **Variants:**
+/// - `TwitterPost`: `tweet` and `tweet --later`
+/// - `TwitterDraft`: `draft`
+/// - `History`: `save` and `summarize`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum Tool {
+    TwitterPost,
+    TwitterDraft,
+    History,
+}
+
+/// # ToolSet
+///
+/// **Summary:**
+/// The tools one persona's agents have.
+///
+/// **Fields:**
+/// - `tools`: Tools listed; None when the persona has no `tools` list, which allows everything
+///
+/// **Details:**
+/// `twitter` is shorthand for both `twitter_post` and `twitter_draft`.
+/// Other entries that don't name a `Tool` (`code_review`, `twitter_trends`)
+/// are ignored here; they still describe the persona but gate nothing.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ToolSet {
+    tools: Option<HashSet<Tool>>,
+}
+
+impl ToolSet {
+    /// The tools named in `persona.tools`
+    pub fn for_persona(persona: &Persona) -> Self {
+        Self::from_names(persona.tools.as_deref())
+    }
+
+    /// # from_names
+    ///
+    /// **Purpose:**
+    /// Builds a tool set from a `tools` list as written in the YAML.
+    ///
+    /// **Parameters:**
+    /// - `names`: The list, or None if the persona has none
+    pub fn from_names(names: Option<&[String]>) -> Self {
+        Self {
+            tools: names.map(|names| names.iter().flat_map(|name| Self::tools_for(name)).collect()),
+        }
+    }
+
+    /// The tools one `tools` entry grants, if any
+    fn tools_for(name: &str) -> Vec<Tool> {
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("twitter") {
+            return vec![Tool::TwitterPost, Tool::TwitterDraft];
+        }
+        name.parse().into_iter().collect()
+    }
+
+    /// Whether commands needing `tool` may run
+    pub fn allows(&self, tool: Tool) -> bool {
+        self.tools.as_ref().is_none_or(|tools| tools.contains(&tool))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persona::builtin::load_builtin;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn no_tools_list_allows_everything() {
+        let tools = ToolSet::from_names(None);
+        assert!(tools.allows(Tool::TwitterPost));
+        assert!(tools.allows(Tool::TwitterDraft));
+        assert!(tools.allows(Tool::History));
+    }
+
+    #[test]
+    fn empty_tools_list_allows_nothing() {
+        let tools = ToolSet::from_names(Some(&[]));
+        assert!(!tools.allows(Tool::TwitterPost));
+        assert!(!tools.allows(Tool::TwitterDraft));
+        assert!(!tools.allows(Tool::History));
+    }
+
+    #[test]
+    fn exact_names_are_case_insensitive_and_trimmed() {
+        let tools = ToolSet::from_names(Some(&names(&[" History ", "Twitter_Post"])));
+        assert!(tools.allows(Tool::History));
+        assert!(tools.allows(Tool::TwitterPost));
+        assert!(!tools.allows(Tool::TwitterDraft));
+    }
+
+    #[test]
+    fn twitter_grants_posting_and_drafting() {
+        let tools = ToolSet::from_names(Some(&names(&["twitter"])));
+        assert!(tools.allows(Tool::TwitterPost));
+        assert!(tools.allows(Tool::TwitterDraft));
+        assert!(!tools.allows(Tool::History));
+    }
+
+    #[test]
+    fn twitter_post_and_twitter_draft_grant_only_their_command() {
+        let post = ToolSet::from_names(Some(&names(&["twitter_post"])));
+        assert!(post.allows(Tool::TwitterPost));
+        assert!(!post.allows(Tool::TwitterDraft));
+
+        let draft = ToolSet::from_names(Some(&names(&["twitter_draft"])));
+        assert!(draft.allows(Tool::TwitterDraft));
+        assert!(!draft.allows(Tool::TwitterPost));
+    }
+
+    #[test]
+    fn descriptive_entries_gate_nothing() {
+        let tools = ToolSet::from_names(Some(&names(&["code_review", "twitter_trends", "summarize"])));
+        assert!(!tools.allows(Tool::TwitterPost));
+        assert!(!tools.allows(Tool::TwitterDraft));
+        assert!(!tools.allows(Tool::History));
+    }
+
+    #[test]
+    fn builtin_shadow_can_tweet_and_save_but_not_draft() {
+        let shadow = load_builtin("shadow").unwrap();
+        let tools = ToolSet::for_persona(&shadow);
+        assert!(tools.allows(Tool::TwitterPost));
+        assert!(tools.allows(Tool::History));
+        assert!(!tools.allows(Tool::TwitterDraft));
+    }
+
+    #[test]
+    fn shipped_viral_can_tweet_and_draft() {
+        let viral = Persona::from_yaml_str(include_str!("../../personas/viral/viral.yaml")).unwrap();
+        let tools = ToolSet::for_persona(&viral);
+        assert!(tools.allows(Tool::TwitterPost));
+        assert!(tools.allows(Tool::TwitterDraft));
+    }
+}
@@ -43,7 +43,7 @@ pub use std::path::{Path, PathBuf};
 pub use crate::twitter::*;
 
 // Config file
-pub use crate::config::{AppConfig, GrokConfig, TuiConfig, HistoryConfig, GLOBAL_CONFIG};
+pub use crate::config::{AppConfig, GrokConfig, TuiConfig, HistoryConfig, TwitterConfig, HttpConfig, CredentialsConfig, GLOBAL_CONFIG};
 
 // User specific
 pub use crate::user::user_input::UserInput;
@@ -58,6 +58,7 @@ pub use crate::utilities::outputs::{
     OutputHandler, 
     SharedOutput, 
     CliOutput,
+    ChannelOutput,
 };
 
 // Agent tracking
@@ -66,18 +67,22 @@ pub use crate::agent_history::history::HistoryManager;
 pub use crate::persona::{
     Persona,
     PersonaRef,
+    PersonaSource,
+    SummarizeMode,
 };
 pub use crate::persona::agent_manager::AgentManager;
 pub use crate::persona::agent::AgentInfo;
+pub use crate::persona::chunk_stream::{ChunkStream, ChunkSender, TaskKind};
 
 // AI Connections
 pub use crate::grok::client::GrokClient;
-pub use crate::llm::client::Connection;
+pub use crate::llm::client::{Connection, CompletedReply};
 pub use crate::llm::{LlmClient, StreamResponse};
 pub use crate::claude::client::ClaudeClient;
 
 // TUI related
-pub use crate::tui::{ShadowApp, AgentPane, MessageSource, UnifiedMessage};
+pub use crate::tui::{ShadowApp, AgentPane, MessageSource, MessageKind, GlobalFilter, UnifiedMessage};
 
 // Daegonica Software crates
-pub use dlog::{log_init, log_error, log_info, enums::OutputTarget};
\ No newline at end of file
+pub use dlog::{log_init, enums::OutputTarget};
+pub use crate::{log_error, log_warn, log_info, log_debug, log_trace};
\ No newline at end of file
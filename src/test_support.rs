@@ -0,0 +1,332 @@
+//! # Daegonica Module: test_support
+//!
+//! **Purpose:** Helpers shared by the crate's unit tests
+//!
+//! **Context:**
+//! - Only compiled for `cargo test`
+//! - Many modules read and write files; their tests need somewhere disposable to do it
+//!
+//! **Responsibilities:**
+//! - Hand out unique scratch directories under the system temp dir, removed on drop
+//! - Point the process-wide personas root at a scratch directory, never the repo's `personas/`
+//! - Serve canned HTTP responses from a local mock server and record what was requested
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use crate::persona::{personas_root, set_personas_root};
+
+static NEXT_SCRATCH: AtomicUsize = AtomicUsize::new(0);
+
+/// # ScratchDir
+///
+/// **Summary:**
+/// A fresh, empty directory for one test, deleted with everything in it on drop.
+///
+/// **Fields:**
+/// - `path`: The directory
+pub struct ScratchDir {
+    path: PathBuf,
+}
+
+impl ScratchDir {
+    /// Creates `{temp}/grokprime-test-{pid}-{n}-{label}`
+    pub fn new(label: &str) -> Self {
+        let n = NEXT_SCRATCH.fetch_add(1, Ordering::SeqCst);
+        let path = std::env::temp_dir()
+            .join(format!("grokprime-test-{}-{}-{}", std::process::id(), n, label));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).expect("create scratch dir");
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn join(&self, relative: impl AsRef<Path>) -> PathBuf {
+        self.path.join(relative)
+    }
+
+    /// Writes `contents` to `relative`, creating parent directories; returns the full path
+    pub fn write(&self, relative: impl AsRef<Path>, contents: &str) -> PathBuf {
+        let path = self.join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("create scratch parent");
+        }
+        fs::write(&path, contents).expect("write scratch file");
+        path
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+static TEST_PERSONAS_ROOT: Lazy<PathBuf> = Lazy::new(|| {
+    let root = std::env::temp_dir().join(format!("grokprime-test-{}-personas", std::process::id()));
+    fs::create_dir_all(&root).expect("create test personas root");
+    let _ = set_personas_root(root.clone());
+    root
+});
+
+/// # test_personas_root
+///
+/// **Purpose:**
+/// Points the personas root at a per-process temp directory and returns it.
+///
+/// **Details:**
+/// Call before anything in a test reads `personas_root()`. Panics if the root
+/// was already resolved elsewhere, so a test can never write histories or
+/// archives into the repo's own `personas/`. Tests sharing the root should
+/// use persona names nobody else uses.
+pub fn test_personas_root() -> &'static Path {
+    let root = TEST_PERSONAS_ROOT.as_path();
+    assert_eq!(personas_root(), root, "personas root was read before test_personas_root()");
+    root
+}
+
+/// Held by tests that write the tweet post log, the one state file they can't give a name of their own
+pub static POST_LOG_TURN: Mutex<()> = Mutex::new(());
+
+/// # RecordedRequest
+///
+/// **Summary:**
+/// One request the mock server received.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl RecordedRequest {
+    /// The first header named `name` (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// # MockResponse
+///
+/// **Summary:**
+/// What the mock server answers one request with.
+///
+/// **Fields:**
+/// - `status`: HTTP status code
+/// - `content_type`: `Content-Type` header
+/// - `body`: Whole response body
+/// - `delay`: Wait before answering (to hold a request in flight)
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+    pub delay: Duration,
+}
+
+impl MockResponse {
+    pub fn json(status: u16, body: impl Into<String>) -> Self {
+        Self { status, content_type: "application/json", body: body.into(), delay: Duration::ZERO }
+    }
+
+    pub fn sse(body: impl Into<String>) -> Self {
+        Self { status: 200, content_type: "text/event-stream", body: body.into(), delay: Duration::ZERO }
+    }
+
+    pub fn after(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+type Responder = dyn Fn(&RecordedRequest) -> MockResponse + Send + Sync;
+
+/// # MockServer
+///
+/// **Summary:**
+/// A local HTTP/1.1 server answering each request from a closure.
+///
+/// **Details:**
+/// Only what the crate's clients send is understood: a request line,
+/// headers and a `Content-Length` body. Servers from `start` close the
+/// connection after every answer; servers from `keep_alive` keep it open for
+/// the next request, so `connections` shows whether a client reused it.
+/// The server stops when dropped.
+pub struct MockServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    connections: Arc<AtomicUsize>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Starts serving on a free local port, one request per connection
+    pub async fn start(respond: impl Fn(&RecordedRequest) -> MockResponse + Send + Sync + 'static) -> Self {
+        Self::serving(Arc::new(respond), false).await
+    }
+
+    /// Starts serving on a free local port, keeping each connection open between requests
+    pub async fn keep_alive(respond: impl Fn(&RecordedRequest) -> MockResponse + Send + Sync + 'static) -> Self {
+        Self::serving(Arc::new(respond), true).await
+    }
+
+    async fn serving(respond: Arc<Responder>, keep_alive: bool) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind mock server");
+        let addr = listener.local_addr().expect("mock server address");
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let connections = Arc::new(AtomicUsize::new(0));
+
+        let (recorded, accepted) = (requests.clone(), connections.clone());
+        let task = tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                accepted.fetch_add(1, Ordering::SeqCst);
+                let (recorded, respond) = (recorded.clone(), respond.clone());
+                tokio::spawn(async move {
+                    let _ = serve(socket, recorded, respond, keep_alive).await;
+                });
+            }
+        });
+        Self { addr, requests, connections, task }
+    }
+
+    /// Full URL of `path` on this server
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.addr, path)
+    }
+
+    /// Requests received so far, in arrival order
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// TCP connections accepted so far
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Answers requests on `socket` until it closes, or after the first unless `keep_alive`
+async fn serve(
+    mut socket: TcpStream,
+    recorded: Arc<Mutex<Vec<RecordedRequest>>>,
+    respond: Arc<Responder>,
+    keep_alive: bool,
+) -> std::io::Result<()> {
+    let mut raw = Vec::new();
+    while let Some(request) = read_request(&mut socket, &mut raw).await? {
+        recorded.lock().unwrap().push(request.clone());
+        let response = respond(&request);
+        tokio::time::sleep(response.delay).await;
+
+        let head = format!(
+            "HTTP/1.1 {} Mock\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: {}\r\n\r\n",
+            response.status, response.content_type, response.body.len(),
+            if keep_alive { "keep-alive" } else { "close" }
+        );
+        socket.write_all(head.as_bytes()).await?;
+        socket.write_all(response.body.as_bytes()).await?;
+        if !keep_alive {
+            return socket.shutdown().await;
+        }
+    }
+    Ok(())
+}
+
+/// Reads the next request off `socket`, leaving anything after it in `raw`; None once the client hangs up
+async fn read_request(socket: &mut TcpStream, raw: &mut Vec<u8>) -> std::io::Result<Option<RecordedRequest>> {
+    let mut buf = [0u8; 4096];
+    let header_end = loop {
+        if let Some(end) = raw.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end + 4;
+        }
+        let read = socket.read(&mut buf).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        raw.extend_from_slice(&buf[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&raw[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+    let length = headers.iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    while raw.len() < header_end + length {
+        let read = socket.read(&mut buf).await?;
+        if read == 0 {
+            break;
+        }
+        raw.extend_from_slice(&buf[..read]);
+    }
+    let body_end = raw.len().min(header_end + length);
+    let body = String::from_utf8_lossy(&raw[header_end..body_end]).to_string();
+    raw.drain(..body_end);
+
+    Ok(Some(RecordedRequest { method, path, headers, body }))
+}
+
+/// # grok_reply_sse
+///
+/// **Purpose:**
+/// A Grok responses-API event stream that streams `deltas` and completes as response `id`.
+pub fn grok_reply_sse(id: &str, deltas: &[&str]) -> String {
+    let mut body = String::new();
+    for (n, delta) in deltas.iter().enumerate() {
+        let event = serde_json::json!({
+            "type": "response.output_text.delta",
+            "delta": delta,
+            "sequence_number": n,
+            "content_index": 0,
+            "item_id": "msg_1",
+            "output_index": 0,
+        });
+        body.push_str(&format!("data: {}\n\n", event));
+    }
+    let completed = serde_json::json!({
+        "type": "response.completed",
+        "response": {
+            "id": id,
+            "object": "response",
+            "created_at": 0,
+            "model": "grok-mock",
+            "output": [],
+            "usage": { "input_tokens": 3, "output_tokens": deltas.len(), "total_tokens": 3 + deltas.len() },
+        },
+    });
+    body.push_str(&format!("data: {}\n\n", completed));
+    body
+}
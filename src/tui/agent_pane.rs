@@ -1,17 +1,16 @@
 //! # Daegonica Module: tui::agent_pane
 //!
-//! **Purpose:** Individual agent conversation pane state and behavior
+//! **Purpose:** Presentation state of one agent's pane in the TUI
 //!
 //! **Context:**
-//! - Represents a single agent tab in the TUI
-//! - Manages conversation messages and streaming responses
-//! - Handles channel communication for async operations
+//! - Represents a single agent tab in the TUI, keyed by the agent's id in `ShadowApp::agent_panes`
+//! - Agent state shared with CLI mode (connection, channels, task, messages) lives in
+//!   `persona::agent::AgentInfo`, owned by `AgentManager`
 //!
 //! **Responsibilities:**
-//! - Maintain agent-specific message history
-//! - Manage GrokConnection for this agent
-//! - Handle streaming response channels
-//! - Provide scrolling and text wrapping utilities
+//! - Track message and input scroll positions
+//! - Track the thinking animation and mute state
+//! - Hold the pane's border color (the persona's `tui_color`)
 //!
 //! **Author:** Daegonica Software
 //! **Version:** 0.1.0
@@ -21,6 +20,14 @@
 //! This file is part of the Daegonica Software codebase.
 //! ---------------------------------------------------------------
 
+use ratatui::style::Color;
+use crate::prelude::*;
+use crate::persona::Persona;
+use crate::utilities::accessibility::glyph;
+use crate::tui::gauge::ContextGaugeCache;
+
+/// Lines kept per pane for Up / Down recall
+const INPUT_HISTORY_LIMIT: usize = 200;
 
 /// # AgentPane
 ///
@@ -33,14 +40,27 @@
 /// - `input_scroll`: Vertical scroll position in input area
 /// - `input_max_lines`: Maximum visible lines in input area
 /// - `thinking_animation_frame`: Current frame of the thinking animation (0-3)
+/// - `muted`: Suppress notifications and auto-scroll (the agent keeps working)
+/// - `speaking`: Speak this agent's finished replies aloud (`speak on|off`)
+/// - `context_gauge`: Last context estimate, reused until the conversation changes
+/// - `search_query`: Active `/` search (empty while the first character is awaited)
+/// - `search_editing`: Keys type into the query; false once Enter hands them to `n` / `N`
+/// - `search_current`: Matching line `n` / `N` is on (None: the last one)
+/// - `search_hits`: Lines that matched at the last render
+/// - `search_jump`: Scroll to the current match at the next render
+/// - `last_usage`: Tokens the last reply used, for the status bar (None until a reply reports usage)
+/// - `input_history`: Lines submitted while this pane was current, oldest first, for Up / Down recall
+/// - `history_cursor`: Entry of `input_history` in the input box (None: not recalling)
+/// - `draft`: Input left unsent when another agent was made current; back in the input box on return
+/// - `border_color`: The persona's `tui_color`, or `tui.border_color` when it has none
 ///
 /// **Design Note:**
 /// AgentPane only contains UI state. Agent business logic (messages, connection, etc.)
-/// is stored in AgentManager. This separation prevents state duplication bugs.
+/// is stored in `AgentInfo` inside AgentManager. This separation prevents state duplication bugs.
 ///
 /// **Usage Example:**
 /// ```rust
-/// let pane = AgentPane::new();
+/// let pane = AgentPane::new(&persona);
 /// pane.scroll_to_bottom();
 /// ```
 #[derive(Debug)]
@@ -50,6 +70,19 @@ pub struct AgentPane {
     pub input_scroll: usize,
     pub input_max_lines: u16,
     pub thinking_animation_frame: usize,
+    pub muted: bool,
+    pub speaking: bool,
+    pub context_gauge: Option<ContextGaugeCache>,
+    pub search_query: Option<String>,
+    pub search_editing: bool,
+    pub search_current: Option<usize>,
+    pub search_hits: usize,
+    pub search_jump: bool,
+    pub last_usage: Option<Usage>,
+    pub input_history: Vec<String>,
+    pub history_cursor: Option<usize>,
+    pub draft: Option<String>,
+    pub border_color: Color,
 }
 
 impl AgentPane {
@@ -59,20 +92,35 @@ impl AgentPane {
     /// Creates a new agent pane with default UI state.
     ///
     /// **Parameters:**
-    /// None
+    /// - `persona`: The agent's persona, for its border color
     ///
     /// **Returns:**
     /// Initialized AgentPane with default UI values
     ///
     /// **Errors / Failures:**
     /// - None (infallible)
-    pub fn new() -> Self {
+    pub fn new(persona: &Persona) -> Self {
         Self {
             scroll: 0,
             auto_scroll: true,
             input_scroll: 0,
             input_max_lines: 20,
             thinking_animation_frame: 0,
+            muted: false,
+            speaking: GLOBAL_CONFIG.tts.speak_by_default,
+            context_gauge: None,
+            search_query: None,
+            search_editing: false,
+            search_current: None,
+            search_hits: 0,
+            search_jump: false,
+            last_usage: None,
+            input_history: Vec::new(),
+            history_cursor: None,
+            draft: None,
+            border_color: persona.tui_color
+                .map(|[r, g, b]| Color::Rgb(r, g, b))
+                .unwrap_or(GLOBAL_CONFIG.tui.border_color),
          }
     }
 
@@ -92,4 +140,121 @@ impl AgentPane {
         self.scroll = u16::MAX;  // Will be clamped to actual max by render
         self.auto_scroll = true;   // Re-enable auto-scroll
     }
+
+    /// # title_suffix
+    ///
+    /// **Purpose:**
+    /// Glyph (or text label in accessible mode) appended to the pane title for muted agents.
+    pub fn title_suffix(&self) -> &'static str {
+        if self.muted { glyph(" 🔇", " (muted)") } else { "" }
+    }
+
+    /// Remembers a submitted line for Up / Down recall, unless it repeats the last one
+    pub fn record_input(&mut self, line: &str) {
+        self.history_cursor = None;
+        if self.input_history.last().is_some_and(|last| last == line) {
+            return;
+        }
+        self.input_history.push(line.to_string());
+        if self.input_history.len() > INPUT_HISTORY_LIMIT {
+            self.input_history.remove(0);
+        }
+    }
+
+    /// # recall_older
+    ///
+    /// **Purpose:**
+    /// Steps `history_cursor` back one entry (Up).
+    ///
+    /// **Returns:**
+    /// `Option<&str>` - The entry to put in the input box; None at the oldest one or with no history
+    pub fn recall_older(&mut self) -> Option<&str> {
+        let index = match self.history_cursor {
+            None => self.input_history.len().checked_sub(1)?,
+            Some(index) => index.checked_sub(1)?,
+        };
+        self.history_cursor = Some(index);
+        Some(&self.input_history[index])
+    }
+
+    /// # recall_newer
+    ///
+    /// **Purpose:**
+    /// Steps `history_cursor` forward one entry (Down).
+    ///
+    /// **Returns:**
+    /// `Option<&str>` - The entry to put in the input box, or "" once past the
+    /// newest (recall ends); None when not recalling
+    pub fn recall_newer(&mut self) -> Option<&str> {
+        let index = self.history_cursor? + 1;
+        if index < self.input_history.len() {
+            self.history_cursor = Some(index);
+            Some(&self.input_history[index])
+        } else {
+            self.history_cursor = None;
+            Some("")
+        }
+    }
+
+    /// Starts a new `/` search, typing into an empty query
+    pub fn start_search(&mut self) {
+        self.search_query = Some(String::new());
+        self.search_editing = true;
+        self.search_current = None;
+        self.search_jump = false;
+    }
+
+    pub fn clear_search(&mut self) {
+        self.search_query = None;
+        self.search_editing = false;
+        self.search_current = None;
+        self.search_hits = 0;
+        self.search_jump = false;
+    }
+
+    /// # edit_search
+    ///
+    /// **Purpose:**
+    /// Replaces the query, jumping to the newest match of the new text.
+    pub fn edit_search(&mut self, query: String) {
+        self.search_query = Some(query);
+        self.search_current = None;
+        self.search_jump = true;
+    }
+
+    /// # step_search
+    ///
+    /// **Purpose:**
+    /// Moves to the next (`n`) or previous (`N`) matching line, wrapping around.
+    pub fn step_search(&mut self, forward: bool) {
+        let Some(last) = self.search_hits.checked_sub(1) else {
+            return;
+        };
+        let current = self.search_current.unwrap_or(last).min(last);
+        self.search_current = Some(if forward {
+            if current == last { 0 } else { current + 1 }
+        } else if current == 0 {
+            last
+        } else {
+            current - 1
+        });
+        self.search_jump = true;
+    }
+
+    /// # search_label
+    ///
+    /// **Purpose:**
+    /// The search shown in the pane title, e.g. ` [/error 2/5]`, given this render's matching lines.
+    pub fn search_label(&self, hits: usize) -> String {
+        let Some(query) = &self.search_query else {
+            return String::new();
+        };
+        let cursor = if self.search_editing { glyph("▏", "_") } else { "" };
+        let position = match (hits, self.search_current) {
+            _ if query.is_empty() => String::new(),
+            (0, _) => " no matches".to_string(),
+            (hits, current) => format!(" {}/{}", current.unwrap_or(hits - 1).min(hits - 1) + 1, hits),
+        };
+        format!(" [/{}{}{}]", query, cursor, position)
+    }
 }
@@ -22,23 +22,72 @@
 //! This file is part of the Daegonica Software codebase.
 //! ---------------------------------------------------------------
 
+use std::cell::RefCell;
+use chrono::{DateTime, Utc};
 use std::collections::{HashMap, VecDeque};
-use std::time::SystemTime;
+use std::time::{Duration, Instant, SystemTime};
 use uuid::Uuid;
-use std::path::Path;
 use ratatui::{
-    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
-    layout::{Constraint, Direction, Layout, Position, Rect},
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
+    layout::{Position, Rect},
     style::{Color, Modifier, Style},
     text::{Text, Line, Span},
     Frame,
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
 };
 
 use crate::prelude::*;
 use crate::tui::agent_pane::AgentPane;
-use crate::tui::widgets::render_message_section;
-use crate::commands::{from_input_action, CommandResult};
+use crate::persona::agent_manager::AgentEvent;
+use crate::persona::agent::{AgentInfo, NUDGE_PANE_PREFIX};
+use crate::tui::widgets::{render_message_section, render_message_section_with_gauge, render_mode_line, render_tab_bar, render_status_bar, agent_accent_color, parse_message_lines, wrapped_line_count, CODE_BLOCK_BG};
+use crate::tui::layout::{
+    clamp_split_ratio, compute_cursor, compute_layout, wrap_input, InputLine, saved_layout_mode, save_layout_mode,
+    saved_split_ratio, save_split_ratio, stacked_areas, stacked_window, LayoutMode, SPLIT_RATIO_STEP,
+};
+use crate::commands::{from_input_action, send_check_in, Command, CommandResult, FocusCheckInCommand, HELP_TEXT, NO_AGENT_GUIDANCE};
+use crate::utilities::accessibility::{self, glyph};
+use crate::utilities::profile;
+use crate::utilities::strict;
+use crate::utilities::events::{self, EventKind};
+use crate::utilities::code_lang::{parse_code_blocks, Language};
+use crate::tui::highlight::HighlightCache;
+use crate::agent_history::persistence;
+use crate::user::macros::{MacroRecording, MacroReplay};
+use crate::persona::focus::{self, FocusBlock, FocusOutcome, FocusTimer};
+use crate::persona::escalation::{self, PendingEscalation};
+use crate::tui::tour::{self, Tour};
+use crate::tui::inspect::InspectView;
+use crate::tui::vim::{self, InputMode, NormalOutcome, VimState};
+use crate::tui::gauge::{self, ContextUsage};
+use crate::tui::search;
+use crate::llm::credentials::ApiProvider;
+use crate::claude::client::CLAUDE_MODEL;
+use crate::persona::reload::PersonaWatcher;
+use crate::agent_history::freshness::ResyncChoice;
+use crate::utilities::explain;
+use crate::utilities::clipboard::{self, Copied};
+use crate::utilities::time::{format_clock, Zone};
+use crate::utilities::speech::Speaker;
+use crate::user::system_info::OsInfo;
+use crate::user::user_input::complete_command;
+use crate::tui::hyperlinks::{self, linkify, take_links, FrameLinks, LinkTable};
+use crate::persona::background::BackgroundReports;
+
+/// `pane_rects` key of the System pane (or its overlay), which isn't an agent
+pub const GLOBAL_PANE: Uuid = Uuid::nil();
+
+/// Most command names the completion popup lists
+const COMPLETION_LIMIT: usize = 5;
+
+/// Fewest keys in one burst the paste fallback reads as pasted text; nobody types this fast
+const PASTE_BURST_MIN_KEYS: usize = 32;
+
+/// Pastes with more lines than this are shown collapsed in the input box
+const COLLAPSE_PASTE_LINES: usize = 20;
+
+/// Pastes with more characters than this are shown collapsed in the input box
+const COLLAPSE_PASTE_CHARS: usize = 2000;
 
 /// # UnifiedMessage
 ///
@@ -48,6 +97,7 @@ use crate::commands::{from_input_action, CommandResult};
 /// **Fields:**
 /// - `text`: The message content
 /// - `source`: Where the message originated (Global or specific Agent)
+/// - `kind`: What produced the message (used by the Global pane filter)
 /// - `timestamp`: When the message was created
 ///
 /// **Usage Example:**
@@ -55,6 +105,7 @@ use crate::commands::{from_input_action, CommandResult};
 /// let msg = UnifiedMessage {
 ///     text: "Hello".to_string(),
 ///     source: MessageSource::Global,
+///     kind: MessageKind::Info,
 ///     timestamp: SystemTime::now(),
 /// };
 /// ```
@@ -62,6 +113,7 @@ use crate::commands::{from_input_action, CommandResult};
 pub struct UnifiedMessage {
     pub text: String,
     pub source: MessageSource,
+    pub kind: MessageKind,
     pub timestamp: SystemTime,
 }
 
@@ -73,12 +125,109 @@ pub struct UnifiedMessage {
 /// **Variants:**
 /// - `Global`: Message displayed globally across all panes
 /// - `Agent(String)`: Message from a specific agent with persona name
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MessageSource {
     Global,
     Agent(String),
 }
 
+/// # MessageKind
+///
+/// **Summary:**
+/// Indicates what produced a Global pane entry.
+///
+/// **Variants:**
+/// - `Info`: General application notices (welcome text, input feedback)
+/// - `Command`: Output from an executed command
+/// - `Error`: Command failures and other errors
+/// - `Echo`: A command repeated with Enter on empty input (rendered dim)
+/// - `Warning`: Command output that needs attention but isn't a failure (rendered yellow)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    Info,
+    Command,
+    Error,
+    Echo,
+    Warning,
+}
+
+/// # GlobalFilter
+///
+/// **Summary:**
+/// Restricts which entries the Global pane renders.
+///
+/// **Variants:**
+/// - `Persona(String)`: Only entries attributed to the named persona
+/// - `Errors`: Only error entries
+/// - `Commands`: Only command output
+///
+/// **Usage Example:**
+/// ```no_run
+/// # use grokprime_brain::tui::app::{GlobalFilter, ShadowApp};
+/// # let app = ShadowApp::new();
+/// let filter = GlobalFilter::parse("errors");
+/// let visible = app.unified_messages.iter().filter(|m| filter.matches(m));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GlobalFilter {
+    Persona(String),
+    Errors,
+    Commands,
+}
+
+impl GlobalFilter {
+    /// # parse
+    ///
+    /// **Purpose:**
+    /// Parses a filter argument; anything that isn't a keyword is a persona name.
+    ///
+    /// **Parameters:**
+    /// - `arg`: "errors", "commands", or a persona name
+    ///
+    /// **Returns:**
+    /// The matching GlobalFilter
+    pub fn parse(arg: &str) -> Self {
+        match arg.to_lowercase().as_str() {
+            "errors" => GlobalFilter::Errors,
+            "commands" => GlobalFilter::Commands,
+            persona => GlobalFilter::Persona(persona.to_string()),
+        }
+    }
+
+    /// # matches
+    ///
+    /// **Purpose:**
+    /// Determines whether a Global pane entry passes this filter.
+    ///
+    /// **Parameters:**
+    /// - `msg`: The entry to test
+    ///
+    /// **Returns:**
+    /// `bool` - true if the entry should be displayed
+    pub fn matches(&self, msg: &UnifiedMessage) -> bool {
+        match self {
+            GlobalFilter::Persona(name) => matches!(
+                &msg.source,
+                MessageSource::Agent(source) if source.eq_ignore_ascii_case(name)
+            ),
+            GlobalFilter::Errors => msg.kind == MessageKind::Error,
+            GlobalFilter::Commands => matches!(msg.kind, MessageKind::Command | MessageKind::Echo | MessageKind::Warning),
+        }
+    }
+
+    /// # label
+    ///
+    /// **Purpose:**
+    /// Short description shown in the Global pane title.
+    pub fn label(&self) -> String {
+        match self {
+            GlobalFilter::Persona(name) => capitalize_first(name),
+            GlobalFilter::Errors => "errors".to_string(),
+            GlobalFilter::Commands => "commands".to_string(),
+        }
+    }
+}
+
 
 /// # ShadowApp
 ///
@@ -91,14 +240,39 @@ pub enum MessageSource {
 /// - `scroll`: Global scroll position
 /// - `max_history`: Maximum messages to retain in history
 /// - `user_input`: Optional user input handler
-/// - `is_waiting`: Whether the app is waiting for a response
 /// - `input_scroll`: Scroll position in input area
 /// - `input_max_lines`: Maximum visible lines in input
+/// - `input_cursor`: Byte offset of the cursor in `input` (the end unless Vim mode moved it)
+/// - `input_collapsed`: The input holds a large paste and is shown as a one-line summary
+/// - `paste_fallback`: Read long key bursts as pastes (the terminal has no bracketed paste)
+/// - `input_mode`: Vim Insert or Normal mode (always Insert while Vim mode is off)
+/// - `vim`: Vim mode switch, pending operator, and register (Ctrl+V)
 /// - `personas`: Map of persona names to their configurations
 /// - `agents`: Map of agent IDs to their panes
 /// - `agent_order`: Ordered list of agent IDs for tab switching
 /// - `current_agent`: Currently selected agent ID
 /// - `unified_messages`: All messages with source tracking
+/// - `global_filter`: Optional filter restricting what the Global pane shows
+/// - `last_command`: Last repeatable command line (re-run by Enter on empty input)
+/// - `repeat_on_enter`: Whether Enter on empty input repeats `last_command` (`tui.repeat_last_command`)
+/// - `close_armed`: Agent the last key (Ctrl+W) asked to confirm closing
+/// - `layout_mode`: Split, single-pane, or automatic by terminal width
+/// - `split_ratio`: Percent of the width the agent pane gets in the split layout (Ctrl+Left/Right)
+/// - `single_pane`: Whether the last frame was drawn with a single pane
+/// - `global_overlay`: Global pane shown as an overlay (single-pane layout, Ctrl+G)
+/// - `overlay_scroll`: Scroll position of the Global overlay
+/// - `overlay_follow`: Whether the Global overlay stays on its newest message
+/// - `unread_global`: Global messages that arrived while the overlay was hidden
+/// - `highlight_cache`: Highlighted lines of finished Rust code blocks (filled while drawing)
+/// - `pane_rects`: Where the last frame drew each message pane, by agent id (`GLOBAL_PANE` for System), for mouse hit-testing
+/// - `tab_rects`: Where the last frame drew each agent's tab
+/// - `speaker`: Speaks replies for agents with `speak on`; started the first time one needs it
+/// - `completion_popup`: Command names matching the word being typed, opened with Tab and shown above the input box
+/// - `completion_selected`: Popup entry picked with Up / Down (None until an arrow is pressed)
+/// - `show_timestamps`: Show when each agent pane message was added (`timestamps`)
+/// - `link_table`: Targets of the links in the frame being drawn (filled while drawing)
+/// - `links`: Links in the last frame, for the main loop to write as terminal hyperlinks
+/// - `background`: Work running off the UI loop (`reload keys`), reporting to the Global pane
 ///
 /// **Usage Example:**
 /// ```rust
@@ -116,9 +290,48 @@ pub struct ShadowApp {
     pub max_history: usize,
     pub input_scroll: usize,
     pub input_max_lines: u16,
+    pub input_cursor: usize,
+    pub input_collapsed: bool,
+    pub paste_fallback: bool,
+    pub input_mode: InputMode,
+    pub vim: VimState,
     pub unified_messages: VecDeque<UnifiedMessage>,
+    pub global_filter: Option<GlobalFilter>,
+    pub last_command: Option<String>,
+    pub repeat_on_enter: bool,
+    pub close_armed: Option<Uuid>,
+    pub macro_recording: Option<MacroRecording>,
+    pub macro_replay: Option<MacroReplay>,
+    pub layout_mode: LayoutMode,
+    pub split_ratio: u8,
+    pub single_pane: bool,
+    pub global_overlay: bool,
+    pub overlay_scroll: u16,
+    pub overlay_follow: bool,
+    pub unread_global: usize,
+    pub highlight_cache: RefCell<HighlightCache>,
+    pub focus: Option<FocusTimer>,
+    pub focus_held: Vec<(String, MessageSource, MessageKind)>,
+    pub check_in_escalation: Option<PendingEscalation>,
+    pub tour: Option<Tour>,
+    pub inspect: Option<InspectView>,
+    pub persona_watcher: Option<PersonaWatcher>,
+    pub background: BackgroundReports,
+
+    pub pane_rects: HashMap<Uuid, Rect>,
+    pub tab_rects: Vec<(Uuid, Rect)>,
 
     pub agent_panes: HashMap<Uuid, AgentPane>,
+
+    pub speaker: Option<Speaker>,
+
+    pub completion_popup: Option<Vec<String>>,
+    pub completion_selected: Option<usize>,
+
+    pub show_timestamps: bool,
+
+    pub link_table: RefCell<LinkTable>,
+    pub links: FrameLinks,
 }
 
 impl Default for ShadowApp {
@@ -132,8 +345,42 @@ impl Default for ShadowApp {
             max_history: tui_config.max_history_size,
             input_scroll: 0,
             input_max_lines: tui_config.max_input_lines,
+            input_cursor: 0,
+            input_collapsed: false,
+            paste_fallback: false,
+            input_mode: InputMode::Insert,
+            vim: VimState::default(),
             unified_messages: VecDeque::new(),
+            global_filter: None,
+            last_command: None,
+            repeat_on_enter: tui_config.repeat_last_command,
+            close_armed: None,
+            macro_recording: None,
+            macro_replay: None,
+            layout_mode: saved_layout_mode().unwrap_or(tui_config.layout),
+            split_ratio: saved_split_ratio().unwrap_or(clamp_split_ratio(tui_config.split_ratio)),
+            single_pane: false,
+            global_overlay: false,
+            overlay_scroll: 0,
+            overlay_follow: true,
+            unread_global: 0,
+            highlight_cache: RefCell::new(HighlightCache::default()),
+            focus: None,
+            focus_held: Vec::new(),
+            check_in_escalation: None,
+            tour: None,
+            inspect: None,
+            persona_watcher: None,
+            background: BackgroundReports::default(),
+            pane_rects: HashMap::new(),
+            tab_rects: Vec::new(),
             agent_panes: HashMap::new(),
+            speaker: None,
+            completion_popup: None,
+            completion_selected: None,
+            show_timestamps: tui_config.show_timestamps,
+            link_table: RefCell::new(LinkTable::default()),
+            links: FrameLinks::default(),
         }
     }
 }
@@ -159,26 +406,22 @@ impl ShadowApp {
     /// # load_personas
     ///
     /// **Purpose:**
-    /// Loads persona configurations from YAML files and stores them in the app.
+    /// Loads persona configurations from YAML files (or embedded built-ins) and stores them in the app.
     ///
     /// **Parameters:**
-    /// - `persona_paths`: Vector of paths to persona YAML files
+    /// - `persona_sources`: Vector of persona sources from `discover_personas`
     ///
     /// **Returns:**
-    /// `anyhow::Result<()>` - Success or error if any persona fails to load
-    ///
-    /// **Errors / Failures:**
-    /// - File not found
-    /// - Invalid YAML format
-    /// - Missing required fields in persona config
+    /// `Vec<String>` - One message per persona skipped (missing file, invalid YAML,
+    /// failed validation); the others are loaded regardless
     ///
     /// **Examples:**
     /// ```rust
-    /// let paths = vec![Path::new("personas/shadow/shadow.yaml")];
-    /// app.load_personas(paths)?;
+    /// let source = PersonaSource::File(PathBuf::from("personas/shadow/shadow.yaml"));
+    /// let skipped = app.load_personas(vec![&source]);
     /// ```
-    pub fn load_personas(&mut self, persona_paths: Vec<&Path>) -> anyhow::Result<()> {
-        self.agent_manager.load_personas(persona_paths)
+    pub fn load_personas(&mut self, persona_sources: Vec<&PersonaSource>) -> Vec<String> {
+        self.agent_manager.load_personas(persona_sources)
     }
 
     /// # add_agent
@@ -193,9 +436,16 @@ impl ShadowApp {
     /// **Returns:**
     /// None (mutates internal state)
     pub fn add_agent(&mut self, id: Uuid, persona: PersonaRef) {
-        let pane = AgentPane::new();
-        self.agent_panes.insert(id, pane);
+        let previous = self.agent_manager.current_agent;
+        let mut pane = AgentPane::new(&persona);
         self.agent_manager.add_agent(id, persona);
+        // Replayed unread messages start with the "new since" divider; open the pane there
+        if self.agent_manager.agents.get(&id).is_some_and(|agent| !agent.messages.is_empty()) {
+            pane.scroll = 0;
+            pane.auto_scroll = false;
+        }
+        self.agent_panes.insert(id, pane);
+        self.carry_drafts(previous);
     }
 
     /// # get_agent_name
@@ -223,8 +473,10 @@ impl ShadowApp {
     /// **Returns:**
     /// None (mutates internal state)
     pub fn remove_agent(&mut self, id: Uuid) {
+        let previous = self.agent_manager.current_agent;
         self.agent_panes.remove(&id);
         self.agent_manager.remove_agent(id);
+        self.carry_drafts(previous);
     }
 
     /// # switch_agent
@@ -238,7 +490,86 @@ impl ShadowApp {
     /// **Returns:**
     /// None (mutates current_agent)
     pub fn switch_agent(&mut self, next: bool) {
+        let previous = self.agent_manager.current_agent;
         self.agent_manager.switch_agent(next);
+        self.carry_drafts(previous);
+    }
+
+    /// # set_current_agent
+    ///
+    /// **Purpose:**
+    /// Makes `id` the current agent, carrying the input's draft over.
+    pub fn set_current_agent(&mut self, id: Option<Uuid>) {
+        let previous = self.agent_manager.current_agent;
+        self.agent_manager.current_agent = id;
+        self.carry_drafts(previous);
+    }
+
+    /// # carry_drafts
+    ///
+    /// **Purpose:**
+    /// Keeps the input with the agent it was typed for, once the current agent has changed.
+    ///
+    /// **Parameters:**
+    /// - `previous`: The agent that was current before the change
+    ///
+    /// **Details:**
+    /// Unsent input becomes the previous agent's `draft`, and the new current
+    /// agent's draft comes back into the input box. Input typed for an agent
+    /// that has since closed is dropped. With no agent before, the input stays
+    /// unless the new one brings a draft.
+    fn carry_drafts(&mut self, previous: Option<Uuid>) {
+        if previous == self.agent_manager.current_agent {
+            return;
+        }
+        if let Some(previous) = previous {
+            let input = std::mem::take(&mut self.input);
+            if let Some(pane) = self.agent_panes.get_mut(&previous) {
+                pane.draft = (!input.trim().is_empty()).then_some(input);
+                pane.history_cursor = None;
+            }
+        }
+        let draft = self.current_pane_mut().and_then(|pane| pane.draft.take());
+        if previous.is_some() || draft.is_some() {
+            self.set_input(draft.unwrap_or_default());
+            self.scroll_input_to_bottom();
+            self.close_completions();
+        }
+    }
+
+    /// # close_current_agent
+    ///
+    /// **Purpose:**
+    /// Ctrl+W: closes the current agent, asking first when that would lose something.
+    ///
+    /// **Parameters:**
+    /// - `armed`: The agent the Ctrl+W just before this one asked about
+    ///
+    /// **Details:**
+    /// A reply still streaming, or unsent input, goes with the agent, so the
+    /// first Ctrl+W only warns. Pressed again straight away it closes the
+    /// agent; any other key keeps it.
+    fn close_current_agent(&mut self, armed: Option<Uuid>) {
+        let Some(id) = self.agent_manager.current_agent else {
+            return;
+        };
+        let mut losing = Vec::new();
+        if self.agent_manager.agents.get(&id).is_some_and(|agent| agent.is_waiting) {
+            losing.push("its reply is still streaming");
+        }
+        if !self.input.trim().is_empty() {
+            losing.push("the input box isn't empty");
+        }
+        if losing.is_empty() || armed == Some(id) {
+            self.remove_agent(id);
+            return;
+        }
+        self.close_armed = Some(id);
+        let warning = format!(
+            "Close {}? {}. Ctrl+W again closes it; any other key keeps it.",
+            capitalize_first(&self.get_agent_name(id)), capitalize_first(&losing.join(" and "))
+        );
+        self.push_global_message(warning, MessageSource::Global, MessageKind::Warning);
     }
 
     /// # current_pane
@@ -267,7 +598,58 @@ impl ShadowApp {
         self.agent_manager.current_agent
             .and_then(move |id| self.agent_panes.get_mut(&id))
     }
+
+    /// # context_usage
+    ///
+    /// **Purpose:**
+    /// Estimated context use of the current agent, for the pane's gauge.
+    ///
+    /// **Returns:**
+    /// `Option<ContextUsage>` - None with no agent, or when the connection is busy
+    /// and nothing was measured yet
+    ///
+    /// **Details:**
+    /// Never waits on the connection: while a request holds it, the pane's last
+    /// estimate is shown. The estimate is only redone when the conversation or
+    /// model changed (see `gauge::measure`).
+    fn context_usage(&mut self) -> Option<ContextUsage> {
+        let id = self.agent_manager.current_agent?;
+        let agent = self.agent_manager.agents.get(&id)?;
+        let pane = self.agent_panes.get_mut(&id)?;
+        let Ok(conn) = agent.connection.try_lock() else {
+            return pane.context_gauge.as_ref().map(|cached| cached.usage);
+        };
+        let model = match ApiProvider::for_persona(&conn.conversation.persona) {
+            ApiProvider::Claude => CLAUDE_MODEL,
+            ApiProvider::Grok => conn.conversation.model(),
+        };
+        let measured = gauge::measure(&conn.conversation, model, pane.context_gauge.take(), &GLOBAL_CONFIG.tui.context_gauge);
+        let usage = measured.usage;
+        pane.context_gauge = Some(measured);
+        Some(usage)
+    }
     
+    /// # speaker
+    ///
+    /// **Purpose:**
+    /// The shared speaker, started on first use.
+    ///
+    /// **Details:**
+    /// If the OS has no speech program, that is said once, in `id`'s pane;
+    /// after that replies just aren't spoken.
+    pub fn speaker(&mut self, id: Uuid) -> &mut Speaker {
+        if self.speaker.is_none() {
+            let speaker = Speaker::new(OsInfo::new().os_type());
+            if let Some(warning) = speaker.missing_program_warning()
+                && let Some(agent) = self.agent_manager.agents.get_mut(&id)
+            {
+                agent.add_message(format!("{}{}", glyph("⚠ ", "Warning: "), warning));
+            }
+            self.speaker = Some(speaker);
+        }
+        self.speaker.as_mut().expect("speaker was started above")
+    }
+
     /// # poll_channels
     ///
     /// **Purpose:**
@@ -283,9 +665,90 @@ impl ShadowApp {
     /// - Processes Delta chunks by appending to last message
     /// - Handles Complete chunks by updating connection state
     /// - Processes Error chunks by displaying error messages
-    /// - Updates thinking animation frames while waiting
+    /// - Updates thinking animation frames while waiting (never in accessible mode)
+    /// - Speaks finished replies for agents with `speak on` (queued behind any still being spoken)
+    /// - Surfaces Info chunks, job lifecycle, and background replies in the Global pane unless the agent is muted
+    /// - Holds those notifications back while a focus block runs, and completes the block when its timer fires
+    /// - Follows up on an unanswered check-in when the next escalation is due
+    /// - Advances the current agent's read marker while its pane sits at the bottom
     pub fn poll_channels(&mut self) {
-        self.agent_manager.poll_channels();
+        let events = self.agent_manager.poll_channels();
+
+        for event in events {
+            if let AgentEvent::ReplyReady { id, reply, usage } = &event
+                && let Some(pane) = self.agent_panes.get_mut(id)
+            {
+                if usage.is_some() {
+                    pane.last_usage = *usage;
+                }
+                if pane.speaking {
+                    self.speaker(*id).speak(reply);
+                }
+            }
+            let (id, msg, kind) = match event {
+                AgentEvent::Info { id, msg } => (id, msg, MessageKind::Info),
+                // Only background agents need a nudge; the current pane shows the reply itself
+                AgentEvent::ReplyReady { id, .. } if Some(id) != self.agent_manager.current_agent => {
+                    (id, "Reply ready".to_string(), MessageKind::Info)
+                }
+                AgentEvent::ReplyReady { .. } => continue,
+                AgentEvent::Job { id, msg, failed } => {
+                    (id, msg, if failed { MessageKind::Error } else { MessageKind::Info })
+                }
+            };
+
+            let name = self.get_agent_name(id);
+            if self.agent_panes.get(&id).is_some_and(|pane| pane.muted) {
+                log_info!("[{} (muted)] {}", name, msg);
+            } else if self.focus.is_some() {
+                log_info!("[{} (held for focus)] {}", name, msg);
+                self.focus_held.push((msg, MessageSource::Agent(name), kind));
+            } else {
+                self.push_global_message(msg, MessageSource::Agent(name), kind);
+            }
+        }
+
+        let plan = self.persona_watcher.as_mut().and_then(|watcher| watcher.poll(Instant::now()));
+        if let Some(plan) = plan {
+            let report = self.agent_manager.apply_reload(&plan);
+            if let Some(summary) = report.summary() {
+                let kind = if report.failures.is_empty() { MessageKind::Info } else { MessageKind::Warning };
+                if self.focus.is_some() {
+                    self.focus_held.push((summary, MessageSource::Global, kind));
+                } else {
+                    self.push_global_message(summary, MessageSource::Global, kind);
+                }
+            }
+        }
+
+        for report in self.background.drain() {
+            let kind = if report.failed { MessageKind::Error } else { MessageKind::Info };
+            if self.focus.is_some() {
+                self.focus_held.push((report.msg, MessageSource::Global, kind));
+            } else {
+                self.push_global_message(report.msg, MessageSource::Global, kind);
+            }
+        }
+
+        let focus_over = self.focus.as_mut().is_some_and(|timer| timer.fired());
+        if let Some(block) = focus_over.then(|| self.end_focus()).flatten() {
+            focus::log_focus_event(&block, FocusOutcome::Completed);
+            FocusCheckInCommand::new(block).execute(self);
+        }
+
+        let now = Utc::now();
+        if self.check_in_escalation.as_ref().is_some_and(|pending| pending.is_due(now)) {
+            self.escalate_check_in(now);
+        }
+
+        let at_bottom = self.current_pane().is_some_and(|pane| pane.auto_scroll);
+        if let Some(agent) = self.agent_manager.current_pane_mut() {
+            agent.note_read(at_bottom);
+        }
+
+        if accessibility::is_enabled() {
+            return;
+        }
 
         for (id, pane_tui) in self.agent_panes.iter_mut() {
             if let Some(agent_info) = self.agent_manager.agents.get(id) {
@@ -308,20 +771,175 @@ impl ShadowApp {
     /// **Returns:**
     /// None (mutates internal state)
     pub fn add_message(&mut self, msg: impl Into<String>) {
+        self.push_global_message(msg, MessageSource::Global, MessageKind::Info);
+    }
+
+    /// # push_global_message
+    ///
+    /// **Purpose:**
+    /// Adds an entry to the Global pane with explicit source and kind.
+    ///
+    /// **Parameters:**
+    /// - `msg`: The message content
+    /// - `source`: Global or the originating agent's persona
+    /// - `kind`: What produced the message
+    ///
+    /// **Returns:**
+    /// None (mutates internal state)
+    pub fn push_global_message(&mut self, msg: impl Into<String>, source: MessageSource, kind: MessageKind) {
         let msg = msg.into();
         self.messages.push_back(msg.clone());
         
         self.unified_messages.push_back(UnifiedMessage {
             text: msg,
-            source: MessageSource::Global,
+            source,
+            kind,
             timestamp: SystemTime::now(),
         });
 
+        if self.single_pane && !self.global_overlay {
+            self.unread_global += 1;
+        }
+
         if let Some(pane) = self.current_pane_mut() {
             pane.scroll_to_bottom();
         }
     }
 
+    /// # start_focus
+    ///
+    /// **Purpose:**
+    /// Starts timing a focus block and stores it so a restart can resume it.
+    ///
+    /// **Details:**
+    /// A block already running is replaced without being logged as cancelled.
+    pub fn start_focus(&mut self, block: FocusBlock) {
+        focus::save_focus_block(Some(&block));
+        self.focus = Some(FocusTimer::start(block));
+    }
+
+    /// # watch_personas
+    ///
+    /// **Purpose:**
+    /// Starts reloading persona files under `dir` when they change.
+    ///
+    /// **Details:**
+    /// Events are coalesced by `PersonaWatcher`; each settled burst is applied in
+    /// `poll_channels` and reported as one Global message. If the watcher can't
+    /// start, personas simply aren't reloaded.
+    pub fn watch_personas(&mut self, dir: &Path) {
+        let window = Duration::from_millis(GLOBAL_CONFIG.tui.persona_reload_debounce_ms);
+        match PersonaWatcher::start(dir, window) {
+            Ok(watcher) => self.persona_watcher = Some(watcher),
+            Err(e) => log_error!("Not watching {} for persona changes: {}", dir.display(), e),
+        }
+    }
+
+    /// # arm_check_in_escalation
+    ///
+    /// **Purpose:**
+    /// Schedules follow-ups for a check-in just sent by `persona_name`.
+    pub fn arm_check_in_escalation(&mut self, persona_name: &str) {
+        self.check_in_escalation = PendingEscalation::new(persona_name, Utc::now(), &GLOBAL_CONFIG.tui.check_in_escalation);
+        escalation::save_escalation(self.check_in_escalation.as_ref());
+    }
+
+    /// # check_in_answered
+    ///
+    /// **Purpose:**
+    /// Drops pending follow-ups once the user messages the persona that checked in.
+    pub fn check_in_answered(&mut self, persona_name: &str) {
+        if self.check_in_escalation.as_ref().is_some_and(|pending| pending.persona == persona_name) {
+            log_info!("Check-in from {} answered; follow-ups cancelled", persona_name);
+            self.check_in_escalation = None;
+            escalation::save_escalation(None);
+        }
+    }
+
+    /// # escalate_check_in
+    ///
+    /// **Purpose:**
+    /// Sends the next follow-up to an unanswered check-in and schedules the one after.
+    ///
+    /// **Details:**
+    /// Rings the terminal bell too, unless the policy turns it off or it's
+    /// quiet hours. After the last follow-up the pending state is dropped.
+    fn escalate_check_in(&mut self, now: DateTime<Utc>) {
+        let policy = &GLOBAL_CONFIG.tui.check_in_escalation;
+        let Some(mut pending) = self.check_in_escalation.take() else {
+            return;
+        };
+        let more = pending.escalate(now, policy);
+        events::publish(
+            EventKind::CheckInFollowUp { attempt: pending.escalations_sent, max_attempts: policy.max_escalations },
+            Some(&pending.persona),
+            None,
+        );
+        self.push_global_message(
+            format!("No reply to {}'s check-in yet; following up ({}).", pending.persona, pending.progress(policy)),
+            MessageSource::Global,
+            MessageKind::Warning,
+        );
+        if escalation::may_ring_bell(policy, now) {
+            escalation::ring_bell();
+        }
+        send_check_in(self, &pending.persona, &policy.prompt);
+
+        self.check_in_escalation = more.then_some(pending);
+        escalation::save_escalation(self.check_in_escalation.as_ref());
+    }
+
+    /// # resume_focus
+    ///
+    /// **Purpose:**
+    /// Picks up a focus block that was running when the app last exited, and
+    /// any check-in still waiting for a reply.
+    ///
+    /// **Details:**
+    /// Remaining time comes from the stored start time; a block that ran out
+    /// while the app was closed completes (and checks in) on the first poll.
+    /// Follow-ups keep their stored schedule; one that fell due meanwhile is sent on the first poll.
+    pub fn resume_focus(&mut self) {
+        self.check_in_escalation = escalation::saved_escalation();
+        let Some(block) = focus::saved_focus_block() else {
+            return;
+        };
+        log_info!("Resuming {}", block.describe());
+        self.add_message(format!("Resumed {} ({})", block.describe(), block.countdown()));
+        self.focus = Some(FocusTimer::start(block));
+    }
+
+    /// # end_focus
+    ///
+    /// **Purpose:**
+    /// Stops the running focus block and releases the notifications it held back.
+    ///
+    /// **Returns:**
+    /// `Option<FocusBlock>` - The block that was running, if any
+    pub fn end_focus(&mut self) -> Option<FocusBlock> {
+        let block = self.focus.take()?.block.clone();
+        focus::save_focus_block(None);
+
+        let held = std::mem::take(&mut self.focus_held);
+        if !held.is_empty() {
+            self.add_message(format!("{} notification(s) held during the focus block:", held.len()));
+            for (msg, source, kind) in held {
+                self.push_global_message(msg, source, kind);
+            }
+        }
+        Some(block)
+    }
+
+    /// # clear_global_messages
+    ///
+    /// **Purpose:**
+    /// Empties the Global pane (in-memory only).
+    pub fn clear_global_messages(&mut self) {
+        self.messages.clear();
+        self.unified_messages.clear();
+        self.scroll = 0;
+    }
+
     /// # scroll_to_bottom
     ///
     /// **Purpose:**
@@ -338,69 +956,515 @@ impl ShadowApp {
         }
     }
 
-    fn scroll_input_to_bottom(&mut self) {
-        let wrapped = self.wrap_input_text(100);
-        self.input_scroll = wrapped.len().saturating_sub(self.input_max_lines as usize);
-    }
-    
-    /// # handle_key
+    /// # toggle_global_overlay
     ///
     /// **Purpose:**
-    /// Processes keyboard input events and updates application state accordingly.
-    ///
-    /// **Parameters:**
-    /// - `key`: The keyboard event to process
+    /// Shows or hides the Global overlay in the single-pane layout (Ctrl+G).
     ///
-    /// **Returns:**
-    /// `bool` - true to continue running, false to exit
+    /// **Details:**
+    /// Opening the overlay marks its messages read and jumps to the newest one.
+    pub fn toggle_global_overlay(&mut self) {
+        if !self.single_pane {
+            self.global_overlay = false;
+            return;
+        }
+        self.global_overlay = !self.global_overlay;
+        if self.global_overlay {
+            self.unread_global = 0;
+            self.overlay_follow = true;
+        }
+    }
+
+    /// # start_tour
     ///
-    /// **Errors / Failures:**
-    /// - None (infallible)
+    /// **Purpose:**
+    /// Starts the guided tour from its first step (`tour` command, or first launch).
+    pub fn start_tour(&mut self) {
+        self.tour = Some(Tour::new());
+        self.announce_tour_step();
+    }
+
+    /// # handle_inspect_key
     ///
-    /// **Examples:**
-    /// ```rust
-    /// let should_continue = app.handle_key(key);
-    /// if !should_continue { break; }
-    /// ```
-    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+    /// **Purpose:**
+    /// Keys while the `inspect` view is open: it takes all input until Esc closes it.
+    fn handle_inspect_key(&mut self, key: KeyEvent) {
+        let Some(view) = self.inspect.as_mut() else {
+            return;
+        };
         match key.code {
-            
-            // Agent panel control
-            KeyCode::Tab if !key.modifiers.contains(KeyModifiers::SHIFT) => {
-                self.switch_agent(true);
-                true
-            }
-            KeyCode::Tab if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                self.switch_agent(false);
-                true
-            }
-            KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                if let Some(id) = self.agent_manager.current_agent {
-                    self.remove_agent(id);
-                }
-                true
-            }
-
-            // Input Text control
-            KeyCode::Char(c) => {
-                self.input.push(c);
-                self.scroll_input_to_bottom();
-                true
-            }
-            KeyCode::Backspace => {
-                self.input.pop();
-                self.scroll_input_to_bottom();
-                true
-            }
-            KeyCode::Enter => {
-                let shutdown = self.enter_key();
-                if shutdown {
-                    return false;
+            KeyCode::Esc => self.inspect = None,
+            KeyCode::Up => view.select(false),
+            KeyCode::Down | KeyCode::Tab => view.select(true),
+            KeyCode::Enter | KeyCode::Char(' ') => view.toggle_selected(),
+            KeyCode::PageUp => view.scroll = view.scroll.saturating_sub(GLOBAL_CONFIG.tui.page_scroll_step),
+            KeyCode::PageDown => view.scroll = view.scroll.saturating_add(GLOBAL_CONFIG.tui.page_scroll_step),
+            KeyCode::Char('c') => {
+                let Some(section) = view.selected_section() else {
+                    return;
+                };
+                let title = section.title.clone();
+                match clipboard::copy_to_clipboard(&section.body) {
+                    Ok(()) => self.add_message(format!("Copied {} to the clipboard.", title.to_lowercase())),
+                    Err(e) => self.push_global_message(e, MessageSource::Global, MessageKind::Error),
                 }
-                true
             }
+            _ => {}
+        }
+    }
 
-            // Input Scroll control
+    /// # copy_last_reply
+    ///
+    /// **Purpose:**
+    /// Copies the current pane's last reply to the clipboard (Ctrl+Y).
+    ///
+    /// **Details:**
+    /// The reply is the last pane message that isn't the user's (`>`) or a nudge;
+    /// one still streaming in is copied as far as it has got. Without a clipboard
+    /// the text goes to `~/.grokprime_clipboard.txt` instead, and the message says so.
+    fn copy_last_reply(&mut self) {
+        let reply = self.agent_manager.current_pane()
+            .and_then(|agent| agent.messages.iter().rev()
+                .find(|message| !message.text.starts_with('>') && !message.text.starts_with(NUDGE_PANE_PREFIX)))
+            .map(|message| message.text.clone());
+        let Some(reply) = reply else {
+            self.add_message("No reply to copy in this pane.");
+            return;
+        };
+        match clipboard::copy_or_save(&reply) {
+            Ok(Copied::Clipboard) => self.add_message("Copied to clipboard"),
+            Ok(Copied::File(path)) => self.add_message(format!("No clipboard available; saved the reply to {}", path.display())),
+            Err(e) => self.push_global_message(e, MessageSource::Global, MessageKind::Error),
+        }
+    }
+
+    /// Starts the tour on a first launch (no `tour_seen` marker yet)
+    pub fn start_tour_if_new(&mut self) {
+        if !tour::tour_seen() {
+            self.start_tour();
+        }
+    }
+
+    /// # advance_tour
+    ///
+    /// **Purpose:**
+    /// Moves the tour on when the action it suggested was run.
+    ///
+    /// **Parameters:**
+    /// - `action`: InputAction variant name of the command that just ran
+    /// - `result`: What it returned (a failed command never completes a step)
+    pub fn advance_tour(&mut self, action: &str, result: &CommandResult) {
+        if self.tour.as_mut().is_some_and(|tour| tour.record_action(action, result)) {
+            self.after_tour_step();
+        }
+    }
+
+    /// Skips the current tour step (Ctrl+N)
+    pub fn skip_tour_step(&mut self) {
+        if let Some(tour) = self.tour.as_mut() {
+            tour.skip();
+            self.after_tour_step();
+        }
+    }
+
+    /// # close_tour
+    ///
+    /// **Purpose:**
+    /// Ends the tour (finished or dismissed with Esc) and stops it from auto-starting again.
+    pub fn close_tour(&mut self) {
+        if let Some(tour) = self.tour.take() {
+            let ending = if tour.is_finished() { "Tour complete" } else { "Tour closed" };
+            self.add_message(format!("{}. Type `tour` to see it again.", ending));
+            tour::mark_tour_seen();
+        }
+    }
+
+    fn after_tour_step(&mut self) {
+        if self.tour.as_ref().is_some_and(Tour::is_finished) {
+            self.close_tour();
+        } else {
+            self.announce_tour_step();
+        }
+    }
+
+    /// Screen readers don't see the hint box, so accessible mode gets each step as a message
+    fn announce_tour_step(&mut self) {
+        if !accessibility::is_enabled() {
+            return;
+        }
+        if let Some((step, (index, count))) = self.tour.as_ref().and_then(|tour| tour.current().map(|step| (step, tour.position()))) {
+            self.add_message(format!("Tour {}/{}: {} (Ctrl+N skips, Esc ends the tour)", index, count, step.text));
+        }
+    }
+
+    /// # set_layout
+    ///
+    /// **Purpose:**
+    /// Switches the pane layout and remembers the choice for the next session.
+    pub fn set_layout(&mut self, mode: LayoutMode) {
+        self.layout_mode = mode;
+        save_layout_mode(mode);
+        if matches!(mode, LayoutMode::Split | LayoutMode::Stacked) {
+            self.global_overlay = false;
+            self.unread_global = 0;
+        }
+    }
+
+    /// # toggle_stacked_layout
+    ///
+    /// **Purpose:**
+    /// Switches between the stacked and split layouts (Alt+L), remembering the choice like `layout`.
+    pub fn toggle_stacked_layout(&mut self) {
+        let mode = if self.layout_mode == LayoutMode::Stacked { LayoutMode::Split } else { LayoutMode::Stacked };
+        self.set_layout(mode);
+        self.add_message(format!("Layout: {}", mode));
+    }
+
+    /// # resize_split
+    ///
+    /// **Purpose:**
+    /// Widens (`wider`) or narrows the agent pane by one step (Ctrl+Right / Ctrl+Left).
+    ///
+    /// **Details:**
+    /// Stays within 20–80%. The choice is saved once, on exit (`remember_split_ratio`).
+    pub fn resize_split(&mut self, wider: bool) {
+        let ratio = if wider {
+            self.split_ratio.saturating_add(SPLIT_RATIO_STEP)
+        } else {
+            self.split_ratio.saturating_sub(SPLIT_RATIO_STEP)
+        };
+        self.split_ratio = clamp_split_ratio(ratio);
+    }
+
+    /// # remember_split_ratio
+    ///
+    /// **Purpose:**
+    /// Remembers the split ratio for the next session, if it was changed.
+    pub fn remember_split_ratio(&self) {
+        let starting = saved_split_ratio().unwrap_or(clamp_split_ratio(GLOBAL_CONFIG.tui.split_ratio));
+        if self.split_ratio != starting {
+            save_split_ratio(self.split_ratio);
+        }
+    }
+
+    fn scroll_input_to_bottom(&mut self) {
+        let wrapped = self.wrap_input_text(100);
+        self.input_scroll = wrapped.len().saturating_sub(self.input_max_lines as usize);
+    }
+
+    /// # insert_text
+    ///
+    /// **Purpose:**
+    /// Inserts a block of text into the input in one operation (used for pastes).
+    ///
+    /// **Parameters:**
+    /// - `text`: Text to insert; CRLF and lone CR are normalized to LF
+    ///
+    /// **Details:**
+    /// Wrapping and input scroll are recomputed once for the whole block instead of per character.
+    /// A block over `COLLAPSE_PASTE_LINES` lines or `COLLAPSE_PASTE_CHARS` characters collapses
+    /// the input box to a one-line summary until the input is sent or cleared.
+    /// While a `/` search query is being typed, the text goes into the query on one line.
+    pub fn insert_text(&mut self, text: &str) {
+        if let Some(pane) = self.current_pane_mut()
+            && pane.search_editing
+        {
+            let query = pane.search_query.take().unwrap_or_default();
+            pane.edit_search(query + &text.replace(['\r', '\n'], " "));
+            return;
+        }
+        let text = normalize_newlines(text);
+        let large = text.lines().count() > COLLAPSE_PASTE_LINES || text.chars().count() > COLLAPSE_PASTE_CHARS;
+        self.input_collapsed = large || (self.input_collapsed && !self.input.is_empty());
+        let at = self.cursor();
+        self.input.insert_str(at, &text);
+        self.input_cursor = at + text.len();
+        self.scroll_input_to_bottom();
+    }
+
+    /// The input cursor, clamped to the text and onto a character boundary
+    fn cursor(&self) -> usize {
+        let mut at = self.input_cursor.min(self.input.len());
+        while !self.input.is_char_boundary(at) {
+            at -= 1;
+        }
+        at
+    }
+
+    /// Replaces the whole input, leaving the cursor at the end
+    fn set_input(&mut self, text: String) {
+        self.input = text;
+        self.input_collapsed = false;
+        self.input_cursor = self.input.len();
+    }
+
+    /// # handle_key_burst
+    ///
+    /// **Purpose:**
+    /// Processes key events that arrived back-to-back within a few milliseconds.
+    ///
+    /// **Parameters:**
+    /// - `keys`: Key presses read in one burst
+    ///
+    /// **Returns:**
+    /// `bool` - true to continue running, false to exit
+    ///
+    /// **Details:**
+    /// Fallback for terminals without bracketed paste (`paste_fallback`): a burst
+    /// of at least `PASTE_BURST_MIN_KEYS` plain characters and Enters is faster
+    /// than anyone types, so it is inserted as pasted text. Enters at the end of
+    /// the burst still go through `handle_key`, so they send. Only applies while
+    /// typing goes straight into the input (not Vim Normal mode, not with the
+    /// completion popup open); everything else is handled key by key.
+    pub fn handle_key_burst(&mut self, keys: Vec<KeyEvent>) -> bool {
+        let typing = self.input_mode == InputMode::Insert && self.completion_popup.is_none();
+        if self.paste_fallback
+            && typing
+            && let Some((text, trailing)) = split_paste_burst(&keys)
+        {
+            self.insert_text(&text);
+            return trailing.iter().all(|key| self.handle_key(*key));
+        }
+
+        for key in keys {
+            if !self.handle_key(key) {
+                return false;
+            }
+        }
+        true
+    }
+    
+    /// # handle_mouse
+    ///
+    /// **Purpose:**
+    /// Scrolls the pane under the wheel and switches agents on a tab click.
+    ///
+    /// **Parameters:**
+    /// - `event`: The mouse event to process
+    ///
+    /// **Returns:**
+    /// `bool` - true to continue running (the mouse never quits)
+    ///
+    /// **Details:**
+    /// Hit-testing uses the areas the last frame drew (`pane_rects`, `tab_rects`).
+    /// The wheel moves `tui.scroll_step` lines; the Inspect view, when open,
+    /// takes it wherever the pointer is, and so does the System overlay over
+    /// the agent pane.
+    pub fn handle_mouse(&mut self, event: MouseEvent) -> bool {
+        let pointer = Position::new(event.column, event.row);
+        let up = match event.kind {
+            MouseEventKind::ScrollUp => true,
+            MouseEventKind::ScrollDown => false,
+            MouseEventKind::Down(MouseButton::Left) => {
+                let clicked = self.tab_rects.iter()
+                    .find(|(_, area)| area.contains(pointer))
+                    .map(|(id, _)| *id);
+                if let Some(id) = clicked {
+                    self.set_current_agent(Some(id));
+                }
+                return true;
+            }
+            _ => return true,
+        };
+        let step = GLOBAL_CONFIG.tui.scroll_step;
+        let scroll = |value: u16| if up { value.saturating_sub(step) } else { value.saturating_add(step) };
+
+        if let Some(view) = self.inspect.as_mut() {
+            view.scroll = scroll(view.scroll);
+            return true;
+        }
+        let hovered = self.pane_rects.iter()
+            .find(|(_, area)| area.contains(pointer))
+            .map(|(id, _)| *id);
+        match hovered {
+            Some(GLOBAL_PANE) if self.global_overlay => {
+                self.overlay_scroll = scroll(self.overlay_scroll);
+                if up {
+                    self.overlay_follow = false;
+                }
+            }
+            Some(GLOBAL_PANE) => self.scroll = scroll(self.scroll),
+            Some(id) => {
+                if let Some(pane) = self.agent_panes.get_mut(&id) {
+                    pane.scroll = scroll(pane.scroll);
+                    if up {
+                        pane.auto_scroll = false;
+                    }
+                }
+            }
+            None => {}
+        }
+        true
+    }
+
+    /// # handle_key
+    ///
+    /// **Purpose:**
+    /// Processes keyboard input events and updates application state accordingly.
+    ///
+    /// **Parameters:**
+    /// - `key`: The keyboard event to process
+    ///
+    /// **Returns:**
+    /// `bool` - true to continue running, false to exit
+    ///
+    /// **Errors / Failures:**
+    /// - None (infallible)
+    ///
+    /// **Examples:**
+    /// ```rust
+    /// let should_continue = app.handle_key(key);
+    /// if !should_continue { break; }
+    /// ```
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        // A close waiting on confirmation only lasts until the next key
+        let close_armed = self.close_armed.take();
+        if self.inspect.is_some() {
+            self.handle_inspect_key(key);
+            return true;
+        }
+
+        if key.code == KeyCode::Char('v') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.toggle_vim_mode();
+            return true;
+        }
+        if self.current_pane().is_some_and(|pane| pane.search_query.is_some())
+            && let Some(keep_running) = self.handle_search_key(key)
+        {
+            return keep_running;
+        }
+        if self.input_mode == InputMode::Normal
+            && let Some(keep_running) = self.handle_normal_key(key)
+        {
+            return keep_running;
+        }
+        if self.completion_popup.is_some()
+            && let Some(keep_running) = self.handle_completion_key(key)
+        {
+            return keep_running;
+        }
+
+        match key.code {
+            
+            // Agent panel control
+            // Tab completes a command name being typed; otherwise it switches agents
+            KeyCode::Tab if !key.modifiers.contains(KeyModifiers::SHIFT) => {
+                if !self.open_completions() {
+                    self.switch_agent(true);
+                }
+                true
+            }
+            // Most terminals report Shift+Tab as BackTab
+            KeyCode::BackTab | KeyCode::Tab => {
+                self.switch_agent(false);
+                true
+            }
+            KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.close_current_agent(close_armed);
+                true
+            }
+            KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.toggle_global_overlay();
+                true
+            }
+            KeyCode::Char('y') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.copy_last_reply();
+                true
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                !self.run_action(InputAction::Stop)
+            }
+            KeyCode::Left | KeyCode::Right if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.resize_split(key.code == KeyCode::Right);
+                true
+            }
+            KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.toggle_stacked_layout();
+                true
+            }
+
+            // Tour control (only while the tour is showing)
+            KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) && self.tour.is_some() => {
+                self.skip_tour_step();
+                true
+            }
+            KeyCode::Esc if self.tour.is_some() => {
+                self.close_tour();
+                true
+            }
+            KeyCode::Esc if self.vim.enabled => {
+                self.input_mode = InputMode::Normal;
+                self.input_cursor = vim::leave_insert(&self.input, self.cursor());
+                true
+            }
+
+            // Resync offer (only while the current agent has one)
+            KeyCode::Char('r') | KeyCode::Char('f')
+                if key.modifiers.contains(KeyModifiers::CONTROL) && self.resync_offered() =>
+            {
+                let choice = if key.code == KeyCode::Char('r') { ResyncChoice::Recap } else { ResyncChoice::Fresh };
+                !self.run_action(InputAction::Resync(choice))
+            }
+
+            // Explain (only right after the current agent's request failed)
+            KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) && self.failure_explainable() => {
+                !self.run_action(InputAction::Explain)
+            }
+
+            // Global overlay scroll control
+            KeyCode::Up | KeyCode::PageUp if self.global_overlay => {
+                let step = if key.code == KeyCode::Up { 1 } else { GLOBAL_CONFIG.tui.page_scroll_step };
+                self.overlay_scroll = self.overlay_scroll.saturating_sub(step);
+                self.overlay_follow = false;
+                true
+            }
+            KeyCode::Down | KeyCode::PageDown if self.global_overlay => {
+                let step = if key.code == KeyCode::Down { 1 } else { GLOBAL_CONFIG.tui.page_scroll_step };
+                self.overlay_scroll = self.overlay_scroll.saturating_add(step);
+                true
+            }
+
+            // Search the agent pane (`/` on an empty input)
+            KeyCode::Char('/') if self.input.is_empty() && self.current_pane().is_some() => {
+                if let Some(pane) = self.current_pane_mut() {
+                    pane.start_search();
+                }
+                true
+            }
+
+            // Input Text control
+            KeyCode::Char(c) => {
+                let at = self.cursor();
+                self.input.insert(at, c);
+                self.input_cursor = at + c.len_utf8();
+                self.scroll_input_to_bottom();
+                self.refresh_completions();
+                true
+            }
+            KeyCode::Backspace => {
+                let at = self.cursor();
+                if let Some(prev) = self.input[..at].chars().next_back() {
+                    self.input_cursor = at - prev.len_utf8();
+                    self.input.remove(self.input_cursor);
+                }
+                self.scroll_input_to_bottom();
+                self.refresh_completions();
+                true
+            }
+            // Alt+Enter (or Shift+Enter, where the terminal reports it) starts a new line
+            KeyCode::Enter if key.modifiers.intersects(KeyModifiers::ALT | KeyModifiers::SHIFT) => {
+                self.insert_text("\n");
+                true
+            }
+            KeyCode::Enter => {
+                let shutdown = self.enter_key();
+                if shutdown {
+                    return false;
+                }
+                true
+            }
+
+            // Input Scroll control
             KeyCode::Up if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 self.input_scroll = self.input_scroll.saturating_sub(1);
                 true
@@ -412,6 +1476,14 @@ impl ShadowApp {
                 true
             }
 
+            // Input history recall (Shift+Up / Shift+Down scroll the pane a line instead)
+            KeyCode::Up | KeyCode::Down
+                if !key.modifiers.contains(KeyModifiers::SHIFT) && self.input_has_focus() =>
+            {
+                self.recall_input(key.code == KeyCode::Up);
+                true
+            }
+
             // History Scroll control
             KeyCode::Up => {
                 if let Some(pane) = self.current_pane_mut() {
@@ -440,12 +1512,143 @@ impl ShadowApp {
                 true
             }
             KeyCode::Esc => {
-                return false;
+                // Same path as `quit`, so an active focus block gets its warning
+                !self.run_action(InputAction::Quit)
             }
             _ => true,
         }
     }
     
+    /// # toggle_vim_mode
+    ///
+    /// **Purpose:**
+    /// Turns Vim mode on (starting in Normal mode) or off (back to plain typing).
+    fn toggle_vim_mode(&mut self) {
+        self.vim.enabled = !self.vim.enabled;
+        self.vim.cancel_pending();
+        if self.vim.enabled {
+            self.input_mode = InputMode::Normal;
+            self.input_cursor = vim::normal_cursor(&self.input, self.cursor());
+            self.add_message("Vim mode on (Ctrl+V turns it off)");
+        } else {
+            self.input_mode = InputMode::Insert;
+            self.add_message("Vim mode off");
+        }
+    }
+
+    /// # handle_normal_key
+    ///
+    /// **Purpose:**
+    /// Handles a key in Vim Normal mode.
+    ///
+    /// **Returns:**
+    /// `Option<bool>` - Whether to keep running, or None to handle the key as usual
+    ///
+    /// **Details:**
+    /// Characters go to `tui::vim`; Ctrl shortcuts are ignored so they can't fire
+    /// by accident. Enter, Tab, and the scroll keys work as in Insert mode. Esc
+    /// closes the tour or cancels a half-typed `d` / `y`, but never quits.
+    fn handle_normal_key(&mut self, key: KeyEvent) -> Option<bool> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Some(true);
+        }
+        match key.code {
+            KeyCode::Char('/') if self.vim.pending().is_none() && self.current_pane().is_some() => {
+                if let Some(pane) = self.current_pane_mut() {
+                    pane.start_search();
+                }
+                Some(true)
+            }
+            KeyCode::Char(c) => {
+                let mut cursor = self.cursor();
+                if self.vim.normal_key(c, &mut self.input, &mut cursor) == NormalOutcome::Insert {
+                    self.input_mode = InputMode::Insert;
+                }
+                self.input_cursor = cursor;
+                self.scroll_input_to_bottom();
+                Some(true)
+            }
+            KeyCode::Esc => {
+                if self.tour.is_some() {
+                    self.close_tour();
+                }
+                self.vim.cancel_pending();
+                Some(true)
+            }
+            KeyCode::Backspace => Some(true),
+            _ => None,
+        }
+    }
+
+    /// # handle_search_key
+    ///
+    /// **Purpose:**
+    /// Handles a key while the current pane has a `/` search.
+    ///
+    /// **Returns:**
+    /// `Option<bool>` - Whether to keep running, or None to handle the key as usual
+    ///
+    /// **Details:**
+    /// - Typing the query: characters and Backspace edit it, and Enter keeps it for
+    ///   `n` / `N`. `/` on an empty query types a literal "/" instead (so `//` starts a message)
+    /// - After Enter: `n` / `N` go to the next / previous match and `/` starts a new
+    ///   query; any other character ends the search and is typed as usual
+    /// - Esc ends the search either way; Ctrl shortcuts and scrolling work as usual
+    fn handle_search_key(&mut self, key: KeyEvent) -> Option<bool> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            return None;
+        }
+        let pane = self.current_pane_mut()?;
+        let query = pane.search_query.clone().unwrap_or_default();
+        match key.code {
+            KeyCode::Esc => pane.clear_search(),
+            KeyCode::Char('/') if pane.search_editing && query.is_empty() => {
+                pane.clear_search();
+                self.insert_text("/");
+            }
+            KeyCode::Char(c) if pane.search_editing => pane.edit_search(query + &c.to_string()),
+            KeyCode::Backspace if pane.search_editing => {
+                let mut query = query;
+                if query.pop().is_some() {
+                    pane.edit_search(query);
+                } else {
+                    pane.clear_search();
+                }
+            }
+            KeyCode::Enter if pane.search_editing => {
+                if query.is_empty() {
+                    pane.clear_search();
+                } else {
+                    pane.search_editing = false;
+                }
+            }
+            KeyCode::Enter => {}
+            KeyCode::Char('n') => pane.step_search(true),
+            KeyCode::Char('N') => pane.step_search(false),
+            KeyCode::Char('/') => pane.start_search(),
+            KeyCode::Char(_) => {
+                pane.clear_search();
+                return None;
+            }
+            _ => return None,
+        }
+        Some(true)
+    }
+
+    /// Whether the current agent is offering `resync recap` / `resync fresh`
+    fn resync_offered(&self) -> bool {
+        self.agent_manager.current_agent
+            .and_then(|id| self.agent_manager.agents.get(&id))
+            .is_some_and(|agent| agent.resync_offer.is_some())
+    }
+
+    /// Whether the current agent's last request failed and nothing was sent since
+    fn failure_explainable(&self) -> bool {
+        self.agent_manager.current_agent
+            .and_then(|id| self.agent_manager.agents.get(&id))
+            .is_some_and(|agent| agent.last_failure.is_some())
+    }
+
     /// # enter_key
     ///
     /// **Purpose:**
@@ -462,14 +1665,159 @@ impl ShadowApp {
     /// - Routes commands to appropriate handlers
     /// - Spawns async tasks for Grok API communication
     /// - Clears input field after processing
+    /// - Empty input repeats the last repeatable command (see `InputAction::is_repeatable`)
     fn enter_key(&mut self) -> bool {
         if self.input.trim().is_empty() {
-            return false;
+            return self.repeat_last_command();
         }
 
         let line = self.input.trim().to_string();
-        self.input.clear();
+        self.set_input(String::new());
+        if let Some(pane) = self.current_pane_mut() {
+            pane.record_input(&line);
+        }
+        self.submit_line(line)
+    }
 
+    /// # command_completions
+    ///
+    /// **Purpose:**
+    /// Command names the input could be completed to.
+    ///
+    /// **Details:**
+    /// Only a single word, with the cursor at its end, that starts one or
+    /// more command names (at most `COMPLETION_LIMIT`). A word that is already
+    /// a whole command with nothing longer matching has none.
+    fn command_completions(&self) -> Vec<String> {
+        let word = self.input.as_str();
+        if word.contains(char::is_whitespace) || self.cursor() < word.len() {
+            return Vec::new();
+        }
+        let matches = complete_command(word);
+        if matches.len() == 1 && matches[0].eq_ignore_ascii_case(word) {
+            return Vec::new();
+        }
+        matches.into_iter().take(COMPLETION_LIMIT).map(str::to_string).collect()
+    }
+
+    /// # open_completions
+    ///
+    /// **Purpose:**
+    /// Tab on the input: completes the command name being typed.
+    ///
+    /// **Returns:**
+    /// `bool` - false if there was nothing to complete, so Tab switches agents as usual
+    ///
+    /// **Details:**
+    /// One match is filled in straight away; several open the popup. The
+    /// popup never opens by itself, so typing an ordinary message keeps Tab,
+    /// Up and Down for switching agents and recalling input.
+    fn open_completions(&mut self) -> bool {
+        let mut matches = self.command_completions();
+        match matches.len() {
+            0 => false,
+            1 => {
+                self.set_input(format!("{} ", matches.remove(0)));
+                self.scroll_input_to_bottom();
+                true
+            }
+            _ => {
+                self.completion_popup = Some(matches);
+                self.completion_selected = None;
+                true
+            }
+        }
+    }
+
+    /// Narrows the open popup as the word changes, closing it when nothing matches
+    fn refresh_completions(&mut self) {
+        if self.completion_popup.is_none() {
+            return;
+        }
+        self.completion_selected = None;
+        let matches = self.command_completions();
+        self.completion_popup = (!matches.is_empty()).then_some(matches);
+    }
+
+    /// # handle_completion_key
+    ///
+    /// **Purpose:**
+    /// Handles a key while the command popup is showing.
+    ///
+    /// **Returns:**
+    /// `Option<bool>` - Some(keep running) if the popup used the key; None to handle it as usual
+    ///
+    /// **Details:**
+    /// - Tab completes the picked entry (the first if none is picked)
+    /// - Up / Down pick an entry; Enter then completes it instead of sending
+    /// - Esc closes the popup; typing and Backspace update it
+    /// - Any other key closes the popup and then does what it normally does
+    fn handle_completion_key(&mut self, key: KeyEvent) -> Option<bool> {
+        let count = self.completion_popup.as_ref()?.len();
+        let picked = self.completion_selected;
+        match key.code {
+            KeyCode::Tab if key.modifiers.is_empty() => self.accept_completion(picked.unwrap_or(0)),
+            KeyCode::Enter if key.modifiers.is_empty() && picked.is_some() => self.accept_completion(picked.unwrap_or(0)),
+            KeyCode::Up => self.completion_selected = Some(picked.map_or(count - 1, |i| (i + count - 1) % count)),
+            KeyCode::Down => self.completion_selected = Some(picked.map_or(0, |i| (i + 1) % count)),
+            KeyCode::Esc => self.close_completions(),
+            KeyCode::Char(_) | KeyCode::Backspace if !key.modifiers.contains(KeyModifiers::CONTROL) => return None,
+            _ => {
+                self.close_completions();
+                return None;
+            }
+        }
+        Some(true)
+    }
+
+    /// Replaces the input with popup entry `index` followed by a space, ready for arguments
+    fn accept_completion(&mut self, index: usize) {
+        if let Some(name) = self.completion_popup.as_ref().and_then(|items| items.get(index)) {
+            self.set_input(format!("{} ", name));
+            self.scroll_input_to_bottom();
+        }
+        self.close_completions();
+    }
+
+    fn close_completions(&mut self) {
+        self.completion_popup = None;
+        self.completion_selected = None;
+    }
+
+    /// Whether Up / Down go to the input box: an agent is open and no overlay or search has the keys
+    fn input_has_focus(&self) -> bool {
+        !self.global_overlay
+            && self.inspect.is_none()
+            && self.current_pane().is_some_and(|pane| pane.search_query.is_none())
+    }
+
+    /// # recall_input
+    ///
+    /// **Purpose:**
+    /// Replaces the input with an older (Up) or newer (Down) line from the current pane's input history.
+    ///
+    /// **Details:**
+    /// Up at the oldest line and Down while not recalling leave the input
+    /// alone; Down past the newest line clears it.
+    fn recall_input(&mut self, older: bool) {
+        let Some(pane) = self.current_pane_mut() else {
+            return;
+        };
+        let recalled = if older { pane.recall_older() } else { pane.recall_newer() };
+        if let Some(line) = recalled.map(str::to_string) {
+            self.set_input(line);
+            self.scroll_input_to_bottom();
+        }
+    }
+
+    /// # submit_line
+    ///
+    /// **Purpose:**
+    /// Parses and runs one submitted line, recording it for recall, macros, and repeat.
+    ///
+    /// **Returns:**
+    /// `bool` - true if shutdown signal sent (app should exit), false otherwise
+    fn submit_line(&mut self, line: String) -> bool {
         let Some(user_input) = self.agent_manager.user_input.clone() else {
             self.add_message("No user input handler available.");
             return false;
@@ -481,28 +1829,230 @@ impl ShadowApp {
             InputAction::ContinueNoSend(msg) => {
                 self.add_message(msg);
             }
+            InputAction::RecallCommand { line, confirmed } => {
+                return self.recall_command(line, confirmed);
+            }
             
             // All other actions use the Command Pattern
             action => {
-                // Convert the InputAction into a Command object
-                let command = from_input_action(action);
-                
-                // Execute the command and get the result
-                let result = command.execute(self);
-                
-                // Handle the command result
-                match result {
-                    CommandResult::Continue => {},     // Keep running
-                    CommandResult::Shutdown => return true,  // Exit application
-                    CommandResult::Error(msg) => {
-                        self.add_message(format!("Error: {}", msg));
-                    }
+                if let Some(recording) = self.macro_recording.as_mut().filter(|_| !action.is_macro_control()) {
+                    recording.steps.push(line.clone());
                 }
+                user_input.record_command(&line, &action);
+                if action.is_repeatable() {
+                    self.last_command = Some(line);
+                }
+                return self.run_action(action);
             }
         }
 
         false
     }
+
+    /// # recall_command
+    ///
+    /// **Purpose:**
+    /// Acts on a resolved `!!`-style expansion.
+    ///
+    /// **Details:**
+    /// Unconfirmed, the line goes into the input box to run with Enter or edit
+    /// first. Confirmed, it's echoed dimly and runs like a typed line, so it's
+    /// recorded under its own text rather than the expansion.
+    fn recall_command(&mut self, line: String, confirmed: bool) -> bool {
+        if !confirmed {
+            self.push_global_message(
+                format!("{} {} (Enter runs it; edit it first if needed)", glyph("↻", "Recalled:"), line),
+                MessageSource::Global,
+                MessageKind::Echo,
+            );
+            self.set_input(line);
+            self.scroll_input_to_bottom();
+            return false;
+        }
+        self.push_global_message(format!("{} {}", glyph("↻", "Repeated:"), line), MessageSource::Global, MessageKind::Echo);
+        self.submit_line(line)
+    }
+
+    /// # run_action
+    ///
+    /// **Purpose:**
+    /// Executes an InputAction through the Command Pattern and reports errors.
+    ///
+    /// **Returns:**
+    /// `bool` - true if shutdown signal sent (app should exit), false otherwise
+    fn run_action(&mut self, action: InputAction) -> bool {
+        let name: &'static str = (&action).into();
+        let started = Instant::now();
+        let jobs_before = self.agent_manager.jobs.len();
+        let was_waiting = self.current_agent_waiting();
+
+        // Convert the InputAction into a Command object
+        let command = from_input_action(action);
+
+        // Execute the command and get the result
+        let result = command.execute(self);
+
+        let spawned_async = self.agent_manager.jobs.len() > jobs_before
+            || (!was_waiting && self.current_agent_waiting());
+        profile::record_command(name, started, spawned_async);
+
+        self.advance_tour(name, &result);
+
+        // Handle the command result
+        match result {
+            CommandResult::Continue => false,     // Keep running
+            CommandResult::Shutdown => true,      // Exit application
+            CommandResult::Error(msg) => {
+                let hint = explain::hint(&msg);
+                self.push_global_message(format!("Error: {}", msg), MessageSource::Global, MessageKind::Error);
+                if let Some(hint) = hint {
+                    self.push_global_message(hint, MessageSource::Global, MessageKind::Info);
+                }
+                false
+            }
+        }
+    }
+
+    /// # repeat_last_command
+    ///
+    /// **Purpose:**
+    /// Re-runs the last repeatable command when Enter is pressed on empty input.
+    ///
+    /// **Returns:**
+    /// `bool` - true if shutdown signal sent (app should exit), false otherwise
+    ///
+    /// **Details:**
+    /// - Disabled entirely by `tui.repeat_last_command` (`repeat_on_enter`)
+    /// - The repeated command is echoed dimly in the Global pane
+    /// - The line is parsed again each time, so stale arguments fail the normal way
+    fn repeat_last_command(&mut self) -> bool {
+        if !self.repeat_on_enter {
+            return false;
+        }
+
+        let Some(line) = self.last_command.clone() else {
+            self.add_message("Nothing to repeat yet (Enter on empty input repeats the last command)");
+            return false;
+        };
+
+        let Some(user_input) = self.agent_manager.user_input.clone() else {
+            self.add_message("No user input handler available.");
+            return false;
+        };
+
+        self.push_global_message(format!("{} {}", glyph("↻", "Repeated:"), line), MessageSource::Global, MessageKind::Echo);
+        match user_input.process_input(&line) {
+            action if action.is_repeatable() => self.run_action(action),
+            InputAction::ContinueNoSend(msg) => {
+                self.add_message(msg);
+                false
+            }
+            _ => false,
+        }
+    }
+    
+    /// # advance_macro
+    ///
+    /// **Purpose:**
+    /// Runs the next step of a replaying macro, if it's ready to run.
+    ///
+    /// **Returns:**
+    /// `bool` - true if a replayed step requested shutdown
+    ///
+    /// **Details:**
+    /// - Called once per UI tick, so at most one step runs per tick
+    /// - Steps that need the agent (see `InputAction::needs_idle_agent`) wait
+    ///   until the current agent's reply has finished streaming
+    /// - Each step is echoed dimly in the Global pane
+    pub fn advance_macro(&mut self) -> bool {
+        let Some(replay) = self.macro_replay.as_mut() else {
+            return false;
+        };
+        let Some(line) = replay.remaining.pop_front() else {
+            let name = replay.name.clone();
+            self.macro_replay = None;
+            self.add_message(format!("Macro '{}' finished.", name));
+            return false;
+        };
+
+        let Some(user_input) = self.agent_manager.user_input.clone() else {
+            self.macro_replay = None;
+            self.add_message("No user input handler available.");
+            return false;
+        };
+
+        let action = user_input.process_input(&line);
+        if action.needs_idle_agent() && self.current_agent_waiting() {
+            if let Some(replay) = self.macro_replay.as_mut() {
+                replay.remaining.push_front(line);
+            }
+            return false;
+        }
+
+        self.push_global_message(format!("{} {}", glyph("▶", "Macro step:"), line), MessageSource::Global, MessageKind::Echo);
+        match action {
+            InputAction::DoNothing => false,
+            InputAction::ContinueNoSend(msg) => {
+                self.add_message(msg);
+                false
+            }
+            action => self.run_action(action),
+        }
+    }
+
+    /// # current_agent_waiting
+    ///
+    /// **Purpose:**
+    /// Whether the agent in the current pane has a request in flight.
+    fn current_agent_waiting(&self) -> bool {
+        self.agent_manager.current_pane()
+            .map(|a| a.is_waiting)
+            .unwrap_or(false)
+    }
+
+    /// # other_agents_working
+    ///
+    /// **Purpose:**
+    /// Counts agents other than the current one that are waiting on a response.
+    fn other_agents_working(&self) -> usize {
+        self.agent_manager.agents.values()
+            .filter(|agent| agent.is_waiting && Some(agent.id) != self.agent_manager.current_agent)
+            .count()
+    }
+
+    /// # input_title
+    ///
+    /// **Purpose:**
+    /// Composes the input block title, noting background agents and jobs that are still working.
+    ///
+    /// **Parameters:**
+    /// - `others_working`: Number of non-current agents with a request in flight
+    /// - `jobs_running`: Number of generation jobs in flight
+    ///
+    /// **Returns:**
+    /// `String` - " Input " or e.g. " Input · STRICT · 2 agents working… · 1 job running " (plain punctuation in accessible mode)
+    fn input_title(others_working: usize, jobs_running: usize, warning: Option<String>) -> String {
+        let (separator, ellipsis) = (glyph(" · ", ", "), glyph("…", ""));
+        let mut title = " Input".to_string();
+        if strict::is_enabled() {
+            title.push_str(&format!("{}STRICT", separator));
+        }
+        match others_working {
+            0 => {}
+            1 => title.push_str(&format!("{}1 agent working{}", separator, ellipsis)),
+            n => title.push_str(&format!("{}{} agents working{}", separator, n, ellipsis)),
+        }
+        match jobs_running {
+            0 => {}
+            1 => title.push_str(&format!("{}1 job running", separator)),
+            n => title.push_str(&format!("{}{} jobs running", separator, n)),
+        }
+        if let Some(warning) = warning {
+            title.push_str(&format!("{}{}{}", separator, glyph("⚠ ", "Warning: "), warning));
+        }
+        title.push(' ');
+        title
+    }
     
     /// # calculate_input_height
     ///
@@ -516,13 +2066,10 @@ impl ShadowApp {
     /// `u16` - Height in terminal rows needed for the input widget
     ///
     /// **Details:**
-    /// Returns 3 rows if waiting for response, otherwise calculates based on wrapped text
+    /// Returns 3 rows if the current agent is waiting for a response, otherwise
+    /// calculates based on wrapped text (other agents' tasks don't affect it)
     fn calculate_input_height(&self, width: u16) -> u16 {
-        let is_waiting = self.agent_manager.current_pane()
-            .map(|a| a.is_waiting)
-            .unwrap_or(false);
-        
-        if is_waiting {
+        if self.current_agent_waiting() {
             return 3;
         }
 
@@ -531,11 +2078,7 @@ impl ShadowApp {
             return 3;
         }
 
-        let lines_needed = if self.input.is_empty() {
-            1
-        } else {
-            (self.input.len() / available_width) + 1
-        };
+        let lines_needed = self.wrap_input_text(available_width).len();
 
         (lines_needed.min(self.input_max_lines as usize) as u16) + 2
     }
@@ -552,20 +2095,56 @@ impl ShadowApp {
     /// `Vec<Line>` - Vector of styled lines ready for ratatui rendering
     ///
     /// **Details:**
-    /// User messages (starting with '>') are styled in light yellow and bold
+    /// - Entries hidden by the active `global_filter` are skipped
+    /// - Each entry starts with a source prefix in the agent's accent color
+    /// - User messages (starting with '>') are styled in light yellow and bold
+    /// - Errors are styled red
+    /// - Accessible mode uses "System:" / "Name:" prefixes and labels errors in text
     // Need to take out all the basic code that can be turned into functions for easier reading.
     fn unified_messages(&self) -> Vec<Line<'_>> {
         let mut lines: Vec<Line> = Vec::new();
         for unified in &self.unified_messages {
-            let content = if unified.text.starts_with('>') {
-                Line::from(Span::styled(
-                    unified.text.clone(),
-                    Style::default().fg(GLOBAL_CONFIG.tui.user_message_color).add_modifier(Modifier::BOLD),
-                ))
+            if self.global_filter.as_ref().is_some_and(|filter| !filter.matches(unified)) {
+                continue;
+            }
+
+            let accessible = accessibility::is_enabled();
+            let prefix = match &unified.source {
+                MessageSource::Global if accessible => Span::raw("System: "),
+                MessageSource::Global => Span::styled("[System] ", Style::default().fg(Color::DarkGray)),
+                MessageSource::Agent(name) if accessible => Span::raw(format!("{}: ", capitalize_first(name))),
+                MessageSource::Agent(name) => Span::styled(
+                    format!("[{}] ", capitalize_first(name)),
+                    Style::default().fg(agent_accent_color(name)).add_modifier(Modifier::BOLD),
+                ),
+            };
+            let error_label = accessible
+                && unified.kind == MessageKind::Error
+                && !unified.text.starts_with("Error");
+
+            let text_style = if unified.kind == MessageKind::Error {
+                Style::default().fg(Color::Red)
+            } else if unified.kind == MessageKind::Warning {
+                Style::default().fg(Color::Yellow)
+            } else if unified.kind == MessageKind::Echo {
+                Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)
+            } else if unified.text.starts_with('>') {
+                Style::default().fg(GLOBAL_CONFIG.tui.user_message_color).add_modifier(Modifier::BOLD)
             } else {
-                Line::from(unified.text.clone())
+                Style::default()
             };
-            lines.push(content);
+
+            for (idx, line_text) in unified.text.split('\n').enumerate() {
+                let mut spans = Vec::new();
+                if idx == 0 {
+                    spans.push(prefix.clone());
+                    if error_label {
+                        spans.push(Span::styled("Error: ", text_style));
+                    }
+                }
+                spans.push(Span::styled(line_text, text_style));
+                lines.push(self.with_links(Line::from(spans)));
+            }
         }
         lines
     }
@@ -579,30 +2158,109 @@ impl ShadowApp {
     /// None
     ///
     /// **Returns:**
-    /// `Vec<Line>` - Vector of styled lines for the current agent's messages
+    /// `(Vec<Line>, Vec<usize>)` - Styled lines for the current agent's messages, and the
+    /// indexes of the lines matching the pane's `/` search
     ///
     /// **Details:**
-    /// User messages (starting with '>') are styled in light yellow and bold
-    fn pan_messages(&self) -> Vec<Line<'_>> {
+    /// - User messages (starting with '>') are styled in light yellow and bold
+    /// - Nudges are dim italic, like echoed commands
+    /// - Assistant messages go through `parse_message_lines`: fenced code is cyan on a dark
+    ///   background, with Rust blocks syntax-highlighted over it when enabled
+    /// - Accessible mode labels each message "You:" / "Nudge:" / "<Agent>:" instead of relying on color
+    /// - With `show_timestamps`, each message's first line starts with a dim `[HH:MM:SS]`
+    /// - With a search active, matches are highlighted last, over all other styling
+    fn pan_messages(&self) -> (Vec<Line<'_>>, Vec<usize>) {
+        let mut lines = self.pane_lines();
+        let hits = match self.current_pane().and_then(|pane| pane.search_query.as_deref()) {
+            Some(query) if !query.is_empty() => {
+                search::annotate(&mut lines, query, self.current_pane().and_then(|pane| pane.search_current))
+            }
+            _ => Vec::new(),
+        };
+        (lines, hits)
+    }
+
+    /// The current agent's messages as styled lines (see `pan_messages`)
+    fn pane_lines(&self) -> Vec<Line<'_>> {
+        self.agent_manager.current_pane()
+            .map(|agent| self.agent_lines(agent))
+            .unwrap_or_default()
+    }
+
+    /// One agent's messages as styled lines, without search highlighting (see `pan_messages`)
+    fn agent_lines<'a>(&'a self, agent: &'a AgentInfo) -> Vec<Line<'a>> {
         let mut lines: Vec<Line> = Vec::new();
-        if let Some(agent) = self.agent_manager.current_pane() {
-            for msg in &agent.messages {
-                for line_text in msg.split('\n') {
-                    let content = if msg.starts_with('>') {
-                        Line::from(Span::styled(
-                            line_text,
-                            Style::default().fg(GLOBAL_CONFIG.tui.user_message_color).add_modifier(Modifier::BOLD),
-                        ))
-                    } else {
-                        Line::from(line_text)
-                    };
-                    lines.push(content);
+        // Where each message's lines start, for its timestamp
+        let mut starts = Vec::with_capacity(agent.messages.len());
+
+        if accessibility::is_enabled() {
+            let agent_label = format!("{}: ", capitalize_first(&agent.persona_name));
+            for message in &agent.messages {
+                starts.push((lines.len(), message.timestamp));
+                lines.extend(accessible_lines(&message.text, &agent_label));
+            }
+            if self.show_timestamps {
+                add_timestamps(&mut lines, &starts);
+            }
+            return lines;
+        }
+
+        let mut cache = self.highlight_cache.borrow_mut();
+        for message in &agent.messages {
+            starts.push((lines.len(), message.timestamp));
+            let msg = message.text.as_str();
+            let nudge = msg.starts_with(NUDGE_PANE_PREFIX);
+            if msg.starts_with('>') || nudge {
+                let style = if nudge {
+                    Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)
+                } else {
+                    Style::default().fg(GLOBAL_CONFIG.tui.user_message_color).add_modifier(Modifier::BOLD)
+                };
+                lines.extend(msg.split('\n').map(|line_text| self.with_links(Line::from(Span::styled(line_text, style)))));
+                continue;
+            }
+            let blocks = parse_code_blocks(msg);
+
+            // Display-only labels for untagged code fences; the message itself is untouched
+            let detected: HashMap<usize, Language> = blocks.iter()
+                .filter_map(|block| block.detected.map(|language| (block.open_line, language)))
+                .collect();
+
+            // Highlighted Rust block lines, by line index within the message
+            let mut highlighted: HashMap<usize, Line<'static>> = HashMap::new();
+            if GLOBAL_CONFIG.tui.syntax_highlighting {
+                for block in blocks.iter().filter(|block| block.is_rust()) {
+                    let block_lines = cache.lines(&block.content, block.closed);
+                    highlighted.extend(block_lines.into_iter().enumerate()
+                        .map(|(offset, line)| (block.open_line + 1 + offset, line)));
+                }
+            }
+
+            for (index, mut content) in parse_message_lines(msg).into_iter().enumerate() {
+                if let Some(line) = highlighted.remove(&index) {
+                    content = line.patch_style(Style::default().bg(CODE_BLOCK_BG));
+                } else if let Some(language) = detected.get(&index) {
+                    content.spans.push(Span::styled(format!(" (detected: {})", language), Style::default().fg(Color::DarkGray)));
+                } else if !content.spans.iter().any(|span| span.style.bg == Some(CODE_BLOCK_BG)) {
+                    content = self.with_links(content);
                 }
+                lines.push(content);
             }
         }
+        if self.show_timestamps {
+            add_timestamps(&mut lines, &starts);
+        }
         lines
     }
     
+    /// URLs and markdown links in `line` made clickable, when terminal hyperlinks are on
+    fn with_links<'a>(&self, line: Line<'a>) -> Line<'a> {
+        if !hyperlinks::is_enabled() || accessibility::is_enabled() {
+            return line;
+        }
+        linkify(line, &mut self.link_table.borrow_mut())
+    }
+
     /// # render_input
     ///
     /// **Purpose:**
@@ -616,11 +2274,13 @@ impl ShadowApp {
     /// None (renders directly to frame)
     ///
     /// **Details:**
-    /// Shows "Shadow is thinking..." animation when waiting, otherwise shows wrapped input text
+    /// - Shows "<Agent> is thinking..." when the current agent is waiting, otherwise wrapped input text
+    /// - Busy background agents only show up in the block title, so typing stays available
     fn render_input(&self, frame: &mut Frame<'_>, area: Rect) {
-        let is_waiting = self.agent_manager.current_pane()
-            .map(|a| a.is_waiting)
-            .unwrap_or(false);
+        let is_waiting = self.current_agent_waiting();
+        let agent_name = self.agent_manager.current_pane()
+            .map(|a| capitalize_first(&a.persona_name))
+            .unwrap_or_default();
 
         let dots = match self.current_pane()
             .map(|p| p.thinking_animation_frame)
@@ -633,11 +2293,13 @@ impl ShadowApp {
                 _ => "   ",
             };
 
-        let input_text = if is_waiting {
+        let input_text = if is_waiting && accessibility::is_enabled() {
+            Text::from(format!("Waiting for reply from {}", agent_name))
+        } else if is_waiting {
             Text::from(vec![
                 Line::from(vec![
                     Span::styled(" > ", Style::default().fg(GLOBAL_CONFIG.tui.border_color).add_modifier(Modifier::BOLD)),
-                    Span::styled(format!("Shadow is thinking{}", dots), Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)),
+                    Span::styled(format!("{} is thinking{}", agent_name, dots), Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)),
                 ])
             ])
         } else {
@@ -658,10 +2320,10 @@ impl ShadowApp {
                     if idx == 0 {
                         Line::from(vec![
                             Span::styled(" > ", Style::default().fg(GLOBAL_CONFIG.tui.user_message_color)),
-                            Span::raw(line.to_string()),
+                            Span::raw(line.text.clone()),
                         ])
                     } else {
-                        Line::from(format!("   {}", line))
+                        Line::from(format!("   {}", line.text))
                     }
                 })
                 .collect();
@@ -674,11 +2336,18 @@ impl ShadowApp {
                 Block::default()
                     .borders(Borders::ALL)
                     .border_style(Style::default().fg(GLOBAL_CONFIG.tui.border_color))
-                    .title(" Input "),
+                    .title(Self::input_title(
+                        self.other_agents_working(),
+                        self.agent_manager.jobs.len(),
+                        persistence::warning(),
+                    )),
             )
             .style(Style::default().fg(Color::White));
 
         frame.render_widget(input_widget, area);
+        if self.vim.enabled {
+            render_mode_line(frame, area, self.input_mode, self.vim.pending());
+        }
     }
     
     /// # wrap_input_text
@@ -690,152 +2359,1008 @@ impl ShadowApp {
     /// - `width`: Maximum line width in characters
     ///
     /// **Returns:**
-    /// Vector of wrapped lines
-    fn wrap_input_text(&self, width: usize) -> Vec<String> {
-        if self.input.is_empty() {
-            return vec![String::new()];
-        }
-
-        let mut lines = Vec::new();
-        let mut current_line = String::new();
-
-        for word in self.input.split_inclusive(|c: char| c.is_whitespace()) {
-            if word.contains('\n') {
-                let parts: Vec<&str> = word.split('\n').collect();
-                for (i, part) in parts.iter().enumerate() {
-                    if i > 0 {
-                        lines.push(current_line.clone());
-                        current_line.clear();
-                    }
-                    if !part.is_empty() {
-                        let test_len = current_line.len() + part.len();
-                        if test_len > width && !current_line.is_empty() {
-                            lines.push(current_line.clone());
-                            current_line = part.to_string();
-                        } else {
-                            current_line.push_str(part);
-                        }
-                    }
-                }
-                continue;
-            }
-
-            let test_len = current_line.len() + word.len();
-
-            if test_len > width && !current_line.is_empty() {
-                lines.push(current_line.trim_end().to_string());
-                current_line = word.to_string();
-            } else {
-                current_line.push_str(word);
-            }
-        }
-
-        if !current_line.is_empty() {
-            lines.push(current_line);
-        }
-
-        if lines.is_empty() {
-            vec![String::new()]
-        } else {
-            lines
+    /// Vector of wrapped lines, one per display row (explicit newlines included);
+    /// just the summary line while the input is collapsed
+    fn wrap_input_text(&self, width: usize) -> Vec<InputLine> {
+        if self.input_collapsed && !self.input.is_empty() {
+            return vec![InputLine { start: 0, text: collapsed_input_summary(&self.input) }];
         }
+        wrap_input(&self.input, width)
     }
 
+    /// # draw
+    ///
+    /// **Purpose:**
+    /// Renders the whole TUI for one frame.
+    ///
+    /// **Details:**
+    /// - Layout and cursor math come from the pure functions in `tui::layout`
+    /// - Falls back to a placeholder when the terminal is too small for the split layout
+    /// - Links are taken out of the finished frame into `links`, for the main loop to write
     pub fn draw(&mut self, frame: &mut Frame<'_>) {
+        self.link_table.get_mut().clear();
+        self.draw_frame(frame);
+        self.links = take_links(frame.buffer_mut(), self.link_table.get_mut());
+    }
+
+    /// Everything `draw` renders
+    fn draw_frame(&mut self, frame: &mut Frame<'_>) {
 
         let input_height = self.calculate_input_height(frame.area().width);
+        let plan = compute_layout(
+            frame.area(),
+            input_height,
+            self.layout_mode,
+            GLOBAL_CONFIG.tui.single_pane_below_width,
+            self.split_ratio,
+        );
+        self.single_pane = plan.single;
+        if !plan.single {
+            self.global_overlay = false;
+            self.unread_global = 0;
+        }
 
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Min(0),
-                Constraint::Length(input_height),
-            ])
-            .split(frame.area());
-        let message_area = chunks[0];
-        let split = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(70),
-                Constraint::Percentage(30),
-            ])
-            .split(message_area);
+        if plan.too_small {
+            let placeholder = Paragraph::new("Terminal too small")
+                .style(Style::default().fg(Color::DarkGray));
+            frame.render_widget(placeholder, frame.area());
+            self.pane_rects.clear();
+            self.tab_rects.clear();
+            return;
+        }
 
-        // Setup input area
-        let input_area = chunks[1];
+        let input_area = plan.input_area;
+        let mut pane_rects = HashMap::new();
+        let mut tab_rects = Vec::new();
     
         self.render_input(frame, input_area);
     
 
-        // Gather messages from each pane and unified messages
-        let pane_lines = self.pan_messages();
+        let gauge = if GLOBAL_CONFIG.tui.context_gauge.enabled { self.context_usage() } else { None };
+        // Gather messages from each pane and unified messages; with no agents, a getting-started banner
+        let (pane_lines, search_hits) = if self.agent_manager.agents.is_empty() {
+            (getting_started_lines(), Vec::new())
+        } else {
+            self.pan_messages()
+        };
         let unified_lines = self.unified_messages();
         let mut global_scroll = self.scroll;
         let mut agent_scroll = self.current_pane()
-                .map(|p| if p.auto_scroll { u16::MAX } else { p.scroll })
+                .map(|p| if p.auto_scroll && !p.muted { u16::MAX } else { p.scroll })
                 .unwrap_or(0);
 
-        render_message_section(
-            frame,
-            split[1],
-            unified_lines,
-            &capitalize_first("System"),
-            &mut global_scroll,
-        );
+        let mut global_title = match &self.global_filter {
+            Some(filter) => format!("System [filter: {}]", filter.label()),
+            None => capitalize_first("System"),
+        };
+        if let Some(timer) = &self.focus {
+            global_title.push_str(&format!(" [focus: {}]", timer.block.countdown()));
+        }
+        if !plan.single {
+            render_message_section(
+                frame,
+                plan.global_area,
+                unified_lines.clone(),
+                &global_title,
+                &mut global_scroll,
+                None,
+            );
+            pane_rects.insert(GLOBAL_PANE, plan.global_area);
+        }
 
-        let agent_name = self.get_agent_name(
-            self.agent_manager.current_agent
-                .unwrap_or(Uuid::nil())
-        );
-        let is_at_bottom = render_message_section(
+        // One row of tabs above the agent pane, when there are agents and room for it
+        let mut agent_area = plan.agent_area;
+        if !self.agent_manager.agents.is_empty() && agent_area.height > 3 {
+            let tab_area = Rect { height: 1, ..agent_area };
+            agent_area = Rect { y: agent_area.y + 1, height: agent_area.height - 1, ..agent_area };
+            tab_rects = render_tab_bar(
+                frame,
+                tab_area,
+                &self.agent_manager.agents,
+                &self.agent_manager.agent_order,
+                self.agent_manager.current_agent,
+            );
+
+            if GLOBAL_CONFIG.tui.usage_status_bar && agent_area.height > 3 {
+                let status_area = Rect { height: 1, ..agent_area };
+                agent_area = Rect { y: agent_area.y + 1, height: agent_area.height - 1, ..agent_area };
+                render_status_bar(frame, status_area, self.current_pane().and_then(|pane| pane.last_usage));
+            }
+        }
+
+        // Stacked: one pane per agent, top to bottom; the current one is drawn below like any other layout's
+        let mut stacked_scrolls = Vec::new();
+        if self.layout_mode == LayoutMode::Stacked
+            && let Some(current) = self.agent_manager.current_agent
+            && self.agent_manager.agent_order.len() > 1
+        {
+            let order = &self.agent_manager.agent_order;
+            let position = order.iter().position(|id| *id == current).unwrap_or(0);
+            let shown = &order[stacked_window(order.len(), position)];
+            for (&id, area) in shown.iter().zip(stacked_areas(agent_area, shown.len())) {
+                if id == current {
+                    agent_area = area;
+                    continue;
+                }
+                let (Some(agent), Some(pane)) = (self.agent_manager.agents.get(&id), self.agent_panes.get(&id)) else {
+                    continue;
+                };
+                let mut scroll = if pane.auto_scroll && !pane.muted { u16::MAX } else { pane.scroll };
+                let title = format!("{}{}", capitalize_first(&self.get_agent_name(id)), pane.title_suffix());
+                let at_bottom = render_message_section(
+                    frame,
+                    area,
+                    self.agent_lines(agent),
+                    &title,
+                    &mut scroll,
+                    Some(pane.border_color),
+                );
+                stacked_scrolls.push((id, scroll, at_bottom));
+                pane_rects.insert(id, area);
+            }
+        }
+
+        let agent_name = if self.agent_manager.agents.is_empty() {
+            "getting started".to_string()
+        } else {
+            self.get_agent_name(self.agent_manager.current_agent.unwrap_or(Uuid::nil()))
+        };
+
+        // A search step scrolls its match to a third of the way down the pane
+        let search_jumped = self.current_pane()
+            .filter(|pane| pane.search_jump)
+            .and_then(|pane| {
+                let last = search_hits.len().checked_sub(1)?;
+                Some(search_hits[pane.search_current.unwrap_or(last).min(last)])
+            });
+        if let Some(line) = search_jumped {
+            let content_width = agent_area.width.saturating_sub(2 + u16::from(gauge.is_some()));
+            let above = wrapped_line_count(&pane_lines[..line], content_width as usize);
+            agent_scroll = above.saturating_sub(agent_area.height.saturating_sub(2) / 3);
+        }
+        let is_at_bottom = render_message_section_with_gauge(
             frame,
-            split[0],
+            agent_area,
             pane_lines,
-            &capitalize_first(&agent_name),
+            &format!(
+                "{}{}{}{}{}",
+                capitalize_first(&agent_name),
+                self.current_pane().map(|p| p.title_suffix()).unwrap_or(""),
+                self.current_pane().map(|p| p.search_label(search_hits.len())).unwrap_or_default(),
+                if self.agent_manager.current_pane().is_some_and(|a| a.persona_issue.is_some()) {
+                    glyph(" ⚠", " (persona problem)")
+                } else {
+                    ""
+                },
+                if self.unread_global > 0 {
+                    format!(" [{} new in System, Ctrl+G]", self.unread_global)
+                } else {
+                    String::new()
+                },
+            ),
             &mut agent_scroll,
+            self.current_pane().map(|pane| pane.border_color),
+            gauge,
         );
+        if let Some(id) = self.agent_manager.current_agent {
+            pane_rects.insert(id, agent_area);
+        }
+
+        if plan.single && self.global_overlay {
+            let overlay = overlay_area(plan.agent_area);
+            frame.render_widget(Clear, overlay);
+            let mut overlay_scroll = if self.overlay_follow { u16::MAX } else { self.overlay_scroll };
+            let at_bottom = render_message_section(
+                frame,
+                overlay,
+                unified_lines,
+                &format!("{} (Ctrl+G to close)", global_title),
+                &mut overlay_scroll,
+                None,
+            );
+            self.overlay_scroll = overlay_scroll;
+            self.overlay_follow = at_bottom;
+            pane_rects.clear();
+            pane_rects.insert(GLOBAL_PANE, plan.agent_area);
+        }
+
+        if let Some(view) = self.inspect.as_mut() {
+            frame.render_widget(Clear, plan.agent_area);
+            let title = format!(
+                "Inspect {} (↑↓ select · Enter fold · c copy · PgUp/PgDn scroll · Esc close)",
+                capitalize_first(&view.persona)
+            );
+            render_message_section(frame, plan.agent_area, view.lines(), &title, &mut view.scroll, None);
+        }
+
+        if let Some(step) = self.tour.as_ref().and_then(Tour::current).filter(|_| !accessibility::is_enabled()) {
+            let (index, count) = self.tour.as_ref().map(Tour::position).unwrap_or_default();
+            let width = tour::hint_width(tour::anchor_region(step.anchor, &plan).width);
+            let lines: Vec<Line> = tour::wrap_words(step.text, width.saturating_sub(2) as usize)
+                .into_iter()
+                .map(Line::from)
+                .collect();
+            let area = tour::hint_area(step.anchor, &plan, width, lines.len() as u16 + 2);
+            let hint = Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Yellow))
+                    .title(format!(" Tour {}/{} ", index, count))
+                    .title_bottom(" Ctrl+N skip · Esc close "),
+            );
+            frame.render_widget(Clear, area);
+            frame.render_widget(hint, area);
+        }
 
+        if let Some(items) = self.completion_popup.as_ref().filter(|_| !self.current_agent_waiting()) {
+            let area = completion_area(input_area, items);
+            let list = List::new(items.iter().map(|name| ListItem::new(name.as_str())))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(GLOBAL_CONFIG.tui.border_color))
+                        .title_bottom(" Tab "),
+                )
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+            let mut state = ListState::default().with_selected(self.completion_selected);
+            frame.render_widget(Clear, area);
+            frame.render_stateful_widget(list, area, &mut state);
+        }
+
+        self.scroll = global_scroll;
+        self.pane_rects = pane_rects;
+        self.tab_rects = tab_rects;
         if let Some(pane) = self.current_pane_mut() {
             pane.scroll = agent_scroll;
             
            pane.auto_scroll = is_at_bottom;
+           pane.search_hits = search_hits.len();
+           pane.search_jump = false;
+        }
+        for (id, scroll, at_bottom) in stacked_scrolls {
+            if let Some(pane) = self.agent_panes.get_mut(&id) {
+                pane.scroll = scroll;
+                pane.auto_scroll = at_bottom;
+            }
         }
 
-        let is_waiting = self.agent_manager.current_pane()
-            .map(|a| a.is_waiting)
-            .unwrap_or(false);
-        
-        if input_area.height > 2 && input_area.width > 6 && !is_waiting {
-            let width = input_area.width.saturating_sub(6) as usize;
-            let wrapped = self.wrap_input_text(width);
-
-            let mut chars_counted = 0;
-            let mut cursor_line = 0;
-            let mut cursor_col_in_line = 0;
-
-            for (line_idx, line) in wrapped.iter().enumerate() {
-                let line_len = line.len();
-                if chars_counted + line_len >= self.input.len() {
-                    cursor_line = line_idx;
-                    cursor_col_in_line = self.input.len() - chars_counted;
-                    break;
-                }
-                chars_counted += line_len;
+        if !self.current_agent_waiting() {
+            let wrapped = self.wrap_input_text(input_area.width.saturating_sub(6) as usize);
+            if let Some(cursor_pos) = compute_cursor(input_area, &wrapped, self.cursor(), self.input_scroll) {
+                frame.set_cursor_position(cursor_pos);
             }
+        }
+    }
 
-            if cursor_line >= self.input_scroll {
-                let visible_line = cursor_line - self.input_scroll;
-                let max_visible = input_area.height.saturating_sub(2) as usize;
+}
 
-                if visible_line < max_visible {
-                    let cursor_pos = Position {
-                        x: input_area.x + 3 + cursor_col_in_line as u16,
-                        y: input_area.y + 1 + visible_line as u16,
-                    };
-                    frame.set_cursor_position(cursor_pos);
-                }
+/// # getting_started_lines
+///
+/// **Purpose:**
+/// What the agent pane shows while no agent is open.
+fn getting_started_lines() -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::styled(NO_AGENT_GUIDANCE, Style::default().fg(Color::Yellow)),
+        Line::default(),
+    ];
+    lines.extend(HELP_TEXT.lines().map(|line| Line::from(line.to_string())));
+    lines
+}
+
+/// # completion_area
+///
+/// **Purpose:**
+/// Area for the command popup: just above the input box, lined up with the typed text.
+fn completion_area(input_area: Rect, items: &[String]) -> Rect {
+    let widest = items.iter().map(|name| name.chars().count()).max().unwrap_or(0) as u16;
+    let height = items.len() as u16 + 2;
+    Rect {
+        x: input_area.x + 2,
+        y: input_area.y.saturating_sub(height),
+        width: (widest + 4).max(8).min(input_area.width.saturating_sub(2)),
+        height,
+    }
+}
+
+/// # overlay_area
+///
+/// **Purpose:**
+/// Area for the Global overlay: the lower two thirds of the agent pane, inset by one column.
+fn overlay_area(agent_area: Rect) -> Rect {
+    let height = (agent_area.height * 2 / 3).max(3).min(agent_area.height);
+    Rect {
+        x: agent_area.x + 1,
+        y: agent_area.y + agent_area.height - height,
+        width: agent_area.width.saturating_sub(2),
+        height,
+    }
+}
+
+/// # accessible_lines
+///
+/// **Purpose:**
+/// Builds the lines for one agent-pane message with an explicit role label.
+///
+/// **Parameters:**
+/// - `msg`: Stored message ("> ..." for the user, reply text otherwise)
+/// - `agent_label`: Label for replies, e.g. "Shadow: "
+///
+/// **Details:**
+/// Only the first line is labeled; messages already starting with "Error" keep their own label.
+fn accessible_lines<'a>(msg: &'a str, agent_label: &str) -> Vec<Line<'a>> {
+    let (label, body) = match msg.strip_prefix("> ") {
+        Some(body) => ("You: ".to_string(), body),
+        None if msg.starts_with(NUDGE_PANE_PREFIX) => ("Nudge: ".to_string(), &msg[NUDGE_PANE_PREFIX.len()..]),
+        None if msg.starts_with("Error") => (String::new(), msg),
+        None => (agent_label.to_string(), msg),
+    };
+
+    body.split('\n')
+        .enumerate()
+        .map(|(idx, line_text)| {
+            if idx == 0 {
+                Line::from(vec![Span::raw(label.clone()), Span::raw(line_text)])
+            } else {
+                Line::from(line_text)
+            }
+        })
+        .collect()
+}
+
+/// Puts a dim `[HH:MM:SS]` in front of the first line of each message (`starts`: first line index and time)
+fn add_timestamps(lines: &mut [Line<'_>], starts: &[(usize, SystemTime)]) {
+    for (start, timestamp) in starts {
+        if let Some(line) = lines.get_mut(*start) {
+            let stamp = format!("[{}] ", format_clock(*timestamp, Zone::Local));
+            line.spans.insert(0, Span::styled(stamp, Style::default().fg(Color::DarkGray)));
+        }
+    }
+}
+
+/// # normalize_newlines
+///
+/// **Purpose:**
+/// Converts CRLF and lone CR line endings to LF.
+fn normalize_newlines(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// # collapsed_input_summary
+///
+/// **Purpose:**
+/// The one line the input box shows in place of a large paste.
+fn collapsed_input_summary(input: &str) -> String {
+    format!("[pasted text: {} lines, {} chars]", input.lines().count(), input.chars().count())
+}
+
+/// # split_paste_burst
+///
+/// **Purpose:**
+/// Splits a rapid burst of key presses into pasted text and the Enters that end it.
+///
+/// **Returns:**
+/// `Option<(String, &[KeyEvent])>` - The text and the trailing Enters, if at least
+/// `PASTE_BURST_MIN_KEYS` keys come before those Enters and all of them are plain
+/// characters or Enters
+fn split_paste_burst(keys: &[KeyEvent]) -> Option<(String, &[KeyEvent])> {
+    let body_len = keys.iter().rposition(|key| key.code != KeyCode::Enter).map_or(0, |last| last + 1);
+    if body_len < PASTE_BURST_MIN_KEYS {
+        return None;
+    }
+
+    let (body, trailing) = keys.split_at(body_len);
+    let text = body.iter()
+        .map(|key| {
+            if key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+                return None;
+            }
+            match key.code {
+                KeyCode::Char(c) => Some(c),
+                KeyCode::Enter => Some('\n'),
+                _ => None,
+            }
+        })
+        .collect::<Option<String>>()?;
+    Some((text, trailing))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::AnyClient;
+    use crate::llm::scripted::{ScriptedClient, ScriptedReply};
+    use crate::persona::builtin::load_builtin;
+    use crate::persona::chunk_stream::{ChunkStream, TaskKind};
+    use crate::persona::operations::AgentOperations;
+    use crate::test_support::test_personas_root;
+
+    /// A fresh app; its saved layout and state are read from the test personas root
+    fn test_app() -> ShadowApp {
+        test_personas_root();
+        ShadowApp::new()
+    }
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn typed(text: &str) -> Vec<KeyEvent> {
+        text.chars()
+            .map(|c| press(if c == '\n' { KeyCode::Enter } else { KeyCode::Char(c) }))
+            .collect()
+    }
+
+    fn app_with_input(input: &str, cursor: usize) -> ShadowApp {
+        let mut app = test_app();
+        app.input = input.to_string();
+        app.input_cursor = cursor;
+        app
+    }
+
+    /// An app that parses what's typed, with no agents open
+    fn app_with_parser() -> ShadowApp {
+        let mut app = test_app();
+        app.agent_manager.user_input = Some(UserInput::new_for_tui());
+        app
+    }
+
+    fn enter(app: &mut ShadowApp, line: &str) {
+        app.set_input(line.to_string());
+        app.handle_key(press(KeyCode::Enter));
+    }
+
+    /// An app with one agent open whose replies come from `client`
+    fn app_with_scripted_agent(client: &ScriptedClient) -> ShadowApp {
+        let mut app = app_with_parser();
+        app.agent_manager.client_override = Some(AnyClient::Scripted(client.clone()));
+        let mut persona = load_builtin("shadow").expect("built-in shadow");
+        persona.enable_history = false;
+        app.add_agent(Uuid::new_v4(), Arc::new(persona));
+        app
+    }
+
+    /// Polls the app until the current agent's reply has finished
+    async fn settle(app: &mut ShadowApp) {
+        for _ in 0..400 {
+            app.poll_channels();
+            if !app.current_agent_waiting() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!("the reply never finished");
+    }
+
+    /// The last user message of each request the client was sent
+    fn sent_messages(client: &ScriptedClient) -> Vec<String> {
+        client.requests().iter()
+            .filter_map(|request| request.input.iter().rev().find(|msg| msg.role == "user"))
+            .map(|msg| msg.content.clone())
+            .collect()
+    }
+
+    fn echoes(app: &ShadowApp) -> Vec<&str> {
+        app.unified_messages.iter()
+            .filter(|msg| msg.kind == MessageKind::Echo)
+            .map(|msg| msg.text.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn crlf_and_lone_cr_become_lf() {
+        assert_eq!(normalize_newlines("a\r\nb\rc\nd"), "a\nb\nc\nd");
+    }
+
+    #[test]
+    fn pasted_text_goes_in_at_the_cursor() {
+        let mut app = app_with_input("hello world", 5);
+        app.insert_text(",\r\nbig");
+        assert_eq!(app.input, "hello,\nbig world");
+        assert_eq!(app.input_cursor, "hello,\nbig".len());
+    }
+
+    #[test]
+    fn cursor_inside_a_character_inserts_before_it() {
+        let mut app = app_with_input("héllo", 2);
+        app.insert_text("X");
+        assert_eq!(app.input, "hXéllo");
+    }
+
+    #[test]
+    fn large_pastes_collapse_until_the_input_is_replaced() {
+        let mut app = test_app();
+        let paste = "line\n".repeat(COLLAPSE_PASTE_LINES + 5);
+        app.insert_text(&paste);
+        assert!(app.input_collapsed);
+        assert_eq!(app.wrap_input_text(80).len(), 1);
+        assert!(app.wrap_input_text(80)[0].text.starts_with("[pasted text: 25 lines"));
+
+        // Typing more keeps it collapsed; a small paste into an empty input doesn't
+        app.insert_text("more");
+        assert!(app.input_collapsed);
+        app.set_input(String::new());
+        app.insert_text("short");
+        assert!(!app.input_collapsed);
+        assert_eq!(app.wrap_input_text(80)[0].text, "short");
+    }
+
+    #[test]
+    fn a_long_burst_is_read_as_one_paste() {
+        let text = "fn main() {\n    println!(\"pasted\");\n}";
+        let keys = typed(text);
+        let (pasted, trailing) = split_paste_burst(&keys).unwrap();
+        assert_eq!(pasted, text);
+        assert!(trailing.is_empty());
+    }
+
+    #[test]
+    fn trailing_enters_are_not_part_of_the_paste() {
+        let keys = typed(&format!("{}\n\n", "x".repeat(PASTE_BURST_MIN_KEYS)));
+        let (pasted, trailing) = split_paste_burst(&keys).unwrap();
+        assert_eq!(pasted, "x".repeat(PASTE_BURST_MIN_KEYS));
+        assert_eq!(trailing.len(), 2);
+    }
+
+    #[test]
+    fn short_or_mixed_bursts_are_keys() {
+        assert!(split_paste_burst(&typed("ok\n")).is_none());
+        assert!(split_paste_burst(&typed(&"y".repeat(PASTE_BURST_MIN_KEYS - 1))).is_none());
+
+        let mut keys = typed(&"z".repeat(PASTE_BURST_MIN_KEYS));
+        keys.push(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL));
+        assert!(split_paste_burst(&keys).is_none());
+        keys.pop();
+        keys.insert(3, press(KeyCode::Tab));
+        assert!(split_paste_burst(&keys).is_none());
+    }
+
+    #[test]
+    fn queued_keys_are_typed_not_pasted() {
+        let mut app = test_app();
+        app.paste_fallback = true;
+        assert!(app.handle_key_burst(typed("ok\n")));
+        // Enter submitted the line instead of adding a newline
+        assert!(app.input.is_empty());
+    }
+
+    #[test]
+    fn fallback_paste_inserts_text_then_sends_on_a_trailing_enter() {
+        let line = "a".repeat(PASTE_BURST_MIN_KEYS);
+        let mut app = test_app();
+        app.paste_fallback = true;
+        assert!(app.handle_key_burst(typed(&format!("{}\n{}", line, line))));
+        assert_eq!(app.input, format!("{}\n{}", line, line));
+
+        let mut app = test_app();
+        app.paste_fallback = true;
+        assert!(app.handle_key_burst(typed(&format!("{}\n", line))));
+        assert!(app.input.is_empty());
+    }
+
+    #[test]
+    fn without_the_fallback_bursts_are_handled_key_by_key() {
+        let mut app = test_app();
+        assert!(app.handle_key_burst(typed(&format!("{}\nb", "a".repeat(PASTE_BURST_MIN_KEYS)))));
+        assert_eq!(app.input, "b");
+    }
+
+    #[test]
+    fn typing_a_word_never_opens_the_popup() {
+        let mut app = test_app();
+        for key in typed("hi") {
+            app.handle_key(key);
+        }
+        assert!(app.completion_popup.is_none());
+        assert_eq!(app.input, "hi");
+    }
+
+    #[test]
+    fn tab_opens_the_popup_when_several_commands_match() {
+        let mut app = app_with_input("hi", 2);
+        app.handle_key(press(KeyCode::Tab));
+        assert_eq!(app.completion_popup, Some(vec!["historyinfo".to_string(), "history".to_string()]));
+
+        app.handle_key(press(KeyCode::Char('s')));
+        assert_eq!(app.completion_popup, Some(vec!["historyinfo".to_string(), "history".to_string()]));
+        app.handle_key(press(KeyCode::Down));
+        app.handle_key(press(KeyCode::Down));
+        app.handle_key(press(KeyCode::Enter));
+        assert_eq!(app.input, "history ");
+        assert!(app.completion_popup.is_none());
+    }
+
+    #[test]
+    fn tab_fills_in_a_single_match() {
+        let mut app = app_with_input("twe", 3);
+        app.handle_key(press(KeyCode::Tab));
+        assert_eq!(app.input, "tweet ");
+        assert!(app.completion_popup.is_none());
+    }
+
+    #[test]
+    fn tab_leaves_other_words_alone() {
+        for input in ["hello", "status", "new sh", ""] {
+            let mut app = app_with_input(input, input.len());
+            app.handle_key(press(KeyCode::Tab));
+            assert_eq!(app.input, input);
+            assert!(app.completion_popup.is_none(), "popup opened for {:?}", input);
+        }
+    }
+
+    #[test]
+    fn the_popup_closes_when_nothing_matches_any_more() {
+        let mut app = app_with_input("s", 1);
+        app.handle_key(press(KeyCode::Tab));
+        assert_eq!(app.completion_popup.as_ref().map(Vec::len), Some(COMPLETION_LIMIT));
+
+        app.handle_key(press(KeyCode::Char('q')));
+        assert!(app.completion_popup.is_none());
+        app.handle_key(press(KeyCode::Backspace));
+        assert!(app.completion_popup.is_none(), "the popup only reopens with Tab");
+    }
+
+    #[test]
+    fn enter_on_empty_input_repeats_the_last_command() {
+        let mut app = app_with_parser();
+        enter(&mut app, "list");
+        assert_eq!(app.last_command.as_deref(), Some("list"));
+        assert!(echoes(&app).is_empty());
+
+        enter(&mut app, "");
+        enter(&mut app, "");
+        let repeated = echoes(&app);
+        assert_eq!(repeated.len(), 2);
+        assert!(repeated.iter().all(|text| text.ends_with(" list")), "{:?}", repeated);
+    }
+
+    #[test]
+    fn chat_and_destructive_commands_are_never_recorded() {
+        let mut app = app_with_parser();
+        enter(&mut app, "status");
+        for line in ["hello there", "close", "clearhistory", "global clear", "\\!!"] {
+            enter(&mut app, line);
+            assert_eq!(app.last_command.as_deref(), Some("status"), "{:?} replaced the last command", line);
+        }
+
+        assert!(!InputAction::SendAsMessage("status".into()).is_repeatable());
+        assert!(!InputAction::ClearHistory.is_repeatable());
+        assert!(!InputAction::CloseAgent.is_repeatable());
+        assert!(!InputAction::PostTweet("hi".into()).is_repeatable());
+        assert!(InputAction::AgentStatus.is_repeatable());
+    }
+
+    #[test]
+    fn nothing_recorded_yet_only_gives_a_hint() {
+        let mut app = app_with_parser();
+        enter(&mut app, "");
+        assert!(echoes(&app).is_empty());
+        assert!(app.messages.back().is_some_and(|msg| msg.starts_with("Nothing to repeat yet")));
+    }
+
+    #[test]
+    fn repeating_can_be_turned_off() {
+        let mut app = app_with_parser();
+        app.repeat_on_enter = false;
+        enter(&mut app, "list");
+        let shown = app.messages.len();
+
+        enter(&mut app, "");
+        assert!(echoes(&app).is_empty());
+        assert_eq!(app.messages.len(), shown, "Enter on empty input did something");
+    }
+
+    #[tokio::test]
+    async fn a_macro_step_that_needs_the_agent_waits_for_the_reply() {
+        let client = ScriptedClient::default();
+        let (first, gate) = ScriptedReply::chunks(["Morning", ", boss."]).held();
+        client.push(first);
+        client.push(ScriptedReply::text("Noted."));
+        let mut app = app_with_scripted_agent(&client);
+        let steps = ["good morning", "list", "standup notes"].map(String::from);
+        app.macro_replay = Some(MacroReplay::new("standup", &steps));
+
+        app.advance_macro();
+        assert!(app.current_agent_waiting());
+        // `list` doesn't need the agent, so it runs while the reply streams; the next message waits
+        for _ in 0..5 {
+            app.advance_macro();
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(echoes(&app).len(), 2, "{:?}", echoes(&app));
+        assert_eq!(sent_messages(&client), ["good morning"]);
+
+        gate.release();
+        settle(&mut app).await;
+        let pane = app.agent_manager.current_pane().expect("agent open");
+        assert!(pane.messages.iter().any(|msg| msg.text == "Morning, boss."));
+
+        app.advance_macro();
+        settle(&mut app).await;
+        app.advance_macro();
+        assert!(app.macro_replay.is_none());
+        assert_eq!(sent_messages(&client), ["good morning", "standup notes"]);
+        assert_eq!(app.messages.back().map(String::as_str), Some("Macro 'standup' finished."));
+    }
+
+    #[tokio::test]
+    async fn a_recorded_macro_replays_its_lines_in_order() {
+        let client = ScriptedClient::default();
+        client.push(ScriptedReply::text("Hi."));
+        client.push(ScriptedReply::text("Hi again."));
+        let mut app = app_with_scripted_agent(&client);
+        let name = format!("routine{}", std::process::id());
+
+        enter(&mut app, &format!("macro record {}", name));
+        enter(&mut app, "status");
+        enter(&mut app, "hello");
+        settle(&mut app).await;
+        enter(&mut app, "list");
+        enter(&mut app, "macro stop");
+        assert!(app.macro_recording.is_none());
+
+        enter(&mut app, &format!("macro run {}", name));
+        while app.macro_replay.is_some() {
+            app.advance_macro();
+            settle(&mut app).await;
+        }
+        let ran: Vec<&str> = echoes(&app).into_iter()
+            .filter_map(|echo| echo.strip_prefix(glyph("▶", "Macro step:")))
+            .map(str::trim)
+            .collect();
+        assert_eq!(ran, ["status", "hello", "list"]);
+        assert_eq!(sent_messages(&client), ["hello", "hello"]);
+        assert_eq!(client.queued(), 0);
+    }
+
+    fn entry(text: &str, source: MessageSource, kind: MessageKind) -> UnifiedMessage {
+        UnifiedMessage { text: text.to_string(), source, kind, timestamp: SystemTime::now() }
+    }
+
+    /// Source and text of each Global pane entry, newest last
+    fn global_entries(app: &ShadowApp) -> Vec<(MessageSource, &str)> {
+        app.unified_messages.iter().map(|msg| (msg.source.clone(), msg.text.as_str())).collect()
+    }
+
+    #[test]
+    fn global_filters_parse_and_label() {
+        let cases = [
+            ("errors", GlobalFilter::Errors, "errors"),
+            ("ERRORS", GlobalFilter::Errors, "errors"),
+            ("commands", GlobalFilter::Commands, "commands"),
+            ("Shadow", GlobalFilter::Persona("shadow".into()), "Shadow"),
+            ("friday", GlobalFilter::Persona("friday".into()), "Friday"),
+        ];
+        for (arg, filter, label) in cases {
+            assert_eq!(GlobalFilter::parse(arg), filter, "{:?}", arg);
+            assert_eq!(filter.label(), label);
+        }
+    }
+
+    #[test]
+    fn global_filters_match_by_source_and_kind() {
+        let shadow = || MessageSource::Agent("Shadow".into());
+        let cases = [
+            (GlobalFilter::Persona("shadow".into()), entry("saved", shadow(), MessageKind::Command), true),
+            (GlobalFilter::Persona("shadow".into()), entry("failed", shadow(), MessageKind::Error), true),
+            (GlobalFilter::Persona("shadow".into()), entry("saved", MessageSource::Agent("friday".into()), MessageKind::Command), false),
+            (GlobalFilter::Persona("shadow".into()), entry("shadow mentioned", MessageSource::Global, MessageKind::Command), false),
+            (GlobalFilter::Errors, entry("failed", shadow(), MessageKind::Error), true),
+            (GlobalFilter::Errors, entry("Error: looks like one", MessageSource::Global, MessageKind::Command), false),
+            (GlobalFilter::Commands, entry("listed", MessageSource::Global, MessageKind::Command), true),
+            (GlobalFilter::Commands, entry("> status", MessageSource::Global, MessageKind::Echo), true),
+            (GlobalFilter::Commands, entry("careful", shadow(), MessageKind::Warning), true),
+            (GlobalFilter::Commands, entry("failed", MessageSource::Global, MessageKind::Error), false),
+        ];
+        for (filter, msg, expected) in cases {
+            assert_eq!(filter.matches(&msg), expected, "{:?} on {:?} from {:?}", filter, msg.text, msg.source);
+        }
+    }
+
+    #[test]
+    fn command_output_is_attributed_to_the_agent_it_ran_for() {
+        let client = ScriptedClient::default();
+        let mut app = app_with_scripted_agent(&client);
+        let shadow = MessageSource::Agent("shadow".into());
+
+        enter(&mut app, "historyinfo");
+        let (source, text) = global_entries(&app).pop().expect("historyinfo output");
+        assert_eq!(source, shadow);
+        assert!(text.starts_with("History for shadow: 1 messages in memory"), "{}", text);
+        enter(&mut app, "list");
+        assert_eq!(global_entries(&app).last().map(|(source, _)| source), Some(&MessageSource::Global), "not about one agent");
+
+        enter(&mut app, "close");
+        assert_eq!(global_entries(&app).last(), Some(&(shadow, "Closed current agent.")));
+        enter(&mut app, "close");
+        assert_eq!(global_entries(&app).last().map(|(source, _)| source), Some(&MessageSource::Global), "no agent left");
+    }
+
+    #[test]
+    fn the_filter_hides_entries_until_cleared_and_clear_empties_the_pane() {
+        let mut app = app_with_parser();
+        app.clear_global_messages();
+        app.push_global_message("saved", MessageSource::Agent("shadow".into()), MessageKind::Command);
+        app.push_global_message("tweeted", MessageSource::Agent("friday".into()), MessageKind::Command);
+        app.push_global_message("failed", MessageSource::Global, MessageKind::Error);
+        assert_eq!(app.unified_messages().len(), 3);
+
+        enter(&mut app, "global filter shadow");
+        assert_eq!(app.global_filter, Some(GlobalFilter::Persona("shadow".into())));
+        assert_eq!(app.unified_messages().len(), 1, "only shadow's entry, not the notice");
+        enter(&mut app, "global filter errors");
+        assert_eq!(app.unified_messages().len(), 1);
+
+        enter(&mut app, "global filter off");
+        assert_eq!(app.global_filter, None);
+        assert_eq!(global_entries(&app).last(), Some(&(MessageSource::Global, "Global pane filter cleared.")));
+        assert_eq!(app.unified_messages().len(), app.unified_messages.len());
+
+        enter(&mut app, "global clear");
+        assert!(app.unified_messages.is_empty() && app.messages.is_empty());
+    }
+
+    /// An app with `count` agents open on `client`, and their IDs with the current one last
+    fn app_with_agents(client: &ScriptedClient, count: usize) -> (ShadowApp, Vec<Uuid>) {
+        let mut app = app_with_scripted_agent(client);
+        let mut persona = load_builtin("shadow").expect("built-in shadow");
+        persona.enable_history = false;
+        let persona = Arc::new(persona);
+        for _ in 1..count {
+            app.add_agent(Uuid::new_v4(), persona.clone());
+        }
+        let mut ids: Vec<Uuid> = app.agent_manager.agents.keys().copied().collect();
+        let current = app.agent_manager.current_agent.expect("an agent is current");
+        ids.retain(|id| *id != current);
+        ids.push(current);
+        (app, ids)
+    }
+
+    fn set_waiting(app: &mut ShadowApp, id: Uuid, waiting: bool) {
+        app.agent_manager.agents.get_mut(&id).expect("agent open").is_waiting = waiting;
+    }
+
+    #[test]
+    fn the_input_title_notes_what_else_is_working() {
+        let (dot, more, warn) = (glyph(" · ", ", "), glyph("…", ""), glyph("⚠ ", "Warning: "));
+        let cases = [
+            (0, 0, None, " Input ".to_string()),
+            (1, 0, None, format!(" Input{}1 agent working{} ", dot, more)),
+            (3, 0, None, format!(" Input{}3 agents working{} ", dot, more)),
+            (0, 1, None, format!(" Input{}1 job running ", dot)),
+            (2, 2, Some("key expires soon".to_string()), format!(" Input{}2 agents working{}{}2 jobs running{}{}key expires soon ", dot, more, dot, dot, warn)),
+        ];
+        for (others, jobs, warning, expected) in cases {
+            let expected = if strict::is_enabled() { expected.replacen(" Input", &format!(" Input{}STRICT", dot), 1) } else { expected };
+            assert_eq!(ShadowApp::input_title(others, jobs, warning), expected, "{} others, {} jobs", others, jobs);
+        }
+    }
+
+    #[test]
+    fn only_other_waiting_agents_count_as_working_elsewhere() {
+        let client = ScriptedClient::default();
+        let (mut app, ids) = app_with_agents(&client, 3);
+        let (first, second, current) = (ids[0], ids[1], ids[2]);
+        let cases = [
+            (&[][..], false, 0),
+            (&[current][..], true, 0),
+            (&[first][..], false, 1),
+            (&[first, second][..], false, 2),
+            (&[first, second, current][..], true, 2),
+        ];
+        for (waiting, current_busy, others) in cases {
+            for id in [first, second, current] {
+                set_waiting(&mut app, id, waiting.contains(&id));
+            }
+            assert_eq!((app.current_agent_waiting(), app.other_agents_working()), (current_busy, others), "{} waiting", waiting.len());
+        }
+    }
+
+    #[test]
+    fn the_input_height_follows_the_current_pane_only() {
+        let client = ScriptedClient::default();
+        let (mut app, ids) = app_with_agents(&client, 2);
+        let (other, current) = (ids[0], ids[1]);
+        app.input_max_lines = 5;
+
+        app.set_input("one line".to_string());
+        assert_eq!(app.calculate_input_height(80), 3);
+        app.set_input("a\nb\nc".to_string());
+        assert_eq!(app.calculate_input_height(80), 5, "three lines and the borders");
+        app.set_input("x\n".repeat(20));
+        assert_eq!(app.calculate_input_height(80), 7, "capped at input_max_lines");
+        assert_eq!(app.calculate_input_height(6), 3, "no room to wrap into");
+
+        set_waiting(&mut app, other, true);
+        app.set_input("a\nb\nc".to_string());
+        assert_eq!(app.calculate_input_height(80), 5, "a busy background agent leaves the input alone");
+        set_waiting(&mut app, current, true);
+        assert_eq!(app.calculate_input_height(80), 3, "the current agent thinking shrinks it");
+        set_waiting(&mut app, current, false);
+        assert_eq!(app.calculate_input_height(80), 5);
+    }
+
+    /// Polls the app until agent `id` has no request in flight
+    async fn settle_agent(app: &mut ShadowApp, id: Uuid) {
+        for _ in 0..400 {
+            app.poll_channels();
+            if !app.agent_manager.agents[&id].is_waiting {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!("the reply never finished");
+    }
+
+    fn global_texts(app: &ShadowApp) -> Vec<&str> {
+        app.unified_messages.iter().map(|msg| msg.text.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn muting_silences_notifications_but_not_the_agent() {
+        // (muted, reply finishes while its pane is current, a "Reply ready" notice expected)
+        let cases = [(false, false, true), (true, false, false), (false, true, false), (true, true, false)];
+        for (muted, in_view, notice) in cases {
+            let client = ScriptedClient::default();
+            client.push(ScriptedReply::text("Digest done."));
+            let (mut app, ids) = app_with_agents(&client, 2);
+            let (agent, other) = (ids[1], ids[0]);
+            app.set_agent_muted(agent, muted);
+            assert_eq!(app.agent_panes[&agent].title_suffix().is_empty(), !muted);
+
+            enter(&mut app, "make the digest");
+            if !in_view {
+                app.set_current_agent_id(Some(other));
             }
+            let mut stream = ChunkStream::open(&app.agent_manager.agents[&agent].chunk_sender, TaskKind::Reply);
+            stream.info("Indexing 40 notes").unwrap();
+            drop(stream);
+            settle_agent(&mut app, agent).await;
+
+            let context = format!("muted: {}, in view: {}", muted, in_view);
+            let pane = &app.agent_manager.agents[&agent];
+            assert!(pane.messages.iter().any(|msg| msg.text == "Digest done."), "the reply still streams in ({})", context);
+            let global = global_texts(&app);
+            assert_eq!(global.contains(&"Reply ready"), notice, "{}", context);
+            assert_eq!(global.contains(&"Indexing 40 notes"), !muted, "{}", context);
         }
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn mute_and_unmute_show_in_status_and_the_pane_title() {
+        let client = ScriptedClient::default();
+        let mut app = app_with_scripted_agent(&client);
+        let id = app.agent_manager.current_agent.expect("agent open");
+        let label = glyph(" 🔇", " (muted)");
+
+        enter(&mut app, "mute");
+        assert!(app.is_agent_muted(id));
+        assert_eq!(app.agent_panes[&id].title_suffix(), label);
+        assert_eq!(global_texts(&app).last(), Some(&"Shadow muted"));
+        enter(&mut app, "status");
+        assert!(global_texts(&app).iter().any(|text| text.contains(&format!("Shadow{}", label))), "{:?}", global_texts(&app));
+
+        enter(&mut app, "unmute");
+        assert!(!app.is_agent_muted(id));
+        assert_eq!(app.agent_panes[&id].title_suffix(), "");
+        assert_eq!(global_texts(&app).last(), Some(&"Shadow unmuted"));
+    }
+
+    #[test]
+    fn accessible_lines_label_each_message_in_text() {
+        let cases: [(&str, &[&str]); 5] = [
+            ("> what time is it?", &["You: what time is it?"]),
+            ("Noon.", &["Shadow: Noon."]),
+            ("Two lines\nof reply", &["Shadow: Two lines", "of reply"]),
+            ("Error: 503 Service Unavailable", &["Error: 503 Service Unavailable"]),
+            ("[nudge] keep it short", &["Nudge: keep it short"]),
+        ];
+
+        for (msg, expected) in cases {
+            let lines: Vec<String> = accessible_lines(msg, "Shadow: ")
+                .iter()
+                .map(|line| line.spans.iter().map(|span| span.content.as_ref()).collect())
+                .collect();
+            assert_eq!(lines, expected, "{:?}", msg);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! # Daegonica Module: tui::gauge
+//!
+//! **Purpose:** Ambient cue for how full an agent's context is
+//!
+//! **Context:**
+//! - Drawn as a one-column gauge just inside the agent pane's right border, beside the scrollbar
+//! - `inspect` stays the detail view; the gauge only shows fill and color
+//! - Measured from `GrokConversation::context_texts` with the same estimate `inspect` uses
+//!
+//! **Responsibilities:**
+//! - Look up a model's context window (`tui.context_gauge.windows`)
+//! - Turn estimated use into a fill height and a color level
+//! - Cache the estimate per pane until the conversation changes
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use ratatui::style::Color;
+use crate::agent_history::conversations::GrokConversation;
+use crate::config::ContextGaugeConfig;
+use crate::tui::inspect::estimate_tokens;
+
+/// # GaugeLevel
+///
+/// **Summary:**
+/// How worried the gauge's color should make you.
+///
+/// **Variants:**
+/// - `Ok`: Below `warn_ratio`
+/// - `Warn`: From `warn_ratio` up to `danger_ratio`
+/// - `Danger`: At or above `danger_ratio`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GaugeLevel {
+    Ok,
+    Warn,
+    Danger,
+}
+
+impl GaugeLevel {
+    pub fn color(&self, config: &ContextGaugeConfig) -> Color {
+        match self {
+            GaugeLevel::Ok => config.ok_color,
+            GaugeLevel::Warn => config.warn_color,
+            GaugeLevel::Danger => config.danger_color,
+        }
+    }
+}
+
+/// # ContextUsage
+///
+/// **Summary:**
+/// Estimated context use against the model's window.
+///
+/// **Fields:**
+/// - `tokens`: Estimated tokens the model holds
+/// - `window`: The model's context window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextUsage {
+    pub tokens: usize,
+    pub window: usize,
+}
+
+impl ContextUsage {
+    /// Share of the window in use (can pass 1.0 when the estimate overshoots)
+    pub fn ratio(&self) -> f64 {
+        self.tokens as f64 / self.window.max(1) as f64
+    }
+
+    /// # fill_rows
+    ///
+    /// **Purpose:**
+    /// Rows of a `height`-row gauge to fill.
+    ///
+    /// **Details:**
+    /// Rounds up, so any use shows at least one row; never more than `height`.
+    pub fn fill_rows(&self, height: u16) -> u16 {
+        if self.tokens == 0 {
+            return 0;
+        }
+        ((self.ratio() * height as f64).ceil() as u16).clamp(1, height)
+    }
+
+    pub fn level(&self, config: &ContextGaugeConfig) -> GaugeLevel {
+        let ratio = self.ratio();
+        if ratio >= config.danger_ratio {
+            GaugeLevel::Danger
+        } else if ratio >= config.warn_ratio {
+            GaugeLevel::Warn
+        } else {
+            GaugeLevel::Ok
+        }
+    }
+}
+
+/// The context window of `model`: the first matching prefix, else `default_window`
+pub fn context_window(model: &str, config: &ContextGaugeConfig) -> usize {
+    config.windows.iter()
+        .find(|window| model.starts_with(&window.model_prefix))
+        .map_or(config.default_window, |window| window.tokens)
+}
+
+/// # ContextGaugeCache
+///
+/// **Summary:**
+/// A pane's last estimate and what it was taken from.
+///
+/// **Fields:**
+/// - `fingerprint`: Text count, total bytes, and model at measurement time
+/// - `usage`: The estimate
+///
+/// **Details:**
+/// The fingerprint costs one length read per message; counting tokens means
+/// walking every character, so that only happens when the fingerprint moves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextGaugeCache {
+    fingerprint: (usize, usize, String),
+    pub usage: ContextUsage,
+}
+
+/// # measure
+///
+/// **Purpose:**
+/// Estimates context use, reusing `cached` when the conversation hasn't changed.
+///
+/// **Parameters:**
+/// - `conversation`: The agent's conversation
+/// - `model`: Model the next request goes to
+/// - `cached`: The pane's previous result, if any
+/// - `config`: `tui.context_gauge`
+pub fn measure(
+    conversation: &GrokConversation,
+    model: &str,
+    cached: Option<ContextGaugeCache>,
+    config: &ContextGaugeConfig,
+) -> ContextGaugeCache {
+    let (count, bytes) = conversation.context_texts()
+        .fold((0, 0), |(count, bytes), text| (count + 1, bytes + text.len()));
+    let fingerprint = (count, bytes, model.to_string());
+    if let Some(cached) = cached.filter(|cached| cached.fingerprint == fingerprint) {
+        return cached;
+    }
+
+    let tokens = conversation.context_texts().map(estimate_tokens).sum();
+    ContextGaugeCache {
+        fingerprint,
+        usage: ContextUsage { tokens, window: context_window(model, config) },
+    }
+}
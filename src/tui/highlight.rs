@@ -0,0 +1,385 @@
+//! # Daegonica Module: tui::highlight
+//!
+//! **Purpose:** Syntax highlighting for Rust code blocks in the agent pane
+//!
+//! **Context:**
+//! - Most useful replies from the Rust-focused personas contain Rust code
+//! - Applied by `ShadowApp::pan_messages` to blocks tagged or detected as Rust
+//!
+//! **Responsibilities:**
+//! - Split Rust source lines into token classes with a small hand-rolled lexer
+//! - Map token classes to the colors in `tui.syntax_colors`
+//! - Cache highlighted lines of finished blocks so redraws don't re-lex them
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use crate::prelude::*;
+
+/// Finished blocks remembered before the cache is reset
+const CACHE_LIMIT: usize = 256;
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe",
+    "use", "where", "while",
+];
+
+const PRIMITIVES: &[&str] = &[
+    "bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize",
+    "i8", "i16", "i32", "i64", "i128", "isize", "f32", "f64",
+];
+
+/// # TokenClass
+///
+/// **Summary:**
+/// What a span of Rust source is, for coloring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenClass {
+    Plain,
+    Keyword,
+    Type,
+    String,
+    Comment,
+    Lifetime,
+    Macro,
+    Number,
+}
+
+impl TokenClass {
+    pub fn style(&self) -> Style {
+        let colors = &GLOBAL_CONFIG.tui.syntax_colors;
+        match self {
+            TokenClass::Plain => Style::default(),
+            TokenClass::Keyword => Style::default().fg(colors.keyword),
+            TokenClass::Type => Style::default().fg(colors.type_name),
+            TokenClass::String => Style::default().fg(colors.string),
+            TokenClass::Comment => Style::default().fg(colors.comment),
+            TokenClass::Lifetime => Style::default().fg(colors.lifetime),
+            TokenClass::Macro => Style::default().fg(colors.macro_call),
+            TokenClass::Number => Style::default().fg(colors.number),
+        }
+    }
+}
+
+/// # LexState
+///
+/// **Summary:**
+/// What carries over from one line to the next: an open block comment or string.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LexState {
+    comment_depth: usize,
+    in_string: bool,
+}
+
+/// # tokenize_line
+///
+/// **Purpose:**
+/// Splits one line of Rust into classified pieces.
+///
+/// **Parameters:**
+/// - `line`: Source line
+/// - `state`: Carried between lines of the same block
+///
+/// **Returns:**
+/// `Vec<(TokenClass, &str)>` - Pieces that concatenate back to `line`
+pub fn tokenize_line<'a>(line: &'a str, state: &mut LexState) -> Vec<(TokenClass, &'a str)> {
+    let bytes = line.as_bytes();
+    let mut tokens: Vec<(TokenClass, &'a str)> = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let start = i;
+        let rest = &line[i..];
+
+        let class = if state.comment_depth > 0 {
+            i = scan_block_comment(line, i, state);
+            TokenClass::Comment
+        } else if state.in_string {
+            i = scan_string(line, i, state);
+            TokenClass::String
+        } else if rest.starts_with("//") {
+            i = line.len();
+            TokenClass::Comment
+        } else if rest.starts_with("/*") {
+            state.comment_depth = 1;
+            i = scan_block_comment(line, i + 2, state);
+            TokenClass::Comment
+        } else if bytes[i] == b'"' {
+            state.in_string = true;
+            i = scan_string(line, i + 1, state);
+            TokenClass::String
+        } else if bytes[i] == b'\'' {
+            match char_literal_len(rest) {
+                Some(len) => {
+                    i += len;
+                    TokenClass::String
+                }
+                None => {
+                    i += 1 + rest[1..].find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len() - 1);
+                    TokenClass::Lifetime
+                }
+            }
+        } else if bytes[i].is_ascii_digit() {
+            i += rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.')).unwrap_or(rest.len());
+            TokenClass::Number
+        } else if bytes[i].is_ascii_alphabetic() || bytes[i] == b'_' {
+            i += rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            let word = &line[start..i];
+            if line[i..].starts_with('!') && !line[i..].starts_with("!=") {
+                i += 1;
+                TokenClass::Macro
+            } else if KEYWORDS.contains(&word) {
+                TokenClass::Keyword
+            } else if PRIMITIVES.contains(&word) || word.starts_with(|c: char| c.is_ascii_uppercase()) {
+                TokenClass::Type
+            } else {
+                TokenClass::Plain
+            }
+        } else {
+            i += rest.chars().next().map(char::len_utf8).unwrap_or(1);
+            TokenClass::Plain
+        };
+
+        match tokens.last_mut() {
+            Some((last_class, text)) if *last_class == class && class == TokenClass::Plain => {
+                *text = &line[start - text.len()..i];
+            }
+            _ => tokens.push((class, &line[start..i])),
+        }
+    }
+    tokens
+}
+
+/// Advances through a block comment (nesting allowed); returns the index after it or the line end
+fn scan_block_comment(line: &str, mut i: usize, state: &mut LexState) -> usize {
+    while i < line.len() && state.comment_depth > 0 {
+        if line[i..].starts_with("*/") {
+            state.comment_depth -= 1;
+            i += 2;
+        } else if line[i..].starts_with("/*") {
+            state.comment_depth += 1;
+            i += 2;
+        } else {
+            i += line[i..].chars().next().map(char::len_utf8).unwrap_or(1);
+        }
+    }
+    i
+}
+
+/// Advances through a string body; returns the index after the closing quote or the line end
+fn scan_string(line: &str, mut i: usize, state: &mut LexState) -> usize {
+    let bytes = line.as_bytes();
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => {
+                state.in_string = false;
+                return i + 1;
+            }
+            _ => i += 1,
+        }
+    }
+    line.len()
+}
+
+/// Length of a char literal at the start of `rest` (`'a'`, `'\n'`), None if it's a lifetime
+fn char_literal_len(rest: &str) -> Option<usize> {
+    let mut chars = rest.char_indices().skip(1);
+    let (_, first) = chars.next()?;
+    if first == '\\' {
+        return rest.get(3..)?.find('\'').map(|end| end + 4);
+    }
+    let (index, next) = chars.next()?;
+    (next == '\'').then_some(index + 1)
+}
+
+/// # highlight_block
+///
+/// **Purpose:**
+/// Turns the lines of a Rust block into styled lines.
+pub fn highlight_block(lines: &[&str]) -> Vec<Line<'static>> {
+    let mut state = LexState::default();
+    lines.iter()
+        .map(|line| {
+            Line::from(
+                tokenize_line(line, &mut state).into_iter()
+                    .map(|(class, text)| Span::styled(text.to_string(), class.style()))
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect()
+}
+
+/// # HighlightCache
+///
+/// **Summary:**
+/// Highlighted lines of finished code blocks, keyed by their content.
+///
+/// **Details:**
+/// Only closed blocks are cached: a block still streaming changes every frame,
+/// and once its closing fence arrives its final content gets a fresh entry.
+#[derive(Debug, Default)]
+pub struct HighlightCache {
+    blocks: HashMap<u64, Vec<Line<'static>>>,
+}
+
+impl HighlightCache {
+    /// # lines
+    ///
+    /// **Purpose:**
+    /// Highlighted lines for a block, from the cache when it's finished.
+    pub fn lines(&mut self, content: &str, closed: bool) -> Vec<Line<'static>> {
+        let body: Vec<&str> = content.split('\n').collect();
+        if !closed {
+            return highlight_block(&body);
+        }
+
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        let key = hasher.finish();
+
+        if self.blocks.len() >= CACHE_LIMIT && !self.blocks.contains_key(&key) {
+            self.blocks.clear();
+        }
+        self.blocks.entry(key).or_insert_with(|| highlight_block(&body)).clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TokenClass::*;
+
+    /// Tokenizes `lines` as one block, keeping only the classified pieces
+    fn tokens<'a>(lines: &[&'a str]) -> Vec<Vec<(TokenClass, &'a str)>> {
+        let mut state = LexState::default();
+        lines.iter().map(|line| tokenize_line(line, &mut state)).collect()
+    }
+
+    fn not_plain<'a>(line: &[(TokenClass, &'a str)]) -> Vec<(TokenClass, &'a str)> {
+        line.iter().copied().filter(|(class, _)| *class != Plain).collect()
+    }
+
+    #[test]
+    fn pieces_concatenate_back_to_the_line() {
+        let block = [
+            "use std::collections::HashMap; // imports",
+            "fn parse<'a>(input: &'a str) -> Result<Vec<&'a str>, String> {",
+            "    let quote = '\\''; let tab = '\\t'; let emoji = '😀'; let u = '\\u{1F600}';",
+            "    let s = \"escaped \\\" quote and /* not a comment */\";",
+            "    /* block /* nested */ still comment */ let x = 1_000.5e3;",
+            "    if x != 0 && y!= 1 { println!(\"{}\", x); vec![1, 2] }",
+            "    let ünïcode = \"naïve\"; 'outer: loop { break 'outer; }",
+            "    let unterminated = \"runs to the end \\",
+            "of the next line\"; /* opens",
+            "   and closes */ '",
+            "",
+        ];
+        let mut state = LexState::default();
+        for line in block {
+            let pieces = tokenize_line(line, &mut state);
+            assert_eq!(pieces.iter().map(|(_, text)| *text).collect::<std::string::String>(), line);
+            assert!(pieces.iter().all(|(_, text)| !text.is_empty()), "empty piece in {:?}", line);
+        }
+        assert_eq!(state, LexState::default());
+    }
+
+    #[test]
+    fn keywords_types_numbers_and_macros_are_classified() {
+        let lines = tokens(&["pub fn len(v: Vec<u8>) -> usize { assert!(true); 42 }"]);
+        assert_eq!(not_plain(&lines[0]), [
+            (Keyword, "pub"), (Keyword, "fn"), (Type, "Vec"), (Type, "u8"), (Type, "usize"),
+            (Macro, "assert!"), (Keyword, "true"), (Number, "42"),
+        ]);
+    }
+
+    #[test]
+    fn not_equal_is_not_a_macro() {
+        let lines = tokens(&["if x != y && a!= b && ready!(fut) {}"]);
+        assert_eq!(not_plain(&lines[0]), [(Keyword, "if"), (Macro, "ready!")]);
+    }
+
+    #[test]
+    fn char_literals_are_strings_and_lifetimes_are_not() {
+        let lines = tokens(&["let q = '\\''; fn f<'a>(x: &'a str) -> char { 'x' } 'label: loop {}"]);
+        let classified = not_plain(&lines[0]);
+        assert!(classified.contains(&(String, "'\\''")));
+        assert!(classified.contains(&(String, "'x'")));
+        assert_eq!(
+            classified.iter().filter(|(class, _)| *class == Lifetime).map(|(_, text)| *text).collect::<Vec<_>>(),
+            ["'a", "'a", "'label"]
+        );
+    }
+
+    #[test]
+    fn char_literal_lengths() {
+        assert_eq!(char_literal_len("'a' rest"), Some(3));
+        assert_eq!(char_literal_len("'\\'' rest"), Some(4));
+        assert_eq!(char_literal_len("'\\n'"), Some(4));
+        assert_eq!(char_literal_len("'\\u{1F600}'"), Some(11));
+        assert_eq!(char_literal_len("'é'"), Some(4));
+        assert_eq!(char_literal_len("'a>"), None);
+        assert_eq!(char_literal_len("'static str"), None);
+        assert_eq!(char_literal_len("'"), None);
+    }
+
+    #[test]
+    fn nested_block_comments_close_at_the_matching_end() {
+        let lines = tokens(&["a /* one /* two */ still */ b"]);
+        assert_eq!(lines[0], [(Plain, "a "), (Comment, "/* one /* two */ still */"), (Plain, " b")]);
+    }
+
+    #[test]
+    fn block_comments_span_lines() {
+        let lines = tokens(&["let a = 1; /* starts", "/* nested */ middle", "ends */ let b;"]);
+        assert_eq!(lines[1], [(Comment, "/* nested */ middle")]);
+        assert_eq!(lines[2], [(Comment, "ends */"), (Plain, " "), (Keyword, "let"), (Plain, " b;")]);
+    }
+
+    #[test]
+    fn strings_span_lines_and_escapes_stay_inside() {
+        let lines = tokens(&["let s = \"first \\\" line", "second // not a comment", "end\"; x"]);
+        assert_eq!(not_plain(&lines[0]), [(Keyword, "let"), (String, "\"first \\\" line")]);
+        assert_eq!(lines[1], [(String, "second // not a comment")]);
+        assert_eq!(lines[2], [(String, "end\""), (Plain, "; x")]);
+    }
+
+    #[test]
+    fn line_comments_run_to_the_end() {
+        let lines = tokens(&["x // \"quoted\" /* not a block", "y"]);
+        assert_eq!(lines[0], [(Plain, "x "), (Comment, "// \"quoted\" /* not a block")]);
+        assert_eq!(lines[1], [(Plain, "y")]);
+    }
+
+    #[test]
+    fn the_cache_keeps_only_closed_blocks() {
+        let mut cache = HighlightCache::default();
+        cache.lines("fn main() {", false);
+        assert!(cache.is_empty());
+
+        let first = cache.lines("fn main() {}", true);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.lines("fn main() {}", true), first);
+        assert_eq!(cache.len(), 1);
+    }
+}
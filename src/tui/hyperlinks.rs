@@ -0,0 +1,537 @@
+//! # Daegonica Module: tui::hyperlinks
+//!
+//! **Purpose:** Make URLs in the message panes clickable with OSC 8 terminal hyperlinks
+//!
+//! **Context:**
+//! - ratatui has no hyperlink support, and escape sequences inside a cell would
+//!   throw off its width math and diffing, so links are written outside it
+//! - While lines are built, link text gets a marker in its style (`linkify`);
+//!   after the frame is rendered, marked cells are taken out of ratatui's hands
+//!   (`take_links`) and written by `HyperlinkWriter` between frames, like `terminal_status`
+//! - `tui.hyperlinks` (`auto` / `on` / `off`); `auto` guesses from TERM, TERM_PROGRAM and friends
+//!
+//! **Responsibilities:**
+//! - Decide whether the terminal is likely to support OSC 8
+//! - Replace URLs and markdown links in a line with styled, marked link text
+//! - Collect the marked cells of a rendered frame into link runs
+//! - Write each run wrapped in OSC 8 open/close sequences, only when the links on screen change
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use crossterm::cursor::{MoveTo, RestorePosition, SavePosition};
+use crossterm::queue;
+use crossterm::style::{Attribute, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor};
+use once_cell::sync::Lazy;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use strum::{Display, EnumString};
+use crate::prelude::*;
+use crate::utilities::links::{find_links, shorten_url};
+
+/// Most characters a bare URL shows (the link still goes to the full URL)
+const SHORT_URL_CHARS: usize = 40;
+
+/// # HyperlinkMode
+///
+/// **Summary:**
+/// Whether URLs in the panes are written as clickable terminal hyperlinks.
+///
+/// **Variants:**
+/// - `Auto`: When the terminal looks like it supports them (`terminal_supports_hyperlinks`)
+/// - `On`: Always
+/// - `Off`: Never; URLs show as plain text, exactly as the reply wrote them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, Display, Serialize, Deserialize)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+#[serde(rename_all = "lowercase")]
+pub enum HyperlinkMode {
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+/// # terminal_supports_hyperlinks
+///
+/// **Purpose:**
+/// Guesses from the environment whether the terminal understands OSC 8.
+///
+/// **Parameters:**
+/// - `env`: Looks up an environment variable (a parameter so the guess can be made without touching the process environment)
+///
+/// **Details:**
+/// Inside tmux or screen the answer is no: they drop or mangle the sequence
+/// unless configured for it, so `tui.hyperlinks: on` is needed there.
+/// Otherwise yes for terminals known to support it: Windows Terminal, Konsole,
+/// kitty, VTE 0.50+ (GNOME Terminal, Tilix), iTerm2, WezTerm, VS Code,
+/// Ghostty, Hyper, Tabby, foot and Alacritty.
+pub fn terminal_supports_hyperlinks(env: impl Fn(&str) -> Option<String>) -> bool {
+    let term = env("TERM").unwrap_or_default();
+    if env("TMUX").is_some() || term.starts_with("screen") || term.starts_with("tmux") {
+        return false;
+    }
+    if env("WT_SESSION").is_some() || env("KONSOLE_VERSION").is_some() || env("KITTY_WINDOW_ID").is_some() {
+        return true;
+    }
+    if env("VTE_VERSION").and_then(|version| version.parse::<u32>().ok()).is_some_and(|version| version >= 5000) {
+        return true;
+    }
+    let program = env("TERM_PROGRAM").unwrap_or_default();
+    if ["iTerm.app", "WezTerm", "vscode", "ghostty", "Hyper", "Tabby"].contains(&program.as_str()) {
+        return true;
+    }
+    ["kitty", "foot", "wezterm", "ghostty", "alacritty"].iter().any(|name| term.contains(name))
+}
+
+static ENABLED: Lazy<bool> = Lazy::new(|| match GLOBAL_CONFIG.tui.hyperlinks {
+    HyperlinkMode::On => true,
+    HyperlinkMode::Off => false,
+    HyperlinkMode::Auto => terminal_supports_hyperlinks(|name| std::env::var(name).ok()),
+});
+
+/// Whether links are made clickable this session (`tui.hyperlinks`, decided once)
+pub fn is_enabled() -> bool {
+    *ENABLED
+}
+
+/// How link text looks, on top of the line's own style
+fn link_style() -> Style {
+    Style::default().fg(Color::LightBlue).add_modifier(Modifier::UNDERLINED)
+}
+
+/// # LinkTable
+///
+/// **Summary:**
+/// The link targets of the frame being drawn; a link's index is its marker.
+///
+/// **Details:**
+/// The marker is the link text's underline color, `Color::Rgb` spelling out
+/// the index. Marked cells never reach the terminal through ratatui, so the
+/// color is never shown. Cleared at the start of every frame.
+#[derive(Debug, Default)]
+pub struct LinkTable {
+    targets: Vec<String>,
+}
+
+impl LinkTable {
+    pub fn clear(&mut self) {
+        self.targets.clear();
+    }
+
+    /// The marker for `target`, added to the table if it's new
+    fn marker(&mut self, target: &str) -> Color {
+        let index = match self.targets.iter().position(|known| known == target) {
+            Some(index) => index,
+            None => {
+                self.targets.push(target.to_string());
+                self.targets.len() - 1
+            }
+        };
+        Color::Rgb((index >> 16) as u8, (index >> 8) as u8, index as u8)
+    }
+
+    /// The target a marker stands for
+    fn target(&self, marker: Color) -> Option<&str> {
+        let Color::Rgb(high, mid, low) = marker else {
+            return None;
+        };
+        let index = (high as usize) << 16 | (mid as usize) << 8 | low as usize;
+        self.targets.get(index).map(String::as_str)
+    }
+}
+
+/// # linkify
+///
+/// **Purpose:**
+/// Replaces the URLs and markdown links in a line with marked link text.
+///
+/// **Returns:**
+/// `Line` - The same line; each link becomes its own span in the link style,
+/// showing the link text of a markdown link or a shortened bare URL
+///
+/// **Details:**
+/// Links are found within each span, keeping the span's style underneath.
+/// A target with control characters is left alone, since it would end the
+/// OSC 8 sequence early.
+pub fn linkify<'a>(mut line: Line<'a>, table: &mut LinkTable) -> Line<'a> {
+    let mut spans = Vec::with_capacity(line.spans.len());
+    for span in std::mem::take(&mut line.spans) {
+        let links: Vec<_> = find_links(&span.content).into_iter()
+            .filter(|link| !link.target.chars().any(char::is_control))
+            .collect();
+        if links.is_empty() {
+            spans.push(span);
+            continue;
+        }
+
+        let mut rest_from = 0;
+        for link in links {
+            if link.range.start > rest_from {
+                spans.push(Span::styled(span.content[rest_from..link.range.start].to_string(), span.style));
+            }
+            let text = if link.markdown { link.text } else { shorten_url(&link.target, SHORT_URL_CHARS) };
+            let style = span.style.patch(link_style()).underline_color(table.marker(&link.target));
+            spans.push(Span::styled(text, style));
+            rest_from = link.range.end;
+        }
+        if rest_from < span.content.len() {
+            spans.push(Span::styled(span.content[rest_from..].to_string(), span.style));
+        }
+    }
+    line.spans = spans;
+    line
+}
+
+/// # LinkRun
+///
+/// **Summary:**
+/// Link text on one screen row, to be written with its hyperlink.
+///
+/// **Fields:**
+/// - `x` / `y`: Screen position of the first cell
+/// - `text`: The cells' symbols
+/// - `style`: The cells' style (marker removed)
+/// - `target`: Where the link goes
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkRun {
+    pub x: u16,
+    pub y: u16,
+    pub text: String,
+    pub style: Style,
+    pub target: String,
+}
+
+/// # FrameLinks
+///
+/// **Summary:**
+/// Every link run in a rendered frame, with the frame's size.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrameLinks {
+    pub area: Rect,
+    pub runs: Vec<LinkRun>,
+}
+
+/// # take_links
+///
+/// **Purpose:**
+/// Finds the marked cells in a rendered frame and takes them out of ratatui's output.
+///
+/// **Returns:**
+/// `FrameLinks` - One run per stretch of adjacent cells with the same target and style
+///
+/// **Details:**
+/// Marked cells are set to `skip`, so ratatui neither draws them nor counts
+/// the link sequences in its widths; `HyperlinkWriter` draws them instead. A
+/// link wrapped onto several rows gives a run per row.
+pub fn take_links(buffer: &mut Buffer, table: &LinkTable) -> FrameLinks {
+    let area = buffer.area;
+    let mut runs: Vec<LinkRun> = Vec::new();
+    // Column just past the current run, in display width
+    let mut run_end = 0;
+
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            let Some(cell) = buffer.cell_mut((x, y)) else {
+                continue;
+            };
+            let Some(target) = cell.modifier.contains(Modifier::UNDERLINED)
+                .then(|| table.target(cell.underline_color))
+                .flatten()
+            else {
+                continue;
+            };
+            cell.set_skip(true);
+
+            let style = Style::default().fg(cell.fg).bg(cell.bg).add_modifier(cell.modifier);
+            let symbol = cell.symbol();
+            let width = Span::raw(symbol).width().max(1) as u16;
+            match runs.last_mut() {
+                Some(run) if run.y == y && run_end == x && run.style == style && run.target == target => {
+                    run.text.push_str(symbol);
+                }
+                _ => runs.push(LinkRun { x, y, text: symbol.to_string(), style, target: target.to_string() }),
+            }
+            run_end = x + width;
+        }
+    }
+    FrameLinks { area, runs }
+}
+
+/// OSC 8 opening sequence; `id` ties the rows of a wrapped link together for hover highlighting
+fn osc8_open(target: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    target.hash(&mut hasher);
+    format!("\x1b]8;id={:x};{}\x1b\\", hasher.finish(), target)
+}
+
+/// OSC 8 closing sequence
+const OSC8_CLOSE: &str = "\x1b]8;;\x1b\\";
+
+/// The crossterm attributes for a ratatui modifier set (the ones link text can carry)
+fn attributes(modifier: Modifier) -> impl Iterator<Item = Attribute> {
+    [
+        (Modifier::BOLD, Attribute::Bold),
+        (Modifier::DIM, Attribute::Dim),
+        (Modifier::ITALIC, Attribute::Italic),
+        (Modifier::UNDERLINED, Attribute::Underlined),
+        (Modifier::REVERSED, Attribute::Reverse),
+        (Modifier::CROSSED_OUT, Attribute::CrossedOut),
+    ]
+    .into_iter()
+    .filter(move |(flag, _)| modifier.contains(*flag))
+    .map(|(_, attribute)| attribute)
+}
+
+/// # HyperlinkWriter
+///
+/// **Summary:**
+/// Writes a frame's link runs to the terminal after ratatui has drawn it.
+///
+/// **Fields:**
+/// - `shown`: The links last written
+///
+/// **Details:**
+/// Runs are only rewritten when the links on screen change (or the terminal
+/// was resized, which makes ratatui clear it). The escape sequences go
+/// straight to the writer, so they take up no cells; the cursor is saved and
+/// restored around them so it stays where ratatui put it.
+#[derive(Debug, Default)]
+pub struct HyperlinkWriter {
+    shown: FrameLinks,
+}
+
+impl HyperlinkWriter {
+    /// # emit
+    ///
+    /// **Purpose:**
+    /// Writes `links` if they differ from what's on screen.
+    ///
+    /// **Parameters:**
+    /// - `out`: The writer ratatui draws to, called between frames
+    /// - `links`: The links of the frame just drawn
+    ///
+    /// **Errors / Failures:**
+    /// - Writing to the terminal fails
+    pub fn emit(&mut self, out: &mut impl Write, links: &FrameLinks) -> io::Result<()> {
+        if *links == self.shown {
+            return Ok(());
+        }
+        queue!(out, SavePosition)?;
+        for run in &links.runs {
+            queue!(out, MoveTo(run.x, run.y), SetAttribute(Attribute::Reset), ResetColor)?;
+            if let Some(fg) = run.style.fg {
+                queue!(out, SetForegroundColor(fg.into()))?;
+            }
+            if let Some(bg) = run.style.bg {
+                queue!(out, SetBackgroundColor(bg.into()))?;
+            }
+            for attribute in attributes(run.style.add_modifier) {
+                queue!(out, SetAttribute(attribute))?;
+            }
+            queue!(out, Print(osc8_open(&run.target)), Print(&run.text), Print(OSC8_CLOSE))?;
+        }
+        queue!(out, SetAttribute(Attribute::Reset), ResetColor, RestorePosition)?;
+        out.flush()?;
+        self.shown = links.clone();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::widgets::{Paragraph, Widget};
+
+    /// Renders `line`, linkified, into a `width` x `height` buffer
+    fn render(line: &str, width: u16, height: u16) -> (Buffer, LinkTable) {
+        let mut table = LinkTable::default();
+        let line = linkify(Line::from(line.to_string()), &mut table);
+        let mut buffer = Buffer::empty(Rect::new(0, 0, width, height));
+        Paragraph::new(line).wrap(ratatui::widgets::Wrap { trim: false }).render(buffer.area, &mut buffer);
+        (buffer, table)
+    }
+
+    fn row(buffer: &Buffer, y: u16) -> String {
+        (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect()
+    }
+
+    fn runs(links: &FrameLinks) -> Vec<(u16, u16, &str, &str)> {
+        links.runs.iter().map(|run| (run.x, run.y, run.text.as_str(), run.target.as_str())).collect()
+    }
+
+    #[test]
+    fn support_is_guessed_from_the_environment() {
+        let cases: &[(&[(&str, &str)], bool)] = &[
+            (&[], false),
+            (&[("TERM", "xterm-256color")], false),
+            (&[("TERM", "xterm-kitty")], true),
+            (&[("TERM", "alacritty")], true),
+            (&[("TERM_PROGRAM", "iTerm.app")], true),
+            (&[("TERM_PROGRAM", "Apple_Terminal")], false),
+            (&[("WT_SESSION", "1")], true),
+            (&[("VTE_VERSION", "5000")], true),
+            (&[("VTE_VERSION", "4803")], false),
+            (&[("VTE_VERSION", "new")], false),
+            (&[("TERM_PROGRAM", "WezTerm"), ("TMUX", "/tmp/tmux-1000/default,1,0")], false),
+            (&[("TERM", "screen-256color"), ("KITTY_WINDOW_ID", "1")], false),
+            (&[("TERM", "tmux-256color")], false),
+        ];
+        for (env, expected) in cases {
+            let lookup = |name: &str| env.iter().find(|(key, _)| *key == name).map(|(_, value)| value.to_string());
+            assert_eq!(terminal_supports_hyperlinks(lookup), *expected, "env {:?}", env);
+        }
+    }
+
+    #[test]
+    fn mode_parses_from_config_text() {
+        assert_eq!("auto".parse::<HyperlinkMode>(), Ok(HyperlinkMode::Auto));
+        assert_eq!("ON".parse::<HyperlinkMode>(), Ok(HyperlinkMode::On));
+        assert_eq!(HyperlinkMode::Off.to_string(), "off");
+        assert!("sometimes".parse::<HyperlinkMode>().is_err());
+    }
+
+    #[test]
+    fn linkify_shows_link_text_and_keeps_the_rest() {
+        let mut table = LinkTable::default();
+        let base = Style::default().fg(Color::Gray);
+        let line = Line::from(vec![
+            Span::styled("see [docs](https://docs.rs/x) or ", base),
+            Span::raw("https://www.example.com/a/b/c/d/e/f/g/h/i/j/k/l/m/n/o/page.html!"),
+        ]);
+        let line = linkify(line, &mut table);
+        let spans: Vec<&str> = line.spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(spans, ["see ", "docs", " or ", "example.com/…/page.html", "!"]);
+        assert_eq!(line.spans[0].style, base);
+        assert_eq!(line.spans[1].style.fg, Some(Color::LightBlue), "link style over the span's own");
+        assert_eq!(line.spans[1].style.underline_color, Some(Color::Rgb(0, 0, 0)));
+        assert_eq!(line.spans[3].style.underline_color, Some(Color::Rgb(0, 0, 1)));
+        assert_eq!(table.target(Color::Rgb(0, 0, 1)), Some("https://www.example.com/a/b/c/d/e/f/g/h/i/j/k/l/m/n/o/page.html"));
+        assert_eq!(table.target(Color::Rgb(0, 0, 2)), None);
+        assert_eq!(table.target(Color::Red), None);
+
+        let plain = Line::from("nothing to link");
+        assert_eq!(linkify(plain.clone(), &mut table), plain);
+    }
+
+    #[test]
+    fn the_same_target_gets_the_same_marker() {
+        let mut table = LinkTable::default();
+        let first = table.marker("https://a.io");
+        assert_eq!(table.marker("https://b.io"), Color::Rgb(0, 0, 1));
+        assert_eq!(table.marker("https://a.io"), first);
+        table.clear();
+        assert_eq!(table.target(first), None);
+        let far = (0..300).map(|n| table.marker(&format!("https://a.io/{}", n))).last();
+        assert_eq!(far, Some(Color::Rgb(0, 1, 43)), "index 299 spills into the middle byte");
+    }
+
+    #[test]
+    fn rendered_links_become_runs_and_leave_ratatui() {
+        let (mut buffer, table) = render("go [here](https://a.io/h) now", 20, 1);
+        assert_eq!(row(&buffer, 0), "go here now         ", "no escape sequences take up cells");
+
+        let links = take_links(&mut buffer, &table);
+        assert_eq!(links.area, Rect::new(0, 0, 20, 1));
+        assert_eq!(runs(&links), [(3, 0, "here", "https://a.io/h")]);
+        assert_eq!(links.runs[0].style.fg, Some(Color::LightBlue));
+        assert!(links.runs[0].style.add_modifier.contains(Modifier::UNDERLINED));
+
+        let skipped: Vec<bool> = (0..11).map(|x| buffer[(x, 0)].skip).collect();
+        assert_eq!(skipped, [false, false, false, true, true, true, true, false, false, false, false]);
+    }
+
+    #[test]
+    fn a_wrapped_link_gives_a_run_per_row() {
+        let (mut buffer, table) = render("[a long link text](https://a.io/w)", 10, 2);
+        assert_eq!(runs(&take_links(&mut buffer, &table)), [
+            (0, 0, "a long", "https://a.io/w"),
+            (0, 1, "link text", "https://a.io/w"),
+        ]);
+    }
+
+    #[test]
+    fn neighbouring_links_and_wide_text_split_into_runs_correctly() {
+        let (mut buffer, table) = render("[ab](https://a.io)[cd](https://b.io) [日本](https://c.io)", 20, 1);
+        assert_eq!(runs(&take_links(&mut buffer, &table)), [
+            (0, 0, "ab", "https://a.io"),
+            (2, 0, "cd", "https://b.io"),
+            (5, 0, "日本", "https://c.io"),
+        ]);
+    }
+
+    #[test]
+    fn unmarked_cells_are_left_alone() {
+        let (mut buffer, table) = render("plain https text", 20, 1);
+        let before = buffer.clone();
+        assert!(take_links(&mut buffer, &table).runs.is_empty());
+        assert_eq!(buffer, before);
+    }
+
+    #[test]
+    fn emit_wraps_each_run_in_osc8_and_restores_the_cursor() {
+        let links = FrameLinks {
+            area: Rect::new(0, 0, 20, 2),
+            runs: vec![
+                LinkRun {
+                    x: 3,
+                    y: 1,
+                    text: "here".into(),
+                    style: Style::default().fg(Color::LightBlue).add_modifier(Modifier::UNDERLINED | Modifier::BOLD),
+                    target: "https://a.io/h".into(),
+                },
+                LinkRun { x: 0, y: 0, text: "x".into(), style: Style::default(), target: "https://b.io".into() },
+            ],
+        };
+        let mut writer = HyperlinkWriter::default();
+        let mut out = Vec::new();
+        writer.emit(&mut out, &links).unwrap();
+
+        let expected = [
+            "\x1b7".to_string(),
+            "\x1b[2;4H\x1b[0m\x1b[0m".to_string(),
+            "\x1b[38;5;12m\x1b[1m\x1b[4m".to_string(),
+            format!("{}here{}", osc8_open("https://a.io/h"), OSC8_CLOSE),
+            "\x1b[1;1H\x1b[0m\x1b[0m".to_string(),
+            format!("{}x{}", osc8_open("https://b.io"), OSC8_CLOSE),
+            "\x1b[0m\x1b[0m\x1b8".to_string(),
+        ]
+        .concat();
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+
+        let open = osc8_open("https://a.io/h");
+        assert!(open.starts_with("\x1b]8;id=") && open.ends_with(";https://a.io/h\x1b\\"), "{:?}", open);
+        assert_eq!(open, osc8_open("https://a.io/h"), "rows of one link share an id");
+        assert_ne!(open, osc8_open("https://b.io"));
+    }
+
+    #[test]
+    fn emit_writes_only_when_the_links_change() {
+        let links = FrameLinks {
+            area: Rect::new(0, 0, 10, 1),
+            runs: vec![LinkRun { x: 0, y: 0, text: "a".into(), style: Style::default(), target: "https://a.io".into() }],
+        };
+        let mut writer = HyperlinkWriter::default();
+        let mut out = Vec::new();
+        writer.emit(&mut out, &FrameLinks::default()).unwrap();
+        assert!(out.is_empty(), "nothing on screen and nothing new");
+
+        writer.emit(&mut out, &links).unwrap();
+        assert!(!out.is_empty());
+        out.clear();
+        writer.emit(&mut out, &links).unwrap();
+        assert!(out.is_empty(), "unchanged links aren't rewritten");
+
+        let resized = FrameLinks { area: Rect::new(0, 0, 12, 1), ..links };
+        writer.emit(&mut out, &resized).unwrap();
+        assert!(!out.is_empty(), "a resize clears the screen, so the links go out again");
+    }
+}
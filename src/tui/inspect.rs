@@ -0,0 +1,187 @@
+//! # Daegonica Module: tui::inspect
+//!
+//! **Purpose:** Read-only view of the context head an agent actually holds in memory
+//!
+//! **Context:**
+//! - Opened by `inspect`; drawn by `ShadowApp::draw` over the agent pane
+//! - After summarization, edits, and reloads, memory can differ from the persona YAML
+//! - Built from `GrokConversation` accessors, never from raw history indices
+//!
+//! **Responsibilities:**
+//! - Collect the live system prompt, both summary tiers, other system messages, and message counts
+//! - Label each section with its size and an estimated token count
+//! - Track the selected section and which sections are collapsed
+//! - Copy a section to the system clipboard
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use crate::agent_history::conversations::GrokConversation;
+use crate::utilities::accessibility::glyph;
+
+/// # estimate_tokens
+///
+/// **Purpose:**
+/// Rough token count for display (about four characters per token).
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// # InspectSection
+///
+/// **Summary:**
+/// One labeled block of the view.
+///
+/// **Fields:**
+/// - `title`: Section heading
+/// - `body`: Exact text in memory (or a short note when there's none)
+/// - `measured`: Whether `body` is real context whose size should be shown
+/// - `collapsed`: Only the heading is drawn
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InspectSection {
+    pub title: String,
+    pub body: String,
+    pub measured: bool,
+    pub collapsed: bool,
+}
+
+impl InspectSection {
+    fn new(title: impl Into<String>, body: impl Into<String>, measured: bool) -> Self {
+        Self { title: title.into(), body: body.into(), measured, collapsed: false }
+    }
+
+    /// Heading with size, e.g. "System prompt (1432 bytes, ~358 tokens)"
+    pub fn heading(&self) -> String {
+        if self.measured {
+            format!("{} ({} bytes, ~{} tokens)", self.title, self.body.len(), estimate_tokens(&self.body))
+        } else {
+            self.title.clone()
+        }
+    }
+}
+
+/// # InspectView
+///
+/// **Summary:**
+/// Snapshot of one agent's context head, taken when `inspect` ran.
+///
+/// **Fields:**
+/// - `persona`: Agent the snapshot belongs to
+/// - `sections`: Prompt, summary, other system messages, and counts, in request order
+/// - `selected`: Section the keys act on
+/// - `scroll`: Scroll position of the view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InspectView {
+    pub persona: String,
+    pub sections: Vec<InspectSection>,
+    pub selected: usize,
+    pub scroll: u16,
+}
+
+impl InspectView {
+    /// # from_conversation
+    ///
+    /// **Purpose:**
+    /// Takes the snapshot.
+    pub fn from_conversation(conversation: &GrokConversation) -> Self {
+        let prompt = conversation.get_system_prompt()
+            .filter(|msg| msg.role == "system")
+            .map(|msg| InspectSection::new("System prompt", msg.content.clone(), true))
+            .unwrap_or_else(|| InspectSection::new("System prompt", "(none in memory)", false));
+
+        let long_term = match conversation.long_term_summary_text() {
+            Some(summary) => InspectSection::new("Long-term summary", summary, true),
+            None => InspectSection::new("Long-term summary", "(none; no recent summary has been folded in yet)", false),
+        };
+        let summary = match conversation.summary_text() {
+            Some(summary) => InspectSection::new("Recent summary", summary, true),
+            None => InspectSection::new("Recent summary", "(none; the history hasn't been summarized)", false),
+        };
+
+        let injected = conversation.injected_messages();
+        let injected = if injected.is_empty() {
+            InspectSection::new("Other system messages", "(none)", false)
+        } else {
+            let body = injected.iter().map(|msg| msg.content.as_str()).collect::<Vec<_>>().join("\n\n");
+            InspectSection::new(format!("Other system messages ({})", injected.len()), body, true)
+        };
+
+        let (user, assistant) = conversation.conversation_counts();
+        let next_request = if conversation.next_request_threaded() {
+            "continues the thread; only the newest message is sent"
+        } else {
+            "sends the full history: everything above and every message"
+        };
+        let counts = InspectSection::new(
+            "Messages",
+            format!(
+                "{} user, {} assistant ({} in memory including system)\nNext request: {}",
+                user, assistant, conversation.message_count(), next_request
+            ),
+            false,
+        );
+
+        Self {
+            persona: conversation.persona.name.clone(),
+            sections: vec![prompt, long_term, summary, injected, counts],
+            selected: 0,
+            scroll: 0,
+        }
+    }
+
+    /// Moves the selection to the next (or previous) section, wrapping around
+    pub fn select(&mut self, next: bool) {
+        let count = self.sections.len().max(1);
+        self.selected = if next { (self.selected + 1) % count } else { (self.selected + count - 1) % count };
+    }
+
+    /// Collapses or expands the selected section
+    pub fn toggle_selected(&mut self) {
+        if let Some(section) = self.sections.get_mut(self.selected) {
+            section.collapsed = !section.collapsed;
+        }
+    }
+
+    pub fn selected_section(&self) -> Option<&InspectSection> {
+        self.sections.get(self.selected)
+    }
+
+    /// # lines
+    ///
+    /// **Purpose:**
+    /// Styled lines for the overlay: a heading per section, then its body unless collapsed.
+    pub fn lines(&self) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+        for (index, section) in self.sections.iter().enumerate() {
+            let fold = if section.collapsed { glyph("▸", "+") } else { glyph("▾", "-") };
+            let mut style = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+            if index == self.selected {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            lines.push(Line::styled(format!("{} {}", fold, section.heading()), style));
+            if !section.collapsed {
+                lines.extend(section.body.lines().map(|line| Line::from(line.to_string())));
+            }
+            lines.push(Line::default());
+        }
+        lines
+    }
+
+    /// # render_text
+    ///
+    /// **Purpose:**
+    /// The whole view as plain text (CLI and accessible mode, where there is no overlay).
+    pub fn render_text(&self) -> String {
+        let sections: Vec<String> = self.sections.iter()
+            .map(|section| format!("== {} ==\n{}", section.heading(), section.body))
+            .collect();
+        format!("Live context for {}:\n{}", self.persona, sections.join("\n"))
+    }
+}
@@ -0,0 +1,531 @@
+//! # Daegonica Module: tui::layout
+//!
+//! **Purpose:** Pure layout and cursor math for the TUI
+//!
+//! **Context:**
+//! - Called by `ShadowApp::draw` before any widget is built
+//! - Takes sizes and input state only, never a Frame, so results are deterministic
+//!
+//! **Responsibilities:**
+//! - Split the terminal into agent, global, and input areas
+//! - Switch to a single full-width pane on narrow terminals (or when forced)
+//! - Stack one pane per agent in the agent area, in the stacked layout
+//! - Decide when the terminal is too small to draw the normal layout
+//! - Wrap the input text into display lines, keeping explicit newlines
+//! - Compute where the input cursor belongs
+//! - Remember the chosen layout mode and split ratio between sessions
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::ops::Range;
+use ratatui::layout::{Constraint, Direction, Layout, Position, Rect};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use crate::{log_error, log_info};
+use crate::agent_history::persistence;
+use crate::persona::{state_path, writable_state_path};
+
+/// Where the `layout` command's choice is remembered
+pub(crate) const SESSION_FILE: &str = "tui_session.json";
+
+/// Narrowest terminal the split layout is drawn in
+pub const MIN_WIDTH: u16 = 20;
+
+/// Rows the message panes need (borders + one line of content)
+pub const MIN_MESSAGE_HEIGHT: u16 = 3;
+
+/// Column offset of the input text from the input box's left edge
+const INPUT_PROMPT_WIDTH: u16 = 3;
+
+/// Narrowest and widest share of the width the agent pane can be given (percent)
+pub const MIN_SPLIT_RATIO: u8 = 20;
+pub const MAX_SPLIT_RATIO: u8 = 80;
+
+/// Percent one Ctrl+Left/Right moves the split
+pub const SPLIT_RATIO_STEP: u8 = 5;
+
+/// Most agents the stacked layout shows at once; past this only the current one and its neighbours are
+pub const MAX_STACKED_PANES: usize = 4;
+
+/// # LayoutMode
+///
+/// **Summary:**
+/// How the message panes share the screen.
+///
+/// **Variants:**
+/// - `Auto`: Split when wide enough, single pane below `tui.single_pane_below_width`
+/// - `Split`: Always agent and Global side by side
+/// - `Single`: Always one full-width pane; Global is a Ctrl+G overlay
+/// - `Stacked`: Split, with the agent side divided into one pane per agent, top to bottom (Alt+L)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, Display, Serialize, Deserialize)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+#[serde(rename_all = "lowercase")]
+pub enum LayoutMode {
+    #[default]
+    Auto,
+    Split,
+    Single,
+    Stacked,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TuiSession {
+    #[serde(default)]
+    layout: Option<LayoutMode>,
+    #[serde(default)]
+    split_ratio: Option<u8>,
+}
+
+fn load_session() -> Option<TuiSession> {
+    let content = std::fs::read_to_string(state_path(SESSION_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Rewrites the session file with `update` applied, keeping the other saved choices
+fn update_session(update: impl FnOnce(&mut TuiSession)) -> Result<Option<()>, Box<dyn std::error::Error>> {
+    let mut session = load_session().unwrap_or_default();
+    update(&mut session);
+    persistence::auto_write(|| {
+        std::fs::write(writable_state_path(SESSION_FILE)?, serde_json::to_string_pretty(&session)?)?;
+        Ok(())
+    })
+}
+
+/// # saved_layout_mode
+///
+/// **Purpose:**
+/// The layout mode chosen with `layout` in an earlier session, if any.
+pub fn saved_layout_mode() -> Option<LayoutMode> {
+    load_session()?.layout
+}
+
+/// # save_layout_mode
+///
+/// **Purpose:**
+/// Remembers the layout mode for the next session (failures are only logged).
+pub fn save_layout_mode(mode: LayoutMode) {
+    match update_session(|session| session.layout = Some(mode)) {
+        Ok(Some(())) => log_info!("Saved layout mode {}", mode),
+        Ok(None) => log_info!("Layout mode {} not saved: persistence suspended", mode),
+        Err(e) => log_error!("Failed to save layout mode: {}", e),
+    }
+}
+
+/// Keeps a split ratio inside `MIN_SPLIT_RATIO..=MAX_SPLIT_RATIO`
+pub fn clamp_split_ratio(ratio: u8) -> u8 {
+    ratio.clamp(MIN_SPLIT_RATIO, MAX_SPLIT_RATIO)
+}
+
+/// # saved_split_ratio
+///
+/// **Purpose:**
+/// The split ratio left by Ctrl+Left/Right in an earlier session, if any.
+pub fn saved_split_ratio() -> Option<u8> {
+    load_session()?.split_ratio.map(clamp_split_ratio)
+}
+
+/// # save_split_ratio
+///
+/// **Purpose:**
+/// Remembers the split ratio for the next session (failures are only logged).
+pub fn save_split_ratio(ratio: u8) {
+    match update_session(|session| session.split_ratio = Some(ratio)) {
+        Ok(Some(())) => log_info!("Saved split ratio {}%", ratio),
+        Ok(None) => log_info!("Split ratio {}% not saved: persistence suspended", ratio),
+        Err(e) => log_error!("Failed to save split ratio: {}", e),
+    }
+}
+
+/// # LayoutPlan
+///
+/// **Summary:**
+/// Where every section of the TUI goes for a given frame size.
+///
+/// **Fields:**
+/// - `agent_area`: Current agent's pane (left, or full width in single mode)
+/// - `global_area`: Global/system pane (right, or the overlay area in single mode)
+/// - `input_area`: Input box (bottom)
+/// - `input_height`: Rows given to the input box
+/// - `single`: Only one message pane fits; Global is shown as an overlay
+/// - `too_small`: The frame can't fit the layout; draw a placeholder instead
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutPlan {
+    pub agent_area: Rect,
+    pub global_area: Rect,
+    pub input_area: Rect,
+    pub input_height: u16,
+    pub single: bool,
+    pub too_small: bool,
+}
+
+/// # compute_layout
+///
+/// **Purpose:**
+/// Splits the frame into message panes and the input box.
+///
+/// **Parameters:**
+/// - `frame_size`: Full terminal area
+/// - `input_height`: Rows the input box wants (from `calculate_input_height`)
+/// - `mode`: Requested layout mode
+/// - `single_below_width`: Width under which `Auto` drops to a single pane
+/// - `split_ratio`: Percent of the width for the agent pane when split (clamped to 20–80)
+///
+/// **Returns:**
+/// `LayoutPlan` - Areas for each section; `too_small` set when they wouldn't fit
+///
+/// **Details:**
+/// The input box is shrunk before the message panes disappear entirely.
+pub fn compute_layout(
+    frame_size: Rect,
+    input_height: u16,
+    mode: LayoutMode,
+    single_below_width: u16,
+    split_ratio: u8,
+) -> LayoutPlan {
+    let too_small = frame_size.width < MIN_WIDTH
+        || frame_size.height < MIN_MESSAGE_HEIGHT + 3;
+
+    let input_height = input_height
+        .min(frame_size.height.saturating_sub(MIN_MESSAGE_HEIGHT))
+        .max(3.min(frame_size.height));
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(input_height),
+        ])
+        .split(frame_size);
+
+    let single = match mode {
+        LayoutMode::Auto => frame_size.width < single_below_width,
+        LayoutMode::Split | LayoutMode::Stacked => false,
+        LayoutMode::Single => true,
+    };
+    if single {
+        return LayoutPlan {
+            agent_area: chunks[0],
+            global_area: chunks[0],
+            input_area: chunks[1],
+            input_height,
+            single,
+            too_small,
+        };
+    }
+
+    let agent_percent = clamp_split_ratio(split_ratio) as u16;
+    let split = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(agent_percent),
+            Constraint::Percentage(100 - agent_percent),
+        ])
+        .split(chunks[0]);
+
+    LayoutPlan {
+        agent_area: split[0],
+        global_area: split[1],
+        input_area: chunks[1],
+        input_height,
+        single,
+        too_small,
+    }
+}
+
+/// # stacked_window
+///
+/// **Purpose:**
+/// Which agents the stacked layout shows, as a range of positions in the tab order.
+///
+/// **Parameters:**
+/// - `count`: Open agents
+/// - `current`: Position of the current agent
+///
+/// **Returns:**
+/// `Range<usize>` - All of them up to `MAX_STACKED_PANES`; past that the current
+/// agent and the ones either side of it (the first or last three at the ends)
+pub fn stacked_window(count: usize, current: usize) -> Range<usize> {
+    if count <= MAX_STACKED_PANES {
+        return 0..count;
+    }
+    let start = current.saturating_sub(1).min(count - 3);
+    start..start + 3
+}
+
+/// # stacked_areas
+///
+/// **Purpose:**
+/// Divides `area` into `count` panes of equal height, top to bottom.
+pub fn stacked_areas(area: Rect, count: usize) -> Vec<Rect> {
+    let count = count.max(1) as u32;
+    Layout::vertical((0..count).map(|_| Constraint::Ratio(1, count)))
+        .split(area)
+        .to_vec()
+}
+
+/// # InputLine
+///
+/// **Summary:**
+/// One display line of the input box.
+///
+/// **Fields:**
+/// - `start`: Byte offset in the input where the line begins
+/// - `text`: The line's text (no newline; a soft-wrapped line keeps its trailing space)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputLine {
+    pub start: usize,
+    pub text: String,
+}
+
+/// # wrap_input
+///
+/// **Purpose:**
+/// Wraps the input text into display lines of at most `width` characters.
+///
+/// **Returns:**
+/// `Vec<InputLine>` - At least one line; empty input gives one empty line
+///
+/// **Details:**
+/// Every newline starts a new line, so a trailing newline gives an empty last
+/// line for the cursor to sit on. Lines wrap between words; a word longer
+/// than `width` is broken wherever it reaches the edge.
+pub fn wrap_input(input: &str, width: usize) -> Vec<InputLine> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut line_start = 0;
+
+    for logical in input.split('\n') {
+        let mut start = line_start;
+        let mut len = 0;
+        let mut offset = line_start;
+
+        for word in logical.split_inclusive(char::is_whitespace) {
+            let word_len = word.chars().count();
+            if len + word_len > width && len > 0 {
+                lines.push(InputLine { start, text: input[start..offset].to_string() });
+                start = offset;
+                len = 0;
+            }
+            // A word wider than the box is broken at the edge
+            let mut rest = word;
+            while len + rest.chars().count() > width {
+                let cut = rest.char_indices().nth(width - len).map(|(i, _)| i).unwrap_or(rest.len());
+                lines.push(InputLine { start, text: input[start..offset + cut].to_string() });
+                offset += cut;
+                start = offset;
+                len = 0;
+                rest = &rest[cut..];
+            }
+            len += rest.chars().count();
+            offset += rest.len();
+        }
+
+        lines.push(InputLine { start, text: input[start..offset].to_string() });
+        line_start = offset + 1;
+    }
+    lines
+}
+
+/// # compute_cursor
+///
+/// **Purpose:**
+/// Finds the terminal position of the input cursor.
+///
+/// **Parameters:**
+/// - `input_area`: Area of the input box (including borders)
+/// - `wrapped`: Input text as wrapped for display (`wrap_input`)
+/// - `cursor_pos`: Cursor byte offset into the input text
+/// - `input_scroll`: First wrapped line currently shown
+///
+/// **Returns:**
+/// `Option<Position>` - None if the box is too small or the cursor line is scrolled out of view
+///
+/// **Details:**
+/// The cursor goes on the last line starting at or before it, so at a soft
+/// wrap it sits at the start of the next line.
+pub fn compute_cursor(
+    input_area: Rect,
+    wrapped: &[InputLine],
+    cursor_pos: usize,
+    input_scroll: usize,
+) -> Option<Position> {
+    if input_area.height <= 2 || input_area.width <= 6 {
+        return None;
+    }
+
+    let cursor_line = wrapped.iter().rposition(|line| line.start <= cursor_pos).unwrap_or(0);
+    let cursor_col_in_line = wrapped.get(cursor_line)
+        .map(|line| {
+            let end = cursor_pos.saturating_sub(line.start).min(line.text.len());
+            line.text.get(..end).map_or(end, |before| before.chars().count())
+        })
+        .unwrap_or(0);
+
+    let visible_line = cursor_line.checked_sub(input_scroll)?;
+    let max_visible = input_area.height.saturating_sub(2) as usize;
+    if visible_line >= max_visible {
+        return None;
+    }
+
+    let max_col = input_area.width.saturating_sub(INPUT_PROMPT_WIDTH + 2) as usize;
+    Some(Position {
+        x: input_area.x + INPUT_PROMPT_WIDTH + cursor_col_in_line.min(max_col) as u16,
+        y: input_area.y + 1 + visible_line as u16,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::widgets::clamp_scroll;
+
+    fn rect(x: u16, y: u16, width: u16, height: u16) -> Rect {
+        Rect { x, y, width, height }
+    }
+
+    fn lines(wrapped: &[InputLine]) -> Vec<(usize, &str)> {
+        wrapped.iter().map(|line| (line.start, line.text.as_str())).collect()
+    }
+
+    #[test]
+    fn layout_splits_by_mode_and_width() {
+        // (frame, mode, split ratio) -> (agent, global, single)
+        let cases = [
+            (rect(0, 0, 100, 30), LayoutMode::Auto, 70, rect(0, 0, 70, 27), rect(70, 0, 30, 27), false),
+            (rect(0, 0, 50, 30), LayoutMode::Auto, 70, rect(0, 0, 50, 27), rect(0, 0, 50, 27), true),
+            (rect(0, 0, 50, 30), LayoutMode::Split, 70, rect(0, 0, 35, 27), rect(35, 0, 15, 27), false),
+            (rect(0, 0, 100, 30), LayoutMode::Single, 70, rect(0, 0, 100, 27), rect(0, 0, 100, 27), true),
+            (rect(0, 0, 100, 30), LayoutMode::Stacked, 50, rect(0, 0, 50, 27), rect(50, 0, 50, 27), false),
+            // Ratios outside 20-80 are clamped
+            (rect(0, 0, 100, 30), LayoutMode::Split, 95, rect(0, 0, 80, 27), rect(80, 0, 20, 27), false),
+            (rect(0, 0, 100, 30), LayoutMode::Split, 0, rect(0, 0, 20, 27), rect(20, 0, 80, 27), false),
+            // An offset frame keeps its origin
+            (rect(5, 2, 100, 30), LayoutMode::Auto, 70, rect(5, 2, 70, 27), rect(75, 2, 30, 27), false),
+        ];
+        for (frame, mode, ratio, agent, global, single) in cases {
+            let plan = compute_layout(frame, 3, mode, 60, ratio);
+            let context = format!("{:?} {} {}%", frame, mode, ratio);
+            assert_eq!(plan.agent_area, agent, "{}", context);
+            assert_eq!(plan.global_area, global, "{}", context);
+            assert_eq!(plan.input_area, rect(frame.x, frame.y + 27, frame.width, 3), "{}", context);
+            assert_eq!(plan.single, single, "{}", context);
+            assert!(!plan.too_small, "{}", context);
+        }
+    }
+
+    #[test]
+    fn the_input_box_shrinks_before_the_panes_disappear() {
+        // (frame, wanted input height) -> (input height, too small)
+        let cases = [
+            (rect(0, 0, 100, 30), 3, 3, false),
+            (rect(0, 0, 100, 30), 10, 10, false),
+            (rect(0, 0, 100, 30), 40, 27, false),
+            (rect(0, 0, 100, 6), 5, 3, false),
+            (rect(0, 0, 100, 5), 3, 3, true),
+            (rect(0, 0, 100, 1), 3, 1, true),
+            (rect(0, 0, 19, 30), 3, 3, true),
+            (rect(0, 0, 0, 0), 3, 0, true),
+        ];
+        for (frame, wanted, height, too_small) in cases {
+            let plan = compute_layout(frame, wanted, LayoutMode::Auto, 60, 70);
+            let context = format!("{:?} wanting {}", frame, wanted);
+            assert_eq!(plan.input_height, height, "{}", context);
+            assert_eq!(plan.input_area.height, height, "{}", context);
+            assert_eq!(plan.too_small, too_small, "{}", context);
+            assert_eq!(plan.agent_area.height + height, frame.height, "{}", context);
+        }
+    }
+
+    #[test]
+    fn stacked_panes_window_around_the_current_agent() {
+        let cases = [(0, 0, 0..0), (3, 1, 0..3), (4, 3, 0..4), (10, 0, 0..3), (10, 1, 0..3), (10, 5, 4..7), (10, 9, 7..10)];
+        for (count, current, expected) in cases {
+            assert_eq!(stacked_window(count, current), expected, "{} agents, current {}", count, current);
+        }
+
+        let areas = stacked_areas(rect(0, 0, 50, 10), 3);
+        assert_eq!(areas.len(), 3);
+        assert_eq!(areas.iter().map(|area| area.height).sum::<u16>(), 10);
+        assert_eq!(stacked_areas(rect(0, 0, 50, 10), 0), vec![rect(0, 0, 50, 10)]);
+    }
+
+    /// Each wrapped row as (byte offset it starts at, its text)
+    type Rows<'a> = &'a [(usize, &'a str)];
+
+    #[test]
+    fn input_wraps_between_words_and_keeps_newlines() {
+        let cases: [(&str, usize, Rows); 10] = [
+            ("", 10, &[(0, "")]),
+            ("abcd", 4, &[(0, "abcd")]),
+            ("hello world", 6, &[(0, "hello "), (6, "world")]),
+            ("hello world", 20, &[(0, "hello world")]),
+            ("abcdefghij", 4, &[(0, "abcd"), (4, "efgh"), (8, "ij")]),
+            ("ab\ncd", 10, &[(0, "ab"), (3, "cd")]),
+            ("ab\n", 10, &[(0, "ab"), (3, "")]),
+            ("\n\n", 10, &[(0, ""), (1, ""), (2, "")]),
+            // Widths count characters, starts count bytes
+            ("héllo wörld", 6, &[(0, "héllo "), (7, "wörld")]),
+            ("ab", 0, &[(0, "a"), (1, "b")]),
+        ];
+        for (input, width, expected) in cases {
+            assert_eq!(lines(&wrap_input(input, width)), expected, "{:?} at width {}", input, width);
+        }
+    }
+
+    #[test]
+    fn the_cursor_follows_wraps_scrolling_and_multibyte_text() {
+        let area = rect(0, 27, 100, 4);
+        let wrapped = wrap_input("hello world\nhéllo\nlast", 6);
+        // Lines: "hello " (0), "world" (6), "héllo" (12), "last" (19)
+        let cases = [
+            (0, 0, Some((3, 28))),
+            (5, 0, Some((8, 28))),
+            // At a soft wrap the cursor starts the next line
+            (6, 0, Some((3, 29))),
+            (11, 0, Some((8, 29))),
+            // After "hé": two characters, three bytes
+            (15, 0, None),
+            (15, 1, Some((5, 29))),
+            (19, 2, Some((3, 29))),
+            // Scrolled past the cursor line
+            (0, 1, None),
+        ];
+        for (cursor, scroll, expected) in cases {
+            let position = compute_cursor(area, &wrapped, cursor, scroll).map(|pos| (pos.x, pos.y));
+            assert_eq!(position, expected, "cursor {} scroll {}", cursor, scroll);
+        }
+
+        // Too small to hold a cursor
+        assert_eq!(compute_cursor(rect(0, 0, 100, 2), &wrapped, 0, 0), None);
+        assert_eq!(compute_cursor(rect(0, 0, 6, 5), &wrapped, 0, 0), None);
+        // A column past the box edge is pinned to it
+        let long = wrap_input("abcdefghij", 20);
+        assert_eq!(compute_cursor(rect(0, 0, 10, 3), &long, 8, 0), Some(Position { x: 8, y: 1 }));
+    }
+
+    #[test]
+    fn scroll_is_clamped_to_the_content() {
+        // (content rows, viewport rows, requested, auto scroll) -> (scroll, at bottom)
+        let cases = [
+            (10, 20, 5, false, (0, true)),
+            (50, 20, 10, false, (10, false)),
+            (50, 20, 30, false, (30, true)),
+            (50, 20, 100, false, (30, true)),
+            (50, 20, 0, true, (30, true)),
+            (0, 0, 7, false, (0, true)),
+            (u16::MAX, 1, u16::MAX, false, (u16::MAX - 1, true)),
+        ];
+        for (content, viewport, requested, auto, expected) in cases {
+            assert_eq!(
+                clamp_scroll(content, viewport, requested, auto), expected,
+                "{} rows in {}, asked for {} (auto {})", content, viewport, requested, auto
+            );
+        }
+    }
+}
@@ -23,8 +23,17 @@
 // Module declarations
 pub mod agent_pane;
 pub mod app;
+pub mod gauge;
+pub mod highlight;
+pub mod hyperlinks;
+pub mod inspect;
+pub mod layout;
+pub mod search;
+pub mod terminal_status;
+pub mod tour;
+pub mod vim;
 pub mod widgets;
 
 // Re-exports for public API
-pub use app::{ShadowApp, MessageSource, UnifiedMessage};
+pub use app::{ShadowApp, MessageSource, MessageKind, GlobalFilter, UnifiedMessage};
 pub use agent_pane::AgentPane;
\ No newline at end of file
@@ -0,0 +1,166 @@
+//! # Daegonica Module: tui::search
+//!
+//! **Purpose:** Incremental search of the agent pane (`/`, then `n` / `N`)
+//!
+//! **Context:**
+//! - `ShadowApp` keeps the query on the current `AgentPane` and routes keys here while it's active
+//! - `ShadowApp::pan_messages` calls `annotate` on the finished lines, so code highlighting is kept
+//! - Matching is a case-insensitive substring test on each displayed line
+//!
+//! **Responsibilities:**
+//! - Find case-insensitive matches with byte ranges usable on the original text
+//! - Split a line's spans so each match gets its own highlighted span
+//! - Report which lines matched, for `n` / `N` and the pane title
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::ops::Range;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+/// # find_matches
+///
+/// **Purpose:**
+/// Byte ranges of `query` in `text`, ignoring case.
+///
+/// **Returns:**
+/// `Vec<Range<usize>>` - Non-overlapping matches, left to right (none for an empty query)
+///
+/// **Details:**
+/// Compares lowercased characters one at a time instead of lowercasing the
+/// whole text, since lowercasing can change byte lengths and the ranges must
+/// land on the original text.
+pub fn find_matches(text: &str, query: &str) -> Vec<Range<usize>> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut found = Vec::new();
+    let mut from = 0;
+    for (start, _) in text.char_indices() {
+        if start < from {
+            continue;
+        }
+        if let Some(len) = match_len(&text[start..], &query) {
+            found.push(start..start + len);
+            from = start + len;
+        }
+    }
+    found
+}
+
+/// Bytes of `text` matching `query` (lowercased) from its start, if it matches
+fn match_len(text: &str, query: &[char]) -> Option<usize> {
+    let mut want = query.iter();
+    for (offset, c) in text.char_indices() {
+        for lower in c.to_lowercase() {
+            if want.next() != Some(&lower) {
+                return None;
+            }
+        }
+        if want.len() == 0 {
+            return Some(offset + c.len_utf8());
+        }
+    }
+    None
+}
+
+/// # highlight
+///
+/// **Purpose:**
+/// Restyles the parts of `line` covered by `ranges`, keeping every other style.
+///
+/// **Parameters:**
+/// - `line`: A rendered line
+/// - `ranges`: Byte ranges in the line's text, sorted and non-overlapping
+/// - `style`: Patched over the original style inside each range
+///
+/// **Details:**
+/// A match can cross span boundaries (e.g. in a highlighted code line), so
+/// spans are cut at both ends of every range.
+pub fn highlight<'a>(line: Line<'a>, ranges: &[Range<usize>], style: Style) -> Line<'a> {
+    let Line { spans: original, style: line_style, alignment } = line;
+    let mut spans = Vec::with_capacity(original.len() + ranges.len() * 2);
+    let mut ranges = ranges.iter().peekable();
+    let mut offset = 0;
+    for span in original {
+        let len = span.content.len();
+        if ranges.peek().is_none_or(|range| range.start >= offset + len) {
+            offset += len;
+            spans.push(span);
+            continue;
+        }
+        let text = span.content.as_ref();
+        let mut cut = 0;
+        while cut < len {
+            match ranges.peek() {
+                Some(range) if range.start < offset + len => {
+                    let start = range.start.max(offset + cut) - offset;
+                    let end = range.end.min(offset + len) - offset;
+                    if start > cut {
+                        spans.push(Span::styled(text[cut..start].to_string(), span.style));
+                    }
+                    spans.push(Span::styled(text[start..end].to_string(), span.style.patch(style)));
+                    cut = end;
+                    if range.end <= offset + len {
+                        ranges.next();
+                    }
+                }
+                _ => {
+                    spans.push(Span::styled(text[cut..].to_string(), span.style));
+                    cut = len;
+                }
+            }
+        }
+        offset += len;
+    }
+    Line { spans, style: line_style, alignment }
+}
+
+/// # annotate
+///
+/// **Purpose:**
+/// Highlights every match of `query` in `lines`.
+///
+/// **Parameters:**
+/// - `lines`: The pane's rendered lines
+/// - `query`: Search text
+/// - `current`: Index (among matching lines) of the match `n` / `N` is on; None
+///   means the last one
+///
+/// **Returns:**
+/// `Vec<usize>` - Indexes of the lines that matched, top to bottom
+///
+/// **Details:**
+/// Matches are black on yellow; the current line's matches are black on
+/// orange and bold, so it stands out among the rest.
+pub fn annotate(lines: &mut [Line], query: &str, current: Option<usize>) -> Vec<usize> {
+    let found: Vec<(usize, Vec<Range<usize>>)> = lines.iter()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let text: String = line.spans.iter().map(|span| span.content.as_ref()).collect();
+            let ranges = find_matches(&text, query);
+            (!ranges.is_empty()).then_some((index, ranges))
+        })
+        .collect();
+    let Some(last) = found.len().checked_sub(1) else {
+        return Vec::new();
+    };
+
+    let current = current.unwrap_or(last).min(last);
+    let matched = Style::default().fg(Color::Black).bg(Color::Yellow);
+    let selected = Style::default().fg(Color::Black).bg(Color::Rgb(255, 140, 0)).add_modifier(Modifier::BOLD);
+    for (hit, (index, ranges)) in found.iter().enumerate() {
+        let style = if hit == current { selected } else { matched };
+        lines[*index] = highlight(std::mem::take(&mut lines[*index]), ranges, style);
+    }
+    found.into_iter().map(|(index, _)| index).collect()
+}
@@ -0,0 +1,333 @@
+//! # Daegonica Module: tui::terminal_status
+//!
+//! **Purpose:** Show whether Shadow is busy outside its own window: the terminal title and a tmux status file
+//!
+//! **Context:**
+//! - Off unless `tui.set_terminal_title` or `tui.tmux_status` is set
+//! - The TUI loop polls after each draw, so nothing is written while ratatui is mid-frame
+//! - Escapes go to the same writer ratatui draws with; the tmux file is for `#(cat ...)` in `status-right`
+//!
+//! **Responsibilities:**
+//! - Derive one activity state (streaming / error / idle) from the open agents
+//! - Emit only on state changes, at most once a second
+//! - Save the terminal's title on start and put it back on exit
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::time::{Duration, Instant};
+use crate::prelude::*;
+use crate::utilities::accessibility::glyph;
+
+/// Fewest seconds between two emitted updates
+const MIN_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// xterm: push the current window and icon title onto the terminal's title stack
+const PUSH_TITLE: &str = "\x1b[22;0t";
+/// xterm: pop the saved title back
+const POP_TITLE: &str = "\x1b[23;0t";
+
+/// # Activity
+///
+/// **Summary:**
+/// What Shadow is doing, as far as someone in another tmux window cares.
+///
+/// **Variants:**
+/// - `Streaming`: Some agent is waiting on a reply
+/// - `Error`: Nothing is running and some agent's last request failed
+/// - `Idle`: Nothing is running
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activity {
+    Streaming,
+    Error,
+    Idle,
+}
+
+impl Activity {
+    /// # of_agents
+    ///
+    /// **Purpose:**
+    /// The activity shown for a set of open agents.
+    ///
+    /// **Details:**
+    /// A reply in flight anywhere wins over a failure, since the failure is
+    /// old news once something new is running.
+    pub fn of_agents<'a>(agents: impl IntoIterator<Item = &'a AgentInfo>) -> Activity {
+        let mut failed = false;
+        for agent in agents {
+            if agent.is_waiting {
+                return Activity::Streaming;
+            }
+            failed |= agent.last_failure.is_some();
+        }
+        if failed { Activity::Error } else { Activity::Idle }
+    }
+
+    /// The status string, e.g. "shadow ● streaming…" (plain text in accessible mode)
+    pub fn status(&self) -> String {
+        let state = match self {
+            Activity::Streaming => glyph("● streaming…", "streaming"),
+            Activity::Error => glyph("⚠ error", "error"),
+            Activity::Idle => glyph("✓ idle", "idle"),
+        };
+        format!("shadow {}", state)
+    }
+}
+
+/// # TerminalStatus
+///
+/// **Summary:**
+/// Decides when the outside-world status changes, and writes it.
+///
+/// **Fields:**
+/// - `title`: Set the terminal title (`tui.set_terminal_title`)
+/// - `tmux_path`: Where to write the tmux status string (`tui.tmux_status`), if anywhere
+/// - `shown`: The activity last emitted
+/// - `shown_at`: When it was emitted
+#[derive(Debug, Clone)]
+pub struct TerminalStatus {
+    title: bool,
+    tmux_path: Option<PathBuf>,
+    shown: Option<Activity>,
+    shown_at: Option<Instant>,
+}
+
+impl TerminalStatus {
+    /// Sets the title if `title`, writes the tmux status to `tmux_path` if given
+    pub fn new(title: bool, tmux_path: Option<PathBuf>) -> Self {
+        Self { title, tmux_path, shown: None, shown_at: None }
+    }
+
+    /// From `tui` config; None when both outputs are off
+    pub fn from_config() -> Option<Self> {
+        let config = &GLOBAL_CONFIG.tui;
+        let tmux_path = config.tmux_status.then(|| PathBuf::from(&config.tmux_status_path));
+        (config.set_terminal_title || tmux_path.is_some()).then(|| Self::new(config.set_terminal_title, tmux_path))
+    }
+
+    /// # next_update
+    ///
+    /// **Purpose:**
+    /// The activity to emit now, if any.
+    ///
+    /// **Parameters:**
+    /// - `current`: The activity right now
+    /// - `now`: The current time
+    ///
+    /// **Returns:**
+    /// `Option<Activity>` - Some when `current` differs from what's shown and a
+    /// second has passed since the last update
+    ///
+    /// **Details:**
+    /// A change inside the second isn't lost: the next poll after the second
+    /// is up emits whatever the state is by then. A short flicker that
+    /// settles back to the shown state never appears at all.
+    pub fn next_update(&mut self, current: Activity, now: Instant) -> Option<Activity> {
+        if self.shown == Some(current) {
+            return None;
+        }
+        if self.shown_at.is_some_and(|at| now.duration_since(at) < MIN_UPDATE_INTERVAL) {
+            return None;
+        }
+        self.shown = Some(current);
+        self.shown_at = Some(now);
+        Some(current)
+    }
+
+    /// # start
+    ///
+    /// **Purpose:**
+    /// Saves the terminal's title so `finish` can put it back.
+    pub fn start(&self, out: &mut impl Write) -> io::Result<()> {
+        if self.title {
+            out.write_all(PUSH_TITLE.as_bytes())?;
+            out.flush()?;
+        }
+        Ok(())
+    }
+
+    /// # emit
+    ///
+    /// **Purpose:**
+    /// Writes `activity` to the terminal title and/or the tmux status file.
+    ///
+    /// **Parameters:**
+    /// - `out`: The writer ratatui draws to, called between frames
+    /// - `activity`: What to show
+    ///
+    /// **Errors / Failures:**
+    /// - Writing the title fails; a tmux file that can't be written is only logged
+    pub fn emit(&self, out: &mut impl Write, activity: Activity) -> io::Result<()> {
+        let status = activity.status();
+        if self.title {
+            crossterm::execute!(out, crossterm::terminal::SetTitle(&status))?;
+        }
+        if let Some(path) = &self.tmux_path
+            && let Err(e) = fs::write(path, format!("{}\n", status))
+        {
+            log_error!("Failed to write tmux status to {}: {}", path.display(), e);
+        }
+        Ok(())
+    }
+
+    /// # finish
+    ///
+    /// **Purpose:**
+    /// Puts the saved title back and removes the tmux status file.
+    pub fn finish(&self, out: &mut impl Write) -> io::Result<()> {
+        if let Some(path) = &self.tmux_path {
+            let _ = fs::remove_file(path);
+        }
+        if self.title {
+            out.write_all(POP_TITLE.as_bytes())?;
+            out.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::CrosstermBackend;
+    use ratatui::layout::Rect;
+    use ratatui::{TerminalOptions, Viewport};
+    use crate::llm::AnyClient;
+    use crate::llm::scripted::ScriptedClient;
+    use crate::test_support::{test_personas_root, ScratchDir};
+    use crate::tui::app::ShadowApp;
+    use crate::utilities::explain::FailedRequest;
+
+    /// What SetTitle writes for `status`
+    fn title(status: &str) -> Vec<u8> {
+        format!("\x1b]0;{}\x07", status).into_bytes()
+    }
+
+    /// Output not yet flushed, and each flushed batch
+    type Batches = (Vec<u8>, Vec<Vec<u8>>);
+
+    /// A writer that keeps each flushed batch of output apart; clones share the batches
+    #[derive(Clone, Default)]
+    struct Capture(std::rc::Rc<std::cell::RefCell<Batches>>);
+
+    impl Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().0.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            let (pending, flushed) = &mut *self.0.borrow_mut();
+            if !pending.is_empty() {
+                flushed.push(std::mem::take(pending));
+            }
+            Ok(())
+        }
+    }
+
+    fn agent(waiting: bool, failed: bool) -> AgentInfo {
+        test_personas_root();
+        let persona = Persona::from_yaml_str("name: Titled\nsystem_prompt: Be brief.\nenable_history: false\n").unwrap();
+        let mut agent = AgentInfo::with_client(uuid::Uuid::new_v4(), Arc::new(persona), AnyClient::Scripted(ScriptedClient::default()));
+        agent.is_waiting = waiting;
+        agent.last_failure = failed.then(|| FailedRequest { error: "HTTP 500".to_string(), trigger: None });
+        agent
+    }
+
+    #[test]
+    fn the_activity_comes_from_every_open_agent() {
+        let cases = [
+            (vec![], Activity::Idle),
+            (vec![(false, false), (false, false)], Activity::Idle),
+            (vec![(false, false), (false, true)], Activity::Error),
+            (vec![(false, true), (true, false)], Activity::Streaming),
+            (vec![(true, true)], Activity::Streaming),
+        ];
+        for (states, expected) in cases {
+            let agents: Vec<AgentInfo> = states.iter().map(|(waiting, failed)| agent(*waiting, *failed)).collect();
+            assert_eq!(Activity::of_agents(&agents), expected, "{:?}", states);
+        }
+    }
+
+    #[test]
+    fn each_activity_has_its_status_string() {
+        assert_eq!(Activity::Streaming.status(), "shadow ● streaming…");
+        assert_eq!(Activity::Error.status(), "shadow ⚠ error");
+        assert_eq!(Activity::Idle.status(), "shadow ✓ idle");
+    }
+
+    #[test]
+    fn updates_follow_changes_at_most_once_a_second() {
+        let mut status = TerminalStatus::new(true, None);
+        let start = Instant::now();
+        let at = |millis: u64| start + Duration::from_millis(millis);
+
+        assert_eq!(status.next_update(Activity::Idle, at(0)), Some(Activity::Idle), "the first poll always shows");
+        assert_eq!(status.next_update(Activity::Idle, at(5000)), None, "no change, no update");
+        assert_eq!(status.next_update(Activity::Streaming, at(5100)), Some(Activity::Streaming));
+        assert_eq!(status.next_update(Activity::Error, at(5600)), None, "inside the second");
+        assert_eq!(status.next_update(Activity::Error, at(6100)), Some(Activity::Error), "caught up after it");
+        assert_eq!(status.next_update(Activity::Idle, at(6500)), None);
+        assert_eq!(status.next_update(Activity::Error, at(7200)), None, "a flicker that settled back never shows");
+    }
+
+    #[test]
+    fn the_title_is_saved_set_and_restored() {
+        let mut out = Vec::new();
+        let status = TerminalStatus::new(true, None);
+        status.start(&mut out).unwrap();
+        status.emit(&mut out, Activity::Streaming).unwrap();
+        status.finish(&mut out).unwrap();
+        assert_eq!(out, [PUSH_TITLE.as_bytes(), &title("shadow ● streaming…"), POP_TITLE.as_bytes()].concat());
+
+        let mut untouched = Vec::new();
+        let tmux_only = TerminalStatus::new(false, None);
+        tmux_only.start(&mut untouched).unwrap();
+        tmux_only.emit(&mut untouched, Activity::Idle).unwrap();
+        tmux_only.finish(&mut untouched).unwrap();
+        assert!(untouched.is_empty(), "without set_terminal_title nothing reaches the terminal");
+    }
+
+    #[test]
+    fn the_tmux_file_holds_the_latest_status_until_exit() {
+        let scratch = ScratchDir::new("tmux-status");
+        let path = scratch.join("shadow.status");
+        let status = TerminalStatus::new(false, Some(path.clone()));
+        status.emit(&mut io::sink(), Activity::Streaming).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "shadow ● streaming…\n");
+        status.emit(&mut io::sink(), Activity::Idle).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "shadow ✓ idle\n");
+        status.finish(&mut io::sink()).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn updates_go_between_frames_never_inside_one() {
+        test_personas_root();
+        let mut app = ShadowApp::new();
+        let viewport = Viewport::Fixed(Rect::new(0, 0, 80, 24));
+        let capture = Capture::default();
+        let mut terminal = Terminal::with_options(CrosstermBackend::new(capture.clone()), TerminalOptions { viewport }).unwrap();
+        let mut status = TerminalStatus::new(true, None);
+        let start = Instant::now();
+
+        // The main loop: draw, then poll the status on the same writer
+        for (n, activity) in [Activity::Idle, Activity::Idle, Activity::Streaming, Activity::Error].into_iter().enumerate() {
+            terminal.draw(|frame| app.draw(frame)).unwrap();
+            if let Some(activity) = status.next_update(activity, start + Duration::from_secs(n as u64 * 2)) {
+                status.emit(terminal.backend_mut(), activity).unwrap();
+            }
+        }
+
+        let batches = capture.0.borrow().1.clone();
+        let titles: Vec<&Vec<u8>> = batches.iter().filter(|batch| batch.windows(4).any(|w| w == b"\x1b]0;")).collect();
+        assert_eq!(titles, [&title("shadow ✓ idle"), &title("shadow ● streaming…"), &title("shadow ⚠ error")], "each title is a batch of its own");
+        assert!(batches.first().is_some_and(|first| !titles.contains(&first)), "a frame is drawn first");
+    }
+}
@@ -0,0 +1,307 @@
+//! # Daegonica Module: tui::tour
+//!
+//! **Purpose:** Guided first-run tour of the TUI
+//!
+//! **Context:**
+//! - Shown on the first TUI launch (until `{root}/state/tour_seen` exists) and by the `tour` command
+//! - Drawn by `ShadowApp::draw` as a small hint box over the region it talks about
+//! - Advanced from `ShadowApp::run_action` when the suggested command actually runs
+//!
+//! **Responsibilities:**
+//! - Define the tour as data: anchor, text, and the actions that complete each step
+//! - Track progress through the steps
+//! - Place a hint box next to its anchor for a given layout
+//! - Remember that the tour was finished or dismissed
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use ratatui::layout::Rect;
+use crate::{log_error, log_info};
+use crate::agent_history::persistence;
+use crate::commands::CommandResult;
+use crate::persona::{state_path, writable_state_path};
+use crate::tui::layout::LayoutPlan;
+
+/// Marker written once the tour has been finished or dismissed (`{root}/state/tour_seen`)
+pub(crate) const TOUR_MARKER_FILE: &str = "tour_seen";
+
+/// Widest a hint box gets (including borders)
+const HINT_MAX_WIDTH: u16 = 52;
+
+/// # TourAnchor
+///
+/// **Summary:**
+/// The part of the screen a step talks about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TourAnchor {
+    Input,
+    AgentPane,
+    GlobalPane,
+}
+
+/// # TourStep
+///
+/// **Summary:**
+/// One hint of the tour.
+///
+/// **Fields:**
+/// - `anchor`: Where the hint box is drawn
+/// - `text`: What the hint says
+/// - `completed_by`: InputAction variant names (as in `profile`) that finish the step when they succeed
+#[derive(Debug, Clone, Copy)]
+pub struct TourStep {
+    pub anchor: TourAnchor,
+    pub text: &'static str,
+    pub completed_by: &'static [&'static str],
+}
+
+impl TourStep {
+    /// Whether running `action` (an InputAction variant name) with `result` completes this step
+    pub fn is_completed_by(&self, action: &str, result: &CommandResult) -> bool {
+        !matches!(result, CommandResult::Error(_)) && self.completed_by.contains(&action)
+    }
+}
+
+/// The tour, in order
+pub const TOUR_STEPS: &[TourStep] = &[
+    TourStep {
+        anchor: TourAnchor::Input,
+        text: "This is the input. Type a message and press Enter to talk to Shadow.",
+        completed_by: &["SendAsMessage"],
+    },
+    TourStep {
+        anchor: TourAnchor::AgentPane,
+        text: "Replies stream into this pane (Up/Down and PgUp/PgDn scroll). Open a second agent with `new friday`; `list` shows every persona.",
+        completed_by: &["NewAgent"],
+    },
+    TourStep {
+        anchor: TourAnchor::AgentPane,
+        text: "Each agent has its own pane: Tab / Shift+Tab switch, Ctrl+W closes one. Type `status` to see them all.",
+        completed_by: &["AgentStatus", "ListAgents"],
+    },
+    TourStep {
+        anchor: TourAnchor::GlobalPane,
+        text: "Command output and background notices land in the System pane. Try `global filter errors`, then `global filter off`.",
+        completed_by: &["GlobalFilter"],
+    },
+    TourStep {
+        anchor: TourAnchor::Input,
+        text: "History is saved after each reply. `historyinfo` shows what this agent remembers; `tour` replays this guide.",
+        completed_by: &["HistoryInfo"],
+    },
+];
+
+/// # Tour
+///
+/// **Summary:**
+/// Progress through `TOUR_STEPS`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tour {
+    step: usize,
+}
+
+impl Tour {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The step being shown, None once the tour is over
+    pub fn current(&self) -> Option<&'static TourStep> {
+        TOUR_STEPS.get(self.step)
+    }
+
+    /// 1-based position of the current step and the step count
+    pub fn position(&self) -> (usize, usize) {
+        ((self.step + 1).min(TOUR_STEPS.len()), TOUR_STEPS.len())
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current().is_none()
+    }
+
+    /// # record_action
+    ///
+    /// **Purpose:**
+    /// Moves to the next step if `action` is what the current step asked for and it didn't fail.
+    ///
+    /// **Returns:**
+    /// `bool` - true if the tour advanced
+    pub fn record_action(&mut self, action: &str, result: &CommandResult) -> bool {
+        let completes = self.current().is_some_and(|step| step.is_completed_by(action, result));
+        if completes {
+            self.step += 1;
+        }
+        completes
+    }
+
+    /// Moves to the next step without doing it
+    pub fn skip(&mut self) {
+        self.step = (self.step + 1).min(TOUR_STEPS.len());
+    }
+}
+
+/// # hint_area
+///
+/// **Purpose:**
+/// Where to draw a step's hint box for the current layout.
+///
+/// **Parameters:**
+/// - `anchor`: The region the step talks about
+/// - `plan`: This frame's layout
+/// - `width`: Desired box width (clamped to the anchor region)
+/// - `height`: Desired box height (clamped to the anchor region)
+///
+/// **Details:**
+/// Input hints sit just above the input box; pane hints sit at the top of their
+/// pane, inset by one cell. Global hints fall back to the agent pane's top-right
+/// corner in single-pane mode, where the System pane isn't on screen.
+pub fn hint_area(anchor: TourAnchor, plan: &LayoutPlan, width: u16, height: u16) -> Rect {
+    let region = anchor_region(anchor, plan);
+    let width = width.min(region.width.saturating_sub(2)).max(1);
+    let height = height.min(region.height.saturating_sub(2)).max(1);
+
+    let x = match anchor {
+        TourAnchor::GlobalPane if plan.single => region.x + region.width.saturating_sub(width + 1),
+        _ => region.x + 1,
+    };
+    let y = match anchor {
+        TourAnchor::Input => (region.y + region.height).saturating_sub(height + 1).max(region.y),
+        _ => region.y + 1,
+    };
+    Rect { x, y, width, height }
+}
+
+/// The pane a hint box is drawn inside
+pub fn anchor_region(anchor: TourAnchor, plan: &LayoutPlan) -> Rect {
+    match anchor {
+        TourAnchor::Input | TourAnchor::AgentPane => plan.agent_area,
+        TourAnchor::GlobalPane if plan.single => plan.agent_area,
+        TourAnchor::GlobalPane => plan.global_area,
+    }
+}
+
+/// Hint box width inside a region of `region_width` columns, including borders
+pub fn hint_width(region_width: u16) -> u16 {
+    HINT_MAX_WIDTH.min(region_width.saturating_sub(2))
+}
+
+/// # wrap_words
+///
+/// **Purpose:**
+/// Greedy word wrap for hint text (words longer than `width` get their own line).
+pub fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Whether the tour was already finished or dismissed on this machine
+pub fn tour_seen() -> bool {
+    state_path(TOUR_MARKER_FILE).exists()
+}
+
+/// # mark_tour_seen
+///
+/// **Purpose:**
+/// Stops the tour from starting automatically again (failures are only logged).
+pub fn mark_tour_seen() {
+    match persistence::auto_write(|| Ok(std::fs::write(writable_state_path(TOUR_MARKER_FILE)?, b"")?)) {
+        Ok(Some(())) => log_info!("Tour marked as seen"),
+        Ok(None) => log_info!("Tour marker not saved: persistence suspended"),
+        Err(e) => log_error!("Failed to save tour marker: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persona::STATE_DIR;
+    use crate::test_support::test_personas_root;
+
+    const OK: CommandResult = CommandResult::Continue;
+
+    fn failed() -> CommandResult {
+        CommandResult::Error("No agent open".to_string())
+    }
+
+    #[test]
+    fn each_step_is_completed_only_by_its_actions_succeeding() {
+        for step in TOUR_STEPS {
+            for action in step.completed_by {
+                assert!(step.is_completed_by(action, &OK), "{} should complete \"{}\"", action, step.text);
+                assert!(step.is_completed_by(action, &CommandResult::Shutdown));
+                assert!(!step.is_completed_by(action, &failed()), "a failed {} completed \"{}\"", action, step.text);
+            }
+            assert!(!step.is_completed_by("Quit", &OK));
+            assert!(!step.is_completed_by("", &OK));
+        }
+    }
+
+    #[test]
+    fn completion_matches_the_variant_name_exactly() {
+        let step = &TOUR_STEPS[1];
+        assert!(step.is_completed_by("NewAgent", &OK));
+        assert!(!step.is_completed_by("newagent", &OK));
+        assert!(!step.is_completed_by("NewAgentFromDraft", &OK));
+    }
+
+    #[test]
+    fn the_tour_advances_through_every_step_in_order() {
+        let mut tour = Tour::new();
+        for (index, step) in TOUR_STEPS.iter().enumerate() {
+            assert_eq!(tour.position(), (index + 1, TOUR_STEPS.len()));
+            assert!(!tour.record_action(step.completed_by[0], &failed()));
+            assert_eq!(tour.current().map(|s| s.text), Some(step.text));
+            assert!(tour.record_action(step.completed_by[0], &OK));
+        }
+        assert!(tour.is_finished());
+        assert!(!tour.record_action("SendAsMessage", &OK));
+    }
+
+    #[test]
+    fn a_later_steps_action_does_not_skip_ahead() {
+        let mut tour = Tour::new();
+        assert!(!tour.record_action("HistoryInfo", &OK));
+        assert!(!tour.record_action("NewAgent", &OK));
+        assert_eq!(tour.position().0, 1);
+        assert!(tour.record_action("SendAsMessage", &OK));
+        assert!(tour.record_action("NewAgent", &OK));
+    }
+
+    #[test]
+    fn skipping_stops_at_the_end() {
+        let mut tour = Tour::new();
+        for _ in 0..TOUR_STEPS.len() + 2 {
+            tour.skip();
+        }
+        assert!(tour.is_finished());
+        assert_eq!(tour.position(), (TOUR_STEPS.len(), TOUR_STEPS.len()));
+    }
+
+    #[test]
+    fn the_marker_lives_under_the_personas_root() {
+        let root = test_personas_root();
+        mark_tour_seen();
+
+        assert!(root.join(STATE_DIR).join(TOUR_MARKER_FILE).is_file());
+        assert!(tour_seen());
+    }
+}
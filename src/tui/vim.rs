@@ -0,0 +1,266 @@
+//! # Daegonica Module: tui::vim
+//!
+//! **Purpose:** Vim-style editing of the input box
+//!
+//! **Context:**
+//! - Toggled with Ctrl+V; off by default, when the input box behaves as it always has
+//! - `ShadowApp::handle_key` sends Normal-mode keys here; Insert mode types as usual
+//! - Pure editing logic over the input text and a byte cursor, no rendering
+//!
+//! **Responsibilities:**
+//! - Define `InputMode` (Insert / Normal) and the Vim state (pending operator, register)
+//! - Apply Normal-mode keys: motions, `dd`, `yy`, `p`, and `i` / `a` into Insert mode
+//! - Keep the cursor on a character while in Normal mode
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+/// # InputMode
+///
+/// **Summary:**
+/// Which Vim mode the input box is in.
+///
+/// **Variants:**
+/// - `Insert`: Keys type text (the only mode while Vim mode is off)
+/// - `Normal`: Keys move the cursor and edit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputMode {
+    #[default]
+    Insert,
+    Normal,
+}
+
+impl InputMode {
+    /// The label shown under the input box
+    pub fn label(&self) -> &'static str {
+        match self {
+            InputMode::Insert => "-- INSERT --",
+            InputMode::Normal => "-- NORMAL --",
+        }
+    }
+}
+
+/// # NormalOutcome
+///
+/// **Summary:**
+/// What a Normal-mode key asks of the app.
+///
+/// **Variants:**
+/// - `Stay`: Handled (or ignored); stay in Normal mode
+/// - `Insert`: Switch to Insert mode at the cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalOutcome {
+    Stay,
+    Insert,
+}
+
+/// # VimState
+///
+/// **Summary:**
+/// Vim mode's on/off switch and what it remembers between keys.
+///
+/// **Fields:**
+/// - `enabled`: Whether Vim mode is on (Ctrl+V)
+/// - `pending`: First key of a two-key command (`d` of `dd`, `y` of `yy`)
+/// - `register`: Text from the last `yy` or `dd`, put back by `p`
+#[derive(Debug, Clone, Default)]
+pub struct VimState {
+    pub enabled: bool,
+    pending: Option<char>,
+    register: String,
+}
+
+impl VimState {
+    /// The half-typed command, for the mode line
+    pub fn pending(&self) -> Option<char> {
+        self.pending
+    }
+
+    pub fn cancel_pending(&mut self) {
+        self.pending = None;
+    }
+
+    /// # normal_key
+    ///
+    /// **Purpose:**
+    /// Applies one Normal-mode key to the input.
+    ///
+    /// **Parameters:**
+    /// - `key`: The character typed
+    /// - `input`: The input text
+    /// - `cursor`: Byte offset of the cursor, moved or kept on a character
+    ///
+    /// **Returns:**
+    /// `NormalOutcome` - Whether to switch to Insert mode
+    ///
+    /// **Details:**
+    /// - `h` / `l` move within a line, `j` / `k` between lines, keeping the column
+    /// - `w` / `b` jump to the next / previous word start, `0` / `$` to the line's ends
+    /// - `dd` clears the input and `yy` copies it, both into the register; `p` puts
+    ///   the register after the cursor
+    /// - `i` inserts at the cursor, `a` after it
+    ///
+    /// A key that doesn't complete a pending `d` / `y` cancels it and is then
+    /// handled on its own. Unknown keys do nothing.
+    pub fn normal_key(&mut self, key: char, input: &mut String, cursor: &mut usize) -> NormalOutcome {
+        let pos = normal_cursor(input, *cursor);
+        match (self.pending.take(), key) {
+            (Some('d'), 'd') => {
+                self.register = std::mem::take(input);
+                *cursor = 0;
+                return NormalOutcome::Stay;
+            }
+            (Some('y'), 'y') => {
+                self.register = input.clone();
+                *cursor = pos;
+                return NormalOutcome::Stay;
+            }
+            _ => {}
+        }
+
+        *cursor = match key {
+            'd' | 'y' => {
+                self.pending = Some(key);
+                pos
+            }
+            'h' => match input[..pos].chars().next_back() {
+                Some(prev) if prev != '\n' => pos - prev.len_utf8(),
+                _ => pos,
+            },
+            'l' => {
+                let next = next_boundary(input, pos);
+                let on_newline = input[pos..].starts_with('\n');
+                if next < input.len() && !on_newline && !input[next..].starts_with('\n') { next } else { pos }
+            }
+            '0' => line_start(input, pos),
+            '$' => line_last_char(input, pos),
+            'w' => word_forward(input, pos),
+            'b' => word_backward(input, pos),
+            'j' => match input[pos..].find('\n') {
+                Some(offset) => move_to_column(input, pos + offset + 1, column(input, pos)),
+                None => pos,
+            },
+            'k' => match line_start(input, pos).checked_sub(1) {
+                Some(newline) => move_to_column(input, line_start(input, newline), column(input, pos)),
+                None => pos,
+            },
+            'p' if !self.register.is_empty() => {
+                let at = if input.is_empty() { 0 } else { next_boundary(input, pos) };
+                input.insert_str(at, &self.register);
+                at + self.register.len() - self.register.chars().next_back().map_or(0, char::len_utf8)
+            }
+            'i' => {
+                *cursor = pos;
+                return NormalOutcome::Insert;
+            }
+            'a' => {
+                *cursor = if input.is_empty() { 0 } else { next_boundary(input, pos) };
+                return NormalOutcome::Insert;
+            }
+            _ => pos,
+        };
+        NormalOutcome::Stay
+    }
+}
+
+/// # normal_cursor
+///
+/// **Purpose:**
+/// Where the cursor sits in Normal mode: on a character, never past the end.
+///
+/// **Details:**
+/// Clamps to the text and onto a character boundary, so a cursor left over
+/// from edits elsewhere is always usable.
+pub fn normal_cursor(input: &str, cursor: usize) -> usize {
+    let mut pos = cursor.min(input.len());
+    while !input.is_char_boundary(pos) {
+        pos -= 1;
+    }
+    if pos == input.len() {
+        pos = input[..pos].chars().next_back().map_or(0, |last| pos - last.len_utf8());
+    }
+    pos
+}
+
+/// # leave_insert
+///
+/// **Purpose:**
+/// The cursor after Esc: one character back, as in Vim, unless at a line start.
+pub fn leave_insert(input: &str, cursor: usize) -> usize {
+    let pos = cursor.min(input.len());
+    let pos = match input[..pos].chars().next_back() {
+        Some(prev) if prev != '\n' => pos - prev.len_utf8(),
+        _ => pos,
+    };
+    normal_cursor(input, pos)
+}
+
+fn next_boundary(input: &str, pos: usize) -> usize {
+    input[pos..].chars().next().map_or(pos, |c| pos + c.len_utf8())
+}
+
+fn line_start(input: &str, pos: usize) -> usize {
+    input[..pos].rfind('\n').map_or(0, |newline| newline + 1)
+}
+
+/// The start of the line's last character (the line start for an empty line)
+fn line_last_char(input: &str, pos: usize) -> usize {
+    let start = line_start(input, pos);
+    let end = input[pos..].find('\n').map_or(input.len(), |offset| pos + offset);
+    input[start..end].chars().next_back().map_or(start, |last| end - last.len_utf8())
+}
+
+/// Characters between the line start and `pos`
+fn column(input: &str, pos: usize) -> usize {
+    input[line_start(input, pos)..pos].chars().count()
+}
+
+/// The position `column` characters into the line starting at `start`, or its last character
+fn move_to_column(input: &str, start: usize, column: usize) -> usize {
+    let last = line_last_char(input, start);
+    input[start..].char_indices()
+        .map(|(offset, _)| start + offset)
+        .take_while(|at| *at <= last)
+        .nth(column)
+        .unwrap_or(last)
+}
+
+/// Vim's character classes for word motions: blank, word (alphanumeric or `_`), punctuation
+fn class(c: char) -> u8 {
+    if c.is_whitespace() {
+        0
+    } else if c.is_alphanumeric() || c == '_' {
+        1
+    } else {
+        2
+    }
+}
+
+fn word_forward(input: &str, pos: usize) -> usize {
+    let mut chars = input[pos..].char_indices().map(|(offset, c)| (pos + offset, c)).peekable();
+    if let Some(&(_, first)) = chars.peek() {
+        let start_class = class(first);
+        if start_class != 0 {
+            while chars.next_if(|(_, c)| class(*c) == start_class).is_some() {}
+        }
+    }
+    while chars.next_if(|(_, c)| class(*c) == 0).is_some() {}
+    chars.next().map_or_else(|| normal_cursor(input, input.len()), |(at, _)| at)
+}
+
+fn word_backward(input: &str, pos: usize) -> usize {
+    let mut chars = input[..pos].char_indices().rev().peekable();
+    while chars.next_if(|(_, c)| class(*c) == 0).is_some() {}
+    let Some((mut at, first)) = chars.next() else {
+        return 0;
+    };
+    while let Some((prev, _)) = chars.next_if(|(_, c)| class(*c) == class(first)) {
+        at = prev;
+    }
+    at
+}
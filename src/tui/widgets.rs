@@ -8,7 +8,10 @@
 //! - Used by app.rs to draw the interface
 //!
 //! **Responsibilities:**
-//! - Render scrollable message sections
+//! - Render scrollable message sections, optionally with a context gauge
+//! - Render the agent tab bar
+//! - Render the Vim mode line
+//! - Style assistant message lines, setting fenced code apart
 //! - Format text with proper styling
 //! - Calculate widget dimensions
 //! - Handle text wrapping
@@ -21,13 +24,28 @@
 //! This file is part of the Daegonica Software codebase.
 //! ---------------------------------------------------------------
 
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
-    text::{Line, Text},
-    widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Padding, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap},
     Frame,
 };
+use uuid::Uuid;
+use crate::prelude::*;
+use crate::utilities::accessibility::{self, glyph};
+use crate::tui::vim::InputMode;
+use crate::tui::gauge::ContextUsage;
+
+/// Frames of the spinner shown on a waiting agent's tab
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+/// How long each spinner frame shows
+const SPINNER_FRAME_MS: u128 = 100;
+/// Background behind fenced code in assistant messages
+pub const CODE_BLOCK_BG: Color = Color::Rgb(30, 30, 30);
+
 /// # render_message_section
 ///
 /// **Purpose:**
@@ -39,6 +57,7 @@ use ratatui::{
 /// - `lines`: Vector of formatted lines to display
 /// - `title`: Title to display in the border
 /// - `scroll`: Mutable reference to scroll position (updated if out of bounds)
+/// - `border_color`: Border and title color, e.g. the agent's `tui_color`; None uses `tui.border_color`
 ///
 /// **Returns:**
 /// `bool` - true if scroll is at the actual bottom after clamping, false otherwise
@@ -46,40 +65,55 @@ use ratatui::{
 /// **Details:**
 /// - Automatically bounds scroll position to valid range
 /// - Renders scrollbar with up/down arrows and position indicator
-/// - Applies text wrapping and orange border styling
+/// - Applies text wrapping and border styling
 pub fn render_message_section(
     frame: &mut Frame,
     area: Rect,
     lines: Vec<Line>,
     title: &String,
     scroll: &mut u16,
+    border_color: Option<Color>,
+) -> bool {
+    render_message_section_with_gauge(frame, area, lines, title, scroll, border_color, None)
+}
+
+/// # render_message_section_with_gauge
+///
+/// **Purpose:**
+/// `render_message_section` with a context gauge in the column just inside the right border.
+///
+/// **Parameters:**
+/// - `gauge`: Context use to show; None draws the plain section
+///
+/// **Returns:**
+/// `bool` - true if scroll is at the actual bottom after clamping, false otherwise
+///
+/// **Details:**
+/// The text gives up that column so the gauge never covers it, and the
+/// scrollbar keeps the border column, so the two sit side by side. Too small
+/// a pane skips the gauge.
+pub fn render_message_section_with_gauge(
+    frame: &mut Frame,
+    area: Rect,
+    lines: Vec<Line>,
+    title: &str,
+    scroll: &mut u16,
+    border_color: Option<Color>,
+    gauge: Option<ContextUsage>,
 ) -> bool {
+    let gauge = gauge.filter(|_| area.width > 6 && area.height > 2);
+    let gutter = u16::from(gauge.is_some());
 
     let visible_height = area.height.saturating_sub(2);
-    let content_width = area.width.saturating_sub(2) as usize; // Account for borders
-    
-    // Calculate actual wrapped line count
-    let mut wrapped_line_count = 0u16;
-    for line in &lines {
-        let line_width = line.width();
-        if line_width == 0 {
-            wrapped_line_count += 1; // Empty lines still take 1 line
-        } else {
-            // Calculate how many visual lines this Line will wrap into
-            let visual_lines = (line_width + content_width - 1) / content_width.max(1);
-            wrapped_line_count += visual_lines as u16;
-        }
-    }
-    
-    let content_height = wrapped_line_count;
+    let content_width = area.width.saturating_sub(2 + gutter) as usize; // Account for borders and gauge
+
+    let content_height = wrapped_line_count(&lines, content_width);
     let content_len = content_height as usize;
     let viewport_len = visible_height as usize;
 
     // Set scroll within bounds
-    let max_scroll = content_height.saturating_sub(visible_height);
-    if *scroll == u16::MAX || *scroll > max_scroll {
-        *scroll = max_scroll;
-    }
+    let (effective_scroll, at_bottom) = clamp_scroll(content_height, visible_height, *scroll, *scroll == u16::MAX);
+    *scroll = effective_scroll;
     let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
         .begin_symbol(Some("↑"))
         .end_symbol(Some("↓"))
@@ -97,10 +131,11 @@ pub fn render_message_section(
     let paragraph = Paragraph::new(text)
         .block(
             Block::default()
-                .title(title.as_str())
+                .title(title)
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Rgb(255, 140, 0)))
-                .title_style(Style::default().fg(Color::Rgb(255, 165, 0)).add_modifier(Modifier::BOLD)),
+                .border_style(Style::default().fg(border_color.unwrap_or(GLOBAL_CONFIG.tui.border_color)))
+                .title_style(Style::default().fg(border_color.unwrap_or(Color::Rgb(255, 165, 0))).add_modifier(Modifier::BOLD))
+                .padding(Padding::right(gutter)),
         )
         .wrap(Wrap { trim: true })
         .scroll((*scroll, 0));
@@ -109,8 +144,274 @@ pub fn render_message_section(
     frame.render_widget(paragraph, area);
     // Add scrollbar to message area
     frame.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
+    if let Some(usage) = gauge {
+        let gauge_area = Rect { x: area.right() - 2, y: area.y + 1, width: 1, height: visible_height };
+        render_context_gauge(frame, gauge_area, usage);
+    }
     
     // Return whether we're at the actual bottom
-    *scroll >= max_scroll
+    at_bottom
 }
 
+/// # render_context_gauge
+///
+/// **Purpose:**
+/// Draws a one-column gauge filled from the bottom in proportion to context use.
+///
+/// **Details:**
+/// The fill is colored by `tui.context_gauge` level (ok / warn / danger); the
+/// unfilled track is dim.
+fn render_context_gauge(frame: &mut Frame, area: Rect, usage: ContextUsage) {
+    let config = &GLOBAL_CONFIG.tui.context_gauge;
+    let filled = usage.fill_rows(area.height);
+    let fill = Style::default().fg(usage.level(config).color(config));
+    let track = Style::default().fg(Color::DarkGray);
+    let lines: Vec<Line> = (0..area.height)
+        .map(|row| if row >= area.height - filled {
+            Line::styled(glyph("█", "#"), fill)
+        } else {
+            Line::styled(glyph("░", "."), track)
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+/// # wrapped_line_count
+///
+/// **Purpose:**
+/// Counts how many visual rows lines occupy once wrapped to a width.
+///
+/// **Parameters:**
+/// - `lines`: Lines to measure
+/// - `content_width`: Width available inside the borders
+///
+/// **Returns:**
+/// `u16` - Row count (empty lines count as one row; saturates instead of overflowing)
+pub fn wrapped_line_count(lines: &[Line], content_width: usize) -> u16 {
+    let content_width = content_width.max(1);
+    let rows: usize = lines.iter()
+        .map(|line| line.width().div_ceil(content_width).max(1))
+        .sum();
+    rows.min(u16::MAX as usize) as u16
+}
+
+/// # parse_message_lines
+///
+/// **Purpose:**
+/// Splits an assistant message into display lines, setting fenced code apart.
+///
+/// **Returns:**
+/// `Vec<Line>` - One line per message line; lines between ``` fences are cyan
+/// on a dark block background, everything else (fences included) is plain
+///
+/// **Details:**
+/// Fences are recognized the way `parse_code_blocks` does (after leading
+/// whitespace), so line indexes here line up with its blocks. An unclosed
+/// fence (e.g. mid-stream) styles everything after it.
+pub fn parse_message_lines(msg: &str) -> Vec<Line<'_>> {
+    let code_style = Style::default().bg(CODE_BLOCK_BG).fg(Color::Cyan);
+    let mut within_code_block = false;
+    msg.split('\n')
+        .map(|line_text| {
+            if line_text.trim_start().starts_with("```") {
+                within_code_block = !within_code_block;
+                Line::from(line_text)
+            } else if within_code_block {
+                Line::from(Span::styled(line_text, code_style))
+            } else {
+                Line::from(line_text)
+            }
+        })
+        .collect()
+}
+
+/// # clamp_scroll
+///
+/// **Purpose:**
+/// Bounds a requested scroll offset to the content actually available.
+///
+/// **Parameters:**
+/// - `content_lines`: Wrapped content height in rows
+/// - `viewport_height`: Visible rows inside the borders
+/// - `requested_scroll`: Scroll offset the pane asked for
+/// - `auto_scroll`: Pin to the bottom regardless of the requested offset
+///
+/// **Returns:**
+/// `(u16, bool)` - Effective scroll and whether it sits at the bottom
+pub fn clamp_scroll(content_lines: u16, viewport_height: u16, requested_scroll: u16, auto_scroll: bool) -> (u16, bool) {
+    let max_scroll = content_lines.saturating_sub(viewport_height);
+    let scroll = if auto_scroll { max_scroll } else { requested_scroll.min(max_scroll) };
+    (scroll, scroll >= max_scroll)
+}
+
+/// # agent_accent_color
+///
+/// **Purpose:**
+/// Picks a stable accent color for an agent so its entries are recognizable.
+///
+/// **Parameters:**
+/// - `persona_name`: The agent's persona name
+///
+/// **Returns:**
+/// `Color` - The same color for the same name on every run
+pub fn agent_accent_color(persona_name: &str) -> Color {
+    const PALETTE: [Color; 6] = [
+        Color::Cyan,
+        Color::LightMagenta,
+        Color::LightGreen,
+        Color::LightBlue,
+        Color::Yellow,
+        Color::LightRed,
+    ];
+
+    let hash = persona_name.to_lowercase()
+        .bytes()
+        .fold(0usize, |acc, b| acc.wrapping_mul(31).wrapping_add(b as usize));
+    PALETTE[hash % PALETTE.len()]
+}
+
+/// # render_tab_bar
+///
+/// **Purpose:**
+/// One-row strip naming every open agent, in tab order, above the agent pane.
+///
+/// **Parameters:**
+/// - `frame`: The ratatui frame to render into
+/// - `area`: The row to draw in
+/// - `agents`: Open agents by id
+/// - `agent_order`: Tab order (the order Tab cycles through)
+/// - `current_agent`: The agent whose pane is showing
+///
+/// **Returns:**
+/// `Vec<(Uuid, Rect)>` - Where each visible tab was drawn, for mouse clicks
+///
+/// **Details:**
+/// The current tab gets a `tui.border_color` background; the others use the
+/// agent's accent color. A waiting agent's tab ends with a spinner. When the
+/// full names don't fit, the other tabs collapse to initials first, then the
+/// current one too.
+pub fn render_tab_bar(
+    frame: &mut Frame,
+    area: Rect,
+    agents: &HashMap<Uuid, AgentInfo>,
+    agent_order: &[Uuid],
+    current_agent: Option<Uuid>,
+) -> Vec<(Uuid, Rect)> {
+    let tabs: Vec<(&AgentInfo, bool)> = agent_order.iter()
+        .filter_map(|id| agents.get(id))
+        .map(|agent| (agent, Some(agent.id) == current_agent))
+        .collect();
+    let spinner = spinner_frame();
+    let separator = glyph("│", "|");
+
+    let label = |agent: &AgentInfo, initials_only: bool| {
+        let name = if initials_only { initials(&agent.persona_name) } else { capitalize_first(&agent.persona_name) };
+        if agent.is_waiting { format!(" {} {} ", name, spinner) } else { format!(" {} ", name) }
+    };
+    // (collapse others, collapse current): full names, then others as initials, then all
+    let width = |collapse_others: bool, collapse_current: bool| {
+        tabs.iter()
+            .map(|(agent, current)| label(agent, if *current { collapse_current } else { collapse_others }).chars().count())
+            .sum::<usize>()
+            + tabs.len().saturating_sub(1) * separator.chars().count()
+    };
+    let (collapse_others, collapse_current) = [(false, false), (true, false)].into_iter()
+        .find(|&(others, current)| width(others, current) <= area.width as usize)
+        .unwrap_or((true, true));
+
+    let mut spans = Vec::new();
+    let mut hit_areas = Vec::new();
+    let mut x = area.x;
+    for (index, (agent, current)) in tabs.iter().enumerate() {
+        if index > 0 {
+            spans.push(Span::styled(separator, Style::default().fg(Color::DarkGray)));
+            x = x.saturating_add(separator.chars().count() as u16);
+        }
+        let text = label(agent, if *current { collapse_current } else { collapse_others });
+        let style = if *current {
+            Style::default().bg(GLOBAL_CONFIG.tui.border_color).fg(Color::Black).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(agent_accent_color(&agent.persona_name))
+        };
+        let span = Span::styled(text, style);
+        let width = (span.width() as u16).min(area.right().saturating_sub(x));
+        if width > 0 {
+            hit_areas.push((agent.id, Rect { x, width, ..area }));
+        }
+        x = x.saturating_add(span.width() as u16);
+        spans.push(span);
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    hit_areas
+}
+
+/// # render_status_bar
+///
+/// **Purpose:**
+/// One-row strip under the tab bar with the token usage of the current agent's last reply.
+///
+/// **Parameters:**
+/// - `frame`: The ratatui frame to render into
+/// - `area`: The row to draw in
+/// - `usage`: The last reply's usage; None shows dashes (no reply yet, or the provider didn't report it)
+pub fn render_status_bar(frame: &mut Frame, area: Rect, usage: Option<Usage>) {
+    let text = match usage {
+        Some(usage) => format!(
+            "In: {} | Out: {} | Total: {}",
+            usage.input_tokens, usage.output_tokens, usage.total_tokens
+        ),
+        None => "In: - | Out: - | Total: -".to_string(),
+    };
+    let line = Line::from(Span::styled(text, Style::default().fg(Color::DarkGray)));
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+/// # initials
+///
+/// **Purpose:**
+/// A persona name shortened for a crowded tab bar.
+///
+/// **Returns:**
+/// `String` - The first letter of each word (split on spaces, `-`, and `_`), uppercased
+pub fn initials(name: &str) -> String {
+    name.split([' ', '-', '_'])
+        .filter_map(|word| word.chars().next())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// The spinner frame for now (a plain "*" in accessible mode)
+fn spinner_frame() -> &'static str {
+    if accessibility::is_enabled() {
+        return "*";
+    }
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+    SPINNER_FRAMES[(millis / SPINNER_FRAME_MS) as usize % SPINNER_FRAMES.len()]
+}
+
+/// # render_mode_line
+///
+/// **Purpose:**
+/// Shows the Vim mode (and a half-typed `d` / `y`) on the bottom border of the input box.
+///
+/// **Parameters:**
+/// - `frame`: The ratatui frame to render into
+/// - `area`: The input box, borders included
+/// - `mode`: Current input mode
+/// - `pending`: First key of an unfinished two-key command
+pub fn render_mode_line(frame: &mut Frame, area: Rect, mode: InputMode, pending: Option<char>) {
+    if area.height < 3 || area.width < 6 {
+        return;
+    }
+    let mut text = format!(" {} ", mode.label());
+    if let Some(key) = pending {
+        text.push_str(&format!("{} ", key));
+    }
+    let style = match mode {
+        InputMode::Normal => Style::default().fg(Color::Black).bg(GLOBAL_CONFIG.tui.border_color).add_modifier(Modifier::BOLD),
+        InputMode::Insert => Style::default().fg(GLOBAL_CONFIG.tui.border_color).add_modifier(Modifier::BOLD),
+    };
+    let width = (text.chars().count() as u16).min(area.width - 4);
+    let line_area = Rect { x: area.x + 2, y: area.y + area.height - 1, width, height: 1 };
+    frame.render_widget(Paragraph::new(Span::styled(text, style)), line_area);
+}
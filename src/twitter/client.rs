@@ -8,7 +8,8 @@
 //!
 //! **Responsibilities:**
 //! - Authenticate with Twitter using OAuth 1.0a
-//! - Post tweets via API
+//! - Post tweets and threads via API, within the remaining post quota
+//! - Record posts and rate-limit headers in the audit trail
 //! - Handle API errors gracefully
 //! - Display success/failure messages
 //!
@@ -22,8 +23,22 @@
 
 use crate::prelude::*;
 use crate::twitter::models::*;
+use crate::twitter::quota::{Outbox, PostLog, ServerLimit};
+use crate::llm::credentials::load_env_value;
+use crate::utilities::events::{self, EventKind};
 use oauth1_request as oauth;
 
+/// Variables TwitterConnection reads (from .env or the environment)
+pub const CREDENTIAL_VARS: [&str; 4] = [
+    "TWITTER_API_KEY",
+    "TWITTER_API_SECRET",
+    "TWITTER_ACCESS_TOKEN",
+    "TWITTER_ACCESS_TOKEN_SECRET",
+];
+
+/// Twitter API v2 endpoint for creating tweets
+const TWEETS_URL: &str = "https://api.twitter.com/2/tweets";
+
 /// # TwitterConnection
 ///
 /// **Summary:**
@@ -35,7 +50,9 @@ use oauth1_request as oauth;
 /// - `access_token`: User access token (from env)
 /// - `access_token_secret`: User access token secret (from env)
 /// - `client`: HTTP client for making requests
+/// - `endpoint`: URL tweets are posted to
 /// - `output`: Shared output handler for displaying results
+/// - `conflicts`: Variables whose shell and .env values differ (shown on 401s)
 ///
 /// **Usage Example:**
 /// ```rust
@@ -48,7 +65,9 @@ pub struct TwitterConnection {
     access_token: String,
     access_token_secret: String,
     client: Client,
+    endpoint: String,
     output: SharedOutput,
+    conflicts: Vec<String>,
 }
 /// # EmptyRequest
 ///
@@ -83,27 +102,64 @@ impl TwitterConnection {
     /// let twitter = TwitterConnection::new(Arc::clone(&output));
     /// ```
     pub fn new(output: SharedOutput) -> Self {
-        dotenv().ok();
-    
-        let api_key = env::var("TWITTER_API_KEY")
-            .expect("TWITTER_API_KEY not set in .env");
-        let api_secret = env::var("TWITTER_API_SECRET")
-            .expect("TWITTER_API_SECRET not set in .env");
-        let access_token = env::var("TWITTER_ACCESS_TOKEN")
-            .expect("TWITTER_ACCESS_TOKEN not set in .env");
-        let access_token_secret = env::var("TWITTER_ACCESS_TOKEN_SECRET")
-            .expect("TWITTER_ACCESS_TOKEN_SECRET not set in .env");
-    
-        TwitterConnection {
+        Self::try_new(output).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// # try_new
+    ///
+    /// **Purpose:**
+    /// Like `new`, but reports missing credentials instead of panicking.
+    ///
+    /// **Errors / Failures:**
+    /// - Any of the four TWITTER_* variables is not set in .env or the environment
+    pub fn try_new(output: SharedOutput) -> Result<Self, String> {
+        let mut values = Vec::new();
+        let mut conflicts = Vec::new();
+        for name in CREDENTIAL_VARS {
+            let loaded = load_env_value(name)
+                .ok_or_else(|| format!("{} not set in .env or the environment", name))?;
+            conflicts.extend(loaded.provenance.conflict_note(name));
+            values.push(loaded.value);
+        }
+        let [api_key, api_secret, access_token, access_token_secret]: [String; 4] =
+            values.try_into().expect("one value per credential variable");
+
+        Ok(TwitterConnection {
             api_key,
             api_secret,
             access_token,
             access_token_secret,
             client: Client::new(),
+            endpoint: TWEETS_URL.to_string(),
+            output,
+            conflicts,
+        })
+    }
+
+    /// Connection with placeholder credentials posting to `endpoint` (tests point it at a mock server)
+    #[cfg(test)]
+    pub(crate) fn with_endpoint(output: SharedOutput, endpoint: impl Into<String>) -> Self {
+        let placeholder = || "test".to_string();
+        TwitterConnection {
+            api_key: placeholder(),
+            api_secret: placeholder(),
+            access_token: placeholder(),
+            access_token_secret: placeholder(),
+            client: Client::new(),
+            endpoint: endpoint.into(),
             output,
+            conflicts: Vec::new(),
         }
     }
 
+    /// Appended to a 401 error when the shell and .env disagree on a credential
+    fn unauthorized_hint(&self, status: reqwest::StatusCode) -> String {
+        if status != reqwest::StatusCode::UNAUTHORIZED || self.conflicts.is_empty() {
+            return String::new();
+        }
+        format!(" ({})", self.conflicts.join("; "))
+    }
+
     /// # post_tweet
     ///
     /// **Purpose:**
@@ -130,10 +186,92 @@ impl TwitterConnection {
     /// }
     /// ```
     pub async fn post_tweet(&self, text: &str) -> Result<TweetData, Box<dyn std::error::Error>> {
-        let url = "https://api.twitter.com/2/tweets";
+        PostLog::load()?.quota().check_thread(1)?;
+        self.send_tweet(text, None).await
+    }
+
+    /// # post_thread
+    ///
+    /// **Purpose:**
+    /// Posts a thread, each part replying to the previous one.
+    ///
+    /// **Parameters:**
+    /// - `parts`: Thread parts in order
+    ///
+    /// **Returns:**
+    /// `Result<Vec<TweetData>, Box<dyn std::error::Error>>` - Posted parts
+    ///
+    /// **Errors / Failures:**
+    /// - The remaining quota can't fit every part (nothing is posted)
+    /// - Any part fails to post (earlier parts stay posted)
+    pub async fn post_thread(&self, parts: &[String]) -> Result<Vec<TweetData>, Box<dyn std::error::Error>> {
+        PostLog::load()?.quota().check_thread(parts.len())?;
+
+        let mut posted: Vec<TweetData> = Vec::new();
+        for part in parts {
+            let reply_to = posted.last().map(|previous| previous.id.clone());
+            posted.push(self.send_tweet(part, reply_to).await?);
+        }
+        Ok(posted)
+    }
+
+    /// # flush_outbox
+    ///
+    /// **Purpose:**
+    /// Posts queued tweets, oldest first, for as long as the quota allows.
+    ///
+    /// **Returns:**
+    /// `Result<usize, Box<dyn std::error::Error>>` - Number of queued posts sent
+    ///
+    /// **Details:**
+    /// A post that fails is put back at the front of the outbox and flushing stops.
+    pub async fn flush_outbox(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        let mut outbox = Outbox::load()?;
+        let mut sent = 0;
+
+        while let Some(queued) = outbox.take_ready(&PostLog::load()?.quota()) {
+            outbox.save()?;
+            if let Err(e) = self.post_thread(&queued.parts).await {
+                outbox.queued.insert(0, queued);
+                outbox.save()?;
+                return Err(e);
+            }
+            self.output.display(format!("Queued post from {} sent", queued.queued_at));
+            sent += 1;
+        }
+
+        Ok(sent)
+    }
+
+    /// # send_tweet
+    ///
+    /// **Purpose:**
+    /// Sends one tweet and publishes a `tweet_posted` or `error` lifecycle event for it.
+    async fn send_tweet(&self, text: &str, reply_to: Option<String>) -> Result<TweetData, Box<dyn std::error::Error>> {
+        let reply = reply_to.is_some();
+        match self.post_one(text, reply_to).await {
+            Ok(tweet) => {
+                let chars = text.chars().count();
+                events::publish(EventKind::TweetPosted { tweet_id: tweet.id.clone(), chars, reply }, None, None);
+                Ok(tweet)
+            }
+            Err(e) => {
+                events::publish(EventKind::Error { source: "twitter".to_string(), message: e.to_string() }, None, None);
+                Err(e)
+            }
+        }
+    }
+
+    /// # post_one
+    ///
+    /// **Purpose:**
+    /// Sends one tweet and records it (and any rate-limit headers) in the audit trail.
+    async fn post_one(&self, text: &str, reply_to: Option<String>) -> Result<TweetData, Box<dyn std::error::Error>> {
+        let url = self.endpoint.as_str();
 
         let body = CreateTweetRequest {
             text: text.to_string(),
+            reply: reply_to.map(|id| TweetReply { in_reply_to_tweet_id: id }),
         };
 
         let json_body = serde_json::to_string(&body)?;
@@ -158,11 +296,17 @@ impl TwitterConnection {
             .await?;
 
         let status = response.status();
+        let server_limit = ServerLimit::from_headers(response.headers());
         let text = response.text().await?;
 
+        let mut log = PostLog::load()?;
         if status.is_success() {
             match serde_json::from_str::<TweetResponse>(&text) {
                 Ok(tweet_response) => {
+                    log.record_post(&tweet_response.data.id, &tweet_response.data.text, server_limit);
+                    if let Err(e) = log.save() {
+                        log_error!("Failed to update tweets log: {}", e);
+                    }
                     self.output.display(format!(
                         "✓ Tweet posted! ID: {} | Text: '{}'",
                         tweet_response.data.id,
@@ -175,6 +319,13 @@ impl TwitterConnection {
                 }
             }
         } else {
+            if server_limit.is_some() {
+                log.server_limit = server_limit;
+                if let Err(e) = log.save() {
+                    log_error!("Failed to update tweets log: {}", e);
+                }
+            }
+
             match serde_json::from_str::<TwitterErrorResponse>(&text) {
                 Ok(error_body) => {
                     let error_msg = error_body.errors
@@ -182,12 +333,78 @@ impl TwitterConnection {
                         .map(|e| e.message.as_str())
                         .collect::<Vec<_>>()
                         .join(", ");
-                    Err(format!("Twitter API Error: {}", error_msg).into())
+                    Err(format!("Twitter API Error: {}{}", error_msg, self.unauthorized_hint(status)).into())
                 }
                 Err(_) => {
-                    Err(format!("Request failed ({}): {}", status, text).into())
+                    Err(format!("Request failed ({}): {}{}", status, text, self.unauthorized_hint(status)).into())
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc::Receiver;
+    use crate::test_support::{test_personas_root, MockResponse, MockServer, POST_LOG_TURN};
+    use crate::utilities::events::{Event, EVENT_BUS};
+    use crate::utilities::outputs::CliOutput;
+
+    /// Tweet events on the bus so far (only this module publishes them)
+    fn tweet_events(events: &Receiver<Event>) -> Vec<EventKind> {
+        events.try_iter()
+            .filter(|event| event.persona.is_none() && event.agent_id.is_none())
+            .map(|event| event.kind)
+            .filter(|kind| match kind {
+                EventKind::TweetPosted { .. } => true,
+                EventKind::Error { source, .. } => source == "twitter",
+                _ => false,
+            })
+            .collect()
+    }
+
+    // Both tests post, so they take turns with the post log; the turn spans the whole request
+    #[allow(clippy::await_holding_lock)]
+    #[tokio::test]
+    async fn each_posted_part_publishes_a_tweet_posted_event() {
+        test_personas_root();
+        let _turn = POST_LOG_TURN.lock().unwrap_or_else(|e| e.into_inner());
+        let posted = AtomicUsize::new(0);
+        let server = MockServer::start(move |request| {
+            let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+            let id = 1900 + posted.fetch_add(1, Ordering::SeqCst);
+            MockResponse::json(201, serde_json::json!({ "data": { "id": id.to_string(), "text": body["text"] } }).to_string())
+        }).await;
+        let twitter = TwitterConnection::with_endpoint(Arc::new(CliOutput), server.url("/2/tweets"));
+        let events = EVENT_BUS.subscribe();
+
+        let parts = ["Shipping the event log today. 1/2".to_string(), "Schema is versioned. 2/2".to_string()];
+        twitter.post_thread(&parts).await.unwrap();
+
+        assert_eq!(tweet_events(&events), [
+            EventKind::TweetPosted { tweet_id: "1900".into(), chars: 33, reply: false },
+            EventKind::TweetPosted { tweet_id: "1901".into(), chars: 24, reply: true },
+        ]);
+        let second: serde_json::Value = serde_json::from_str(&server.requests()[1].body).unwrap();
+        assert_eq!(second["reply"]["in_reply_to_tweet_id"], "1900");
+    }
+
+    #[allow(clippy::await_holding_lock)]
+    #[tokio::test]
+    async fn a_rejected_tweet_publishes_an_error_event() {
+        test_personas_root();
+        let _turn = POST_LOG_TURN.lock().unwrap_or_else(|e| e.into_inner());
+        let server = MockServer::start(|_| {
+            MockResponse::json(403, r#"{"errors":[{"message":"You are not allowed to create a Tweet with duplicate content."}]}"#)
+        }).await;
+        let twitter = TwitterConnection::with_endpoint(Arc::new(CliOutput), server.url("/2/tweets"));
+        let events = EVENT_BUS.subscribe();
+
+        let error = twitter.post_tweet("Again.").await.unwrap_err().to_string();
+
+        assert_eq!(error, "Twitter API Error: You are not allowed to create a Tweet with duplicate content.");
+        assert_eq!(tweet_events(&events), [EventKind::Error { source: "twitter".into(), message: error }]);
+    }
+}
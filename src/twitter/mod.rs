@@ -20,6 +20,9 @@
 
 pub mod models;
 pub mod client;
+pub mod preview;
+pub mod quota;
+pub mod spellcheck;
 
 pub use client::TwitterConnection;
 pub use models::*;
\ No newline at end of file
@@ -28,16 +28,29 @@ use serde::{Deserialize, Serialize};
 ///
 /// **Fields:**
 /// - `text`: The tweet content (max 280 characters)
+/// - `reply`: Tweet this one replies to (used to chain threads)
 ///
 /// **Usage Example:**
 /// ```rust
 /// let request = CreateTweetRequest {
 ///     text: "Hello Twitter!".to_string(),
+///     reply: None,
 /// };
 /// ```
 #[derive(Serialize, Debug)]
 pub struct CreateTweetRequest {
     pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply: Option<TweetReply>,
+}
+
+/// # TweetReply
+///
+/// **Summary:**
+/// Reply target for a new tweet.
+#[derive(Serialize, Debug)]
+pub struct TweetReply {
+    pub in_reply_to_tweet_id: String,
 }
 
 /// # TweetResponse
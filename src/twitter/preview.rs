@@ -0,0 +1,192 @@
+//! # Daegonica Module: twitter::preview
+//!
+//! **Purpose:** Preview-then-confirm flow for outgoing tweets
+//!
+//! **Context:**
+//! - Posted tweets are attributed to the user and can't be edited
+//! - `tweet <text>` and `draft <idea>` both end in a pending preview; `tweet confirm` posts it
+//!
+//! **Responsibilities:**
+//! - Hold the exact parts that will be posted
+//! - Render the preview block from those same parts
+//! - Keep the one pending post awaiting confirmation
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use crate::prelude::*;
+use crate::twitter::quota::PostLog;
+use crate::twitter::spellcheck::{self, TWEET_CHAR_LIMIT};
+use crate::utilities::accessibility::glyph;
+
+/// The post awaiting `tweet confirm`, if any
+static PENDING: Lazy<Mutex<Option<TweetPreview>>> = Lazy::new(|| Mutex::new(None));
+
+/// # TweetPreview
+///
+/// **Summary:**
+/// A tweet or thread exactly as it will be sent.
+///
+/// **Details:**
+/// `render` and the posting code both read `parts`, so what the user
+/// confirmed is byte-for-byte what gets posted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TweetPreview {
+    parts: Vec<String>,
+}
+
+impl TweetPreview {
+    pub fn single(text: impl Into<String>) -> Self {
+        Self { parts: vec![text.into()] }
+    }
+
+    /// # from_draft
+    ///
+    /// **Purpose:**
+    /// Builds a preview from model output, splitting it into a thread if it's too long.
+    ///
+    /// **Details:**
+    /// Blank lines separate thread parts; a draft that fits in one tweet stays whole.
+    pub fn from_draft(text: &str) -> Self {
+        let text = text.trim();
+        if text.chars().count() <= TWEET_CHAR_LIMIT {
+            return Self::single(text);
+        }
+
+        let parts: Vec<String> = text.split("\n\n")
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(str::to_string)
+            .collect();
+        if parts.is_empty() { Self::single(text) } else { Self { parts } }
+    }
+
+    pub fn parts(&self) -> &[String] {
+        &self.parts
+    }
+
+    /// # render
+    ///
+    /// **Purpose:**
+    /// Formats the preview block: account, each part verbatim with its length, and hints.
+    pub fn render(&self) -> String {
+        let bar = glyph("│ ", "");
+        let account = GLOBAL_CONFIG.twitter.account_handle.as_deref().unwrap_or("(account handle not configured)");
+        let kind = if self.parts.len() == 1 { "Tweet".to_string() } else { format!("Thread of {}", self.parts.len()) };
+        let mut lines = vec![format!("{}{} preview, posting as {}", glyph("┌ ", ""), kind, account)];
+
+        for (index, part) in self.parts.iter().enumerate() {
+            if self.parts.len() > 1 {
+                lines.push(format!("{}Part {}/{}:", bar, index + 1, self.parts.len()));
+            }
+            // split, not lines: a trailing newline is sent, so it's shown
+            lines.extend(part.split('\n').map(|line| format!("{}{}", bar, line)));
+
+            let check = spellcheck::check_part(part);
+            let mut summary = vec![format!(
+                "{}/{} chars{}", check.chars, TWEET_CHAR_LIMIT, if check.over_limit() { " (too long)" } else { "" }
+            )];
+            if GLOBAL_CONFIG.twitter.spellcheck {
+                summary.extend(check.findings.iter().map(|finding| finding.describe()));
+            }
+            lines.push(format!("{}{}", bar, summary.join(" · ")));
+        }
+
+        if let Err(quota) = PostLog::load().map(|log| log.quota().check_thread(self.parts.len())).unwrap_or(Ok(())) {
+            lines.push(format!("{}{}", bar, quota));
+        }
+        lines.push(format!("{}'tweet confirm' to post, 'tweet cancel' to discard", glyph("└ ", "")));
+        lines.join("\n")
+    }
+}
+
+/// # set_pending
+///
+/// **Purpose:**
+/// Makes this the post `tweet confirm` will send, replacing any earlier one.
+pub fn set_pending(preview: TweetPreview) {
+    *PENDING.lock().unwrap_or_else(|e| e.into_inner()) = Some(preview);
+}
+
+/// # take_pending
+///
+/// **Purpose:**
+/// Removes and returns the post awaiting confirmation.
+pub fn take_pending() -> Option<TweetPreview> {
+    PENDING.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads the parts back out of a rendered block: each part's lines up to its char count
+    fn shown_parts(rendered: &str) -> Vec<String> {
+        let body: Vec<&str> = rendered.lines()
+            .filter_map(|line| line.strip_prefix("│ "))
+            .collect();
+        let mut parts = Vec::new();
+        let mut current: Option<Vec<&str>> = Some(Vec::new());
+        for line in body {
+            if line.starts_with("Part ") && line.ends_with(':') {
+                current = Some(Vec::new());
+            } else if line.contains(&format!("/{} chars", TWEET_CHAR_LIMIT)) {
+                if let Some(lines) = current.take() {
+                    parts.push(lines.join("\n"));
+                }
+            } else if let Some(lines) = current.as_mut() {
+                lines.push(line);
+            }
+        }
+        parts
+    }
+
+    #[test]
+    fn the_preview_shows_exactly_the_parts_that_will_be_sent() {
+        let long_paragraph = "word ".repeat(40);
+        let cases = [
+            ("plain", TweetPreview::single("Shipping the preview flow today.")),
+            ("spacing kept", TweetPreview::single("  two  spaces,  kept  ")),
+            ("unicode", TweetPreview::single("Café ☕ — naïve résumé 🚀")),
+            ("line breaks", TweetPreview::single("first line\n\nthird line")),
+            ("trailing newline", TweetPreview::single("ends with a newline\n")),
+            ("thread", TweetPreview::from_draft(&format!("{}\n\n{}\n\nlast", long_paragraph, long_paragraph))),
+        ];
+
+        for (label, preview) in cases {
+            let rendered = preview.render();
+            assert_eq!(shown_parts(&rendered), preview.parts(), "{}:\n{}", label, rendered);
+        }
+    }
+
+    #[test]
+    fn the_preview_counts_characters_not_bytes() {
+        let rendered = TweetPreview::single("Café ☕").render();
+        assert!(rendered.contains(&format!("6/{} chars", TWEET_CHAR_LIMIT)), "{}", rendered);
+
+        let rendered = TweetPreview::single("x".repeat(TWEET_CHAR_LIMIT + 1)).render();
+        assert!(rendered.contains(&format!("{0}/{1} chars (too long)", TWEET_CHAR_LIMIT + 1, TWEET_CHAR_LIMIT)), "{}", rendered);
+    }
+
+    #[test]
+    fn drafts_split_into_a_thread_only_when_too_long() {
+        let short = "One idea.\n\nTwo ideas.";
+        assert_eq!(TweetPreview::from_draft(&format!("  {}\n", short)).parts(), [short]);
+
+        let long = format!("{}\n\n\n\n{}", "a".repeat(200), "b".repeat(200));
+        let thread = TweetPreview::from_draft(&long);
+        assert_eq!(thread.parts(), ["a".repeat(200), "b".repeat(200)], "empty paragraphs are dropped");
+        assert!(thread.render().starts_with("┌ Thread of 2 preview, posting as "));
+        assert!(thread.render().ends_with("└ 'tweet confirm' to post, 'tweet cancel' to discard"));
+
+        let unbroken = "c".repeat(TWEET_CHAR_LIMIT + 20);
+        assert_eq!(TweetPreview::from_draft(&unbroken).parts(), [unbroken], "nowhere to split, so it stays whole");
+    }
+}
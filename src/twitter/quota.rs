@@ -0,0 +1,459 @@
+//! # Daegonica Module: twitter::quota
+//!
+//! **Purpose:** Post quota tracking and a deferred-post outbox
+//!
+//! **Context:**
+//! - The free X API tier allows very few writes, and running out mid-thread
+//!   leaves half a thread posted
+//! - Used by `TwitterConnection` before and after every post
+//!
+//! **Responsibilities:**
+//! - Keep the `tweets_log.json` audit trail of posts
+//! - Compute remaining daily/monthly quota from the log and `TwitterConfig`
+//! - Prefer the API's rate-limit headers over the local estimate
+//! - Persist posts deferred with `tweet --later` in `tweets_outbox.json`
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use reqwest::header::HeaderMap;
+use crate::prelude::*;
+use crate::persona::{state_path, writable_state_path};
+use crate::utilities::time::{format_timestamp, Zone};
+
+/// Every post and the last rate limit X reported (`{root}/state/tweets_log.json`)
+pub(crate) const POST_LOG_FILE: &str = "tweets_log.json";
+/// Tweets queued until quota frees up (`{root}/state/tweets_outbox.json`)
+pub(crate) const OUTBOX_FILE: &str = "tweets_outbox.json";
+
+fn parse_time(stamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(stamp).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// # PostRecord
+///
+/// **Summary:**
+/// One posted tweet in the audit trail.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostRecord {
+    pub posted_at: String,
+    pub tweet_id: String,
+    pub text: String,
+}
+
+/// # ServerLimit
+///
+/// **Summary:**
+/// Remaining writes as last reported by the API's rate-limit headers.
+///
+/// **Fields:**
+/// - `remaining`: Posts left in the current window
+/// - `reset_at`: RFC3339 time the window resets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerLimit {
+    pub remaining: u32,
+    pub reset_at: String,
+}
+
+impl ServerLimit {
+    /// # from_headers
+    ///
+    /// **Purpose:**
+    /// Reads the per-user 24h limit headers, falling back to the per-endpoint ones.
+    ///
+    /// **Returns:**
+    /// `Option<Self>` - None when the response carried no usable rate-limit headers
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let read = |name: &str| headers.get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<i64>().ok());
+
+        let (remaining, reset) = match (read("x-user-limit-24hour-remaining"), read("x-user-limit-24hour-reset")) {
+            (Some(remaining), Some(reset)) => (remaining, reset),
+            _ => (read("x-rate-limit-remaining")?, read("x-rate-limit-reset")?),
+        };
+
+        Some(Self {
+            remaining: remaining.max(0) as u32,
+            reset_at: Utc.timestamp_opt(reset, 0).single()?.to_rfc3339(),
+        })
+    }
+}
+
+/// # Quota
+///
+/// **Summary:**
+/// How many posts can be made right now.
+///
+/// **Fields:**
+/// - `daily_remaining`: Posts left in the rolling 24h window
+/// - `monthly_remaining`: Posts left this calendar month (UTC)
+/// - `resets_at`: When the next post slot frees up, if the daily quota is used up
+/// - `from_server`: Daily figure came from API headers rather than the local log
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quota {
+    pub daily_remaining: u32,
+    pub monthly_remaining: u32,
+    pub resets_at: Option<DateTime<Utc>>,
+    pub from_server: bool,
+}
+
+impl Quota {
+    pub fn remaining(&self) -> u32 {
+        self.daily_remaining.min(self.monthly_remaining)
+    }
+
+    /// # check_thread
+    ///
+    /// **Purpose:**
+    /// Refuses to start a post (or thread) that can't be finished with the remaining quota.
+    ///
+    /// **Parameters:**
+    /// - `parts`: Number of posts in the thread (1 for a single tweet)
+    ///
+    /// **Returns:**
+    /// `Result<(), String>` - Err with a message like "3 parts, 2 posts remaining today ..."
+    pub fn check_thread(&self, parts: usize) -> Result<(), String> {
+        if parts as u64 <= self.remaining() as u64 {
+            return Ok(());
+        }
+
+        let period = if self.monthly_remaining < self.daily_remaining { "this month" } else { "today" };
+        let reset = self.resets_at
+            .map(|at| format!(" (next slot {})", format_timestamp(at, Zone::Local)))
+            .unwrap_or_default();
+        Err(format!(
+            "{} part{}, {} post{} remaining {}{}. Queue it with 'tweet --later' instead.",
+            parts, if parts == 1 { "" } else { "s" },
+            self.remaining(), if self.remaining() == 1 { "" } else { "s" },
+            period, reset
+        ))
+    }
+
+    pub fn describe(&self) -> String {
+        let source = if self.from_server { "reported by X" } else { "estimated" };
+        let mut line = format!(
+            "{} posts left today ({}), {} left this month",
+            self.daily_remaining, source, self.monthly_remaining
+        );
+        if let Some(at) = self.resets_at {
+            line.push_str(&format!(", next slot {}", format_timestamp(at, Zone::Local)));
+        }
+        line
+    }
+}
+
+/// # PostLog
+///
+/// **Summary:**
+/// The `tweets_log.json` audit trail plus the last server-reported limit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PostLog {
+    #[serde(default)]
+    pub posts: Vec<PostRecord>,
+    #[serde(default)]
+    pub server_limit: Option<ServerLimit>,
+}
+
+impl PostLog {
+    /// Loads the audit trail (empty if it doesn't exist yet)
+    pub fn load() -> Result<Self, ShadowError> {
+        match fs::read_to_string(state_path(POST_LOG_FILE)) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), ShadowError> {
+        fs::write(writable_state_path(POST_LOG_FILE)?, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Appends a successful post and the limit headers that came with it
+    pub fn record_post(&mut self, tweet_id: &str, text: &str, server_limit: Option<ServerLimit>) {
+        self.posts.push(PostRecord {
+            posted_at: Utc::now().to_rfc3339(),
+            tweet_id: tweet_id.to_string(),
+            text: text.to_string(),
+        });
+        if server_limit.is_some() {
+            self.server_limit = server_limit;
+        }
+    }
+
+    /// # quota_at
+    ///
+    /// **Purpose:**
+    /// Computes the remaining quota at `now`.
+    ///
+    /// **Details:**
+    /// - Daily: rolling 24h window over the log, unless a server limit is still current
+    /// - Monthly: posts since the first of `now`'s month (UTC)
+    pub fn quota_at(&self, now: DateTime<Utc>, config: &TwitterConfig) -> Quota {
+        let times: Vec<DateTime<Utc>> = self.posts.iter()
+            .filter_map(|post| parse_time(&post.posted_at))
+            .collect();
+
+        let day_ago = now - Duration::hours(24);
+        let mut in_window: Vec<DateTime<Utc>> = times.iter().copied().filter(|t| *t > day_ago).collect();
+        in_window.sort();
+
+        let month_start = Utc.with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0).single();
+        let this_month = times.iter()
+            .filter(|t| month_start.is_some_and(|start| **t >= start))
+            .count() as u32;
+
+        let local_daily = config.daily_post_limit.saturating_sub(in_window.len() as u32);
+        let local_reset = in_window.first().map(|oldest| *oldest + Duration::hours(24));
+
+        let server = self.server_limit.as_ref()
+            .and_then(|limit| parse_time(&limit.reset_at).map(|reset| (limit.remaining, reset)))
+            .filter(|(_, reset)| *reset > now);
+
+        let (daily_remaining, resets_at, from_server) = match server {
+            Some((remaining, reset)) => (remaining, Some(reset), true),
+            None => (local_daily, local_reset, false),
+        };
+
+        Quota {
+            daily_remaining,
+            monthly_remaining: config.monthly_post_limit.saturating_sub(this_month),
+            resets_at: resets_at.filter(|_| daily_remaining == 0),
+            from_server,
+        }
+    }
+
+    pub fn quota(&self) -> Quota {
+        self.quota_at(Utc::now(), &GLOBAL_CONFIG.twitter)
+    }
+}
+
+/// # QueuedPost
+///
+/// **Summary:**
+/// A tweet (or thread) deferred until quota is available.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedPost {
+    pub queued_at: String,
+    pub parts: Vec<String>,
+}
+
+/// # Outbox
+///
+/// **Summary:**
+/// Persistent queue of deferred posts (`tweets_outbox.json`), oldest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Outbox {
+    #[serde(default)]
+    pub queued: Vec<QueuedPost>,
+}
+
+impl Outbox {
+    /// Loads the outbox (empty if it doesn't exist yet)
+    pub fn load() -> Result<Self, ShadowError> {
+        match fs::read_to_string(state_path(OUTBOX_FILE)) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), ShadowError> {
+        fs::write(writable_state_path(OUTBOX_FILE)?, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn enqueue(&mut self, parts: Vec<String>) {
+        self.queued.push(QueuedPost {
+            queued_at: Utc::now().to_rfc3339(),
+            parts,
+        });
+    }
+
+    /// # take_ready
+    ///
+    /// **Purpose:**
+    /// Removes and returns the oldest queued post if the quota can fit all of its parts.
+    ///
+    /// **Details:**
+    /// Strictly first-in first-out: a big thread at the front blocks later posts,
+    /// so posts never go out of order.
+    pub fn take_ready(&mut self, quota: &Quota) -> Option<QueuedPost> {
+        let first = self.queued.first()?;
+        quota.check_thread(first.parts.len()).ok()?;
+        Some(self.queued.remove(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+    use crate::test_support::{test_personas_root, POST_LOG_TURN};
+
+    fn config(daily: u32, monthly: u32) -> TwitterConfig {
+        TwitterConfig { daily_post_limit: daily, monthly_post_limit: monthly, ..GLOBAL_CONFIG.twitter.clone() }
+    }
+
+    fn at(stamp: &str) -> DateTime<Utc> {
+        parse_time(stamp).expect("test times are RFC3339")
+    }
+
+    fn log_of(stamps: &[&str]) -> PostLog {
+        PostLog {
+            posts: stamps.iter()
+                .enumerate()
+                .map(|(i, stamp)| PostRecord { posted_at: stamp.to_string(), tweet_id: i.to_string(), text: format!("post {}", i) })
+                .collect(),
+            server_limit: None,
+        }
+    }
+
+    fn quota(daily: u32, monthly: u32) -> Quota {
+        Quota { daily_remaining: daily, monthly_remaining: monthly, resets_at: None, from_server: false }
+    }
+
+    #[test]
+    fn the_daily_window_rolls_across_midnight() {
+        let posts = ["2026-03-09T23:30:00+00:00", "2026-03-10T00:30:00+00:00", "2026-03-10T09:00:00+00:00"];
+        let cases = [
+            ("same evening", "2026-03-09T23:45:00+00:00", 16, 1),
+            ("just past midnight", "2026-03-10T00:45:00+00:00", 15, 2),
+            ("next morning, all three in the window", "2026-03-10T12:00:00+00:00", 14, 3),
+            ("exactly 24h after the first drops it", "2026-03-10T23:30:00+00:00", 15, 3),
+            ("a day after the last", "2026-03-11T09:30:00+00:00", 17, 3),
+        ];
+
+        for (case, now, daily, month) in cases {
+            // The log as it stood at `now`
+            let posted: Vec<&str> = posts.iter().copied().filter(|post| at(post) <= at(now)).collect();
+            let quota = log_of(&posted).quota_at(at(now), &config(17, 100));
+            assert_eq!(quota.daily_remaining, daily, "{}", case);
+            assert_eq!(quota.monthly_remaining, 100 - month, "{}", case);
+            assert!(!quota.from_server, "{}", case);
+        }
+    }
+
+    #[test]
+    fn the_month_resets_on_the_first_even_when_the_day_does_not() {
+        let log = log_of(&["2026-01-31T22:00:00+00:00", "2026-01-31T23:59:00+00:00"]);
+
+        let january = log.quota_at(at("2026-01-31T23:59:30+00:00"), &config(5, 2));
+        assert_eq!((january.daily_remaining, january.monthly_remaining, january.remaining()), (3, 0, 0));
+
+        let february = log.quota_at(at("2026-02-01T00:01:00+00:00"), &config(5, 2));
+        assert_eq!((february.daily_remaining, february.monthly_remaining, february.remaining()), (3, 2, 2));
+    }
+
+    #[test]
+    fn the_next_slot_is_only_reported_once_the_day_is_used_up() {
+        let log = log_of(&["2026-03-10T08:00:00+00:00", "2026-03-10T10:00:00+00:00"]);
+
+        assert_eq!(log.quota_at(at("2026-03-10T12:00:00+00:00"), &config(3, 100)).resets_at, None);
+        let spent = log.quota_at(at("2026-03-10T12:00:00+00:00"), &config(2, 100));
+        assert_eq!(spent.daily_remaining, 0);
+        assert_eq!(spent.resets_at, Some(at("2026-03-11T08:00:00+00:00")), "when the oldest post leaves the window");
+    }
+
+    #[test]
+    fn a_current_server_limit_beats_the_estimate() {
+        let mut log = log_of(&["2026-03-10T08:00:00+00:00"]);
+        log.server_limit = Some(ServerLimit { remaining: 0, reset_at: "2026-03-10T20:00:00+00:00".to_string() });
+
+        let current = log.quota_at(at("2026-03-10T12:00:00+00:00"), &config(17, 100));
+        assert_eq!(current, Quota {
+            daily_remaining: 0,
+            monthly_remaining: 99,
+            resets_at: Some(at("2026-03-10T20:00:00+00:00")),
+            from_server: true,
+        });
+
+        let expired = log.quota_at(at("2026-03-10T21:00:00+00:00"), &config(17, 100));
+        assert_eq!((expired.daily_remaining, expired.from_server), (16, false), "an expired report falls back to the log");
+    }
+
+    #[test]
+    fn a_thread_that_cannot_finish_is_refused_up_front() {
+        let cases = [
+            (quota(3, 10), 3, None),
+            (quota(3, 10), 1, None),
+            (quota(2, 10), 3, Some("3 parts, 2 posts remaining today. Queue it with 'tweet --later' instead.")),
+            (quota(1, 10), 2, Some("2 parts, 1 post remaining today. Queue it with 'tweet --later' instead.")),
+            (quota(0, 10), 1, Some("1 part, 0 posts remaining today. Queue it with 'tweet --later' instead.")),
+            (quota(10, 2), 4, Some("4 parts, 2 posts remaining this month. Queue it with 'tweet --later' instead.")),
+        ];
+
+        for (quota, parts, refusal) in cases {
+            assert_eq!(quota.check_thread(parts).err().as_deref(), refusal, "{} parts against {:?}", parts, quota);
+        }
+    }
+
+    #[test]
+    fn server_headers_prefer_the_24h_user_limit() {
+        let headers = |pairs: &[(&'static str, &'static str)]| {
+            let mut map = HeaderMap::new();
+            for (name, value) in pairs {
+                map.insert(*name, HeaderValue::from_static(value));
+            }
+            map
+        };
+        let cases = [
+            (headers(&[("x-user-limit-24hour-remaining", "4"), ("x-user-limit-24hour-reset", "1773187200"),
+                       ("x-rate-limit-remaining", "50"), ("x-rate-limit-reset", "1773100800")]), Some((4, "2026-03-11T00:00:00+00:00"))),
+            (headers(&[("x-rate-limit-remaining", "50"), ("x-rate-limit-reset", "1773100800")]), Some((50, "2026-03-10T00:00:00+00:00"))),
+            (headers(&[("x-user-limit-24hour-remaining", "-1"), ("x-user-limit-24hour-reset", "1773187200")]), Some((0, "2026-03-11T00:00:00+00:00"))),
+            (headers(&[("x-user-limit-24hour-remaining", "4")]), None),
+            (headers(&[("x-rate-limit-remaining", "many"), ("x-rate-limit-reset", "1773100800")]), None),
+        ];
+
+        for (headers, expected) in cases {
+            let limit = ServerLimit::from_headers(&headers);
+            assert_eq!(limit.as_ref().map(|limit| (limit.remaining, limit.reset_at.as_str())), expected, "{:?}", headers);
+        }
+    }
+
+    #[test]
+    fn the_outbox_goes_out_in_order_as_quota_allows() {
+        let mut outbox = Outbox::default();
+        outbox.enqueue(vec!["thread 1/3".into(), "thread 2/3".into(), "thread 3/3".into()]);
+        outbox.enqueue(vec!["single".into()]);
+
+        assert!(outbox.take_ready(&quota(2, 10)).is_none(), "the thread at the front waits, and holds back the rest");
+        assert_eq!(outbox.queued.len(), 2);
+
+        assert_eq!(outbox.take_ready(&quota(3, 10)).map(|post| post.parts.len()), Some(3));
+        assert_eq!(outbox.take_ready(&quota(1, 10)).map(|post| post.parts), Some(vec!["single".to_string()]));
+        assert!(outbox.take_ready(&quota(5, 10)).is_none());
+    }
+
+    #[test]
+    fn the_outbox_and_log_survive_a_restart() {
+        test_personas_root();
+        let _turn = POST_LOG_TURN.lock().unwrap_or_else(|e| e.into_inner());
+        let mut outbox = Outbox::default();
+        outbox.enqueue(vec!["later 1/2".into(), "later 2/2".into()]);
+        outbox.enqueue(vec!["later too".into()]);
+        outbox.save().unwrap();
+        let mut log = PostLog::default();
+        log.record_post("1001", "posted", Some(ServerLimit { remaining: 7, reset_at: "2026-03-11T00:00:00+00:00".into() }));
+        log.record_post("1002", "posted again", None);
+        log.save().unwrap();
+
+        let reloaded = Outbox::load().unwrap();
+        let parts: Vec<Vec<String>> = reloaded.queued.iter().map(|post| post.parts.clone()).collect();
+        assert_eq!(parts, [vec!["later 1/2".to_string(), "later 2/2".to_string()], vec!["later too".to_string()]]);
+        assert_eq!(reloaded.queued[0].queued_at, outbox.queued[0].queued_at);
+
+        let reloaded = PostLog::load().unwrap();
+        let ids: Vec<&str> = reloaded.posts.iter().map(|post| post.tweet_id.as_str()).collect();
+        assert_eq!(ids, ["1001", "1002"]);
+        assert_eq!(reloaded.server_limit.map(|limit| limit.remaining), Some(7), "a post without headers keeps the last report");
+    }
+}
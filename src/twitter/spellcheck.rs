@@ -0,0 +1,393 @@
+//! # Daegonica Module: twitter::spellcheck
+//!
+//! **Purpose:** Local spelling and punctuation hints for outgoing tweets
+//!
+//! **Context:**
+//! - Posted tweets can't be edited, so typos are worth catching first
+//! - Entirely local: a plain wordlist on disk plus a custom dictionary, no network
+//! - The custom dictionary lives under the personas root (`state/custom_dictionary.txt`)
+//!
+//! **Responsibilities:**
+//! - Load the wordlist and the user's custom dictionary once
+//! - Flag unknown words (with close suggestions), double spaces, and unbalanced quotes/brackets
+//! - Render an annotated preview without touching the text that gets posted
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::collections::HashSet;
+use std::sync::RwLock;
+use once_cell::sync::Lazy;
+use crate::prelude::*;
+use crate::persona::{state_path, writable_state_path};
+
+/// Words added with `ignore <word>`, one per line (`{root}/state/custom_dictionary.txt`)
+pub(crate) const CUSTOM_DICTIONARY_FILE: &str = "custom_dictionary.txt";
+
+/// Longest tweet X accepts on the free tier
+pub const TWEET_CHAR_LIMIT: usize = 280;
+
+/// # Dictionary
+///
+/// **Summary:**
+/// Known words: the system wordlist plus the custom dictionary, all lowercase.
+#[derive(Debug, Default)]
+struct Dictionary {
+    words: HashSet<String>,
+    loaded_wordlist: bool,
+}
+
+static DICTIONARY: Lazy<RwLock<Dictionary>> = Lazy::new(|| RwLock::new(Dictionary::load()));
+
+impl Dictionary {
+    fn load() -> Self {
+        Self::load_from(Path::new(&GLOBAL_CONFIG.twitter.spellcheck_wordlist), &state_path(CUSTOM_DICTIONARY_FILE))
+    }
+
+    /// Reads the wordlist and the custom dictionary; either may be missing
+    fn load_from(wordlist: &Path, custom: &Path) -> Self {
+        let mut words = HashSet::new();
+
+        let loaded_wordlist = match fs::read_to_string(wordlist) {
+            Ok(content) => {
+                words.extend(content.lines().map(|w| w.trim().to_lowercase()).filter(|w| !w.is_empty()));
+                true
+            }
+            Err(e) => {
+                log_error!("Spellcheck wordlist {} unavailable: {}", wordlist.display(), e);
+                false
+            }
+        };
+
+        if let Ok(content) = fs::read_to_string(custom) {
+            words.extend(content.lines().map(|w| w.trim().to_lowercase()).filter(|w| !w.is_empty()));
+        }
+
+        Self { words, loaded_wordlist }
+    }
+
+    fn knows(&self, word: &str) -> bool {
+        let lower = word.to_lowercase();
+        let base = lower.strip_suffix("'s").unwrap_or(&lower);
+        self.words.contains(&lower) || self.words.contains(base)
+    }
+
+    /// Known words one edit (delete, swap, replace, insert) away, best first
+    fn suggest(&self, word: &str) -> Vec<String> {
+        const LETTERS: &str = "abcdefghijklmnopqrstuvwxyz";
+        let chars: Vec<char> = word.to_lowercase().chars().collect();
+        let mut candidates: Vec<String> = Vec::new();
+
+        for i in 0..=chars.len() {
+            if i < chars.len() {
+                candidates.push(chars[..i].iter().chain(&chars[i + 1..]).collect());
+            }
+            if i + 1 < chars.len() {
+                let mut swapped = chars.clone();
+                swapped.swap(i, i + 1);
+                candidates.push(swapped.into_iter().collect());
+            }
+            for letter in LETTERS.chars() {
+                if i < chars.len() {
+                    let mut replaced = chars.clone();
+                    replaced[i] = letter;
+                    candidates.push(replaced.into_iter().collect());
+                }
+                let mut inserted = chars.clone();
+                inserted.insert(i, letter);
+                candidates.push(inserted.into_iter().collect());
+            }
+        }
+
+        let mut seen = HashSet::new();
+        candidates.into_iter()
+            .filter(|candidate| self.words.contains(candidate) && seen.insert(candidate.clone()))
+            .take(3)
+            .collect()
+    }
+}
+
+/// # Finding
+///
+/// **Summary:**
+/// One thing worth a second look in a tweet.
+///
+/// **Variants:**
+/// - `Misspelled`: Unknown word and up to three suggestions
+/// - `DoubleSpace`: Two or more spaces in a row
+/// - `Unbalanced`: A quote or bracket character that isn't closed (or opened)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Finding {
+    Misspelled { word: String, suggestions: Vec<String> },
+    DoubleSpace,
+    Unbalanced(char),
+}
+
+impl Finding {
+    pub fn describe(&self) -> String {
+        match self {
+            Finding::Misspelled { word, suggestions } if suggestions.is_empty() => {
+                format!("unknown word \"{}\"", word)
+            }
+            Finding::Misspelled { word, suggestions } => {
+                format!("unknown word \"{}\" (did you mean {}?)", word, suggestions.join(", "))
+            }
+            Finding::DoubleSpace => "double space".to_string(),
+            Finding::Unbalanced(c) => format!("unbalanced {}", c),
+        }
+    }
+}
+
+/// # PartCheck
+///
+/// **Summary:**
+/// Results for one tweet (or one part of a thread).
+#[derive(Debug, Clone)]
+pub struct PartCheck {
+    pub chars: usize,
+    pub findings: Vec<Finding>,
+}
+
+impl PartCheck {
+    pub fn over_limit(&self) -> bool {
+        self.chars > TWEET_CHAR_LIMIT
+    }
+}
+
+/// Words worth spellchecking: skips mentions, hashtags, links, numbers, and acronyms
+fn checkable_words(text: &str) -> impl Iterator<Item = &str> {
+    text.split_whitespace()
+        .filter(|token| !token.starts_with(['@', '#']) && !token.contains("://") && !token.starts_with("www."))
+        .flat_map(|token| token.split(|c: char| !(c.is_alphabetic() || c == '\'')))
+        .map(|word| word.trim_matches('\''))
+        .filter(|word| word.chars().count() > 1)
+        .filter(|word| !word.chars().all(|c| c.is_uppercase()))
+}
+
+/// # preload
+///
+/// **Purpose:**
+/// Loads the dictionaries ahead of the first check (call from a blocking task at startup).
+pub fn preload() {
+    Lazy::force(&DICTIONARY);
+}
+
+/// # check_part
+///
+/// **Purpose:**
+/// Checks one tweet's text.
+///
+/// **Details:**
+/// Spelling is skipped (not failed) when no wordlist could be loaded.
+pub fn check_part(text: &str) -> PartCheck {
+    check_with(&DICTIONARY.read().unwrap_or_else(|e| e.into_inner()), text)
+}
+
+/// `check_part` against a given dictionary
+fn check_with(dictionary: &Dictionary, text: &str) -> PartCheck {
+    let mut findings = Vec::new();
+
+    if dictionary.loaded_wordlist {
+        let mut flagged = HashSet::new();
+        for word in checkable_words(text) {
+            if !dictionary.knows(word) && flagged.insert(word.to_lowercase()) {
+                findings.push(Finding::Misspelled {
+                    word: word.to_string(),
+                    suggestions: dictionary.suggest(word),
+                });
+            }
+        }
+    }
+
+    if text.contains("  ") {
+        findings.push(Finding::DoubleSpace);
+    }
+
+    let count = |c: char| text.chars().filter(|x| *x == c).count();
+    for (open, close) in [('(', ')'), ('[', ']'), ('{', '}'), ('“', '”')] {
+        if count(open) != count(close) {
+            findings.push(Finding::Unbalanced(if count(open) > count(close) { open } else { close }));
+        }
+    }
+    if count('"') % 2 == 1 {
+        findings.push(Finding::Unbalanced('"'));
+    }
+
+    PartCheck { chars: text.chars().count(), findings }
+}
+
+/// # annotate
+///
+/// **Purpose:**
+/// Renders a preview with unknown words bracketed, plus a summary line.
+///
+/// **Parameters:**
+/// - `text`: The tweet as it will be posted (never modified)
+/// - `check`: Results from `check_part` for the same text
+///
+/// **Returns:**
+/// `String` - e.g. `I [recieve] it` + `\n  142/280 chars · unknown word "recieve" (did you mean receive?)`
+pub fn annotate(text: &str, check: &PartCheck) -> String {
+    let misspelled: HashSet<String> = check.findings.iter()
+        .filter_map(|finding| match finding {
+            Finding::Misspelled { word, .. } => Some(word.to_lowercase()),
+            _ => None,
+        })
+        .collect();
+
+    let mut preview = String::with_capacity(text.len() + misspelled.len() * 2);
+    let mut word = String::new();
+    let flush = |word: &mut String, preview: &mut String| {
+        let bare = word.trim_matches('\'');
+        if !bare.is_empty() && misspelled.contains(&bare.to_lowercase()) {
+            preview.push('[');
+            preview.push_str(word);
+            preview.push(']');
+        } else {
+            preview.push_str(word);
+        }
+        word.clear();
+    };
+
+    for c in text.chars() {
+        if c.is_alphabetic() || c == '\'' {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut preview);
+            preview.push(c);
+        }
+    }
+    flush(&mut word, &mut preview);
+
+    let mut summary = vec![format!(
+        "{}/{} chars{}",
+        check.chars, TWEET_CHAR_LIMIT, if check.over_limit() { " (too long)" } else { "" }
+    )];
+    summary.extend(check.findings.iter().map(Finding::describe));
+
+    format!("{}\n  {}", preview, summary.join(" · "))
+}
+
+/// # check_and_annotate
+///
+/// **Purpose:**
+/// Annotated preview for a tweet, or None when spellchecking is disabled in config.
+pub fn check_and_annotate(text: &str) -> Option<String> {
+    GLOBAL_CONFIG.twitter.spellcheck.then(|| annotate(text, &check_part(text)))
+}
+
+/// # ignore_word
+///
+/// **Purpose:**
+/// Adds a word to the custom dictionary so it stops being flagged.
+///
+/// **Errors / Failures:**
+/// - The custom dictionary cannot be written
+pub fn ignore_word(word: &str) -> Result<(), ShadowError> {
+    let word = word.trim().to_lowercase();
+    let mut dictionary = DICTIONARY.write().unwrap_or_else(|e| e.into_inner());
+    if !dictionary.words.insert(word.clone()) {
+        return Ok(());
+    }
+
+    append_word(&writable_state_path(CUSTOM_DICTIONARY_FILE)?, &word)?;
+    Ok(())
+}
+
+/// Adds one line to the custom dictionary at `path`
+fn append_word(path: &Path, word: &str) -> io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persona::STATE_DIR;
+    use crate::test_support::{test_personas_root, ScratchDir};
+
+    fn dictionary(words: &[&str]) -> Dictionary {
+        Dictionary { words: words.iter().map(|w| w.to_string()).collect(), loaded_wordlist: true }
+    }
+
+    #[test]
+    fn annotate_brackets_unknown_words_and_summarizes() {
+        let dictionary = dictionary(&["i", "it", "receive"]);
+        let text = "I recieve it";
+        let preview = annotate(text, &check_with(&dictionary, text));
+
+        assert_eq!(preview, "I [recieve] it\n  12/280 chars · unknown word \"recieve\" (did you mean receive?)");
+    }
+
+    #[test]
+    fn annotate_lists_punctuation_findings_after_the_length() {
+        let dictionary = dictionary(&["hi", "there"]);
+        let text = "Hi  (there";
+        let preview = annotate(text, &check_with(&dictionary, text));
+
+        assert_eq!(preview, "Hi  (there\n  10/280 chars · double space · unbalanced (");
+    }
+
+    #[test]
+    fn annotate_marks_a_tweet_over_the_limit() {
+        let text = "a ".repeat(150);
+        let preview = annotate(&text, &check_with(&dictionary(&[]), &text));
+
+        assert_eq!(preview, format!("{}\n  300/280 chars (too long)", text));
+    }
+
+    #[test]
+    fn the_preview_is_the_posted_text_plus_brackets() {
+        let dictionary = dictionary(&["posting", "this", "today", "don't"]);
+        let text = "Postng thiss today, don't @someone #tag https://exmple.com 👋";
+        let check = check_with(&dictionary, text);
+        let preview = annotate(text, &check);
+
+        let (annotated, _summary) = preview.split_once("\n  ").unwrap();
+        assert_eq!(annotated, "[Postng] [thiss] today, don't @someone #tag https://exmple.com 👋");
+        assert_eq!(annotated.replace(['[', ']'], ""), text);
+        assert_eq!(check.chars, text.chars().count());
+    }
+
+    #[test]
+    fn spelling_is_skipped_without_a_wordlist() {
+        let scratch = ScratchDir::new("spellcheck-missing");
+        let dictionary = Dictionary::load_from(&scratch.join("words"), &scratch.join("custom.txt"));
+        let check = check_with(&dictionary, "Compleetly unchecked");
+
+        assert!(!dictionary.loaded_wordlist);
+        assert!(check.findings.is_empty());
+    }
+
+    #[test]
+    fn ignored_words_are_known_after_a_reload() {
+        let scratch = ScratchDir::new("spellcheck-custom");
+        let wordlist = scratch.write("words", "hello\nworld\n");
+        let custom = scratch.join("custom.txt");
+        append_word(&custom, "grokprime").unwrap();
+        append_word(&custom, "daegonica").unwrap();
+
+        let dictionary = Dictionary::load_from(&wordlist, &custom);
+        assert!(dictionary.knows("GrokPrime"));
+        assert!(dictionary.knows("daegonica"));
+        assert!(check_with(&dictionary, "hello grokprime world").findings.is_empty());
+    }
+
+    #[test]
+    fn ignore_word_saves_under_the_personas_root() {
+        let root = test_personas_root();
+        ignore_word("Zzyzxquor").unwrap();
+
+        let saved = fs::read_to_string(root.join(STATE_DIR).join(CUSTOM_DICTIONARY_FILE)).unwrap();
+        assert!(saved.lines().any(|line| line == "zzyzxquor"));
+        assert!(check_part("zzyzxquor").findings.is_empty());
+    }
+}
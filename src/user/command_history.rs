@@ -0,0 +1,428 @@
+//! # Daegonica Module: user::command_history
+//!
+//! **Purpose:** Shell-style recall of commands run earlier (`!!`, `!status`, `!? draft`)
+//!
+//! **Context:**
+//! - Separate from chat: only lines that ran a command are kept, never messages
+//! - Expanded by `UserInput::process_input` before normal parsing
+//! - Kept in `command_history.json` so recall works across restarts
+//!
+//! **Responsibilities:**
+//! - Recognize the expansion forms, and the `\!` escape for a literal leading "!"
+//! - Record executed command lines, bounded by `tui.command_history_size`
+//! - Resolve an expansion to the line it stands for
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::collections::VecDeque;
+use chrono::{DateTime, Utc};
+use crate::prelude::*;
+use crate::agent_history::persistence;
+use crate::persona::state_path;
+
+pub(crate) const COMMAND_HISTORY_FILE: &str = "command_history.json";
+
+/// Entries `!?` on its own lists
+const LISTED_ENTRIES: usize = 10;
+
+/// # Expansion
+///
+/// **Summary:**
+/// One history expansion form.
+///
+/// **Variants:**
+/// - `Last`: `!!`, the last command
+/// - `Prefix(String)`: `!status`, the latest command starting with the word
+/// - `Search(String)`: `!? draft`, the latest command containing the text
+/// - `List`: `!?` alone, the recent commands
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expansion {
+    Last,
+    Prefix(String),
+    Search(String),
+    List,
+}
+
+/// # parse_expansion
+///
+/// **Purpose:**
+/// Recognizes input that is exactly one expansion form.
+///
+/// **Details:**
+/// Anything else that starts with "!" is an ordinary message: `!!` only alone,
+/// `!word` only as a single word with no spaces, `!?` only at the start.
+/// `!wow` is still an expansion (of a command that may not exist), so
+/// `\!wow` is the way to send it literally (see `unescape`).
+pub fn parse_expansion(input: &str) -> Option<Expansion> {
+    let input = input.trim();
+    if input == "!!" {
+        return Some(Expansion::Last);
+    }
+    if let Some(query) = input.strip_prefix("!?") {
+        let query = query.trim();
+        return Some(if query.is_empty() { Expansion::List } else { Expansion::Search(query.to_string()) });
+    }
+    let word = input.strip_prefix('!')?;
+    let starts_with_letter = word.chars().next().is_some_and(|c| c.is_ascii_alphabetic());
+    (starts_with_letter && word.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        .then(|| Expansion::Prefix(word.to_string()))
+}
+
+/// The literal text for input escaped with a leading `\!`, which skips expansion
+pub fn unescape(input: &str) -> Option<&str> {
+    input.trim_start().strip_prefix('\\').filter(|rest| rest.starts_with('!'))
+}
+
+/// # CommandRecord
+///
+/// **Summary:**
+/// One executed command line.
+///
+/// **Fields:**
+/// - `line`: The line as it ran (after any expansion)
+/// - `action`: The command it ran, e.g. "AgentStatus"
+/// - `at`: When it ran
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandRecord {
+    pub line: String,
+    pub action: String,
+    pub at: DateTime<Utc>,
+}
+
+/// # CommandHistory
+///
+/// **Summary:**
+/// Executed command lines, oldest first (`{root}/state/command_history.json`).
+///
+/// **Details:**
+/// Running the same line twice in a row keeps one entry, like a shell's
+/// `ignoredups`; the oldest entries drop off past the configured size.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandHistory {
+    #[serde(default)]
+    entries: VecDeque<CommandRecord>,
+}
+
+impl CommandHistory {
+    /// Loads saved history (empty if none was saved or it can't be read)
+    pub fn load() -> Self {
+        Self::load_from(&state_path(COMMAND_HISTORY_FILE))
+    }
+
+    /// # load_from
+    ///
+    /// **Purpose:**
+    /// Loads history from `path`.
+    ///
+    /// **Details:**
+    /// A missing file is an empty history; an unreadable one is logged and
+    /// treated the same, since recall is a convenience.
+    pub fn load_from(path: &Path) -> Self {
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                log_error!("Ignoring unreadable command history {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                log_error!("Could not read command history {}: {}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), ShadowError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &CommandRecord> {
+        self.entries.iter()
+    }
+
+    /// # record
+    ///
+    /// **Purpose:**
+    /// Adds an executed line, if it ran a command.
+    ///
+    /// **Parameters:**
+    /// - `line`: The line as it ran
+    /// - `action`: What it parsed to; chat messages and no-ops aren't recorded
+    /// - `limit`: Entries kept
+    ///
+    /// **Returns:**
+    /// `bool` - Whether the history changed
+    pub fn record(&mut self, line: &str, action: &InputAction, limit: usize) -> bool {
+        if matches!(
+            action,
+            InputAction::SendAsMessage(_)
+                | InputAction::DoNothing
+                | InputAction::ContinueNoSend(_)
+                | InputAction::RecallCommand { .. }
+        ) {
+            return false;
+        }
+        let line = line.trim();
+        if self.entries.back().is_some_and(|last| last.line == line) {
+            return false;
+        }
+        let name: &'static str = action.into();
+        self.entries.push_back(CommandRecord { line: line.to_string(), action: name.to_string(), at: Utc::now() });
+        while self.entries.len() > limit {
+            self.entries.pop_front();
+        }
+        true
+    }
+
+    /// # resolve
+    ///
+    /// **Purpose:**
+    /// The recorded line an expansion stands for.
+    ///
+    /// **Returns:**
+    /// `Result<&CommandRecord, String>` - The latest matching entry, or why there's none
+    ///
+    /// **Details:**
+    /// `Expansion::List` never resolves; use `describe_recent`.
+    pub fn resolve(&self, expansion: &Expansion) -> Result<&CommandRecord, String> {
+        let mut newest_first = self.entries.iter().rev();
+        let found = match expansion {
+            Expansion::Last => newest_first.next(),
+            Expansion::Prefix(prefix) => newest_first.find(|record| record.line.starts_with(prefix.as_str())),
+            Expansion::Search(query) => newest_first.find(|record| record.line.contains(query.as_str())),
+            Expansion::List => None,
+        };
+        found.ok_or_else(|| match expansion {
+            Expansion::Last | Expansion::List => "No commands in history yet.".to_string(),
+            Expansion::Prefix(prefix) => format!(
+                "No command starting with \"{}\" in history. To send \"!{}\" as a message, type \\!{}",
+                prefix, prefix, prefix
+            ),
+            Expansion::Search(query) => format!("No command containing \"{}\" in history.", query),
+        })
+    }
+
+    /// The recent commands, newest last, for `!?` on its own
+    pub fn describe_recent(&self) -> String {
+        if self.entries.is_empty() {
+            return "No commands in history yet.".to_string();
+        }
+        let skip = self.entries.len().saturating_sub(LISTED_ENTRIES);
+        let mut lines = vec!["Recent commands (`!!` last, `!<word>` latest starting with it, `!? <text>` latest containing it):".to_string()];
+        lines.extend(self.entries.iter().skip(skip).map(|record| format!("  {}", record.line)));
+        lines.join("\n")
+    }
+}
+
+/// # SharedCommandHistory
+///
+/// **Summary:**
+/// The session's command history, shared by every clone of `UserInput`.
+///
+/// **Details:**
+/// Each recorded line is saved straight away as an automatic write, so it's
+/// skipped while persistence is suspended.
+#[derive(Debug, Clone, Default)]
+pub struct SharedCommandHistory {
+    inner: Arc<std::sync::Mutex<CommandHistory>>,
+    path: Option<PathBuf>,
+}
+
+impl SharedCommandHistory {
+    /// The persisted history (`{root}/state/command_history.json`)
+    pub fn load() -> Self {
+        Self {
+            inner: Arc::new(std::sync::Mutex::new(CommandHistory::load())),
+            path: Some(state_path(COMMAND_HISTORY_FILE)),
+        }
+    }
+
+    pub fn lock(&self) -> std::sync::MutexGuard<'_, CommandHistory> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Records an executed line and saves the history if it changed
+    pub fn record(&self, line: &str, action: &InputAction) {
+        let mut history = self.lock();
+        if !history.record(line, action, GLOBAL_CONFIG.tui.command_history_size) {
+            return;
+        }
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Err(e) = persistence::auto_write(|| Ok(history.save_to(path)?)) {
+            log_error!("Failed to save command history: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScratchDir;
+    use crate::user::user_input::UserInput;
+
+    /// History saved under `scratch`, as a restart would load it
+    fn shared(scratch: &ScratchDir) -> SharedCommandHistory {
+        let path = scratch.join(COMMAND_HISTORY_FILE);
+        SharedCommandHistory { inner: Arc::new(std::sync::Mutex::new(CommandHistory::load_from(&path))), path: Some(path) }
+    }
+
+    /// Parses `line` and records it, as the input loop does before running it
+    fn run(input: &UserInput, line: &str) -> InputAction {
+        let action = input.process_input(line);
+        input.record_command(line, &action);
+        action
+    }
+
+    /// The line and confirmation of a `RecallCommand`, or what else the input became
+    fn recalled(action: InputAction) -> Result<(String, bool), String> {
+        match action {
+            InputAction::RecallCommand { line, confirmed } => Ok((line, confirmed)),
+            InputAction::ContinueNoSend(shown) => Err(shown),
+            other => Err(format!("{:?}", other)),
+        }
+    }
+
+    fn lines(history: &CommandHistory) -> Vec<&str> {
+        history.entries().map(|record| record.line.as_str()).collect()
+    }
+
+    #[test]
+    fn only_the_exact_forms_expand() {
+        let cases = [
+            ("!!", Some(Expansion::Last)),
+            ("  !!  ", Some(Expansion::Last)),
+            ("!status", Some(Expansion::Prefix("status".into()))),
+            ("!history-list", Some(Expansion::Prefix("history-list".into()))),
+            ("!? draft", Some(Expansion::Search("draft".into()))),
+            ("!?draft", Some(Expansion::Search("draft".into()))),
+            ("!?", Some(Expansion::List)),
+            ("!!!", None),
+            ("!! again", None),
+            ("!hello there", None),
+            ("! wow", None),
+            ("!42", None),
+            ("!wow!", None),
+            ("hello!", None),
+            ("\\!!", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_expansion(input), expected, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn an_escaped_bang_is_sent_literally() {
+        assert_eq!(unescape("\\!!"), Some("!!"));
+        assert_eq!(unescape("  \\!wow"), Some("!wow"));
+        assert_eq!(unescape("\\hello"), None);
+        assert_eq!(unescape("!wow"), None);
+
+        let input = UserInput::new_for_tui();
+        for (line, sent) in [("\\!status", "!status"), ("!hello there", "!hello there")] {
+            assert!(matches!(input.process_input(line), InputAction::SendAsMessage(text) if text == sent), "{}", line);
+        }
+    }
+
+    #[test]
+    fn only_commands_are_recorded_and_repeats_are_kept_once() {
+        let mut history = CommandHistory::default();
+        assert!(history.record("status", &InputAction::AgentStatus, 3));
+        assert!(!history.record("status", &InputAction::AgentStatus, 3), "same line twice in a row");
+        assert!(!history.record("hi there", &InputAction::SendAsMessage("hi there".into()), 3));
+        assert!(!history.record("!!", &InputAction::RecallCommand { line: "status".into(), confirmed: false }, 3));
+        assert!(!history.record("oops", &InputAction::ContinueNoSend("Usage".into()), 3));
+        for line in ["list", "usage", "status"] {
+            history.record(line, &InputAction::ListAgents, 3);
+        }
+        assert_eq!(lines(&history), ["list", "usage", "status"], "the oldest drop off past the limit");
+        assert_eq!(history.entries().last().map(|record| record.action.as_str()), Some("ListAgents"));
+    }
+
+    #[test]
+    fn each_form_resolves_to_the_latest_match() {
+        let mut history = CommandHistory::default();
+        for line in ["draft first idea", "status", "list", "draft second idea", "usage"] {
+            history.record(line, &InputAction::Usage, 10);
+        }
+        let cases = [
+            (Expansion::Last, Ok("usage")),
+            (Expansion::Prefix("st".into()), Ok("status")),
+            (Expansion::Prefix("draft".into()), Ok("draft second idea")),
+            (Expansion::Search("first".into()), Ok("draft first idea")),
+            (Expansion::Search("idea".into()), Ok("draft second idea")),
+            (Expansion::Prefix("wow".into()), Err("No command starting with \"wow\" in history. To send \"!wow\" as a message, type \\!wow")),
+            (Expansion::Search("tweet".into()), Err("No command containing \"tweet\" in history.")),
+            (Expansion::List, Err("No commands in history yet.")),
+        ];
+        for (expansion, expected) in cases {
+            let resolved = history.resolve(&expansion).map(|record| record.line.as_str());
+            assert_eq!(resolved, expected.map_err(str::to_string), "{:?}", expansion);
+        }
+        assert_eq!(CommandHistory::default().resolve(&Expansion::Last).unwrap_err(), "No commands in history yet.");
+    }
+
+    #[test]
+    fn listing_shows_the_recent_commands() {
+        let mut history = CommandHistory::default();
+        assert_eq!(history.describe_recent(), "No commands in history yet.");
+        for n in 0..12 {
+            history.record(&format!("focus {}", n), &InputAction::Usage, 20);
+        }
+        let listed = history.describe_recent();
+        let shown: Vec<&str> = listed.lines().skip(1).map(str::trim).collect();
+        assert_eq!(shown.len(), LISTED_ENTRIES);
+        assert_eq!((shown[0], shown[9]), ("focus 2", "focus 11"));
+    }
+
+    #[test]
+    fn recall_works_across_a_restart() {
+        let scratch = ScratchDir::new("command-history");
+        let input = UserInput::new_for_tui().with_command_history(shared(&scratch));
+        run(&input, "status");
+        run(&input, "a message, not a command");
+        run(&input, "list");
+
+        let restarted = UserInput::new_for_tui().with_command_history(shared(&scratch));
+        assert_eq!(lines(&shared(&scratch).lock()), ["status", "list"]);
+        let confirmed = !GLOBAL_CONFIG.tui.confirm_history_expansion;
+        assert_eq!(recalled(restarted.process_input("!!")), Ok(("list".to_string(), confirmed)));
+        assert_eq!(recalled(restarted.process_input("!stat")), Ok(("status".to_string(), confirmed)));
+        assert_eq!(recalled(restarted.process_input("!? is")), Ok(("list".to_string(), confirmed)));
+        let listed = recalled(restarted.process_input("!?")).unwrap_err();
+        assert!(listed.ends_with("  status\n  list"), "{}", listed);
+        assert_eq!(
+            recalled(restarted.process_input("!nothing")),
+            Err("No command starting with \"nothing\" in history. To send \"!nothing\" as a message, type \\!nothing".to_string())
+        );
+
+        std::fs::write(scratch.join(COMMAND_HISTORY_FILE), "not json").unwrap();
+        assert!(lines(&CommandHistory::load_from(&scratch.join(COMMAND_HISTORY_FILE))).is_empty(), "an unreadable file is an empty history");
+    }
+
+    #[test]
+    fn a_bare_recall_of_a_destructive_command_always_asks() {
+        let scratch = ScratchDir::new("command-history-guard");
+        let input = UserInput::new_for_tui().with_command_history(shared(&scratch));
+        let fresh = run(&input, "resync fresh");
+        assert!(fresh.is_destructive(), "{:?}", fresh);
+
+        assert_eq!(recalled(input.process_input("!!")), Ok(("resync fresh".to_string(), false)));
+        let confirmed = !GLOBAL_CONFIG.tui.confirm_history_expansion;
+        assert_eq!(
+            recalled(input.process_input("!resync")),
+            Ok(("resync fresh".to_string(), confirmed)),
+            "naming the command is confirmation enough"
+        );
+    }
+}
@@ -0,0 +1,112 @@
+//! # Daegonica Module: user::macros
+//!
+//! **Purpose:** Recorded command macros for repetitive TUI workflows
+//!
+//! **Context:**
+//! - `macro record <name>` captures submitted command lines, `macro run <name>` replays them
+//! - Lines are stored as typed and parsed again on replay, so they survive keymap changes
+//! - Replay is driven by `ShadowApp::advance_macro` from the TUI loop
+//!
+//! **Responsibilities:**
+//! - Persist macros in `macros.yaml`
+//! - Hold the in-progress recording and replay state
+//! - Decide which steps need confirmation or an idle agent
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::collections::{BTreeMap, VecDeque};
+use crate::prelude::*;
+use crate::persona::{state_path, writable_state_path};
+
+/// Saved macros (`{root}/state/macros.yaml`)
+pub(crate) const MACROS_FILE: &str = "macros.yaml";
+
+/// # Macro
+///
+/// **Summary:**
+/// A named sequence of command lines.
+///
+/// **Fields:**
+/// - `steps`: Lines exactly as they were submitted
+/// - `trusted`: Destructive steps run without asking for confirmation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Macro {
+    pub steps: Vec<String>,
+    #[serde(default)]
+    pub trusted: bool,
+}
+
+impl Macro {
+    /// # destructive_steps
+    ///
+    /// **Purpose:**
+    /// Lists the steps that need a confirmed run (see `InputAction::is_destructive`).
+    pub fn destructive_steps(&self, parser: &UserInput) -> Vec<String> {
+        self.steps.iter()
+            .filter(|line| parser.process_input(line).is_destructive())
+            .cloned()
+            .collect()
+    }
+}
+
+/// # MacroStore
+///
+/// **Summary:**
+/// All saved macros, keyed by name (`macros.yaml`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MacroStore {
+    #[serde(default)]
+    pub macros: BTreeMap<String, Macro>,
+}
+
+impl MacroStore {
+    /// Loads saved macros (empty if none have been saved yet)
+    pub fn load() -> Result<Self, ShadowError> {
+        match fs::read_to_string(state_path(MACROS_FILE)) {
+            Ok(content) => Ok(serde_yaml::from_str(&content)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), ShadowError> {
+        fs::write(writable_state_path(MACROS_FILE)?, serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// # MacroRecording
+///
+/// **Summary:**
+/// A macro being captured from submitted lines.
+#[derive(Debug, Clone, Default)]
+pub struct MacroRecording {
+    pub name: String,
+    pub steps: Vec<String>,
+    pub trusted: bool,
+}
+
+/// # MacroReplay
+///
+/// **Summary:**
+/// A macro being replayed, one step per UI tick.
+#[derive(Debug, Clone, Default)]
+pub struct MacroReplay {
+    pub name: String,
+    pub remaining: VecDeque<String>,
+}
+
+impl MacroReplay {
+    pub fn new(name: impl Into<String>, steps: &[String]) -> Self {
+        Self {
+            name: name.into(),
+            remaining: steps.iter().cloned().collect(),
+        }
+    }
+}
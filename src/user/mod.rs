@@ -7,7 +7,7 @@
 //! - Provides system information for context-aware responses
 //!
 //! **Responsibilities:**
-//! - Expose user input and system info modules
+//! - Expose user input, command history, macro, and system info modules
 //! - Does NOT contain implementation (module declaration only)
 //!
 //! **Author:** Daegonica Software
@@ -18,5 +18,8 @@
 //! This file is part of the Daegonica Software codebase.
 //! ---------------------------------------------------------------
 
+pub mod command_history;
+pub mod macros;
+pub mod outgoing;
 pub mod system_info;
 pub mod user_input;
\ No newline at end of file
@@ -0,0 +1,395 @@
+//! # Daegonica Module: user::outgoing
+//!
+//! **Purpose:** One preprocessing pipeline for everything sent to an agent
+//!
+//! **Context:**
+//! - Input is transformed before it reaches the API (trimming, prompt templates, attached files)
+//! - The agent pane echoes the display form; `sent` shows what the model actually got
+//!
+//! **Responsibilities:**
+//! - Define `Transform` steps that each produce a display form and a send form
+//! - Run steps in order and keep a `SentMessage` record of the result
+//! - Render a line diff between the typed input and the sent content
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::path::Path;
+use crate::persona::review::REPLACEMENT_PATH_PREFIX;
+use crate::utilities::accessibility::glyph;
+use crate::utilities::code_lang::Language;
+use crate::utilities::explain::ExplainRequest;
+use crate::utilities::language::{self, Lang};
+
+/// Sent records kept per agent for `sent [n]`
+pub const SENT_RECORD_LIMIT: usize = 20;
+
+/// # Transform
+///
+/// **Summary:**
+/// One preprocessing step.
+///
+/// **Details:**
+/// `apply` gets the current display and send forms and returns new ones, so a
+/// step can change what's sent without hiding that from the echo (or vice versa).
+pub trait Transform: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn apply(&self, display: String, send: String) -> (String, String);
+}
+
+/// # Trim
+///
+/// **Summary:**
+/// Strips outer whitespace and trailing whitespace on each line (common in pastes).
+pub struct Trim;
+
+impl Transform for Trim {
+    fn name(&self) -> &'static str {
+        "trim"
+    }
+
+    fn apply(&self, display: String, send: String) -> (String, String) {
+        let trim = |text: String| text.lines().map(str::trim_end).collect::<Vec<_>>().join("\n").trim().to_string();
+        (trim(display), trim(send))
+    }
+}
+
+/// # DraftTemplate
+///
+/// **Summary:**
+/// Wraps a tweet idea in the drafting instructions; the echo shows just the idea.
+///
+/// **Fields:**
+/// - `language`: Language the tweet should be written in, if known
+pub struct DraftTemplate {
+    pub language: Option<Lang>,
+}
+
+impl Transform for DraftTemplate {
+    fn name(&self) -> &'static str {
+        "draft template"
+    }
+
+    fn apply(&self, display: String, send: String) -> (String, String) {
+        let prompt = format!(r#"
+            Please draft a tweet with the following content: "{}"
+            Keep it under 280 characters and suitable for Twitter.
+            Respond only with the tweet text, no additional commentary.
+            Use a casual and engaging tone.
+            Have at least one hashtag relevant to the content.
+            Have at least one mention of a relevant Twitter handle.
+            Prefer threads if necessary to fit the content.
+            Make it engaging and likely to get interactions.
+            Tag it with -Shadow at the end.
+            "#, send);
+        let prompt = match self.language {
+            Some(lang) => format!("{}{}\n", prompt, language::instruction(lang)),
+            None => prompt,
+        };
+        (format!("Tweet Draft: {}", display), prompt)
+    }
+}
+
+/// # ExplainTemplate
+///
+/// **Summary:**
+/// Replaces `explain` with the packaged error and context; the echo names the error.
+///
+/// **Fields:**
+/// - `request`: The failed request and its context
+/// - `max_chars`: Cap on what's sent (`explain.max_prompt_chars`)
+pub struct ExplainTemplate {
+    pub request: ExplainRequest,
+    pub max_chars: usize,
+}
+
+impl Transform for ExplainTemplate {
+    fn name(&self) -> &'static str {
+        "explain template"
+    }
+
+    fn apply(&self, _display: String, _send: String) -> (String, String) {
+        (format!("Explain: {}", self.request.headline()), self.request.prompt(self.max_chars))
+    }
+}
+
+/// # AttachTemplate
+///
+/// **Summary:**
+/// Sends a file for review with the typed note; the echo names the file instead of pasting it.
+///
+/// **Fields:**
+/// - `path`: The file as typed after `attach`
+/// - `code`: Its contents
+///
+/// **Details:**
+/// The code goes in a fenced block whose first line is `// file: <path>`, the
+/// same header the reviewer persona uses for replacement blocks.
+pub struct AttachTemplate {
+    pub path: String,
+    pub code: String,
+}
+
+impl Transform for AttachTemplate {
+    fn name(&self) -> &'static str {
+        "attach template"
+    }
+
+    fn apply(&self, display: String, send: String) -> (String, String) {
+        let fence = Path::new(&self.path).extension()
+            .and_then(|extension| Language::for_extension(&extension.to_string_lossy()))
+            .map(|lang| lang.to_string())
+            .unwrap_or_default();
+        let request = if send.is_empty() { "Please review this file.".to_string() } else { send };
+        let prompt = format!(
+            "{}\n\n```{}\n{} {}\n{}\n```",
+            request, fence, REPLACEMENT_PATH_PREFIX, self.path, self.code.trim_end()
+        );
+        let lines = self.code.lines().count();
+        let echo = format!("Attached {} ({} line{})", self.path, lines, if lines == 1 { "" } else { "s" });
+        let echo = if display.is_empty() { echo } else { format!("{}: {}", echo, display) };
+        (echo, prompt)
+    }
+}
+
+/// # SentMessage
+///
+/// **Summary:**
+/// What was typed, what the pane shows, and what the model was sent.
+///
+/// **Fields:**
+/// - `typed`: The input as submitted
+/// - `display`: Form echoed in the agent pane
+/// - `send`: Exact content added to the conversation
+/// - `steps`: Names of the steps that changed something, in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentMessage {
+    pub typed: String,
+    pub display: String,
+    pub send: String,
+    pub steps: Vec<&'static str>,
+}
+
+impl SentMessage {
+    /// The agent-pane echo line, with a badge when the sent content differs
+    pub fn echo(&self) -> String {
+        if self.send == self.typed {
+            format!("> {}", self.display)
+        } else {
+            format!("> {} {}", self.display, glyph("✎", "(transformed)"))
+        }
+    }
+}
+
+/// # Pipeline
+///
+/// **Summary:**
+/// Ordered preprocessing steps for one kind of outgoing message.
+///
+/// **Usage Example:**
+/// ```ignore
+/// use grokprime_brain::user::outgoing::Pipeline;
+///
+/// let sent = Pipeline::chat().run(&line);
+/// connection.add_user_message(&sent.send);
+/// agent.echo_sent(sent);
+/// ```
+pub struct Pipeline {
+    steps: Vec<Box<dyn Transform>>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    pub fn step(mut self, step: impl Transform + 'static) -> Self {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    /// Steps for a regular chat message
+    pub fn chat() -> Self {
+        Self::new().step(Trim)
+    }
+
+    /// Steps for a `draft` tweet request, written in `language` when given
+    pub fn draft(language: Option<Lang>) -> Self {
+        Self::new().step(Trim).step(DraftTemplate { language })
+    }
+
+    /// Steps for `attach`: the typed note becomes a review request carrying the file
+    pub fn attach(path: impl Into<String>, code: impl Into<String>) -> Self {
+        Self::new().step(Trim).step(AttachTemplate { path: path.into(), code: code.into() })
+    }
+
+    /// Steps for `explain`: the typed command becomes the packaged error
+    pub fn explain(request: ExplainRequest, max_chars: usize) -> Self {
+        Self::new().step(ExplainTemplate { request, max_chars })
+    }
+
+    /// # run
+    ///
+    /// **Purpose:**
+    /// Runs every step over the typed input.
+    pub fn run(&self, typed: &str) -> SentMessage {
+        let (mut display, mut send) = (typed.to_string(), typed.to_string());
+        let mut steps = Vec::new();
+        for step in &self.steps {
+            let (next_display, next_send) = step.apply(display.clone(), send.clone());
+            if next_display != display || next_send != send {
+                steps.push(step.name());
+            }
+            (display, send) = (next_display, next_send);
+        }
+        SentMessage { typed: typed.to_string(), display, send, steps }
+    }
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// # line_diff
+///
+/// **Purpose:**
+/// Line-by-line diff from `before` to `after` (longest common subsequence).
+///
+/// **Returns:**
+/// `Vec<String>` - Lines prefixed with "  " (kept), "- " (removed), or "+ " (added)
+pub fn line_diff(before: &str, after: &str) -> Vec<String> {
+    let a: Vec<&str> = before.lines().collect();
+    let b: Vec<&str> = after.lines().collect();
+
+    // lcs[i][j] = common lines between a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::new();
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            out.push(format!("  {}", a[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(format!("- {}", a[i]));
+            i += 1;
+        } else {
+            out.push(format!("+ {}", b[j]));
+            j += 1;
+        }
+    }
+    out.extend(a[i..].iter().map(|line| format!("- {}", line)));
+    out.extend(b[j..].iter().map(|line| format!("+ {}", line)));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Upper-cases what's sent but leaves the echo alone
+    struct Shout;
+
+    impl Transform for Shout {
+        fn name(&self) -> &'static str {
+            "shout"
+        }
+
+        fn apply(&self, display: String, send: String) -> (String, String) {
+            (display, send.to_uppercase())
+        }
+    }
+
+    #[test]
+    fn chat_messages_are_trimmed_in_both_forms() {
+        // typed -> (display and send, steps)
+        let cases: [(&str, &str, &[&str]); 5] = [
+            ("hello", "hello", &[]),
+            ("  hello  ", "hello", &["trim"]),
+            ("line one   \nline two\t\n\n", "line one\nline two", &["trim"]),
+            ("keep  inner   spacing", "keep  inner   spacing", &[]),
+            ("   ", "", &["trim"]),
+        ];
+        for (typed, expected, steps) in cases {
+            let sent = Pipeline::chat().run(typed);
+            assert_eq!((sent.display.as_str(), sent.send.as_str()), (expected, expected), "{:?}", typed);
+            assert_eq!(sent.steps, steps, "{:?}", typed);
+            assert_eq!(sent.typed, typed);
+        }
+    }
+
+    #[test]
+    fn a_draft_echoes_the_idea_and_sends_the_template() {
+        let sent = Pipeline::draft(None).run("  launch day for the new pipeline ");
+
+        assert_eq!(sent.display, "Tweet Draft: launch day for the new pipeline");
+        assert!(sent.send.contains(r#"Please draft a tweet with the following content: "launch day for the new pipeline""#), "{}", sent.send);
+        assert!(!sent.send.contains("Respond in the same language"));
+        assert_eq!(sent.steps, ["trim", "draft template"]);
+
+        let french = Pipeline::draft(Some(Lang::Fra)).run("la sortie");
+        assert!(french.send.trim_end().ends_with(&language::instruction(Lang::Fra)), "{}", french.send);
+    }
+
+    #[test]
+    fn an_attachment_is_named_in_the_echo_and_fenced_in_what_is_sent() {
+        let code = "fn main() {\n    println!(\"hi\");\n}\n";
+
+        let sent = Pipeline::attach("src/main.rs", code).run(" why does this print twice? ");
+        assert_eq!(sent.display, "Attached src/main.rs (3 lines): why does this print twice?");
+        assert_eq!(
+            sent.send,
+            "why does this print twice?\n\n```rust\n// file: src/main.rs\nfn main() {\n    println!(\"hi\");\n}\n```"
+        );
+
+        let bare = Pipeline::attach("notes.unknown", "one line").run("");
+        assert_eq!(bare.display, "Attached notes.unknown (1 line)");
+        assert!(bare.send.starts_with("Please review this file.\n\n```\n// file: notes.unknown\n"), "{}", bare.send);
+    }
+
+    #[test]
+    fn steps_run_in_order_and_only_changes_are_listed() {
+        let sent = Pipeline::new().step(Trim).step(Shout).step(Trim).run(" quiet please ");
+        assert_eq!(sent.display, "quiet please");
+        assert_eq!(sent.send, "QUIET PLEASE");
+        assert_eq!(sent.steps, ["trim", "shout"], "the second trim changed nothing");
+
+        let untouched = Pipeline::new().run("as typed ");
+        assert_eq!((untouched.display.as_str(), untouched.send.as_str()), ("as typed ", "as typed "));
+        assert!(untouched.steps.is_empty());
+    }
+
+    #[test]
+    fn the_echo_is_badged_only_when_the_sent_content_differs() {
+        assert_eq!(Pipeline::chat().run("same").echo(), "> same");
+        assert_eq!(Pipeline::chat().run("same ").echo(), "> same ✎");
+        assert_eq!(Pipeline::new().step(Shout).run("quiet").echo(), "> quiet ✎", "the display can match the typing and still be badged");
+    }
+
+    #[test]
+    fn line_diff_marks_kept_removed_and_added_lines() {
+        let cases: [(&str, &str, &[&str]); 5] = [
+            ("same", "same", &["  same"]),
+            ("", "added", &["+ added"]),
+            ("removed", "", &["- removed"]),
+            ("a\nb\nc", "a\nB\nc", &["  a", "- b", "+ B", "  c"]),
+            ("idea", "Draft:\nidea\nthanks", &["+ Draft:", "  idea", "+ thanks"]),
+        ];
+        for (before, after, expected) in cases {
+            assert_eq!(line_diff(before, after), expected, "{:?} -> {:?}", before, after);
+        }
+    }
+}
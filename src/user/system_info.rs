@@ -5,10 +5,12 @@
 //! **Context:**
 //! - Provides system context for AI responses
 //! - Used when user requests system information via 'system' command
+//! - Static details are read once per process; some platforms are slow to answer
 //!
 //! **Responsibilities:**
 //! - Detect and report OS type, version, and kernel information
 //! - Provide hostname and system details
+//! - Sample session context (uptime, battery, load, memory) on refresh
 //! - Format system information for display
 //!
 //! **Author:** Daegonica Software
@@ -19,8 +21,99 @@
 //! This file is part of the Daegonica Software codebase.
 //! ---------------------------------------------------------------
 
+use once_cell::sync::Lazy;
 use sysinfo::System;
 
+/// Name, version, kernel, and host never change while we run
+static STATIC_INFO: Lazy<(String, String, String, String)> = Lazy::new(|| (
+    System::name().unwrap_or_default(),
+    System::os_version().unwrap_or_default(),
+    System::kernel_version().unwrap_or_default(),
+    System::host_name().unwrap_or_default(),
+));
+
+/// Hostname of this machine (empty if the OS doesn't report one)
+pub fn host_name() -> String {
+    STATIC_INFO.3.clone()
+}
+
+/// # BatteryStatus
+///
+/// **Summary:**
+/// Charge level of the machine's battery.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BatteryStatus {
+    pub percent: f32,
+    pub charging: bool,
+}
+
+/// # SystemProbe
+///
+/// **Summary:**
+/// Source of the dynamic fields, one platform-specific implementation per OS.
+///
+/// **Details:**
+/// Anything a platform can't report comes back as None rather than an error.
+pub trait SystemProbe {
+    fn uptime_secs(&self) -> Option<u64>;
+    fn battery(&self) -> Option<BatteryStatus>;
+    fn load_average(&self) -> Option<f64>;
+    fn memory_used_percent(&self) -> Option<f32>;
+}
+
+/// # HostProbe
+///
+/// **Summary:**
+/// Probe for the machine we're running on (sysinfo, plus sysfs for the battery on Linux).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HostProbe;
+
+impl SystemProbe for HostProbe {
+    fn uptime_secs(&self) -> Option<u64> {
+        Some(System::uptime()).filter(|secs| *secs > 0)
+    }
+
+    fn battery(&self) -> Option<BatteryStatus> {
+        read_battery()
+    }
+
+    fn load_average(&self) -> Option<f64> {
+        // sysinfo reports zeros on Windows, which has no load average
+        if cfg!(windows) {
+            return None;
+        }
+        Some(System::load_average().one)
+    }
+
+    fn memory_used_percent(&self) -> Option<f32> {
+        let mut system = System::new();
+        system.refresh_memory();
+        let total = system.total_memory();
+        (total > 0).then(|| system.used_memory() as f32 / total as f32 * 100.0)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_battery() -> Option<BatteryStatus> {
+    let entries = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    entries.flatten()
+        .map(|entry| entry.path())
+        .filter(|path| std::fs::read_to_string(path.join("type")).is_ok_and(|kind| kind.trim() == "Battery"))
+        .find_map(|path| {
+            let percent = std::fs::read_to_string(path.join("capacity")).ok()?.trim().parse().ok()?;
+            let status = std::fs::read_to_string(path.join("status")).unwrap_or_default();
+            Some(BatteryStatus {
+                percent,
+                charging: matches!(status.trim(), "Charging" | "Full"),
+            })
+        })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_battery() -> Option<BatteryStatus> {
+    None
+}
+
 /// # OsInfo
 ///
 /// **Summary:**
@@ -31,6 +124,14 @@ use sysinfo::System;
 /// - `version`: OS version string
 /// - `kernel_version`: Kernel or build version
 /// - `host_name`: Network hostname of the machine
+/// - `uptime_secs`: Seconds since boot
+/// - `battery`: Battery charge, if the machine has one and the platform reports it
+/// - `load_average`: One-minute load average (not available on Windows)
+/// - `memory_used_percent`: Share of physical memory in use
+///
+/// **Details:**
+/// The first four fields come from a per-process cache; the rest are sampled
+/// by `new` and `refresh`.
 ///
 /// **Usage Example:**
 /// ```rust
@@ -43,6 +144,10 @@ pub struct OsInfo {
     pub version: String,
     pub kernel_version: String,
     pub host_name: String,
+    pub uptime_secs: Option<u64>,
+    pub battery: Option<BatteryStatus>,
+    pub load_average: Option<f64>,
+    pub memory_used_percent: Option<f32>,
 }
 
 /// # OsType
@@ -84,18 +189,30 @@ impl OsInfo {
     /// let os_info = OsInfo::new();
     /// ```
     pub fn new() -> Self {
-        Self {
-            name: System::name().unwrap_or_default(),
-            version: System::os_version().unwrap_or_default(),
-            kernel_version: System::kernel_version().unwrap_or_default(),
-            host_name: System::host_name().unwrap_or_default(),
-        }
+        Self::with_probe(&HostProbe)
+    }
+
+    /// # with_probe
+    ///
+    /// **Purpose:**
+    /// Creates an OsInfo whose dynamic fields come from the given probe.
+    pub fn with_probe(probe: &dyn SystemProbe) -> Self {
+        let (name, version, kernel_version, host_name) = STATIC_INFO.clone();
+        let mut info = Self {
+            name,
+            version,
+            kernel_version,
+            host_name,
+            ..Self::default()
+        };
+        info.refresh_with(probe);
+        info
     }
 
     /// # refresh
     ///
     /// **Purpose:**
-    /// Re-queries system information to update all fields.
+    /// Re-samples the dynamic fields (uptime, battery, load, memory).
     ///
     /// **Parameters:**
     /// None
@@ -106,7 +223,14 @@ impl OsInfo {
     /// **Errors / Failures:**
     /// - None (infallible)
     pub fn refresh(&mut self) {
-        *self = Self::new();
+        self.refresh_with(&HostProbe);
+    }
+
+    pub fn refresh_with(&mut self, probe: &dyn SystemProbe) {
+        self.uptime_secs = probe.uptime_secs();
+        self.battery = probe.battery();
+        self.load_average = probe.load_average();
+        self.memory_used_percent = probe.memory_used_percent();
     }
 
     /// # os_type
@@ -179,6 +303,78 @@ impl OsInfo {
         format!("Host Name: {}", self.host_name)
     }
 
+    /// # display_uptime
+    ///
+    /// **Purpose:**
+    /// Formats the time since boot for display.
+    ///
+    /// **Returns:**
+    /// Formatted string, e.g. "Uptime: 6h 12m"
+    pub fn display_uptime(&self) -> String {
+        format!("Uptime: {}", self.uptime_secs.map(format_duration).unwrap_or_else(|| "unknown".to_string()))
+    }
+
+    /// # display_battery
+    ///
+    /// **Purpose:**
+    /// Formats the battery charge for display.
+    ///
+    /// **Returns:**
+    /// Formatted string, e.g. "Battery: 9% (discharging)"
+    pub fn display_battery(&self) -> String {
+        match self.battery {
+            Some(battery) => format!(
+                "Battery: {:.0}% ({})",
+                battery.percent, if battery.charging { "charging" } else { "discharging" }
+            ),
+            None => "Battery: none".to_string(),
+        }
+    }
+
+    /// # display_load
+    ///
+    /// **Purpose:**
+    /// Formats the one-minute load average and memory use for display.
+    ///
+    /// **Returns:**
+    /// Formatted string, e.g. "Load: 1.20, Memory: 63% used"
+    pub fn display_load(&self) -> String {
+        let load = self.load_average
+            .map(|load| format!("{:.2}", load))
+            .unwrap_or_else(|| "n/a".to_string());
+        let memory = self.memory_used_percent
+            .map(|used| format!("{:.0}% used", used))
+            .unwrap_or_else(|| "n/a".to_string());
+        format!("Load: {}, Memory: {}", load, memory)
+    }
+
+    /// # display_session_context
+    ///
+    /// **Purpose:**
+    /// One line of dynamic context suitable for injecting into a prompt.
+    ///
+    /// **Returns:**
+    /// e.g. "Machine up 6h 12m, battery 9% (discharging), load 1.20, memory 63% used"
+    pub fn display_session_context(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(secs) = self.uptime_secs {
+            parts.push(format!("Machine up {}", format_duration(secs)));
+        }
+        if let Some(battery) = self.battery {
+            parts.push(format!(
+                "battery {:.0}% ({})",
+                battery.percent, if battery.charging { "charging" } else { "discharging" }
+            ));
+        }
+        if let Some(load) = self.load_average {
+            parts.push(format!("load {:.2}", load));
+        }
+        if let Some(used) = self.memory_used_percent {
+            parts.push(format!("memory {:.0}% used", used));
+        }
+        parts.join(", ")
+    }
+
     /// # display_all
     ///
     /// **Purpose:**
@@ -194,11 +390,127 @@ impl OsInfo {
     /// ```
     pub fn display_all(&self)  -> String {
         format!(
-            "{}\n{}\n{}\n{}",
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}",
             self.display_name(),
             self.display_version(),
             self.display_kernel_version(),
             self.display_host_name(),
+            self.display_uptime(),
+            self.display_battery(),
+            self.display_load(),
         )
     }
-}
\ No newline at end of file
+}
+
+/// Renders a duration as "2d 3h", "6h 12m", or "45m"
+fn format_duration(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86_400, secs % 86_400 / 3600, secs % 3600 / 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct FakeProbe {
+        uptime_secs: Option<u64>,
+        battery: Option<BatteryStatus>,
+        load_average: Option<f64>,
+        memory_used_percent: Option<f32>,
+    }
+
+    impl SystemProbe for FakeProbe {
+        fn uptime_secs(&self) -> Option<u64> { self.uptime_secs }
+        fn battery(&self) -> Option<BatteryStatus> { self.battery }
+        fn load_average(&self) -> Option<f64> { self.load_average }
+        fn memory_used_percent(&self) -> Option<f32> { self.memory_used_percent }
+    }
+
+    fn laptop() -> FakeProbe {
+        FakeProbe {
+            uptime_secs: Some(6 * 3600 + 12 * 60 + 30),
+            battery: Some(BatteryStatus { percent: 9.4, charging: false }),
+            load_average: Some(1.2),
+            memory_used_percent: Some(62.6),
+        }
+    }
+
+    #[test]
+    fn durations_keep_the_two_largest_units() {
+        let cases = [
+            (0, "0m"),
+            (59, "0m"),
+            (45 * 60, "45m"),
+            (3600, "1h 0m"),
+            (6 * 3600 + 12 * 60 + 59, "6h 12m"),
+            (86_400, "1d 0h"),
+            (2 * 86_400 + 3 * 3600 + 59 * 60, "2d 3h"),
+        ];
+        for (secs, expected) in cases {
+            assert_eq!(format_duration(secs), expected, "{} seconds", secs);
+        }
+    }
+
+    #[test]
+    fn dynamic_fields_format_for_display() {
+        let info = OsInfo::with_probe(&laptop());
+        assert_eq!(info.display_uptime(), "Uptime: 6h 12m");
+        assert_eq!(info.display_battery(), "Battery: 9% (discharging)");
+        assert_eq!(info.display_load(), "Load: 1.20, Memory: 63% used");
+        assert_eq!(
+            info.display_session_context(),
+            "Machine up 6h 12m, battery 9% (discharging), load 1.20, memory 63% used"
+        );
+
+        let charging = OsInfo::with_probe(&FakeProbe {
+            battery: Some(BatteryStatus { percent: 100.0, charging: true }),
+            ..FakeProbe::default()
+        });
+        assert_eq!(charging.display_battery(), "Battery: 100% (charging)");
+        assert_eq!(charging.display_session_context(), "battery 100% (charging)");
+    }
+
+    #[test]
+    fn a_platform_that_reports_nothing_degrades_gracefully() {
+        let info = OsInfo::with_probe(&FakeProbe::default());
+        assert_eq!(info.display_uptime(), "Uptime: unknown");
+        assert_eq!(info.display_battery(), "Battery: none");
+        assert_eq!(info.display_load(), "Load: n/a, Memory: n/a");
+        assert_eq!(info.display_session_context(), "");
+
+        let all = info.display_all();
+        let lines: Vec<&str> = all.lines().map(|line| line.split(':').next().unwrap_or_default()).collect();
+        assert_eq!(lines, ["OS Name", "OS Version", "Kernel Version", "Host Name", "Uptime", "Battery", "Load"]);
+    }
+
+    #[test]
+    fn refresh_resamples_only_the_dynamic_fields() {
+        let mut info = OsInfo::with_probe(&laptop());
+        info.name = "Cached OS".to_string();
+        info.host_name = "cached-host".to_string();
+
+        info.refresh_with(&FakeProbe { uptime_secs: Some(90 * 60), ..FakeProbe::default() });
+        assert_eq!(info.uptime_secs, Some(5400));
+        assert_eq!(info.battery, None, "a battery that disappears is dropped");
+        assert_eq!(info.load_average, None);
+        assert_eq!(info.name, "Cached OS");
+        assert_eq!(info.host_name, "cached-host");
+    }
+
+    #[test]
+    fn static_fields_come_from_the_process_cache() {
+        let first = OsInfo::with_probe(&FakeProbe::default());
+        let second = OsInfo::with_probe(&laptop());
+        assert_eq!(
+            (&first.name, &first.version, &first.kernel_version, &first.host_name),
+            (&second.name, &second.version, &second.kernel_version, &second.host_name)
+        );
+    }
+}
@@ -10,6 +10,7 @@
 //! **Responsibilities:**
 //! - Read user input from stdin
 //! - Parse commands with arguments
+//! - Expand `!!`-style command history references before parsing
 //! - Convert input to InputAction enum variants
 //! - Validate command syntax and provide usage hints
 //!
@@ -22,8 +23,14 @@
 //! ---------------------------------------------------------------
 
 use crate::prelude::*;
-use strum::{EnumString, IntoStaticStr, EnumIter};
+use strum::{EnumString, IntoStaticStr, EnumIter, IntoEnumIterator};
 use std::str::FromStr;
+use crate::persona::experiment::Rating;
+use crate::agent_history::freshness::ResyncChoice;
+use crate::utilities::log_level::LogLevel;
+use crate::tui::layout::LayoutMode;
+use crate::persona::focus::MAX_FOCUS_MINUTES;
+use crate::user::command_history::{self, Expansion, SharedCommandHistory};
 
 /// # UserInput
 ///
@@ -33,6 +40,7 @@ use std::str::FromStr;
 /// **Fields:**
 /// - `os_info`: System information for context-aware commands
 /// - `output`: Shared output handler for displaying messages
+/// - `command_history`: Executed command lines for `!!`-style recall (empty unless one is attached)
 ///
 /// **Usage Example:**
 /// ```rust
@@ -45,6 +53,7 @@ use std::str::FromStr;
 pub struct UserInput {
     os_info: OsInfo,
     output: Option<SharedOutput>,
+    command_history: SharedCommandHistory,
 }
 
 impl std::fmt::Debug for UserInput {
@@ -52,6 +61,7 @@ impl std::fmt::Debug for UserInput {
         f.debug_struct("UserInput")
             .field("os_info", &self.os_info)
             .field("output", &"<OutputHandler>")
+            .field("command_history", &self.command_history)
             .finish()
     }
 }
@@ -78,13 +88,54 @@ impl UserInput {
     /// ```
     pub fn new(output: Option<SharedOutput>) -> Self {
         let os_info = OsInfo::new();
-        UserInput{ os_info, output}
+        UserInput{ os_info, output, command_history: SharedCommandHistory::default() }
     }
 
     pub fn new_for_tui() -> Self {
         Self::new(None)
     }
 
+    /// Uses `history` for `!!`-style recall (clones share it)
+    pub fn with_command_history(mut self, history: SharedCommandHistory) -> Self {
+        self.command_history = history;
+        self
+    }
+
+    /// Records a line that just ran, for later recall (chat messages are skipped)
+    pub fn record_command(&self, line: &str, action: &InputAction) {
+        self.command_history.record(line, action);
+    }
+
+    /// # expand_history
+    ///
+    /// **Purpose:**
+    /// Turns a `!!`, `!word`, or `!? text` line into the earlier command it names.
+    ///
+    /// **Returns:**
+    /// `InputAction` - `RecallCommand`, or `ContinueNoSend` listing or explaining a miss
+    ///
+    /// **Details:**
+    /// The recalled line runs straight away only when
+    /// `tui.confirm_history_expansion` is off, and never for a destructive
+    /// command reached through a bare `!!`.
+    fn expand_history(&self, expansion: Expansion) -> InputAction {
+        let history = self.command_history.lock();
+        if expansion == Expansion::List {
+            return InputAction::ContinueNoSend(history.describe_recent());
+        }
+        let line = match history.resolve(&expansion) {
+            Ok(record) => record.line.clone(),
+            Err(msg) => return InputAction::ContinueNoSend(msg),
+        };
+        drop(history);
+
+        let guarded = expansion == Expansion::Last && self.process_input(&line).is_destructive();
+        InputAction::RecallCommand {
+            line,
+            confirmed: !GLOBAL_CONFIG.tui.confirm_history_expansion && !guarded,
+        }
+    }
+
     /// # read_user_input
     ///
     /// **Purpose:**
@@ -136,6 +187,10 @@ impl UserInput {
     /// **Errors / Failures:**
     /// - None (unrecognized commands become SendAsMessage)
     ///
+    /// **Details:**
+    /// History expansion comes first: a line that is exactly `!!`, `!word`, or
+    /// `!? text` becomes `RecallCommand`, and `\!...` sends the rest as a message.
+    ///
     /// **Examples:**
     /// ```rust
     /// let action = user_input.process_input("tweet Hello world!");
@@ -145,6 +200,13 @@ impl UserInput {
     /// }
     /// ```
     pub fn process_input(&self, raw_input: &str) -> InputAction {
+        if let Some(literal) = command_history::unescape(raw_input) {
+            return InputAction::SendAsMessage(literal.to_string());
+        }
+        if let Some(expansion) = command_history::parse_expansion(raw_input) {
+            return self.expand_history(expansion);
+        }
+
         let parts: Vec<&str> = raw_input.splitn(2, ' ').collect();
         let potential_command = parts[0];
         let remainder = if parts.len() > 1 { parts[1] } else { "" };
@@ -154,7 +216,10 @@ impl UserInput {
         match cmd {
             // System OS info command
             UserCommand::System => {
-                let output_text = self.os_info.display_all();
+                // Static details are cached, so this only re-samples uptime/battery/load
+                let mut os_info = self.os_info.clone();
+                os_info.refresh();
+                let output_text = os_info.display_all();
                 InputAction::ContinueNoSend(output_text)
             },
 
@@ -165,13 +230,29 @@ impl UserInput {
             UserCommand::Tweet => {
                 if remainder.is_empty() {
                     if let Some(ref output) = self.output {
-                        output.display("Usage: tweet <your message>".to_string());
+                        output.display("Usage: tweet <your message> | tweet confirm | tweet cancel | tweet --later <message> | tweet check <message> | tweet quota".to_string());
                     }
                     InputAction::DoNothing
+                } else if remainder.trim() == "quota" {
+                    InputAction::TweetQuota
+                } else if remainder.trim() == "confirm" {
+                    InputAction::ConfirmTweet
+                } else if remainder.trim() == "cancel" {
+                    InputAction::CancelTweet
+                } else if let Some(text) = remainder.strip_prefix("check").map(str::trim).filter(|t| !t.is_empty()) {
+                    InputAction::CheckTweet(text.to_string())
+                } else if let Some(text) = remainder.strip_prefix("--later").map(str::trim).filter(|t| !t.is_empty()) {
+                    InputAction::QueueTweet(text.to_string())
                 } else {
                     InputAction::PostTweet(remainder.to_string())
                 }
             },
+            UserCommand::Ignore => {
+                match remainder.split_whitespace().collect::<Vec<_>>().as_slice() {
+                    [word] => InputAction::IgnoreWord(word.to_string()),
+                    _ => InputAction::ContinueNoSend("Usage: ignore <word>".to_string()),
+                }
+            }
             UserCommand::Draft => {
                 if remainder.is_empty() {
                     if let Some(ref output) = self.output {
@@ -187,6 +268,7 @@ impl UserInput {
             UserCommand::Status => {
                 InputAction::AgentStatus
             }
+            UserCommand::Usage => InputAction::Usage,
             UserCommand::New => {
                 if remainder.is_empty() {
                     if let Some(ref output) = self.output {
@@ -199,10 +281,287 @@ impl UserInput {
             },
             UserCommand::Close => InputAction::CloseAgent,
             UserCommand::List => InputAction::ListAgents,
+            UserCommand::Help => InputAction::Help,
+            UserCommand::Attach => match remainder.trim().split_once(char::is_whitespace) {
+                _ if remainder.trim().is_empty() => InputAction::ContinueNoSend("Usage: attach <file> [note]".to_string()),
+                Some((path, note)) => InputAction::Attach { path: path.to_string(), note: note.trim().to_string() },
+                None => InputAction::Attach { path: remainder.trim().to_string(), note: String::new() },
+            },
+            UserCommand::Inspect => match remainder.trim() {
+                "" => InputAction::Inspect { diff: false },
+                "diff" => InputAction::Inspect { diff: true },
+                _ => InputAction::ContinueNoSend("Usage: inspect | inspect diff".to_string()),
+            },
+            UserCommand::Mute => InputAction::MuteAgent(true),
+            UserCommand::Unmute => InputAction::MuteAgent(false),
+            UserCommand::Speak => match remainder.trim() {
+                "on" => InputAction::Speak(true),
+                "off" => InputAction::Speak(false),
+                _ => InputAction::ContinueNoSend("Usage: speak on | speak off".to_string()),
+            },
+            UserCommand::Open => match remainder.trim() {
+                "" => InputAction::OpenLink(None),
+                n => match n.parse::<usize>() {
+                    Ok(nth) if nth > 0 => InputAction::OpenLink(Some(nth)),
+                    _ => InputAction::ContinueNoSend("Usage: open [n] (1 = most recent link)".to_string()),
+                },
+            },
+
+            // Persona management commands
+            UserCommand::Persona => {
+                let args: Vec<&str> = remainder.split_whitespace().collect();
+                match args.as_slice() {
+                    ["export-builtin", dir] => InputAction::ExportBuiltinPersonas { dir: dir.to_string(), inline: false },
+                    ["export-builtin", dir, "--inline"] => InputAction::ExportBuiltinPersonas { dir: dir.to_string(), inline: true },
+                    ["history", name] => InputAction::PersonaHistory(name.to_string()),
+                    _ => InputAction::ContinueNoSend(
+                        "Usage: persona export-builtin <dir> [--inline] | persona history <name>".to_string()
+                    ),
+                }
+            }
+
+            UserCommand::Overview => {
+                let usage = || InputAction::ContinueNoSend("Usage: overview [--sort <column>] [--json]".to_string());
+                let mut sort = None;
+                let mut json = false;
+                let mut args = remainder.split_whitespace();
+                while let Some(arg) = args.next() {
+                    match (arg, sort.is_none()) {
+                        ("--json", _) => json = true,
+                        ("--sort", true) => match args.next() {
+                            Some(column) => sort = Some(column.to_string()),
+                            None => return usage(),
+                        },
+                        _ => return usage(),
+                    }
+                }
+                InputAction::Overview { sort, json }
+            }
+            UserCommand::Insights => {
+                let usage = || InputAction::ContinueNoSend("Usage: insights [YYYY-MM] [--md <path>]".to_string());
+                let mut month = None;
+                let mut export = None;
+                let mut args = remainder.split_whitespace();
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "--md" if export.is_none() => match args.next() {
+                            Some(path) => export = Some(path.to_string()),
+                            None => return usage(),
+                        },
+                        _ if month.is_none() && !arg.starts_with("--") => month = Some(arg.to_string()),
+                        _ => return usage(),
+                    }
+                }
+                InputAction::Insights { month, export }
+            }
+
+            // Global pane commands
+            UserCommand::Global => {
+                let args: Vec<&str> = remainder.split_whitespace().collect();
+                match args.as_slice() {
+                    ["clear"] => InputAction::GlobalClear,
+                    ["filter"] | ["filter", "off"] | ["filter", "all"] => InputAction::GlobalFilter(None),
+                    ["filter", filter] => InputAction::GlobalFilter(Some(filter.to_string())),
+                    _ => InputAction::ContinueNoSend(
+                        "Usage: global filter <persona|errors|commands|off> | global clear".to_string()
+                    ),
+                }
+            }
+
+            UserCommand::Layout => {
+                match remainder.trim().parse::<LayoutMode>() {
+                    Ok(mode) => InputAction::SetLayout(mode),
+                    Err(_) => InputAction::ContinueNoSend("Usage: layout auto|split|single|stacked".to_string()),
+                }
+            }
+            UserCommand::Timestamps => match remainder.trim() {
+                "" => InputAction::Timestamps(None),
+                "on" => InputAction::Timestamps(Some(true)),
+                "off" => InputAction::Timestamps(Some(false)),
+                _ => InputAction::ContinueNoSend("Usage: timestamps [on|off]".to_string()),
+            },
+
+            // Runtime maintenance commands
+            UserCommand::Reload => {
+                match remainder.trim() {
+                    "" => InputAction::ReloadPersonas { apply: false },
+                    "--apply" => InputAction::ReloadPersonas { apply: true },
+                    "keys" => InputAction::ReloadKeys,
+                    _ => InputAction::ContinueNoSend("Usage: reload [--apply] | reload keys".to_string()),
+                }
+            }
+
+            UserCommand::Whence => {
+                match remainder.trim() {
+                    "" => InputAction::Whence(1),
+                    n => match n.parse::<usize>() {
+                        Ok(nth_last) if nth_last > 0 => InputAction::Whence(nth_last),
+                        _ => InputAction::ContinueNoSend("Usage: whence [n] (1 = last reply)".to_string()),
+                    },
+                }
+            }
+
+            UserCommand::Sent => {
+                match remainder.trim() {
+                    "" => InputAction::Sent(1),
+                    n => match n.parse::<usize>() {
+                        Ok(nth_last) if nth_last > 0 => InputAction::Sent(nth_last),
+                        _ => InputAction::ContinueNoSend("Usage: sent [n] (1 = last message)".to_string()),
+                    },
+                }
+            }
+
+            UserCommand::LogLevel => {
+                let args: Vec<&str> = remainder.split_whitespace().collect();
+                let level = args.first().and_then(|level| level.parse::<LogLevel>().ok());
+                match (level, args.as_slice()) {
+                    (Some(level), [_]) => InputAction::SetLogLevel { level, target: None },
+                    (Some(level), [_, target]) => InputAction::SetLogLevel { level, target: Some(target.to_string()) },
+                    _ => InputAction::ContinueNoSend(
+                        "Usage: loglevel <error|warn|info|debug|trace> [module::path]".to_string()
+                    ),
+                }
+            }
+
+            // Prompt experiment commands
+            UserCommand::Experiment => {
+                let args: Vec<&str> = remainder.split_whitespace().collect();
+                match args.as_slice() {
+                    ["start", name, variant_path] => InputAction::ExperimentStart {
+                        name: name.to_string(),
+                        variant_path: variant_path.to_string(),
+                    },
+                    ["status"] => InputAction::ExperimentStatus,
+                    ["stop"] => InputAction::ExperimentStop(None),
+                    ["stop", name] => InputAction::ExperimentStop(Some(name.to_string())),
+                    _ => InputAction::ContinueNoSend(
+                        "Usage: experiment start <name> <variant-b.yaml> | experiment status | experiment stop [name]".to_string()
+                    ),
+                }
+            }
+            UserCommand::Rate => {
+                match remainder.trim().to_lowercase().as_str() {
+                    "good" => InputAction::RateConversation(Rating::Good),
+                    "bad" => InputAction::RateConversation(Rating::Bad),
+                    _ => InputAction::ContinueNoSend("Usage: rate good|bad".to_string()),
+                }
+            }
+
+            // Generation jobs
+            UserCommand::Jobs => {
+                let args: Vec<&str> = remainder.split_whitespace().collect();
+                match args.as_slice() {
+                    [] => InputAction::ListJobs,
+                    ["cancel", id] => match id.parse() {
+                        Ok(id) => InputAction::CancelJob(id),
+                        Err(_) => InputAction::ContinueNoSend("Usage: jobs cancel <n>".to_string()),
+                    },
+                    _ => InputAction::ContinueNoSend("Usage: jobs | jobs cancel <n>".to_string()),
+                }
+            }
+
+            UserCommand::Stop => InputAction::Stop,
+
+            // Macros
+            UserCommand::Macro => {
+                let args: Vec<&str> = remainder.split_whitespace().collect();
+                match args.as_slice() {
+                    ["record", name] => InputAction::MacroRecord { name: name.to_string(), trusted: false },
+                    ["record", name, "--trusted"] => InputAction::MacroRecord { name: name.to_string(), trusted: true },
+                    ["stop"] => InputAction::MacroStop { trusted: false },
+                    ["stop", "--trusted"] => InputAction::MacroStop { trusted: true },
+                    ["run", name] => InputAction::MacroRun { name: name.to_string(), confirmed: false },
+                    ["run", name, "confirm"] => InputAction::MacroRun { name: name.to_string(), confirmed: true },
+                    ["list"] => InputAction::MacroList,
+                    ["delete", name] => InputAction::MacroDelete(name.to_string()),
+                    _ => InputAction::ContinueNoSend(
+                        "Usage: macro record <name> [--trusted] | macro stop [--trusted] | macro run <name> [confirm] | macro list | macro delete <name>".to_string()
+                    ),
+                }
+            }
+
+            UserCommand::Profile => {
+                match remainder.trim() {
+                    "" => InputAction::Profile { frames: false },
+                    "frames" => InputAction::Profile { frames: true },
+                    _ => InputAction::ContinueNoSend("Usage: profile | profile frames".to_string()),
+                }
+            }
+
+            UserCommand::Focus => {
+                let usage = format!("Usage: focus <minutes 1-{}> [goal] | focus cancel", MAX_FOCUS_MINUTES);
+                let (first, goal) = remainder.trim().split_once(char::is_whitespace)
+                    .map(|(first, goal)| (first, Some(goal.trim().to_string())))
+                    .unwrap_or((remainder.trim(), None));
+                match first {
+                    "" => InputAction::FocusStatus,
+                    "cancel" if goal.is_none() => InputAction::FocusCancel,
+                    minutes => match minutes.parse::<u32>() {
+                        Ok(minutes) if (1..=MAX_FOCUS_MINUTES).contains(&minutes) => InputAction::FocusStart { minutes, goal },
+                        _ => InputAction::ContinueNoSend(usage),
+                    },
+                }
+            }
+
+            UserCommand::Tour => InputAction::Tour,
+
+            UserCommand::Language => {
+                match remainder.split_whitespace().collect::<Vec<_>>().as_slice() {
+                    [] => InputAction::Language(None),
+                    [code] => InputAction::Language(Some(code.to_string())),
+                    _ => InputAction::ContinueNoSend("Usage: language | language <code or name> | language auto".to_string()),
+                }
+            }
+
+            UserCommand::Model => {
+                match remainder.split_whitespace().collect::<Vec<_>>().as_slice() {
+                    [] => InputAction::Model(None),
+                    [model] => InputAction::Model(Some(model.to_string())),
+                    _ => InputAction::ContinueNoSend("Usage: model | model <name> | model default".to_string()),
+                }
+            }
+
+            UserCommand::Set => {
+                match remainder.split_whitespace().collect::<Vec<_>>().as_slice() {
+                    ["summarize"] => InputAction::SetSummarize(None),
+                    ["summarize", mode] => InputAction::SetSummarize(Some(mode.to_string())),
+                    _ => InputAction::ContinueNoSend("Usage: set summarize [auto|manual|off|default]".to_string()),
+                }
+            }
+
+            UserCommand::Nudge => {
+                match remainder.trim() {
+                    "" => InputAction::ContinueNoSend("Usage: nudge <instruction> | nudge list | nudge clear".to_string()),
+                    "list" => InputAction::NudgeList,
+                    "clear" => InputAction::NudgeClear,
+                    instruction => InputAction::NudgeAdd(instruction.to_string()),
+                }
+            }
+
+            UserCommand::Resync => match remainder.trim().parse::<ResyncChoice>() {
+                Ok(choice) => InputAction::Resync(choice),
+                Err(_) => InputAction::ContinueNoSend("Usage: resync recap | resync fresh".to_string()),
+            },
+
+            UserCommand::Explain => InputAction::Explain,
+
+            UserCommand::Rollback => {
+                let args: Vec<&str> = remainder.split_whitespace().collect();
+                let (index, apply) = match args.as_slice() {
+                    [] => (None, false),
+                    [index] => (Some(*index), false),
+                    [index, "confirm"] => (Some(*index), true),
+                    _ => return InputAction::ContinueNoSend("Usage: rollback [<n> [confirm]]".to_string()),
+                };
+                match index.map(|index| index.trim_start_matches('#').parse::<usize>()).transpose() {
+                    Ok(index) => InputAction::Rollback { index, apply },
+                    Err(_) => InputAction::ContinueNoSend("Usage: rollback [<n> [confirm]]".to_string()),
+                }
+            }
 
             // Send as regular message to agent
             UserCommand::Unknown => {
-                log_info!("Processing as regular message: {}", raw_input);
+                log_trace!("Processing as regular message: {}", raw_input);
                 InputAction::SendAsMessage(raw_input.to_string())
             },
 
@@ -210,6 +569,55 @@ impl UserInput {
             UserCommand::SaveHistory => InputAction::SaveHistory,
             UserCommand::HistoryInfo => InputAction::HistoryInfo,
             UserCommand::Summarize => InputAction::Summarize,
+            UserCommand::History => {
+                let args: Vec<&str> = remainder.split_whitespace().collect();
+                match args.as_slice() {
+                    ["dedupe"] => InputAction::DedupeHistory { apply: false },
+                    ["dedupe", "confirm"] => InputAction::DedupeHistory { apply: true },
+                    ["list"] => InputAction::HistoryList,
+                    ["remove", index, flags @ ..]
+                        if flags.len() <= 2 && flags.iter().all(|f| ["--pair", "confirm"].contains(f)) =>
+                    {
+                        match index.trim_start_matches('#').parse::<usize>() {
+                            Ok(index) => InputAction::HistoryRemove {
+                                index,
+                                pair: flags.contains(&"--pair"),
+                                apply: flags.contains(&"confirm"),
+                            },
+                            Err(_) => InputAction::ContinueNoSend("Usage: history remove <n> [--pair] [confirm]".to_string()),
+                        }
+                    }
+                    ["verify", rest @ ..] => {
+                        let (persona, flags) = match rest {
+                            [persona, flags @ ..] if !["fix", "confirm"].contains(persona) => (Some(persona.to_string()), flags),
+                            flags => (None, flags),
+                        };
+                        match flags {
+                            [] => InputAction::VerifyHistory { persona, fix: false, apply: false },
+                            ["fix"] => InputAction::VerifyHistory { persona, fix: true, apply: false },
+                            ["fix", "confirm"] => InputAction::VerifyHistory { persona, fix: true, apply: true },
+                            _ => InputAction::ContinueNoSend("Usage: history verify [persona] [fix [confirm]]".to_string()),
+                        }
+                    }
+                    _ => InputAction::ContinueNoSend(
+                        "Usage: history dedupe [confirm] | history list | history remove <n> [--pair] [confirm] | history verify [persona] [fix [confirm]]".to_string()
+                    ),
+                }
+            }
+            UserCommand::Archives => {
+                let args: Vec<&str> = remainder.split_whitespace().collect();
+                match args.as_slice() {
+                    [] => InputAction::ListArchives(None),
+                    ["since", when @ ..] if !when.is_empty() => InputAction::ListArchives(Some(when.join(" "))),
+                    ["export", archive, dest] => InputAction::ExportArchive {
+                        archive: archive.to_string(),
+                        dest: dest.to_string(),
+                    },
+                    _ => InputAction::ContinueNoSend(
+                        "Usage: archives [since <when>] | archives export <archive> <dest.md>".to_string()
+                    ),
+                }
+            }
         }
     }
 
@@ -224,11 +632,46 @@ impl UserInput {
 /// - `System`: Display system information
 /// - `Quit`: Exit the application
 /// - `Exit`: Alternative exit command
-/// - `Tweet`: Post a tweet with given text
+/// - `Tweet`: Post a tweet with given text (or queue/check it, or show quota)
+/// - `Ignore`: Add a word to the tweet spellcheck's custom dictionary
 /// - `Draft`: Generate a tweet draft from an idea
 /// - `New`: Create a new agent with specified persona
 /// - `Close`: Close the current agent
 /// - `List`: List all active agents
+/// - `Usage`: Show each agent's token use this session
+/// - `Help`: Show the getting-started command list
+/// - `Attach`: Send a file to the current agent for review, with an optional note
+/// - `Inspect`: Show the current agent's live context head, or diff its prompt (`inspect diff`)
+/// - `Mute` / `Unmute`: Toggle notifications for the current agent
+/// - `Speak`: Speak the current agent's replies aloud (`speak on|off`)
+/// - `Open`: List the current pane's links, or open one in the browser (`open [n]`)
+/// - `History`: History maintenance subcommands (dedupe, list, remove, verify)
+/// - `Archives`: List or export the current agent's archives
+/// - `Persona`: Persona management subcommands (export-builtin, history)
+/// - `Overview`: Table of every persona's history on disk (`overview [--sort <column>] [--json]`)
+/// - `Insights`: Local monthly usage report (`insights [YYYY-MM] [--md <path>]`)
+/// - `Global`: Global pane subcommands (filter, clear)
+/// - `Layout`: Choose split, single-pane, stacked, or automatic layout
+/// - `Timestamps`: Show or hide agent pane message times (`timestamps [on|off]`, alone toggles)
+/// - `Reload`: Re-scan personas (`reload [--apply]`) or re-read API keys (`reload keys`)
+/// - `Whence`: Show which model wrote the last (or nth-last) reply
+/// - `Sent`: Show exactly what was sent for the last (or nth-last) message
+/// - `LogLevel`: Change log verbosity at runtime
+/// - `Experiment`: Prompt experiment subcommands (start, status, stop)
+/// - `Rate`: Rate the current experiment conversation
+/// - `Jobs`: List or cancel running generation jobs
+/// - `Stop`: Cancel the current agent's in-flight reply
+/// - `Macro`: Record, replay, list, and delete command macros
+/// - `Profile`: Show recent command timings or frame times
+/// - `Focus`: Start, show, or cancel a focus block
+/// - `Tour`: Replay the guided TUI tour
+/// - `Language`: Show or set the conversation language used for summaries and drafts
+/// - `Model`: Show or switch the current agent's Grok model
+/// - `Set`: Per-agent settings (`set summarize`)
+/// - `Nudge`: Add, list, or clear system-role instructions for the current agent
+/// - `Resync`: Answer the recap / fresh start offer after a long break
+/// - `Explain`: Ask about the last error in the current agent's pane
+/// - `Rollback`: List, preview, or restore the current agent's checkpoints
 /// - `Unknown`: Unrecognized command (fallback)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, IntoStaticStr, EnumIter)]
 #[strum(serialize_all = "lowercase")]
@@ -244,17 +687,105 @@ enum UserCommand {
     HistoryInfo,
     Summarize,
     SaveHistory,
+    History,
+    Archives,
 
     // Twitter related
     Tweet,
     Draft,
+    Ignore,
 
     // Agent related
     New,
     Close,
     List,
     Status,
+    Usage,
+    Help,
+    Attach,
+    Inspect,
+    Mute,
+    Unmute,
+    Speak,
+    Open,
+
+    // Persona related
+    Persona,
+    Overview,
+    Insights,
+
+    // Global pane related
+    Global,
+    Layout,
+    Timestamps,
+
+    // Runtime maintenance
+    Reload,
+    Whence,
+    Sent,
+    LogLevel,
+
+    // Prompt experiments
+    Experiment,
+    Rate,
+
+    // Generation jobs
+    Jobs,
+    Stop,
+
+    // Macros
+    Macro,
+
+    // Diagnostics
+    Profile,
+
+    // Focus blocks
+    Focus,
+
+    // Onboarding
+    Tour,
+
+    // Conversation language
+    Language,
+
+    // Model selection
+    Model,
+
+    // Per-agent settings
+    Set,
+
+    // System-role steering
+    Nudge,
+
+    // Picking up after a long break
+    Resync,
+
+    // Asking about the last error
+    Explain,
+
+    // Undoing history rewrites
+    Rollback,
 
     #[strum(disabled)]
     Unknown,
 }
+
+/// # complete_command
+///
+/// **Purpose:**
+/// Lists the command names that start with `prefix`, for input completion.
+///
+/// **Returns:**
+/// `Vec<&'static str>` - Matching names (ignoring case) in declaration order;
+/// empty for an empty prefix
+pub fn complete_command(prefix: &str) -> Vec<&'static str> {
+    if prefix.is_empty() {
+        return Vec::new();
+    }
+    let prefix = prefix.to_ascii_lowercase();
+    UserCommand::iter()
+        .filter(|cmd| *cmd != UserCommand::Unknown)
+        .map(<&'static str>::from)
+        .filter(|name| name.starts_with(&prefix))
+        .collect()
+}
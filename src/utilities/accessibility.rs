@@ -0,0 +1,53 @@
+//! # Daegonica Module: utilities::accessibility
+//!
+//! **Purpose:** Screen-reader-friendly output mode
+//!
+//! **Context:**
+//! - Enabled by `--accessible` or `tui.accessible` at startup
+//! - Read by the line-building helpers, the stream handler, and status output
+//!
+//! **Responsibilities:**
+//! - Hold the process-wide on/off switch
+//! - Swap decorative glyphs for plain text labels
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// # set_enabled
+///
+/// **Purpose:**
+/// Turns accessible output on or off for the whole process.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// # is_enabled
+///
+/// **Purpose:**
+/// Whether roles must be spelled out, animation avoided, and streams batched into whole lines.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// # glyph
+///
+/// **Purpose:**
+/// Picks a decorative glyph normally, or its text label in accessible mode.
+///
+/// **Examples:**
+/// ```rust
+/// # use grokprime_brain::utilities::accessibility::glyph;
+/// let muted = glyph("🔇", "(muted)");
+/// ```
+pub fn glyph(decorative: &'static str, label: &'static str) -> &'static str {
+    if is_enabled() { label } else { decorative }
+}
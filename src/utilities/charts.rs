@@ -0,0 +1,147 @@
+//! # Daegonica Module: utilities::charts
+//!
+//! **Purpose:** Plain ASCII charts for reports shown in a pane or written to Markdown
+//!
+//! **Context:**
+//! - Used by the `insights` report; output is plain lines, so it reads the same in a pane, a log, or a code block
+//! - Only ASCII characters, so accessible mode and any terminal font render it unchanged
+//!
+//! **Responsibilities:**
+//! - Scale values to a fixed width or height, keeping every non-zero value visible
+//! - Draw labelled horizontal bars and a column chart with an x-axis
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+/// Character a bar or column is drawn with
+const FILL: char = '#';
+
+/// # scaled
+///
+/// **Purpose:**
+/// Length of `value` on a scale where `max` is `size` characters long.
+///
+/// **Details:**
+/// Rounds to the nearest character, except that a non-zero value is never
+/// drawn as zero: a persona with one message still gets a sliver.
+pub fn scaled(value: f64, max: f64, size: usize) -> usize {
+    if value <= 0.0 || max <= 0.0 {
+        return 0;
+    }
+    (((value / max) * size as f64).round() as usize).clamp(1, size)
+}
+
+/// # bar_chart
+///
+/// **Purpose:**
+/// One horizontal bar per row, labels left-aligned, each followed by its shown value.
+///
+/// **Parameters:**
+/// - `rows`: Label, value, and the text shown after the bar
+/// - `width`: Length of the longest bar
+///
+/// **Returns:**
+/// `Vec<String>` - One line per row, e.g. `shadow  | ########## 42`
+pub fn bar_chart(rows: &[(String, f64, String)], width: usize) -> Vec<String> {
+    let label_width = rows.iter().map(|(label, _, _)| label.chars().count()).max().unwrap_or(0);
+    let max = rows.iter().map(|(_, value, _)| *value).fold(0.0, f64::max);
+    rows.iter()
+        .map(|(label, value, shown)| {
+            let bar = FILL.to_string().repeat(scaled(*value, max, width));
+            format!("{:<label_width$} | {} {}", label, bar, shown).trim_end().to_string()
+        })
+        .collect()
+}
+
+/// # column_chart
+///
+/// **Purpose:**
+/// Vertical columns, one per value, over an x-axis with sparse labels.
+///
+/// **Parameters:**
+/// - `values`: Column heights in data units, left to right
+/// - `height`: Rows used for the tallest column
+/// - `labels`: Axis labels as (column index, text); a label that would overlap the previous one is dropped
+///
+/// **Returns:**
+/// `Vec<String>` - `height` rows of columns (the top one marked with the maximum), the axis, and the label row
+///
+/// **Details:**
+/// Each column is two characters wide so 24 hours or 31 days stay readable.
+pub fn column_chart(values: &[u64], height: usize, labels: &[(usize, String)]) -> Vec<String> {
+    let max = values.iter().copied().max().unwrap_or(0);
+    let gutter = max.to_string().len();
+    let heights: Vec<usize> = values.iter().map(|value| scaled(*value as f64, max as f64, height)).collect();
+
+    let mut lines: Vec<String> = (1..=height).rev()
+        .map(|row| {
+            let axis = if row == height { max.to_string() } else { String::new() };
+            let cells: String = heights.iter()
+                .map(|column| if *column >= row { format!("{} ", FILL) } else { "  ".to_string() })
+                .collect();
+            format!("{:>gutter$} |{}", axis, cells).trim_end().to_string()
+        })
+        .collect();
+    lines.push(format!("{:>gutter$} +{}", 0, "-".repeat(values.len() * 2)));
+
+    let mut label_row = " ".repeat(gutter + 2);
+    for (column, text) in labels {
+        let at = gutter + 2 + column * 2;
+        if label_row.len() > at {
+            continue;
+        }
+        label_row.push_str(&" ".repeat(at - label_row.len()));
+        label_row.push_str(text);
+        label_row.push(' ');
+    }
+    lines.push(label_row.trim_end().to_string());
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaling_rounds_but_keeps_small_values_visible() {
+        let cases = [
+            (10.0, 10.0, 30, 30),
+            (5.0, 10.0, 30, 15),
+            (0.1, 100.0, 30, 1),
+            (0.0, 10.0, 30, 0),
+            (3.0, 0.0, 30, 0),
+            (12.0, 10.0, 30, 30),
+        ];
+        for (value, max, size, expected) in cases {
+            assert_eq!(scaled(value, max, size), expected, "{} of {} in {}", value, max, size);
+        }
+    }
+
+    #[test]
+    fn bars_line_up_after_the_longest_label() {
+        let rows = vec![
+            ("shadow".to_string(), 4.0, "4".to_string()),
+            ("friday".to_string(), 2.0, "2".to_string()),
+            ("ai".to_string(), 0.0, String::new()),
+        ];
+        assert_eq!(bar_chart(&rows, 8), ["shadow | ######## 4", "friday | #### 2", "ai     |"]);
+        assert!(bar_chart(&[], 8).is_empty());
+    }
+
+    #[test]
+    fn columns_sit_over_an_axis_with_sparse_labels() {
+        let labels = vec![(0, "00".to_string()), (1, "01".to_string()), (3, "03".to_string())];
+        assert_eq!(column_chart(&[2, 0, 1, 12], 3, &labels), [
+            "12 |      #",
+            "   |      #",
+            "   |#   # #",
+            " 0 +--------",
+            "    00    03",
+        ], "01 would touch 00, so it is dropped");
+    }
+}
@@ -7,9 +7,10 @@
 //! - Parsed at application startup in main.rs
 //!
 //! **Responsibilities:**
-//! - Define CLI argument structure
-//! - Parse command-line flags
+//! - Define CLI argument structure; field doc comments are the `--help` text
+//! - Parse command-line flags and subcommands
 //! - Provide mode detection helper
+//! - Generate shell completion scripts from the same definition
 //!
 //! **Author:** Daegonica Software
 //! **Version:** 0.1.0
@@ -19,16 +20,22 @@
 //! This file is part of the Daegonica Software codebase.
 //! ---------------------------------------------------------------
 
-use clap::Parser;
+use std::io::Write;
+use std::path::PathBuf;
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use crate::agent_history::legacy::{ConflictChoice, LEGACY_HISTORY_DIR};
+use crate::persona::{DEFAULT_PERSONAS_DIR, PERSONAS_DIR_ENV};
+use crate::utilities::log_level::LogLevel;
 
 /// # Args
 ///
 /// **Summary:**
 /// Command-line arguments for controlling application mode.
 ///
-/// **Fields:**
-/// - `tui`: Enable TUI mode (default: true)
-/// - `cli`: Enable CLI mode (conflicts with tui)
+/// **Details:**
+/// Each field's doc comment is its `--help` description, so the flags are
+/// described in one place only.
 ///
 /// **Usage Example:**
 /// ```rust
@@ -41,14 +48,80 @@ use clap::Parser;
 #[command(name = "grokprime-brain")]
 #[command(about = "Shadow AI Assistant", long_about = None)]
 pub struct Args {
+    /// Run the terminal UI (the default)
     #[arg(long, default_value_t = true)]
     pub tui: bool,
 
+    /// Run the line-based CLI instead of the terminal UI
     #[arg(long, conflicts_with = "tui")]
     pub cli: bool,
 
+    /// Name of the persona a --cli session talks to (the TUI starts from its own layout)
     #[arg(long, default_value = "shadow")]
     pub persona: String,
+
+    /// Starting log verbosity (error, warn, info, debug, trace); `loglevel` changes it at runtime
+    #[arg(long)]
+    pub log_level: Option<LogLevel>,
+
+    /// Screen-reader-friendly output: text labels, no animation, whole-line streaming
+    #[arg(long)]
+    pub accessible: bool,
+
+    /// Refuse every command that posts, deletes, or overwrites files, for the whole session
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Directory holding persona files; histories, archives, and experiments are kept there too
+    #[arg(long, value_name = "DIR", env = PERSONAS_DIR_ENV, default_value = DEFAULT_PERSONAS_DIR)]
+    pub personas_dir: PathBuf,
+
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+/// # CliCommand
+///
+/// **Summary:**
+/// Subcommands that do one job and exit instead of starting a session.
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum CliCommand {
+    /// Print a shell completion script to stdout
+    Completions {
+        /// Shell to generate the script for
+        shell: Shell,
+    },
+    /// Move histories from the old flat history/ directory into each persona's directory
+    MigrateHistory {
+        /// Directory holding the old {persona}_history.json files
+        #[arg(long, value_name = "DIR", default_value = LEGACY_HISTORY_DIR)]
+        from: PathBuf,
+
+        /// Carry out the migration; without it, only report what would happen
+        #[arg(long)]
+        apply: bool,
+
+        /// When the persona already has a history: keep-newer or merge (without it, such files are left alone)
+        #[arg(long, value_name = "CHOICE")]
+        conflict: Option<ConflictChoice>,
+
+        /// Create a stub persona for files whose persona doesn't exist (otherwise they're skipped)
+        #[arg(long)]
+        create_stubs: bool,
+    },
+    /// Check saved histories against their archives; exits non-zero when a check fails
+    VerifyHistory {
+        /// Persona to verify (every persona with a saved history if omitted)
+        persona: Option<String>,
+
+        /// Repair what can be repaired (the message total, out-of-order timestamps), after asking
+        #[arg(long)]
+        fix: bool,
+
+        /// Apply --fix repairs without asking, for scheduled runs
+        #[arg(long, requires = "fix")]
+        yes: bool,
+    },
 }
 
 impl Args {
@@ -75,4 +148,129 @@ impl Args {
     pub fn is_tui_mode(&self) -> bool {
         !self.cli
     }
-}
\ No newline at end of file
+}
+
+/// # write_completions
+///
+/// **Purpose:**
+/// Writes the completion script for `shell`, generated from `Args`, to `out`.
+///
+/// **Details:**
+/// Completions are for the binary name clap reports (`grokprime-brain`), so
+/// they stay in step with every flag and subcommand defined above.
+pub fn write_completions(shell: Shell, out: &mut dyn Write) {
+    let mut command = Args::command();
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name, out);
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ValueEnum;
+    use clap::error::ErrorKind;
+
+    fn parse(line: &str) -> Result<Args, clap::Error> {
+        Args::try_parse_from(std::iter::once("grokprime-brain").chain(line.split_whitespace()))
+    }
+
+    fn parsed(line: &str) -> Args {
+        parse(line).unwrap_or_else(|e| panic!("{:?} should parse: {}", line, e))
+    }
+
+    #[test]
+    fn the_definition_is_consistent() {
+        Args::command().debug_assert();
+    }
+
+    #[test]
+    fn no_arguments_start_the_tui_with_the_defaults() {
+        let args = parsed("");
+        assert!(args.is_tui_mode());
+        assert_eq!(args.persona, "shadow");
+        assert_eq!(args.log_level, None);
+        assert!(!args.accessible && !args.strict);
+        assert_eq!(args.command, None);
+        if std::env::var_os(PERSONAS_DIR_ENV).is_none() {
+            assert_eq!(args.personas_dir, PathBuf::from(DEFAULT_PERSONAS_DIR));
+        }
+    }
+
+    #[test]
+    fn flags_parse_into_the_session_settings() {
+        let args = parsed("--cli --persona friday --log-level DEBUG --accessible --strict --personas-dir /tmp/p");
+        assert!(!args.is_tui_mode());
+        assert_eq!(args.persona, "friday");
+        assert_eq!(args.log_level, Some(LogLevel::Debug));
+        assert!(args.accessible && args.strict);
+        assert_eq!(args.personas_dir, PathBuf::from("/tmp/p"));
+
+        assert!(parsed("--tui").is_tui_mode());
+        assert_eq!(parsed("--log-level=warn").log_level, Some(LogLevel::Warn));
+    }
+
+    #[test]
+    fn subcommands_parse_with_their_options() {
+        let cases = [
+            ("completions bash", CliCommand::Completions { shell: Shell::Bash }),
+            ("completions zsh", CliCommand::Completions { shell: Shell::Zsh }),
+            ("completions powershell", CliCommand::Completions { shell: Shell::PowerShell }),
+            (
+                "migrate-history",
+                CliCommand::MigrateHistory { from: PathBuf::from(LEGACY_HISTORY_DIR), apply: false, conflict: None, create_stubs: false },
+            ),
+            (
+                "migrate-history --from old --apply --conflict keep-newer --create-stubs",
+                CliCommand::MigrateHistory { from: PathBuf::from("old"), apply: true, conflict: Some(ConflictChoice::KeepNewer), create_stubs: true },
+            ),
+            ("verify-history", CliCommand::VerifyHistory { persona: None, fix: false, yes: false }),
+            ("verify-history friday --fix --yes", CliCommand::VerifyHistory { persona: Some("friday".into()), fix: true, yes: true }),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(parsed(line).command, Some(expected), "{:?}", line);
+        }
+        assert_eq!(parsed("--strict verify-history").command, Some(CliCommand::VerifyHistory { persona: None, fix: false, yes: false }));
+    }
+
+    #[test]
+    fn bad_arguments_are_refused_with_the_reason() {
+        let cases = [
+            ("--tui --cli", ErrorKind::ArgumentConflict),
+            ("--log-level loud", ErrorKind::ValueValidation),
+            ("--persona", ErrorKind::InvalidValue),
+            ("--verbose", ErrorKind::UnknownArgument),
+            ("completions", ErrorKind::MissingRequiredArgument),
+            ("completions tcsh", ErrorKind::InvalidValue),
+            ("migrate-history --conflict newest", ErrorKind::ValueValidation),
+            ("verify-history --yes", ErrorKind::MissingRequiredArgument),
+            ("chat", ErrorKind::InvalidSubcommand),
+            ("--help", ErrorKind::DisplayHelp),
+        ];
+        for (line, kind) in cases {
+            match parse(line) {
+                Err(e) => assert_eq!(e.kind(), kind, "{:?}: {}", line, e),
+                Ok(args) => panic!("{:?} should be refused, parsed as {:?}", line, args),
+            }
+        }
+    }
+
+    #[test]
+    fn help_describes_the_persona_flags() {
+        let help = Args::command().render_help().to_string();
+        for expected in ["--persona <PERSONA>", "Name of the persona a --cli session talks to", "--personas-dir <DIR>", "Directory holding persona files", PERSONAS_DIR_ENV] {
+            assert!(help.contains(expected), "--help lacks {:?}:\n{}", expected, help);
+        }
+    }
+
+    #[test]
+    fn every_shell_gets_a_completion_script() {
+        for shell in Shell::value_variants() {
+            let mut script = Vec::new();
+            write_completions(*shell, &mut script);
+            let script = String::from_utf8(script).expect("scripts are text");
+            assert!(!script.is_empty(), "{} script is empty", shell);
+            for word in ["grokprime-brain", "completions", "migrate-history", "personas-dir"] {
+                assert!(script.contains(word), "{} script lacks {:?}", shell, word);
+            }
+        }
+    }
+}
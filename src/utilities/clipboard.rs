@@ -0,0 +1,83 @@
+//! # Daegonica Module: utilities::clipboard
+//!
+//! **Purpose:** Put text on the system clipboard, with a file to fall back on
+//!
+//! **Context:**
+//! - Used by the `inspect` view's `c` and by Ctrl+Y (copy the last reply) in the TUI
+//! - Headless sessions and SSH without a display server have no clipboard
+//!
+//! **Responsibilities:**
+//! - Copy text to the system clipboard (`arboard`)
+//! - Otherwise write it to `~/.grokprime_clipboard.txt` and say where it went
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use crate::prelude::*;
+
+/// File in the home directory that takes the text when there is no clipboard
+pub const CLIPBOARD_FALLBACK_FILE: &str = ".grokprime_clipboard.txt";
+
+/// # copy_to_clipboard
+///
+/// **Purpose:**
+/// Puts text on the system clipboard.
+///
+/// **Errors / Failures:**
+/// - No clipboard available (headless session, missing display server)
+pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text.to_string()))
+        .map_err(|e| format!("Clipboard unavailable: {}", e))
+}
+
+/// # Copied
+///
+/// **Summary:**
+/// Where `copy_or_save` put the text.
+///
+/// **Variants:**
+/// - `Clipboard`: On the system clipboard
+/// - `File(PathBuf)`: In the fallback file, since there was no clipboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Copied {
+    Clipboard,
+    File(PathBuf),
+}
+
+/// The fallback file's path: `$HOME` (or `%USERPROFILE%`), else the working directory
+pub fn fallback_path() -> PathBuf {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .unwrap_or_default()
+        .join(CLIPBOARD_FALLBACK_FILE)
+}
+
+/// # copy_or_save
+///
+/// **Purpose:**
+/// Copies text to the clipboard, or writes it to `fallback_path()` if there is none.
+///
+/// **Returns:**
+/// `Result<Copied, String>` - Where the text went
+///
+/// **Errors / Failures:**
+/// - No clipboard, and the fallback file can't be written either
+pub fn copy_or_save(text: &str) -> Result<Copied, String> {
+    let unavailable = match copy_to_clipboard(text) {
+        Ok(()) => return Ok(Copied::Clipboard),
+        Err(e) => e,
+    };
+    log_info!("{}; writing to the fallback file instead", unavailable);
+
+    let path = fallback_path();
+    fs::write(&path, text)
+        .map(|()| Copied::File(path.clone()))
+        .map_err(|e| format!("{}, and {} couldn't be written: {}", unavailable, path.display(), e))
+}
@@ -0,0 +1,322 @@
+//! # Daegonica Module: utilities::code_lang
+//!
+//! **Purpose:** Guess the language of fenced code blocks the model left untagged
+//!
+//! **Context:**
+//! - Replies often open code blocks with a bare ``` fence
+//! - Used by the agent pane to label those blocks; stored message text is never changed
+//!
+//! **Responsibilities:**
+//! - Find fenced code blocks in a message and their info strings
+//! - Score a snippet against keyword/signature heuristics per language
+//! - Stay conservative: no tag unless one language clearly wins
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+/// A language needs at least this many distinct signals to be tagged
+const MIN_SIGNALS: usize = 2;
+
+/// # Language
+///
+/// **Summary:**
+/// Languages the detector can tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumIter)]
+#[strum(serialize_all = "lowercase")]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Json,
+    Yaml,
+    Toml,
+    Sh,
+    Sql,
+}
+
+impl Language {
+    /// File extension to suggest when saving a block of this language
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Language::Rust => "rs",
+            Language::Python => "py",
+            Language::JavaScript => "js",
+            Language::TypeScript => "ts",
+            Language::Json => "json",
+            Language::Yaml => "yaml",
+            Language::Toml => "toml",
+            Language::Sh => "sh",
+            Language::Sql => "sql",
+        }
+    }
+
+    /// The language a file extension (without the dot) is written in
+    pub fn for_extension(extension: &str) -> Option<Language> {
+        Language::iter().find(|lang| lang.extension().eq_ignore_ascii_case(extension))
+    }
+
+    /// Distinct signals of this language found in the snippet
+    fn signals(&self, code: &str) -> usize {
+        let lines: Vec<&str> = code.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+        let any_line = |test: &dyn Fn(&str) -> bool| lines.iter().any(|l| test(l));
+        let has = |needle: &str| code.contains(needle);
+
+        let checks: Vec<bool> = match self {
+            Language::Rust => vec![
+                any_line(&|l| (l.starts_with("fn ") || l.starts_with("pub fn ")) && l.contains('(')),
+                has("let mut "),
+                any_line(&|l| l.starts_with("impl ") || l.starts_with("impl<")),
+                any_line(&|l| l.starts_with("use ") && l.contains("::") && l.ends_with(';')),
+                has("#[derive(") || has("#[cfg("),
+                has("println!(") || has("format!(") || has("vec!["),
+                has("&mut ") || has("&self"),
+                has("Option<") || has("Result<") || has(".unwrap()"),
+                any_line(&|l| l.starts_with("struct ") || l.starts_with("pub struct ") || l.starts_with("enum ")),
+            ],
+            Language::Python => vec![
+                any_line(&|l| l.starts_with("def ") && l.ends_with(':')),
+                any_line(&|l| l.starts_with("class ") && l.ends_with(':')),
+                any_line(&|l| l.starts_with("from ") && l.contains(" import ")),
+                any_line(&|l| l.starts_with("import ") && !l.ends_with(';') && !l.contains(" from ")),
+                has("self.") && !has("&self"),
+                any_line(&|l| l.starts_with("elif ") || l.starts_with("except") || l == "else:"),
+                has("__init__") || has("__name__"),
+                any_line(&|l| (l.starts_with("for ") || l.starts_with("if ") || l.starts_with("while ")) && l.ends_with(':')),
+            ],
+            Language::JavaScript => vec![
+                any_line(&|l| l.starts_with("const ") || l.starts_with("let ") || l.starts_with("var ")),
+                has("function ") || has("function("),
+                has("=> {") || has("=> ("),
+                has("console.log("),
+                has("require(") || any_line(&|l| l.starts_with("export ") || (l.starts_with("import ") && l.contains(" from "))),
+                has("===") || has("!=="),
+                has("document.") || has("window."),
+            ],
+            Language::TypeScript => {
+                let js = Language::JavaScript.signals(code);
+                vec![
+                    js >= 1,
+                    has(": string") || has(": number") || has(": boolean"),
+                    any_line(&|l| l.starts_with("interface ") || l.starts_with("export interface ")),
+                    any_line(&|l| (l.starts_with("type ") || l.starts_with("export type ")) && l.contains(" = ")),
+                    has("): ") && js >= 1,
+                ]
+            }
+            Language::Json => {
+                let trimmed = code.trim();
+                let structured = (trimmed.starts_with('{') || trimmed.starts_with('['))
+                    && serde_json::from_str::<serde_json::Value>(trimmed).is_ok();
+                // Parsing as JSON is decisive on its own
+                vec![structured, structured, structured]
+            }
+            Language::Yaml => {
+                let yaml_like = |l: &str| {
+                    l.starts_with("- ") || l.starts_with('#') || l == "---"
+                        || l.split_once(": ").is_some_and(|(k, _)| is_plain_key(k))
+                        || (l.ends_with(':') && is_plain_key(&l[..l.len() - 1]))
+                };
+                vec![
+                    lines.len() >= 2 && lines.iter().all(|l| yaml_like(l)),
+                    any_line(&|l| l.ends_with(':') && is_plain_key(&l[..l.len() - 1])),
+                    code.lines().any(|l| l.starts_with("  ") && l.trim_start().split_once(": ").is_some_and(|(k, _)| is_plain_key(k))),
+                    lines.first().is_some_and(|l| *l == "---"),
+                ]
+            }
+            Language::Toml => vec![
+                any_line(&|l| l.starts_with('[') && l.ends_with(']') && is_plain_key(l.trim_matches(['[', ']']))),
+                any_line(&|l| l.split_once(" = ").is_some_and(|(k, _)| is_plain_key(k))),
+                lines.len() >= 2 && lines.iter().all(|l| {
+                    l.starts_with('#') || (l.starts_with('[') && l.ends_with(']'))
+                        || l.split_once(" = ").is_some_and(|(k, _)| is_plain_key(k))
+                }),
+            ],
+            Language::Sh => vec![
+                lines.first().is_some_and(|l| l.starts_with("#!/bin/") || l.starts_with("#!/usr/bin/env bash")),
+                any_line(&|l| l.starts_with("$ ")),
+                any_line(&|l| ["echo ", "sudo ", "cd ", "export ", "mkdir ", "chmod ", "apt ", "brew "].iter().any(|c| l.starts_with(c))),
+                any_line(&|l| ["cargo ", "npm ", "pip ", "git ", "curl ", "docker "].iter().any(|c| l.starts_with(c))),
+                has(" | grep") || has(" && ") || has("$HOME") || has("${"),
+                any_line(&|l| l.starts_with("if [") || l == "fi" || l == "done"),
+            ],
+            Language::Sql => {
+                let upper = code.to_uppercase();
+                let keyword = |kw: &str| code.contains(kw) || code.contains(&kw.to_lowercase());
+                vec![
+                    keyword("SELECT ") && upper.contains(" FROM "),
+                    keyword("INSERT INTO ") || keyword("DELETE FROM ") || (keyword("UPDATE ") && upper.contains(" SET ")),
+                    keyword("CREATE TABLE") || keyword("ALTER TABLE") || keyword("DROP TABLE"),
+                    upper.contains(" WHERE ") || upper.contains("\nWHERE "),
+                    upper.contains(" JOIN ") || upper.contains("GROUP BY") || upper.contains("ORDER BY"),
+                    code.trim_end().ends_with(';') && !code.contains('{'),
+                ]
+            }
+        };
+        checks.into_iter().filter(|hit| *hit).count()
+    }
+}
+
+/// An identifier-like config key (letters, digits, `_`, `-`, `.`)
+fn is_plain_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// # detect_language
+///
+/// **Purpose:**
+/// Guesses a snippet's language from keywords and signatures.
+///
+/// **Returns:**
+/// `Option<Language>` - None unless one language has at least `MIN_SIGNALS`
+/// signals and strictly more than any other
+///
+/// **Details:**
+/// TypeScript only wins over JavaScript with type-annotation signals.
+pub fn detect_language(code: &str) -> Option<Language> {
+    let mut scores: Vec<(Language, usize)> = Language::iter()
+        .map(|language| (language, language.signals(code)))
+        .collect();
+    scores.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+
+    let (best, best_score) = scores[0];
+    let runner_up = scores[1].1;
+    (best_score >= MIN_SIGNALS && best_score > runner_up).then_some(best)
+}
+
+/// # CodeBlock
+///
+/// **Summary:**
+/// A fenced code block found in a message.
+///
+/// **Fields:**
+/// - `open_line`: Index of the opening fence line within the message
+/// - `info`: The fence's info string, if the model gave one
+/// - `detected`: Heuristic language, only when `info` is missing
+/// - `content`: Text between the fences
+/// - `closed`: The closing fence has arrived (false while a reply is still streaming)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    pub open_line: usize,
+    pub info: Option<String>,
+    pub detected: Option<Language>,
+    pub content: String,
+    pub closed: bool,
+}
+
+impl CodeBlock {
+    /// Whether the block is Rust, by info string (`rust`, `rs`) or detection
+    pub fn is_rust(&self) -> bool {
+        match &self.info {
+            Some(info) => matches!(info.split([' ', ',']).next(), Some("rust" | "rs")),
+            None => self.detected == Some(Language::Rust),
+        }
+    }
+
+    /// Number of content lines (between the fences)
+    pub fn line_count(&self) -> usize {
+        self.content.split('\n').count()
+    }
+}
+
+/// # parse_code_blocks
+///
+/// **Purpose:**
+/// Finds ``` fenced blocks and tags the untagged ones.
+///
+/// **Details:**
+/// An unclosed final block (e.g. mid-stream) is still reported.
+pub fn parse_code_blocks(text: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut open: Option<(usize, Option<String>, Vec<&str>)> = None;
+
+    for (index, line) in text.split('\n').enumerate() {
+        let fence = line.trim_start().strip_prefix("```");
+        match (open.take(), fence) {
+            (None, Some(info)) => {
+                let info = Some(info.trim().to_string()).filter(|i| !i.is_empty());
+                open = Some((index, info, Vec::new()));
+            }
+            (Some((open_line, info, body)), Some(_)) => blocks.push(finish_block(open_line, info, &body, true)),
+            (Some((open_line, info, mut body)), None) => {
+                body.push(line);
+                open = Some((open_line, info, body));
+            }
+            (None, None) => {}
+        }
+    }
+    if let Some((open_line, info, body)) = open {
+        blocks.push(finish_block(open_line, info, &body, false));
+    }
+    blocks
+}
+
+fn finish_block(open_line: usize, info: Option<String>, body: &[&str], closed: bool) -> CodeBlock {
+    let content = body.join("\n");
+    let detected = if info.is_none() { detect_language(&content) } else { None };
+    CodeBlock { open_line, info, detected, content, closed }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snippets_are_tagged_only_when_one_language_clearly_wins() {
+        let cases: [(&str, &str, Option<Language>); 18] = [
+            ("rust fn", "fn main() {\n    let mut total = 0;\n    println!(\"{}\", total);\n}", Some(Language::Rust)),
+            ("rust impl", "impl Parser {\n    pub fn next(&mut self) -> Option<Token> {\n        self.tokens.pop()\n    }\n}", Some(Language::Rust)),
+            ("python", "def greet(name):\n    if name:\n        print(name)\n    else:\n        print('nobody')", Some(Language::Python)),
+            ("python class", "import os\n\nclass Config:\n    def __init__(self):\n        self.path = os.getcwd()", Some(Language::Python)),
+            ("javascript", "const add = (a, b) => {\n  return a + b;\n};\nconsole.log(add(1, 2));", Some(Language::JavaScript)),
+            ("typescript", "interface User {\n  name: string;\n}\nconst greet = (user: User): string => {\n  return user.name;\n};", Some(Language::TypeScript)),
+            ("json", "{\n  \"name\": \"shadow\",\n  \"tags\": [\"a\", \"b\"]\n}", Some(Language::Json)),
+            ("json array", "[1, 2, 3]", Some(Language::Json)),
+            ("yaml", "name: shadow\nhistory:\n  enabled: true\n  limit: 50", Some(Language::Yaml)),
+            ("toml", "[package]\nname = \"grokprime\"\nversion = \"0.1.0\"", Some(Language::Toml)),
+            ("shell", "#!/bin/bash\ncd ~/project && cargo build\necho done", Some(Language::Sh)),
+            ("shell prompt", "$ cargo build && cargo test", Some(Language::Sh)),
+            ("sql", "SELECT name, count(*) FROM users\nWHERE active = 1\nGROUP BY name;", Some(Language::Sql)),
+            // Unknown: too little to go on, prose, or a tie
+            ("one word", "hello", None),
+            ("empty", "", None),
+            ("prose", "This is just a sentence about code, not code.", None),
+            ("single signal", "let x = 5", None),
+            ("broken json", "{ \"name\": \"shadow\", }", None),
+        ];
+        for (label, code, expected) in cases {
+            assert_eq!(detect_language(code), expected, "{}: {:?}", label, code);
+        }
+    }
+
+    #[test]
+    fn only_untagged_blocks_are_detected() {
+        let text = "Try this:\n```\nfn main() {\n    let mut n = 0;\n}\n```\nor\n```python\nx = 1\n```\n```\nstill streaming";
+        let blocks = parse_code_blocks(text);
+        assert_eq!(blocks.len(), 3);
+
+        assert_eq!((blocks[0].open_line, blocks[0].info.as_deref(), blocks[0].detected), (1, None, Some(Language::Rust)));
+        assert!(blocks[0].is_rust() && blocks[0].closed);
+        assert_eq!(blocks[0].line_count(), 3);
+
+        assert_eq!((blocks[1].info.as_deref(), blocks[1].detected), (Some("python"), None));
+        assert!(!blocks[1].is_rust());
+
+        assert_eq!((blocks[2].content.as_str(), blocks[2].detected, blocks[2].closed), ("still streaming", None, false));
+    }
+
+    #[test]
+    fn extensions_map_both_ways() {
+        for language in Language::iter() {
+            assert_eq!(Language::for_extension(language.extension()), Some(language));
+        }
+        assert_eq!(Language::for_extension("RS"), Some(Language::Rust));
+        assert_eq!(Language::for_extension("md"), None);
+    }
+}
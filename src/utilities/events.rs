@@ -0,0 +1,317 @@
+//! # Daegonica Module: utilities::events
+//!
+//! **Purpose:** Structured lifecycle events for external dashboards
+//!
+//! **Context:**
+//! - Code paths publish to the process-wide `EVENT_BUS`; nothing writes the event file directly
+//! - When `EventsConfig::enabled`, `start_file_sink` appends every event to `events.jsonl`
+//! - Tests and embedders can `subscribe` to the bus and receive the same events in memory
+//!
+//! **Responsibilities:**
+//! - Define the event schema (`Event`, `EventKind`) as serde types
+//! - Fan published events out to subscribers without blocking the publisher
+//! - Write events as JSON lines on a background thread
+//!
+//! **Stability:**
+//! - Every line is one JSON object with `schema_version`, `timestamp` (RFC 3339, UTC),
+//!   `type`, `persona`, `agent_id`, and the fields of that type
+//! - Within a schema version, fields and types are only ever added; consumers should ignore
+//!   fields and event types they don't know
+//! - Renaming or removing a field, or changing its meaning, bumps `EVENT_SCHEMA_VERSION`
+//! - `persona` and `agent_id` are null for events not tied to an agent (e.g. tweets)
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::io::BufWriter;
+use std::sync::mpsc as std_mpsc;
+use std::sync::Mutex;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use uuid::Uuid;
+use crate::prelude::*;
+
+/// Version of the event line format; see the module docs for what bumps it
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// # EventKind
+///
+/// **Summary:**
+/// What happened, with the fields specific to it (serialized under `type`).
+///
+/// **Variants:**
+/// - `AgentCreated`: An agent was opened
+/// - `AgentClosed`: An agent was closed
+/// - `MessageSent`: A request left for the model (`history_messages` = messages in the request's history)
+/// - `MessageCompleted`: The reply finished, with model, latency, and token usage when the provider reported it
+/// - `Summarized`: History was condensed from `messages_before` to `messages_after` messages
+/// - `TweetPosted`: One tweet (or thread part) was posted
+/// - `CheckInFollowUp`: An unanswered check-in was followed up (`attempt` of `max_attempts`)
+/// - `Error`: Something failed; `source` names the area (`request`, `summarize`, `save`, `twitter`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    AgentCreated,
+    AgentClosed,
+    MessageSent {
+        history_messages: usize,
+    },
+    MessageCompleted {
+        model: String,
+        latency_ms: Option<u64>,
+        input_tokens: Option<u32>,
+        output_tokens: Option<u32>,
+        reply_chars: usize,
+    },
+    Summarized {
+        messages_before: usize,
+        messages_after: usize,
+    },
+    TweetPosted {
+        tweet_id: String,
+        chars: usize,
+        reply: bool,
+    },
+    CheckInFollowUp {
+        attempt: u32,
+        max_attempts: u32,
+    },
+    Error {
+        source: String,
+        message: String,
+    },
+}
+
+/// # Event
+///
+/// **Summary:**
+/// One line of the event log.
+///
+/// **Fields:**
+/// - `schema_version`: `EVENT_SCHEMA_VERSION` when written
+/// - `timestamp`: When the event was published
+/// - `persona`: Persona of the agent involved, if any
+/// - `agent_id`: Agent involved (its UUID as a string), if any
+/// - `kind`: Event type and its fields (flattened into the object)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    pub schema_version: u32,
+    pub timestamp: DateTime<Utc>,
+    pub persona: Option<String>,
+    pub agent_id: Option<String>,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+impl Event {
+    pub fn new(kind: EventKind, persona: Option<&str>, agent_id: Option<Uuid>) -> Self {
+        Self {
+            schema_version: EVENT_SCHEMA_VERSION,
+            timestamp: Utc::now(),
+            persona: persona.map(str::to_string),
+            agent_id: agent_id.map(|id| id.to_string()),
+            kind,
+        }
+    }
+}
+
+/// # EventBus
+///
+/// **Summary:**
+/// Fans published events out to every live subscriber.
+///
+/// **Details:**
+/// Each subscriber gets an unbounded channel, so publishing is a handful of
+/// sends and never waits on disk. Subscribers whose receiver was dropped are
+/// pruned on the next publish.
+#[derive(Debug, Default)]
+pub struct EventBus {
+    subscribers: Mutex<Vec<std_mpsc::Sender<Event>>>,
+}
+
+impl EventBus {
+    pub fn subscribe(&self) -> std_mpsc::Receiver<Event> {
+        let (tx, rx) = std_mpsc::channel();
+        self.subscribers.lock().unwrap_or_else(|e| e.into_inner()).push(tx);
+        rx
+    }
+
+    pub fn publish(&self, event: Event) {
+        let mut subscribers = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+    }
+}
+
+/// The bus every emission site publishes to
+pub static EVENT_BUS: Lazy<EventBus> = Lazy::new(EventBus::default);
+
+/// # publish
+///
+/// **Purpose:**
+/// Publishes an event to `EVENT_BUS`, stamped with the schema version and current time.
+pub fn publish(kind: EventKind, persona: Option<&str>, agent_id: Option<Uuid>) {
+    EVENT_BUS.publish(Event::new(kind, persona, agent_id));
+}
+
+/// # start_file_sink
+///
+/// **Purpose:**
+/// Appends every event published from now on to `path`, one JSON object per line.
+///
+/// **Errors / Failures:**
+/// - The file (or its directory) can't be created or opened
+///
+/// **Details:**
+/// Writing happens on a background thread; a failed write is logged and the
+/// event dropped, never surfaced to the code that published it.
+pub fn start_file_sink(path: &Path) -> io::Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+    let events = EVENT_BUS.subscribe();
+    let path = path.to_path_buf();
+    log_info!("Writing lifecycle events to {}", path.display());
+
+    std::thread::spawn(move || {
+        let mut writer = BufWriter::new(file);
+        while let Ok(event) = events.recv() {
+            // Flushed per event so a dashboard tailing the file sees it right away
+            let written = serde_json::to_string(&event)
+                .map_err(io::Error::from)
+                .and_then(|line| writeln!(writer, "{}", line))
+                .and_then(|_| writer.flush());
+            if let Err(e) = written {
+                log_error!("Failed to write event to {}: {}", path.display(), e);
+            }
+        }
+    });
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+    use crate::test_support::ScratchDir;
+
+    fn at_noon(kind: EventKind, persona: Option<&str>, agent_id: Option<&str>) -> Event {
+        Event {
+            schema_version: EVENT_SCHEMA_VERSION,
+            timestamp: "2026-01-21T12:00:00Z".parse().unwrap(),
+            persona: persona.map(str::to_string),
+            agent_id: agent_id.map(str::to_string),
+            kind,
+        }
+    }
+
+    #[test]
+    fn each_event_type_has_a_flat_documented_shape() {
+        let agent = "6f1c2a9e-0000-4000-8000-000000000001";
+        let cases = [
+            (EventKind::AgentCreated, r#"{"type":"agent_created"}"#),
+            (EventKind::AgentClosed, r#"{"type":"agent_closed"}"#),
+            (EventKind::MessageSent { history_messages: 7 }, r#"{"type":"message_sent","history_messages":7}"#),
+            (
+                EventKind::MessageCompleted { model: "grok-4".into(), latency_ms: Some(1830), input_tokens: Some(1200), output_tokens: None, reply_chars: 42 },
+                r#"{"type":"message_completed","model":"grok-4","latency_ms":1830,"input_tokens":1200,"output_tokens":null,"reply_chars":42}"#,
+            ),
+            (EventKind::Summarized { messages_before: 40, messages_after: 12 }, r#"{"type":"summarized","messages_before":40,"messages_after":12}"#),
+            (
+                EventKind::TweetPosted { tweet_id: "1900".into(), chars: 120, reply: true },
+                r#"{"type":"tweet_posted","tweet_id":"1900","chars":120,"reply":true}"#,
+            ),
+            (EventKind::CheckInFollowUp { attempt: 1, max_attempts: 2 }, r#"{"type":"check_in_follow_up","attempt":1,"max_attempts":2}"#),
+            (
+                EventKind::Error { source: "request".into(), message: "503 Service Unavailable".into() },
+                r#"{"type":"error","source":"request","message":"503 Service Unavailable"}"#,
+            ),
+        ];
+
+        for (kind, fields) in cases {
+            let event = at_noon(kind, Some("shadow"), Some(agent));
+            let mut expected: serde_json::Value = serde_json::from_str(fields).unwrap();
+            expected["schema_version"] = 1.into();
+            expected["timestamp"] = "2026-01-21T12:00:00Z".into();
+            expected["persona"] = "shadow".into();
+            expected["agent_id"] = agent.into();
+
+            assert_eq!(serde_json::to_value(&event).unwrap(), expected, "{}", fields);
+            let line = serde_json::to_string(&event).unwrap();
+            assert!(!line.contains('\n'), "one event per line");
+            assert_eq!(serde_json::from_str::<Event>(&line).unwrap(), event, "{}", fields);
+        }
+    }
+
+    #[test]
+    fn events_without_an_agent_carry_nulls_and_readers_ignore_new_fields() {
+        let tweet = at_noon(EventKind::TweetPosted { tweet_id: "1".into(), chars: 5, reply: false }, None, None);
+        let value = serde_json::to_value(&tweet).unwrap();
+        assert_eq!((&value["persona"], &value["agent_id"]), (&serde_json::Value::Null, &serde_json::Value::Null));
+
+        let later = r#"{"schema_version":1,"timestamp":"2026-01-21T12:00:00Z","persona":null,"agent_id":null,
+            "type":"tweet_posted","tweet_id":"1","chars":5,"reply":false,"added_later":true}"#;
+        assert_eq!(serde_json::from_str::<Event>(later).unwrap(), tweet);
+    }
+
+    #[test]
+    fn new_events_are_stamped_with_the_version_and_time() {
+        let id = Uuid::new_v4();
+        let before = Utc::now();
+        let event = Event::new(EventKind::AgentCreated, Some("friday"), Some(id));
+        assert_eq!(event.schema_version, EVENT_SCHEMA_VERSION);
+        assert!(event.timestamp >= before && event.timestamp <= Utc::now());
+        assert_eq!((event.persona.as_deref(), event.agent_id), (Some("friday"), Some(id.to_string())));
+    }
+
+    #[test]
+    fn the_bus_fans_out_and_prunes_dropped_subscribers() {
+        let bus = EventBus::default();
+        let first = bus.subscribe();
+        let second = bus.subscribe();
+        let dropped = bus.subscribe();
+        drop(dropped);
+
+        bus.publish(at_noon(EventKind::AgentCreated, Some("a"), None));
+        assert_eq!(bus.subscribers.lock().unwrap().len(), 2);
+        drop(second);
+        bus.publish(at_noon(EventKind::AgentClosed, Some("a"), None));
+
+        let received: Vec<EventKind> = first.try_iter().map(|event| event.kind).collect();
+        assert_eq!(received, [EventKind::AgentCreated, EventKind::AgentClosed]);
+        assert_eq!(bus.subscribers.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn the_file_sink_appends_one_line_per_event() {
+        let dir = ScratchDir::new("events");
+        let path = dir.join("logs/events.jsonl");
+        dir.write("logs/events.jsonl", "{\"earlier\":true}\n");
+        start_file_sink(&path).unwrap();
+
+        publish(EventKind::AgentCreated, Some("file-sink"), None);
+        publish(EventKind::AgentClosed, Some("file-sink"), None);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mine = loop {
+            let lines: Vec<String> = fs::read_to_string(&path).unwrap().lines().map(str::to_string).collect();
+            let mine: Vec<Event> = lines.iter()
+                .filter_map(|line| serde_json::from_str::<Event>(line).ok())
+                .filter(|event| event.persona.as_deref() == Some("file-sink"))
+                .collect();
+            if mine.len() == 2 || Instant::now() > deadline {
+                assert_eq!(lines[0], "{\"earlier\":true}", "appended, not truncated");
+                break mine;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        let kinds: Vec<EventKind> = mine.into_iter().map(|event| event.kind).collect();
+        assert_eq!(kinds, [EventKind::AgentCreated, EventKind::AgentClosed]);
+    }
+}
@@ -0,0 +1,444 @@
+//! # Daegonica Module: utilities::explain
+//!
+//! **Purpose:** Turn the last error in an agent pane into something to ask about
+//!
+//! **Context:**
+//! - Used by `explain` (Ctrl+E right after a failure) and by the hint shown under pane errors
+//! - Errors that mean the agent can't answer (bad key, rate limit) are explained locally, with no request
+//! - Everything packaged for an agent goes through secret redaction and a size cap first
+//!
+//! **Responsibilities:**
+//! - Define `FailedRequest`, what an agent remembers about its last failed request
+//! - Hold the table of known error categories and their canned guidance
+//! - Gather environment facts (version, OS, key source, history writes) without the secrets
+//! - Build the prompt sent to an agent and the local explanation shown instead
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use strum::Display;
+use crate::agent_history::persistence;
+use crate::llm::credentials::{self, ApiProvider};
+use crate::user::system_info::OsInfo;
+
+/// Stored in place of anything that looks like a secret
+pub const REDACTED_SECRET: &str = "[secret]";
+
+/// Cap on the message that triggered the error, inside the prompt
+const TRIGGER_MAX_CHARS: usize = 300;
+
+/// # FailedRequest
+///
+/// **Summary:**
+/// The last request an agent couldn't complete.
+///
+/// **Fields:**
+/// - `error`: Error text as shown in the pane (without the "Error: " prefix)
+/// - `trigger`: What was sent, as echoed in the pane, if anything was
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedRequest {
+    pub error: String,
+    pub trigger: Option<String>,
+}
+
+/// # ErrorCategory
+///
+/// **Summary:**
+/// Errors common enough to have canned guidance.
+///
+/// **Variants:**
+/// - `Unauthorized`: The provider rejected the API key (401)
+/// - `RateLimited`: The provider is throttling requests (429)
+/// - `MissingPersona`: A persona name that doesn't exist
+/// - `ReadOnlyFs`: History or session files can't be written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ErrorCategory {
+    Unauthorized,
+    RateLimited,
+    MissingPersona,
+    ReadOnlyFs,
+}
+
+/// # KnownError
+///
+/// **Summary:**
+/// One row of `KNOWN_ERRORS`.
+///
+/// **Fields:**
+/// - `category`: Which error this is
+/// - `pattern`: Regex searched for in the error text
+/// - `agent_can_answer`: Whether the erroring agent can still take a request;
+///   when it can't, `explain` answers locally instead of sending
+/// - `guidance`: What it means and what to do, shown as the pane hint and the local explanation
+#[derive(Debug, Clone, Copy)]
+pub struct KnownError {
+    pub category: ErrorCategory,
+    pub pattern: &'static str,
+    pub agent_can_answer: bool,
+    pub guidance: &'static str,
+}
+
+/// Known error categories, checked in order; the first match wins
+pub const KNOWN_ERRORS: &[KnownError] = &[
+    KnownError {
+        category: ErrorCategory::Unauthorized,
+        pattern: r"(?i)\b401\b|unauthorized|api key rejected|invalid (x-)?api[ _-]?key",
+        agent_can_answer: false,
+        guidance: "The provider rejected the API key. Check GROK_KEY / CLAUDE_KEY in .env or your shell, \
+                   then run `reload keys`. This agent can't answer until the key works.",
+    },
+    KnownError {
+        category: ErrorCategory::RateLimited,
+        pattern: r"(?i)\b429\b|rate limit|too many requests",
+        agent_can_answer: false,
+        guidance: "The provider is rate limiting this key. Wait a minute before sending again; if it keeps \
+                   happening, check the usage limits for the key on the provider's console.",
+    },
+    KnownError {
+        category: ErrorCategory::MissingPersona,
+        pattern: r"(?i)persona '[^']*' not found|persona not found|no persona file found",
+        agent_can_answer: true,
+        guidance: "There's no persona with that name. `list` shows every persona you can open; \
+                   a persona file's name (without .yaml) is its persona name.",
+    },
+    KnownError {
+        category: ErrorCategory::ReadOnlyFs,
+        pattern: r"(?i)read-only file ?system|os error 30\b",
+        agent_can_answer: true,
+        guidance: "The history directory is read-only, so conversations aren't being saved. Replies still \
+                   work; fix the mount or permissions, then `save` to write the history and resume saving.",
+    },
+];
+
+static KNOWN_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    KNOWN_ERRORS.iter()
+        .map(|known| Regex::new(known.pattern).expect("KNOWN_ERRORS patterns are valid"))
+        .collect()
+});
+
+/// Shapes of secrets that can turn up in error text or echoed input
+static SECRET_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    [
+        r"(?i)\bbearer\s+[A-Za-z0-9._~+/=-]{8,}",
+        r"\b(xai|sk)-[A-Za-z0-9_-]{12,}",
+        r#"(?i)\b(api[_-]?key|token|secret|password)(["']?\s*[:=]\s*["']?)[^\s"',}]{6,}"#,
+    ]
+    .iter()
+    .map(|pattern| Regex::new(pattern).expect("secret patterns are valid"))
+    .collect()
+});
+
+/// # classify
+///
+/// **Purpose:**
+/// Finds the known category an error belongs to.
+///
+/// **Returns:**
+/// `Option<&'static KnownError>` - The first `KNOWN_ERRORS` row whose pattern matches
+pub fn classify(error: &str) -> Option<&'static KnownError> {
+    KNOWN_ERRORS.iter()
+        .zip(KNOWN_PATTERNS.iter())
+        .find(|(_, pattern)| pattern.is_match(error))
+        .map(|(known, _)| known)
+}
+
+/// The line shown under an error in a pane, for known categories
+pub fn hint(error: &str) -> Option<String> {
+    classify(error).map(|known| format!("Hint: {}", known.guidance))
+}
+
+/// # redact_secrets
+///
+/// **Purpose:**
+/// Removes API keys and anything shaped like a credential.
+///
+/// **Details:**
+/// The configured credential values are replaced by name (e.g. `[GROK_KEY]`),
+/// wherever they came from; bearer tokens, provider-style keys, and
+/// `key=value` / `"token": "..."` pairs are replaced with `REDACTED_SECRET`.
+pub fn redact_secrets(text: &str) -> String {
+    let mut redacted = text.to_string();
+    let names = ApiProvider::ALL.iter().map(|provider| provider.env_var())
+        .chain(crate::twitter::client::CREDENTIAL_VARS);
+    for name in names {
+        if let Some(value) = credentials::load_env_value(name).map(|env| env.value)
+            && value.len() >= 8
+        {
+            redacted = redacted.replace(&value, &format!("[{}]", name));
+        }
+    }
+    for pattern in SECRET_PATTERNS.iter() {
+        redacted = pattern.replace_all(&redacted, |caps: &regex::Captures| match (caps.get(1), caps.get(2)) {
+            (Some(name), Some(separator)) => format!("{}{}{}", name.as_str(), separator.as_str(), REDACTED_SECRET),
+            _ => REDACTED_SECRET.to_string(),
+        }).into_owned();
+    }
+    redacted
+}
+
+/// # cap
+///
+/// **Purpose:**
+/// Shortens text to at most `max_chars` characters, saying how much was cut.
+pub fn cap(text: &str, max_chars: usize) -> String {
+    let total = text.chars().count();
+    if total <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars).collect();
+    format!("{}\n[... {} more characters cut]", kept.trim_end(), total - max_chars)
+}
+
+/// # environment_facts
+///
+/// **Purpose:**
+/// Local facts worth knowing when reading an error, as label / value pairs.
+///
+/// **Parameters:**
+/// - `provider`: Provider the erroring agent talks to, for the key's source
+///
+/// **Details:**
+/// Says where the key came from, never what it is.
+pub fn environment_facts(provider: ApiProvider) -> Vec<(&'static str, String)> {
+    let os = OsInfo::new();
+    let key = match credentials::load_env_value(provider.env_var()) {
+        Some(env) => format!("{} set, {}", provider.env_var(), env.provenance.describe()),
+        None => format!("{} not set", provider.env_var()),
+    };
+    let history = match persistence::suspended_reason() {
+        Some(reason) => format!("suspended ({})", reason),
+        None => "active".to_string(),
+    };
+    vec![
+        ("App version", env!("CARGO_PKG_VERSION").to_string()),
+        ("OS", format!("{} {}", os.name, os.version).trim().to_string()),
+        ("API key", key),
+        ("History writes", history),
+    ]
+}
+
+/// # ExplainRequest
+///
+/// **Summary:**
+/// A failed request packaged with the context needed to explain it.
+///
+/// **Fields:**
+/// - `failure`: The error and what triggered it
+/// - `persona`: Agent the request went to
+/// - `provider` / `model`: Where it was sent
+/// - `environment`: `environment_facts` for that provider
+#[derive(Debug, Clone)]
+pub struct ExplainRequest {
+    pub failure: FailedRequest,
+    pub persona: String,
+    pub provider: ApiProvider,
+    pub model: String,
+    pub environment: Vec<(&'static str, String)>,
+}
+
+impl ExplainRequest {
+    /// The error's first line, for the pane echo
+    pub fn headline(&self) -> String {
+        let first = self.failure.error.lines().next().unwrap_or_default();
+        cap(&redact_secrets(first), 120)
+    }
+
+    /// # prompt
+    ///
+    /// **Purpose:**
+    /// The message sent to an agent asking what the error means.
+    ///
+    /// **Parameters:**
+    /// - `max_chars`: `explain.max_prompt_chars`
+    ///
+    /// **Details:**
+    /// Secrets are redacted before capping, so a cut never leaves half a key.
+    /// The trigger gets its own smaller cap so a long paste can't crowd out
+    /// the error.
+    pub fn prompt(&self, max_chars: usize) -> String {
+        let mut context = vec![format!(
+            "- Sent to: the {} persona ({}, model {})",
+            self.persona, self.provider.display_name(), self.model
+        )];
+        if let Some(trigger) = &self.failure.trigger {
+            context.push(format!("- Triggered by: {}", cap(trigger, TRIGGER_MAX_CHARS)));
+        }
+        context.extend(self.environment.iter().map(|(label, value)| format!("- {}: {}", label, value)));
+        if let Some(known) = classify(&self.failure.error) {
+            context.push(format!("- Looks like: {} ({})", known.category, known.guidance));
+        }
+
+        let prompt = format!(
+            "This error just showed up in my GrokPrime-Brain session. Explain briefly what it most likely \
+             means and what I should do about it.\n\nError:\n{}\n\nContext:\n{}",
+            self.failure.error,
+            context.join("\n")
+        );
+        cap(&redact_secrets(&prompt), max_chars)
+    }
+}
+
+/// # local_explanation
+///
+/// **Purpose:**
+/// What `explain` shows when no request is sent.
+///
+/// **Parameters:**
+/// - `failure`: The failed request
+/// - `known`: Its category, if it has one
+/// - `reason`: Why nothing was sent (e.g. the agent can't answer)
+pub fn local_explanation(failure: &FailedRequest, known: Option<&KnownError>, reason: &str) -> String {
+    let guidance = known.map(|known| known.guidance)
+        .unwrap_or("No canned explanation for this error; the log has the full response.");
+    format!(
+        "Explain ({}; nothing sent):\n  Error: {}\n  {}",
+        reason,
+        cap(&redact_secrets(failure.error.lines().next().unwrap_or_default()), 200),
+        guidance
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(error: &str, trigger: Option<&str>) -> FailedRequest {
+        FailedRequest { error: error.to_string(), trigger: trigger.map(str::to_string) }
+    }
+
+    fn request(error: &str, trigger: Option<&str>) -> ExplainRequest {
+        ExplainRequest {
+            failure: failure(error, trigger),
+            persona: "shadow".to_string(),
+            provider: ApiProvider::Grok,
+            model: "grok-4".to_string(),
+            environment: vec![("App version", "0.1.0".to_string()), ("History writes", "active".to_string())],
+        }
+    }
+
+    #[test]
+    fn errors_are_classified_by_the_first_matching_row() {
+        let cases = [
+            ("API error 401: {\"error\":\"invalid\"}", Some(ErrorCategory::Unauthorized)),
+            ("Invalid API key provided", Some(ErrorCategory::Unauthorized)),
+            ("HTTP 429 Too Many Requests", Some(ErrorCategory::RateLimited)),
+            ("rate limit exceeded for model", Some(ErrorCategory::RateLimited)),
+            ("Persona 'Ghost' not found.", Some(ErrorCategory::MissingPersona)),
+            ("No persona file found for ghost", Some(ErrorCategory::MissingPersona)),
+            ("Failed to save history: Read-only file system (os error 30)", Some(ErrorCategory::ReadOnlyFs)),
+            ("401 and then 429", Some(ErrorCategory::Unauthorized)),
+            ("took 4010ms and timed out", None),
+            ("connection reset by peer", None),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(classify(error).map(|known| known.category), expected, "{}", error);
+        }
+    }
+
+    #[test]
+    fn only_auth_and_rate_limits_keep_the_agent_from_answering() {
+        let unanswerable: Vec<ErrorCategory> = KNOWN_ERRORS.iter()
+            .filter(|known| !known.agent_can_answer)
+            .map(|known| known.category)
+            .collect();
+        assert_eq!(unanswerable, [ErrorCategory::Unauthorized, ErrorCategory::RateLimited]);
+        assert_eq!(KNOWN_PATTERNS.len(), KNOWN_ERRORS.len(), "every pattern compiles");
+    }
+
+    #[test]
+    fn hints_reuse_the_table_guidance() {
+        for known in KNOWN_ERRORS {
+            let error = match known.category {
+                ErrorCategory::Unauthorized => "status 401",
+                ErrorCategory::RateLimited => "status 429",
+                ErrorCategory::MissingPersona => "Persona 'x' not found.",
+                ErrorCategory::ReadOnlyFs => "os error 30",
+            };
+            assert_eq!(hint(error), Some(format!("Hint: {}", known.guidance)), "{}", known.category);
+        }
+        assert_eq!(hint("connection reset by peer"), None);
+    }
+
+    #[test]
+    fn secret_shapes_are_redacted() {
+        let cases = [
+            ("Authorization: Bearer abcdefgh12345678", "Authorization: [secret]"),
+            ("key xai-abcdefghijklmnop rejected", "key [secret] rejected"),
+            ("used sk-ant-0123456789abcdef", "used [secret]"),
+            ("api_key=hunter2hunter2 failed", "api_key=[secret] failed"),
+            (r#"{"token": "abcdef123456"}"#, r#"{"token": "[secret]"}"#),
+            ("password: short", "password: short"),
+            ("no secrets here", "no secrets here"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(redact_secrets(text), expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn capping_says_how_much_was_cut() {
+        assert_eq!(cap("short", 10), "short");
+        assert_eq!(cap("exactly10!", 10), "exactly10!");
+        assert_eq!(cap("héllo wörld", 6), "héllo\n[... 5 more characters cut]");
+    }
+
+    #[test]
+    fn the_prompt_packages_context_without_secrets() {
+        let prompt = request(
+            "API error 401: key xai-abcdefghijklmnop rejected",
+            Some("try again with api_key=hunter2hunter2"),
+        ).prompt(4000);
+
+        assert!(prompt.contains("Error:\nAPI error 401: key [secret] rejected"), "{}", prompt);
+        assert!(prompt.contains("- Sent to: the shadow persona (Grok, model grok-4)"), "{}", prompt);
+        assert!(prompt.contains("- Triggered by: try again with api_key=[secret]"), "{}", prompt);
+        assert!(prompt.contains("- App version: 0.1.0\n- History writes: active"), "{}", prompt);
+        assert!(prompt.contains("- Looks like: unauthorized (The provider rejected the API key."), "{}", prompt);
+        assert!(!prompt.contains("hunter2") && !prompt.contains("xai-abc"), "{}", prompt);
+
+        let plain = request("connection reset by peer", None).prompt(4000);
+        assert!(!plain.contains("Triggered by") && !plain.contains("Looks like"), "{}", plain);
+    }
+
+    #[test]
+    fn the_prompt_and_trigger_are_capped() {
+        let paste = "x".repeat(1000);
+        let prompt = request("connection reset by peer", Some(&paste)).prompt(4000);
+        assert!(prompt.contains(&format!("- Triggered by: {}\n[... 700 more characters cut]", "x".repeat(300))), "{}", prompt);
+
+        let capped = request("connection reset by peer", Some(&paste)).prompt(200);
+        assert_eq!(capped.lines().last(), Some(format!("[... {} more characters cut]", prompt.chars().count() - 200).as_str()));
+        assert!(capped.starts_with("This error just showed up"));
+    }
+
+    #[test]
+    fn the_redaction_happens_before_the_cut() {
+        let error = format!("{}xai-abcdefghijklmnop", "e".repeat(160));
+        let prompt = request(&error, None).prompt(250);
+        assert!(!prompt.contains("xai-"), "no half key survives the cut: {}", prompt);
+    }
+
+    #[test]
+    fn the_local_explanation_falls_back_without_a_category() {
+        let known = classify("status 429");
+        assert_eq!(
+            local_explanation(&failure("status 429\nbody: slow down", None), known, "asking this agent would fail the same way"),
+            format!("Explain (asking this agent would fail the same way; nothing sent):\n  Error: status 429\n  {}", known.unwrap().guidance)
+        );
+        let shown = local_explanation(&failure("bearer abcdefgh12345678 refused", None), None, "no helper");
+        assert_eq!(shown, "Explain (no helper; nothing sent):\n  Error: [secret] refused\n  No canned explanation for this error; the log has the full response.");
+    }
+
+    #[test]
+    fn the_headline_is_the_redacted_first_line() {
+        assert_eq!(request("status 401 for xai-abcdefghijklmnop\ndetails", None).headline(), "status 401 for [secret]");
+    }
+}
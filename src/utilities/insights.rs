@@ -0,0 +1,791 @@
+//! # Daegonica Module: utilities::insights
+//!
+//! **Purpose:** Monthly self-review of how the tool is used, computed locally from files already written
+//!
+//! **Context:**
+//! - Backs the `insights [month] [--md <path>]` command; nothing is sent anywhere
+//! - Reads the lifecycle event log (`events.jsonl`, written only with `events.enabled`) and the focus log
+//! - Either file may be missing or partly unreadable; the report shows what it could compute and says what it couldn't
+//!
+//! **Responsibilities:**
+//! - Read JSON-lines sources into typed records, skipping lines that don't parse
+//! - Answer one query per figure (messages per day and hour, personas, sessions, weekly tokens and cost, check-ins)
+//! - Render the month as ASCII charts for the pane, or as Markdown for export
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::collections::BTreeMap;
+use std::io::BufReader;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Timelike, Utc};
+use serde::de::DeserializeOwned;
+use crate::prelude::*;
+use crate::config::ModelPrice;
+use crate::persona::focus::{FocusOutcome, FOCUS_LOG_FILE};
+use crate::persona::state_path;
+use crate::utilities::charts::{bar_chart, column_chart};
+use crate::utilities::events::{Event, EventKind};
+use crate::utilities::time::Zone;
+
+/// Length of the longest horizontal bar
+const BAR_WIDTH: usize = 30;
+/// Rows used by the tallest column in a column chart
+const COLUMN_HEIGHT: usize = 6;
+
+/// # Month
+///
+/// **Summary:**
+/// A calendar month, read in the report's time zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Month {
+    pub year: i32,
+    pub month: u32,
+}
+
+impl Month {
+    /// # parse
+    ///
+    /// **Purpose:**
+    /// Reads `YYYY-MM`, e.g. "2026-03".
+    ///
+    /// **Errors / Failures:**
+    /// - Anything else, or a month outside 1-12
+    pub fn parse(text: &str) -> Result<Self, String> {
+        NaiveDate::parse_from_str(&format!("{}-01", text.trim()), "%Y-%m-%d")
+            .map(|date| Self { year: date.year(), month: date.month() })
+            .map_err(|_| format!("\"{}\" isn't a month; use YYYY-MM, e.g. 2026-03", text.trim()))
+    }
+
+    /// The month `now` falls in, in `zone`
+    pub fn containing(now: DateTime<Utc>, zone: Zone) -> Self {
+        let date = zone.to_zoned(now).date_naive();
+        Self { year: date.year(), month: date.month() }
+    }
+
+    pub fn first_day(&self) -> NaiveDate {
+        NaiveDate::from_ymd_opt(self.year, self.month, 1).expect("month is always valid")
+    }
+
+    /// Days in the month (28-31)
+    pub fn days(&self) -> u32 {
+        let next = match self.month {
+            12 => NaiveDate::from_ymd_opt(self.year + 1, 1, 1),
+            month => NaiveDate::from_ymd_opt(self.year, month + 1, 1),
+        };
+        next.map_or(31, |next| (next - self.first_day()).num_days() as u32)
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        date.year() == self.year && date.month() == self.month
+    }
+}
+
+impl std::fmt::Display for Month {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{:02}", self.year, self.month)
+    }
+}
+
+/// # SourceStatus
+///
+/// **Summary:**
+/// How reading one source file went.
+///
+/// **Variants:**
+/// - `Read`: The file was read; `skipped` lines didn't parse (corrupt, or an event type this version doesn't know)
+/// - `Missing`: No file at that path
+/// - `Unreadable`: The file exists but couldn't be opened or read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceStatus {
+    Read { skipped: usize },
+    Missing,
+    Unreadable(String),
+}
+
+/// # Source
+///
+/// **Summary:**
+/// The records read from one JSON-lines file, and how reading went.
+#[derive(Debug, Clone)]
+pub struct Source<T> {
+    pub path: PathBuf,
+    pub records: Vec<T>,
+    pub status: SourceStatus,
+}
+
+impl<T> Source<T> {
+    /// Line for the report's notes, when there is something to say
+    fn note(&self, missing_hint: &str) -> Option<String> {
+        match &self.status {
+            SourceStatus::Read { skipped: 0 } => None,
+            SourceStatus::Read { skipped } => Some(format!("{}: skipped {} unreadable line(s)", self.path.display(), skipped)),
+            SourceStatus::Missing => Some(format!("{} not found; {}", self.path.display(), missing_hint)),
+            SourceStatus::Unreadable(e) => Some(format!("{}: {}", self.path.display(), e)),
+        }
+    }
+}
+
+/// # read_jsonl
+///
+/// **Purpose:**
+/// Reads every line of `path` that parses as `T`.
+///
+/// **Details:**
+/// Blank lines are ignored and lines that don't parse are counted, not fatal:
+/// a half-written last line or an event type added later must not hide the rest.
+/// A read error midway keeps what was read so far.
+pub fn read_jsonl<T: DeserializeOwned>(path: &Path) -> Source<T> {
+    let mut source = Source { path: path.to_path_buf(), records: Vec::new(), status: SourceStatus::Read { skipped: 0 } };
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            source.status = SourceStatus::Missing;
+            return source;
+        }
+        Err(e) => {
+            source.status = SourceStatus::Unreadable(e.to_string());
+            return source;
+        }
+    };
+
+    let mut skipped = 0;
+    for line in BufReader::new(file).lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                source.status = SourceStatus::Unreadable(format!("stopped reading after {} records: {}", source.records.len(), e));
+                return source;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(record) => source.records.push(record),
+            Err(_) => skipped += 1,
+        }
+    }
+    source.status = SourceStatus::Read { skipped };
+    source
+}
+
+/// # FocusRecord
+///
+/// **Summary:**
+/// The parts of a focus log line the report needs (block details are ignored).
+#[derive(Debug, Clone, Deserialize)]
+pub struct FocusRecord {
+    pub event: FocusOutcome,
+    pub at: DateTime<Utc>,
+}
+
+/// Events of the month, as (local time, event)
+fn in_month(events: &[Event], month: Month, zone: Zone) -> impl Iterator<Item = (DateTime<chrono::FixedOffset>, &Event)> {
+    events.iter()
+        .map(move |event| (zone.to_zoned(event.timestamp), event))
+        .filter(move |(at, _)| month.contains(at.date_naive()))
+}
+
+/// # messages_per_day
+///
+/// **Purpose:**
+/// Messages sent on each day of the month (index 0 is the 1st).
+pub fn messages_per_day(events: &[Event], month: Month, zone: Zone) -> Vec<u64> {
+    let mut days = vec![0; month.days() as usize];
+    for (at, event) in in_month(events, month, zone) {
+        if matches!(event.kind, EventKind::MessageSent { .. }) {
+            days[at.day0() as usize] += 1;
+        }
+    }
+    days
+}
+
+/// # messages_by_hour
+///
+/// **Purpose:**
+/// Messages sent in each hour of the day across the month (index 0 is midnight).
+pub fn messages_by_hour(events: &[Event], month: Month, zone: Zone) -> [u64; 24] {
+    let mut hours = [0; 24];
+    for (at, event) in in_month(events, month, zone) {
+        if matches!(event.kind, EventKind::MessageSent { .. }) {
+            hours[at.hour() as usize] += 1;
+        }
+    }
+    hours
+}
+
+/// # persona_counts
+///
+/// **Purpose:**
+/// Messages sent to each persona over the month, most first (ties by name).
+pub fn persona_counts(events: &[Event], month: Month, zone: Zone) -> Vec<(String, u64)> {
+    let mut counts: BTreeMap<String, u64> = BTreeMap::new();
+    for (_, event) in in_month(events, month, zone) {
+        if let (EventKind::MessageSent { .. }, Some(persona)) = (&event.kind, &event.persona) {
+            *counts.entry(persona.clone()).or_default() += 1;
+        }
+    }
+    let mut counts: Vec<(String, u64)> = counts.into_iter().collect();
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    counts
+}
+
+/// # SessionStats
+///
+/// **Summary:**
+/// Agent sessions opened during the month.
+///
+/// **Fields:**
+/// - `sessions`: Agents opened in the month
+/// - `total`: Their combined length
+/// - `unclosed`: Sessions with no close event, measured to their last event instead
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionStats {
+    pub sessions: u64,
+    pub total: Duration,
+    pub unclosed: u64,
+}
+
+impl SessionStats {
+    pub fn average(&self) -> Option<Duration> {
+        (self.sessions > 0).then(|| self.total / self.sessions as i32)
+    }
+}
+
+/// # sessions
+///
+/// **Purpose:**
+/// Length of each agent session that started in the month.
+///
+/// **Details:**
+/// A session runs from `agent_created` to `agent_closed` for the same agent
+/// ID. Agents still open when the app exited have no close event, so they end
+/// at their last event (usually the last reply) rather than being dropped.
+pub fn sessions(events: &[Event], month: Month, zone: Zone) -> SessionStats {
+    // agent_id -> (opened, last seen, closed)
+    let mut spans: BTreeMap<&str, (DateTime<Utc>, DateTime<Utc>, bool)> = BTreeMap::new();
+    for event in events {
+        let Some(agent_id) = event.agent_id.as_deref() else { continue };
+        match event.kind {
+            EventKind::AgentCreated => {
+                spans.insert(agent_id, (event.timestamp, event.timestamp, false));
+            }
+            _ => {
+                if let Some(span) = spans.get_mut(agent_id).filter(|span| !span.2) {
+                    span.1 = span.1.max(event.timestamp);
+                    span.2 = matches!(event.kind, EventKind::AgentClosed);
+                }
+            }
+        }
+    }
+
+    let mut stats = SessionStats { sessions: 0, total: Duration::zero(), unclosed: 0 };
+    for (opened, last, closed) in spans.into_values() {
+        if !month.contains(zone.to_zoned(opened).date_naive()) {
+            continue;
+        }
+        stats.sessions += 1;
+        stats.total += last - opened;
+        stats.unclosed += u64::from(!closed);
+    }
+    stats
+}
+
+/// # WeekUsage
+///
+/// **Summary:**
+/// Token use for replies finished in one week (Monday start) of the month.
+///
+/// **Fields:**
+/// - `week_start`: The Monday, which may fall in the previous month
+/// - `replies`: Replies finished
+/// - `input_tokens` / `output_tokens`: Totals over replies whose provider reported them
+/// - `cost`: Estimated cost of the replies with a configured price
+/// - `unpriced`: Replies whose model has no configured price
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeekUsage {
+    pub week_start: NaiveDate,
+    pub replies: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost: f64,
+    pub unpriced: u64,
+}
+
+/// # weekly_usage
+///
+/// **Purpose:**
+/// Tokens and estimated cost per week, from `message_completed` events.
+///
+/// **Details:**
+/// Only days inside the month count, so the first and last weeks are partial.
+/// The first price whose `model_prefix` starts the model name is used.
+pub fn weekly_usage(events: &[Event], month: Month, zone: Zone, prices: &[ModelPrice]) -> Vec<WeekUsage> {
+    let mut weeks: BTreeMap<NaiveDate, WeekUsage> = BTreeMap::new();
+    for (at, event) in in_month(events, month, zone) {
+        let EventKind::MessageCompleted { model, input_tokens, output_tokens, .. } = &event.kind else { continue };
+        let date = at.date_naive();
+        let week_start = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+        let week = weeks.entry(week_start).or_insert_with(|| WeekUsage {
+            week_start, replies: 0, input_tokens: 0, output_tokens: 0, cost: 0.0, unpriced: 0,
+        });
+
+        let (input, output) = (input_tokens.unwrap_or(0) as u64, output_tokens.unwrap_or(0) as u64);
+        week.replies += 1;
+        week.input_tokens += input;
+        week.output_tokens += output;
+        match ModelPrice::find(prices, model) {
+            Some(price) => week.cost += price.cost(input, output),
+            None => week.unpriced += 1,
+        }
+    }
+    weeks.into_values().collect()
+}
+
+/// # CheckInStats
+///
+/// **Summary:**
+/// Focus check-ins sent during the month and how many needed chasing.
+///
+/// **Fields:**
+/// - `sent`: Focus blocks that ran out, each ending in a check-in
+/// - `followed_up`: Check-ins that got at least one follow-up because they went unanswered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckInStats {
+    pub sent: u64,
+    pub followed_up: u64,
+}
+
+impl CheckInStats {
+    /// Share answered before any follow-up, None with no check-ins
+    pub fn response_rate(&self) -> Option<f64> {
+        (self.sent > 0).then(|| self.sent.saturating_sub(self.followed_up) as f64 / self.sent as f64)
+    }
+}
+
+/// # check_ins
+///
+/// **Purpose:**
+/// Counts check-ins from the focus log and first follow-ups from the event log.
+///
+/// **Details:**
+/// Only each check-in's first follow-up is counted, so one check-in chased
+/// twice counts once. With follow-ups turned off none are ever logged, and
+/// every check-in looks answered.
+pub fn check_ins(focus: &[FocusRecord], events: &[Event], month: Month, zone: Zone) -> CheckInStats {
+    let sent = focus.iter()
+        .filter(|record| record.event == FocusOutcome::Completed && month.contains(zone.to_zoned(record.at).date_naive()))
+        .count() as u64;
+    let followed_up = in_month(events, month, zone)
+        .filter(|(_, event)| matches!(event.kind, EventKind::CheckInFollowUp { attempt: 1, .. }))
+        .count() as u64;
+    CheckInStats { sent, followed_up }
+}
+
+/// # InsightSources
+///
+/// **Summary:**
+/// Where the report's data is read from.
+#[derive(Debug, Clone)]
+pub struct InsightSources {
+    pub events: PathBuf,
+    pub focus_log: PathBuf,
+}
+
+impl InsightSources {
+    /// The files the app itself writes
+    pub fn configured() -> Self {
+        Self {
+            events: PathBuf::from(&GLOBAL_CONFIG.events.path),
+            focus_log: state_path(FOCUS_LOG_FILE),
+        }
+    }
+}
+
+/// # MonthInsights
+///
+/// **Summary:**
+/// Every figure for one month, plus notes on what couldn't be computed.
+///
+/// **Fields:**
+/// - `month`: The month covered
+/// - `per_day` / `by_hour`: Messages sent per day of the month and per hour of the day
+/// - `personas`: Messages per persona, most first
+/// - `sessions`: Agent sessions opened in the month
+/// - `weeks`: Tokens and cost per week
+/// - `priced`: Prices are configured, so costs are shown
+/// - `check_ins`: Check-in figures, None without a focus log
+/// - `notes`: Missing or damaged sources and figures with no data source
+#[derive(Debug, Clone)]
+pub struct MonthInsights {
+    pub month: Month,
+    pub per_day: Vec<u64>,
+    pub by_hour: [u64; 24],
+    pub personas: Vec<(String, u64)>,
+    pub sessions: SessionStats,
+    pub weeks: Vec<WeekUsage>,
+    pub priced: bool,
+    pub check_ins: Option<CheckInStats>,
+    pub notes: Vec<String>,
+}
+
+impl MonthInsights {
+    /// # collect
+    ///
+    /// **Purpose:**
+    /// Reads the sources and computes the month.
+    ///
+    /// **Details:**
+    /// A missing event log yields zeros and a note (the log is off by default);
+    /// a missing focus log leaves the check-in figures out.
+    pub fn collect(sources: &InsightSources, month: Month, zone: Zone, prices: &[ModelPrice]) -> Self {
+        let events: Source<Event> = read_jsonl(&sources.events);
+        let focus: Source<FocusRecord> = read_jsonl(&sources.focus_log);
+
+        let mut notes: Vec<String> = [
+            events.note("turn on events.enabled to record messages, sessions, and tokens"),
+            focus.note("check-in figures need at least one finished focus block"),
+        ].into_iter().flatten().collect();
+        notes.push("Commands used: not recorded; no log of commands is kept.".to_string());
+
+        let check_ins = (focus.status != SourceStatus::Missing)
+            .then(|| check_ins(&focus.records, &events.records, month, zone));
+        Self {
+            month,
+            per_day: messages_per_day(&events.records, month, zone),
+            by_hour: messages_by_hour(&events.records, month, zone),
+            personas: persona_counts(&events.records, month, zone),
+            sessions: sessions(&events.records, month, zone),
+            weeks: weekly_usage(&events.records, month, zone, prices),
+            priced: !prices.is_empty(),
+            check_ins,
+            notes,
+        }
+    }
+
+    /// # sections
+    ///
+    /// **Purpose:**
+    /// The report as (heading, lines, is a chart) blocks, shared by both renderings.
+    fn sections(&self) -> Vec<(String, Vec<String>, bool)> {
+        let mut sections = Vec::new();
+
+        let total: u64 = self.per_day.iter().sum();
+        let busiest = self.per_day.iter().enumerate().max_by_key(|(day, count)| (**count, std::cmp::Reverse(*day)));
+        let heading = match busiest {
+            Some((day, count)) if *count > 0 => format!(
+                "Messages per day ({} total, busiest {}-{:02} with {})", total, self.month, day + 1, count
+            ),
+            _ => "Messages per day (none)".to_string(),
+        };
+        let day_labels: Vec<(usize, String)> = [1, 5, 10, 15, 20, 25, 30].iter()
+            .filter(|day| **day <= self.per_day.len())
+            .map(|day| (day - 1, day.to_string()))
+            .collect();
+        sections.push((heading, column_chart(&self.per_day, COLUMN_HEIGHT, &day_labels), true));
+
+        let hour_labels: Vec<(usize, String)> = [0, 6, 12, 18, 23].iter().map(|hour| (*hour, format!("{:02}", hour))).collect();
+        sections.push(("Messages by hour of day".to_string(), column_chart(&self.by_hour, COLUMN_HEIGHT, &hour_labels), true));
+
+        let persona_rows: Vec<(String, f64, String)> = self.personas.iter()
+            .map(|(persona, count)| {
+                let share = *count as f64 * 100.0 / total.max(1) as f64;
+                (persona.clone(), *count as f64, format!("{} ({:.0}%)", count, share))
+            })
+            .collect();
+        let persona_lines = if persona_rows.is_empty() { vec!["(no messages)".to_string()] } else { bar_chart(&persona_rows, BAR_WIDTH) };
+        sections.push(("Personas".to_string(), persona_lines, !persona_rows.is_empty()));
+
+        let session_line = match self.sessions.average() {
+            Some(average) => {
+                let mut line = format!("{} opened, average length {}", self.sessions.sessions, format_duration(average));
+                if self.sessions.unclosed > 0 {
+                    line.push_str(&format!(" ({} still open at exit, measured to their last reply)", self.sessions.unclosed));
+                }
+                line
+            }
+            None => "(none)".to_string(),
+        };
+        sections.push(("Sessions".to_string(), vec![session_line], false));
+
+        let week_rows: Vec<(String, f64, String)> = self.weeks.iter()
+            .map(|week| {
+                let mut shown = format!(
+                    "{} in / {} out, {} repl{}",
+                    format_tokens(week.input_tokens), format_tokens(week.output_tokens),
+                    week.replies, if week.replies == 1 { "y" } else { "ies" }
+                );
+                if self.priced {
+                    shown.push_str(&format!(", ~${:.2}", week.cost));
+                    if week.unpriced > 0 {
+                        shown.push_str(&format!(" ({} unpriced)", week.unpriced));
+                    }
+                }
+                (format!("week of {}", week.week_start.format("%m-%d")), (week.input_tokens + week.output_tokens) as f64, shown)
+            })
+            .collect();
+        let mut week_lines = if week_rows.is_empty() { vec!["(no replies)".to_string()] } else { bar_chart(&week_rows, BAR_WIDTH) };
+        if !self.priced && !week_rows.is_empty() {
+            week_lines.push("Costs: no prices configured (insights.model_prices)".to_string());
+        }
+        sections.push(("Tokens per week".to_string(), week_lines, !week_rows.is_empty()));
+
+        let check_in_line = match self.check_ins {
+            Some(stats) => match stats.response_rate() {
+                Some(rate) => format!(
+                    "{} sent, {} answered before a follow-up ({:.0}%)",
+                    stats.sent, stats.sent.saturating_sub(stats.followed_up), rate * 100.0
+                ),
+                None => "(no focus check-ins this month)".to_string(),
+            },
+            None => "(no focus log)".to_string(),
+        };
+        sections.push(("Check-ins".to_string(), vec![check_in_line], false));
+
+        if !self.notes.is_empty() {
+            sections.push(("Notes".to_string(), self.notes.clone(), false));
+        }
+        sections
+    }
+
+    /// # render
+    ///
+    /// **Purpose:**
+    /// The report as plain text for the pane.
+    pub fn render(&self) -> String {
+        let mut out = vec![format!("Insights for {} (computed locally)", self.month)];
+        for (heading, lines, _) in self.sections() {
+            out.push(String::new());
+            out.push(format!("{}:", heading));
+            out.extend(lines.into_iter().map(|line| format!("  {}", line)));
+        }
+        out.join("\n")
+    }
+
+    /// # to_markdown
+    ///
+    /// **Purpose:**
+    /// The report as Markdown, charts in code blocks so they keep their alignment.
+    pub fn to_markdown(&self) -> String {
+        let mut out = vec![format!("# Insights for {}", self.month)];
+        out.push(format!("\nGenerated {} from local files.", Local::now().format("%Y-%m-%d %H:%M")));
+        for (heading, lines, chart) in self.sections() {
+            out.push(format!("\n## {}\n", heading));
+            if chart {
+                out.push("```".to_string());
+                out.extend(lines);
+                out.push("```".to_string());
+            } else if lines.len() == 1 {
+                out.extend(lines);
+            } else {
+                out.extend(lines.into_iter().map(|line| format!("- {}", line)));
+            }
+        }
+        out.push(String::new());
+        out.join("\n")
+    }
+}
+
+/// Token count for display ("950", "12.3k", "1.2M")
+pub fn format_tokens(tokens: u64) -> String {
+    match tokens {
+        t if t < 1000 => t.to_string(),
+        t if t < 1_000_000 => format!("{:.1}k", t as f64 / 1000.0),
+        t => format!("{:.1}M", t as f64 / 1_000_000.0),
+    }
+}
+
+/// Session length for display ("45s", "18m", "1h 05m")
+fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes();
+    match minutes {
+        0 => format!("{}s", duration.num_seconds().max(0)),
+        m if m < 60 => format!("{}m", m),
+        m => format!("{}h {:02}m", m / 60, m % 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScratchDir;
+
+    fn fixtures() -> InsightSources {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/insights");
+        InsightSources { events: dir.join("events.jsonl"), focus_log: dir.join("focus_log.jsonl") }
+    }
+
+    fn march() -> Month {
+        Month::parse("2026-03").expect("a valid month")
+    }
+
+    fn utc() -> Zone {
+        Zone::parse("utc").expect("utc parses")
+    }
+
+    fn events() -> Vec<Event> {
+        let source: Source<Event> = read_jsonl(&fixtures().events);
+        assert_eq!(source.status, SourceStatus::Read { skipped: 1 }, "only the unknown event type is skipped");
+        source.records
+    }
+
+    fn prices() -> Vec<ModelPrice> {
+        vec![ModelPrice { model_prefix: "grok-4".to_string(), input_per_million: 3.0, output_per_million: 15.0 }]
+    }
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").expect("a valid date")
+    }
+
+    #[test]
+    fn months_parse_and_know_their_length() {
+        assert_eq!(march(), Month { year: 2026, month: 3 });
+        assert_eq!(march().to_string(), "2026-03");
+        for (text, days) in [("2026-02", 28), ("2028-02", 29), ("2026-04", 30), ("2026-12", 31)] {
+            assert_eq!(Month::parse(text).expect(text).days(), days, "{}", text);
+        }
+        for text in ["2026-13", "March", "2026/03", ""] {
+            let err = Month::parse(text).expect_err(text);
+            assert!(err.contains("use YYYY-MM"), "{}: {}", text, err);
+        }
+        assert!(march().contains(date("2026-03-31")));
+        assert!(!march().contains(date("2026-04-01")));
+    }
+
+    #[test]
+    fn messages_are_counted_per_day_hour_and_persona() {
+        let events = events();
+        let per_day = messages_per_day(&events, march(), utc());
+        assert_eq!(per_day.len(), 31);
+        assert_eq!(per_day.iter().sum::<u64>(), 5, "February and April are left out");
+        assert_eq!((per_day[1], per_day[9]), (3, 2));
+
+        let by_hour = messages_by_hour(&events, march(), utc());
+        let busy: Vec<(usize, u64)> = by_hour.iter().copied().enumerate().filter(|(_, count)| *count > 0).collect();
+        assert_eq!(busy, [(9, 2), (14, 2), (21, 1)]);
+
+        assert_eq!(persona_counts(&events, march(), utc()), [("shadow".to_string(), 4), ("friday".to_string(), 1)]);
+    }
+
+    #[test]
+    fn the_zone_decides_which_month_an_event_falls_in() {
+        let events = events();
+        let east = Zone::parse("+02:00").expect("an offset parses");
+        let per_day = messages_per_day(&events, march(), east);
+        assert_eq!(per_day.iter().sum::<u64>(), 6, "February 28th 23:30 UTC is March 1st at +02:00");
+        assert_eq!(per_day[0], 1);
+        assert_eq!(sessions(&events, march(), east).sessions, 4);
+    }
+
+    #[test]
+    fn sessions_measure_unclosed_agents_to_their_last_event() {
+        let stats = sessions(&events(), march(), utc());
+        assert_eq!(stats, SessionStats {
+            sessions: 3,
+            total: Duration::minutes(30 + 45 + 5) + Duration::seconds(10),
+            unclosed: 1,
+        });
+        assert_eq!(stats.average().map(format_duration).as_deref(), Some("26m"));
+        assert_eq!(SessionStats { sessions: 0, total: Duration::zero(), unclosed: 0 }.average(), None);
+    }
+
+    #[test]
+    fn weekly_usage_sums_tokens_and_prices_known_models() {
+        let weeks = weekly_usage(&events(), march(), utc(), &prices());
+        assert_eq!(weeks.len(), 2);
+
+        let first = &weeks[0];
+        assert_eq!(
+            (first.week_start, first.replies, first.input_tokens, first.output_tokens, first.unpriced),
+            (date("2026-03-02"), 3, 2700, 600, 1),
+            "the claude reply has no price"
+        );
+        assert!((first.cost - 0.0141).abs() < 1e-9, "{}", first.cost);
+
+        let second = &weeks[1];
+        assert_eq!(
+            (second.week_start, second.replies, second.input_tokens, second.output_tokens, second.unpriced),
+            (date("2026-03-09"), 2, 2000, 400, 0),
+            "a reply without usage still counts as a reply"
+        );
+        assert!((second.cost - 0.012).abs() < 1e-9, "{}", second.cost);
+
+        let unpriced = weekly_usage(&events(), march(), utc(), &[]);
+        assert_eq!(unpriced.iter().map(|week| week.unpriced).collect::<Vec<_>>(), [3, 2]);
+        assert!(unpriced.iter().all(|week| week.cost == 0.0));
+    }
+
+    #[test]
+    fn a_check_in_chased_twice_counts_once() {
+        let focus: Source<FocusRecord> = read_jsonl(&fixtures().focus_log);
+        assert_eq!(focus.status, SourceStatus::Read { skipped: 0 });
+        let stats = check_ins(&focus.records, &events(), march(), utc());
+        assert_eq!(stats, CheckInStats { sent: 3, followed_up: 1 });
+        assert!((stats.response_rate().expect("check-ins were sent") - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(CheckInStats { sent: 0, followed_up: 0 }.response_rate(), None);
+    }
+
+    #[test]
+    fn the_report_renders_the_fixture_month() {
+        let insights = MonthInsights::collect(&fixtures(), march(), utc(), &prices());
+        let report = insights.render();
+        let lines: Vec<&str> = report.lines().collect();
+
+        assert_eq!(lines[0], "Insights for 2026-03 (computed locally)");
+        for expected in [
+            "Messages per day (5 total, busiest 2026-03-02 with 3):",
+            "  shadow | ############################## 4 (80%)",
+            "  friday | ######## 1 (20%)",
+            "  3 opened, average length 26m (1 still open at exit, measured to their last reply)",
+            "  week of 03-02 | ############################## 2.7k in / 600 out, 3 replies, ~$0.01 (1 unpriced)",
+            "  week of 03-09 | ###################### 2.0k in / 400 out, 2 replies, ~$0.01",
+            "  3 sent, 2 answered before a follow-up (67%)",
+            "  Commands used: not recorded; no log of commands is kept.",
+        ] {
+            assert!(lines.contains(&expected), "missing {:?} in:\n{}", expected, report);
+        }
+        assert!(
+            insights.notes.iter().any(|note| note.ends_with("events.jsonl: skipped 1 unreadable line(s)")),
+            "{:?}", insights.notes
+        );
+
+        let markdown = insights.to_markdown();
+        assert!(markdown.starts_with("# Insights for 2026-03\n"), "{}", markdown);
+        assert!(markdown.contains("## Personas\n\n```\nshadow | "), "{}", markdown);
+        assert!(markdown.contains("## Check-ins\n\n3 sent, 2 answered"), "{}", markdown);
+        assert!(markdown.contains("## Notes\n\n- "), "{}", markdown);
+    }
+
+    #[test]
+    fn missing_sources_leave_zeros_and_notes() {
+        let dir = ScratchDir::new("insights-missing");
+        let sources = InsightSources { events: dir.join("events.jsonl"), focus_log: dir.join("focus_log.jsonl") };
+        let insights = MonthInsights::collect(&sources, march(), utc(), &[]);
+
+        assert_eq!(insights.per_day.iter().sum::<u64>(), 0);
+        assert!(insights.weeks.is_empty());
+        assert_eq!(insights.check_ins, None, "no focus log, no check-in figures");
+        assert_eq!(insights.notes.len(), 3, "{:?}", insights.notes);
+        assert!(insights.notes[0].ends_with("not found; turn on events.enabled to record messages, sessions, and tokens"), "{}", insights.notes[0]);
+
+        let report = insights.render();
+        for expected in ["Messages per day (none):", "  (no messages)", "  (none)", "  (no replies)", "  (no focus log)"] {
+            assert!(report.lines().any(|line| line == expected), "missing {:?} in:\n{}", expected, report);
+        }
+    }
+
+    #[test]
+    fn tokens_and_durations_format_compactly() {
+        for (tokens, shown) in [(950, "950"), (12_345, "12.3k"), (1_234_567, "1.2M")] {
+            assert_eq!(format_tokens(tokens), shown);
+        }
+        for (duration, shown) in [(Duration::seconds(45), "45s"), (Duration::minutes(18), "18m"), (Duration::minutes(65), "1h 05m")] {
+            assert_eq!(format_duration(duration), shown);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! # Daegonica Module: utilities::language
+//!
+//! **Purpose:** Work out which language a conversation is held in
+//!
+//! **Context:**
+//! - The historian and draft prompts are English; without a hint, replies to them are too
+//! - The resolved language is templated into those prompts as an explicit instruction
+//!
+//! **Responsibilities:**
+//! - Detect the language of a text sample (whatlang), only when the result is reliable
+//! - Track a conversation's language, switching only after a sustained shift
+//! - Parse language codes and names given by the user or a persona file
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::collections::VecDeque;
+pub use whatlang::Lang;
+
+/// Samples shorter than this are too short to detect reliably
+const MIN_SAMPLE_CHARS: usize = 20;
+
+/// Samples combined while the language is still unknown
+const SEED_MESSAGES: usize = 5;
+
+/// # detect
+///
+/// **Purpose:**
+/// Detects the language of a sample.
+///
+/// **Returns:**
+/// `Option<Lang>` - None for short samples or when whatlang isn't confident
+pub fn detect(text: &str) -> Option<Lang> {
+    if text.trim().chars().count() < MIN_SAMPLE_CHARS {
+        return None;
+    }
+    whatlang::detect(text)
+        .filter(|info| info.is_reliable())
+        .map(|info| info.lang())
+}
+
+/// # parse_language
+///
+/// **Purpose:**
+/// Reads a language from an ISO 639-3 code (`spa`) or English name (`Spanish`).
+pub fn parse_language(value: &str) -> Option<Lang> {
+    let value = value.trim().to_lowercase();
+    Lang::from_code(value.as_str())
+        .or_else(|| Lang::all().iter().copied().find(|lang| lang.eng_name().to_lowercase() == value))
+}
+
+/// The line added to summarizer and draft prompts
+pub fn instruction(lang: Lang) -> String {
+    format!("Respond in the same language as the conversation: {}.", lang.eng_name())
+}
+
+/// # LanguageSource
+///
+/// **Summary:**
+/// Where a conversation's language came from, in order of precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LanguageSource {
+    Override,
+    Persona,
+    Detected,
+}
+
+impl LanguageSource {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            LanguageSource::Override => "set with `language`",
+            LanguageSource::Persona => "pinned by persona",
+            LanguageSource::Detected => "detected",
+        }
+    }
+}
+
+/// # LanguageTracker
+///
+/// **Summary:**
+/// The detected language of a conversation and any shift in progress.
+///
+/// **Fields:**
+/// - `detected`: Language settled on so far, None until a sample was reliable
+/// - `unsettled`: Recent samples, kept only while the language is unknown
+/// - `shift`: A different language seen in the latest consecutive messages, with the count
+///
+/// **Details:**
+/// While the language is unknown, the last few samples are detected together so
+/// short messages add up. Once it's known, it only changes after `shift_after`
+/// consecutive messages in the same other language; messages too short to
+/// detect don't break a streak.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LanguageTracker {
+    detected: Option<Lang>,
+    unsettled: VecDeque<String>,
+    shift: Option<(Lang, usize)>,
+}
+
+impl LanguageTracker {
+    pub fn detected(&self) -> Option<Lang> {
+        self.detected
+    }
+
+    /// # observe
+    ///
+    /// **Purpose:**
+    /// Takes a new user message into account.
+    ///
+    /// **Parameters:**
+    /// - `sample`: The message as the user wrote it
+    /// - `shift_after`: Consecutive messages needed to switch languages
+    ///
+    /// **Returns:**
+    /// `Option<Lang>` - The new language when it was set or switched
+    pub fn observe(&mut self, sample: &str, shift_after: usize) -> Option<Lang> {
+        let Some(current) = self.detected else {
+            if self.unsettled.len() == SEED_MESSAGES {
+                self.unsettled.pop_front();
+            }
+            self.unsettled.push_back(sample.to_string());
+            self.detected = detect(&self.unsettled.iter().map(String::as_str).collect::<Vec<_>>().join("\n"));
+            if self.detected.is_some() {
+                self.unsettled.clear();
+            }
+            return self.detected;
+        };
+
+        let seen = detect(sample)?;
+        if seen == current {
+            self.shift = None;
+            return None;
+        }
+
+        let count = match self.shift {
+            Some((lang, count)) if lang == seen => count + 1,
+            _ => 1,
+        };
+        if count >= shift_after.max(1) {
+            self.detected = Some(seen);
+            self.shift = None;
+            Some(seen)
+        } else {
+            self.shift = Some((seen, count));
+            None
+        }
+    }
+
+    /// # seed
+    ///
+    /// **Purpose:**
+    /// Starts from the last user messages of a loaded history.
+    pub fn seed<'a>(&mut self, user_messages: impl DoubleEndedIterator<Item = &'a str>, shift_after: usize) {
+        let mut recent: Vec<&str> = user_messages.rev().take(SEED_MESSAGES).collect();
+        recent.reverse();
+        for sample in recent {
+            self.observe(sample, shift_after);
+        }
+    }
+
+    /// Forgets what was detected (history was cleared)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPANISH: &str = "Hola, ¿cómo estás? Quiero hablar contigo sobre el proyecto de la semana que viene, porque tenemos muchas cosas pendientes.";
+    const ENGLISH: &str = "Could you remind me tomorrow that I need to call my sister in the afternoon?";
+    const FRENCH: &str = "Peux-tu me rappeler demain que je dois appeler ma sœur dans l'après-midi ?";
+
+    #[test]
+    fn short_or_unreliable_samples_detect_nothing() {
+        let cases = [
+            ("", None),
+            ("ok", None),
+            ("sí, claro", None),
+            (SPANISH, Some(Lang::Spa)),
+            (ENGLISH, Some(Lang::Eng)),
+            (FRENCH, Some(Lang::Fra)),
+        ];
+
+        for (sample, expected) in cases {
+            assert_eq!(detect(sample), expected, "{:?}", sample);
+        }
+    }
+
+    #[test]
+    fn languages_parse_from_codes_and_names() {
+        let cases = [
+            ("spa", Some(Lang::Spa)),
+            ("Spanish", Some(Lang::Spa)),
+            (" FRA ", Some(Lang::Fra)),
+            ("es", None),
+            ("klingon", None),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(parse_language(value), expected, "{:?}", value);
+        }
+        assert_eq!(instruction(Lang::Spa), "Respond in the same language as the conversation: Spanish.");
+    }
+
+    #[test]
+    fn short_messages_add_up_until_the_language_is_known() {
+        let mut tracker = LanguageTracker::default();
+
+        assert_eq!(tracker.observe("hola, ¿qué tal?", 3), None);
+        assert_eq!(detect("muy bien, gracias"), None, "too short on its own");
+        assert_eq!(tracker.observe("muy bien, gracias", 3), Some(Lang::Spa));
+        assert_eq!(tracker.detected(), Some(Lang::Spa));
+    }
+
+    #[test]
+    fn the_language_only_switches_after_a_sustained_shift() {
+        let mut tracker = LanguageTracker::default();
+        tracker.observe(SPANISH, 3);
+
+        assert_eq!(tracker.observe(ENGLISH, 3), None);
+        assert_eq!(tracker.observe(ENGLISH, 3), None);
+        assert_eq!(tracker.detected(), Some(Lang::Spa), "two messages are not a shift");
+        assert_eq!(tracker.observe(ENGLISH, 3), Some(Lang::Eng));
+        assert_eq!(tracker.detected(), Some(Lang::Eng));
+    }
+
+    #[test]
+    fn a_streak_is_broken_by_the_current_language_but_not_by_short_messages() {
+        let cases: [(&str, &[&str], Lang); 4] = [
+            ("broken by Spanish", &[ENGLISH, ENGLISH, SPANISH, ENGLISH], Lang::Spa),
+            ("not broken by 'ok'", &[ENGLISH, "ok", ENGLISH, "ok", ENGLISH], Lang::Eng),
+            ("restarted by French", &[ENGLISH, ENGLISH, FRENCH, FRENCH], Lang::Spa),
+            ("French in full", &[ENGLISH, FRENCH, FRENCH, FRENCH], Lang::Fra),
+        ];
+
+        for (case, messages, expected) in cases {
+            let mut tracker = LanguageTracker::default();
+            tracker.observe(SPANISH, 3);
+            for message in messages {
+                tracker.observe(message, 3);
+            }
+            assert_eq!(tracker.detected(), Some(expected), "{}", case);
+        }
+    }
+
+    #[test]
+    fn seeding_reads_only_the_latest_messages() {
+        let mut tracker = LanguageTracker::default();
+        let history = [ENGLISH, ENGLISH, SPANISH, SPANISH, SPANISH, SPANISH, SPANISH];
+        tracker.seed(history.into_iter(), 3);
+        assert_eq!(tracker.detected(), Some(Lang::Spa));
+
+        tracker.reset();
+        assert_eq!(tracker, LanguageTracker::default());
+    }
+}
@@ -0,0 +1,286 @@
+//! # Daegonica Module: utilities::links
+//!
+//! **Purpose:** Find URLs in message text and open them in the browser
+//!
+//! **Context:**
+//! - Replies carry docs links, markdown links and tweet URLs that were only usable by select-copying
+//! - The TUI turns them into clickable links (`tui::hyperlinks`); `open <n>` works in both modes
+//!
+//! **Responsibilities:**
+//! - Find markdown links and bare URLs in a line (`find_links`, pure)
+//! - Shorten a bare URL for display (`shorten_url`, pure)
+//! - List a pane's recent URLs, newest first
+//! - Launch a URL with the platform opener (`open`, `xdg-open`, the Windows URL handler)
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::ops::Range;
+use std::process::Stdio;
+use crate::user::system_info::OsType;
+
+/// `[text](https://...)`; the text may not span lines or contain `]`
+static MARKDOWN_LINK: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\[([^\]\n]+)\]\((https?://[^)\s]+)\)").expect("markdown link pattern is valid")
+});
+
+/// A bare http(s) URL, up to whitespace or a character that can't be part of one
+static BARE_URL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"https?://[^\s<>\[\]"'`]+"#).expect("URL pattern is valid")
+});
+
+/// # FoundLink
+///
+/// **Summary:**
+/// A link found in a line of text.
+///
+/// **Fields:**
+/// - `range`: Byte range of the whole link in the line (`[text](url)` or the bare URL)
+/// - `text`: What to show: the link text of a markdown link, or the URL itself
+/// - `target`: The URL
+/// - `markdown`: Whether it was written as a markdown link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundLink {
+    pub range: Range<usize>,
+    pub text: String,
+    pub target: String,
+    pub markdown: bool,
+}
+
+/// # find_links
+///
+/// **Purpose:**
+/// Finds the markdown links and bare URLs in `line`.
+///
+/// **Returns:**
+/// `Vec<FoundLink>` - In order, never overlapping
+///
+/// **Details:**
+/// A bare URL loses trailing sentence punctuation (`.`, `,`, `!`, ...) and a
+/// closing `)` it doesn't open, so "see (https://a.io/x)." links to `https://a.io/x`.
+pub fn find_links(line: &str) -> Vec<FoundLink> {
+    let mut links: Vec<FoundLink> = MARKDOWN_LINK.captures_iter(line)
+        .filter_map(|caps| {
+            let whole = caps.get(0)?;
+            Some(FoundLink {
+                range: whole.range(),
+                text: caps[1].to_string(),
+                target: caps[2].to_string(),
+                markdown: true,
+            })
+        })
+        .collect();
+
+    let bare: Vec<FoundLink> = BARE_URL.find_iter(line)
+        .filter(|found| !links.iter().any(|link| link.range.contains(&found.start())))
+        .map(|found| {
+            let url = trim_url(found.as_str());
+            FoundLink {
+                range: found.start()..found.start() + url.len(),
+                text: url.to_string(),
+                target: url.to_string(),
+                markdown: false,
+            }
+        })
+        .collect();
+
+    links.extend(bare);
+    links.sort_by_key(|link| link.range.start);
+    links
+}
+
+/// Drops trailing punctuation and unbalanced closing brackets from a bare URL
+fn trim_url(url: &str) -> &str {
+    let mut url = url;
+    loop {
+        let Some(last) = url.chars().next_back() else {
+            return url;
+        };
+        let unbalanced = last == ')' && url.matches(')').count() > url.matches('(').count();
+        if matches!(last, '.' | ',' | ';' | ':' | '!' | '?') || unbalanced {
+            url = &url[..url.len() - 1];
+        } else {
+            return url;
+        }
+    }
+}
+
+/// # shorten_url
+///
+/// **Purpose:**
+/// A short display form of a URL, at most `max_chars` characters.
+///
+/// **Details:**
+/// Drops the scheme and a leading `www.`. If that is still too long, keeps
+/// the host and the last path segment (`docs.rs/…/struct.Span.html`), and
+/// failing that cuts the end off with `…`.
+pub fn shorten_url(url: &str, max_chars: usize) -> String {
+    let bare = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let bare = bare.strip_prefix("www.").unwrap_or(bare);
+    let bare = bare.strip_suffix('/').unwrap_or(bare);
+    if bare.chars().count() <= max_chars {
+        return bare.to_string();
+    }
+
+    if let Some((host, path)) = bare.split_once('/')
+        && let Some(last) = path.rsplit('/').find(|segment| !segment.is_empty())
+    {
+        let short = format!("{}/…/{}", host, last);
+        if short.chars().count() <= max_chars && last != path {
+            return short;
+        }
+    }
+
+    let mut cut: String = bare.chars().take(max_chars.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+/// # recent_urls
+///
+/// **Purpose:**
+/// The distinct URLs in a pane's messages, newest first.
+///
+/// **Parameters:**
+/// - `messages`: Pane messages, oldest first
+/// - `limit`: Most URLs to return
+///
+/// **Returns:**
+/// `Vec<String>` - Link targets; within one message, the last link counts as the newest
+pub fn recent_urls<'a>(messages: impl DoubleEndedIterator<Item = &'a str>, limit: usize) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for message in messages.rev() {
+        for link in find_links(message).into_iter().rev() {
+            if urls.len() == limit {
+                return urls;
+            }
+            if !urls.contains(&link.target) {
+                urls.push(link.target);
+            }
+        }
+    }
+    urls
+}
+
+/// The program (and leading arguments) that opens a URL in the default browser on `os`
+fn opener(os: OsType) -> Option<(&'static str, &'static [&'static str])> {
+    match os {
+        OsType::MacOs => Some(("open", &[])),
+        // Not `cmd /C start`, which would parse `&` in the URL as a command separator
+        OsType::Windows => Some(("rundll32", &["url.dll,FileProtocolHandler"])),
+        OsType::Linux => Some(("xdg-open", &[])),
+        OsType::Other => None,
+    }
+}
+
+/// # open_in_browser
+///
+/// **Purpose:**
+/// Opens `url` in the default browser without waiting for it.
+///
+/// **Errors / Failures:**
+/// - No opener is known for the OS, or it can't be started
+pub fn open_in_browser(url: &str, os: OsType) -> std::io::Result<()> {
+    let Some((program, args)) = opener(os) else {
+        return Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "no way to open a browser on this OS"));
+    };
+    let mut child = std::process::Command::new(program)
+        .args(args)
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    // The opener hands off to the browser and exits; reap it so it doesn't linger as a zombie
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A link as (text, target, markdown)
+    type Found = (String, String, bool);
+
+    /// (text, target, markdown) of each link in `line`, checking each range covers what it replaces
+    fn links(line: &str) -> Vec<Found> {
+        find_links(line).into_iter()
+            .map(|link| {
+                let covered = &line[link.range.clone()];
+                if link.markdown {
+                    assert_eq!(covered, format!("[{}]({})", link.text, link.target), "range of a markdown link in {:?}", line);
+                } else {
+                    assert_eq!(covered, link.target, "range of a bare URL in {:?}", line);
+                }
+                (link.text, link.target, link.markdown)
+            })
+            .collect()
+    }
+
+    fn bare(url: &str) -> Found {
+        (url.to_string(), url.to_string(), false)
+    }
+
+    #[test]
+    fn links_are_found_without_the_punctuation_around_them() {
+        let cases: &[(&str, Vec<Found>)] = &[
+            ("no links here", vec![]),
+            ("ftp://files.example.com is not http", vec![]),
+            ("see https://docs.rs/ratatui", vec![bare("https://docs.rs/ratatui")]),
+            ("Done: https://x.com/daegonica/status/1881.", vec![bare("https://x.com/daegonica/status/1881")]),
+            ("really?! http://a.io/x?!", vec![bare("http://a.io/x")]),
+            ("(see https://a.io/x).", vec![bare("https://a.io/x")]),
+            ("https://en.wikipedia.org/wiki/Rust_(programming_language)", vec![bare("https://en.wikipedia.org/wiki/Rust_(programming_language)")]),
+            ("quoted \"https://a.io/q\" and <https://a.io/angle>", vec![bare("https://a.io/q"), bare("https://a.io/angle")]),
+            ("read [the docs](https://docs.rs/x) first", vec![("the docs".into(), "https://docs.rs/x".into(), true)]),
+            (
+                "[one](https://a.io/1) then https://b.io/2, and [three](http://c.io/3)",
+                vec![("one".into(), "https://a.io/1".into(), true), bare("https://b.io/2"), ("three".into(), "http://c.io/3".into(), true)],
+            ),
+            ("[not a link](docs/local.md)", vec![]),
+            ("[spaces in](https://a.io/has space)", vec![bare("https://a.io/has")]),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(&links(line), expected, "links in {:?}", line);
+        }
+    }
+
+    #[test]
+    fn urls_are_shortened_to_fit() {
+        let cases = [
+            ("https://docs.rs/", 40, "docs.rs"),
+            ("https://www.example.com/about", 40, "example.com/about"),
+            ("http://a.io/x", 3, "a.…"),
+            ("https://docs.rs/ratatui/latest/ratatui/text/struct.Span.html", 40, "docs.rs/…/struct.Span.html"),
+            ("https://docs.rs/ratatui/latest/ratatui/text/struct.Span.html", 60, "docs.rs/ratatui/latest/ratatui/text/struct.Span.html"),
+            ("https://x.com/daegonica/status/18812345678901234567/", 20, "x.com/daegonica/sta…"),
+            ("https://example.com/a-very-long-single-segment-name", 20, "example.com/a-very-…"),
+            ("https://example.com/ünïcödé/päth/ënd", 20, "example.com/…/ënd"),
+        ];
+        for (url, max_chars, expected) in cases {
+            let short = shorten_url(url, max_chars);
+            assert_eq!(short, expected, "{} in {} chars", url, max_chars);
+            assert!(short.chars().count() <= max_chars, "{:?} is longer than {}", short, max_chars);
+        }
+    }
+
+    #[test]
+    fn recent_urls_are_distinct_and_newest_first() {
+        let messages = [
+            "old: https://a.io/1 and https://a.io/2",
+            "no links",
+            "[again](https://a.io/1) and https://a.io/3",
+        ];
+        assert_eq!(recent_urls(messages.into_iter(), 10), ["https://a.io/3", "https://a.io/1", "https://a.io/2"]);
+        assert_eq!(recent_urls(messages.into_iter(), 2), ["https://a.io/3", "https://a.io/1"]);
+        assert!(recent_urls(["nothing"].into_iter(), 5).is_empty());
+    }
+}
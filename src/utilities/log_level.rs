@@ -0,0 +1,227 @@
+//! # Daegonica Module: utilities::log_level
+//!
+//! **Purpose:** Runtime-adjustable log verbosity
+//!
+//! **Context:**
+//! - Gates the crate's `log_error!` / `log_warn!` / `log_info!` / `log_debug!` / `log_trace!` macros before they reach dlog
+//! - Set at startup by `--log-level` and live by the `loglevel` command
+//!
+//! **Responsibilities:**
+//! - Hold the default level and per-module overrides
+//! - Answer "is this level enabled for this module?"
+//! - Hand enabled log lines to dlog
+//! - Describe the effective filter for status output
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::fmt::Arguments;
+use std::sync::RwLock;
+use once_cell::sync::Lazy;
+use strum::{Display, EnumString};
+
+/// # LogLevel
+///
+/// **Summary:**
+/// Verbosity levels, least to most verbose.
+///
+/// **Details:**
+/// dlog only has error and info macros, so `warn` lines go out as errors and
+/// `debug` / `trace` lines as info, each tagged with its level (`log`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumString, Display)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+#[derive(Debug)]
+struct LogFilter {
+    default: LogLevel,
+    targets: Vec<(String, LogLevel)>,
+}
+
+static FILTER: Lazy<RwLock<LogFilter>> = Lazy::new(|| RwLock::new(LogFilter {
+    default: LogLevel::Info,
+    targets: Vec::new(),
+}));
+
+/// # set_level
+///
+/// **Purpose:**
+/// Changes the default level, or the level for one module path and its children.
+///
+/// **Parameters:**
+/// - `level`: New maximum verbosity
+/// - `target`: Module path such as `grokprime_brain::grok::client` (None = default)
+pub fn set_level(level: LogLevel, target: Option<&str>) {
+    let mut filter = FILTER.write().unwrap_or_else(|e| e.into_inner());
+    match target {
+        None => filter.default = level,
+        Some(target) => {
+            filter.targets.retain(|(existing, _)| existing != target);
+            filter.targets.push((target.to_string(), level));
+        }
+    }
+}
+
+/// # enabled
+///
+/// **Purpose:**
+/// Checks whether a log call at `level` from `module_path` should be emitted.
+///
+/// **Details:**
+/// The longest matching target prefix wins; otherwise the default level applies.
+pub fn enabled(level: LogLevel, module_path: &str) -> bool {
+    let filter = FILTER.read().unwrap_or_else(|e| e.into_inner());
+    let max = filter.targets.iter()
+        .filter(|(target, _)| {
+            module_path == target || module_path.starts_with(&format!("{}::", target))
+        })
+        .max_by_key(|(target, _)| target.len())
+        .map(|(_, level)| *level)
+        .unwrap_or(filter.default);
+    level <= max
+}
+
+/// # describe
+///
+/// **Purpose:**
+/// Renders the effective filter, e.g. `info, grokprime_brain::grok::client=debug`.
+pub fn describe() -> String {
+    let filter = FILTER.read().unwrap_or_else(|e| e.into_inner());
+    std::iter::once(filter.default.to_string())
+        .chain(filter.targets.iter().map(|(target, level)| format!("{}={}", target, level)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// # log
+///
+/// **Purpose:**
+/// Writes one log line through dlog if `level` is enabled for `module_path`.
+///
+/// **Parameters:**
+/// - `level`: The line's level
+/// - `module_path`: Where the line was logged from (`module_path!()`)
+/// - `args`: The message, only formatted when the line is written
+///
+/// **Details:**
+/// Called by the logging macros rather than directly.
+pub fn log(level: LogLevel, module_path: &str, args: Arguments) {
+    if !enabled(level, module_path) {
+        return;
+    }
+    #[cfg(test)]
+    if tests::capture(level, &args) {
+        return;
+    }
+    match level {
+        LogLevel::Error => dlog::log_error!("{}", args),
+        LogLevel::Warn => dlog::log_error!("[warn] {}", args),
+        LogLevel::Info => dlog::log_info!("{}", args),
+        LogLevel::Debug => dlog::log_info!("[debug] {}", args),
+        LogLevel::Trace => dlog::log_info!("[trace] {}", args),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static CAPTURED: RefCell<Option<Vec<(LogLevel, String)>>> = const { RefCell::new(None) };
+    }
+
+    /// Keeps the line instead of writing it when this thread is capturing
+    pub(super) fn capture(level: LogLevel, args: &Arguments) -> bool {
+        CAPTURED.with_borrow_mut(|captured| match captured {
+            Some(lines) => {
+                lines.push((level, args.to_string()));
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// The lines `f` logs, at the levels they were logged at
+    fn captured(f: impl FnOnce()) -> Vec<(LogLevel, String)> {
+        CAPTURED.set(Some(Vec::new()));
+        f();
+        CAPTURED.take().unwrap_or_default()
+    }
+
+    fn log_one_of_each() {
+        crate::log_error!("error {}", 1);
+        crate::log_warn!("warn {}", 2);
+        crate::log_info!("info {}", 3);
+        crate::log_debug!("debug {}", 4);
+        crate::log_trace!("trace {}", 5);
+    }
+
+    // Levels are set for this module only, so tests elsewhere are unaffected
+    #[test]
+    fn each_level_shows_itself_and_everything_less_verbose() {
+        let all = [LogLevel::Error, LogLevel::Warn, LogLevel::Info, LogLevel::Debug, LogLevel::Trace];
+        for level in all {
+            set_level(level, Some(module_path!()));
+            let levels: Vec<LogLevel> = captured(log_one_of_each).into_iter().map(|(level, _)| level).collect();
+            let expected: Vec<LogLevel> = all.into_iter().filter(|shown| *shown <= level).collect();
+            assert_eq!(levels, expected, "at {}", level);
+        }
+        set_level(LogLevel::Debug, Some(module_path!()));
+        assert_eq!(captured(|| crate::log_debug!("raised to {}", "debug")), [(LogLevel::Debug, "raised to debug".to_string())]);
+        set_level(LogLevel::Info, Some(module_path!()));
+        assert!(captured(|| crate::log_debug!("lowered again")).is_empty());
+    }
+
+    #[test]
+    fn filtered_lines_are_never_formatted() {
+        struct Loud;
+        impl std::fmt::Display for Loud {
+            fn fmt(&self, _: &mut std::fmt::Formatter) -> std::fmt::Result {
+                panic!("formatted a filtered line");
+            }
+        }
+        assert!(!enabled(LogLevel::Trace, "grokprime_brain::utilities::log_level::elsewhere"));
+        log(LogLevel::Trace, "grokprime_brain::utilities::log_level::elsewhere", format_args!("{}", Loud));
+    }
+
+    #[test]
+    fn the_longest_matching_target_wins() {
+        set_level(LogLevel::Trace, Some("filter_test"));
+        set_level(LogLevel::Error, Some("filter_test::quiet"));
+        let cases = [
+            ("filter_test", LogLevel::Trace, true),
+            ("filter_test::noisy", LogLevel::Trace, true),
+            ("filter_test::quiet", LogLevel::Warn, false),
+            ("filter_test::quiet::inner", LogLevel::Error, true),
+            ("filter_test::quieter", LogLevel::Trace, true),
+            ("filter_testing", LogLevel::Trace, false),
+        ];
+        for (module_path, level, expected) in cases {
+            assert_eq!(enabled(level, module_path), expected, "{} at {}", module_path, level);
+        }
+        assert!(describe().contains("filter_test=trace, filter_test::quiet=error"), "{}", describe());
+
+        set_level(LogLevel::Warn, Some("filter_test::quiet"));
+        assert!(describe().contains("filter_test=trace, filter_test::quiet=warn"), "replaced, not added: {}", describe());
+    }
+
+    #[test]
+    fn levels_parse_from_flags_and_commands() {
+        assert_eq!("DEBUG".parse::<LogLevel>(), Ok(LogLevel::Debug));
+        assert_eq!("trace".parse::<LogLevel>(), Ok(LogLevel::Trace));
+        assert!("verbose".parse::<LogLevel>().is_err());
+        assert!(LogLevel::Error < LogLevel::Trace);
+    }
+}
@@ -18,8 +18,24 @@
 //! This file is part of the Daegonica Software codebase.
 //! ---------------------------------------------------------------
 
+pub mod accessibility;
+pub mod charts;
 pub mod cli;
+pub mod clipboard;
+pub mod code_lang;
+pub mod events;
+pub mod explain;
+pub mod insights;
+pub mod language;
+pub mod links;
+pub mod log_level;
 pub mod outputs;
+pub mod profile;
+pub mod speech;
+pub mod stream_text;
+pub mod strict;
+pub mod strip;
+pub mod time;
 
 pub use cli::*;
 pub use outputs::*;
\ No newline at end of file
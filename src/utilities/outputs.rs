@@ -23,6 +23,7 @@
 
 use std::sync::Arc;
 use std::fmt::Debug;
+use crate::persona::chunk_stream::{ChunkSender, ChunkStream, TaskKind};
 
 /// # OutputHandler
 ///
@@ -60,6 +61,40 @@ impl OutputHandler for CliOutput {
     }
 }
 
+/// # ChannelOutput
+///
+/// **Summary:**
+/// Output implementation that forwards messages to an agent's chunk channel as Info.
+///
+/// **Details:**
+/// Lets background work (e.g. posting a tweet) report into the TUI without
+/// printing over the alternate screen.
+///
+/// **Usage Example:**
+/// ```rust
+/// # use std::sync::Arc;
+/// # use grokprime_brain::utilities::outputs::{ChannelOutput, SharedOutput};
+/// # let (chunk_sender, _chunks) = tokio::sync::mpsc::unbounded_channel();
+/// let output: SharedOutput = Arc::new(ChannelOutput::new(&chunk_sender));
+/// ```
+#[derive(Debug)]
+pub struct ChannelOutput {
+    stream: std::sync::Mutex<ChunkStream>,
+}
+
+impl ChannelOutput {
+    pub fn new(tx: &ChunkSender) -> Self {
+        Self { stream: std::sync::Mutex::new(ChunkStream::open(tx, TaskKind::Report)) }
+    }
+}
+
+impl OutputHandler for ChannelOutput {
+    fn display(&self, msg: String) {
+        let mut stream = self.stream.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let _ = stream.info(msg);
+    }
+}
+
 /// # SharedOutput
 ///
 /// **Summary:**
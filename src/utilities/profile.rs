@@ -0,0 +1,332 @@
+//! # Daegonica Module: utilities::profile
+//!
+//! **Purpose:** Lightweight timing of command execution and TUI frames
+//!
+//! **Context:**
+//! - Recorded from `ShadowApp::run_action`, the CLI command path, and the draw loop
+//! - Read by the `profile` command
+//!
+//! **Responsibilities:**
+//! - Keep bounded rings of recent command and frame samples
+//! - Aggregate samples into per-command count, mean, and p95
+//! - Flag samples over the configured thresholds
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+use crate::prelude::*;
+
+/// Command samples kept for `profile`
+const COMMAND_CAPACITY: usize = 256;
+/// Frame samples kept for `profile frames` (a few seconds of drawing)
+const FRAME_CAPACITY: usize = 512;
+
+static PROFILER: Lazy<Mutex<Profiler>> = Lazy::new(|| Mutex::new(Profiler::new()));
+
+/// # Ring
+///
+/// **Summary:**
+/// Fixed-capacity buffer that overwrites its oldest entry once full.
+///
+/// **Details:**
+/// Storage is allocated once up front, so pushing never allocates.
+#[derive(Debug)]
+pub struct Ring<T> {
+    slots: Vec<T>,
+    capacity: usize,
+    next: usize,
+}
+
+impl<T> Ring<T> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self { slots: Vec::with_capacity(capacity), capacity, next: 0 }
+    }
+
+    pub fn push(&mut self, value: T) {
+        if self.slots.len() < self.capacity {
+            self.slots.push(value);
+        } else {
+            self.slots[self.next] = value;
+        }
+        self.next = (self.next + 1) % self.capacity;
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Entries from oldest to newest
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let split = if self.slots.len() < self.capacity { 0 } else { self.next };
+        self.slots[split..].iter().chain(&self.slots[..split])
+    }
+}
+
+/// # CommandSample
+///
+/// **Summary:**
+/// One timed command execution.
+///
+/// **Fields:**
+/// - `command`: InputAction variant name
+/// - `duration`: Time spent in `Command::execute`
+/// - `spawned_async`: The command left background work running (a job or a reply)
+/// - `at`: When it finished
+#[derive(Debug, Clone, Copy)]
+pub struct CommandSample {
+    pub command: &'static str,
+    pub duration: Duration,
+    pub spawned_async: bool,
+    pub at: Instant,
+}
+
+/// # Stats
+///
+/// **Summary:**
+/// Aggregate of a set of durations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub count: usize,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// # from_durations
+    ///
+    /// **Purpose:**
+    /// Computes count, mean, nearest-rank p95, and max.
+    ///
+    /// **Returns:**
+    /// `Option<Stats>` - None for an empty set
+    pub fn from_durations(mut durations: Vec<Duration>) -> Option<Self> {
+        if durations.is_empty() {
+            return None;
+        }
+        durations.sort();
+        let count = durations.len();
+        let total: Duration = durations.iter().sum();
+        let rank = (count * 95).div_ceil(100).max(1);
+        Some(Self {
+            count,
+            mean: total / count as u32,
+            p95: durations[rank - 1],
+            max: durations[count - 1],
+        })
+    }
+}
+
+/// # aggregate_commands
+///
+/// **Purpose:**
+/// Groups command samples by name, slowest p95 first.
+pub fn aggregate_commands<'a>(samples: impl Iterator<Item = &'a CommandSample>) -> Vec<(&'static str, Stats)> {
+    let mut by_command: BTreeMap<&'static str, Vec<Duration>> = BTreeMap::new();
+    for sample in samples {
+        by_command.entry(sample.command).or_default().push(sample.duration);
+    }
+
+    let mut stats: Vec<(&'static str, Stats)> = by_command.into_iter()
+        .filter_map(|(command, durations)| Stats::from_durations(durations).map(|stats| (command, stats)))
+        .collect();
+    stats.sort_by_key(|(_, stats)| Reverse(stats.p95));
+    stats
+}
+
+/// # Profiler
+///
+/// **Summary:**
+/// Recent command and frame samples for the whole process.
+#[derive(Debug)]
+pub struct Profiler {
+    pub commands: Ring<CommandSample>,
+    pub frames: Ring<Duration>,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self {
+            commands: Ring::with_capacity(COMMAND_CAPACITY),
+            frames: Ring::with_capacity(FRAME_CAPACITY),
+        }
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// # record_command
+///
+/// **Purpose:**
+/// Stores one command timing; commands over `tui.slow_command_ms` are also logged.
+pub fn record_command(command: &'static str, started: Instant, spawned_async: bool) {
+    let at = Instant::now();
+    let duration = at - started;
+    if is_slow_command(duration) {
+        log_info!("Slow command {} took {:.1} ms", command, millis(duration));
+    }
+    PROFILER.lock().unwrap_or_else(|e| e.into_inner())
+        .commands.push(CommandSample { command, duration, spawned_async, at });
+}
+
+/// # record_frame
+///
+/// **Purpose:**
+/// Stores how long one draw of the TUI took.
+pub fn record_frame(duration: Duration) {
+    PROFILER.lock().unwrap_or_else(|e| e.into_inner()).frames.push(duration);
+}
+
+/// Copies out the recorded command samples, oldest first
+pub fn command_samples() -> Vec<CommandSample> {
+    PROFILER.lock().unwrap_or_else(|e| e.into_inner()).commands.iter().copied().collect()
+}
+
+/// Copies out the recorded frame times, oldest first
+pub fn frame_samples() -> Vec<Duration> {
+    PROFILER.lock().unwrap_or_else(|e| e.into_inner()).frames.iter().copied().collect()
+}
+
+pub fn is_slow_command(duration: Duration) -> bool {
+    duration >= Duration::from_millis(GLOBAL_CONFIG.tui.slow_command_ms)
+}
+
+pub fn is_slow_frame(duration: Duration) -> bool {
+    duration >= Duration::from_millis(GLOBAL_CONFIG.tui.slow_frame_ms)
+}
+
+/// Milliseconds with a fractional part, for display
+pub fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&v| Duration::from_millis(v)).collect()
+    }
+
+    fn sample(command: &'static str, millis: u64) -> CommandSample {
+        CommandSample { command, duration: Duration::from_millis(millis), spawned_async: false, at: Instant::now() }
+    }
+
+    #[test]
+    fn the_ring_keeps_the_newest_entries_in_order() {
+        let mut ring = Ring::with_capacity(3);
+        assert!(ring.is_empty());
+
+        // (pushed so far) -> contents oldest first
+        let cases: [(u32, &[u32]); 5] = [
+            (1, &[1]),
+            (3, &[1, 2, 3]),
+            (4, &[2, 3, 4]),
+            (6, &[4, 5, 6]),
+            (7, &[5, 6, 7]),
+        ];
+        let mut pushed = 0;
+        for (upto, expected) in cases {
+            while pushed < upto {
+                pushed += 1;
+                ring.push(pushed);
+            }
+            assert_eq!(ring.iter().copied().collect::<Vec<_>>(), expected, "after {} pushes", upto);
+            assert_eq!(ring.len(), expected.len());
+        }
+    }
+
+    #[test]
+    fn the_ring_never_grows_past_its_first_allocation() {
+        let mut ring = Ring::with_capacity(4);
+        let allocated = ring.slots.capacity();
+        for i in 0..100 {
+            ring.push(i);
+        }
+        assert_eq!(ring.slots.capacity(), allocated);
+        assert_eq!(ring.len(), 4);
+
+        let mut tiny = Ring::with_capacity(0);
+        tiny.push("a");
+        tiny.push("b");
+        assert_eq!(tiny.iter().copied().collect::<Vec<_>>(), ["b"], "a zero capacity still holds one");
+    }
+
+    /// (count, mean, p95, max), all but count in ms
+    type Expected = (usize, u64, u64, u64);
+
+    #[test]
+    fn stats_use_nearest_rank_p95() {
+        let twenty: Vec<u64> = (1..=20).collect();
+        let hundred: Vec<u64> = (1..=100).rev().collect();
+        let cases: [(&[u64], Expected); 5] = [
+            (&[7], (1, 7, 7, 7)),
+            (&[30, 10, 20], (3, 20, 30, 30)),
+            (&twenty, (20, 10, 19, 20)),
+            (&hundred, (100, 50, 95, 100)),
+            (&[1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 200], (20, 10, 1, 200)),
+        ];
+        for (durations, (count, mean, p95, max)) in cases {
+            let stats = Stats::from_durations(ms(durations)).expect("samples given");
+            let context = format!("{:?}", durations);
+            assert_eq!(stats.count, count, "{}", context);
+            assert_eq!(stats.p95, Duration::from_millis(p95), "{}", context);
+            assert_eq!(stats.max, Duration::from_millis(max), "{}", context);
+            let mean_error = stats.mean.abs_diff(Duration::from_millis(mean));
+            assert!(mean_error < Duration::from_millis(1), "{}: mean {:?}", context, stats.mean);
+        }
+        assert_eq!(Stats::from_durations(Vec::new()), None);
+    }
+
+    #[test]
+    fn commands_aggregate_by_name_slowest_p95_first() {
+        let samples = [
+            sample("HistoryInfo", 40),
+            sample("AgentStatus", 2),
+            sample("HistoryInfo", 60),
+            sample("Send", 5),
+            sample("AgentStatus", 4),
+        ];
+
+        let stats = aggregate_commands(samples.iter());
+
+        let summary: Vec<(&str, usize, Duration)> = stats.iter().map(|(name, stats)| (*name, stats.count, stats.mean)).collect();
+        assert_eq!(summary, [
+            ("HistoryInfo", 2, Duration::from_millis(50)),
+            ("Send", 1, Duration::from_millis(5)),
+            ("AgentStatus", 2, Duration::from_millis(3)),
+        ]);
+        assert!(aggregate_commands([].iter()).is_empty());
+    }
+
+    #[test]
+    fn thresholds_are_inclusive() {
+        let command = Duration::from_millis(GLOBAL_CONFIG.tui.slow_command_ms);
+        assert!(is_slow_command(command));
+        assert!(!is_slow_command(command - Duration::from_micros(1)));
+
+        let frame = Duration::from_millis(GLOBAL_CONFIG.tui.slow_frame_ms);
+        assert!(is_slow_frame(frame));
+        assert!(!is_slow_frame(frame - Duration::from_micros(1)));
+        assert_eq!(millis(Duration::from_micros(1500)), 1.5);
+    }
+}
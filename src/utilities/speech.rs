@@ -0,0 +1,443 @@
+//! # Daegonica Module: utilities::speech
+//!
+//! **Purpose:** Speak finished replies aloud through the OS text-to-speech program
+//!
+//! **Context:**
+//! - For check-ins while away from the keyboard; switched on per agent with `speak on`
+//! - Off by default (`tts.speak_by_default`), and nothing is spoken without a program on PATH
+//! - Replies are markdown: code, links and markup read badly, so they are cut before speaking
+//!
+//! **Responsibilities:**
+//! - Pick the speech program for the OS (`say`, PowerShell System.Speech, `spd-say` / `espeak`)
+//! - Turn a reply into the text to speak (`prepare_text`, pure)
+//! - Queue utterances on one background task, so replies never talk over each other
+//! - Stop speaking on `stop` / Ctrl+C, and when the app quits
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::io::AsyncWriteExt;
+use tokio::task;
+use crate::prelude::*;
+use crate::config::TtsConfig;
+use crate::user::system_info::OsType;
+
+/// # SpeechProgram
+///
+/// **Summary:**
+/// A text-to-speech command that reads the text to speak from stdin.
+///
+/// **Fields:**
+/// - `program`: Binary looked up on PATH
+/// - `args`: Arguments that make it read stdin and return once it has finished speaking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpeechProgram {
+    pub program: &'static str,
+    pub args: &'static [&'static str],
+}
+
+const MACOS_PROGRAMS: &[SpeechProgram] = &[
+    SpeechProgram { program: "say", args: &["-f", "-"] },
+];
+
+const WINDOWS_PROGRAMS: &[SpeechProgram] = &[
+    SpeechProgram {
+        program: "powershell",
+        args: &[
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "Add-Type -AssemblyName System.Speech; \
+             (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak([Console]::In.ReadToEnd())",
+        ],
+    },
+];
+
+const LINUX_PROGRAMS: &[SpeechProgram] = &[
+    SpeechProgram { program: "spd-say", args: &["--wait", "--pipe-mode"] },
+    SpeechProgram { program: "espeak", args: &["--stdin"] },
+];
+
+/// Speech programs to try on `os`, in order of preference
+pub fn candidates(os: OsType) -> &'static [SpeechProgram] {
+    match os {
+        OsType::MacOs => MACOS_PROGRAMS,
+        OsType::Windows => WINDOWS_PROGRAMS,
+        OsType::Linux => LINUX_PROGRAMS,
+        OsType::Other => &[],
+    }
+}
+
+/// Whether `program` is an executable file in a PATH directory
+fn on_path(program: &str) -> bool {
+    let Some(paths) = std::env::var_os("PATH") else {
+        return false;
+    };
+    std::env::split_paths(&paths).any(|dir| {
+        dir.join(program).is_file() || (cfg!(windows) && dir.join(format!("{}.exe", program)).is_file())
+    })
+}
+
+/// The first speech program for `os` that is installed
+pub fn resolve(os: OsType) -> Option<SpeechProgram> {
+    candidates(os).iter().copied().find(|candidate| on_path(candidate.program))
+}
+
+/// What to tell the user when `os` has no speech program installed
+pub fn missing_program_warning(os: OsType) -> String {
+    let tried: Vec<&str> = candidates(os).iter().map(|candidate| candidate.program).collect();
+    if tried.is_empty() {
+        return "Replies can't be spoken: no text-to-speech program is known for this OS.".to_string();
+    }
+    format!("Replies can't be spoken: no text-to-speech program found (looked for {}).", tried.join(", "))
+}
+
+/// Fenced code blocks, including one left open at the end of the reply
+static CODE_BLOCK: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)```.*?(```|$)").expect("code block pattern is valid"));
+
+/// Markdown links, keeping their text
+static LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[([^\]]+)\]\([^)]*\)").expect("link pattern is valid"));
+
+static URL: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b(https?://|www\.)\S+").expect("URL pattern is valid"));
+
+/// Heading, quote and bullet markers at the start of a line
+static LINE_MARKUP: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)^[ \t]*(#+|>|[-*+] )[ \t]*").expect("line markup pattern is valid"));
+
+/// Emphasis and inline code markers
+static INLINE_MARKUP: Lazy<Regex> = Lazy::new(|| Regex::new(r"[`*~]").expect("inline markup pattern is valid"));
+
+/// # prepare_text
+///
+/// **Purpose:**
+/// Turns a finished reply into the text to speak.
+///
+/// **Parameters:**
+/// - `reply`: The reply as the model sent it (markdown)
+/// - `config`: Sentence and length limits
+///
+/// **Returns:**
+/// `Option<String>` - None if nothing speakable is left
+///
+/// **Details:**
+/// Code blocks and URLs are dropped, link text is kept, markdown markers are
+/// removed and whitespace collapsed. Then only the first `max_sentences`
+/// sentences are kept (0 keeps all), and the result is cut at the last whole
+/// word within `max_chars`.
+pub fn prepare_text(reply: &str, config: &TtsConfig) -> Option<String> {
+    let text = CODE_BLOCK.replace_all(reply, " ");
+    let text = LINK.replace_all(&text, "$1");
+    let text = URL.replace_all(&text, "");
+    let text = LINE_MARKUP.replace_all(&text, "");
+    let text = INLINE_MARKUP.replace_all(&text, "");
+    let mut text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let sentences_end = config.max_sentences.checked_sub(1).and_then(|n| sentence_ends(&text).nth(n));
+    if let Some(end) = sentences_end {
+        text.truncate(end);
+    }
+
+    if text.chars().count() > config.max_chars {
+        let cut = text.char_indices().nth(config.max_chars).map(|(i, _)| i).unwrap_or(text.len());
+        // Keep whole words; a single overlong word is cut where it stands
+        let end = if text[cut..].starts_with(' ') {
+            cut
+        } else {
+            text[..cut].rfind(' ').filter(|&space| space > 0).unwrap_or(cut)
+        };
+        text.truncate(end);
+    }
+
+    let text = text.trim_end();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Byte offsets just past each sentence-ending `.`, `!` or `?` (followed by a space or the end)
+fn sentence_ends(text: &str) -> impl Iterator<Item = usize> + '_ {
+    let mut chars = text.char_indices().peekable();
+    std::iter::from_fn(move || {
+        while let Some((i, c)) = chars.next() {
+            let ends = matches!(c, '.' | '!' | '?');
+            let next = chars.peek().map(|(_, next)| *next);
+            if ends && next.is_none_or(|next| next == ' ') {
+                return Some(i + c.len_utf8());
+            }
+        }
+        None
+    })
+}
+
+/// # Speaker
+///
+/// **Summary:**
+/// The app's one voice: a queue of texts spoken in order by a background task.
+///
+/// **Fields:**
+/// - `os`: Platform the program was picked for
+/// - `program`: Speech program in use; None if none is installed
+/// - `queue`: Texts waiting to be spoken
+/// - `pending`: Texts queued or being spoken
+/// - `task`: The task speaking them
+///
+/// **Details:**
+/// Each text is spoken to the end before the next starts. `stop` (and
+/// dropping the speaker, e.g. on quit) aborts the task, which kills the
+/// program mid-sentence and discards the queue.
+#[derive(Debug)]
+pub struct Speaker {
+    os: OsType,
+    program: Option<SpeechProgram>,
+    queue: mpsc::UnboundedSender<String>,
+    pending: Arc<AtomicUsize>,
+    task: task::JoinHandle<()>,
+}
+
+impl Speaker {
+    /// Picks the speech program for `os` and starts the speaking task
+    pub fn new(os: OsType) -> Self {
+        let program = resolve(os);
+        match program {
+            Some(program) => log_info!("Speaking replies with {}", program.program),
+            None => log_info!("No text-to-speech program found for {:?}", os),
+        }
+        let (queue, pending, task) = Self::start(program);
+        Self { os, program, queue, pending, task }
+    }
+
+    fn start(program: Option<SpeechProgram>) -> (mpsc::UnboundedSender<String>, Arc<AtomicUsize>, task::JoinHandle<()>) {
+        let (queue, mut rx) = mpsc::unbounded_channel::<String>();
+        let pending = Arc::new(AtomicUsize::new(0));
+        let counter = pending.clone();
+        let task = tokio::spawn(async move {
+            while let Some(text) = rx.recv().await {
+                if let Some(program) = program
+                    && let Err(e) = speak_with(program, &text).await
+                {
+                    log_error!("{} failed: {}", program.program, e);
+                }
+                counter.fetch_sub(1, Ordering::Relaxed);
+            }
+        });
+        (queue, pending, task)
+    }
+
+    /// The warning to show once, if there's nothing to speak with
+    pub fn missing_program_warning(&self) -> Option<String> {
+        self.program.is_none().then(|| missing_program_warning(self.os))
+    }
+
+    /// # speak
+    ///
+    /// **Purpose:**
+    /// Queues a finished reply to be spoken after anything already queued.
+    ///
+    /// **Details:**
+    /// Does nothing without a speech program, or if `prepare_text` leaves nothing to say.
+    pub fn speak(&self, reply: &str) {
+        if self.program.is_none() {
+            return;
+        }
+        let Some(text) = prepare_text(reply, &GLOBAL_CONFIG.tts) else {
+            return;
+        };
+        self.pending.fetch_add(1, Ordering::Relaxed);
+        if self.queue.send(text).is_err() {
+            self.pending.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// # stop
+    ///
+    /// **Purpose:**
+    /// Cuts off the reply being spoken and drops the queued ones.
+    ///
+    /// **Returns:**
+    /// `bool` - true if anything was being spoken or queued
+    pub fn stop(&mut self) -> bool {
+        let speaking = self.pending.load(Ordering::Relaxed) > 0;
+        self.task.abort();
+        (self.queue, self.pending, self.task) = Self::start(self.program);
+        speaking
+    }
+}
+
+impl Drop for Speaker {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Speaks `text` with `program` and waits for it to finish; the program is killed if this is dropped
+async fn speak_with(program: SpeechProgram, text: &str) -> std::io::Result<()> {
+    let mut child = tokio::process::Command::new(program.program)
+        .args(program.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes()).await?;
+    }
+    let status = child.wait().await?;
+    if !status.success() {
+        log_error!("{} exited with {}", program.program, status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(unix)]
+    use std::time::Duration;
+    #[cfg(unix)]
+    use crate::test_support::ScratchDir;
+
+    fn limits(max_chars: usize, max_sentences: usize) -> TtsConfig {
+        TtsConfig { speak_by_default: false, max_chars, max_sentences }
+    }
+
+    #[test]
+    fn code_urls_and_markup_are_not_spoken() {
+        let cases = [
+            ("Here's the fix:\n```rust\nfn main() {}\n```\nRun it.", Some("Here's the fix: Run it.")),
+            ("See [the docs](https://docs.rs/x) or https://example.com/a?b=c for more.", Some("See the docs or for more.")),
+            ("www.example.com has it.", Some("has it.")),
+            ("# Plan\n- **Buy** milk\n> quoted `code`\n* ~~old~~ item", Some("Plan Buy milk quoted code old item")),
+            ("Try this:\n```\nls -la", Some("Try this:")),
+            ("```\nls -la\n```", None),
+            ("https://example.com", None),
+            ("  \n ", None),
+        ];
+        for (reply, expected) in cases {
+            assert_eq!(prepare_text(reply, &TtsConfig::default()).as_deref(), expected, "{:?}", reply);
+        }
+    }
+
+    #[test]
+    fn only_the_first_sentences_are_kept() {
+        let cases = [
+            ("One. Two! Three? Four.", 2, "One. Two!"),
+            ("One. Two! Three? Four.", 0, "One. Two! Three? Four."),
+            ("One. Two", 5, "One. Two"),
+            ("Pi is 3.14 today. Yes.", 1, "Pi is 3.14 today."),
+            ("Done!\n\nNext up?", 1, "Done!"),
+        ];
+        for (reply, max_sentences, expected) in cases {
+            assert_eq!(prepare_text(reply, &limits(600, max_sentences)).as_deref(), Some(expected), "{:?} to {}", reply, max_sentences);
+        }
+    }
+
+    #[test]
+    fn long_text_is_cut_at_a_word_boundary() {
+        let cases = [
+            ("The quick brown fox", 9, "The quick"),
+            ("The quick brown fox", 8, "The"),
+            ("The quick brown fox", 19, "The quick brown fox"),
+            ("Supercalifragilistic", 5, "Super"),
+            ("héllo wörld", 7, "héllo"),
+            ("héllo", 5, "héllo"),
+            ("One. Two three four.", 12, "One. Two"),
+        ];
+        for (reply, max_chars, expected) in cases {
+            assert_eq!(prepare_text(reply, &limits(max_chars, 0)).as_deref(), Some(expected), "{:?} to {}", reply, max_chars);
+        }
+        assert_eq!(prepare_text("A long one. Short.", &limits(8, 1)).as_deref(), Some("A long"), "both limits apply");
+    }
+
+    #[test]
+    fn each_os_has_its_programs_and_a_warning_naming_them() {
+        assert_eq!(candidates(OsType::MacOs)[0].program, "say");
+        assert_eq!(candidates(OsType::Windows)[0].program, "powershell");
+        let linux: Vec<&str> = candidates(OsType::Linux).iter().map(|candidate| candidate.program).collect();
+        assert_eq!(linux, ["spd-say", "espeak"]);
+        assert!(candidates(OsType::Other).is_empty());
+        assert_eq!(resolve(OsType::Other), None);
+
+        assert_eq!(
+            missing_program_warning(OsType::Linux),
+            "Replies can't be spoken: no text-to-speech program found (looked for spd-say, espeak)."
+        );
+        assert_eq!(
+            missing_program_warning(OsType::Other),
+            "Replies can't be spoken: no text-to-speech program is known for this OS."
+        );
+    }
+
+    #[tokio::test]
+    async fn without_a_program_nothing_is_queued() {
+        let mut speaker = Speaker::new(OsType::Other);
+        assert!(speaker.missing_program_warning().is_some());
+        speaker.speak("Hello there.");
+        assert_eq!(speaker.pending.load(Ordering::Relaxed), 0);
+        assert!(!speaker.stop());
+    }
+
+    /// A speaker whose "program" is `sh -c script`, with `$LOG` set to `log`
+    #[cfg(unix)]
+    fn speaker_running(script: &str, log: &std::path::Path) -> Speaker {
+        let script: &'static str = Box::leak(format!("LOG='{}'; {}", log.display(), script).into_boxed_str());
+        let args: &'static [&'static str] = Box::leak(vec!["-c", script].into_boxed_slice());
+        let program = SpeechProgram { program: "sh", args };
+        let (queue, pending, task) = Speaker::start(Some(program));
+        Speaker { os: OsType::Linux, program: Some(program), queue, pending, task }
+    }
+
+    /// Waits until `done` holds, for up to five seconds
+    #[cfg(unix)]
+    async fn wait_until(mut done: impl FnMut() -> bool) {
+        let waited = tokio::time::timeout(Duration::from_secs(5), async {
+            while !done() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        });
+        waited.await.expect("timed out waiting on the speaker");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn replies_are_spoken_one_after_another_in_order() {
+        let scratch = ScratchDir::new("speech-order");
+        let log = scratch.join("spoken.log");
+        let speaker = speaker_running(r#"cat >> "$LOG"; echo >> "$LOG""#, &log);
+
+        for reply in ["First **reply**.", "```\nskipped\n```", "Second reply.", "Third reply."] {
+            speaker.speak(reply);
+        }
+        wait_until(|| speaker.pending.load(Ordering::Relaxed) == 0).await;
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "First reply.\nSecond reply.\nThird reply.\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stopping_cuts_off_the_current_reply_and_drops_the_queue() {
+        let scratch = ScratchDir::new("speech-stop");
+        let log = scratch.join("spoken.log");
+        let mut speaker = speaker_running(r#"cat >> "$LOG"; echo >> "$LOG"; sleep 5"#, &log);
+
+        for reply in ["First reply.", "Second reply.", "Third reply."] {
+            speaker.speak(reply);
+        }
+        wait_until(|| std::fs::read_to_string(&log).is_ok_and(|spoken| spoken.ends_with('\n'))).await;
+        assert!(speaker.stop(), "something was being spoken");
+        assert_eq!(speaker.pending.load(Ordering::Relaxed), 0);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "First reply.\n", "the rest were dropped");
+        assert!(!speaker.stop(), "nothing left to stop");
+
+        // Speaking again afterwards works
+        speaker.speak("Fourth reply.");
+        wait_until(|| std::fs::read_to_string(&log).is_ok_and(|spoken| spoken.ends_with("Fourth reply.\n"))).await;
+    }
+}
@@ -0,0 +1,228 @@
+//! # Daegonica Module: utilities::stream_text
+//!
+//! **Purpose:** Make streamed reply text safe to show in a ratatui pane
+//!
+//! **Context:**
+//! - Some output carries `\r` progress updates and ANSI escape sequences
+//! - ratatui draws both literally, so progress bars pile up into garbled text
+//! - Applied to deltas as they're appended to the pane and to the full reply saved in history
+//!
+//! **Responsibilities:**
+//! - Treat `\r` as "replace the current line" (progress-bar semantics); `\r\n` stays a newline
+//! - Strip ANSI escape sequences (CSI, OSC, two-byte) and other control characters
+//! - Carry partial sequences across delta boundaries
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+const ESC: char = '\u{1b}';
+const BEL: char = '\u{7}';
+
+/// An unfinished escape sequence longer than this is dropped instead of carried
+const MAX_CARRY_BYTES: usize = 256;
+
+/// # StreamTextFilter
+///
+/// **Summary:**
+/// Applies streamed deltas to an in-progress text, one delta at a time.
+///
+/// **Fields:**
+/// - `carry`: Start of an escape sequence split across deltas, held for the next one
+/// - `pending_cr`: The last character seen was `\r`; the next text replaces the current line
+///
+/// **Details:**
+/// A `\r` only takes effect when text follows it, so `\r\n` split across two
+/// deltas is still a plain newline and a trailing `\r` leaves the line as it
+/// was. Each replacement truncates the line first, so thousands of updates
+/// leave only the final state behind.
+///
+/// **Usage Example:**
+/// ```rust
+/// # use grokprime_brain::utilities::stream_text::StreamTextFilter;
+/// let mut reply = String::new();
+/// let mut filter = StreamTextFilter::default();
+/// filter.push(&mut reply, "Downloading 10%\r");
+/// filter.push(&mut reply, "Downloading 20%\r");
+/// filter.finish();
+/// assert_eq!(reply, "Downloading 20%");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamTextFilter {
+    carry: String,
+    pending_cr: bool,
+}
+
+impl StreamTextFilter {
+    /// # push
+    ///
+    /// **Purpose:**
+    /// Applies one delta onto `target`, the text received so far.
+    pub fn push(&mut self, target: &mut String, delta: &str) {
+        let input = std::mem::take(&mut self.carry) + delta;
+        let mut i = 0;
+
+        while let Some(c) = input[i..].chars().next() {
+            match c {
+                ESC => match escape_len(&input[i..]) {
+                    Some(len) => {
+                        i += len;
+                        continue;
+                    }
+                    None => {
+                        if input.len() - i <= MAX_CARRY_BYTES {
+                            self.carry = input[i..].to_string();
+                        }
+                        return;
+                    }
+                },
+                '\r' => self.pending_cr = true,
+                '\n' => {
+                    self.pending_cr = false;
+                    target.push('\n');
+                }
+                '\t' => self.write(target, c),
+                c if c.is_control() => {}
+                c => self.write(target, c),
+            }
+            i += c.len_utf8();
+        }
+    }
+
+    /// Ends the reply: drops an unfinished escape sequence and any trailing `\r`
+    pub fn finish(&mut self) {
+        *self = Self::default();
+    }
+
+    fn write(&mut self, target: &mut String, c: char) {
+        if self.pending_cr {
+            self.pending_cr = false;
+            let line_start = target.rfind('\n').map_or(0, |newline| newline + 1);
+            target.truncate(line_start);
+        }
+        target.push(c);
+    }
+}
+
+/// Byte length of the escape sequence at the start of `text`, None if it isn't finished yet
+fn escape_len(text: &str) -> Option<usize> {
+    let mut chars = text.char_indices().skip(1);
+    let (_, kind) = chars.next()?;
+    match kind {
+        // CSI: parameter and intermediate bytes, then one final byte
+        '[' => {
+            for (index, c) in chars {
+                match c {
+                    '\u{30}'..='\u{3f}' | '\u{20}'..='\u{2f}' => {}
+                    '\u{40}'..='\u{7e}' => return Some(index + 1),
+                    // Malformed: drop the introducer, keep the rest as text
+                    _ => return Some(index),
+                }
+            }
+            None
+        }
+        // OSC: ends at BEL or ESC \
+        ']' => {
+            let mut previous_esc = false;
+            for (index, c) in chars {
+                if c == BEL || (previous_esc && c == '\\') {
+                    return Some(index + c.len_utf8());
+                }
+                previous_esc = c == ESC;
+            }
+            None
+        }
+        other => Some(ESC.len_utf8() + other.len_utf8()),
+    }
+}
+
+/// # normalize_stream_text
+///
+/// **Purpose:**
+/// Filters a complete reply the same way the pane filtered its deltas.
+pub fn normalize_stream_text(text: &str) -> String {
+    let mut filter = StreamTextFilter::default();
+    let mut normalized = String::with_capacity(text.len());
+    filter.push(&mut normalized, text);
+    filter.finish();
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs `deltas` through one filter as the pane would
+    fn streamed(deltas: &[&str]) -> String {
+        let mut filter = StreamTextFilter::default();
+        let mut text = String::new();
+        for delta in deltas {
+            filter.push(&mut text, delta);
+        }
+        filter.finish();
+        text
+    }
+
+    #[test]
+    fn progress_updates_keep_only_the_final_state_of_each_line() {
+        let cases: [(&str, &[&str], &str); 6] = [
+            ("one delta", &["Downloading 10%\rDownloading 55%\rDownloading 100%\nDone."], "Downloading 100%\nDone."),
+            ("one update per delta", &["[#   ] 25%", "\r[##  ] 50%", "\r[####] 100%", "\n"], "[####] 100%\n"),
+            ("earlier lines untouched", &["Fetching\n", "1/3\r2/3\r3/3"], "Fetching\n3/3"),
+            ("a shorter update", &["Estimating time left...\rdone"], "done"),
+            ("a trailing \\r", &["Saving 99%\r"], "Saving 99%"),
+            ("\\r\\n split across deltas", &["line one\r", "\nline two"], "line one\nline two"),
+        ];
+
+        for (case, deltas, expected) in cases {
+            assert_eq!(streamed(deltas), expected, "{}", case);
+        }
+    }
+
+    #[test]
+    fn thousands_of_updates_collapse_to_one_line() {
+        let transcript: String = (0..=5000).map(|i| format!("\rstep {}/5000", i)).collect();
+
+        assert_eq!(normalize_stream_text(&format!("Start\n{}\nEnd", transcript)), "Start\nstep 5000/5000\nEnd");
+    }
+
+    #[test]
+    fn escape_sequences_are_stripped() {
+        let cases: [(&str, &[&str], &str); 7] = [
+            ("SGR colors", &["\u{1b}[1;31mError:\u{1b}[0m disk full"], "Error: disk full"),
+            ("a color split across deltas", &["ok \u{1b}[3", "2mgreen\u{1b}", "[0m!"], "ok green!"),
+            ("cursor movement", &["a\u{1b}[2Kb\u{1b}[1Ac"], "abc"),
+            ("an OSC title ended by BEL", &["\u{1b}]0;build\u{7}compiled"], "compiled"),
+            ("an OSC hyperlink ended by ST", &["\u{1b}]8;;https://x.io\u{1b}\\link\u{1b}]8;;\u{1b}\\"], "link"),
+            ("a two-byte escape", &["\u{1b}7saved\u{1b}8"], "saved"),
+            ("an escape never finished", &["tail \u{1b}[38;5"], "tail "),
+        ];
+
+        for (case, deltas, expected) in cases {
+            assert_eq!(streamed(deltas), expected, "{}", case);
+        }
+    }
+
+    #[test]
+    fn mixed_content_keeps_text_tabs_and_newlines() {
+        let transcript = "Running tests\n\u{1b}[32m\u{2713}\u{1b}[0m\tparse\n\u{1b}[33m50%\u{1b}[0m\r\u{1b}[32m100%\u{1b}[0m\u{7}\u{8}\n¿Listo? sí\r\n";
+
+        assert_eq!(normalize_stream_text(transcript), "Running tests\n\u{2713}\tparse\n100%\n¿Listo? sí\n");
+    }
+
+    #[test]
+    fn an_overlong_partial_sequence_is_dropped_not_carried() {
+        let mut filter = StreamTextFilter::default();
+        let mut text = String::new();
+        filter.push(&mut text, &format!("before\u{1b}]{}", "x".repeat(MAX_CARRY_BYTES)));
+        filter.push(&mut text, " after");
+
+        assert_eq!(text, "before after");
+        filter.finish();
+        assert_eq!(filter, StreamTextFilter::default());
+    }
+}
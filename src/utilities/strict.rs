@@ -0,0 +1,57 @@
+//! # Daegonica Module: utilities::strict
+//!
+//! **Purpose:** Strict mode: a session where nothing can post, delete, or overwrite
+//!
+//! **Context:**
+//! - Enabled by `--strict` or `strict_mode` at startup, for demos or handing the keyboard to someone else
+//! - `from_input_action` checks it, so every path that runs a command (typed, recalled, macro) goes through it
+//! - Which actions it refuses comes from `InputAction::effect`, the same metadata macro replay uses
+//!
+//! **Responsibilities:**
+//! - Hold the process-wide switch, which can be turned on but never off
+//! - Word the refusal the same way for every refused command
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::models::ActionEffect;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// # enable
+///
+/// **Purpose:**
+/// Turns strict mode on for the rest of the process.
+///
+/// **Details:**
+/// There is deliberately no way to turn it off: leaving strict mode takes a
+/// restart without `--strict` and with `strict_mode` off.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// # refusal
+///
+/// **Purpose:**
+/// The message shown in place of running a command strict mode refuses.
+///
+/// **Parameters:**
+/// - `command`: The command as the user would name it
+/// - `effect`: Why it's refused
+pub fn refusal(command: &str, effect: ActionEffect) -> String {
+    format!(
+        "Strict mode: `{}` {} and is refused. Restart without --strict (and with strict_mode off) to allow it.",
+        command,
+        effect.describe()
+    )
+}
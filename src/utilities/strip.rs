@@ -0,0 +1,280 @@
+//! # Daegonica Module: utilities::strip
+//!
+//! **Purpose:** Strip signatures and boilerplate a model adds around its replies
+//!
+//! **Context:**
+//! - Applied by `Connection` to a finished reply before it is committed to history
+//! - Patterns come from the persona's `strip_patterns` plus, when enabled, `StripConfig::default_patterns`
+//! - The pane still shows the reply as streamed; history, exports, and tweets get the stripped text
+//!
+//! **Responsibilities:**
+//! - Validate patterns (no empty matches, nothing spanning lines)
+//! - Remove matching lines from the start and end of a reply, never from the middle
+//! - Report what was removed so it can be kept in the message metadata
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use crate::prelude::*;
+use crate::config::StripConfig;
+
+/// Inline flag groups turning on `s` (dot matches newline), e.g. `(?s)` or `(?is:...)`
+static DOT_ALL_FLAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"\(\?[a-zA-Z]*s[a-zA-Z]*[:)]").unwrap());
+
+/// # compile_pattern
+///
+/// **Purpose:**
+/// Compiles one strip pattern, rejecting ones that can't be meant line by line.
+///
+/// **Returns:**
+/// `Result<Regex, String>` - The compiled pattern, or why it was rejected
+///
+/// **Errors / Failures:**
+/// - Not a valid regex
+/// - Matches the empty string (it would match every line)
+/// - Mentions a newline or turns on the `s` flag; each pattern only ever sees one line
+pub fn compile_pattern(pattern: &str) -> Result<Regex, String> {
+    if pattern.contains(['\n', '\r']) || pattern.contains(r"\n") || pattern.contains(r"\r") || DOT_ALL_FLAG.is_match(pattern) {
+        return Err(format!("strip pattern \"{}\" spans lines; patterns are matched one line at a time", pattern));
+    }
+    let regex = Regex::new(pattern).map_err(|e| format!("strip pattern \"{}\" is not a valid regex: {}", pattern, e))?;
+    if regex.is_match("") {
+        return Err(format!("strip pattern \"{}\" matches an empty line", pattern));
+    }
+    Ok(regex)
+}
+
+/// # Stripped
+///
+/// **Summary:**
+/// A reply with its matching edge lines taken off.
+///
+/// **Fields:**
+/// - `text`: The reply without them
+/// - `removed`: The removed lines (trimmed), leading ones first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stripped {
+    pub text: String,
+    pub removed: Vec<String>,
+}
+
+/// # strip_edges
+///
+/// **Purpose:**
+/// Removes lines matching any pattern from the start and end of `text`.
+///
+/// **Returns:**
+/// `Option<Stripped>` - None when nothing matched
+///
+/// **Details:**
+/// Lines are trimmed before matching and blank lines between stripped ones
+/// are skipped. Stripping stops at the first non-blank line that doesn't
+/// match, so a matching line in the middle of the reply is always kept. A
+/// reply made up entirely of matching lines is left alone rather than emptied.
+pub fn strip_edges(text: &str, patterns: &[Regex]) -> Option<Stripped> {
+    if patterns.is_empty() {
+        return None;
+    }
+    let lines: Vec<&str> = text.lines().collect();
+    let matches = |line: &str| patterns.iter().any(|pattern| pattern.is_match(line.trim()));
+    let blank = |index: usize| lines[index].trim().is_empty();
+
+    let (mut start, mut end) = (0, lines.len());
+    let mut leading = Vec::new();
+    loop {
+        while start < end && blank(start) {
+            start += 1;
+        }
+        if start < end && matches(lines[start]) {
+            leading.push(lines[start].trim().to_string());
+            start += 1;
+        } else {
+            break;
+        }
+    }
+    let mut trailing = Vec::new();
+    loop {
+        while end > start && blank(end - 1) {
+            end -= 1;
+        }
+        if end > start && matches(lines[end - 1]) {
+            trailing.push(lines[end - 1].trim().to_string());
+            end -= 1;
+        } else {
+            break;
+        }
+    }
+
+    if start >= end || (leading.is_empty() && trailing.is_empty()) {
+        return None;
+    }
+    trailing.reverse();
+    leading.extend(trailing);
+    Some(Stripped { text: lines[start..end].join("\n"), removed: leading })
+}
+
+/// # StripRules
+///
+/// **Summary:**
+/// The compiled patterns that apply to one persona's replies.
+///
+/// **Fields:**
+/// - `patterns`: Persona patterns, then the defaults unless the persona is exempt
+/// - `warn_only`: Report matches without changing the reply
+#[derive(Debug, Clone, Default)]
+pub struct StripRules {
+    pub patterns: Vec<Regex>,
+    pub warn_only: bool,
+}
+
+impl StripRules {
+    /// # for_persona
+    ///
+    /// **Purpose:**
+    /// Collects the rules for `persona` under `config`.
+    ///
+    /// **Details:**
+    /// The persona's own `strip_patterns` always apply (they were validated
+    /// when the persona loaded). `default_patterns` are added only with
+    /// `use_defaults`, and never for personas in `exempt_personas`, whose
+    /// signatures are wanted. A default pattern that fails validation is logged and skipped.
+    pub fn for_persona(persona: &Persona, config: &StripConfig) -> Self {
+        let defaults = config.use_defaults && !config.exempt_personas.iter().any(|name| name == &persona.name);
+        let patterns = persona.strip_patterns.iter()
+            .chain(config.default_patterns.iter().filter(|_| defaults))
+            .filter_map(|pattern| match compile_pattern(pattern) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    log_error!("Persona {}: {}", persona.name, e);
+                    None
+                }
+            })
+            .collect();
+        Self { patterns, warn_only: config.warn_only }
+    }
+
+    pub fn apply(&self, text: &str) -> Option<Stripped> {
+        strip_edges(text, &self.patterns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(sources: &[&str]) -> Vec<Regex> {
+        sources.iter().map(|source| compile_pattern(source).unwrap()).collect()
+    }
+
+    fn defaults() -> Vec<Regex> {
+        StripConfig::default().default_patterns.iter().map(|source| compile_pattern(source).unwrap()).collect()
+    }
+
+    #[test]
+    fn patterns_that_cant_be_meant_line_by_line_are_rejected() {
+        let cases = [
+            ("^-Shadow$", None),
+            ("(?i)^as an ai\\b", None),
+            ("^(", Some("is not a valid regex")),
+            ("x*", Some("matches an empty line")),
+            ("^$", Some("matches an empty line")),
+            ("Thanks\\nBye", Some("spans lines")),
+            ("Thanks\nBye", Some("spans lines")),
+            ("(?s)Thanks.*Bye", Some("spans lines")),
+            ("(?is:thanks.*bye)", Some("spans lines")),
+        ];
+        for (pattern, rejected) in cases {
+            match (compile_pattern(pattern), rejected) {
+                (Ok(_), None) => {}
+                (Err(e), Some(reason)) => assert!(e.contains(reason) && e.contains(pattern), "{}: {}", pattern, e),
+                (result, _) => panic!("{}: {:?}", pattern, result.map(|regex| regex.to_string())),
+            }
+        }
+        assert_eq!(defaults().len(), StripConfig::default().default_patterns.len(), "every shipped default compiles");
+    }
+
+    #[test]
+    fn only_edge_lines_are_stripped() {
+        type Case<'a> = (&'a str, &'a str, Option<(&'a str, &'a [&'a str])>);
+        let cases: [Case; 7] = [
+            (
+                "leading boilerplate",
+                "As an AI language model, I don't have opinions.\nBut here's a summary:\n- one\n- two",
+                Some(("But here's a summary:\n- one\n- two", &["As an AI language model, I don't have opinions."])),
+            ),
+            (
+                "trailing signature and sign-off",
+                "Tuesday works.\n\nI hope this helps!\n\n-Shadow",
+                Some(("Tuesday works.", &["I hope this helps!", "-Shadow"])),
+            ),
+            (
+                "both edges, indented",
+                "  As an AI assistant, I can't browse.  \nThe docs say 3.\n  — Friday",
+                Some(("The docs say 3.", &["As an AI assistant, I can't browse.", "— Friday"])),
+            ),
+            ("a signature mid-text is kept", "Signed:\n-Shadow\nsent from the road", None),
+            ("a disclaimer mid-text is kept", "Fine.\nAs an AI, I can't say.\nAnyway, done.", None),
+            ("list items are not sign-offs", "Groceries:\n- Milk\n- Eggs", None),
+            ("a reply that is all boilerplate is left alone", "I hope this helps!\n-Shadow", None),
+        ];
+        let defaults = defaults();
+        for (name, reply, expected) in cases {
+            let stripped = strip_edges(reply, &defaults);
+            let expected = expected.map(|(text, removed)| Stripped {
+                text: text.to_string(),
+                removed: removed.iter().map(|line| line.to_string()).collect(),
+            });
+            assert_eq!(stripped, expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn patterns_interact_line_by_line() {
+        let reply = "Sources: internal wiki\nThe build is green.\nSources: CI\n-Shadow";
+        type Case<'a> = (&'a [&'a str], Option<(&'a str, &'a [&'a str])>);
+        let cases: [Case; 4] = [
+            (&[], None),
+            // The signature is the last line, so the trailing "Sources:" line is shielded by it
+            (&["^Sources:"], Some(("The build is green.\nSources: CI\n-Shadow", &["Sources: internal wiki"]))),
+            (&["^-Shadow$"], Some(("Sources: internal wiki\nThe build is green.\nSources: CI", &["-Shadow"]))),
+            // Any pattern may take the next edge line, so together they peel off both trailing lines
+            (&["^Sources:", "^-Shadow$"], Some(("The build is green.", &["Sources: internal wiki", "Sources: CI", "-Shadow"]))),
+        ];
+        for (sources, expected) in cases {
+            let expected = expected.map(|(text, removed)| Stripped {
+                text: text.to_string(),
+                removed: removed.iter().map(|line| line.to_string()).collect(),
+            });
+            assert_eq!(strip_edges(reply, &patterns(sources)), expected, "{:?}", sources);
+        }
+    }
+
+    #[test]
+    fn defaults_apply_only_when_enabled_and_never_to_exempt_personas() {
+        let mut shadow = Persona::from_yaml_str("name: shadow\nsystem_prompt: Talk.\n").unwrap();
+        shadow.strip_patterns = vec!["^Sources:".to_string()];
+        let viral = Persona::from_yaml_str("name: viral\nsystem_prompt: Post.\n").unwrap();
+        let on = StripConfig { use_defaults: true, ..StripConfig::default() };
+        let default_count = on.default_patterns.len();
+
+        let cases = [
+            ("own patterns only by default", &shadow, StripConfig::default(), 1),
+            ("own patterns plus defaults", &shadow, on.clone(), 1 + default_count),
+            ("an exempt persona keeps its signature", &viral, on.clone(), 0),
+        ];
+        for (name, persona, config, count) in cases {
+            assert_eq!(StripRules::for_persona(persona, &config).patterns.len(), count, "{}", name);
+        }
+        assert_eq!(StripRules::for_persona(&viral, &on).apply("Big news.\n-Shadow"), None);
+
+        let broken = StripConfig { default_patterns: vec!["(".to_string(), "^-\\w+$".to_string()], ..on };
+        assert_eq!(StripRules::for_persona(&viral, &StripConfig { exempt_personas: vec![], ..broken }).patterns.len(), 1, "a bad default is skipped");
+    }
+}
@@ -0,0 +1,536 @@
+//! # Daegonica Module: utilities::time
+//!
+//! **Purpose:** One place for user-facing time parsing and formatting
+//!
+//! **Context:**
+//! - Timestamps are stored as RFC3339 UTC; archive names as local `%Y-%m-%d_%H-%M-%S`
+//! - Users want to see and type local times, not UTC strings
+//!
+//! **Responsibilities:**
+//! - Resolve the display zone (local, or a per-persona override)
+//! - Parse lenient time arguments ("8.30am", "tomorrow 9am", "last monday", "2h ago")
+//! - Format timestamps as relative when recent, absolute local otherwise
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime,
+    NaiveTime, Offset, TimeZone, Utc, Weekday,
+};
+use std::time::SystemTime;
+use thiserror::Error;
+use crate::persona::Persona;
+use crate::log_error;
+
+/// Format of the timestamp embedded in archive file names
+pub const ARCHIVE_STAMP_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+const EXAMPLES: &str = "Try \"8:30\", \"8.30am\", \"tomorrow 9am\", \"last monday\" or \"2h ago\".";
+
+/// # TimeParseError
+///
+/// **Summary:**
+/// Why a time argument was rejected. The Display text is shown to the user as-is.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TimeParseError {
+    #[error("No time given. {}", EXAMPLES)]
+    Empty,
+
+    #[error("Couldn't understand \"{0}\". {}", EXAMPLES)]
+    Unrecognized(String),
+
+    #[error("\"{0}\" is not a valid time of day (use 0-23 hours, or 1-12 with am/pm, and 0-59 minutes).")]
+    InvalidClock(String),
+
+    #[error("\"{0}\" is ambiguous: write \"{0}:00\", \"{0}am\" or \"{0}pm\".")]
+    AmbiguousHour(String),
+
+    #[error("\"{0}\" is ambiguous: say \"last {0}\" or \"next {0}\".")]
+    AmbiguousWeekday(String),
+
+    #[error("Unknown timezone \"{0}\". Use \"local\", \"utc\" or an offset like \"+02:00\".")]
+    InvalidZone(String),
+
+    #[error("\"{0}\" is too far from now to be a date.")]
+    OutOfRange(String),
+}
+
+/// # Zone
+///
+/// **Summary:**
+/// Time zone used to read and display times.
+///
+/// **Variants:**
+/// - `Local`: The machine's local zone
+/// - `Fixed(FixedOffset)`: An explicit offset (persona `timezone: "+02:00"` or `"utc"`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Zone {
+    #[default]
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl Zone {
+    /// # parse
+    ///
+    /// **Purpose:**
+    /// Reads a zone setting: `local`, `utc`/`z`, or `±HH:MM` / `±HHMM` / `±HH`.
+    pub fn parse(text: &str) -> Result<Self, TimeParseError> {
+        let trimmed = text.trim().to_ascii_lowercase();
+        match trimmed.as_str() {
+            "local" | "" => return Ok(Zone::Local),
+            "utc" | "z" | "gmt" => return Ok(Zone::Fixed(Utc.fix())),
+            _ => {}
+        }
+
+        let invalid = || TimeParseError::InvalidZone(text.trim().to_string());
+        let (sign, rest) = if let Some(rest) = trimmed.strip_prefix('+') {
+            (1, rest)
+        } else if let Some(rest) = trimmed.strip_prefix('-') {
+            (-1, rest)
+        } else {
+            return Err(invalid());
+        };
+        let digits: String = rest.chars().filter(|c| *c != ':').collect();
+        if !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let (hours, minutes) = match digits.len() {
+            1 | 2 => (digits.parse::<i32>().map_err(|_| invalid())?, 0),
+            4 => (
+                digits[..2].parse::<i32>().map_err(|_| invalid())?,
+                digits[2..].parse::<i32>().map_err(|_| invalid())?,
+            ),
+            _ => return Err(invalid()),
+        };
+        if hours > 14 || minutes > 59 {
+            return Err(invalid());
+        }
+
+        FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+            .map(Zone::Fixed)
+            .ok_or_else(invalid)
+    }
+
+    /// # for_persona
+    ///
+    /// **Purpose:**
+    /// The persona's `timezone` override, or local time when unset or invalid.
+    pub fn for_persona(persona: &Persona) -> Self {
+        match persona.timezone.as_deref().map(Zone::parse) {
+            Some(Ok(zone)) => zone,
+            Some(Err(e)) => {
+                log_error!("Persona {}: {}", persona.name, e);
+                Zone::Local
+            }
+            None => Zone::Local,
+        }
+    }
+
+    /// Offset in effect at the given instant
+    pub fn offset_at(&self, at: DateTime<Utc>) -> FixedOffset {
+        match self {
+            Zone::Local => Local.offset_from_utc_datetime(&at.naive_utc()).fix(),
+            Zone::Fixed(offset) => *offset,
+        }
+    }
+
+    /// Converts an instant into this zone
+    pub fn to_zoned(&self, at: DateTime<Utc>) -> DateTime<FixedOffset> {
+        at.with_timezone(&self.offset_at(at))
+    }
+
+    /// Interprets a wall-clock time in this zone
+    pub fn from_naive(&self, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            Zone::Local => Local.from_local_datetime(&naive).earliest().map(|t| t.with_timezone(&Utc)),
+            Zone::Fixed(offset) => offset.from_local_datetime(&naive).single().map(|t| t.with_timezone(&Utc)),
+        }
+    }
+}
+
+/// # parse_time_arg
+///
+/// **Purpose:**
+/// Parses a user-typed time relative to now, in the given zone.
+///
+/// **Returns:**
+/// `Result<DateTime<Utc>, TimeParseError>` - The instant meant, or a message for the user
+///
+/// **Details:**
+/// See `parse_time_arg_at` for the accepted forms.
+pub fn parse_time_arg(input: &str, zone: Zone) -> Result<DateTime<Utc>, TimeParseError> {
+    parse_time_arg_at(input, zone.to_zoned(Utc::now()))
+}
+
+/// # parse_time_arg_at
+///
+/// **Purpose:**
+/// Parses a user-typed time relative to an explicit `now` (the zone is `now`'s offset).
+///
+/// **Accepted forms:**
+/// - `now`, RFC3339, `2026-01-20`, `2026-01-20 08:30`
+/// - Clock times: `8:30`, `8.30`, `8.30am`, `8 pm`, `20:15`, `noon`, `midnight` (today)
+/// - Days: `today`, `tomorrow`, `yesterday`, `last monday`, `next fri`, each optionally
+///   followed by a clock time (start of day otherwise)
+/// - Offsets: `2h ago`, `30 min ago`, `3d ago`, `in 2h`, `in 1 week`
+///
+/// **Errors / Failures:**
+/// - A bare hour ("9") or bare weekday ("monday") is ambiguous and rejected
+/// - An offset that lands outside the representable dates is out of range
+/// - Anything else unrecognized, with examples of what works
+pub fn parse_time_arg_at(input: &str, now: DateTime<FixedOffset>) -> Result<DateTime<Utc>, TimeParseError> {
+    let text = input.trim().to_ascii_lowercase();
+    if text.is_empty() {
+        return Err(TimeParseError::Empty);
+    }
+
+    if text == "now" {
+        return Ok(now.with_timezone(&Utc));
+    }
+    if let Ok(exact) = DateTime::parse_from_rfc3339(input.trim()) {
+        return Ok(exact.with_timezone(&Utc));
+    }
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let today = now.date_naive();
+
+    // Offsets: "2h ago", "2 h ago", "in 2h", "in 2 hours"
+    if let Some(duration) = parse_offset(&words, input)? {
+        return now
+            .checked_add_signed(duration)
+            .map(|t| t.with_timezone(&Utc))
+            .ok_or_else(|| TimeParseError::OutOfRange(input.trim().to_string()));
+    }
+
+    // Explicit dates: "2026-01-20" or "2026-01-20 08:30"
+    if let Ok(date) = NaiveDate::parse_from_str(words[0], "%Y-%m-%d") {
+        let time = clock_or_midnight(&words[1..])?;
+        return at_local(now, date.and_time(time), input);
+    }
+
+    // Day words, optionally followed by a clock time
+    let (date, rest) = match words.as_slice() {
+        ["today", rest @ ..] => (today, rest),
+        ["tomorrow", rest @ ..] => (today + Duration::days(1), rest),
+        ["yesterday", rest @ ..] => (today - Duration::days(1), rest),
+        ["last", day, rest @ ..] => match parse_weekday(day) {
+            Some(weekday) => (previous_weekday(today, weekday), rest),
+            None => return Err(TimeParseError::Unrecognized(input.trim().to_string())),
+        },
+        ["next", day, rest @ ..] => match parse_weekday(day) {
+            Some(weekday) => (following_weekday(today, weekday), rest),
+            None => return Err(TimeParseError::Unrecognized(input.trim().to_string())),
+        },
+        [day, ..] if parse_weekday(day).is_some() => {
+            return Err(TimeParseError::AmbiguousWeekday(day.to_string()));
+        }
+        _ => {
+            // A clock time alone means today
+            let time = parse_clock(&words.concat())?
+                .ok_or_else(|| TimeParseError::Unrecognized(input.trim().to_string()))?;
+            return at_local(now, today.and_time(time), input);
+        }
+    };
+
+    let time = clock_or_midnight(rest)?;
+    at_local(now, date.and_time(time), input)
+}
+
+fn at_local(now: DateTime<FixedOffset>, naive: NaiveDateTime, input: &str) -> Result<DateTime<Utc>, TimeParseError> {
+    now.offset()
+        .from_local_datetime(&naive)
+        .single()
+        .map(|t| t.with_timezone(&Utc))
+        .ok_or_else(|| TimeParseError::Unrecognized(input.trim().to_string()))
+}
+
+/// Parses the remaining words as a clock time ("at" optional), or midnight if none
+fn clock_or_midnight(words: &[&str]) -> Result<NaiveTime, TimeParseError> {
+    let words = match words {
+        ["at", rest @ ..] => rest,
+        rest => rest,
+    };
+    if words.is_empty() {
+        return Ok(NaiveTime::MIN);
+    }
+    let joined = words.concat();
+    parse_clock(&joined)?.ok_or(TimeParseError::Unrecognized(words.join(" ")))
+}
+
+/// # parse_clock
+///
+/// **Purpose:**
+/// Parses a time of day with spaces already removed ("8:30", "8.30am", "20:15", "noon").
+///
+/// **Returns:**
+/// `Ok(None)` if this doesn't look like a time at all, `Err` if it does but is invalid or ambiguous.
+fn parse_clock(text: &str) -> Result<Option<NaiveTime>, TimeParseError> {
+    match text {
+        "noon" | "midday" => return Ok(NaiveTime::from_hms_opt(12, 0, 0)),
+        "midnight" => return Ok(Some(NaiveTime::MIN)),
+        _ => {}
+    }
+
+    let (body, meridiem) = if let Some(body) = text.strip_suffix("am").or_else(|| text.strip_suffix("a.m.")) {
+        (body, Some(false))
+    } else if let Some(body) = text.strip_suffix("pm").or_else(|| text.strip_suffix("p.m.")) {
+        (body, Some(true))
+    } else {
+        (text, None)
+    };
+
+    let (hour_text, minute_text) = match body.split_once([':', '.']) {
+        Some((hour, minute)) => (hour, Some(minute)),
+        None => (body, None),
+    };
+
+    let is_number = |s: &str| !s.is_empty() && s.len() <= 2 && s.chars().all(|c| c.is_ascii_digit());
+    if !is_number(hour_text) || minute_text.is_some_and(|m| m.len() != 2 || !is_number(m)) {
+        return Ok(None);
+    }
+
+    let invalid = || TimeParseError::InvalidClock(text.to_string());
+    let hour: u32 = hour_text.parse().map_err(|_| invalid())?;
+    let minute: u32 = minute_text.map(str::parse).transpose().map_err(|_| invalid())?.unwrap_or(0);
+
+    let hour = match meridiem {
+        Some(pm) => {
+            if !(1..=12).contains(&hour) {
+                return Err(invalid());
+            }
+            match (hour, pm) {
+                (12, false) => 0,
+                (12, true) => 12,
+                (h, true) => h + 12,
+                (h, false) => h,
+            }
+        }
+        None if minute_text.is_none() => return Err(TimeParseError::AmbiguousHour(hour_text.to_string())),
+        None => hour,
+    };
+
+    NaiveTime::from_hms_opt(hour, minute, 0).map(Some).ok_or_else(invalid)
+}
+
+/// # parse_offset
+///
+/// **Purpose:**
+/// Parses "2h ago", "2 hours ago", "in 30m" into a signed duration.
+///
+/// **Returns:**
+/// `Ok(None)` if this isn't an offset, `Err(OutOfRange)` if it is but no duration is that long.
+fn parse_offset(words: &[&str], input: &str) -> Result<Option<Duration>, TimeParseError> {
+    let (amount_words, sign) = match words {
+        ["in", rest @ ..] => (rest, 1),
+        [rest @ .., "ago"] => (rest, -1),
+        _ => return Ok(None),
+    };
+
+    let joined = amount_words.concat();
+    let Some(split) = joined.find(|c: char| !c.is_ascii_digit()) else {
+        return Ok(None);
+    };
+    let (number, unit) = joined.split_at(split);
+    if number.is_empty() {
+        return Ok(None);
+    }
+
+    let out_of_range = || TimeParseError::OutOfRange(input.trim().to_string());
+    let to_duration: fn(i64) -> Option<Duration> = match unit {
+        "m" | "min" | "mins" | "minute" | "minutes" => Duration::try_minutes,
+        "h" | "hr" | "hrs" | "hour" | "hours" => Duration::try_hours,
+        "d" | "day" | "days" => Duration::try_days,
+        "w" | "wk" | "week" | "weeks" => Duration::try_weeks,
+        _ => return Ok(None),
+    };
+    // All digits, so the only way to fail is being too long for an i64
+    let amount: i64 = number.parse().map_err(|_| out_of_range())?;
+    let duration = to_duration(amount).ok_or_else(out_of_range)?;
+    Ok(Some(duration * sign))
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    let weekday = match word {
+        "mon" | "monday" => Weekday::Mon,
+        "tue" | "tues" | "tuesday" => Weekday::Tue,
+        "wed" | "wednesday" => Weekday::Wed,
+        "thu" | "thur" | "thurs" | "thursday" => Weekday::Thu,
+        "fri" | "friday" => Weekday::Fri,
+        "sat" | "saturday" => Weekday::Sat,
+        "sun" | "sunday" => Weekday::Sun,
+        _ => return None,
+    };
+    Some(weekday)
+}
+
+/// Most recent `weekday` strictly before `today` (1-7 days back)
+fn previous_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let back = (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+    today - Duration::days(if back == 0 { 7 } else { back as i64 })
+}
+
+/// Next `weekday` strictly after `today` (1-7 days ahead)
+fn following_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let ahead = (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+    today + Duration::days(if ahead == 0 { 7 } else { ahead as i64 })
+}
+
+/// # format_relative
+///
+/// **Purpose:**
+/// Compact relative time: "just now", "5m ago", "3h ago", "2d ago", "in 10m".
+pub fn format_relative(then: DateTime<Utc>) -> String {
+    let seconds = (Utc::now() - then).num_seconds();
+    let (amount, unit) = match seconds.abs() {
+        s if s < 60 => return "just now".to_string(),
+        s if s < 3600 => (s / 60, "m"),
+        s if s < 86_400 => (s / 3600, "h"),
+        s => (s / 86_400, "d"),
+    };
+
+    if seconds < 0 {
+        format!("in {}{}", amount, unit)
+    } else {
+        format!("{}{} ago", amount, unit)
+    }
+}
+
+/// # format_timestamp
+///
+/// **Purpose:**
+/// Formats an instant for display: relative within a day, absolute in `zone` otherwise.
+///
+/// **Examples:**
+/// "5m ago", "3h ago", "Mon 19 Jan 14:05", "2025-12-01 09:30"
+pub fn format_timestamp(then: DateTime<Utc>, zone: Zone) -> String {
+    if (Utc::now() - then).num_hours().abs() < 24 {
+        return format_relative(then);
+    }
+
+    let local = zone.to_zoned(then);
+    if local.year() == zone.to_zoned(Utc::now()).year() {
+        local.format("%a %-d %b %H:%M").to_string()
+    } else {
+        local.format("%Y-%m-%d %H:%M").to_string()
+    }
+}
+
+/// Wall-clock time of an instant in `zone`, as `HH:MM:SS`
+pub fn format_clock(at: SystemTime, zone: Zone) -> String {
+    zone.to_zoned(DateTime::<Utc>::from(at)).format("%H:%M:%S").to_string()
+}
+
+/// # format_rfc3339
+///
+/// **Purpose:**
+/// Friendly form of a stored RFC3339 string; unparseable input is returned unchanged.
+pub fn format_rfc3339(stored: &str, zone: Zone) -> String {
+    DateTime::parse_from_rfc3339(stored)
+        .map(|t| format_timestamp(t.with_timezone(&Utc), zone))
+        .unwrap_or_else(|_| stored.to_string())
+}
+
+/// # parse_archive_stamp
+///
+/// **Purpose:**
+/// Reads the local timestamp embedded in an archive file name.
+pub fn parse_archive_stamp(stamp: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(stamp, ARCHIVE_STAMP_FORMAT)
+        .ok()
+        .and_then(|naive| Zone::Local.from_naive(naive))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wednesday 2026-01-21 10:00 at UTC+1
+    fn now() -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(3600).unwrap().with_ymd_and_hms(2026, 1, 21, 10, 0, 0).unwrap()
+    }
+
+    fn utc(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn accepted_forms_resolve_against_now_in_its_zone() {
+        let cases = [
+            ("now", "2026-01-21T09:00:00Z"),
+            ("2025-12-01T09:30:00Z", "2025-12-01T09:30:00Z"),
+            ("2026-01-20", "2026-01-19T23:00:00Z"),
+            ("2026-01-20 08:30", "2026-01-20T07:30:00Z"),
+            ("8:30", "2026-01-21T07:30:00Z"),
+            ("8.30am", "2026-01-21T07:30:00Z"),
+            ("8 pm", "2026-01-21T19:00:00Z"),
+            ("12am", "2026-01-20T23:00:00Z"),
+            ("20:15", "2026-01-21T19:15:00Z"),
+            ("noon", "2026-01-21T11:00:00Z"),
+            ("tomorrow 9am", "2026-01-22T08:00:00Z"),
+            ("yesterday", "2026-01-19T23:00:00Z"),
+            ("last monday", "2026-01-18T23:00:00Z"),
+            ("last wednesday", "2026-01-13T23:00:00Z"),
+            ("next fri at 8:30", "2026-01-23T07:30:00Z"),
+            ("2h ago", "2026-01-21T07:00:00Z"),
+            ("30 min ago", "2026-01-21T08:30:00Z"),
+            ("in 1 week", "2026-01-28T09:00:00Z"),
+            ("  In 3D  ", "2026-01-24T09:00:00Z"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_time_arg_at(input, now()), Ok(utc(expected)), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn ambiguous_and_invalid_input_says_why() {
+        let cases = [
+            ("", "No time given. Try \"8:30\", \"8.30am\", \"tomorrow 9am\", \"last monday\" or \"2h ago\"."),
+            ("9", "\"9\" is ambiguous: write \"9:00\", \"9am\" or \"9pm\"."),
+            ("tomorrow 7", "\"7\" is ambiguous: write \"7:00\", \"7am\" or \"7pm\"."),
+            ("monday", "\"monday\" is ambiguous: say \"last monday\" or \"next monday\"."),
+            ("25:00", "\"25:00\" is not a valid time of day (use 0-23 hours, or 1-12 with am/pm, and 0-59 minutes)."),
+            ("8:61", "\"8:61\" is not a valid time of day (use 0-23 hours, or 1-12 with am/pm, and 0-59 minutes)."),
+            ("13pm", "\"13pm\" is not a valid time of day (use 0-23 hours, or 1-12 with am/pm, and 0-59 minutes)."),
+            ("soonish", "Couldn't understand \"soonish\". Try \"8:30\", \"8.30am\", \"tomorrow 9am\", \"last monday\" or \"2h ago\"."),
+            ("last funday", "Couldn't understand \"last funday\". Try \"8:30\", \"8.30am\", \"tomorrow 9am\", \"last monday\" or \"2h ago\"."),
+            ("tomorrow at teatime", "Couldn't understand \"teatime\". Try \"8:30\", \"8.30am\", \"tomorrow 9am\", \"last monday\" or \"2h ago\"."),
+            ("in 2 fortnights", "Couldn't understand \"in 2 fortnights\". Try \"8:30\", \"8.30am\", \"tomorrow 9am\", \"last monday\" or \"2h ago\"."),
+            ("in 99999999999999 min", "\"in 99999999999999 min\" is too far from now to be a date."),
+            ("999999999999 weeks ago", "\"999999999999 weeks ago\" is too far from now to be a date."),
+            ("99999999999999999999h ago", "\"99999999999999999999h ago\" is too far from now to be a date."),
+        ];
+        for (input, expected) in cases {
+            let err = parse_time_arg_at(input, now()).expect_err(input);
+            assert_eq!(err.to_string(), expected, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn zones_parse_or_are_rejected_without_panicking() {
+        let east = |seconds| Ok(Zone::Fixed(FixedOffset::east_opt(seconds).unwrap()));
+        let cases = [
+            ("local", Ok(Zone::Local)),
+            ("", Ok(Zone::Local)),
+            ("UTC", east(0)),
+            ("+02:00", east(2 * 3600)),
+            ("-0530", east(-(5 * 3600 + 30 * 60))),
+            ("+5", east(5 * 3600)),
+            ("+15:00", Err(TimeParseError::InvalidZone("+15:00".into()))),
+            ("+02:75", Err(TimeParseError::InvalidZone("+02:75".into()))),
+            ("Europe/Paris", Err(TimeParseError::InvalidZone("Europe/Paris".into()))),
+            // Unicode minus and other multibyte first characters
+            ("−05:00", Err(TimeParseError::InvalidZone("−05:00".into()))),
+            ("é", Err(TimeParseError::InvalidZone("é".into()))),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(Zone::parse(input), expected, "{:?}", input);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! The TUI in accessible mode: roles spelled out in text, and a screen that
+//! only redraws differently when there is something new to announce.
+//!
+//! Accessible mode is process-wide, so it lives in its own test binary where
+//! every test runs with it on.
+
+mod support;
+
+use std::time::Duration;
+use grokprime_brain::llm::scripted::ScriptedReply;
+use grokprime_brain::utilities::accessibility;
+use support::tui::{buffer_text, TuiHarness};
+
+fn accessible_harness() -> TuiHarness {
+    accessibility::set_enabled(true);
+    let mut h = TuiHarness::new();
+    h.submit("new shadow");
+    h
+}
+
+/// The last frame exactly as drawn, clock and all
+fn frame(h: &TuiHarness) -> String {
+    buffer_text(h.buffer())
+}
+
+#[tokio::test]
+async fn roles_are_labeled_in_text() {
+    let mut h = accessible_harness();
+    h.client.push(ScriptedReply::text("Noon, give or take."));
+    h.submit("what time is it?");
+    h.settle().await;
+
+    assert!(h.shows("You: what time is it?"), "{}", h.screen());
+    assert!(h.shows("Shadow: Noon, give or take."), "{}", h.screen());
+    assert!(h.shows("Shadow: Created new agent"), "Global pane entries are labeled too: {}", h.screen());
+    assert!(!h.shows("[Shadow]"), "no color-only bracket prefixes");
+
+    h.client.push(ScriptedReply::error("503 Service Unavailable"));
+    h.submit("again?");
+    h.settle().await;
+    assert!(h.shows("Error: 503 Service Unavailable"), "{}", h.screen());
+}
+
+#[tokio::test]
+async fn a_waiting_frame_only_changes_when_a_whole_line_arrives() {
+    let mut h = accessible_harness();
+    let (reply, gate) = ScriptedReply::chunks(["First line", " ends here.\nSecond", " line."]).held_after(2);
+    h.client.push(reply);
+    h.submit("hello there");
+    h.tick_until("the first whole line", |app| {
+        app.agent_manager.current_pane().is_some_and(|agent| agent.messages.iter().any(|msg| msg.text.contains("ends here.")))
+    }).await;
+
+    let waiting = frame(&h);
+    assert!(waiting.contains("Shadow: First line ends here."), "{}", waiting);
+    assert!(waiting.contains("Waiting for reply from"), "{}", waiting);
+    assert!(!waiting.contains("Second"), "the partial line is held back");
+
+    // No spinner, dots or timers: idle ticks redraw the same frame
+    for _ in 0..30 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        h.tick();
+        assert_eq!(frame(&h), waiting, "the frame changed with nothing new to say");
+    }
+
+    gate.release();
+    h.settle().await;
+    let done = frame(&h);
+    assert_ne!(done, waiting);
+    assert!(done.contains("Second line."), "{}", done);
+    assert!(!done.contains("Waiting for reply"), "{}", done);
+
+    for _ in 0..5 {
+        h.tick();
+        assert_eq!(frame(&h), done, "a finished reply doesn't redraw differently");
+    }
+}
@@ -0,0 +1,263 @@
+//! An agent's lifecycle (create, send, complete, fail, cancel, close) run through the
+//! same commands against both front ends: the CLI's `AgentManager` and the TUI's `ShadowApp`.
+//!
+//! Both keep their agents as `AgentInfo` inside an `AgentManager`, so these
+//! are the same checks twice; a difference between the two runs is a
+//! "works in CLI, broken in TUI" bug.
+
+mod support;
+
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+use grokprime_brain::commands::CommandResult;
+use grokprime_brain::llm::AnyClient;
+use grokprime_brain::llm::scripted::{ScriptedClient, ScriptedReply};
+use grokprime_brain::persona::agent::AgentInfo;
+use grokprime_brain::persona::agent_manager::AgentManager;
+use grokprime_brain::persona::operations::AgentOperations;
+use grokprime_brain::tui::app::ShadowApp;
+use support::*;
+
+/// A front end the lifecycle runs against
+trait Host: AgentOperations {
+    fn manager(&self) -> &AgentManager;
+
+    /// One turn of the front end's loop: drain the agents' chunks
+    fn poll(&mut self);
+
+    /// Whether the front end has presentation state for `id` exactly when the agent is open
+    fn in_sync(&self, id: Uuid) -> bool;
+}
+
+impl Host for AgentManager {
+    fn manager(&self) -> &AgentManager {
+        self
+    }
+
+    fn poll(&mut self) {
+        self.poll_channels();
+    }
+
+    fn in_sync(&self, _id: Uuid) -> bool {
+        true
+    }
+}
+
+impl Host for ShadowApp {
+    fn manager(&self) -> &AgentManager {
+        &self.agent_manager
+    }
+
+    fn poll(&mut self) {
+        self.poll_channels();
+    }
+
+    fn in_sync(&self, id: Uuid) -> bool {
+        self.agent_panes.contains_key(&id) == self.agent_manager.agents.contains_key(&id)
+    }
+}
+
+fn cli(client: &ScriptedClient) -> AgentManager {
+    manager_with(client)
+}
+
+fn tui(client: &ScriptedClient) -> ShadowApp {
+    let mut app = ShadowApp::new();
+    app.agent_manager.client_override = Some(AnyClient::Scripted(client.clone()));
+    app
+}
+
+/// Opens an agent on a copy of the built-in shadow persona stored as `name`
+fn open(host: &mut impl Host, name: &str) -> Uuid {
+    let id = Uuid::new_v4();
+    host.add_new_agent(id, Arc::new(builtin_as("shadow", name)));
+    id
+}
+
+fn agent(host: &impl Host, id: Uuid) -> &AgentInfo {
+    host.manager().agents.get(&id).expect("agent is open")
+}
+
+fn lines(host: &impl Host, id: Uuid) -> Vec<String> {
+    agent(host, id).messages.iter().map(|msg| msg.text.clone()).collect()
+}
+
+/// Polls until `id` isn't waiting on a reply
+async fn settle(host: &mut impl Host, id: Uuid) {
+    let waited = tokio::time::timeout(REPLY_TIMEOUT, async {
+        while agent(host, id).is_waiting {
+            host.poll();
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    });
+    waited.await.expect("the reply never finished");
+}
+
+/// Lets spawned reply tasks run without polling their chunks
+async fn yield_to_tasks() {
+    tokio::time::sleep(Duration::from_millis(20)).await;
+}
+
+async fn create_send_complete(host: &mut impl Host, client: &ScriptedClient, name: &str) {
+    client.push(ScriptedReply::chunks(["Hello ", "from ", "the agent."]));
+
+    let id = open(host, name);
+    assert_eq!(host.get_current_agent_id(), Some(id));
+    assert!(host.in_sync(id));
+    assert!(!agent(host, id).is_waiting);
+
+    assert!(matches!(run_line(host, "hello there"), CommandResult::Continue));
+    assert!(agent(host, id).is_waiting);
+    assert!(agent(host, id).active_task.is_some());
+    settle(host, id).await;
+
+    let agent = agent(host, id);
+    assert!(agent.active_task.is_none());
+    assert!(agent.reply_stream.is_none());
+    assert_eq!(lines(host, id), ["> hello there", "Hello from the agent."]);
+    let conn = agent.connection.try_lock().expect("connection is free once the reply is done");
+    let history: Vec<(&str, &str)> = conn.local_history().iter()
+        .map(|msg| (msg.role.as_str(), msg.content.as_str()))
+        .filter(|(role, _)| *role != "system")
+        .collect();
+    assert_eq!(history, [("user", "hello there"), ("assistant", "Hello from the agent.")]);
+    assert_eq!(client.requests().len(), 1);
+}
+
+async fn fail_then_retry(host: &mut impl Host, client: &ScriptedClient, name: &str) {
+    client.push(ScriptedReply::error("503 Service Unavailable"));
+    client.push(ScriptedReply::text("Back again."));
+    let id = open(host, name);
+
+    run_line(host, "are you there?");
+    settle(host, id).await;
+    let failed = agent(host, id);
+    assert_eq!(failed.failures_in_a_row, 1);
+    assert_eq!(failed.last_failure.as_ref().map(|failure| failure.error.as_str()), Some("503 Service Unavailable"));
+    assert!(lines(host, id).contains(&"Error: 503 Service Unavailable".to_string()));
+
+    run_line(host, "are you there?");
+    settle(host, id).await;
+    let recovered = agent(host, id);
+    assert_eq!(recovered.failures_in_a_row, 0);
+    assert!(recovered.last_failure.is_none());
+    assert_eq!(lines(host, id).last().map(String::as_str), Some("Back again."));
+}
+
+async fn cancel_then_send(host: &mut impl Host, client: &ScriptedClient, name: &str) {
+    let (held, gate) = ScriptedReply::text("This reply never shows.").held();
+    client.push(held);
+    client.push(ScriptedReply::text("Second answer."));
+    let id = open(host, name);
+
+    run_line(host, "first question");
+    yield_to_tasks().await;
+    assert!(agent(host, id).is_waiting);
+
+    assert!(matches!(run_line(host, "stop"), CommandResult::Continue));
+    let cancelled = agent(host, id);
+    assert!(!cancelled.is_waiting);
+    assert!(cancelled.active_task.is_none());
+    assert_eq!(lines(host, id).last().map(String::as_str), Some("[response cancelled]"));
+
+    // The aborted request can't stream anything now, even once its reply is let go
+    gate.release();
+    yield_to_tasks().await;
+    host.poll();
+    assert_eq!(lines(host, id).last().map(String::as_str), Some("[response cancelled]"));
+
+    // Nothing left to stop
+    run_line(host, "stop");
+    assert!(!agent(host, id).is_waiting);
+
+    run_line(host, "second question");
+    settle(host, id).await;
+    let text = lines(host, id);
+    assert_eq!(text[text.len() - 2..], ["> second question", "Second answer."]);
+    assert!(!text.iter().any(|line| line.contains("never shows")));
+}
+
+async fn close_mid_reply(host: &mut impl Host, client: &ScriptedClient, first: &str, second: &str) {
+    let (held, gate) = ScriptedReply::text("Too late.").held();
+    client.push(held);
+    let kept = open(host, first);
+    let closed = open(host, second);
+    assert_eq!(host.get_agent_order(), &vec![kept, closed]);
+
+    run_line(host, "a long question");
+    yield_to_tasks().await;
+    assert!(matches!(run_line(host, "close"), CommandResult::Continue));
+
+    assert!(!host.manager().agents.contains_key(&closed));
+    assert!(host.in_sync(closed));
+    assert_eq!(host.get_agent_order(), &vec![kept]);
+    assert_eq!(host.get_current_agent_id(), Some(kept));
+
+    // The closed agent's reply goes nowhere
+    gate.release();
+    yield_to_tasks().await;
+    host.poll();
+    assert!(lines(host, kept).is_empty());
+
+    run_line(host, "close");
+    assert_eq!(host.get_current_agent_id(), None);
+    assert!(host.in_sync(kept));
+    assert!(matches!(run_line(host, "close"), CommandResult::Continue), "closing with nothing open is only a message");
+}
+
+#[tokio::test]
+async fn cli_create_send_complete() {
+    test_root();
+    let client = ScriptedClient::default();
+    create_send_complete(&mut cli(&client), &client, "life-cli-send").await;
+}
+
+#[tokio::test]
+async fn tui_create_send_complete() {
+    test_root();
+    let client = ScriptedClient::default();
+    create_send_complete(&mut tui(&client), &client, "life-tui-send").await;
+}
+
+#[tokio::test]
+async fn cli_fail_then_retry() {
+    test_root();
+    let client = ScriptedClient::default();
+    fail_then_retry(&mut cli(&client), &client, "life-cli-fail").await;
+}
+
+#[tokio::test]
+async fn tui_fail_then_retry() {
+    test_root();
+    let client = ScriptedClient::default();
+    fail_then_retry(&mut tui(&client), &client, "life-tui-fail").await;
+}
+
+#[tokio::test]
+async fn cli_cancel_then_send() {
+    test_root();
+    let client = ScriptedClient::default();
+    cancel_then_send(&mut cli(&client), &client, "life-cli-cancel").await;
+}
+
+#[tokio::test]
+async fn tui_cancel_then_send() {
+    test_root();
+    let client = ScriptedClient::default();
+    cancel_then_send(&mut tui(&client), &client, "life-tui-cancel").await;
+}
+
+#[tokio::test]
+async fn cli_close_mid_reply() {
+    test_root();
+    let client = ScriptedClient::default();
+    close_mid_reply(&mut cli(&client), &client, "life-cli-kept", "life-cli-closed").await;
+}
+
+#[tokio::test]
+async fn tui_close_mid_reply() {
+    test_root();
+    let client = ScriptedClient::default();
+    close_mid_reply(&mut tui(&client), &client, "life-tui-kept", "life-tui-closed").await;
+}
@@ -0,0 +1,226 @@
+//! End-to-end checks of the built-in `reviewer` and `historian` personas against a scripted model:
+//! the prompts they're sent, and how their replies are read back.
+
+mod support;
+
+use std::sync::Arc;
+use grokprime_brain::agent_history::conversations::SUMMARY_PREFIX;
+use grokprime_brain::agent_history::summary_format::{SummaryFormatError, SummarySections};
+use grokprime_brain::commands::CommandResult;
+use grokprime_brain::llm::AnyClient;
+use grokprime_brain::llm::client::{Connection, SummaryCheck};
+use grokprime_brain::llm::scripted::{ScriptedClient, ScriptedReply};
+use grokprime_brain::persona::builtin::load_builtin;
+use grokprime_brain::persona::review::{Review, ReviewFormatError, Severity, Verdict};
+use support::*;
+
+const LIB_RS: &str = "pub fn first(items: &[u32]) -> u32 {\n    items[0]\n}\n";
+
+const WELL_FORMED_REVIEW: &str = "\
+Findings:
+1. [blocker] src/lib.rs:2 - indexing panics on an empty slice
+   Why: `items[0]` panics when `items` is empty
+   Fix: return Option<u32> and use `first()`
+```rust
+// file: src/lib.rs
+pub fn first(items: &[u32]) -> Option<u32> {
+    items.first().copied()
+}
+```
+2. [nit] first - name doesn't say what happens on empty input
+   Why: callers have to read the body
+   Fix: document the empty case
+
+Verdict: request changes
+";
+
+#[tokio::test]
+async fn attach_sends_the_file_to_the_reviewer_and_reads_its_findings() {
+    test_root();
+    let path = write_file("attach/src/lib.rs", LIB_RS);
+    let client = ScriptedClient::default();
+    client.push(ScriptedReply::chunks(WELL_FORMED_REVIEW.split_inclusive('\n')));
+    let mut manager = manager_with(&client);
+    open_agent(&mut manager, builtin_as("reviewer", "reviewer-attach"));
+
+    let result = run_line(&mut manager, &format!("attach {} check the indexing", path.display()));
+    assert!(matches!(result, CommandResult::Continue));
+    wait_for_reply(&mut manager).await;
+
+    let requests = client.requests();
+    assert_eq!(requests.len(), 1);
+    let system = system_prompt(&requests[0]);
+    assert!(system.starts_with("You are Reviewer"), "{}", system);
+    for scaffolding in ["Findings:", "[severity] location - one-line title", "// file: <path>", "Verdict: approve | approve with nits | request changes"] {
+        assert!(system.contains(scaffolding), "system prompt lacks {:?}", scaffolding);
+    }
+    let sent = last_user_message(&requests[0]);
+    assert!(sent.starts_with("check the indexing\n\n```rust\n"), "{}", sent);
+    assert!(sent.contains(&format!("// file: {}\n{}", path.display(), LIB_RS.trim_end())), "{}", sent);
+    assert!(sent.ends_with("\n```"));
+    assert_eq!(requests[0].temperature, 0.2);
+
+    let lines = pane_lines(&manager);
+    assert!(lines.iter().any(|line| line.starts_with(&format!("> Attached {} (3 lines): check the indexing", path.display()))), "{:?}", lines);
+    assert_eq!(lines.last().map(String::as_str), Some("Review: 2 findings (1 blocker, 1 nit); verdict: request changes"));
+}
+
+#[tokio::test]
+async fn a_review_that_breaks_the_format_is_reported_not_misread() {
+    test_root();
+    let path = write_file("malformed/main.rs", "fn main() {}\n");
+    let client = ScriptedClient::default();
+    client.push(ScriptedReply::text("Looks fine to me!\n\nFindings:\n1. [critical] main - empty main\n"));
+    let mut manager = manager_with(&client);
+    open_agent(&mut manager, builtin_as("reviewer", "reviewer-malformed"));
+
+    run_line(&mut manager, &format!("attach {}", path.display()));
+    wait_for_reply(&mut manager).await;
+
+    assert!(last_user_message(&client.requests()[0]).starts_with("Please review this file."));
+    assert_eq!(
+        pane_lines(&manager).last().map(String::as_str),
+        Some("Review: couldn't read the findings (finding 1 has unknown severity 'critical' (expected blocker, major, minor or nit))")
+    );
+}
+
+#[test]
+fn attach_refuses_missing_files_and_needs_an_agent() {
+    test_root();
+    let client = ScriptedClient::default();
+    let mut manager = manager_with(&client);
+    assert!(matches!(run_line(&mut manager, "attach Cargo.toml"), CommandResult::Continue));
+    assert!(client.requests().is_empty(), "sent without an agent");
+
+    open_agent(&mut manager, builtin_as("reviewer", "reviewer-missing"));
+    let missing = test_root().join("files/nowhere.rs");
+    match run_line(&mut manager, &format!("attach {}", missing.display())) {
+        CommandResult::Error(msg) => assert!(msg.starts_with("Can't attach"), "{}", msg),
+        other => panic!("expected an error, got {:?}", other),
+    }
+    assert!(client.requests().is_empty());
+}
+
+#[test]
+fn findings_parse_with_their_replacement_blocks() {
+    let review = Review::parse(WELL_FORMED_REVIEW).expect("well-formed review");
+    assert_eq!(review.verdict, Verdict::RequestChanges);
+    assert_eq!(review.findings.len(), 2);
+
+    let blocker = &review.findings[0];
+    assert_eq!(blocker.severity, Severity::Blocker);
+    assert_eq!(blocker.location, "src/lib.rs:2");
+    assert_eq!(blocker.title, "indexing panics on an empty slice");
+    assert_eq!(blocker.fix.as_deref(), Some("return Option<u32> and use `first()`"));
+    let replacement = blocker.replacement.as_ref().expect("replacement block");
+    assert_eq!(replacement.path, "src/lib.rs");
+    assert_eq!(replacement.code, "pub fn first(items: &[u32]) -> Option<u32> {\n    items.first().copied()\n}");
+
+    assert_eq!(review.findings[1].severity, Severity::Nit);
+    assert!(review.findings[1].replacement.is_none());
+
+    let clean = Review::parse("Nothing to flag.\n\nFindings: none\n\nVerdict: approve").expect("empty review");
+    assert!(clean.findings.is_empty());
+    assert_eq!(clean.describe(), "no findings; verdict: approve");
+}
+
+#[test]
+fn malformed_findings_say_what_is_wrong() {
+    let cases = [
+        ("The code looks fine.", ReviewFormatError::NoFindingsSection),
+        ("Findings:\nsome prose instead\nVerdict: approve", ReviewFormatError::EmptyFindings),
+        ("Findings:\n1. [blocker] a.rs:1 - x\n```rust\n// file: a.rs\nfn a() {}\n", ReviewFormatError::UnclosedBlock),
+        ("Findings:\n1. [minor] a.rs:1 - x\n", ReviewFormatError::NoVerdict),
+        ("Findings: none\nVerdict: ship it", ReviewFormatError::UnknownVerdict("ship it".into())),
+    ];
+    for (reply, expected) in cases {
+        assert_eq!(Review::parse(reply), Err(expected), "{:?}", reply);
+    }
+}
+
+const WELL_FORMED_SUMMARY: &str = "\
+Topics: Review of src/lib.rs indexing.
+Decisions: Return Option from first(); blocker on empty slices stays open until fixed.
+Context: first() in src/lib.rs, items[0].
+Status: Waiting on the fix.";
+
+/// A reviewer connection with enough back-and-forth to summarize
+fn reviewer_connection(client: &ScriptedClient, storage_name: &str) -> Connection<AnyClient> {
+    let persona = Arc::new(builtin_as("reviewer", storage_name));
+    let mut conn = Connection::new_without_output(AnyClient::Scripted(client.clone()), persona);
+    for round in 1..=5 {
+        conn.add_user_message(&format!("Round {}: please look at first() again", round));
+        conn.conversation.add_assistant_message(format!("Findings:\n1. [blocker] src/lib.rs:2 - round {} still indexes\nVerdict: request changes", round));
+    }
+    conn
+}
+
+#[tokio::test]
+async fn summarize_sends_the_historian_prompt_and_accepts_its_sections() {
+    test_root();
+    let client = ScriptedClient::default();
+    client.push(ScriptedReply::chunks(WELL_FORMED_SUMMARY.split_inclusive('\n')));
+    let mut conn = reviewer_connection(&client, "reviewer-summary");
+
+    let outcome = conn.summarize_history().await.expect("summarized").expect("enough history");
+    assert_eq!(outcome.check, SummaryCheck::Passed);
+    assert_eq!(outcome.format_issue, None);
+    assert!(!outcome.describe().contains("format"));
+
+    let requests = client.requests();
+    assert_eq!(requests.len(), 1);
+    let historian = load_builtin("historian").expect("built-in historian");
+    assert_eq!(system_prompt(&requests[0]), historian.system_prompt);
+    assert!(historian.system_prompt.contains("Topics:") && historian.system_prompt.contains("Status:"));
+    assert!(requests[0].stream, "summaries are always streamed");
+    let prompt = last_user_message(&requests[0]);
+    assert!(prompt.starts_with("Summarize this conversation:\n\nUSER: Round 1: please look at first() again"), "{}", prompt);
+    assert!(prompt.contains("ASSISTANT: Findings:"));
+    // The reviewer's summary settings: bullet style and the required term
+    assert!(prompt.contains("Format the summary as concise bullet points."), "{}", prompt);
+    assert!(prompt.contains("The summary must mention: blocker."), "{}", prompt);
+
+    let summary = &conn.local_history()[1];
+    assert!(summary.content.starts_with(SUMMARY_PREFIX));
+    assert!(summary.content.contains("Status: Waiting on the fix."));
+}
+
+#[tokio::test]
+async fn a_summary_off_schema_is_kept_but_flagged() {
+    test_root();
+    let client = ScriptedClient::default();
+    client.push(ScriptedReply::text("Here is the summary: they talked about first()."));
+    client.push(ScriptedReply::text("Topics: first().\nDecisions: none\nContext: src/lib.rs"));
+    let mut conn = reviewer_connection(&client, "reviewer-off-schema");
+
+    let outcome = conn.summarize_history().await.expect("summarized").expect("enough history");
+    // The first reply left out "blocker", so the historian was asked once to revise
+    let requests = client.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(last_user_message(&requests[1]).starts_with("Your summary leaves out: blocker."));
+    assert_eq!(outcome.check, SummaryCheck::Failed(vec!["blocker".to_string()]));
+    assert_eq!(outcome.format_issue.as_deref(), Some("no Status section"));
+    assert!(outcome.describe().ends_with("; not in the historian's format (no Status section)"));
+    assert!(conn.local_history()[1].content.contains("Context: src/lib.rs"));
+}
+
+#[test]
+fn summary_sections_parse_and_reject() {
+    let sections = SummarySections::parse(WELL_FORMED_SUMMARY).expect("well-formed summary");
+    assert_eq!(sections.topics, "Review of src/lib.rs indexing.");
+    assert_eq!(sections.status, "Waiting on the fix.");
+
+    let bullets = "- Topics:\n  - attach flow\n- Decisions: none\n- Context:\n  - src/lib.rs\n- Status: done";
+    assert_eq!(SummarySections::parse(bullets).expect("bullet style").context, "- src/lib.rs");
+
+    let cases = [
+        ("Here is the summary:\nTopics: a\nDecisions: b\nContext: c\nStatus: d", SummaryFormatError::Preamble("Here is the summary:".into())),
+        ("Topics: a\nDecisions: b\nStatus: d", SummaryFormatError::Missing("Context")),
+        ("Topics: a\nTopics: again\nDecisions: b\nContext: c\nStatus: d", SummaryFormatError::Repeated("Topics")),
+        ("Decisions: b\nTopics: a\nContext: c\nStatus: d", SummaryFormatError::OutOfOrder("Topics")),
+        ("Topics: a\nDecisions:\nContext: c\nStatus: d", SummaryFormatError::Empty("Decisions")),
+    ];
+    for (summary, expected) in cases {
+        assert_eq!(SummarySections::parse(summary), Err(expected), "{:?}", summary);
+    }
+}
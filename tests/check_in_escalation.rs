@@ -0,0 +1,113 @@
+//! Follow-ups to an unanswered check-in, driven through the TUI: sent when
+//! due, cancelled by any message to the persona, and resumed after a restart.
+//!
+//! The pending check-in is saved for the next session, so these live in their
+//! own test binary and take turns with it. The clock isn't mocked here;
+//! instead each test stores a schedule that is already due (the timing itself
+//! is tested in `persona::escalation`).
+
+mod support;
+
+use std::sync::{Mutex, MutexGuard};
+use chrono::{Duration, Utc};
+use grokprime_brain::config::GLOBAL_CONFIG;
+use grokprime_brain::llm::scripted::ScriptedReply;
+use grokprime_brain::persona::escalation::{save_escalation, saved_escalation, PendingEscalation};
+use grokprime_brain::utilities::events::{EventKind, EVENT_BUS};
+use support::{last_user_message, test_root};
+use support::tui::TuiHarness;
+
+static ESCALATION_TURN: Mutex<()> = Mutex::new(());
+
+/// Shadow's check-in, sent `minutes` ago with its first follow-up due a minute ago
+fn overdue(minutes: i64) -> PendingEscalation {
+    let now = Utc::now();
+    PendingEscalation {
+        persona: "shadow".to_string(),
+        checked_in_at: now - Duration::minutes(minutes),
+        escalations_sent: 0,
+        next_at: now - Duration::minutes(1),
+    }
+}
+
+/// A harness with the saved escalation to itself until the guard drops
+fn harness() -> (TuiHarness, MutexGuard<'static, ()>) {
+    let turn = ESCALATION_TURN.lock().unwrap_or_else(|e| e.into_inner());
+    test_root();
+    save_escalation(None);
+    (TuiHarness::new(), turn)
+}
+
+/// The latest Global pane line
+fn last_global(h: &TuiHarness) -> String {
+    h.app.unified_messages.iter().last().map(|msg| msg.text.clone()).unwrap_or_default()
+}
+
+// Each turn spans the whole scenario; nothing else runs on the test's runtime
+#[allow(clippy::await_holding_lock)]
+#[tokio::test]
+async fn a_due_follow_up_is_sent_and_an_answer_cancels_the_rest() {
+    let (mut h, _turn) = harness();
+    let policy = &GLOBAL_CONFIG.tui.check_in_escalation;
+    h.app.check_in_escalation = Some(overdue(31));
+    h.client.push(ScriptedReply::text("Well? What got done?"));
+    let events = EVENT_BUS.subscribe();
+
+    h.tick();
+    h.settle().await;
+    assert_eq!(last_global(&h), "No reply to shadow's check-in yet; following up (1/2).");
+    let follow_ups: Vec<_> = events.try_iter()
+        .filter(|event| matches!(event.kind, EventKind::CheckInFollowUp { .. }))
+        .map(|event| (event.persona, event.agent_id, event.kind))
+        .collect();
+    assert_eq!(follow_ups, [(Some("shadow".to_string()), None, EventKind::CheckInFollowUp { attempt: 1, max_attempts: 2 })]);
+    assert_eq!(last_user_message(&h.client.requests()[0]), policy.prompt);
+    assert!(h.shows("Well? What got done?"), "{}", h.screen());
+
+    let pending = h.app.check_in_escalation.clone().expect("one follow-up left");
+    assert_eq!(pending.escalations_sent, 1);
+    let wait = pending.next_at - Utc::now();
+    assert!(wait > Duration::minutes(59) && wait <= Duration::minutes(60), "twice the first wait: {}", wait);
+    assert_eq!(saved_escalation(), Some(pending), "saved for a restart");
+
+    h.client.push(ScriptedReply::text("Good."));
+    h.submit("Sorry, finished the report.");
+    assert_eq!(h.app.check_in_escalation, None);
+    assert_eq!(saved_escalation(), None);
+    h.settle().await;
+    assert_eq!(h.client.requests().len(), 2, "no more follow-ups");
+}
+
+#[allow(clippy::await_holding_lock)]
+#[tokio::test]
+async fn a_message_to_another_persona_is_not_an_answer() {
+    let (mut h, _turn) = harness();
+    let pending = PendingEscalation { next_at: Utc::now() + Duration::minutes(20), ..overdue(10) };
+    h.app.check_in_escalation = Some(pending.clone());
+
+    h.submit("new friday");
+    h.client.push(ScriptedReply::text("Hi."));
+    h.submit("hello friday");
+    h.settle().await;
+    assert_eq!(h.app.check_in_escalation, Some(pending));
+}
+
+#[allow(clippy::await_holding_lock)]
+#[tokio::test]
+async fn a_restart_resumes_the_saved_follow_ups_once() {
+    let (h, _turn) = harness();
+    save_escalation(Some(&overdue(200)));
+
+    let mut restarted = h.restart(None);
+    restarted.app.resume_focus();
+    restarted.client.push(ScriptedReply::text("You went quiet."));
+    restarted.tick();
+    restarted.settle().await;
+    for _ in 0..5 {
+        restarted.tick();
+    }
+
+    assert_eq!(restarted.client.requests().len(), 1, "the missed follow-up is sent once");
+    assert_eq!(last_global(&restarted), "No reply to shadow's check-in yet; following up (1/2).");
+    assert_eq!(restarted.app.check_in_escalation.as_ref().map(|pending| pending.escalations_sent), Some(1));
+}
@@ -0,0 +1,171 @@
+//! Checkpoints taken before each command that rewrites history, and
+//! `rollback` restoring one into memory and the saved history, undoably.
+
+mod support;
+
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+use grokprime_brain::agent_history::checkpoint::{list_checkpoints, load_checkpoint, CheckpointReason};
+use grokprime_brain::agent_history::history::HistoryManager;
+use grokprime_brain::commands::CommandResult;
+use grokprime_brain::llm::AnyClient;
+use grokprime_brain::llm::scripted::{ScriptedClient, ScriptedReply};
+use grokprime_brain::models::Message;
+use grokprime_brain::persona::operations::AgentOperations;
+use grokprime_brain::tui::app::ShadowApp;
+use support::*;
+
+/// Includes a message sent twice in a row, for `history dedupe`
+const EXCHANGES: [(&str, &str); 5] = [
+    ("user", "My cat is called Rex."),
+    ("user", "My cat is called Rex."),
+    ("assistant", "Rex is a fine name."),
+    ("user", "What should I feed him?"),
+    ("assistant", "Cat food."),
+];
+
+/// An app on `client` with a history-keeping shadow stored as `storage_name`,
+/// holding `EXCHANGES` in memory and on disk
+fn app_with_history(client: &ScriptedClient, storage_name: &str, history_message_limit: usize) -> (ShadowApp, Uuid) {
+    test_root();
+    let mut app = ShadowApp::new();
+    app.agent_manager.client_override = Some(AnyClient::Scripted(client.clone()));
+    let mut persona = builtin_as("shadow", storage_name);
+    persona.enable_history = true;
+    persona.history_message_limit = history_message_limit;
+    let id = Uuid::new_v4();
+    app.add_new_agent(id, Arc::new(persona));
+    {
+        let mut conn = app.agent_manager.agents[&id].connection.try_lock().expect("connection is idle");
+        conn.conversation.local_history.extend(EXCHANGES.iter().map(|(role, content)| Message {
+            role: role.to_string(),
+            content: content.to_string(),
+            meta: None,
+            source: None,
+        }));
+        conn.conversation.set_last_response_id("resp_1".to_string());
+        conn.save_persona_history().expect("history saved");
+    }
+    (app, id)
+}
+
+/// Messages in memory after the prompt
+fn history(app: &ShadowApp, id: Uuid) -> Vec<(String, String)> {
+    let conn = app.agent_manager.agents[&id].connection.try_lock().expect("connection is idle");
+    conn.local_history().iter().skip(1).map(|msg| (msg.role.clone(), msg.content.clone())).collect()
+}
+
+fn response_id(app: &ShadowApp, id: Uuid) -> Option<String> {
+    let conn = app.agent_manager.agents[&id].connection.try_lock().expect("connection is idle");
+    conn.conversation.get_last_response_id().cloned()
+}
+
+/// Messages in the saved history, if there is one
+fn saved(storage_name: &str) -> Option<Vec<(String, String)>> {
+    let history = HistoryManager::load_persona_history(storage_name).ok()?;
+    Some(history.recent_messages.into_iter().map(|msg| (msg.role, msg.content)).collect())
+}
+
+fn exchanges() -> Vec<(String, String)> {
+    EXCHANGES.iter().map(|(role, content)| (role.to_string(), content.to_string())).collect()
+}
+
+/// Messages after the prompt in the newest checkpoint, and its reason
+fn newest_checkpoint(storage_name: &str) -> (CheckpointReason, Vec<(String, String)>) {
+    let newest = list_checkpoints(storage_name).expect("checkpoints listed").into_iter().next().expect("a checkpoint");
+    let checkpoint = load_checkpoint(&newest.path).expect("checkpoint loads");
+    (newest.reason, checkpoint.messages.into_iter().skip(1).map(|msg| (msg.role, msg.content)).collect())
+}
+
+/// The last `count` lines shown in the Global pane
+fn last_lines(app: &ShadowApp, count: usize) -> Vec<String> {
+    let lines: Vec<String> = app.unified_messages.iter().map(|msg| msg.text.clone()).collect();
+    lines[lines.len().saturating_sub(count)..].to_vec()
+}
+
+#[test]
+fn each_rewriting_command_checkpoints_first() {
+    let cases = [
+        ("history dedupe confirm", CheckpointReason::Dedupe),
+        ("history remove 4 confirm", CheckpointReason::HistoryRemove),
+        ("clearhistory", CheckpointReason::Clear),
+        ("resync fresh", CheckpointReason::FreshStart),
+    ];
+    for (n, (line, reason)) in cases.into_iter().enumerate() {
+        let storage_name = format!("checkpoint-command-{}", n);
+        let (mut app, id) = app_with_history(&ScriptedClient::default(), &storage_name, 10);
+        app.agent_manager.agents.get_mut(&id).unwrap().resync_offer = Some(chrono::Duration::days(16));
+        let before = (history(&app, id), saved(&storage_name));
+
+        // Previews and dry runs take none
+        for preview in ["history dedupe", "history remove 4", "rollback"] {
+            run_line(&mut app, preview);
+        }
+        assert!(list_checkpoints(&storage_name).unwrap().is_empty(), "{}", line);
+
+        assert_eq!(run_line(&mut app, line), CommandResult::Continue, "{}", line);
+        assert_ne!((history(&app, id), saved(&storage_name)), before, "{} changed the history in memory or on disk", line);
+        assert_eq!(newest_checkpoint(&storage_name), (reason, exchanges()), "{} checkpointed the state before it", line);
+        assert_eq!(list_checkpoints(&storage_name).unwrap().len(), 1, "{}", line);
+    }
+}
+
+#[tokio::test]
+async fn summarizing_checkpoints_first() {
+    let client = ScriptedClient::default();
+    let (mut app, id) = app_with_history(&client, "checkpoint-summarize", 2);
+    client.push(ScriptedReply::text("- Rex is a cat."));
+
+    run_line(&mut app, "summarize");
+    let waited = tokio::time::timeout(REPLY_TIMEOUT, async {
+        while !app.agent_manager.jobs.is_empty() {
+            app.agent_manager.poll_channels();
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    });
+    waited.await.expect("the summary never finished");
+
+    assert!(history(&app, id).len() < EXCHANGES.len(), "older messages were folded into a summary");
+    assert_eq!(newest_checkpoint("checkpoint-summarize"), (CheckpointReason::Summarize, exchanges()));
+}
+
+#[test]
+fn rollback_restores_and_can_itself_be_rolled_back() {
+    let storage_name = "checkpoint-rollback";
+    let (mut app, id) = app_with_history(&ScriptedClient::default(), storage_name, 10);
+    run_line(&mut app, "history remove 4 --pair confirm");
+    let removed = (history(&app, id), response_id(&app, id));
+    assert_eq!(removed.0, exchanges()[..3]);
+
+    run_line(&mut app, "rollback");
+    let listed = last_lines(&app, 2);
+    assert_eq!(listed[0], "Checkpoints for checkpoint-rollback (newest first; preview with 'rollback <n>'):");
+    assert!(listed[1].starts_with("  #1  ") && listed[1].contains("  before history-remove  6 messages, "), "{}", listed[1]);
+
+    // A preview changes nothing
+    run_line(&mut app, "rollback 1");
+    assert!(last_lines(&app, 2)[0].ends_with("\nRestoring it replaces the current 4 messages with its 6."), "{:?}", last_lines(&app, 2));
+    assert_eq!((history(&app, id), response_id(&app, id)), removed);
+
+    // Restoring puts back memory, the saved history and the thread that goes with them
+    assert_eq!(run_line(&mut app, "rollback 1 confirm"), CommandResult::Continue);
+    assert_eq!(last_lines(&app, 1), ["Rolled back to #1 (6 messages). 'rollback 1 confirm' undoes this."]);
+    assert_eq!(history(&app, id), exchanges());
+    assert_eq!(saved(storage_name), Some(exchanges()));
+    assert_eq!(response_id(&app, id).as_deref(), Some("resp_1"));
+    assert_eq!(newest_checkpoint(storage_name), (CheckpointReason::Rollback, removed.0.clone()), "the replaced state was checkpointed");
+
+    // Rolling back the rollback returns to the state it replaced
+    run_line(&mut app, "rollback 1 confirm");
+    assert_eq!((history(&app, id), response_id(&app, id)), removed);
+    assert_eq!(saved(storage_name), Some(removed.0.clone()));
+    let reasons: Vec<CheckpointReason> = list_checkpoints(storage_name).unwrap().iter().map(|summary| summary.reason).collect();
+    assert_eq!(reasons, [CheckpointReason::Rollback, CheckpointReason::Rollback, CheckpointReason::HistoryRemove]);
+    assert_eq!(newest_checkpoint(storage_name), (CheckpointReason::Rollback, exchanges()));
+
+    assert_eq!(
+        run_line(&mut app, "rollback 9 confirm"),
+        CommandResult::Error("No checkpoint #9; 'rollback' lists 3 for checkpoint-rollback.".to_string())
+    );
+}
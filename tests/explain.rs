@@ -0,0 +1,95 @@
+//! `explain` after a failed request: answered locally when the agent can't
+//! answer, otherwise sent to it redacted with the context that goes along.
+
+mod support;
+
+use std::sync::Arc;
+use uuid::Uuid;
+use grokprime_brain::llm::AnyClient;
+use grokprime_brain::llm::scripted::{ScriptedClient, ScriptedReply};
+use grokprime_brain::persona::operations::AgentOperations;
+use grokprime_brain::tui::app::ShadowApp;
+use support::*;
+
+/// An app whose agents reply from `client`, with shadow open
+fn app_with(client: &ScriptedClient) -> ShadowApp {
+    test_root();
+    let mut app = ShadowApp::new();
+    app.agent_manager.client_override = Some(AnyClient::Scripted(client.clone()));
+    app.add_new_agent(Uuid::new_v4(), Arc::new(repo_persona("shadow")));
+    app
+}
+
+/// Sends `line` and lets the scripted `reply` play out
+async fn send(app: &mut ShadowApp, client: &ScriptedClient, line: &str, reply: ScriptedReply) {
+    client.push(reply);
+    run_line(app, line);
+    wait_for_reply(&mut app.agent_manager).await;
+}
+
+/// The last line shown in the Global pane
+fn last_line(app: &ShadowApp) -> String {
+    app.unified_messages.iter().last().map(|msg| msg.text.clone()).unwrap_or_default()
+}
+
+#[tokio::test]
+async fn a_rate_limit_gets_a_hint_and_a_local_explanation() {
+    let client = ScriptedClient::default();
+    let mut app = app_with(&client);
+    send(&mut app, &client, "hi", ScriptedReply::error("HTTP 429 Too Many Requests")).await;
+
+    let lines = pane_lines(&app.agent_manager);
+    let hint = lines.iter().find(|line| line.starts_with("Hint: ")).expect("a hint under the error");
+    assert!(hint.contains("rate limiting this key"), "{}", hint);
+
+    run_line(&mut app, "explain");
+    assert_eq!(client.requests().len(), 1, "nothing is sent to an agent that would fail the same way");
+    let shown = last_line(&app);
+    assert!(shown.starts_with("Explain (asking this agent would fail the same way; nothing sent):"), "{}", shown);
+    assert!(shown.ends_with(&hint["Hint: ".len()..]), "the same guidance as the hint: {}", shown);
+}
+
+#[tokio::test]
+async fn other_errors_are_sent_redacted_with_their_trigger() {
+    let client = ScriptedClient::default();
+    let mut app = app_with(&client);
+    run_line(&mut app, "explain");
+    assert_eq!(last_line(&app), "Nothing to explain: the last request didn't fail.");
+
+    let error = ScriptedReply::error("connection reset while sending xai-abcdefghijklmnop");
+    send(&mut app, &client, "use api_key=hunter2hunter2 please", error).await;
+    send(&mut app, &client, "explain", ScriptedReply::text("The network dropped mid-request.")).await;
+
+    let requests = client.requests();
+    assert_eq!(requests.len(), 2);
+    let prompt = last_user_message(&requests[1]);
+    assert!(prompt.starts_with("This error just showed up"), "{}", prompt);
+    assert!(prompt.contains("connection reset while sending [secret]"), "{}", prompt);
+    assert!(prompt.contains("- Triggered by: ") && prompt.contains("api_key=[secret] please"), "{}", prompt);
+    assert!(prompt.contains("- Sent to: the shadow persona"), "{}", prompt);
+    assert!(!prompt.contains("hunter2") && !prompt.contains("xai-abc"), "{}", prompt);
+
+    let lines = pane_lines(&app.agent_manager);
+    assert!(lines.iter().any(|line| line.contains("Explain: connection reset while sending [secret]")), "{:?}", lines);
+    assert_eq!(lines.last().map(String::as_str), Some("The network dropped mid-request."));
+
+    // The reply answered it, so there's nothing left to explain
+    run_line(&mut app, "explain");
+    assert_eq!(last_line(&app), "Nothing to explain: the last request didn't fail.");
+}
+
+#[tokio::test]
+async fn repeated_failures_without_a_helper_are_explained_locally() {
+    let client = ScriptedClient::default();
+    let mut app = app_with(&client);
+    send(&mut app, &client, "hi", ScriptedReply::error("connection reset by peer")).await;
+    send(&mut app, &client, "hi again", ScriptedReply::error("connection reset by peer")).await;
+
+    run_line(&mut app, "explain");
+    assert_eq!(client.requests().len(), 2);
+    assert_eq!(last_line(&app), [
+        "Explain (the last 2 requests here failed and no explain.helper_persona is set; nothing sent):",
+        "  Error: connection reset by peer",
+        "  No canned explanation for this error; the log has the full response.",
+    ].join("\n"));
+}
@@ -0,0 +1,206 @@
+//! The jobs ported onto `GenerationJob` (summarize, draft), each run to
+//! success, to failure, and cancelled part way, against a scripted model.
+
+mod support;
+
+use std::time::Duration;
+use uuid::Uuid;
+use grokprime_brain::llm::scripted::{ScriptedClient, ScriptedReply};
+use grokprime_brain::models::Message;
+use grokprime_brain::persona::agent_manager::{AgentEvent, AgentManager};
+use grokprime_brain::twitter::preview;
+use support::*;
+
+/// An agent on a copy of shadow that keeps only the last 4 messages when summarizing
+fn manager_with_agent(client: &ScriptedClient, storage_name: &str) -> (AgentManager, Uuid) {
+    test_root();
+    let mut manager = manager_with(client);
+    let mut persona = builtin_as("shadow", storage_name);
+    persona.enable_history = false;
+    persona.history_message_limit = 4;
+    let id = open_agent(&mut manager, persona);
+    (manager, id)
+}
+
+/// Gives the agent ten messages of conversation to summarize
+fn seed_history(manager: &AgentManager, id: Uuid) {
+    let agent = manager.agents.get(&id).expect("agent is open");
+    let mut conn = agent.connection.try_lock().expect("connection is idle");
+    for i in 0..5 {
+        conn.conversation.local_history.push(Message { role: "user".into(), content: format!("question {}", i), meta: None, source: None });
+        conn.conversation.local_history.push(Message { role: "assistant".into(), content: format!("answer {}", i), meta: None, source: None });
+    }
+}
+
+fn history_len(manager: &AgentManager, id: Uuid) -> usize {
+    manager.agents[&id].connection.try_lock().expect("connection is idle").local_history().len()
+}
+
+/// Polls until no job is left running; returns the job lines the Global pane would get
+async fn run_jobs(manager: &mut AgentManager) -> Vec<(String, bool)> {
+    let mut lines = Vec::new();
+    let waited = tokio::time::timeout(REPLY_TIMEOUT, async {
+        loop {
+            for event in manager.poll_channels() {
+                if let AgentEvent::Job { msg, failed, .. } = event {
+                    lines.push((msg, failed));
+                }
+            }
+            if manager.jobs.is_empty() && manager.current_pane().is_none_or(|agent| !agent.is_waiting) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    });
+    waited.await.expect("the jobs never finished");
+    lines
+}
+
+/// Lets any aborted task's leftovers arrive, returning the job lines they produce
+async fn drain(manager: &mut AgentManager) -> Vec<String> {
+    let mut lines = Vec::new();
+    for _ in 0..20 {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        for event in manager.poll_channels() {
+            if let AgentEvent::Job { msg, .. } = event {
+                lines.push(msg);
+            }
+        }
+    }
+    lines
+}
+
+fn ok(msg: &str) -> (String, bool) {
+    (msg.to_string(), false)
+}
+
+#[tokio::test]
+async fn summarize_job_succeeds() {
+    let client = ScriptedClient::default();
+    let (mut manager, id) = manager_with_agent(&client, "jobs-summarize-ok");
+    seed_history(&manager, id);
+    client.push(ScriptedReply::text("- Five questions, five answers."));
+
+    run_line(&mut manager, "summarize");
+    assert_eq!(manager.jobs.len(), 1);
+    let lines = run_jobs(&mut manager).await;
+
+    assert_eq!(lines[0], ok("Job 1 (summarize) started"));
+    let (done, failed) = lines.last().expect("a completion line");
+    assert!(done.starts_with("Job 1 (summarize) completed: history summarized"), "{}", done);
+    assert!(!failed);
+    assert!(history_len(&manager, id) < 11, "older messages were folded into a summary");
+}
+
+#[tokio::test]
+async fn summarize_job_with_nothing_to_do_still_completes() {
+    let client = ScriptedClient::default();
+    let (mut manager, _) = manager_with_agent(&client, "jobs-summarize-short");
+
+    run_line(&mut manager, "summarize");
+    let lines = run_jobs(&mut manager).await;
+
+    assert_eq!(lines, [
+        ok("Job 1 (summarize) started"),
+        ok("Job 1 (summarize) completed: history is already short enough; nothing to summarize"),
+    ]);
+    assert!(client.requests().is_empty());
+}
+
+#[tokio::test]
+async fn summarize_job_fails() {
+    let client = ScriptedClient::default();
+    let (mut manager, id) = manager_with_agent(&client, "jobs-summarize-fail");
+    seed_history(&manager, id);
+    client.push(ScriptedReply::error("503 Service Unavailable"));
+
+    run_line(&mut manager, "summarize");
+    let lines = run_jobs(&mut manager).await;
+
+    let (failure, failed) = lines.last().expect("a failure line");
+    assert!(failure.starts_with("Job 1 (summarize) failed:"), "{}", failure);
+    assert!(failure.contains("503 Service Unavailable"), "{}", failure);
+    assert!(failed);
+    assert_eq!(history_len(&manager, id), 11, "history is left as it was");
+}
+
+#[tokio::test]
+async fn summarize_job_cancelled() {
+    let client = ScriptedClient::default();
+    let (mut manager, id) = manager_with_agent(&client, "jobs-summarize-cancel");
+    seed_history(&manager, id);
+    let (reply, gate) = ScriptedReply::text("- Too late.").held();
+    client.push(reply);
+
+    run_line(&mut manager, "summarize");
+    tokio::time::timeout(REPLY_TIMEOUT, async {
+        while client.requests().is_empty() {
+            manager.poll_channels();
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }).await.expect("the historian was asked");
+
+    run_line(&mut manager, "jobs cancel 1");
+    assert!(manager.jobs.is_empty());
+    gate.release();
+
+    assert!(drain(&mut manager).await.is_empty(), "a cancelled job says nothing more");
+    assert_eq!(history_len(&manager, id), 11, "the summary never landed");
+}
+
+#[tokio::test]
+async fn draft_job_succeeds_and_leaves_a_preview() {
+    let client = ScriptedClient::default();
+    let (mut manager, _) = manager_with_agent(&client, "jobs-draft-ok");
+    client.push(ScriptedReply::chunks(["Jobs now report ", "progress."]));
+
+    run_line(&mut manager, "draft jobs report progress now");
+    assert!(manager.current_pane().is_some_and(|agent| agent.is_waiting));
+    let lines = run_jobs(&mut manager).await;
+
+    assert_eq!(lines, [ok("Job 1 (draft) started"), ok("Job 1 (draft) completed: draft ready for review")]);
+    assert!(pane_lines(&manager).iter().any(|line| line == "Jobs now report progress."), "{:?}", pane_lines(&manager));
+    let pending = preview::take_pending().expect("the draft awaits confirmation");
+    assert_eq!(pending.parts(), ["Jobs now report progress."]);
+}
+
+#[tokio::test]
+async fn draft_job_fails() {
+    let client = ScriptedClient::default();
+    let (mut manager, _) = manager_with_agent(&client, "jobs-draft-fail");
+    client.push(ScriptedReply::error("429 Too Many Requests"));
+
+    run_line(&mut manager, "draft something");
+    let lines = run_jobs(&mut manager).await;
+
+    assert_eq!(lines.len(), 2, "{:?}", lines);
+    assert!(lines[1].0.starts_with("Job 1 (draft) failed:") && lines[1].1, "{:?}", lines[1]);
+    assert!(pane_lines(&manager).iter().any(|line| line == "Error: 429 Too Many Requests"), "{:?}", pane_lines(&manager));
+    assert!(!manager.current_pane().is_some_and(|agent| agent.is_waiting));
+}
+
+#[tokio::test]
+async fn draft_job_cancelled() {
+    let client = ScriptedClient::default();
+    let (mut manager, _) = manager_with_agent(&client, "jobs-draft-cancel");
+    let (reply, gate) = ScriptedReply::chunks(["Half a ", "draft."]).held_after(1);
+    client.push(reply);
+
+    run_line(&mut manager, "draft something");
+    tokio::time::timeout(REPLY_TIMEOUT, async {
+        while !pane_lines(&manager).iter().any(|line| line == "Half a ") {
+            manager.poll_channels();
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }).await.expect("the first chunk arrived");
+
+    run_line(&mut manager, "jobs cancel 1");
+    assert!(manager.jobs.is_empty());
+    let agent = manager.current_pane().expect("agent is open");
+    assert!(!agent.is_waiting, "the pane stops waiting");
+    assert_eq!(pane_lines(&manager).last().map(String::as_str), Some("(cancelled)"));
+
+    gate.release();
+    assert!(drain(&mut manager).await.is_empty());
+    assert!(!pane_lines(&manager).iter().any(|line| line.contains("draft.")), "{:?}", pane_lines(&manager));
+}
@@ -0,0 +1,277 @@
+//! History maintenance commands (dedupe, remove) run against an open agent, checked in both
+//! the agent's memory and the history file they rewrite.
+
+mod support;
+
+use std::sync::Arc;
+use uuid::Uuid;
+use grokprime_brain::agent_history::conversations::{LONG_TERM_PREFIX, SUMMARY_PREFIX};
+use grokprime_brain::agent_history::history::{HistoryManager, RemovedMessages};
+use grokprime_brain::agent_history::redaction::{redacted_badge, EphemeralPattern, RedactScope};
+use grokprime_brain::commands::CommandResult;
+use grokprime_brain::llm::AnyClient;
+use grokprime_brain::llm::scripted::ScriptedClient;
+use grokprime_brain::models::Message;
+use grokprime_brain::persona::operations::AgentOperations;
+use grokprime_brain::tui::app::ShadowApp;
+use support::*;
+
+fn app_with_agent(storage_name: &str) -> (ShadowApp, Uuid) {
+    test_root();
+    let mut app = ShadowApp::new();
+    app.agent_manager.client_override = Some(AnyClient::Scripted(ScriptedClient::default()));
+    let id = Uuid::new_v4();
+    app.add_new_agent(id, Arc::new(builtin_as("shadow", storage_name)));
+    (app, id)
+}
+
+fn message(role: &str, content: &str) -> Message {
+    Message { role: role.to_string(), content: content.to_string(), meta: None, source: None }
+}
+
+fn history(app: &ShadowApp, id: Uuid) -> Vec<(String, String)> {
+    let agent = app.agent_manager.agents.get(&id).expect("agent is open");
+    let conn = agent.connection.try_lock().expect("connection is idle");
+    conn.local_history().iter()
+        .filter(|msg| msg.role != "system")
+        .map(|msg| (msg.role.clone(), msg.content.clone()))
+        .collect()
+}
+
+fn saved(storage_name: &str) -> Vec<(String, String)> {
+    HistoryManager::load_persona_history(storage_name).expect("history was saved")
+        .recent_messages.into_iter()
+        .filter(|msg| msg.role != "system")
+        .map(|msg| (msg.role, msg.content))
+        .collect()
+}
+
+fn last_lines(app: &ShadowApp, count: usize) -> Vec<String> {
+    let texts: Vec<String> = app.unified_messages.iter().map(|msg| msg.text.clone()).collect();
+    texts[texts.len().saturating_sub(count)..].to_vec()
+}
+
+/// An agent with three exchanges after its prompt, threaded on a response ID
+fn app_with_exchanges(storage_name: &str) -> (ShadowApp, Uuid) {
+    let (app, id) = app_with_agent(storage_name);
+    {
+        let agent = app.agent_manager.agents.get(&id).expect("agent is open");
+        let mut conn = agent.connection.try_lock().expect("connection is idle");
+        conn.conversation.local_history.extend([
+            message("user", "My cat is called Rex."),
+            message("assistant", "Rex is a fine name for a cat."),
+            message("user", "Actually I have a dog."),
+            message("assistant", "Noted: a dog."),
+            message("user", "What should I feed Rex?"),
+            message("assistant", "Cat food, of course."),
+        ]);
+        conn.conversation.set_last_response_id("resp_1".to_string());
+    }
+    (app, id)
+}
+
+fn response_id(app: &ShadowApp, id: Uuid) -> Option<String> {
+    let agent = app.agent_manager.agents.get(&id).expect("agent is open");
+    let conn = agent.connection.try_lock().expect("connection is idle");
+    conn.conversation.get_last_response_id().cloned()
+}
+
+/// The recovery copy named in the removal message
+fn recovery_copy(app: &ShadowApp) -> RemovedMessages {
+    let text = app.unified_messages.iter().rev()
+        .find_map(|msg| msg.text.strip_prefix("Removed ").map(str::to_string))
+        .expect("a removal was reported");
+    let path = text.split_once("recovery copy at ").and_then(|(_, rest)| rest.split_once(". The next")).expect("a recovery path").0;
+    serde_json::from_str(&std::fs::read_to_string(path).expect("the recovery copy exists")).expect("the recovery copy parses")
+}
+
+fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+    expected.iter().map(|(role, content)| (role.to_string(), content.to_string())).collect()
+}
+
+#[test]
+fn dedupe_lists_first_then_rewrites_memory_and_file() {
+    let (mut app, id) = app_with_agent("dedupe-shadow");
+    {
+        let agent = app.agent_manager.agents.get(&id).expect("agent is open");
+        let mut conn = agent.connection.try_lock().expect("connection is idle");
+        conn.conversation.local_history.extend([
+            message("user", "what time is it?"),
+            message("user", "what time is it?"),
+            message("assistant", "Noon."),
+            message("user", "yes"),
+            message("assistant", "Done."),
+            message("assistant", "Done."),
+            message("user", "yes"),
+        ]);
+    }
+
+    run_line(&mut app, "history dedupe");
+    assert_eq!(last_lines(&app, 4), [
+        "2 duplicate message(s) would be removed:",
+        "  #2 user: what time is it?",
+        "  #6 assistant: Done.",
+        "Run 'history dedupe confirm' to remove them.",
+    ]);
+    assert_eq!(history(&app, id).len(), 7, "a dry run changes nothing");
+    assert!(!HistoryManager::history_exists("dedupe-shadow"), "nor writes anything");
+
+    run_line(&mut app, "history dedupe confirm");
+    assert_eq!(last_lines(&app, 1), ["Removed 2 duplicate message(s)"]);
+    let expected = pairs(&[
+        ("user", "what time is it?"),
+        ("assistant", "Noon."),
+        ("user", "yes"),
+        ("assistant", "Done."),
+        ("user", "yes"),
+    ]);
+    assert_eq!(history(&app, id), expected, "the repeated 'yes' minutes apart is kept");
+    assert_eq!(saved("dedupe-shadow"), expected);
+
+    run_line(&mut app, "history dedupe");
+    assert_eq!(last_lines(&app, 1), ["No duplicate messages found."]);
+}
+
+#[test]
+fn removing_a_message_in_the_middle_saves_a_recovery_copy_and_drops_the_thread() {
+    let (mut app, id) = app_with_exchanges("remove-middle");
+
+    run_line(&mut app, "history remove 1");
+    assert_eq!(last_lines(&app, 3), [
+        "1 message(s) would be removed:",
+        "  #1 user: My cat is called Rex.",
+        "Run 'history remove 1 confirm' to remove them.",
+    ]);
+    assert_eq!(history(&app, id).len(), 6, "a dry run changes nothing");
+
+    run_line(&mut app, "history remove 1 confirm");
+    let expected = pairs(&[
+        ("assistant", "Rex is a fine name for a cat."),
+        ("user", "Actually I have a dog."),
+        ("assistant", "Noted: a dog."),
+        ("user", "What should I feed Rex?"),
+        ("assistant", "Cat food, of course."),
+    ]);
+    assert_eq!(history(&app, id), expected);
+    assert_eq!(saved("remove-middle"), expected);
+    assert_eq!(response_id(&app, id), None, "the next request re-sends the edited history");
+
+    // The list is recomputed: the messages after the removal moved up
+    assert_eq!(last_lines(&app, 5), [
+        "  #1 assistant: Rex is a fine name for a cat.",
+        "  #2 user: Actually I have a dog.",
+        "  #3 assistant: Noted: a dog.",
+        "  #4 user: What should I feed Rex?",
+        "  #5 assistant: Cat food, of course.",
+    ]);
+    let recovery = recovery_copy(&app);
+    assert_eq!(recovery.index, 1);
+    assert_eq!(recovery.persona, "remove-middle");
+    assert_eq!(recovery.messages.iter().map(|msg| (msg.role.as_str(), msg.content.as_str())).collect::<Vec<_>>(), [("user", "My cat is called Rex.")]);
+}
+
+#[test]
+fn removing_the_latest_pair_from_either_half() {
+    for (case, line) in [("from the reply", "history remove 6 --pair confirm"), ("from the question", "history remove 5 --pair confirm")] {
+        let storage_name = format!("remove-latest-{}", case.replace(' ', "-"));
+        let (mut app, id) = app_with_exchanges(&storage_name);
+
+        run_line(&mut app, line);
+        let expected = pairs(&[
+            ("user", "My cat is called Rex."),
+            ("assistant", "Rex is a fine name for a cat."),
+            ("user", "Actually I have a dog."),
+            ("assistant", "Noted: a dog."),
+        ]);
+        assert_eq!(history(&app, id), expected, "{}", case);
+        assert_eq!(saved(&storage_name), expected, "{}", case);
+        assert_eq!(response_id(&app, id), None, "{}", case);
+
+        let recovery = recovery_copy(&app);
+        assert_eq!(recovery.index, 5, "{}", case);
+        assert_eq!(
+            recovery.messages.iter().map(|msg| msg.content.as_str()).collect::<Vec<_>>(),
+            ["What should I feed Rex?", "Cat food, of course."],
+            "{}", case
+        );
+    }
+}
+
+#[test]
+fn protected_and_unpaired_messages_are_refused() {
+    let (mut app, id) = app_with_exchanges("remove-refused");
+    {
+        let agent = app.agent_manager.agents.get(&id).expect("agent is open");
+        let mut conn = agent.connection.try_lock().expect("connection is idle");
+        conn.conversation.local_history.insert(1, message("system", "[Summary of earlier conversation]\n- Rex came up."));
+        conn.conversation.local_history.push(message("user", "Hello?"));
+    }
+    let cases = [
+        ("history remove 0 confirm", "#0 is a system message (prompt or summary) and can't be removed"),
+        ("history remove 1 confirm", "#1 is a system message (prompt or summary) and can't be removed"),
+        ("history remove 8 --pair confirm", "#8 has no paired message next to it; drop --pair to remove it alone"),
+        ("history remove 9 confirm", "No message #9 (history has 9)"),
+    ];
+
+    for (line, expected) in cases {
+        match run_line(&mut app, line) {
+            CommandResult::Error(msg) => assert_eq!(msg, expected, "{}", line),
+            other => panic!("{} was not refused: {:?}", line, other),
+        }
+    }
+    assert_eq!(history(&app, id).len(), 7, "nothing was removed");
+    assert_eq!(response_id(&app, id).as_deref(), Some("resp_1"), "nor the thread dropped");
+    assert!(!HistoryManager::history_exists("remove-refused"));
+}
+
+#[test]
+fn history_info_reports_both_summary_tiers() {
+    let (mut app, id) = app_with_exchanges("info-tiers");
+    run_line(&mut app, "historyinfo");
+    let shown = last_lines(&app, 1).concat();
+    assert!(shown.contains("recent summary: none, long-term summary: none, summarized 0 times"), "{}", shown);
+
+    {
+        let agent = app.agent_manager.agents.get(&id).expect("agent is open");
+        let mut conn = agent.connection.try_lock().expect("connection is idle");
+        conn.conversation.local_history.splice(1..1, [
+            message("system", &format!("{}{}]", LONG_TERM_PREFIX, "- Rex is a dog.\n".repeat(128))),
+            message("system", &format!("{}- Asked what to feed Rex; the answer assumed a cat.]", SUMMARY_PREFIX)),
+        ]);
+        conn.conversation.summarization_count = 3;
+    }
+    run_line(&mut app, "historyinfo");
+    let shown = last_lines(&app, 1).concat();
+    assert!(shown.contains("recent summary: 0.0 KB, long-term summary: 2.0 KB, summarized 3 times"), "{}", shown);
+}
+
+#[test]
+fn history_list_shows_the_stored_form_of_ephemeral_content() {
+    test_root();
+    let mut app = ShadowApp::new();
+    app.agent_manager.client_override = Some(AnyClient::Scripted(ScriptedClient::default()));
+    let mut persona = builtin_as("shadow", "list-ephemeral");
+    persona.ephemeral_patterns = vec![
+        EphemeralPattern { pattern: r"\$\d[\d,]*".to_string(), scope: RedactScope::Span },
+        EphemeralPattern { pattern: "(?i)salary".to_string(), scope: RedactScope::Message },
+    ];
+    let id = Uuid::new_v4();
+    app.add_new_agent(id, Arc::new(persona));
+    {
+        let agent = app.agent_manager.agents.get(&id).expect("agent is open");
+        let mut conn = agent.connection.try_lock().expect("connection is idle");
+        conn.conversation.local_history.extend([
+            message("user", "My salary is $120,000."),
+            message("assistant", "That's $10,000 over last year."),
+            message("user", "Lunch?"),
+        ]);
+    }
+
+    run_line(&mut app, "history list");
+    assert_eq!(last_lines(&app, 3), [
+        format!("  #1 user: [message not stored] {}", redacted_badge()),
+        format!("  #2 assistant: That's [redacted] over last year. {}", redacted_badge()),
+        "  #3 user: Lunch?".to_string(),
+    ]);
+    assert_eq!(history(&app, id)[0].1, "My salary is $120,000.", "listing changes nothing in memory");
+}
@@ -0,0 +1,110 @@
+//! Every history format version this build reads, from the fixtures in
+//! `tests/fixtures/history/`: each migrates to the current version, then
+//! survives a save and a reload unchanged.
+//!
+//! Adding a field to `ConversationHistory` without a new format version fails
+//! `the_current_fixture_has_every_field_the_build_writes`.
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use serde_json::Value;
+use grokprime_brain::agent_history::schema::{parse_history, HistorySchemaError, CURRENT_HISTORY_VERSION};
+use grokprime_brain::models::{ConversationHistory, Message};
+use grokprime_brain::persona::experiment::{ExperimentTag, Variant};
+
+fn fixture(version: u32) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("tests/fixtures/history/v{}.json", version));
+    std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("no fixture for history format v{} at {} ({}); add one with each new version", version, path.display(), e))
+}
+
+fn load(version: u32) -> ConversationHistory {
+    parse_history(&fixture(version)).unwrap_or_else(|e| panic!("v{} fixture: {}", version, e))
+}
+
+/// As `HistoryManager::save_persona_history` writes it
+fn saved(history: &ConversationHistory) -> String {
+    serde_json::to_string_pretty(history).expect("history serializes")
+}
+
+fn keys(value: &Value) -> BTreeSet<String> {
+    value.as_object().expect("a JSON object").keys().cloned().collect()
+}
+
+#[test]
+fn every_version_migrates_then_round_trips() {
+    for version in 1..=CURRENT_HISTORY_VERSION {
+        let history = load(version);
+        assert_eq!(history.format_version, CURRENT_HISTORY_VERSION, "v{} fixture", version);
+
+        let first_save = saved(&history);
+        let reloaded = parse_history(&first_save).unwrap_or_else(|e| panic!("v{} after one save: {}", version, e));
+        assert_eq!(saved(&reloaded), first_save, "v{} changes on its second save", version);
+    }
+}
+
+#[test]
+fn migration_fills_what_older_versions_left_out() {
+    let v1 = load(1);
+    assert_eq!(v1.summary, None);
+    assert_eq!(v1.total_message_count, 3, "counted from the recent messages");
+    assert_eq!(v1.summarization_count, 0);
+    assert_eq!(v1.experiment, None);
+    assert_eq!(v1.started_at, None);
+    assert_eq!(v1.last_updated, "2025-11-02T08:15:00+00:00", "kept, not replaced with now");
+
+    let v2 = load(2);
+    assert_eq!(v2.total_message_count, 14);
+    assert_eq!(v2.experiment, Some(ExperimentTag { experiment: "terse".into(), variant: Variant::B }));
+    assert_eq!(v2.summary.as_deref(), Some("[Summary of earlier conversation]\nTopics: the history migration."));
+    assert_eq!(v2.long_term_summary, None);
+
+    let v3 = load(3);
+    assert_eq!(v3.long_term_summary.as_deref(), Some("[Long-term summary]\nTopics: the history migration, summary tiers."));
+    assert_eq!(v3.last_response_id, None);
+    assert_eq!(v3.last_exchange_at, None);
+
+    let v4 = load(4);
+    assert_eq!(v4.last_response_id.as_deref(), Some("resp_01JHX8Q2Z5"));
+    assert_eq!(v4.last_response_at.as_deref(), Some("2026-01-20T18:29:58+00:00"));
+    let meta = v4.recent_messages[2].meta.as_ref().expect("assistant meta");
+    assert_eq!((meta.model.as_str(), meta.latency_ms), ("grok-4", Some(1830)));
+}
+
+#[test]
+fn the_current_fixture_has_every_field_the_build_writes() {
+    let everything_set = ConversationHistory {
+        persona_name: "shadow".into(),
+        summary: Some("recent".into()),
+        long_term_summary: Some("long term".into()),
+        recent_messages: vec![Message { role: "user".into(), content: "hi".into(), meta: None, source: None }],
+        total_message_count: 1,
+        last_updated: "2026-01-20T18:30:00+00:00".into(),
+        summarization_count: 0,
+        experiment: Some(ExperimentTag { experiment: "terse".into(), variant: Variant::A }),
+        started_at: Some("2026-01-20T18:00:00+00:00".into()),
+        last_exchange_at: Some("2026-01-20T18:29:58+00:00".into()),
+        last_response_id: Some("resp_1".into()),
+        last_response_at: Some("2026-01-20T18:29:58+00:00".into()),
+        format_version: CURRENT_HISTORY_VERSION,
+    };
+    let written = keys(&serde_json::to_value(&everything_set).expect("history serializes"));
+    let current: Value = serde_json::from_str(&fixture(CURRENT_HISTORY_VERSION)).expect("fixture is JSON");
+    assert_eq!(
+        written, keys(&current),
+        "ConversationHistory and the v{} fixture disagree on fields; a new field needs a new format version, a migration and a fixture",
+        CURRENT_HISTORY_VERSION
+    );
+}
+
+#[test]
+fn a_newer_version_is_refused() {
+    let mut newer: Value = serde_json::from_str(&fixture(CURRENT_HISTORY_VERSION)).expect("fixture is JSON");
+    newer["format_version"] = Value::from(CURRENT_HISTORY_VERSION + 1);
+    match parse_history(&newer.to_string()) {
+        Err(HistorySchemaError::NewerVersion { found, supported }) => {
+            assert_eq!((found, supported), (CURRENT_HISTORY_VERSION + 1, CURRENT_HISTORY_VERSION));
+        }
+        other => panic!("expected NewerVersion, got {:?}", other.map(|history| history.format_version)),
+    }
+}
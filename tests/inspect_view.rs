@@ -0,0 +1,66 @@
+//! The `inspect` view over a conversation with and without summaries and
+//! injected system messages, drawn by the real TUI.
+//!
+//! Screens are compared with `tests/snapshots/<name>.txt`; rerun with
+//! `UPDATE_SNAPSHOTS=1` after an intended change and review the diff.
+
+mod support;
+
+use crossterm::event::KeyCode;
+use grokprime_brain::models::Message;
+use support::tui::TuiHarness;
+
+fn message(role: &str, content: &str) -> Message {
+    Message { role: role.to_string(), content: content.to_string(), meta: None, source: None }
+}
+
+/// A `shadow` agent whose history in memory is exactly `history`
+fn harness_holding(history: Vec<Message>) -> TuiHarness {
+    let mut h = TuiHarness::new();
+    h.submit("new shadow");
+    {
+        let agent = h.app.agent_manager.current_pane().expect("an agent is open");
+        let mut conn = agent.connection.try_lock().expect("connection is idle");
+        conn.conversation.local_history = history;
+    }
+    h.tick();
+    h
+}
+
+#[tokio::test]
+async fn a_conversation_without_summaries() {
+    let mut h = harness_holding(vec![
+        message("system", "Be brief."),
+        message("user", "What is a monad?"),
+        message("assistant", "A burrito."),
+    ]);
+
+    h.submit("inspect");
+    h.assert_snapshot("inspect_plain");
+}
+
+#[tokio::test]
+async fn a_summarized_conversation_with_memory_blocks() {
+    let mut h = harness_holding(vec![
+        message("system", "Be brief."),
+        message("system", "[Long-term summary: - Met in January; likes Rust.]"),
+        message("system", "[Previous conversation summary: - Asked about monads twice.]"),
+        message("system", "Remembered: the user drinks tea, not coffee."),
+        message("user", "And a functor?"),
+        message("assistant", "A box you can map over."),
+    ]);
+
+    h.submit("inspect");
+    h.assert_snapshot("inspect_summarized");
+
+    // Fold the long-term summary; the rest stays open
+    h.press(KeyCode::Down);
+    h.press(KeyCode::Enter);
+    assert!(h.shows("▸ Long-term summary"), "{}", h.screen());
+    assert!(!h.shows("Met in January"), "{}", h.screen());
+    assert!(h.shows("Asked about monads twice."), "{}", h.screen());
+
+    h.press(KeyCode::Esc);
+    assert!(h.app.inspect.is_none());
+    assert!(!h.shows("Recent summary"), "{}", h.screen());
+}
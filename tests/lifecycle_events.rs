@@ -0,0 +1,127 @@
+//! The lifecycle events an agent publishes as it is opened, talks, summarizes
+//! and closes, read off an in-memory subscriber to the event bus.
+//!
+//! Every test binary shares one bus, so each test keeps only the events of the
+//! agent it opened.
+
+mod support;
+
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+use uuid::Uuid;
+use grokprime_brain::llm::scripted::{ScriptedClient, ScriptedReply};
+use grokprime_brain::models::Message;
+use grokprime_brain::persona::agent_manager::AgentManager;
+use grokprime_brain::utilities::events::{Event, EventKind, EVENT_BUS, EVENT_SCHEMA_VERSION};
+use support::*;
+
+fn manager_with_agent(client: &ScriptedClient, storage_name: &str) -> (AgentManager, Uuid, Receiver<Event>) {
+    test_root();
+    let mut manager = manager_with(client);
+    let mut persona = builtin_as("shadow", storage_name);
+    persona.enable_history = false;
+    persona.history_message_limit = 4;
+    let events = EVENT_BUS.subscribe();
+    let id = open_agent(&mut manager, persona);
+    (manager, id, events)
+}
+
+/// The events published for agent `id` so far, checking the envelope of each
+fn events_for(events: &Receiver<Event>, id: Uuid, persona: &str) -> Vec<EventKind> {
+    events.try_iter()
+        .filter(|event| event.agent_id == Some(id.to_string()))
+        .map(|event| {
+            assert_eq!(event.schema_version, EVENT_SCHEMA_VERSION);
+            assert_eq!(event.persona.as_deref(), Some(persona), "{:?}", event);
+            event.kind
+        })
+        .collect()
+}
+
+/// The single `message_completed` among `kinds`, with its latency blanked
+fn completed(kinds: &[EventKind]) -> EventKind {
+    let mut found = kinds.iter().filter(|kind| matches!(kind, EventKind::MessageCompleted { .. }));
+    let mut kind = found.next().expect("a message_completed event").clone();
+    assert!(found.next().is_none(), "{:?}", kinds);
+    if let EventKind::MessageCompleted { latency_ms, .. } = &mut kind {
+        assert!(latency_ms.is_some(), "a finished request reports its latency");
+        *latency_ms = None;
+    }
+    kind
+}
+
+#[test]
+fn opening_and_closing_an_agent() {
+    let (mut manager, id, events) = manager_with_agent(&ScriptedClient::default(), "events-open-close");
+
+    manager.remove_agent(id);
+
+    assert_eq!(events_for(&events, id, "events-open-close"), [EventKind::AgentCreated, EventKind::AgentClosed]);
+}
+
+#[tokio::test]
+async fn a_reply_is_sent_then_completed() {
+    let client = ScriptedClient::default();
+    let (mut manager, id, events) = manager_with_agent(&client, "events-reply");
+    client.push(ScriptedReply::chunks(["Noon, ", "give or take."]));
+
+    run_line(&mut manager, "what time is it?");
+    wait_for_reply(&mut manager).await;
+
+    let kinds = events_for(&events, id, "events-reply");
+    assert_eq!(kinds.len(), 3, "{:?}", kinds);
+    assert_eq!(kinds[0], EventKind::AgentCreated);
+    assert_eq!(kinds[1], EventKind::MessageSent { history_messages: 2 }, "the prompt and the question");
+    assert_eq!(completed(&kinds), EventKind::MessageCompleted {
+        model: client.requests()[0].model.clone(),
+        latency_ms: None,
+        input_tokens: None,
+        output_tokens: None,
+        reply_chars: "Noon, give or take.".chars().count(),
+    });
+}
+
+#[tokio::test]
+async fn a_failed_request_publishes_an_error() {
+    let client = ScriptedClient::default();
+    let (mut manager, id, events) = manager_with_agent(&client, "events-request-error");
+    client.push(ScriptedReply::error("503 Service Unavailable"));
+
+    run_line(&mut manager, "hello?");
+    wait_for_reply(&mut manager).await;
+
+    assert_eq!(events_for(&events, id, "events-request-error"), [
+        EventKind::AgentCreated,
+        EventKind::MessageSent { history_messages: 2 },
+        EventKind::Error { source: "request".into(), message: "503 Service Unavailable".into() },
+    ]);
+}
+
+#[tokio::test]
+async fn a_summary_reports_the_history_before_and_after() {
+    let client = ScriptedClient::default();
+    let (mut manager, id, events) = manager_with_agent(&client, "events-summarized");
+    {
+        let agent = manager.agents.get(&id).expect("agent is open");
+        let mut conn = agent.connection.try_lock().expect("connection is idle");
+        for i in 0..5 {
+            conn.conversation.local_history.push(Message { role: "user".into(), content: format!("question {}", i), meta: None, source: None });
+            conn.conversation.local_history.push(Message { role: "assistant".into(), content: format!("answer {}", i), meta: None, source: None });
+        }
+    }
+    client.push(ScriptedReply::text("- Five questions, five answers."));
+
+    run_line(&mut manager, "summarize");
+    tokio::time::timeout(REPLY_TIMEOUT, async {
+        while !manager.jobs.is_empty() {
+            manager.poll_channels();
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }).await.expect("the summary finished");
+
+    let kinds = events_for(&events, id, "events-summarized");
+    assert!(
+        kinds.contains(&EventKind::Summarized { messages_before: 11, messages_after: 6 }),
+        "the prompt, the summary and the last 4 messages remain: {:?}", kinds
+    );
+}
@@ -0,0 +1,214 @@
+//! `migrate-history` over the legacy fixtures in `tests/fixtures/legacy_history/`:
+//! both old formats, a persona that already has a history, a persona that
+//! doesn't exist, and a file that is neither format.
+//!
+//! Each test copies the fixtures into its own legacy directory with a prefix
+//! on every file name, so the personas they migrate into don't overlap.
+
+mod support;
+
+use std::path::{Path, PathBuf};
+use grokprime_brain::agent_history::history::HistoryManager;
+use grokprime_brain::agent_history::legacy::{self, ConflictChoice, Disposition, LegacyFormat, MigrateOptions, MigrationReport};
+use grokprime_brain::models::{ConversationHistory, Message};
+use support::*;
+
+/// Copies every fixture into `{root}/legacy-{prefix}/` as `{prefix}{name}`
+fn legacy_dir(prefix: &str) -> PathBuf {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/legacy_history");
+    let dir = test_root().join(format!("legacy-{}", prefix));
+    std::fs::create_dir_all(&dir).unwrap();
+    for entry in std::fs::read_dir(&fixtures).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy();
+        std::fs::copy(&path, dir.join(format!("{}{}", prefix, name))).unwrap();
+    }
+    dir
+}
+
+/// Writes a persona file for `name`, as if the user had one
+fn persona(name: &str) {
+    let dir = test_root().join("personas").join(name);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join(format!("{}.yaml", name)), format!("name: {}\nsystem_prompt: Be brief.\n", name)).unwrap();
+}
+
+fn message(role: &str, content: &str) -> Message {
+    Message { role: role.to_string(), content: content.to_string(), meta: None, source: None }
+}
+
+fn current_path(name: &str) -> PathBuf {
+    test_root().join("personas").join(name).join("history").join(format!("{}_history.json", name))
+}
+
+/// Saves a history for `name` in the new layout, last updated at `at`
+fn current(name: &str, at: &str, exchange: &[(&str, &str)]) {
+    let mut history = ConversationHistory::new(name.to_string());
+    history.recent_messages = exchange.iter().map(|(role, content)| message(role, content)).collect();
+    history.total_message_count = exchange.len();
+    history.last_updated = at.to_string();
+    let path = current_path(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, serde_json::to_string_pretty(&history).unwrap()).unwrap();
+}
+
+fn contents(history: &ConversationHistory) -> Vec<(&str, &str)> {
+    history.recent_messages.iter().map(|msg| (msg.role.as_str(), msg.content.as_str())).collect()
+}
+
+fn loaded(name: &str) -> ConversationHistory {
+    HistoryManager::load_persona_history(name).unwrap_or_else(|e| panic!("{} has no history: {}", name, e))
+}
+
+fn run(from: &Path, apply: bool, conflict: Option<ConflictChoice>, create_stubs: bool) -> MigrationReport {
+    legacy::migrate(&MigrateOptions { from: from.to_path_buf(), apply, conflict, create_stubs }).expect("migration runs")
+}
+
+/// (persona, format, disposition) for each file in the report
+fn dispositions(report: &MigrationReport) -> Vec<(String, Option<LegacyFormat>, Disposition)> {
+    report.files.iter()
+        .inspect(|file| assert_eq!(file.error, None, "{}", file.describe()))
+        .map(|file| (file.persona.clone(), file.format, file.disposition.clone()))
+        .collect()
+}
+
+/// File names directly in `dir`, sorted
+fn names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir).map(|entries| {
+        entries.map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned()).collect()
+    }).unwrap_or_default();
+    names.sort();
+    names
+}
+
+#[test]
+fn a_dry_run_reports_every_file_and_changes_nothing() {
+    test_root();
+    let from = legacy_dir("dry-");
+    for name in ["dry-messages", "dry-conversation", "dry-collide"] {
+        persona(name);
+    }
+    current("dry-collide", "2026-01-05T09:00:00+00:00", &[("user", "Hi."), ("assistant", "Hello.")]);
+
+    let report = run(&from, false, None, false);
+    let notes = dispositions(&report).pop().unwrap();
+    assert_eq!(dispositions(&report)[..4], [
+        ("dry-collide".to_string(), Some(LegacyFormat::Conversation), Disposition::Conflict),
+        ("dry-conversation".to_string(), Some(LegacyFormat::Conversation), Disposition::Move),
+        ("dry-ghost".to_string(), Some(LegacyFormat::Messages), Disposition::UnknownPersona),
+        ("dry-messages".to_string(), Some(LegacyFormat::Messages), Disposition::Move),
+    ]);
+    assert_eq!((notes.0.as_str(), notes.1), ("dry-notes", None));
+    assert!(matches!(&notes.2, Disposition::Invalid(reason) if reason.starts_with("neither a message list nor a history file")), "{:?}", notes.2);
+
+    let shown = report.describe();
+    assert!(shown.starts_with(&format!("Legacy histories in {} (dry run; add --apply", from.display())), "{}", shown);
+    assert!(shown.contains("  dry-messages_history.json -> dry-messages: move into the new layout (message list, converted)"), "{}", shown);
+    assert!(shown.contains("  dry-ghost_history.json -> dry-ghost: skipped: no persona 'dry-ghost'; --create-stubs creates one"), "{}", shown);
+    assert!(shown.ends_with("3 file(s) left in place; re-run after resolving them."), "{}", shown);
+
+    assert_eq!(names(&from).len(), 5, "nothing moved");
+    assert!(!current_path("dry-messages").exists() && !current_path("dry-conversation").exists());
+    assert_eq!(contents(&loaded("dry-collide")), [("user", "Hi."), ("assistant", "Hello.")]);
+}
+
+#[test]
+fn applying_converts_both_formats_and_a_rerun_changes_nothing() {
+    test_root();
+    let from = legacy_dir("apply-");
+    for name in ["apply-messages", "apply-conversation", "apply-collide"] {
+        persona(name);
+    }
+    current("apply-collide", "2026-01-05T09:00:00+00:00", &[("user", "Hi."), ("assistant", "Hello.")]);
+
+    let report = run(&from, true, None, false);
+    assert!(report.describe().starts_with("Migrated legacy histories from"), "{}", report.describe());
+
+    // A message list loses its old prompt; its summaries become the summary fields
+    let messages = loaded("apply-messages");
+    assert_eq!(contents(&messages), [("user", "What's left before launch?"), ("assistant", "The pricing page.")]);
+    assert_eq!(messages.summary.as_deref(), Some("Planned the launch week."));
+    assert_eq!(messages.long_term_summary.as_deref(), Some("- Ana runs the beta."));
+    assert_eq!(messages.total_message_count, 2);
+
+    let conversation = loaded("apply-conversation");
+    assert_eq!(contents(&conversation), [("user", "When do I plant tomatoes?"), ("assistant", "After the last frost.")]);
+    assert_eq!((conversation.summary.as_deref(), conversation.total_message_count), (Some("Talked about the garden."), 6));
+    assert_eq!(conversation.last_updated, "2025-11-02T08:15:00+00:00");
+
+    let pending = ["apply-collide_history.json", "apply-ghost_history.json", "apply-notes.json"];
+    assert_eq!(names(&from), ["apply-collide_history.json", "apply-ghost_history.json", "apply-notes.json", "migrated"]);
+    assert_eq!(names(&from.join("migrated")), ["apply-conversation_history.json", "apply-messages_history.json"]);
+
+    // Re-running only sees what's still waiting on a decision
+    let rerun = run(&from, true, None, false);
+    let personas: Vec<String> = dispositions(&rerun).into_iter().map(|(persona, _, _)| persona).collect();
+    assert_eq!(personas, ["apply-collide", "apply-ghost", "apply-notes"]);
+    assert_eq!(names(&from)[..3], pending);
+    assert_eq!(contents(&loaded("apply-messages")).len(), 2, "migrated histories are untouched");
+
+    // Deciding the rest leaves only the unreadable file
+    run(&from, true, Some(ConflictChoice::Merge), true);
+    assert_eq!(names(&from), ["apply-notes.json", "migrated"]);
+    assert!(test_root().join("personas/apply-ghost/apply-ghost.yaml").exists(), "a stub persona was created");
+    assert_eq!(contents(&loaded("apply-ghost")), [("user", "Boo?"), ("assistant", "Boo.")]);
+    let last = run(&from, true, Some(ConflictChoice::Merge), true);
+    assert_eq!(last.files.len(), 1);
+    assert_eq!(last.describe().lines().last(), Some("1 file(s) left in place; re-run after resolving them."));
+    assert_eq!(contents(&loaded("apply-collide")).len(), 6, "not merged a second time");
+}
+
+#[test]
+fn keep_newer_keeps_whichever_history_is_later() {
+    test_root();
+    let newer_exchange = [("user", "Hi."), ("assistant", "Hello.")];
+
+    // The existing history is newer: it stays and the legacy file is set aside
+    let from = legacy_dir("newer-");
+    persona("newer-collide");
+    current("newer-collide", "2026-01-05T09:00:00+00:00", &newer_exchange);
+    let report = run(&from, true, Some(ConflictChoice::KeepNewer), false);
+    assert_eq!(report.files[0].disposition, Disposition::KeepCurrent);
+    assert_eq!(contents(&loaded("newer-collide")), newer_exchange);
+    assert!(names(&from.join("migrated")).contains(&"newer-collide_history.json".to_string()));
+
+    // The legacy file is newer: it replaces the history, which is set aside
+    let from = legacy_dir("older-");
+    persona("older-collide");
+    current("older-collide", "2025-01-05T09:00:00+00:00", &newer_exchange);
+    let report = run(&from, true, Some(ConflictChoice::KeepNewer), false);
+    assert_eq!(report.files[0].disposition, Disposition::ReplaceCurrent);
+    assert_eq!(contents(&loaded("older-collide")).len(), 4);
+    let set_aside = names(&from.join("migrated"));
+    assert!(set_aside.contains(&"older-collide_history.json".to_string()), "{:?}", set_aside);
+    let replaced = set_aside.iter().find(|name| name.starts_with("older-collide_history.replaced-")).expect("the replaced history is kept");
+    let kept: ConversationHistory = serde_json::from_str(&std::fs::read_to_string(from.join("migrated").join(replaced)).unwrap()).unwrap();
+    assert_eq!(contents(&kept), newer_exchange);
+}
+
+#[test]
+fn merging_keeps_both_conversations_oldest_first_without_the_overlap() {
+    test_root();
+    let from = legacy_dir("merge-");
+    persona("merge-collide");
+    current("merge-collide", "2026-01-05T09:00:00+00:00", &[
+        ("user", "Shorter please."),
+        ("assistant", "Done, shorter."),
+        ("user", "Post it."),
+        ("assistant", "Posted."),
+    ]);
+
+    let report = run(&from, true, Some(ConflictChoice::Merge), false);
+    assert_eq!(report.files[0].disposition, Disposition::Merge);
+    let merged = loaded("merge-collide");
+    assert_eq!(contents(&merged), [
+        ("user", "Draft the launch post."),
+        ("assistant", "Drafted."),
+        ("user", "Shorter please."),
+        ("assistant", "Done, shorter."),
+        ("user", "Post it."),
+        ("assistant", "Posted."),
+    ]);
+    assert_eq!(merged.total_message_count, 6);
+    assert_eq!(merged.last_updated, "2026-01-05T09:00:00+00:00", "settings come from the newer history");
+}
@@ -0,0 +1,124 @@
+//! The single-pane layout: chosen by width or by `layout`, with System behind
+//! a Ctrl+G overlay that keeps its own scroll and counts what arrives while hidden.
+//!
+//! The layout choice is saved for the next session, so these live in their
+//! own test binary and take turns with it.
+
+mod support;
+
+use std::sync::{Mutex, MutexGuard};
+use crossterm::event::KeyCode;
+use grokprime_brain::tui::layout::LayoutMode;
+use support::tui::{TuiHarness, SCREEN};
+
+static LAYOUT_TURN: Mutex<()> = Mutex::new(());
+
+/// A harness in `mode` with shadow open, and the saved layout to itself until the guard drops
+fn harness_in(mode: &str) -> (TuiHarness, MutexGuard<'static, ()>) {
+    let turn = LAYOUT_TURN.lock().unwrap_or_else(|e| e.into_inner());
+    let mut h = TuiHarness::new();
+    h.submit(&format!("layout {}", mode));
+    h.submit("new shadow");
+    (h, turn)
+}
+
+/// The agent pane's top border row
+fn agent_border(h: &TuiHarness) -> String {
+    h.screen().lines().find(|row| row.starts_with('┌')).unwrap_or_default().to_string()
+}
+
+/// Title of the agent pane, between the corners
+fn agent_title(h: &TuiHarness) -> String {
+    agent_border(h).trim_matches(|c| c == '┌' || c == '─' || c == '┐').to_string()
+}
+
+#[test]
+fn auto_layout_drops_to_one_pane_below_the_threshold() {
+    let (mut h, _turn) = harness_in("auto");
+    let (width, height) = SCREEN;
+    assert!(!h.app.single_pane, "{} columns is not below the default threshold of 100", width);
+    assert!(h.shows("System"), "{}", h.screen());
+
+    h.resize(90, height);
+    assert!(h.app.single_pane);
+    assert!(!h.shows("System"), "{}", h.screen());
+    let border = agent_border(&h);
+    assert!(border.chars().count() == 90 && border.ends_with('┐'), "the agent pane is full width:\n{}", h.screen());
+
+    h.resize(140, height);
+    assert!(!h.app.single_pane);
+    assert!(h.shows("System"), "{}", h.screen());
+}
+
+#[test]
+fn the_overlay_counts_unread_messages_only_while_hidden() {
+    let (mut h, _turn) = harness_in("single");
+    assert!(h.app.single_pane);
+    let hidden = h.app.unread_global;
+
+    h.submit("status");
+    h.submit("status");
+    assert_eq!(h.app.unread_global, hidden + 2);
+    assert!(agent_title(&h).contains(&format!("[{} new in System, Ctrl+G]", hidden + 2)), "{}", agent_title(&h));
+
+    h.ctrl('g');
+    assert!(h.app.global_overlay);
+    assert_eq!(h.app.unread_global, 0, "opening the overlay reads them");
+    assert!(h.shows("System (Ctrl+G to close)"), "{}", h.screen());
+    assert!(!agent_title(&h).contains("new in System"));
+
+    h.submit("status");
+    assert_eq!(h.app.unread_global, 0, "nothing is unread while the overlay is open");
+
+    h.ctrl('g');
+    assert!(!h.app.global_overlay);
+    assert!(!h.shows("Ctrl+G to close"));
+    h.submit("status");
+    assert_eq!(h.app.unread_global, 1, "counting resumes once it is hidden again");
+}
+
+#[test]
+fn the_overlay_scrolls_on_its_own() {
+    let (mut h, _turn) = harness_in("single");
+    for _ in 0..8 {
+        h.submit("status");
+    }
+    h.ctrl('g');
+    let agent_scroll = h.app.current_pane().map(|pane| pane.scroll);
+    let following = h.screen();
+
+    h.press(KeyCode::PageUp);
+    assert!(!h.app.overlay_follow, "scrolled back, it stops following new messages");
+    assert_ne!(h.screen(), following);
+    assert_eq!(h.app.current_pane().map(|pane| pane.scroll), agent_scroll, "the agent pane didn't move");
+
+    h.press(KeyCode::PageDown);
+    h.press(KeyCode::PageDown);
+    assert!(h.app.overlay_follow, "back at the bottom, it follows again");
+    assert_eq!(h.screen(), following);
+
+    h.press(KeyCode::Up);
+    h.ctrl('g');
+    h.ctrl('g');
+    assert!(h.app.overlay_follow, "reopening jumps to the newest message");
+    assert_eq!(h.screen(), following);
+}
+
+#[test]
+fn switching_to_split_closes_the_overlay_and_the_choice_is_remembered() {
+    let (mut h, _turn) = harness_in("single");
+    h.submit("status");
+    h.ctrl('g');
+    assert!(h.app.global_overlay);
+    assert_eq!(h.restart(None).app.layout_mode, LayoutMode::Single);
+
+    h.submit("layout split");
+    assert!(!h.app.single_pane);
+    assert!(!h.app.global_overlay);
+    assert_eq!(h.app.unread_global, 0);
+    assert!(h.shows("System"), "{}", h.screen());
+    assert_eq!(h.restart(None).app.layout_mode, LayoutMode::Split);
+
+    h.ctrl('g');
+    assert!(!h.app.global_overlay, "Ctrl+G does nothing with both panes shown");
+}
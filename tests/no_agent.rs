@@ -0,0 +1,95 @@
+//! Every command run in a fresh TUI with no agent open: the ones that need an
+//! agent all answer with the same guidance in the Global pane, and the ones
+//! meant for getting started work in full.
+
+mod support;
+
+use std::sync::Arc;
+use grokprime_brain::commands::{CommandResult, NO_AGENT_GUIDANCE};
+use grokprime_brain::llm::AnyClient;
+use grokprime_brain::llm::scripted::ScriptedClient;
+use grokprime_brain::tui::app::ShadowApp;
+use support::*;
+use support::tui::assert_text_snapshot;
+
+/// One line per command that needs an agent
+const NEEDS_AGENT: [&str; 30] = [
+    "hello there",
+    "attach notes.txt",
+    "clearhistory",
+    "close",
+    "tweet confirm",
+    "draft a launch post",
+    "history dedupe",
+    "explain",
+    "historyinfo",
+    "history list",
+    "history remove 1",
+    "inspect",
+    "language",
+    "archives",
+    "model",
+    "mute",
+    "nudge be brief",
+    "nudge clear",
+    "nudge list",
+    "open",
+    "rate good",
+    "resync fresh",
+    "rollback",
+    "savehistory",
+    "sent",
+    "set summarize auto",
+    "speak on",
+    "stop",
+    "summarize",
+    "whence",
+];
+
+fn empty_app() -> ShadowApp {
+    test_root();
+    let mut app = ShadowApp::new();
+    app.agent_manager.client_override = Some(AnyClient::Scripted(ScriptedClient::default()));
+    for name in ["shadow", "friday"] {
+        app.agent_manager.personas.insert(name.to_string(), Arc::new(repo_persona(name)));
+    }
+    app.unified_messages.clear();
+    app
+}
+
+/// Runs `line` and returns what it added to the Global pane, errors included
+fn answer(app: &mut ShadowApp, line: &str) -> Vec<String> {
+    let before = app.unified_messages.len();
+    let result = run_line(app, line);
+    let mut shown: Vec<String> = app.unified_messages.iter().skip(before).map(|msg| msg.text.clone()).collect();
+    if let CommandResult::Error(msg) = result {
+        shown.push(format!("Error: {}", msg));
+    }
+    shown
+}
+
+#[test]
+fn commands_that_need_an_agent_all_say_how_to_open_one() {
+    let mut app = empty_app();
+
+    for line in NEEDS_AGENT {
+        assert_eq!(answer(&mut app, line), [NO_AGENT_GUIDANCE], "{}", line);
+        assert!(app.agent_manager.agents.is_empty(), "{} opened an agent", line);
+    }
+}
+
+#[test]
+fn getting_started_commands_work_without_an_agent() {
+    let mut app = empty_app();
+    let mut transcript = String::new();
+
+    for line in ["help", "list", "status"].into_iter().chain(NEEDS_AGENT) {
+        transcript.push_str(&format!("> {}\n", line));
+        for shown in answer(&mut app, line) {
+            transcript.push_str(&shown);
+            transcript.push('\n');
+        }
+    }
+
+    assert_text_snapshot("no_agent_guidance", &transcript);
+}
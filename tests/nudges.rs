@@ -0,0 +1,143 @@
+//! `nudge` on an open agent: what the next requests carry, how the pane shows
+//! it, and the places a nudge must not pass for the user talking (tweet
+//! drafts, the historian's transcript).
+
+mod support;
+
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+use grokprime_brain::llm::AnyClient;
+use grokprime_brain::llm::request::ChatRequest;
+use grokprime_brain::llm::scripted::{ScriptedClient, ScriptedReply};
+use grokprime_brain::persona::operations::AgentOperations;
+use grokprime_brain::tui::app::ShadowApp;
+use support::*;
+
+fn app_with_agent(client: &ScriptedClient, storage_name: &str) -> ShadowApp {
+    test_root();
+    let mut app = ShadowApp::new();
+    app.agent_manager.client_override = Some(AnyClient::Scripted(client.clone()));
+    let mut persona = builtin_as("shadow", storage_name);
+    persona.enable_history = false;
+    persona.history_message_limit = 2;
+    app.add_new_agent(Uuid::new_v4(), Arc::new(persona));
+    app
+}
+
+fn last_line(app: &ShadowApp) -> String {
+    app.unified_messages.iter().last().map(|msg| msg.text.clone()).unwrap_or_default()
+}
+
+/// Roles and contents of a request's input, the system prompt of a full-history request left out
+fn input(request: &ChatRequest) -> Vec<(String, String)> {
+    let prompt = usize::from(request.previous_response_id.is_none());
+    request.input.iter().skip(prompt).map(|msg| (msg.role.clone(), msg.content.clone())).collect()
+}
+
+fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+    expected.iter().map(|(role, content)| (role.to_string(), content.to_string())).collect()
+}
+
+async fn exchange(app: &mut ShadowApp, client: &ScriptedClient, question: &str, reply: &str) {
+    client.push(ScriptedReply::chunks([reply]));
+    run_line(app, question);
+    wait_for_reply(&mut app.agent_manager).await;
+}
+
+/// Polls until the jobs are done and the pane has stopped waiting
+async fn finish_jobs(app: &mut ShadowApp) {
+    tokio::time::timeout(REPLY_TIMEOUT, async {
+        loop {
+            app.agent_manager.poll_channels();
+            if app.agent_manager.jobs.is_empty() && app.agent_manager.current_pane().is_none_or(|agent| !agent.is_waiting) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }).await.expect("the jobs never finished");
+}
+
+#[tokio::test]
+async fn a_nudge_steers_every_request_until_cleared() {
+    let client = ScriptedClient::default();
+    let mut app = app_with_agent(&client, "nudge-steers");
+    exchange(&mut app, &client, "hi", "Hello.").await;
+
+    run_line(&mut app, "nudge from now on answer in bullet points");
+    assert_eq!(last_line(&app), "Nudge added (1 active); it goes out with your next message. `nudge clear` stops it.");
+    assert_eq!(client.requests().len(), 1, "nothing is sent right away");
+
+    exchange(&mut app, &client, "what's next?", "- Lunch.").await;
+    let requests = client.requests();
+    assert_eq!(requests[1].previous_response_id.as_deref(), Some("scripted-1"));
+    assert_eq!(input(&requests[1]), pairs(&[("system", "from now on answer in bullet points"), ("user", "what's next?")]));
+    let lines = pane_lines(&app.agent_manager);
+    assert_eq!(lines[lines.len() - 3..], [
+        "[nudge] from now on answer in bullet points".to_string(),
+        "> what's next?".to_string(),
+        "- Lunch.".to_string(),
+    ]);
+
+    exchange(&mut app, &client, "and then?", "- Nap.").await;
+    assert_eq!(input(&client.requests()[2]), pairs(&[("user", "and then?")]), "the thread already holds it");
+
+    run_line(&mut app, "nudge list");
+    assert_eq!(last_line(&app), "Active nudges:\n 1. from now on answer in bullet points");
+
+    run_line(&mut app, "nudge clear");
+    assert_eq!(last_line(&app), "Cleared 1 nudge(s); the next message sends the full history without them.");
+    run_line(&mut app, "nudge list");
+    assert_eq!(last_line(&app), "No active nudges.");
+
+    exchange(&mut app, &client, "recap?", "Lunch, then a nap.").await;
+    let cleared = &client.requests()[3];
+    assert_eq!(cleared.previous_response_id, None);
+    assert_eq!(input(cleared), pairs(&[
+        ("user", "hi"),
+        ("assistant", "Hello."),
+        ("user", "what's next?"),
+        ("assistant", "- Lunch."),
+        ("user", "and then?"),
+        ("assistant", "- Nap."),
+        ("user", "recap?"),
+    ]), "the record stays in history but isn't sent");
+}
+
+#[tokio::test]
+async fn a_draft_is_not_steered_by_a_nudge() {
+    let client = ScriptedClient::default();
+    let mut app = app_with_agent(&client, "nudge-draft");
+    exchange(&mut app, &client, "hi", "Hello.").await;
+    run_line(&mut app, "nudge answer only in French");
+    exchange(&mut app, &client, "ça va?", "Oui.").await;
+
+    client.push(ScriptedReply::text("Shipping nudges today."));
+    run_line(&mut app, "draft nudges shipped");
+    finish_jobs(&mut app).await;
+
+    let draft = &client.requests()[2];
+    assert_eq!(draft.previous_response_id, None, "the thread holds the nudge, so it isn't continued");
+    assert!(draft.input.iter().all(|msg| msg.content != "answer only in French"), "{:?}", input(draft));
+
+    exchange(&mut app, &client, "encore?", "Oui.").await;
+    assert_eq!(input(&client.requests()[3])[..1], pairs(&[("system", "answer only in French")]), "chat is nudged again");
+}
+
+#[tokio::test]
+async fn the_historian_sees_a_nudge_as_an_instruction() {
+    let client = ScriptedClient::default();
+    let mut app = app_with_agent(&client, "nudge-summary");
+    run_line(&mut app, "nudge answer in bullet points");
+    exchange(&mut app, &client, "plan my day", "- Work.").await;
+    exchange(&mut app, &client, "and the evening?", "- Rest.").await;
+
+    client.push(ScriptedReply::text("- Planned the day in bullets."));
+    run_line(&mut app, "summarize");
+    finish_jobs(&mut app).await;
+
+    let transcript = last_user_message(client.requests().last().expect("the historian was asked")).to_string();
+    assert!(transcript.contains("INSTRUCTION: answer in bullet points"), "{}", transcript);
+    assert!(!transcript.contains("USER: answer in bullet points"), "{}", transcript);
+    assert!(transcript.contains("INSTRUCTION lines are steering instructions given to the assistant, not things the user said."), "{}", transcript);
+}
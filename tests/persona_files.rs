@@ -0,0 +1,207 @@
+//! What an open agent does when the YAML behind its persona is deleted,
+//! broken, or restored: through the watcher's reload plans, and through the
+//! check before each send when nothing is watching.
+//!
+//! Watcher events go straight into a `ReloadAggregator` with no quiet window,
+//! as `PersonaWatcher::poll` would record them, so no test waits on the OS.
+
+mod support;
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+use grokprime_brain::llm::AnyClient;
+use grokprime_brain::llm::scripted::{ScriptedClient, ScriptedReply};
+use grokprime_brain::persona::PersonaSource;
+use grokprime_brain::persona::agent::AgentInfo;
+use grokprime_brain::persona::agent_manager::AgentManager;
+use grokprime_brain::persona::operations::AgentOperations;
+use grokprime_brain::persona::reload::{ReloadAggregator, ReloadReport};
+use grokprime_brain::tui::app::ShadowApp;
+use support::*;
+
+const WARNING_END: &str = "— agent continues with last good configuration";
+
+fn yaml(name: &str, prompt: &str) -> String {
+    format!("name: {}\nsystem_prompt: {}\nenable_history: false\n", name, prompt)
+}
+
+/// Writes `{name}/{name}.yaml` and registers it the way startup discovery does
+fn load_persona_file(manager: &mut AgentManager, name: &str) -> PathBuf {
+    let path = write_file(&format!("{0}/{0}.yaml", name), &yaml(name, "Be brief."));
+    let skipped = manager.load_personas(vec![&PersonaSource::File(path.clone())]);
+    assert!(skipped.is_empty(), "{:?}", skipped);
+    path
+}
+
+fn read(path: &Path) -> Option<Vec<u8>> {
+    std::fs::read(path).ok()
+}
+
+/// Feeds one watcher event for `path` and applies the plan it settles into
+fn watcher_sees(manager: &mut AgentManager, aggregator: &mut ReloadAggregator, path: &Path) -> ReloadReport {
+    let now = Instant::now();
+    aggregator.record(path.to_path_buf(), now);
+    let plan = aggregator.take_plan(now, read).expect("the event settles into a plan");
+    manager.apply_reload(&plan)
+}
+
+fn agent(manager: &AgentManager, id: Uuid) -> &AgentInfo {
+    manager.agents.get(&id).expect("agent is open")
+}
+
+fn lines(manager: &AgentManager, id: Uuid) -> Vec<String> {
+    agent(manager, id).messages.iter().map(|msg| msg.text.clone()).collect()
+}
+
+#[test]
+fn corrupt_delete_restore_through_the_watcher() {
+    test_root();
+    let mut manager = manager_with(&ScriptedClient::default());
+    let path = load_persona_file(&mut manager, "watched");
+    let id = Uuid::new_v4();
+    manager.add_agent(id, manager.personas["watched"].clone());
+    let mut aggregator = ReloadAggregator::new(Duration::ZERO, [path.clone()], read);
+
+    // Broken YAML: the agent is flagged once and keeps its persona
+    std::fs::write(&path, "name: [watched\n").unwrap();
+    let report = watcher_sees(&mut manager, &mut aggregator, &path);
+    assert_eq!(report.failures.len(), 1);
+    let issue = agent(&manager, id).persona_issue.clone().expect("flagged as invalid");
+    assert!(issue.starts_with(&format!("persona file {} invalid", path.display())), "{}", issue);
+    assert_eq!(lines(&manager, id), [format!("Warning: {} {}", issue, WARNING_END)]);
+    assert_eq!(manager.personas["watched"].system_prompt, "Be brief.", "the last good version stays");
+
+    // Broken differently: the issue is updated without another pane message
+    std::fs::write(&path, "name: watched\nsystem_prompt: [\n").unwrap();
+    watcher_sees(&mut manager, &mut aggregator, &path);
+    assert_ne!(agent(&manager, id).persona_issue.as_ref(), Some(&issue));
+    assert_eq!(lines(&manager, id).len(), 1);
+
+    // Deleted: now missing, still one message
+    std::fs::remove_file(&path).unwrap();
+    watcher_sees(&mut manager, &mut aggregator, &path);
+    assert_eq!(agent(&manager, id).persona_issue, Some(format!("persona file {} missing", path.display())));
+    assert_eq!(lines(&manager, id).len(), 1);
+    assert!(!manager.personas.contains_key("watched"), "no built-in to fall back to");
+
+    // Restored: the flag clears and the pane says so once
+    std::fs::write(&path, yaml("watched", "Be briefer.")).unwrap();
+    let report = watcher_sees(&mut manager, &mut aggregator, &path);
+    assert!(report.failures.is_empty());
+    assert_eq!(agent(&manager, id).persona_issue, None);
+    assert_eq!(
+        lines(&manager, id).last().map(String::as_str),
+        Some("Persona file for watched is valid again; open a new agent with 'new watched' to use it")
+    );
+    assert_eq!(lines(&manager, id).len(), 2);
+    assert_eq!(manager.personas["watched"].system_prompt, "Be briefer.");
+
+    // A further good edit says nothing
+    std::fs::write(&path, yaml("watched", "Be briefest.")).unwrap();
+    watcher_sees(&mut manager, &mut aggregator, &path);
+    assert_eq!(lines(&manager, id).len(), 2);
+}
+
+#[test]
+fn a_deletion_is_flagged_only_on_agents_of_that_file() {
+    test_root();
+    let mut manager = manager_with(&ScriptedClient::default());
+    let gone = load_persona_file(&mut manager, "doomed");
+    load_persona_file(&mut manager, "spared");
+    let doomed = Uuid::new_v4();
+    manager.add_agent(doomed, manager.personas["doomed"].clone());
+    let spared = Uuid::new_v4();
+    manager.add_agent(spared, manager.personas["spared"].clone());
+    let mut aggregator = ReloadAggregator::new(Duration::ZERO, [gone.clone()], read);
+
+    std::fs::remove_file(&gone).unwrap();
+    watcher_sees(&mut manager, &mut aggregator, &gone);
+
+    assert!(agent(&manager, doomed).persona_issue.is_some());
+    assert_eq!(agent(&manager, spared).persona_issue, None);
+    assert!(lines(&manager, spared).is_empty());
+}
+
+#[tokio::test]
+async fn without_a_watcher_the_file_is_checked_before_each_send() {
+    test_root();
+    let client = ScriptedClient::default();
+    let mut manager = manager_with(&client);
+    let path = load_persona_file(&mut manager, "unwatched");
+    let id = Uuid::new_v4();
+    manager.add_agent(id, manager.personas["unwatched"].clone());
+
+    std::fs::remove_file(&path).unwrap();
+    client.push(ScriptedReply::text("Still here."));
+    run_line(&mut manager, "are you there?");
+    wait_for_reply(&mut manager).await;
+    assert_eq!(lines(&manager, id), [
+        format!("Warning: persona file {} missing {}", path.display(), WARNING_END),
+        "> are you there?".to_string(),
+        "Still here.".to_string(),
+    ]);
+    assert_eq!(system_prompt(&client.requests()[0]), "Be brief.", "sent with the last good configuration");
+
+    // Still missing: no second warning
+    client.push(ScriptedReply::text("Yes."));
+    run_line(&mut manager, "still?");
+    wait_for_reply(&mut manager).await;
+    assert_eq!(lines(&manager, id).len(), 5);
+
+    std::fs::write(&path, yaml("unwatched", "Be brief.")).unwrap();
+    client.push(ScriptedReply::text("Good."));
+    run_line(&mut manager, "fixed");
+    wait_for_reply(&mut manager).await;
+    assert_eq!(lines(&manager, id)[5], "Persona file for unwatched is valid again; open a new agent with 'new unwatched' to use it");
+    assert_eq!(agent(&manager, id).persona_issue, None);
+}
+
+#[test]
+fn status_shows_the_problem_until_it_is_fixed() {
+    test_root();
+    let mut app = ShadowApp::new();
+    app.agent_manager.client_override = Some(AnyClient::Scripted(ScriptedClient::default()));
+    let path = load_persona_file(&mut app.agent_manager, "reported");
+    let persona = app.agent_manager.personas["reported"].clone();
+    app.add_new_agent(Uuid::new_v4(), persona);
+    let mut aggregator = ReloadAggregator::new(Duration::ZERO, [path.clone()], read);
+
+    let status = |app: &mut ShadowApp| {
+        run_line(app, "status");
+        app.unified_messages.iter().last().map(|msg| msg.text.clone()).unwrap_or_default()
+    };
+
+    std::fs::remove_file(&path).unwrap();
+    watcher_sees(&mut app.agent_manager, &mut aggregator, &path);
+    let shown = status(&mut app);
+    assert!(shown.contains(&format!("Reported ⚠ persona file {} missing", path.display())), "{}", shown);
+
+    std::fs::write(&path, yaml("reported", "Be brief.")).unwrap();
+    watcher_sees(&mut app.agent_manager, &mut aggregator, &path);
+    let shown = status(&mut app);
+    assert!(shown.contains("-> Reported\n"), "{}", shown);
+    assert!(!shown.contains("⚠"), "{}", shown);
+}
+
+#[test]
+fn editing_only_the_prompt_file_reloads_the_persona() {
+    test_root();
+    let mut manager = manager_with(&ScriptedClient::default());
+    write_file("prompted/prompt.md", "Be brief.");
+    let path = write_file("prompted/prompted.yaml", "name: prompted\nsystem_prompt_file: prompt.md\nenable_history: false\n");
+    let skipped = manager.load_personas(vec![&PersonaSource::File(path.clone())]);
+    assert!(skipped.is_empty(), "{:?}", skipped);
+    assert_eq!(manager.personas["prompted"].system_prompt, "Be brief.");
+    let mut aggregator = ReloadAggregator::new(Duration::ZERO, [path.clone()], read);
+
+    let prompt = write_file("prompted/prompt.md", "Be briefer.");
+    let report = watcher_sees(&mut manager, &mut aggregator, &prompt);
+    assert!(report.failures.is_empty(), "{:?}", report.failures);
+    assert_eq!(manager.personas["prompted"].system_prompt, "Be briefer.");
+
+    std::fs::remove_file(&prompt).unwrap();
+    let report = watcher_sees(&mut manager, &mut aggregator, &prompt);
+    assert_eq!(report.failures.len(), 1, "a deleted prompt file breaks its persona");
+    assert_eq!(manager.personas["prompted"].system_prompt, "Be briefer.", "the last good version stays");
+}
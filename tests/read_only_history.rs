@@ -0,0 +1,119 @@
+//! History writes on a disk that refuses them: the first read-only or
+//! permission failure suspends automatic writes, and an explicit `savehistory` that
+//! succeeds resumes them.
+//!
+//! Suspension is process-wide, so these live in their own test binary and take
+//! turns with it. Tests run as root can't make a directory unwritable, so the
+//! failing writes are closures returning the error kinds a read-only mount gives.
+
+mod support;
+
+use std::cell::Cell;
+use std::io;
+use std::sync::{Mutex, MutexGuard};
+use grokprime_brain::agent_history::history::HistoryManager;
+use grokprime_brain::agent_history::persistence::{self, PersistenceState};
+use grokprime_brain::llm::scripted::ScriptedReply;
+use support::tui::TuiHarness;
+
+static PERSISTENCE_TURN: Mutex<()> = Mutex::new(());
+
+/// Persistence active, and to this test until the guard drops
+fn turn() -> MutexGuard<'static, ()> {
+    let turn = PERSISTENCE_TURN.lock().unwrap_or_else(|e| e.into_inner());
+    persistence::resume();
+    turn
+}
+
+fn failing(kind: io::ErrorKind) -> Result<(), Box<dyn std::error::Error>> {
+    Err(io::Error::from(kind).into())
+}
+
+#[test]
+fn failing_suspended_recovered() {
+    let _turn = turn();
+    let attempts = Cell::new(0);
+    let count = || {
+        attempts.set(attempts.get() + 1);
+        Ok(())
+    };
+
+    assert_eq!(persistence::auto_write(count).unwrap(), Some(()));
+
+    // An ordinary failure is reported and writes go on
+    assert!(persistence::auto_write(|| failing(io::ErrorKind::NotFound)).is_err());
+    assert_eq!(persistence::state(), PersistenceState::Active);
+
+    // The first read-only failure is reported once, then suspends
+    assert!(persistence::auto_write(|| failing(io::ErrorKind::ReadOnlyFilesystem)).is_err());
+    assert_eq!(persistence::warning().as_deref(), Some("history not being saved: read-only filesystem"));
+    for _ in 0..3 {
+        assert_eq!(persistence::auto_write(count).unwrap(), None, "skipped without an error");
+    }
+    assert_eq!(attempts.get(), 1, "nothing was attempted while suspended");
+
+    // An explicit save still tries; failing again keeps it suspended with the new reason
+    assert!(persistence::explicit_write(|| failing(io::ErrorKind::PermissionDenied)).is_err());
+    assert_eq!(persistence::suspended_reason().as_deref(), Some("permission denied"));
+    assert!(persistence::explicit_write(|| failing(io::ErrorKind::NotFound)).is_err());
+    assert!(persistence::suspended_reason().is_some(), "an unrelated failure doesn't resume");
+
+    // After a remount an explicit save succeeds and automatic writes resume
+    persistence::explicit_write(count).unwrap();
+    assert_eq!(persistence::state(), PersistenceState::Active);
+    assert_eq!(persistence::auto_write(count).unwrap(), Some(()));
+    assert_eq!(attempts.get(), 3);
+}
+
+#[test]
+fn suspend_and_resume_report_only_the_transition() {
+    let _turn = turn();
+    assert!(persistence::suspend("read-only filesystem"));
+    assert!(!persistence::suspend("read-only filesystem"), "already suspended");
+    assert!(persistence::resume());
+    assert!(!persistence::resume(), "already active");
+}
+
+#[test]
+fn a_probe_of_a_writable_directory_leaves_writes_on() {
+    let _turn = turn();
+    let dir = support::test_root().join("probed");
+    persistence::probe_writable(&dir).unwrap();
+    assert!(dir.is_dir());
+    assert!(!dir.join(".write_probe").exists(), "the probe cleans up after itself");
+    assert_eq!(persistence::state(), PersistenceState::Active);
+}
+
+// The turn has to span the whole scenario; nothing else runs on this test's runtime
+#[allow(clippy::await_holding_lock)]
+#[tokio::test]
+async fn while_suspended_exchanges_skip_saving_quietly_until_save_works() {
+    let _turn = turn();
+    let mut h = TuiHarness::with_history("suspended");
+    h.submit("new shadow");
+    persistence::suspend("read-only filesystem");
+
+    for reply in ["One.", "Two."] {
+        h.client.push(ScriptedReply::text(reply));
+        h.submit("count");
+        h.settle().await;
+    }
+    assert!(!HistoryManager::history_exists("suspended-shadow"), "nothing was written");
+    let pane: Vec<String> = h.app.agent_manager.current_pane().expect("shadow is open")
+        .messages.iter().map(|msg| msg.text.clone()).collect();
+    assert!(!pane.iter().chain(&h.system_lines()).any(|line| line.contains("Failed to save")), "{:?}", pane);
+    assert!(h.shows("history not being saved: read-only filesystem"), "{}", h.screen());
+
+    h.submit("savehistory");
+    assert_eq!(persistence::state(), PersistenceState::Active);
+    assert!(h.system_lines().iter().any(|line| line == "History is writable again; automatic saves resumed."), "{:?}", h.system_lines());
+    assert!(!h.shows("history not being saved"), "{}", h.screen());
+    let saved = HistoryManager::load_persona_history("suspended-shadow").expect("saved by hand");
+    assert!(saved.recent_messages.iter().any(|msg| msg.content == "Two."));
+
+    h.client.push(ScriptedReply::text("Three."));
+    h.submit("count");
+    h.settle().await;
+    let saved = HistoryManager::load_persona_history("suspended-shadow").expect("saved automatically");
+    assert!(saved.recent_messages.iter().any(|msg| msg.content == "Three."), "automatic saves are back");
+}
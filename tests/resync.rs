@@ -0,0 +1,147 @@
+//! Reopening an agent after a long break: the one-off context note and the
+//! banner offering a recap, and `resync fresh` against archiving and
+//! clearing by hand.
+
+mod support;
+
+use std::sync::Arc;
+use uuid::Uuid;
+use chrono::{Duration, Utc};
+use grokprime_brain::agent_history::conversations::{GrokConversation, LONG_TERM_PREFIX};
+use grokprime_brain::agent_history::history::HistoryManager;
+use grokprime_brain::llm::AnyClient;
+use grokprime_brain::llm::client::Connection;
+use grokprime_brain::llm::scripted::{ScriptedClient, ScriptedReply};
+use grokprime_brain::models::{ConversationHistory, Message};
+use grokprime_brain::persona::Persona;
+use grokprime_brain::persona::operations::AgentOperations;
+use grokprime_brain::tui::app::ShadowApp;
+use support::*;
+
+const NOTE_START: &str = "Note: the last exchange was 16 days ago.";
+
+fn message(role: &str, content: &str) -> Message {
+    Message { role: role.to_string(), content: content.to_string(), meta: None, source: None }
+}
+
+/// A history-keeping copy of shadow whose saved history ended 16 days ago
+fn stale_persona(storage_name: &str) -> Persona {
+    test_root();
+    let mut persona = builtin_as("shadow", storage_name);
+    persona.enable_history = true;
+    let mut conversation = GrokConversation::new(Arc::new(persona.clone()));
+    conversation.local_history.extend([
+        message("system", &format!("{}- Ana runs the beta.]", LONG_TERM_PREFIX)),
+        message("user", "Ship the beta by Friday."),
+        message("assistant", "Friday it is."),
+    ]);
+    conversation.last_exchange_at = Some(Utc::now() - Duration::days(16));
+    HistoryManager::save_persona_history(&conversation).expect("stale history saved");
+    persona
+}
+
+/// An app whose agents reply from `client`, with one open on `persona`
+fn app_with(client: &ScriptedClient, persona: Persona) -> (ShadowApp, Uuid) {
+    let mut app = ShadowApp::new();
+    app.agent_manager.client_override = Some(AnyClient::Scripted(client.clone()));
+    let id = Uuid::new_v4();
+    app.add_new_agent(id, Arc::new(persona));
+    (app, id)
+}
+
+/// The last line shown in the Global pane
+fn last_line(app: &ShadowApp) -> Option<String> {
+    app.unified_messages.iter().last().map(|msg| msg.text.clone())
+}
+
+fn notes(messages: &[Message]) -> Vec<&str> {
+    messages.iter()
+        .filter(|msg| msg.content.starts_with("Note: the last exchange"))
+        .map(|msg| msg.content.as_str())
+        .collect()
+}
+
+/// Summary, long-term summary, recent messages, and the two counts
+type Stored = (Option<String>, Option<String>, Vec<(String, String)>, usize, usize);
+
+/// What a saved history holds, minus when and under which name it was saved
+fn stored(storage_name: &str) -> Stored {
+    let ConversationHistory { summary, long_term_summary, recent_messages, total_message_count, summarization_count, .. } =
+        HistoryManager::load_persona_history(storage_name).expect("history was saved");
+    let recent = recent_messages.into_iter().map(|msg| (msg.role, msg.content)).collect();
+    (summary, long_term_summary, recent, total_message_count, summarization_count)
+}
+
+/// Contents of the persona's only archive
+fn archived(storage_name: &str) -> Vec<(String, String)> {
+    let archives = HistoryManager::list_archives(storage_name).expect("archives listed");
+    assert_eq!(archives.len(), 1, "{}", storage_name);
+    HistoryManager::load_archive(&archives[0].path).expect("archive loads")
+        .into_iter()
+        .map(|msg| (msg.role, msg.content))
+        .collect()
+}
+
+#[tokio::test]
+async fn the_first_request_after_a_long_break_carries_a_note() {
+    let client = ScriptedClient::default();
+    let (mut app, id) = app_with(&client, stale_persona("resync-note"));
+
+    let banner = pane_lines(&app.agent_manager).into_iter().find(|line| line.contains("Last exchange was 16 days ago.")).expect("a banner");
+    assert!(banner.contains("`resync recap` (Ctrl+R)") && banner.contains("`resync fresh` (Ctrl+F)"), "{}", banner);
+    assert!(app.agent_manager.agents[&id].resync_offer.is_some());
+
+    client.push(ScriptedReply::text("Welcome back."));
+    run_line(&mut app, "where were we?");
+    wait_for_reply(&mut app.agent_manager).await;
+    client.push(ScriptedReply::text("Still Friday."));
+    run_line(&mut app, "and the beta?");
+    wait_for_reply(&mut app.agent_manager).await;
+
+    let requests = client.requests();
+    let first = &requests[0].input;
+    let sent_notes = notes(first);
+    assert_eq!(sent_notes.len(), 1, "{:?}", first);
+    assert!(sent_notes[0].starts_with(NOTE_START), "{}", sent_notes[0]);
+    assert!(first[first.len() - 2].content.starts_with(NOTE_START), "just before the newest message");
+    assert!(notes(&requests[1].input).is_empty(), "only the first request carries it");
+
+    let conn = app.agent_manager.agents[&id].connection.try_lock().expect("connection is idle");
+    assert!(notes(conn.local_history()).is_empty(), "never part of the conversation");
+    drop(conn);
+    assert!(stored("resync-note").2.iter().all(|(_, content)| !content.starts_with("Note:")), "nor of what's saved");
+
+    // Carrying on answered the offer
+    assert!(app.agent_manager.agents[&id].resync_offer.is_none());
+    run_line(&mut app, "resync fresh");
+    assert_eq!(last_line(&app).as_deref(), Some("Nothing to resync: this conversation hasn't been away long."));
+}
+
+#[tokio::test]
+async fn resync_fresh_matches_archiving_and_clearing_by_hand() {
+    let client = ScriptedClient::default();
+    let (mut app, _) = app_with(&client, stale_persona("resync-command"));
+
+    run_line(&mut app, "resync fresh");
+    assert_eq!(last_line(&app).as_deref(), Some("Archived 2 message(s) and started fresh; the long-term summary was kept."));
+
+    // The same history, archived and cleared by hand
+    let mut by_hand = Connection::new_without_output(client.clone(), Arc::new(stale_persona("resync-manual")));
+    HistoryManager::archive_full_history(&by_hand.conversation).expect("archived by hand");
+    by_hand.conversation.fresh_start();
+    by_hand.save_persona_history().expect("saved by hand");
+
+    assert_eq!(stored("resync-command"), stored("resync-manual"));
+    assert_eq!(archived("resync-command"), archived("resync-manual"));
+    let (summary, long_term, recent, _, _) = stored("resync-command");
+    assert_eq!((summary, long_term.as_deref(), recent.len()), (None, Some("- Ana runs the beta."), 0));
+    assert_eq!(archived("resync-command").len(), 4, "prompt, long-term summary and the exchange");
+
+    // Nothing is left to be stale, so the next request goes without the note
+    client.push(ScriptedReply::text("Hello."));
+    run_line(&mut app, "hi");
+    wait_for_reply(&mut app.agent_manager).await;
+    let input = &client.requests()[0].input;
+    assert!(notes(input).is_empty(), "{:?}", input);
+    assert_eq!(last_user_message(&client.requests()[0]), "hi");
+}
@@ -0,0 +1,114 @@
+//! What an agent was sent versus what was typed: the pane echo, the request,
+//! the conversation history, and `sent` all agree on the preprocessed message.
+
+mod support;
+
+use std::sync::Arc;
+use uuid::Uuid;
+use grokprime_brain::llm::AnyClient;
+use grokprime_brain::llm::scripted::{ScriptedClient, ScriptedReply};
+use grokprime_brain::persona::operations::AgentOperations;
+use grokprime_brain::tui::app::ShadowApp;
+use support::*;
+
+fn app_with_agent(client: &ScriptedClient, storage_name: &str) -> (ShadowApp, Uuid) {
+    test_root();
+    let mut app = ShadowApp::new();
+    app.agent_manager.client_override = Some(AnyClient::Scripted(client.clone()));
+    let id = Uuid::new_v4();
+    let mut persona = builtin_as("shadow", storage_name);
+    persona.enable_history = false;
+    app.add_new_agent(id, Arc::new(persona));
+    (app, id)
+}
+
+fn user_history(app: &ShadowApp, id: Uuid) -> Vec<String> {
+    let agent = app.agent_manager.agents.get(&id).expect("agent is open");
+    let conn = agent.connection.try_lock().expect("connection is idle");
+    conn.local_history().iter().filter(|msg| msg.role == "user").map(|msg| msg.content.clone()).collect()
+}
+
+fn last_line(app: &ShadowApp) -> String {
+    app.unified_messages.iter().last().map(|msg| msg.text.clone()).unwrap_or_default()
+}
+
+#[tokio::test]
+async fn a_trimmed_message_is_echoed_sent_and_kept_the_same_way() {
+    let client = ScriptedClient::default();
+    let (mut app, id) = app_with_agent(&client, "sent-trimmed");
+
+    client.push(ScriptedReply::text("Plenty."));
+    run_line(&mut app, "what changed?   ");
+    wait_for_reply(&mut app.agent_manager).await;
+
+    assert_eq!(last_user_message(&client.requests()[0]), "what changed?");
+    assert_eq!(user_history(&app, id), ["what changed?"]);
+    let pane = pane_lines(&app.agent_manager);
+    assert_eq!(pane[0], "> what changed? ✎", "{:?}", pane);
+
+    run_line(&mut app, "sent");
+    let shown = last_line(&app);
+    let lines: Vec<&str> = shown.lines().collect();
+    assert_eq!(lines[..2], ["Sent message #1 from the end:", "what changed?"]);
+    assert_eq!(lines[2], "Changed by: trim. Typed → sent:");
+    assert_eq!(lines[3..], ["- what changed?   ", "+ what changed?"]);
+}
+
+#[tokio::test]
+async fn an_unchanged_message_has_no_badge_or_diff() {
+    let client = ScriptedClient::default();
+    let (mut app, id) = app_with_agent(&client, "sent-unchanged");
+
+    client.push(ScriptedReply::text("Hi."));
+    run_line(&mut app, "hello");
+    wait_for_reply(&mut app.agent_manager).await;
+
+    assert_eq!(pane_lines(&app.agent_manager)[0], "> hello");
+    assert_eq!(user_history(&app, id), ["hello"]);
+    run_line(&mut app, "sent");
+    assert_eq!(last_line(&app), "Sent message #1 from the end:\nhello");
+
+    run_line(&mut app, "sent 2");
+    assert_eq!(last_line(&app), "No sent message #2 from the end");
+}
+
+#[tokio::test]
+async fn a_draft_echoes_the_idea_but_history_holds_the_template() {
+    let client = ScriptedClient::default();
+    let (mut app, id) = app_with_agent(&client, "sent-draft");
+
+    client.push(ScriptedReply::text("Pipelines everywhere. #rust"));
+    run_line(&mut app, "draft pipelines everywhere");
+    wait_for_reply(&mut app.agent_manager).await;
+
+    let sent = last_user_message(&client.requests()[0]).to_string();
+    assert!(sent.contains(r#"content: "pipelines everywhere""#), "{}", sent);
+    assert_eq!(user_history(&app, id), [sent.as_str()], "history keeps exactly what was sent");
+    assert_eq!(pane_lines(&app.agent_manager)[0], "> Tweet Draft: pipelines everywhere ✎");
+
+    run_line(&mut app, "sent");
+    let shown = last_line(&app);
+    assert!(shown.starts_with(&format!("Sent message #1 from the end:\n{}\nChanged by: draft template.", sent)), "{}", shown);
+    assert!(shown.contains("\n- pipelines everywhere\n"), "{}", shown);
+}
+
+#[tokio::test]
+async fn a_message_summarized_away_is_still_shown_from_the_record() {
+    let client = ScriptedClient::default();
+    let (mut app, id) = app_with_agent(&client, "sent-forgotten");
+
+    client.push(ScriptedReply::text("Noted."));
+    run_line(&mut app, "remember the blue door");
+    wait_for_reply(&mut app.agent_manager).await;
+    {
+        let agent = app.agent_manager.agents.get(&id).expect("agent is open");
+        let mut conn = agent.connection.try_lock().expect("connection is idle");
+        conn.conversation.local_history.retain(|msg| msg.role != "user");
+    }
+
+    run_line(&mut app, "sent");
+    assert_eq!(
+        last_line(&app),
+        "Sent message #1 from the end:\n(no longer in history: dropped as a duplicate or summarized away)\nremember the blue door"
+    );
+}
@@ -0,0 +1,78 @@
+//! `speak on|off` per agent, the one warning when the OS has no speech
+//! program, and `stop` with nothing being spoken.
+//!
+//! Nothing here finishes a reply, so no test speaks aloud even where a
+//! speech program is installed; queueing is covered in `utilities::speech`.
+
+mod support;
+
+use std::sync::Arc;
+use uuid::Uuid;
+use grokprime_brain::commands::CommandResult;
+use grokprime_brain::llm::AnyClient;
+use grokprime_brain::llm::scripted::ScriptedClient;
+use grokprime_brain::persona::operations::AgentOperations;
+use grokprime_brain::tui::app::ShadowApp;
+use support::*;
+
+/// An app with two agents open, the second one current
+fn app_with_two_agents() -> (ShadowApp, Uuid, Uuid) {
+    test_root();
+    let mut app = ShadowApp::new();
+    app.agent_manager.client_override = Some(AnyClient::Scripted(ScriptedClient::default()));
+    let first = Uuid::new_v4();
+    app.add_new_agent(first, Arc::new(builtin_as("shadow", "speech-first")));
+    let second = Uuid::new_v4();
+    app.add_new_agent(second, Arc::new(builtin_as("shadow", "speech-second")));
+    (app, first, second)
+}
+
+fn warnings(app: &ShadowApp) -> usize {
+    app.agent_manager.agents.values()
+        .flat_map(|agent| agent.messages.iter())
+        .filter(|msg| msg.text.contains("Replies can't be spoken"))
+        .count()
+}
+
+#[tokio::test]
+async fn speaking_is_off_by_default_and_toggled_per_agent() {
+    let (mut app, first, second) = app_with_two_agents();
+    assert!(!app.is_agent_speaking(first) && !app.is_agent_speaking(second));
+    assert!(app.speaker.is_none(), "nothing is started until an agent speaks");
+
+    assert_eq!(run_line(&mut app, "speak on"), CommandResult::Continue);
+    assert!(app.is_agent_speaking(second));
+    assert!(!app.is_agent_speaking(first), "only the current agent");
+    assert!(app.unified_messages.iter().any(|msg| msg.text.ends_with("Speaking replies aloud")));
+
+    run_line(&mut app, "speak off");
+    assert!(!app.is_agent_speaking(second));
+    assert!(app.unified_messages.iter().last().is_some_and(|msg| msg.text.ends_with("Not speaking replies")));
+}
+
+#[tokio::test]
+async fn a_missing_speech_program_is_reported_once() {
+    let (mut app, first, second) = app_with_two_agents();
+    run_line(&mut app, "speak on");
+    let missing = app.speaker.as_ref().expect("started by speak on").missing_program_warning();
+
+    // Again, and for another agent: the speaker is shared, so no second warning
+    run_line(&mut app, "speak on");
+    app.set_agent_speaking(first, true);
+    assert_eq!(warnings(&app), usize::from(missing.is_some()));
+    if let Some(warning) = missing {
+        assert!(pane_lines(&app.agent_manager).iter().any(|line| line.ends_with(&warning)), "shown in {}'s pane", second);
+    }
+}
+
+#[tokio::test]
+async fn stop_with_nothing_spoken_or_running_says_so() {
+    let (mut app, _, _) = app_with_two_agents();
+    run_line(&mut app, "speak on");
+    assert!(!app.stop_speech());
+    assert_eq!(run_line(&mut app, "stop"), CommandResult::Continue);
+    assert_eq!(
+        app.unified_messages.iter().last().map(|msg| msg.text.as_str()),
+        Some("Nothing to stop: no reply is running.")
+    );
+}
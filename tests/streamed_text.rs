@@ -0,0 +1,35 @@
+//! Replies carrying `\r` progress updates and ANSI escape codes, streamed
+//! into an agent's pane in pieces: the pane and the saved history both end
+//! up with the text as it would finally read on a terminal.
+
+mod support;
+
+use grokprime_brain::llm::scripted::{ScriptedClient, ScriptedReply};
+use support::*;
+
+#[tokio::test]
+async fn the_pane_and_history_keep_the_final_state_of_progress_output() {
+    test_root();
+    let client = ScriptedClient::default();
+    let mut manager = manager_with(&client);
+    let mut persona = builtin_as("shadow", "streamed-progress");
+    persona.enable_history = false;
+    let id = open_agent(&mut manager, persona);
+    client.push(ScriptedReply::chunks([
+        "Building \u{1b}[1m",
+        "crate\u{1b}[0m\n[#   ] 25%\r[## ",
+        " ] 50%\r[####] 100%\r",
+        "\n\u{1b}[32mok\u{1b}[",
+        "0m",
+    ]));
+
+    run_line(&mut manager, "build it");
+    wait_for_reply(&mut manager).await;
+
+    let expected = "Building crate\n[####] 100%\nok";
+    assert_eq!(pane_lines(&manager).last().map(String::as_str), Some(expected), "{:?}", pane_lines(&manager));
+    let agent = manager.agents.get(&id).expect("agent is open");
+    let conn = agent.connection.try_lock().expect("connection is idle");
+    let saved = conn.local_history().last().expect("the reply is in history");
+    assert_eq!((saved.role.as_str(), saved.content.as_str()), ("assistant", expected));
+}
@@ -0,0 +1,141 @@
+//! Strict mode over every categorized command: what posts, deletes, or
+//! overwrites is refused with the one message, and previews, drafts, dry runs
+//! and what only ends something in the session still run.
+//!
+//! Strict mode can't be turned off once on, so it has this test binary to
+//! itself: every test enables it first.
+
+mod support;
+
+use std::sync::Arc;
+use uuid::Uuid;
+use grokprime_brain::commands::CommandResult;
+use grokprime_brain::llm::AnyClient;
+use grokprime_brain::llm::scripted::{ScriptedClient, ScriptedReply};
+use grokprime_brain::models::ActionEffect;
+use grokprime_brain::persona::operations::AgentOperations;
+use grokprime_brain::tui::app::ShadowApp;
+use grokprime_brain::user::user_input::UserInput;
+use grokprime_brain::utilities::strict;
+use support::*;
+
+/// Each categorized command as typed, with the effect it's categorized under
+const CATEGORIZED: &[(&str, ActionEffect)] = &[
+    ("tweet confirm", ActionEffect::Publishes),
+    ("clearhistory", ActionEffect::Deletes),
+    ("history dedupe confirm", ActionEffect::Deletes),
+    ("history remove 1 confirm", ActionEffect::Deletes),
+    ("history remove 1 --pair confirm", ActionEffect::Deletes),
+    ("macro delete demo", ActionEffect::Deletes),
+    ("resync fresh", ActionEffect::Deletes),
+    ("rollback 1 confirm", ActionEffect::Deletes),
+    ("archives export old.json out.md", ActionEffect::Overwrites),
+    ("history verify fix confirm", ActionEffect::Overwrites),
+    ("persona export-builtin out", ActionEffect::Overwrites),
+    ("persona export-builtin out --inline", ActionEffect::Overwrites),
+    ("insights 2026-03 --md out.md", ActionEffect::Overwrites),
+    ("reload --apply", ActionEffect::Overwrites),
+    ("quit", ActionEffect::Ends),
+    ("close", ActionEffect::Ends),
+    ("global clear", ActionEffect::Ends),
+    ("experiment stop", ActionEffect::Ends),
+    ("jobs cancel 1", ActionEffect::Ends),
+    ("stop", ActionEffect::Ends),
+    ("tweet Hello world", ActionEffect::None),
+    ("tweet --later Hello world", ActionEffect::None),
+    ("tweet cancel", ActionEffect::None),
+    ("draft Hello world", ActionEffect::None),
+    ("history dedupe", ActionEffect::None),
+    ("history remove 1", ActionEffect::None),
+    ("history verify fix", ActionEffect::None),
+    ("rollback 1", ActionEffect::None),
+    ("insights 2026-03", ActionEffect::None),
+    ("reload", ActionEffect::None),
+    ("resync recap", ActionEffect::None),
+    ("macro list", ActionEffect::None),
+    ("status", ActionEffect::None),
+];
+
+/// A strict app whose agents reply from `client`, with shadow open under `storage_name`
+fn strict_app(client: &ScriptedClient, storage_name: &str) -> (ShadowApp, Uuid) {
+    test_root();
+    strict::enable();
+    let mut app = ShadowApp::new();
+    app.agent_manager.client_override = Some(AnyClient::Scripted(client.clone()));
+    let id = Uuid::new_v4();
+    app.add_new_agent(id, Arc::new(builtin_as("shadow", storage_name)));
+    (app, id)
+}
+
+fn is_strict_refusal(result: &CommandResult) -> bool {
+    matches!(result, CommandResult::Error(msg) if msg.starts_with("Strict mode:"))
+}
+
+#[test]
+fn every_categorized_command_has_the_effect_strict_mode_reads() {
+    let input = UserInput::new_for_tui();
+    for (line, effect) in CATEGORIZED {
+        let action = input.process_input(line);
+        assert_eq!(action.effect(), *effect, "{}", line);
+        assert_eq!(action.is_destructive(), *effect != ActionEffect::None, "macro replay agrees for {}", line);
+    }
+    let refused: Vec<ActionEffect> = [ActionEffect::None, ActionEffect::Ends, ActionEffect::Overwrites, ActionEffect::Deletes, ActionEffect::Publishes]
+        .into_iter()
+        .filter(ActionEffect::refused_in_strict)
+        .collect();
+    assert_eq!(refused, [ActionEffect::Overwrites, ActionEffect::Deletes, ActionEffect::Publishes]);
+}
+
+#[test]
+fn posting_deleting_and_overwriting_are_refused_with_one_message() {
+    let (mut app, _) = strict_app(&ScriptedClient::default(), "strict-refused");
+    let input = UserInput::new_for_tui();
+    for (line, effect) in CATEGORIZED.iter().filter(|(_, effect)| effect.refused_in_strict()) {
+        let name: &'static str = (&input.process_input(line)).into();
+        assert_eq!(run_line(&mut app, line), CommandResult::Error(strict::refusal(name, *effect)), "{}", line);
+    }
+    assert_eq!(
+        strict::refusal("ConfirmTweet", ActionEffect::Publishes),
+        "Strict mode: `ConfirmTweet` publishes outside the app and is refused. Restart without --strict (and with strict_mode off) to allow it."
+    );
+}
+
+#[test]
+fn previews_and_dry_runs_still_run() {
+    let (mut app, _) = strict_app(&ScriptedClient::default(), "strict-previews");
+    let previews = ["history dedupe", "history remove 1", "history verify fix", "rollback 1", "insights 2026-03", "reload", "macro list"];
+    for line in previews {
+        let result = run_line(&mut app, line);
+        assert!(!is_strict_refusal(&result), "{}: {:?}", line, result);
+    }
+}
+
+#[tokio::test]
+async fn a_refused_command_changes_nothing_and_ending_still_works() {
+    let client = ScriptedClient::default();
+    let (mut app, id) = strict_app(&client, "strict-untouched");
+    client.push(ScriptedReply::text("Hello."));
+    run_line(&mut app, "hi");
+    wait_for_reply(&mut app.agent_manager).await;
+    let before = app.agent_manager.agents[&id].connection.try_lock().unwrap().local_history().len();
+
+    for line in ["clearhistory", "history remove 1 confirm", "resync fresh"] {
+        assert!(is_strict_refusal(&run_line(&mut app, line)), "{}", line);
+    }
+    let conn = app.agent_manager.agents[&id].connection.try_lock().unwrap();
+    assert_eq!(conn.local_history().len(), before, "the history is untouched");
+    assert_eq!(conn.local_history().last().map(|msg| msg.content.as_str()), Some("Hello."));
+    drop(conn);
+
+    // Ending something in the session isn't refused
+    assert_eq!(run_line(&mut app, "close"), CommandResult::Continue);
+    assert!(!app.agent_manager.agents.contains_key(&id));
+}
+
+#[test]
+fn status_names_the_mode() {
+    let (mut app, _) = strict_app(&ScriptedClient::default(), "strict-status");
+    run_line(&mut app, "status");
+    let shown = app.unified_messages.iter().last().map(|msg| msg.text.clone()).unwrap_or_default();
+    assert!(shown.ends_with("\n - Strict mode: on (commands that post, delete, or overwrite are refused)"), "{}", shown);
+}
@@ -0,0 +1,87 @@
+//! Summarize modes on an open agent: manual mode reminds instead of
+//! summarizing, off refuses `summarize`, and `set summarize` outlives the agent.
+
+mod support;
+
+use std::sync::Arc;
+use uuid::Uuid;
+use grokprime_brain::llm::AnyClient;
+use grokprime_brain::llm::scripted::{ScriptedClient, ScriptedReply};
+use grokprime_brain::persona::SummarizeMode;
+use grokprime_brain::persona::agent_manager::AgentManager;
+use grokprime_brain::persona::operations::AgentOperations;
+use grokprime_brain::tui::app::ShadowApp;
+use support::*;
+
+/// A history-keeping shadow that passes its threshold with 4 messages after the prompt
+fn app_in_mode(client: &ScriptedClient, storage_name: &str, mode: SummarizeMode) -> ShadowApp {
+    test_root();
+    let mut app = ShadowApp::new();
+    app.agent_manager.client_override = Some(AnyClient::Scripted(client.clone()));
+    let mut persona = builtin_as("shadow", storage_name);
+    persona.enable_history = true;
+    persona.summary_threshold = 4;
+    persona.history_message_limit = 2;
+    persona.summarize_mode = mode;
+    app.add_new_agent(Uuid::new_v4(), Arc::new(persona));
+    app
+}
+
+/// The latest Global pane line
+fn last_line(app: &ShadowApp) -> String {
+    app.unified_messages.iter().last().map(|msg| msg.text.clone()).unwrap_or_default()
+}
+
+async fn exchange(manager: &mut AgentManager, client: &ScriptedClient, question: &str) {
+    client.push(ScriptedReply::text("Noted."));
+    run_line(manager, question);
+    wait_for_reply(manager).await;
+}
+
+#[tokio::test]
+async fn manual_mode_reminds_instead_of_summarizing() {
+    let client = ScriptedClient::default();
+    let mut app = app_in_mode(&client, "mode-manual", SummarizeMode::Manual);
+
+    for question in ["one", "two", "three"] {
+        exchange(&mut app.agent_manager, &client, question).await;
+    }
+
+    assert_eq!(client.requests().len(), 3, "no summarization request went out");
+    let reminders: Vec<String> = pane_lines(&app.agent_manager).into_iter().filter(|line| line.contains("consider `summarize`")).collect();
+    assert_eq!(reminders, ["History is 5 messages — consider `summarize`"], "one reminder until 10 more messages");
+
+    run_line(&mut app, "summarize");
+    assert_eq!(app.agent_manager.jobs.len(), 1, "summarize still works on request");
+}
+
+#[tokio::test]
+async fn off_refuses_summarize_until_the_override_allows_it() {
+    let mut app = app_in_mode(&ScriptedClient::default(), "mode-off", SummarizeMode::Off);
+
+    run_line(&mut app, "summarize");
+    assert!(app.agent_manager.jobs.is_empty());
+    assert_eq!(last_line(&app), "Summarization is off for mode-off, so its history is never rewritten. `set summarize manual` allows `summarize` again.");
+
+    run_line(&mut app, "set summarize manual");
+    assert_eq!(last_line(&app), "Summarize mode for mode-off: manual (set with `set summarize`)");
+    run_line(&mut app, "summarize");
+    assert_eq!(app.agent_manager.jobs.len(), 1);
+}
+
+#[test]
+fn the_override_is_loaded_when_the_agent_opens_again() {
+    let client = ScriptedClient::default();
+    let mut app = app_in_mode(&client, "mode-persisted", SummarizeMode::Auto);
+    run_line(&mut app, "set summarize off");
+
+    let mut reopened = app_in_mode(&client, "mode-persisted", SummarizeMode::Auto);
+    run_line(&mut reopened, "set summarize");
+    assert_eq!(last_line(&reopened), "Summarize mode for mode-persisted: off (set with `set summarize`)");
+
+    run_line(&mut reopened, "set summarize default");
+    assert_eq!(last_line(&reopened), "Summarize mode for mode-persisted: auto (from persona)");
+    let mut again = app_in_mode(&client, "mode-persisted", SummarizeMode::Auto);
+    run_line(&mut again, "set summarize");
+    assert_eq!(last_line(&again), "Summarize mode for mode-persisted: auto (from persona)");
+}
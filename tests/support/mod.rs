@@ -0,0 +1,143 @@
+//! # Daegonica Module: tests::support
+//!
+//! **Purpose:** Helpers shared by the integration tests under `tests/`
+//!
+//! **Context:**
+//! - Each file in `tests/` is its own binary and pulls this in with `mod support;`
+//! - Agents run on a `ScriptedClient`, so no test needs API keys or the network
+//! - Histories, archives and state go to a per-process temp directory, never the repo's `personas/`
+//!
+//! **Responsibilities:**
+//! - Point the personas root at a temp directory before anything reads it
+//! - Open agents on a scripted client and wait for their replies
+//! - Read the prompts a scripted client was sent
+//! - Drive the TUI through a virtual terminal (`tui`)
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+// Each test binary uses only some of these
+#![allow(dead_code)]
+
+pub mod tui;
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use uuid::Uuid;
+use grokprime_brain::commands::{from_input_action, CommandResult};
+use grokprime_brain::llm::AnyClient;
+use grokprime_brain::llm::scripted::ScriptedClient;
+use grokprime_brain::llm::request::ChatRequest;
+use grokprime_brain::persona::agent_manager::AgentManager;
+use grokprime_brain::persona::operations::AgentOperations;
+use grokprime_brain::persona::builtin::load_builtin;
+use grokprime_brain::persona::{personas_root, set_personas_root, Persona};
+use grokprime_brain::user::user_input::UserInput;
+
+/// How long `wait_for_reply` waits before failing the test
+pub const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+static TEST_ROOT: Lazy<PathBuf> = Lazy::new(|| {
+    let root = std::env::temp_dir().join(format!("grokprime-it-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("personas")).expect("create test personas root");
+    set_personas_root(root.join("personas")).expect("personas root set before the test root");
+    root
+});
+
+/// # test_root
+///
+/// **Purpose:**
+/// Points the personas root at `{temp}/grokprime-it-{pid}/personas` and returns `{temp}/grokprime-it-{pid}`.
+///
+/// **Details:**
+/// Call first in every test. Tests in one binary share the root, so give
+/// each persona you open a name no other test in that file uses.
+pub fn test_root() -> &'static Path {
+    let root = TEST_ROOT.as_path();
+    assert_eq!(personas_root(), root.join("personas"), "personas root was read before test_root()");
+    root
+}
+
+/// Writes `contents` to `relative` under the test root; returns the full path
+pub fn write_file(relative: &str, contents: &str) -> PathBuf {
+    let path = test_root().join("files").join(relative);
+    std::fs::create_dir_all(path.parent().expect("file has a parent")).expect("create file dir");
+    std::fs::write(&path, contents).expect("write test file");
+    path
+}
+
+/// The built-in persona `name`, stored under `storage_name` so tests don't share history files
+pub fn builtin_as(name: &str, storage_name: &str) -> Persona {
+    let mut persona = load_builtin(name).expect("built-in persona");
+    persona.name = storage_name.to_string();
+    persona
+}
+
+/// The persona `personas/<name>/<name>.yaml` in the repo
+pub fn repo_persona(name: &str) -> Persona {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("personas").join(name).join(format!("{}.yaml", name));
+    Persona::from_yaml_file(&path).expect("repo persona")
+}
+
+/// An agent manager whose agents all reply from `client`
+pub fn manager_with(client: &ScriptedClient) -> AgentManager {
+    let mut manager = AgentManager::new();
+    manager.client_override = Some(AnyClient::Scripted(client.clone()));
+    manager
+}
+
+/// Opens an agent on `persona` and makes it current
+pub fn open_agent(manager: &mut AgentManager, persona: Persona) -> Uuid {
+    let id = Uuid::new_v4();
+    manager.add_agent(id, Arc::new(persona));
+    id
+}
+
+/// Runs `line` as if typed in the TUI, against `ops`' current agent
+pub fn run_line(ops: &mut dyn AgentOperations, line: &str) -> CommandResult {
+    let action = UserInput::new_for_tui().process_input(line);
+    from_input_action(action).execute(ops)
+}
+
+/// Polls the manager until the current agent's reply has finished
+pub async fn wait_for_reply(manager: &mut AgentManager) {
+    let waited = tokio::time::timeout(REPLY_TIMEOUT, async {
+        loop {
+            manager.poll_channels();
+            if manager.current_pane().is_none_or(|agent| !agent.is_waiting) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    });
+    waited.await.expect("the reply never finished");
+}
+
+/// The system prompt a request opened with
+pub fn system_prompt(request: &ChatRequest) -> &str {
+    request.input.iter()
+        .find(|msg| msg.role == "system")
+        .map_or("", |msg| msg.content.as_str())
+}
+
+/// The last user message of a request
+pub fn last_user_message(request: &ChatRequest) -> &str {
+    request.input.iter().rev()
+        .find(|msg| msg.role == "user")
+        .map_or("", |msg| msg.content.as_str())
+}
+
+/// Pane lines of the current agent
+pub fn pane_lines(manager: &AgentManager) -> Vec<String> {
+    manager.current_pane()
+        .map(|agent| agent.messages.iter().map(|msg| msg.text.clone()).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,337 @@
+//! # Daegonica Module: tests::support::tui
+//!
+//! **Purpose:** Drive the real TUI through a virtual terminal
+//!
+//! **Context:**
+//! - Boots a `ShadowApp` on a `ScriptedClient` and draws it to ratatui's `TestBackend`
+//! - Keys go through `handle_key` exactly as the main loop sends them; `tick` is one turn of that loop
+//! - Scenarios live in `tests/tui_scenarios.rs`; add new ones there
+//!
+//! **Responsibilities:**
+//! - Type, press and submit keys, and wait for scripted replies to finish
+//! - Check the app's invariants after every step
+//! - Compare normalized screens against golden files in `tests/snapshots/`
+//!
+//! **Author:** Daegonica Software
+//! **Version:** 0.1.0
+//! **Last Updated:** 2026-01-21
+//!
+//! ---------------------------------------------------------------
+//! This file is part of the Daegonica Software codebase.
+//! ---------------------------------------------------------------
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use once_cell::sync::Lazy;
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use regex::Regex;
+use grokprime_brain::llm::AnyClient;
+use grokprime_brain::llm::scripted::ScriptedClient;
+use grokprime_brain::tui::app::ShadowApp;
+use grokprime_brain::user::user_input::UserInput;
+use super::{builtin_as, repo_persona, test_root, REPLY_TIMEOUT};
+
+/// Terminal size scenarios run at unless they pick another
+pub const SCREEN: (u16, u16) = (100, 30);
+
+/// Set to rewrite golden files from what the scenarios render
+pub const UPDATE_SNAPSHOTS: &str = "UPDATE_SNAPSHOTS";
+
+/// Personas a harness can open with `new <name>`
+const PERSONAS: [&str; 2] = ["shadow", "friday"];
+
+/// Things on screen that change from run to run, and what they become
+static VOLATILE: Lazy<Vec<(Regex, &'static str)>> = Lazy::new(|| {
+    [
+        (r"[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}", "<uuid>"),
+        (r"\b\d{2}:\d{2}:\d{2}\b", "HH:MM:SS"),
+        (r"\b\d+(\.\d+)?m?s\b", "<elapsed>"),
+        // Time-based spinner frames, and the poll-counted "thinking" dots
+        (r"[⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏]", "⠿"),
+        (r"thinking[. ]{3}", "thinking..."),
+    ]
+    .into_iter()
+    .map(|(pattern, replacement)| (Regex::new(pattern).expect("volatile pattern is valid"), replacement))
+    .collect()
+});
+
+/// # TuiHarness
+///
+/// **Summary:**
+/// A `ShadowApp` wired to a scripted model and a virtual terminal.
+///
+/// **Fields:**
+/// - `app`: The app under test; scenarios may read anything on it
+/// - `client`: Queue replies here before sending the message they answer
+/// - `running`: False once a key asked the app to exit
+/// - `terminal`: Virtual terminal the app draws to
+///
+/// **Details:**
+/// `shadow` and `friday` can be opened with `new`. They don't keep history
+/// unless the harness is made with `with_history`, so scenarios running in
+/// parallel never read each other's files.
+pub struct TuiHarness {
+    pub app: ShadowApp,
+    pub client: ScriptedClient,
+    pub running: bool,
+    terminal: Terminal<TestBackend>,
+}
+
+impl TuiHarness {
+    /// A fresh app with nothing open, personas without history
+    pub fn new() -> Self {
+        Self::boot(ScriptedClient::default(), None)
+    }
+
+    /// # with_history
+    ///
+    /// **Purpose:**
+    /// A fresh app whose `shadow` saves history, as `prefix-shadow`.
+    ///
+    /// **Details:**
+    /// The pane title shows the stored name, so give each scenario its own prefix.
+    pub fn with_history(prefix: &str) -> Self {
+        Self::boot(ScriptedClient::default(), Some(prefix))
+    }
+
+    /// # restart
+    ///
+    /// **Purpose:**
+    /// Boots a new app on the same personas and scripted model, as a restart would.
+    pub fn restart(&self, prefix: Option<&str>) -> Self {
+        Self::boot(self.client.clone(), prefix)
+    }
+
+    fn boot(client: ScriptedClient, history_prefix: Option<&str>) -> Self {
+        test_root();
+        let mut app = ShadowApp::new();
+        app.agent_manager.client_override = Some(AnyClient::Scripted(client.clone()));
+        app.agent_manager.user_input = Some(UserInput::new_for_tui());
+        for name in PERSONAS {
+            let persona = match history_prefix {
+                Some(prefix) if name == "shadow" => builtin_as(name, &format!("{}-{}", prefix, name)),
+                _ => {
+                    let mut persona = repo_persona(name);
+                    persona.enable_history = false;
+                    persona
+                }
+            };
+            app.agent_manager.personas.insert(name.to_string(), Arc::new(persona));
+        }
+
+        let (width, height) = SCREEN;
+        let terminal = Terminal::new(TestBackend::new(width, height)).expect("test terminal");
+        let mut harness = Self { app, client, running: true, terminal };
+        harness.tick();
+        harness
+    }
+
+    /// # key
+    ///
+    /// **Purpose:**
+    /// Sends one key press, then draws and checks the invariants.
+    pub fn key(&mut self, key: KeyEvent) {
+        assert!(self.running, "key {:?} sent after the app exited", key);
+        self.running = self.app.handle_key(key);
+        self.check_invariants();
+        if self.running {
+            self.draw();
+        }
+    }
+
+    pub fn press(&mut self, code: KeyCode) {
+        self.key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    pub fn ctrl(&mut self, c: char) {
+        self.key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL));
+    }
+
+    /// Types `text` a key at a time (`\n` as Alt+Enter, a new line in the input)
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            match c {
+                '\n' => self.key(KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT)),
+                c => self.press(KeyCode::Char(c)),
+            }
+        }
+    }
+
+    /// Types `line` and presses Enter
+    pub fn submit(&mut self, line: &str) {
+        self.type_text(line);
+        self.press(KeyCode::Enter);
+    }
+
+    /// # tick
+    ///
+    /// **Purpose:**
+    /// One turn of the main loop: drain agent channels, run a macro step, draw.
+    pub fn tick(&mut self) {
+        self.app.poll_channels();
+        if self.app.advance_macro() {
+            self.running = false;
+        }
+        self.check_invariants();
+        if self.running {
+            self.draw();
+        }
+    }
+
+    /// # tick_until
+    ///
+    /// **Purpose:**
+    /// Ticks, letting the reply tasks run in between, until `done` holds.
+    ///
+    /// **Details:**
+    /// Fails the test after `REPLY_TIMEOUT`, naming `what` it was waiting for.
+    pub async fn tick_until(&mut self, what: &str, done: impl Fn(&ShadowApp) -> bool) {
+        let waited = tokio::time::timeout(REPLY_TIMEOUT, async {
+            loop {
+                self.tick();
+                if done(&self.app) {
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        });
+        if waited.await.is_err() {
+            panic!("timed out waiting for {}; screen:\n{}", what, self.screen());
+        }
+    }
+
+    /// Ticks until no agent is waiting on a reply
+    pub async fn settle(&mut self) {
+        self.tick_until("every reply to finish", |app| {
+            app.agent_manager.agents.values().all(|agent| !agent.is_waiting)
+        }).await;
+    }
+
+    /// Resizes the virtual terminal and draws the next frame at the new size
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.terminal.backend_mut().resize(width, height);
+        self.tick();
+    }
+
+    fn draw(&mut self) {
+        let app = &mut self.app;
+        self.terminal.draw(|frame| app.draw(frame)).expect("draw to the test terminal");
+    }
+
+    /// The last frame drawn
+    pub fn buffer(&self) -> &Buffer {
+        self.terminal.backend().buffer()
+    }
+
+    /// The last frame as normalized text, one line per row
+    pub fn screen(&self) -> String {
+        normalize(&buffer_text(self.buffer()))
+    }
+
+    /// What the System pane has been sent, oldest first (wrapping aside)
+    pub fn system_lines(&self) -> Vec<String> {
+        self.app.unified_messages.iter().map(|msg| msg.text.clone()).collect()
+    }
+
+    /// Whether `text` is anywhere on the last frame
+    pub fn shows(&self, text: &str) -> bool {
+        self.screen().contains(text)
+    }
+
+    /// # assert_snapshot
+    ///
+    /// **Purpose:**
+    /// Compares the last frame with `tests/snapshots/<name>.txt` (see `assert_text_snapshot`).
+    pub fn assert_snapshot(&self, name: &str) {
+        assert_text_snapshot(name, &self.screen());
+    }
+
+    /// # check_invariants
+    ///
+    /// **Purpose:**
+    /// Fails the test if the app's state has come apart.
+    ///
+    /// **Details:**
+    /// - Agents, panes and the tab order list the same agents
+    /// - The current agent is open, and there is one whenever any agent is
+    /// - The current pane's draft is in the input box, not held back
+    /// - A pending Ctrl+W confirmation is for the current agent
+    /// - The input cursor sits on a character boundary
+    pub fn check_invariants(&self) {
+        let app = &self.app;
+        let manager = &app.agent_manager;
+        let mut agents: Vec<_> = manager.agents.keys().copied().collect();
+        let mut panes: Vec<_> = app.agent_panes.keys().copied().collect();
+        let mut order = manager.agent_order.clone();
+        agents.sort();
+        panes.sort();
+        order.sort();
+        assert_eq!(agents, panes, "every agent has exactly one pane");
+        assert_eq!(agents, order, "the tab order lists every agent once");
+
+        match manager.current_agent {
+            Some(id) => assert!(manager.agents.contains_key(&id), "the current agent is open"),
+            None => assert!(manager.agents.is_empty(), "an agent is open but none is current"),
+        }
+        assert!(app.current_pane().is_none_or(|pane| pane.draft.is_none()), "the current agent's draft is in the input box");
+        if let Some(armed) = app.close_armed {
+            assert_eq!(Some(armed), manager.current_agent, "a Ctrl+W confirmation is for the current agent");
+        }
+        assert!(app.input.is_char_boundary(app.input_cursor.min(app.input.len())), "cursor on a char boundary");
+    }
+}
+
+/// Rows of `buffer` as text, trailing spaces trimmed
+pub fn buffer_text(buffer: &Buffer) -> String {
+    let width = buffer.area.width as usize;
+    buffer.content()
+        .chunks(width)
+        .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>().trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// # normalize
+///
+/// **Purpose:**
+/// Replaces what differs between runs (ids, clock times, durations, spinners, the test root).
+pub fn normalize(screen: &str) -> String {
+    let root = test_root().display().to_string();
+    let mut text = screen.replace(&root, "<root>");
+    for (pattern, replacement) in VOLATILE.iter() {
+        text = pattern.replace_all(&text, *replacement).into_owned();
+    }
+    text.lines().map(str::trim_end).collect::<Vec<_>>().join("\n") + "\n"
+}
+
+/// # assert_text_snapshot
+///
+/// **Purpose:**
+/// Compares `actual` with `tests/snapshots/<name>.txt`.
+///
+/// **Details:**
+/// With `UPDATE_SNAPSHOTS` set, or when the file doesn't exist yet, the
+/// file is written instead; check the new file in with the scenario.
+pub fn assert_text_snapshot(name: &str, actual: &str) {
+    let path = snapshot_path(name);
+    if std::env::var_os(UPDATE_SNAPSHOTS).is_some() || !path.exists() {
+        std::fs::create_dir_all(path.parent().expect("snapshot dir")).expect("create snapshot dir");
+        std::fs::write(&path, actual).expect("write snapshot");
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).expect("read snapshot");
+    if expected != actual {
+        panic!(
+            "{} differs from {} (rerun with {}=1 to accept it)\n--- expected\n{}\n--- actual\n{}",
+            name, path.display(), UPDATE_SNAPSHOTS, expected, actual
+        );
+    }
+}
+
+fn snapshot_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots").join(format!("{}.txt", name))
+}
@@ -0,0 +1,172 @@
+//! Scenarios driving the real TUI through `support::tui::TuiHarness`: keys in,
+//! rendered screens and app state out.
+//!
+//! Screens are compared with `tests/snapshots/<name>.txt`; after an intended
+//! change to what the TUI draws, rerun with `UPDATE_SNAPSHOTS=1` and review
+//! the diff of those files.
+
+mod support;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use grokprime_brain::llm::scripted::ScriptedReply;
+use grokprime_brain::persona::agent::AgentInfo;
+use grokprime_brain::tui::app::ShadowApp;
+use support::last_user_message;
+use support::tui::TuiHarness;
+
+fn current(app: &ShadowApp) -> &AgentInfo {
+    app.agent_manager.current_pane().expect("an agent is open")
+}
+
+fn current_lines(app: &ShadowApp) -> Vec<String> {
+    current(app).messages.iter().map(|msg| msg.text.clone()).collect()
+}
+
+fn current_name(app: &ShadowApp) -> String {
+    current(app).persona_name.clone()
+}
+
+#[tokio::test]
+async fn send_streams_and_completes() {
+    let mut h = TuiHarness::new();
+    h.assert_snapshot("start");
+
+    h.submit("new shadow");
+    assert_eq!(current_name(&h.app), "shadow");
+    assert!(h.system_lines().iter().any(|line| line == "Created new agent with persona 'Shadow'"));
+
+    let (reply, gate) = ScriptedReply::chunks(["Streaming ", "a reply ", "in three chunks."]).held_after(1);
+    h.client.push(reply);
+    h.submit("hello there");
+    assert!(h.app.input.is_empty());
+    h.tick_until("the first chunk", |app| current_lines(app).iter().any(|line| line.starts_with("Streaming"))).await;
+    assert!(current(&h.app).is_waiting);
+    assert!(h.shows("Shadow is thinking..."));
+    h.assert_snapshot("send_mid_stream");
+
+    gate.release();
+    h.settle().await;
+    assert_eq!(current_lines(&h.app), ["> hello there", "Streaming a reply in three chunks."]);
+    assert!(!h.shows("is thinking"));
+    assert_eq!(last_user_message(&h.client.requests()[0]), "hello there");
+    h.assert_snapshot("send_complete");
+}
+
+#[tokio::test]
+async fn a_failed_request_shows_the_error_and_how_to_retry() {
+    let mut h = TuiHarness::new();
+    h.submit("new shadow");
+    h.client.push(ScriptedReply::error("503 Service Unavailable"));
+    h.submit("are you there?");
+    h.settle().await;
+
+    let agent = current(&h.app);
+    assert_eq!(agent.failures_in_a_row, 1);
+    assert_eq!(agent.last_failure.as_ref().map(|failure| failure.error.as_str()), Some("503 Service Unavailable"));
+    assert!(h.shows("Error: 503 Service Unavailable"));
+    h.assert_snapshot("error_display");
+
+    // Sending again clears the error once the reply arrives
+    h.client.push(ScriptedReply::text("Here now."));
+    h.submit("are you there?");
+    h.settle().await;
+    assert!(current(&h.app).last_failure.is_none());
+    assert!(h.shows("Here now."));
+}
+
+#[tokio::test]
+async fn each_agent_keeps_its_own_draft() {
+    let mut h = TuiHarness::new();
+    h.submit("new shadow");
+    h.submit("new friday");
+    assert_eq!(current_name(&h.app), "friday");
+
+    h.type_text("half a thought for friday");
+    h.press(KeyCode::Tab);
+    assert_eq!(current_name(&h.app), "shadow");
+    assert_eq!(h.app.input, "", "shadow had no draft");
+
+    h.type_text("a question for shadow");
+    h.press(KeyCode::Tab);
+    assert_eq!(current_name(&h.app), "friday");
+    assert_eq!(h.app.input, "half a thought for friday");
+    assert_eq!(h.app.input_cursor, h.app.input.len());
+    h.assert_snapshot("draft_restored");
+
+    // Finishing friday's draft sends it to friday only
+    h.client.push(ScriptedReply::text("Noted."));
+    h.type_text(", finished");
+    h.press(KeyCode::Enter);
+    h.settle().await;
+    assert_eq!(current_lines(&h.app), ["> half a thought for friday, finished", "Noted."]);
+    assert_eq!(h.client.requests().len(), 1);
+
+    h.key(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT));
+    assert_eq!(current_name(&h.app), "shadow");
+    assert_eq!(h.app.input, "a question for shadow");
+    assert!(current_lines(&h.app).is_empty(), "nothing was sent to shadow");
+}
+
+#[tokio::test]
+async fn closing_a_busy_agent_asks_first() {
+    let mut h = TuiHarness::new();
+    h.submit("new shadow");
+    h.submit("new friday");
+    let friday = h.app.agent_manager.current_agent;
+    let (reply, gate) = ScriptedReply::text("Too late.").held();
+    h.client.push(reply);
+    h.submit("a long question");
+    h.tick_until("friday to be waiting", |app| current(app).is_waiting).await;
+
+    h.ctrl('w');
+    assert_eq!(h.app.agent_manager.current_agent, friday, "the first Ctrl+W only warns");
+    assert_eq!(h.app.close_armed, friday);
+    assert_eq!(
+        h.system_lines().last().map(String::as_str),
+        Some("Close Friday? Its reply is still streaming. Ctrl+W again closes it; any other key keeps it.")
+    );
+    h.assert_snapshot("close_confirm");
+
+    // Any other key keeps the agent, and the next Ctrl+W asks again
+    h.press(KeyCode::Right);
+    assert_eq!(h.app.close_armed, None);
+    h.ctrl('w');
+    assert_eq!(h.app.agent_manager.agents.len(), 2);
+
+    h.ctrl('w');
+    assert_eq!(h.app.agent_manager.agents.len(), 1);
+    assert_eq!(current_name(&h.app), "shadow");
+    let tabs = h.screen().lines().next().unwrap_or_default().to_string();
+    assert!(!tabs.contains("Friday"), "friday's tab is gone: {}", tabs);
+
+    gate.release();
+    h.settle().await;
+    assert!(current_lines(&h.app).is_empty(), "friday's reply didn't land in shadow");
+
+    // Nothing to lose: closes straight away
+    h.ctrl('w');
+    assert!(h.app.agent_manager.agents.is_empty());
+    h.assert_snapshot("all_closed");
+}
+
+#[tokio::test]
+async fn quitting_keeps_the_conversation() {
+    let mut h = TuiHarness::with_history("quit");
+    h.submit("new shadow");
+    h.client.push(ScriptedReply::text("I'll remember 42."));
+    h.submit("remember the number 42");
+    h.settle().await;
+
+    h.press(KeyCode::Esc);
+    assert!(!h.running, "Esc quits");
+
+    let mut restarted = h.restart(Some("quit"));
+    restarted.submit("new shadow");
+    let agent = current(&restarted.app);
+    let conn = agent.connection.try_lock().expect("connection is idle");
+    let history: Vec<(&str, &str)> = conn.local_history().iter()
+        .filter(|msg| msg.role != "system")
+        .map(|msg| (msg.role.as_str(), msg.content.as_str()))
+        .collect();
+    assert_eq!(history, [("user", "remember the number 42"), ("assistant", "I'll remember 42.")]);
+}
@@ -0,0 +1,79 @@
+//! `tweet` and `draft` through the command path: the preview shown is the
+//! post held for `tweet confirm`, and the old input actions route to
+//! commands instead of panicking.
+//!
+//! The pending post is process-wide, so these live in their own test binary
+//! and take turns with it.
+//! Nothing here confirms a post, so no test can reach the real API.
+
+mod support;
+
+use std::sync::{Mutex, MutexGuard};
+use grokprime_brain::commands::{from_input_action, CommandResult, NO_AGENT_GUIDANCE};
+use grokprime_brain::llm::AnyClient;
+use grokprime_brain::llm::scripted::ScriptedClient;
+use grokprime_brain::models::InputAction;
+use grokprime_brain::tui::app::ShadowApp;
+use grokprime_brain::twitter::preview::{self, TweetPreview};
+use support::*;
+
+static PENDING_TURN: Mutex<()> = Mutex::new(());
+
+/// An app with no agent open, and the pending post to itself until the guard drops
+fn app() -> (ShadowApp, MutexGuard<'static, ()>) {
+    let turn = PENDING_TURN.lock().unwrap_or_else(|e| e.into_inner());
+    test_root();
+    preview::take_pending();
+    let mut app = ShadowApp::new();
+    app.agent_manager.client_override = Some(AnyClient::Scripted(ScriptedClient::default()));
+    (app, turn)
+}
+
+fn last_line(app: &ShadowApp) -> String {
+    app.unified_messages.iter().last().map(|msg| msg.text.clone()).unwrap_or_default()
+}
+
+#[test]
+fn tweet_shows_the_preview_and_holds_the_same_text() {
+    let (mut app, _turn) = app();
+    let text = "Café ☕ — the  preview is the post 🚀";
+
+    run_line(&mut app, &format!("tweet {}", text));
+    let shown = last_line(&app);
+    assert_eq!(shown, TweetPreview::single(text).render());
+    assert!(shown.contains(&format!("│ {}\n", text)), "{}", shown);
+
+    let pending = preview::take_pending().expect("held for confirmation");
+    assert_eq!(pending.parts(), [text]);
+    assert_eq!(pending.render(), shown, "confirm posts exactly what was shown");
+}
+
+#[test]
+fn a_second_tweet_replaces_the_first_and_cancel_discards_it() {
+    let (mut app, _turn) = app();
+
+    run_line(&mut app, "tweet first thought");
+    run_line(&mut app, "tweet second thought");
+    run_line(&mut app, "tweet cancel");
+    assert_eq!(last_line(&app), "Pending tweet discarded.");
+    assert!(preview::take_pending().is_none());
+
+    run_line(&mut app, "tweet cancel");
+    assert_eq!(last_line(&app), "No pending tweet.");
+    run_line(&mut app, "tweet confirm");
+    assert_eq!(last_line(&app), NO_AGENT_GUIDANCE);
+}
+
+#[test]
+fn the_tweet_actions_run_as_commands() {
+    let (mut app, _turn) = app();
+
+    let result = from_input_action(InputAction::PostTweet("straight from the action".into())).execute(&mut app);
+    assert!(matches!(result, CommandResult::Continue));
+    assert_eq!(preview::take_pending().map(|pending| pending.parts().to_vec()), Some(vec!["straight from the action".to_string()]));
+
+    let result = from_input_action(InputAction::DraftTweet("an idea".into())).execute(&mut app);
+    assert!(matches!(result, CommandResult::Continue));
+    assert_eq!(last_line(&app), NO_AGENT_GUIDANCE, "drafting needs an agent, and says so");
+    assert!(preview::take_pending().is_none());
+}